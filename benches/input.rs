@@ -0,0 +1,115 @@
+//! Benchmarks for the cost of pulling tokens out of different input types.
+//!
+//! The parsers here do very little work per token, so the time spent in `InputRef::next` (and the
+//! input-specific code it dispatches to) dominates. Each grammar is run in check mode through
+//! [`parse_benchable`], which produces no output and so avoids measuring allocation.
+
+use chumsky::{
+    input::{StrInput, ValueInput},
+    prelude::*,
+    text::Char,
+    util::parse_benchable,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+mod utils;
+
+/// A lexer-style grammar: a flat sequence of identifiers, integers and punctuation.
+fn lexer<'a, I, C>() -> impl Parser<'a, I, usize> + Clone
+where
+    I: ValueInput<'a> + StrInput<'a, C>,
+    C: Char,
+{
+    let ident = text::ascii::ident().ignored();
+    let int = text::int(10).ignored();
+    let punct = any()
+        .filter(|c: &C| "+-*/()=,;".contains(c.to_char()))
+        .ignored();
+
+    choice((ident, int, punct)).padded().repeated().count()
+}
+
+/// A tree-style grammar: nested s-expressions.
+fn tree<'a, I, C>() -> impl Parser<'a, I, usize> + Clone
+where
+    I: ValueInput<'a> + StrInput<'a, C>,
+    C: Char,
+{
+    let punct = |p| any().filter(move |c: &C| c.to_char() == p);
+
+    recursive(|tree| {
+        let atom = text::ascii::ident().ignored();
+        let list = tree
+            .padded()
+            .repeated()
+            .ignored()
+            .delimited_by(punct('('), punct(')'));
+
+        atom.or(list).to(1)
+    })
+}
+
+fn lexer_sample() -> String {
+    "let x1 = foo(42, bar) * 7 - baz;\n".repeat(4096)
+}
+
+fn tree_sample() -> String {
+    fn node(depth: usize, out: &mut String) {
+        if depth == 0 {
+            out.push_str("leaf");
+        } else {
+            out.push('(');
+            for _ in 0..4 {
+                node(depth - 1, out);
+                out.push(' ');
+            }
+            out.push(')');
+        }
+    }
+
+    let mut out = String::new();
+    node(7, &mut out);
+    out
+}
+
+fn bench_input(c: &mut Criterion) {
+    let lexer_src = lexer_sample();
+    let tree_src = tree_sample();
+
+    c.bench_function("input_lexer_str", |b| {
+        let parser = lexer::<&str, char>();
+        b.iter(|| assert!(parse_benchable(&parser, black_box(lexer_src.as_str()))))
+    });
+
+    c.bench_function("input_lexer_bytes", |b| {
+        let parser = lexer::<&[u8], u8>();
+        b.iter(|| assert!(parse_benchable(&parser, black_box(lexer_src.as_bytes()))))
+    });
+
+    c.bench_function("input_lexer_str_parse", |b| {
+        let parser = lexer::<&str, char>();
+        b.iter(|| {
+            assert!(black_box(parser.parse(black_box(lexer_src.as_str())))
+                .into_result()
+                .is_ok())
+        })
+    });
+
+    c.bench_function("input_tree_str", |b| {
+        let parser = tree::<&str, char>();
+        b.iter(|| assert!(parse_benchable(&parser, black_box(tree_src.as_str()))))
+    });
+
+    c.bench_function("input_tree_bytes", |b| {
+        let parser = tree::<&[u8], u8>();
+        b.iter(|| assert!(parse_benchable(&parser, black_box(tree_src.as_bytes()))))
+    });
+}
+
+criterion_group!(
+    name = benches;
+    config = utils::make_criterion();
+    targets = bench_input
+);
+
+criterion_main!(benches);
@@ -0,0 +1,430 @@
+//! The `Parse` derive macro.
+//!
+//! Structs (and the fields of enum variants) become a sequence of field parsers, chained with `then`. Enums become a
+//! set of alternatives, chained with `or`, each introduced by a keyword.
+
+use crate::util::*;
+use proc_macro::{Delimiter, Group, Ident, Literal, Span, TokenStream, TokenTree};
+
+/// The options given by `#[parse(...)]` attributes.
+#[derive(Default)]
+struct Attrs {
+    keyword: Option<Literal>,
+    open: Option<Literal>,
+    close: Option<Literal>,
+    sep: Option<Literal>,
+    prefix: Option<Literal>,
+}
+
+enum Fields {
+    Named(Vec<Field>),
+    Unnamed(Vec<Field>),
+    Unit,
+}
+
+struct Field {
+    name: Option<Ident>,
+    ty: TokenStream,
+    attrs: Attrs,
+}
+
+struct Variant {
+    name: Ident,
+    attrs: Attrs,
+    fields: Fields,
+}
+
+const CONTAINER_ATTRS: &[&str] = &["keyword", "open", "close", "sep"];
+const FIELD_ATTRS: &[&str] = &["prefix"];
+
+pub fn expand(input: TokenStream) -> Result<TokenStream> {
+    let mut cur = Cursor::new(input, Span::call_site());
+    let attrs = parse_attrs(&mut cur, CONTAINER_ATTRS)?;
+    skip_vis(&mut cur);
+
+    let kind = cur.expect_ident()?;
+    let name = cur.expect_ident()?;
+    if cur.peek_punct("<") {
+        return Err(Error::new(
+            cur.span(),
+            "`Parse` cannot be derived for generic types",
+        ));
+    }
+
+    let mut field_tys = Vec::new();
+    let parser = match kind.to_string().as_str() {
+        "struct" => {
+            let fields = match cur.next() {
+                Some(TokenTree::Group(group)) => parse_fields(group)?,
+                _ => Fields::Unit,
+            };
+            collect_tys(&fields, &mut field_tys);
+            let keyword = matches!(fields, Fields::Unit).then(|| default_keyword(&name));
+            fields_parser(code("Self"), keyword, &attrs, &fields)?
+        }
+        "enum" => {
+            let variants = match cur.next() {
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                    parse_variants(group)?
+                }
+                _ => return Err(Error::new(name.span(), "expected the variants of the enum")),
+            };
+            if attrs.keyword.is_some()
+                || attrs.open.is_some()
+                || attrs.close.is_some()
+                || attrs.sep.is_some()
+            {
+                return Err(Error::new(
+                    name.span(),
+                    "`#[parse(...)]` attributes on enums must be placed on their variants",
+                ));
+            }
+            let mut parsers = Vec::new();
+            for variant in &variants {
+                collect_tys(&variant.fields, &mut field_tys);
+                let ctor = concat([code("Self::"), tokens(variant.name.clone())]);
+                parsers.push(fields_parser(
+                    ctor,
+                    Some(default_keyword(&variant.name)),
+                    &variant.attrs,
+                    &variant.fields,
+                )?);
+            }
+            let mut parsers = parsers.into_iter();
+            let Some(first) = parsers.next() else {
+                return Err(Error::new(
+                    name.span(),
+                    "`Parse` cannot be derived for empty enums",
+                ));
+            };
+            parsers.fold(first, |acc, parser| {
+                concat([parens(acc), code(".or"), parens(parser)])
+            })
+        }
+        _ => {
+            return Err(Error::new(
+                kind.span(),
+                "`Parse` can only be derived for structs and enums",
+            ))
+        }
+    };
+
+    let mut where_clause = code("where");
+    for ty in field_tys {
+        where_clause.extend([ty, code(": ::chumsky::derive::Parse<'a, E>,")]);
+    }
+
+    Ok(concat([
+        code("impl<'a, E: ::chumsky::extra::ParserExtra<'a, &'a str> + 'a> ::chumsky::derive::Parse<'a, E> for"),
+        tokens(name),
+        where_clause,
+        tokens(Group::new(
+            Delimiter::Brace,
+            concat([
+                code("fn parser() -> ::chumsky::Boxed<'a, 'a, &'a str, Self, E>"),
+                tokens(Group::new(
+                    Delimiter::Brace,
+                    concat([
+                        code("use ::chumsky::Parser as _;"),
+                        parens(parser),
+                        code(".boxed()"),
+                    ]),
+                )),
+            ]),
+        )),
+    ]))
+}
+
+fn collect_tys(fields: &Fields, tys: &mut Vec<TokenStream>) {
+    if let Fields::Named(fields) | Fields::Unnamed(fields) = fields {
+        tys.extend(fields.iter().map(|field| field.ty.clone()));
+    }
+}
+
+/// Generate a parser for a set of fields, constructed with the given path.
+fn fields_parser(
+    ctor: TokenStream,
+    default_keyword: Option<Literal>,
+    attrs: &Attrs,
+    fields: &Fields,
+) -> Result<TokenStream> {
+    let text = |lit: &Literal| {
+        concat([
+            code("::chumsky::primitive::just::<_, &'a str, E>"),
+            parens(tokens(lit.clone())),
+            code(".padded()"),
+        ])
+    };
+
+    // Each part of the sequence, and the name that its output is bound to (if it is a field)
+    let mut parts = Vec::new();
+    if let Some(keyword) = attrs.keyword.clone().or(default_keyword) {
+        let parser = if matches!(literal_str(&keyword), Some(s) if is_ident(&s)) {
+            concat([
+                code("::chumsky::text::ascii::keyword::<&'a str, char, _, E>"),
+                parens(tokens(keyword)),
+                code(".padded()"),
+            ])
+        } else {
+            text(&keyword)
+        };
+        parts.push((parser, None));
+    }
+    parts.extend(attrs.open.as_ref().map(|open| (text(open), None)));
+    let (Fields::Named(fs) | Fields::Unnamed(fs)) = fields else {
+        return Ok(build(parts, ctor));
+    };
+    for (i, field) in fs.iter().enumerate() {
+        if i > 0 {
+            parts.extend(attrs.sep.as_ref().map(|sep| (text(sep), None)));
+        }
+        parts.extend(
+            field
+                .attrs
+                .prefix
+                .as_ref()
+                .map(|prefix| (text(prefix), None)),
+        );
+        let parser = concat([
+            code("<"),
+            field.ty.clone(),
+            code("as ::chumsky::derive::Parse<'a, E>>::parser().padded()"),
+        ]);
+        parts.push((
+            parser,
+            Some(Ident::new(&format!("__field_{i}"), Span::call_site())),
+        ));
+    }
+    parts.extend(attrs.close.as_ref().map(|close| (text(close), None)));
+
+    let args = fs.iter().enumerate().map(|(i, field)| {
+        let binding = tokens(Ident::new(&format!("__field_{i}"), Span::call_site()));
+        match &field.name {
+            Some(name) => concat([tokens(name.clone()), code(":"), binding]),
+            None => binding,
+        }
+    });
+    let args = match fields {
+        Fields::Named(_) => tokens(Group::new(Delimiter::Brace, comma_list(args))),
+        _ => parens(comma_list(args)),
+    };
+    Ok(build(parts, concat([ctor, args])))
+}
+
+/// Chain the parts of a sequence together with `then`, and map their outputs to a value.
+fn build(parts: Vec<(TokenStream, Option<Ident>)>, value: TokenStream) -> TokenStream {
+    let mut parts = parts.into_iter();
+    let (mut parser, mut pattern) = match parts.next() {
+        Some((parser, binding)) => (parser, binding_pattern(binding)),
+        None => (
+            code("::chumsky::primitive::empty::<&'a str, E>()"),
+            code("()"),
+        ),
+    };
+    for (next, binding) in parts {
+        parser = concat([parens(parser), code(".then"), parens(next)]);
+        pattern = parens(comma_list([pattern, binding_pattern(binding)]));
+    }
+    concat([
+        parser,
+        code(".map"),
+        parens(concat([code("|"), pattern, code("|"), value])),
+    ])
+}
+
+fn binding_pattern(binding: Option<Ident>) -> TokenStream {
+    binding.map_or_else(|| code("_"), tokens)
+}
+
+fn parse_fields(group: Group) -> Result<Fields> {
+    let named = match group.delimiter() {
+        Delimiter::Brace => true,
+        Delimiter::Parenthesis => false,
+        _ => {
+            return Err(Error::new(
+                group.span(),
+                "expected the fields of the struct",
+            ))
+        }
+    };
+    let mut cur = Cursor::new(group.stream(), group.span_close());
+    let mut fields = Vec::new();
+    while !cur.is_empty() {
+        let attrs = parse_attrs(&mut cur, FIELD_ATTRS)?;
+        skip_vis(&mut cur);
+        let name = if named {
+            let name = cur.expect_ident()?;
+            cur.expect_punct(":")?;
+            Some(name)
+        } else {
+            None
+        };
+        let ty = parse_until_comma(&mut cur);
+        if ty.is_empty() {
+            return Err(Error::new(cur.span(), "expected the type of the field"));
+        }
+        cur.eat_punct(",");
+        fields.push(Field { name, ty, attrs });
+    }
+    Ok(if named {
+        Fields::Named(fields)
+    } else {
+        Fields::Unnamed(fields)
+    })
+}
+
+fn parse_variants(group: Group) -> Result<Vec<Variant>> {
+    let mut cur = Cursor::new(group.stream(), group.span_close());
+    let mut variants = Vec::new();
+    while !cur.is_empty() {
+        let attrs = parse_attrs(&mut cur, CONTAINER_ATTRS)?;
+        let name = cur.expect_ident()?;
+        let fields = match cur.peek() {
+            Some(TokenTree::Group(group)) if group.delimiter() != Delimiter::Bracket => {
+                let group = group.clone();
+                cur.next();
+                parse_fields(group)?
+            }
+            _ => Fields::Unit,
+        };
+        // Skip explicit discriminants
+        if cur.eat_punct("=") {
+            parse_until_comma(&mut cur);
+        }
+        cur.eat_punct(",");
+        variants.push(Variant {
+            name,
+            attrs,
+            fields,
+        });
+    }
+    Ok(variants)
+}
+
+/// Parse outer attributes, keeping the options of any `#[parse(...)]` attributes.
+fn parse_attrs(cur: &mut Cursor, allowed: &[&str]) -> Result<Attrs> {
+    let mut attrs = Attrs::default();
+    while cur.peek_punct("#") {
+        cur.next();
+        let Some(TokenTree::Group(group)) = cur.next() else {
+            return Err(Error::new(cur.span(), "expected an attribute"));
+        };
+        let mut inner = Cursor::new(group.stream(), group.span_close());
+        if !matches!(inner.peek(), Some(TokenTree::Ident(name)) if name.to_string() == "parse") {
+            continue;
+        }
+        inner.next();
+        let Some(TokenTree::Group(args)) = inner.next() else {
+            return Err(Error::new(inner.span(), "expected `#[parse(...)]`"));
+        };
+
+        let mut args = Cursor::new(args.stream(), args.span_close());
+        while !args.is_empty() {
+            let key = args.expect_ident()?;
+            let slot = match key.to_string().as_str() {
+                key if !allowed.contains(&key) => None,
+                "keyword" => Some(&mut attrs.keyword),
+                "open" => Some(&mut attrs.open),
+                "close" => Some(&mut attrs.close),
+                "sep" => Some(&mut attrs.sep),
+                "prefix" => Some(&mut attrs.prefix),
+                _ => None,
+            };
+            let Some(slot) = slot else {
+                return Err(Error::new(
+                    key.span(),
+                    format!(
+                        "unknown option `{key}`, expected one of: {}",
+                        allowed
+                            .iter()
+                            .map(|a| format!("`{a}`"))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    ),
+                ));
+            };
+            args.expect_punct("=")?;
+            match args.next() {
+                Some(TokenTree::Literal(lit)) if is_str_literal(&lit) => *slot = Some(lit),
+                tok => {
+                    return Err(Error::new(
+                        tok.map_or(args.span(), |tok| tok.span()),
+                        "expected a string literal",
+                    ))
+                }
+            }
+            if !args.is_empty() {
+                args.expect_punct(",")?;
+            }
+        }
+    }
+    Ok(attrs)
+}
+
+fn skip_vis(cur: &mut Cursor) {
+    if matches!(cur.peek(), Some(TokenTree::Ident(vis)) if vis.to_string() == "pub") {
+        cur.next();
+        if matches!(cur.peek(), Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis)
+        {
+            cur.next();
+        }
+    }
+}
+
+/// Collect tokens up to the next comma that is not nested within angle brackets.
+fn parse_until_comma(cur: &mut Cursor) -> TokenStream {
+    let mut depth = 0usize;
+    let mut out = TokenStream::new();
+    while !cur.is_empty() {
+        if depth == 0 && cur.peek_punct(",") {
+            break;
+        } else if cur.peek_punct("->") {
+            out.extend(cur.next());
+        } else if cur.peek_punct("<") {
+            depth += 1;
+        } else if cur.peek_punct(">") {
+            depth = depth.saturating_sub(1);
+        }
+        out.extend(cur.next());
+    }
+    out
+}
+
+fn is_str_literal(lit: &Literal) -> bool {
+    let s = lit.to_string();
+    s.starts_with('"') || s.starts_with("r\"") || s.starts_with("r#")
+}
+
+/// The contents of a plain string literal, if the literal is one.
+fn literal_str(lit: &Literal) -> Option<String> {
+    let s = lit.to_string();
+    let inner = s.strip_prefix('"')?.strip_suffix('"')?;
+    (!inner.contains('\\')).then(|| inner.to_string())
+}
+
+fn is_ident(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// The keyword of a variant or unit struct, if not given explicitly: its name in `snake_case`.
+fn default_keyword(name: &Ident) -> Literal {
+    Literal::string(&snake_case(&name.to_string()))
+}
+
+fn snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut out = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_lower = matches!(chars.get(i + 1), Some(c) if c.is_lowercase());
+            if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_lower) {
+                out.push('_');
+            }
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
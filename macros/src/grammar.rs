@@ -0,0 +1,395 @@
+//! The `grammar!` macro.
+//!
+//! A grammar is an optional header of `type Input = ...;` and `type Extra = ...;` items, followed by a list of rules
+//! of the form `name: Type = pattern;`. Every rule is declared as a `Recursive` parser before any of them are
+//! defined, so rules may refer to one another (or themselves) in any order. The macro evaluates to the first rule.
+
+use crate::util::*;
+use proc_macro::{Delimiter, Group, Ident, Literal, Span, TokenStream, TokenTree};
+
+struct Rule {
+    name: Ident,
+    ty: TokenStream,
+    pattern: Alt,
+}
+
+/// A set of alternatives, separated by `|`.
+struct Alt(Vec<Seq>);
+
+/// A sequence of items, with an optional action to produce the output.
+struct Seq {
+    span: Span,
+    items: Vec<Item>,
+    action: Option<Group>,
+}
+
+/// An element of a sequence, optionally bound to a name for use in an action.
+struct Item {
+    binding: Option<Ident>,
+    elem: Elem,
+}
+
+enum Elem {
+    /// `"foo"`, `'c'`: a specific input.
+    Lit(Literal),
+    /// `'a'..='z'`, `'a'..'z'`: one of a range of inputs.
+    Range(Literal, bool, Literal),
+    /// `name`: another rule.
+    Rule(Ident),
+    /// `( ... )`: a parenthesised pattern.
+    Group(Alt),
+    /// `{ ... }`: an arbitrary parser expression.
+    Embed(Group),
+    /// `$elem`: the slice of the input that the element covers.
+    Slice(Box<Elem>),
+    /// `!elem`: succeeds only if the element does not.
+    Not(Box<Elem>),
+    /// `elem*`, `elem+`: the element, repeated at least the given number of times.
+    Repeat(Box<Elem>, usize),
+    /// `elem?`: the element, optionally.
+    Optional(Box<Elem>),
+}
+
+pub fn expand(input: TokenStream) -> Result<TokenStream> {
+    let mut cur = Cursor::new(input, Span::call_site());
+
+    let (mut input_ty, mut extra_ty) = (code("_"), code("_"));
+    while matches!(cur.peek(), Some(TokenTree::Ident(kw)) if kw.to_string() == "type") {
+        cur.next();
+        let name = cur.expect_ident()?;
+        let slot = match name.to_string().as_str() {
+            "Input" => &mut input_ty,
+            "Extra" => &mut extra_ty,
+            _ => return Err(Error::new(name.span(), "expected `Input` or `Extra`")),
+        };
+        cur.expect_punct("=")?;
+        *slot = parse_type(&mut cur)?;
+        cur.expect_punct(";")?;
+    }
+
+    let mut rules = Vec::new();
+    while !cur.is_empty() {
+        rules.push(parse_rule(&mut cur)?);
+    }
+
+    let Some(first) = rules.first() else {
+        return Err(Error::new(Span::call_site(), "expected at least one rule"));
+    };
+    for (i, rule) in rules.iter().enumerate() {
+        if let Some(prev) = rules[..i]
+            .iter()
+            .find(|r| r.name.to_string() == rule.name.to_string())
+        {
+            return Err(Error::new(
+                rule.name.span(),
+                format!("the rule `{}` is defined more than once", prev.name),
+            ));
+        }
+    }
+
+    let gen = Gen {
+        rules: &rules,
+        input_ty: &input_ty,
+        extra_ty: &extra_ty,
+    };
+    let mut body = code("use ::chumsky::{IterParser as _, Parser as _};");
+    for rule in &rules {
+        body.extend([
+            code("let mut"),
+            tokens(rule_var(&rule.name)),
+            code(": ::chumsky::recursive::Recursive<::chumsky::recursive::Indirect<'_, '_,"),
+            input_ty.clone(),
+            code(","),
+            rule.ty.clone(),
+            code(","),
+            extra_ty.clone(),
+            code(">> = ::chumsky::recursive::Recursive::declare();"),
+        ]);
+    }
+    for rule in &rules {
+        body.extend([
+            tokens(rule_var(&rule.name)),
+            code(".define"),
+            parens(gen.alt(&rule.pattern)?),
+            code(";"),
+        ]);
+    }
+    body.extend([tokens(rule_var(&first.name))]);
+
+    Ok(tokens(Group::new(Delimiter::Brace, body)))
+}
+
+fn rule_var(name: &Ident) -> Ident {
+    Ident::new(&format!("__grammar_rule_{name}"), name.span())
+}
+
+fn parse_rule(cur: &mut Cursor) -> Result<Rule> {
+    let name = cur.expect_ident()?;
+    cur.expect_punct(":")?;
+    let ty = parse_type(cur)?;
+    cur.expect_punct("=")?;
+
+    let pattern = parse_alt(cur)?;
+    cur.expect_punct(";")?;
+    Ok(Rule { name, ty, pattern })
+}
+
+/// Collect the tokens of a type, up to the next lone `=` or `;`.
+fn parse_type(cur: &mut Cursor) -> Result<TokenStream> {
+    let at_end = |cur: &Cursor| {
+        cur.is_empty()
+            || cur.peek_punct(";")
+            || (cur.peek_punct("=") && !cur.peek_punct("=>") && !cur.peek_punct("=="))
+    };
+    let mut ty = TokenStream::new();
+    while !at_end(cur) {
+        ty.extend(cur.next());
+    }
+    if ty.is_empty() {
+        Err(Error::new(cur.span(), "expected a type"))
+    } else {
+        Ok(ty)
+    }
+}
+
+fn parse_alt(cur: &mut Cursor) -> Result<Alt> {
+    let mut seqs = vec![parse_seq(cur)?];
+    while cur.eat_punct("|") {
+        seqs.push(parse_seq(cur)?);
+    }
+    Ok(Alt(seqs))
+}
+
+fn parse_seq(cur: &mut Cursor) -> Result<Seq> {
+    let span = cur.span();
+    let mut items = Vec::new();
+    while !cur.is_empty() && !cur.peek_punct("|") && !cur.peek_punct(";") && !cur.peek_punct("=>") {
+        items.push(parse_item(cur)?);
+    }
+
+    let action = if cur.eat_punct("=>") {
+        match cur.next() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => Some(group),
+            tok => {
+                return Err(Error::new(
+                    tok.map_or(cur.span(), |tok| tok.span()),
+                    "expected a `{ ... }` block after `=>`",
+                ))
+            }
+        }
+    } else {
+        None
+    };
+
+    Ok(Seq {
+        span,
+        items,
+        action,
+    })
+}
+
+fn parse_item(cur: &mut Cursor) -> Result<Item> {
+    let binding = match (cur.peek(), cur.peek_nth(1)) {
+        (Some(TokenTree::Ident(_)), Some(TokenTree::Punct(p)))
+            if p.as_char() == ':' && !cur.peek_punct("::") =>
+        {
+            let binding = cur.expect_ident()?;
+            cur.expect_punct(":")?;
+            Some(binding)
+        }
+        _ => None,
+    };
+    Ok(Item {
+        binding,
+        elem: parse_prefixed(cur)?,
+    })
+}
+
+fn parse_prefixed(cur: &mut Cursor) -> Result<Elem> {
+    if cur.eat_punct("$") {
+        Ok(Elem::Slice(Box::new(parse_prefixed(cur)?)))
+    } else if cur.eat_punct("!") {
+        Ok(Elem::Not(Box::new(parse_prefixed(cur)?)))
+    } else {
+        parse_postfixed(cur)
+    }
+}
+
+fn parse_postfixed(cur: &mut Cursor) -> Result<Elem> {
+    let mut elem = parse_primary(cur)?;
+    loop {
+        elem = if cur.eat_punct("*") {
+            Elem::Repeat(Box::new(elem), 0)
+        } else if cur.eat_punct("+") {
+            Elem::Repeat(Box::new(elem), 1)
+        } else if cur.eat_punct("?") {
+            Elem::Optional(Box::new(elem))
+        } else {
+            break Ok(elem);
+        };
+    }
+}
+
+fn parse_primary(cur: &mut Cursor) -> Result<Elem> {
+    let span = cur.span();
+    match cur.next() {
+        Some(TokenTree::Literal(start)) => {
+            let inclusive = if cur.eat_punct("..=") {
+                true
+            } else if cur.eat_punct("..") {
+                false
+            } else {
+                return Ok(Elem::Lit(start));
+            };
+            match cur.next() {
+                Some(TokenTree::Literal(end)) => Ok(Elem::Range(start, inclusive, end)),
+                _ => Err(Error::new(cur.span(), "expected the end of the range")),
+            }
+        }
+        Some(TokenTree::Ident(name)) => Ok(Elem::Rule(name)),
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            let mut inner = Cursor::new(group.stream(), group.span_close());
+            let alt = parse_alt(&mut inner)?;
+            if !inner.is_empty() {
+                return Err(Error::new(inner.span(), "unexpected token in pattern"));
+            }
+            Ok(Elem::Group(alt))
+        }
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+            Ok(Elem::Embed(group))
+        }
+        _ => Err(Error::new(
+            span,
+            "expected a literal, a rule name, `( ... )`, or `{ ... }`",
+        )),
+    }
+}
+
+struct Gen<'a> {
+    rules: &'a [Rule],
+    input_ty: &'a TokenStream,
+    extra_ty: &'a TokenStream,
+}
+
+impl Gen<'_> {
+    /// Generic arguments for primitives, so that the input type (if known) flows into actions.
+    fn primitive_args(&self, args: &str) -> TokenStream {
+        concat([
+            code(&format!("::<{args}")),
+            self.input_ty.clone(),
+            code(","),
+            self.extra_ty.clone(),
+            code(">"),
+        ])
+    }
+
+    fn alt(&self, alt: &Alt) -> Result<TokenStream> {
+        match &alt.0[..] {
+            [seq] => self.seq(seq),
+            seqs => Ok(concat([
+                code("::chumsky::primitive::choice"),
+                parens(parens(comma_list(
+                    seqs.iter()
+                        .map(|seq| self.seq(seq))
+                        .collect::<Result<Vec<_>>>()?,
+                ))),
+            ])),
+        }
+    }
+
+    fn seq(&self, seq: &Seq) -> Result<TokenStream> {
+        let pattern = |item: &Item| match &item.binding {
+            Some(binding) => tokens(binding.clone()),
+            None => code("_"),
+        };
+
+        let (parser, pattern) = match &seq.items[..] {
+            [] => (
+                concat([
+                    code("::chumsky::primitive::empty"),
+                    self.primitive_args(""),
+                    code("()"),
+                ]),
+                code("()"),
+            ),
+            [item] => (self.elem(&item.elem)?, pattern(item)),
+            items => (
+                concat([
+                    code("::chumsky::primitive::group"),
+                    parens(parens(comma_list(
+                        items
+                            .iter()
+                            .map(|item| self.elem(&item.elem))
+                            .collect::<Result<Vec<_>>>()?,
+                    ))),
+                ]),
+                parens(comma_list(items.iter().map(pattern))),
+            ),
+        };
+
+        let bindings = seq
+            .items
+            .iter()
+            .filter_map(|item| item.binding.clone())
+            .collect::<Vec<_>>();
+        let output = match (&seq.action, &bindings[..]) {
+            (Some(action), _) => tokens(action.clone()),
+            // Sequences of a single item already produce the output of that item
+            (None, _) if seq.items.len() <= 1 => return Ok(parser),
+            (None, []) => return Ok(parser),
+            (None, [binding]) => tokens(binding.clone()),
+            (None, bindings) => parens(comma_list(bindings.iter().map(|b| tokens(b.clone())))),
+        };
+
+        let closure = concat([code("|"), pattern, code("|"), output]);
+        Ok(concat([
+            parser,
+            code(".map"),
+            tokens(group(Delimiter::Parenthesis, closure, seq.span)),
+        ]))
+    }
+
+    fn elem(&self, elem: &Elem) -> Result<TokenStream> {
+        Ok(match elem {
+            Elem::Lit(lit) => concat([
+                code("::chumsky::primitive::just"),
+                self.primitive_args("_,"),
+                parens(tokens(lit.clone())),
+            ]),
+            Elem::Range(start, inclusive, end) => concat([
+                code("::chumsky::primitive::one_of"),
+                self.primitive_args("_,"),
+                parens(concat([
+                    tokens(start.clone()),
+                    code(if *inclusive { "..=" } else { ".." }),
+                    tokens(end.clone()),
+                ])),
+            ]),
+            Elem::Rule(name) => {
+                if !self
+                    .rules
+                    .iter()
+                    .any(|r| r.name.to_string() == name.to_string())
+                {
+                    return Err(Error::new(name.span(), format!("no rule named `{name}`")));
+                }
+                concat([tokens(rule_var(name)), code(".clone()")])
+            }
+            Elem::Group(alt) => parens(self.alt(alt)?),
+            Elem::Embed(block) => parens(tokens(block.clone())),
+            Elem::Slice(elem) => concat([parens(self.elem(elem)?), code(".slice()")]),
+            Elem::Not(elem) => concat([parens(self.elem(elem)?), code(".not()")]),
+            Elem::Repeat(elem, at_least) => concat([
+                parens(self.elem(elem)?),
+                code(".repeated()"),
+                if *at_least > 0 {
+                    code(&format!(".at_least({at_least})"))
+                } else {
+                    TokenStream::new()
+                },
+                code(".collect::<::chumsky::__private::Vec<_>>()"),
+            ]),
+            Elem::Optional(elem) => concat([parens(self.elem(elem)?), code(".or_not()")]),
+        })
+    }
+}
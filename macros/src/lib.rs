@@ -0,0 +1,28 @@
+//! Procedural macros for chumsky.
+//!
+//! You probably don't want to depend on this crate directly: enable chumsky's `macros` feature instead, which
+//! re-exports everything here.
+//!
+//! This crate deliberately has no dependencies beyond [`proc_macro`] to keep chumsky's compile times low.
+
+mod derive;
+mod grammar;
+mod util;
+
+use proc_macro::TokenStream;
+
+/// Write a set of parsers as a grammar of rules, in a BNF-like syntax.
+///
+/// See the documentation for `chumsky::grammar!` for a full description of the syntax.
+#[proc_macro]
+pub fn grammar(input: TokenStream) -> TokenStream {
+    grammar::expand(input).unwrap_or_else(|err| err.into_compile_error())
+}
+
+/// Derive a parser for a struct or enum.
+///
+/// See the documentation for `chumsky::derive` for a description of the generated parsers.
+#[proc_macro_derive(Parse, attributes(parse))]
+pub fn derive_parse(input: TokenStream) -> TokenStream {
+    derive::expand(input).unwrap_or_else(|err| err.into_compile_error())
+}
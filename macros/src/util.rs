@@ -0,0 +1,146 @@
+//! Helpers for inspecting and building token streams without `syn` or `quote`.
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+/// An error to be reported at a particular span.
+pub struct Error {
+    span: Span,
+    msg: String,
+}
+
+impl Error {
+    pub fn new(span: Span, msg: impl Into<String>) -> Self {
+        Self {
+            span,
+            msg: msg.into(),
+        }
+    }
+
+    /// Turn this error into a `compile_error!` invocation that points to the erroneous tokens.
+    pub fn into_compile_error(self) -> TokenStream {
+        let mut msg = Literal::string(&self.msg);
+        msg.set_span(self.span);
+        [
+            TokenTree::Ident(Ident::new("compile_error", self.span)),
+            punct('!', self.span),
+            TokenTree::Group(group(Delimiter::Parenthesis, tokens(msg), self.span)),
+            punct(';', self.span),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// A cursor over a sequence of token trees.
+pub struct Cursor {
+    tokens: Vec<TokenTree>,
+    pos: usize,
+    end: Span,
+}
+
+impl Cursor {
+    pub fn new(stream: TokenStream, end: Span) -> Self {
+        Self {
+            tokens: stream.into_iter().collect(),
+            pos: 0,
+            end,
+        }
+    }
+
+    pub fn peek(&self) -> Option<&TokenTree> {
+        self.tokens.get(self.pos)
+    }
+
+    pub fn peek_nth(&self, n: usize) -> Option<&TokenTree> {
+        self.tokens.get(self.pos + n)
+    }
+
+    pub fn next(&mut self) -> Option<TokenTree> {
+        let tok = self.tokens.get(self.pos).cloned();
+        self.pos += tok.is_some() as usize;
+        tok
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    /// The span of the next token, or of the end of the input if there are no more tokens.
+    pub fn span(&self) -> Span {
+        self.peek().map_or(self.end, TokenTree::span)
+    }
+
+    /// Check whether the next tokens are the given punctuation characters (such as `=>`).
+    pub fn peek_punct(&self, chars: &str) -> bool {
+        chars.chars().enumerate().all(|(i, c)| {
+            matches!(self.peek_nth(i), Some(TokenTree::Punct(p)) if p.as_char() == c
+                && (i + 1 == chars.len() || p.spacing() == Spacing::Joint))
+        })
+    }
+
+    /// Consume the given punctuation characters, if they are next.
+    pub fn eat_punct(&mut self, chars: &str) -> bool {
+        let found = self.peek_punct(chars);
+        if found {
+            self.pos += chars.len();
+        }
+        found
+    }
+
+    pub fn expect_punct(&mut self, chars: &str) -> Result<()> {
+        if self.eat_punct(chars) {
+            Ok(())
+        } else {
+            Err(Error::new(self.span(), format!("expected `{chars}`")))
+        }
+    }
+
+    pub fn expect_ident(&mut self) -> Result<Ident> {
+        match self.next() {
+            Some(TokenTree::Ident(ident)) => Ok(ident),
+            Some(tok) => Err(Error::new(tok.span(), "expected an identifier")),
+            None => Err(Error::new(self.end, "expected an identifier")),
+        }
+    }
+}
+
+pub fn punct(c: char, span: Span) -> TokenTree {
+    let mut punct = Punct::new(c, Spacing::Alone);
+    punct.set_span(span);
+    TokenTree::Punct(punct)
+}
+
+pub fn group(delim: Delimiter, inner: TokenStream, span: Span) -> Group {
+    let mut group = Group::new(delim, inner);
+    group.set_span(span);
+    group
+}
+
+/// Parse a snippet of Rust code. The snippet must be valid on its own (i.e: balanced delimiters).
+pub fn code(s: &str) -> TokenStream {
+    s.parse().expect("generated invalid code")
+}
+
+/// Turn a single token into a token stream.
+pub fn tokens(tok: impl Into<TokenTree>) -> TokenStream {
+    TokenStream::from(tok.into())
+}
+
+/// Build a token stream from a sequence of parts.
+pub fn concat<I: IntoIterator<Item = TokenStream>>(parts: I) -> TokenStream {
+    let mut out = TokenStream::new();
+    out.extend(parts);
+    out
+}
+
+/// Wrap a token stream in parentheses.
+pub fn parens(inner: TokenStream) -> TokenStream {
+    TokenTree::Group(Group::new(Delimiter::Parenthesis, inner)).into()
+}
+
+/// Join token streams with commas, including a trailing comma (so that a single item forms a tuple).
+pub fn comma_list<I: IntoIterator<Item = TokenStream>>(items: I) -> TokenStream {
+    concat(items.into_iter().flat_map(|item| [item, code(",")]))
+}
@@ -13,6 +13,10 @@ where
         M::invoke(*self, inp)
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        T::walk_node(*self, walker);
+    }
+
     go_extra!(O);
 }
 
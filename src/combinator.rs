@@ -7,6 +7,43 @@
 
 use super::*;
 
+/// Stop a repetition whose last iteration succeeded without consuming any input, and so would otherwise repeat
+/// forever. Evaluates to the result of the repetition.
+///
+/// This is always a mistake in the parser, so debug builds panic with the location at which the combinator was
+/// created. Release builds fail with an error at the current position instead.
+macro_rules! no_progress {
+    ($inp:expr, $name:literal, $location:expr) => {{
+        #[cfg(debug_assertions)]
+        let res = no_progress($name, $location);
+        #[cfg(not(debug_assertions))]
+        let res = no_progress($inp);
+        res
+    }};
+}
+
+#[cfg(debug_assertions)]
+#[cold]
+fn no_progress<T>(name: &str, location: Location<'static>) -> Result<T, ()> {
+    panic!(
+        "found {} combinator making no progress at {}",
+        name, location
+    )
+}
+
+/// Fail with an error reporting the next token (or the end of the input) as unexpected.
+#[cfg(not(debug_assertions))]
+#[cold]
+fn no_progress<'a, T, I: Input<'a>, E: ParserExtra<'a, I>>(
+    inp: &mut InputRef<'a, '_, I, E>,
+) -> Result<T, ()> {
+    let before = inp.offset();
+    let (at, found) = inp.next_maybe_inner();
+    let span = inp.span_since(before);
+    inp.add_alt(at, None, found, span);
+    Err(())
+}
+
 /// The type of a lazy parser.
 pub type Lazy<'a, A, I, E> =
     ThenIgnore<A, Repeated<Any<I, E>, <I as Input<'a>>::Token, I, E>, (), E>;
@@ -34,6 +71,10 @@ where
         self.parser.go_cfg::<M>(inp, cfg)
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
@@ -75,6 +116,10 @@ where
         }
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(());
 }
 
@@ -108,6 +153,10 @@ where
     ) -> IPResult<M, O> {
         self.parser.next_cfg(inp, &mut state.0, &state.1)
     }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
 }
 
 /// See [`ConfigIterParser::try_configure`]
@@ -148,6 +197,10 @@ where
         }
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(());
 }
 
@@ -183,6 +236,10 @@ where
     ) -> IPResult<M, O> {
         self.parser.next_cfg(inp, &mut state.0, &state.1)
     }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
 }
 
 /// See [`Parser::map_slice`].
@@ -232,11 +289,14 @@ where
 {
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, U> {
-        let before = inp.offset().offset;
+        let before = inp.offset();
         self.parser.go::<Check>(inp)?;
-        let after = inp.offset().offset;
 
-        Ok(M::bind(|| (self.mapper)(inp.slice_inner(before..after))))
+        Ok(M::bind(|| (self.mapper)(inp.slice_since(before))))
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
     }
 
     go_extra!(U);
@@ -270,11 +330,14 @@ where
     where
         Self: Sized,
     {
-        let before = inp.offset().offset;
+        let before = inp.offset();
         self.parser.go::<Check>(inp)?;
-        let after = inp.offset().offset;
 
-        Ok(M::bind(|| inp.slice_inner(before..after)))
+        Ok(M::bind(|| inp.slice_since(before)))
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
     }
 
     go_extra!(I::Slice);
@@ -317,6 +380,10 @@ where
         })
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
@@ -352,6 +419,10 @@ where
         Ok(M::map(out, &self.mapper))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
@@ -386,6 +457,10 @@ where
             Err(()) => Err(()),
         }
     }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
 }
 
 /// See [`Parser::map_group`].
@@ -425,6 +500,10 @@ where
         Ok(M::map(out, |out| self.mapper.call(out)))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
@@ -461,6 +540,10 @@ where
             Err(()) => Err(()),
         }
     }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
 }
 
 /// See [`Parser::map_with_span`].
@@ -499,6 +582,10 @@ where
         }))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
@@ -528,13 +615,94 @@ where
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, I::Span> {
         let before = inp.offset();
-        self.parser.go::<M>(inp)?;
+        self.parser.go::<Check>(inp)?;
         Ok(M::bind(|| inp.span_since(before)))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(I::Span);
 }
 
+/// See [`Parser::with_span_of`].
+pub struct WithSpanOf<A, OA> {
+    pub(crate) parser: A,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OA>,
+}
+
+impl<A: Copy, OA> Copy for WithSpanOf<A, OA> {}
+impl<A: Clone, OA> Clone for WithSpanOf<A, OA> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, OA, E, A> ParserSealed<'a, I, (OA, I::Span), E> for WithSpanOf<A, OA>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, (OA, I::Span)> {
+        let before = inp.offset();
+        let out = self.parser.go::<M>(inp)?;
+        Ok(M::map(out, |out| (out, inp.span_since(before))))
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!((OA, I::Span));
+}
+
+/// See [`Parser::map_intern`].
+pub struct MapIntern<A, OA> {
+    pub(crate) parser: A,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OA>,
+}
+
+impl<A: Copy, OA> Copy for MapIntern<A, OA> {}
+impl<A: Clone, OA> Clone for MapIntern<A, OA> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E, A, OA> ParserSealed<'a, I, intern::Symbol, E> for MapIntern<A, OA>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: BorrowMut<intern::Interner>,
+    A: Parser<'a, I, OA, E>,
+    OA: AsRef<str>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, intern::Symbol> {
+        // Always intern, even when checking, so that symbols are numbered consistently
+        let out = self.parser.go::<Emit>(inp)?;
+        let sym = inp.state().borrow_mut().intern(out.as_ref());
+        Ok(M::bind(|| sym))
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(intern::Symbol);
+}
+
 /// See [`Parser::map_with_state`].
 pub struct MapWithState<A, OA, F> {
     pub(crate) parser: A,
@@ -572,6 +740,83 @@ where
         }))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::map_with`].
+pub struct MapWith<A, OA, F> {
+    pub(crate) parser: A,
+    pub(crate) mapper: F,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OA>,
+}
+
+impl<A: Copy, OA, F: Copy> Copy for MapWith<A, OA, F> {}
+impl<A: Clone, OA, F: Clone> Clone for MapWith<A, OA, F> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            mapper: self.mapper.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, O, E, A, OA, F> ParserSealed<'a, I, O, E> for MapWith<A, OA, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+    F: Fn(OA, &mut MapExtra<'a, '_, I, E>) -> O,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let before = inp.offset();
+        let out = self.parser.go::<M>(inp)?;
+        Ok(M::map(out, |out| {
+            let mut extra = MapExtra::new(before, inp);
+            (self.mapper)(out, &mut extra)
+        }))
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::inspect`].
+#[derive(Copy, Clone)]
+pub struct Inspect<A, F> {
+    pub(crate) parser: A,
+    pub(crate) f: F,
+}
+
+impl<'a, I, O, E, A, F> ParserSealed<'a, I, O, E> for Inspect<A, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+    F: Fn(&O),
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let out = self.parser.go::<M>(inp)?;
+        Ok(M::map(out, |out| {
+            (self.f)(&out);
+            out
+        }))
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
@@ -615,6 +860,10 @@ where
         }
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
@@ -658,6 +907,10 @@ where
         }
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
@@ -693,6 +946,10 @@ where
         Ok(M::bind(|| self.to.clone()))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
@@ -743,6 +1000,10 @@ where
     ) -> IPResult<M, O::Item> {
         Ok(iter.next().map(|out| M::bind(|| out)))
     }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
 }
 
 /// See [`Parser::ignored`].
@@ -774,6 +1035,10 @@ where
         Ok(M::bind(|| ()))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(());
 }
 
@@ -815,6 +1080,10 @@ where
         }))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
@@ -836,6 +1105,57 @@ where
         }))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::node`].
+#[derive(Copy, Clone)]
+pub struct Node<A> {
+    pub(crate) parser: A,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for Node<A>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        // Start after any trivia, so that the node does not include it
+        let before = inp.offset().offset;
+        // The node is recorded before its inner parser runs so that nested nodes follow it in the outline
+        let idx = inp.errors.outline.as_mut().map(|outline| {
+            outline.push(OutlineNode {
+                start: before,
+                end: before,
+                descendants: 0,
+            });
+            outline.len() - 1
+        });
+
+        let res = self.parser.go::<M>(inp);
+
+        if let (Some(idx), Some(outline)) = (idx, &mut inp.errors.outline) {
+            if res.is_ok() {
+                outline[idx].end = inp.offset;
+                outline[idx].descendants = outline.len() - idx - 1;
+            } else {
+                outline.truncate(idx);
+            }
+        }
+
+        res
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
@@ -856,6 +1176,11 @@ where
 {
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        // Results are memoized by offset, which doesn't account for pushed-back tokens
+        if inp.pending.is_some() {
+            return self.parser.go::<M>(inp);
+        }
+
         let before = inp.offset();
         // TODO: Don't use address, since this might not be constant?
         let key = (
@@ -872,10 +1197,14 @@ where
                     let err_span = inp.span_since(before);
                     inp.add_alt(key.0, None, None, err_span);
                 }
+                #[cfg(feature = "telemetry")]
+                inp.count(|stats| stats.memo_hits += 1);
                 return Err(());
             }
             hashbrown::hash_map::Entry::Vacant(v) => {
                 v.insert(None);
+                #[cfg(feature = "telemetry")]
+                inp.count(|stats| stats.memo_misses += 1);
             }
         }
 
@@ -893,6 +1222,10 @@ where
         res
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
@@ -929,6 +1262,13 @@ where
         Ok(M::combine(a, b, |a: OA, b: OB| (a, b)))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.parser_a.walk_node(walker);
+            self.parser_b.walk_node(walker);
+        });
+    }
+
     go_extra!((OA, OB));
 }
 
@@ -965,6 +1305,13 @@ where
         Ok(M::map(b, |b: OB| b))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.parser_a.walk_node(walker);
+            self.parser_b.walk_node(walker);
+        });
+    }
+
     go_extra!(OB);
 }
 
@@ -1001,95 +1348,327 @@ where
         Ok(M::map(a, |a: OA| a))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.parser_a.walk_node(walker);
+            self.parser_b.walk_node(walker);
+        });
+    }
+
     go_extra!(OA);
 }
 
-/// See [`Parser::nested_in`].
-pub struct NestedIn<A, B, O, E> {
+/// See [`Parser::separated_pair`].
+pub struct SeparatedPair<A, S, B, OS> {
     pub(crate) parser_a: A,
+    pub(crate) separator: S,
     pub(crate) parser_b: B,
     #[allow(dead_code)]
-    pub(crate) phantom: EmptyPhantom<(O, E)>,
+    pub(crate) phantom: EmptyPhantom<OS>,
 }
 
-impl<A: Copy, B: Copy, O, E> Copy for NestedIn<A, B, O, E> {}
-impl<A: Clone, B: Clone, O, E> Clone for NestedIn<A, B, O, E> {
+impl<A: Copy, S: Copy, B: Copy, OS> Copy for SeparatedPair<A, S, B, OS> {}
+impl<A: Clone, S: Clone, B: Clone, OS> Clone for SeparatedPair<A, S, B, OS> {
     fn clone(&self) -> Self {
         Self {
             parser_a: self.parser_a.clone(),
+            separator: self.separator.clone(),
             parser_b: self.parser_b.clone(),
             phantom: EmptyPhantom::new(),
         }
     }
 }
 
-impl<'a, I, E, A, B, O> ParserSealed<'a, I, O, E> for NestedIn<A, B, O, E>
+impl<'a, I, E, A, S, B, OA, OS, OB> ParserSealed<'a, I, (OA, OB), E> for SeparatedPair<A, S, B, OS>
 where
     I: Input<'a>,
     E: ParserExtra<'a, I>,
-    A: Parser<'a, I, O, E>,
-    B: Parser<'a, I, I, E>,
+    A: Parser<'a, I, OA, E>,
+    S: Parser<'a, I, OS, E>,
+    B: Parser<'a, I, OB, E>,
 {
     #[inline(always)]
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
-        let inp2 = self.parser_b.go::<Emit>(inp)?;
-
-        let alt = inp.errors.alt.take();
-
-        #[cfg(feature = "memoization")]
-        let mut memos = HashMap::default();
-        let res = inp.with_input(
-            &inp2,
-            |inp| (&self.parser_a).then_ignore(end()).go::<M>(inp),
-            #[cfg(feature = "memoization")]
-            &mut memos,
-        );
-
-        // TODO: Translate secondary error offsets too
-        let new_alt = inp.errors.alt.take();
-        inp.errors.alt = alt;
-        if let Some(new_alt) = new_alt {
-            inp.add_alt_err(inp.offset().offset, new_alt.err);
-        }
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, (OA, OB)> {
+        let a = self.parser_a.go::<M>(inp)?;
+        self.separator.go::<Check>(inp)?;
+        let b = self.parser_b.go::<M>(inp)?;
+        Ok(M::combine(a, b, |a: OA, b: OB| (a, b)))
+    }
 
-        res
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.parser_a.walk_node(walker);
+            self.separator.walk_node(walker);
+            self.parser_b.walk_node(walker);
+        });
     }
 
-    go_extra!(O);
+    go_extra!((OA, OB));
 }
 
-/// See [`Parser::ignore_with_ctx`].
-pub struct IgnoreWithCtx<A, B, OA, I, E> {
+/// See [`Parser::preceded`].
+pub struct Preceded<A, B, OB> {
     pub(crate) parser: A,
-    pub(crate) then: B,
+    pub(crate) prefix: B,
     #[allow(dead_code)]
-    pub(crate) phantom: EmptyPhantom<(B, OA, E, I)>,
+    pub(crate) phantom: EmptyPhantom<OB>,
 }
 
-impl<A: Copy, B: Copy, OA, I, E> Copy for IgnoreWithCtx<A, B, OA, I, E> {}
-impl<A: Clone, B: Clone, OA, I, E> Clone for IgnoreWithCtx<A, B, OA, I, E> {
+impl<A: Copy, B: Copy, OB> Copy for Preceded<A, B, OB> {}
+impl<A: Clone, B: Clone, OB> Clone for Preceded<A, B, OB> {
     fn clone(&self) -> Self {
         Self {
             parser: self.parser.clone(),
-            then: self.then.clone(),
+            prefix: self.prefix.clone(),
             phantom: EmptyPhantom::new(),
         }
     }
 }
 
-impl<'a, I, E, A, B, OA, OB> ParserSealed<'a, I, OB, E>
-    for IgnoreWithCtx<A, B, OA, I, extra::Full<E::Error, E::State, OA>>
+impl<'a, I, E, A, B, OA, OB> ParserSealed<'a, I, OA, E> for Preceded<A, B, OB>
 where
     I: Input<'a>,
     E: ParserExtra<'a, I>,
     A: Parser<'a, I, OA, E>,
-    B: Parser<'a, I, OB, extra::Full<E::Error, E::State, OA>>,
-    OA: 'a,
+    B: Parser<'a, I, OB, E>,
 {
     #[inline(always)]
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, OB> {
-        let p1 = self.parser.go::<Emit>(inp)?;
-        inp.with_ctx(&p1, |inp| self.then.go::<M>(inp))
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, OA> {
+        self.prefix.go::<Check>(inp)?;
+        self.parser.go::<M>(inp)
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.prefix.walk_node(walker);
+            self.parser.walk_node(walker);
+        });
+    }
+
+    go_extra!(OA);
+}
+
+/// See [`Parser::terminated`].
+pub struct Terminated<A, B, OB> {
+    pub(crate) parser: A,
+    pub(crate) suffix: B,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OB>,
+}
+
+impl<A: Copy, B: Copy, OB> Copy for Terminated<A, B, OB> {}
+impl<A: Clone, B: Clone, OB> Clone for Terminated<A, B, OB> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            suffix: self.suffix.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E, A, B, OA, OB> ParserSealed<'a, I, OA, E> for Terminated<A, B, OB>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+    B: Parser<'a, I, OB, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, OA> {
+        let a = self.parser.go::<M>(inp)?;
+        self.suffix.go::<Check>(inp)?;
+        Ok(a)
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.parser.walk_node(walker);
+            self.suffix.walk_node(walker);
+        });
+    }
+
+    go_extra!(OA);
+}
+
+/// See [`Parser::nested_in`].
+pub struct NestedIn<A, B, O, E> {
+    pub(crate) parser_a: A,
+    pub(crate) parser_b: B,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(O, E)>,
+}
+
+impl<A: Copy, B: Copy, O, E> Copy for NestedIn<A, B, O, E> {}
+impl<A: Clone, B: Clone, O, E> Clone for NestedIn<A, B, O, E> {
+    fn clone(&self) -> Self {
+        Self {
+            parser_a: self.parser_a.clone(),
+            parser_b: self.parser_b.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E, A, B, O> ParserSealed<'a, I, O, E> for NestedIn<A, B, O, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+    B: Parser<'a, I, I, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let inp2 = self.parser_b.go::<Emit>(inp)?;
+
+        let alt = inp.errors.alt.take();
+        // Offsets into the nested input are meaningless in the outline of the outer input
+        let outline = inp.errors.outline.take();
+
+        #[cfg(feature = "memoization")]
+        let mut memos = HashMap::default();
+        let res = inp.with_input(
+            &inp2,
+            |inp| (&self.parser_a).then_ignore(end()).go::<M>(inp),
+            #[cfg(feature = "memoization")]
+            &mut memos,
+        );
+
+        // TODO: Translate secondary error offsets too
+        let new_alt = inp.errors.alt.take();
+        inp.errors.alt = alt;
+        inp.errors.outline = outline;
+        if let Some(new_alt) = new_alt {
+            inp.add_alt_err(inp.offset().offset, new_alt.err);
+        }
+
+        res
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.parser_b.walk_node(walker);
+            self.parser_a.walk_node(walker);
+        });
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::map_input`].
+pub struct MapInput<A, B, OB, F, G, I, E> {
+    pub(crate) parser: A,
+    pub(crate) outer: B,
+    pub(crate) project: F,
+    pub(crate) map_err: G,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(OB, I, E)>,
+}
+
+impl<A: Copy, B: Copy, OB, F: Copy, G: Copy, I, E> Copy for MapInput<A, B, OB, F, G, I, E> {}
+impl<A: Clone, B: Clone, OB, F: Clone, G: Clone, I, E> Clone for MapInput<A, B, OB, F, G, I, E> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            outer: self.outer.clone(),
+            project: self.project.clone(),
+            map_err: self.map_err.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E, J, EJ, A, B, O, OB, F, G> ParserSealed<'a, I, O, E>
+    for MapInput<A, B, OB, F, G, J, EJ>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    J: Input<'a>,
+    EJ: ParserExtra<'a, J>,
+    EJ::State: Default,
+    EJ::Context: Default,
+    A: Parser<'a, J, O, EJ>,
+    B: Parser<'a, I, OB, E>,
+    F: Fn(OB) -> J,
+    G: Fn(EJ::Error, I::Span) -> E::Error,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let before = inp.offset();
+        let outer = self.outer.go::<Emit>(inp)?;
+
+        let (out, mut errs) = InputOwn::<J, EJ>::new((self.project)(outer))
+            .parse_with(&self.parser)
+            .into_output_errors();
+
+        // The inner parse failed: its furthest error becomes the error of this parser
+        let fatal = if out.is_none() { errs.pop() } else { None };
+        for err in errs {
+            inp.emit(before.offset, (self.map_err)(err, inp.span_since(before)));
+        }
+
+        match out {
+            Some(out) => Ok(M::bind(|| out)),
+            None => {
+                let err = fatal.expect("error but no alt?");
+                inp.add_alt_err(
+                    inp.offset().offset,
+                    (self.map_err)(err, inp.span_since(before)),
+                );
+                Err(())
+            }
+        }
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.outer.walk_node(walker);
+            self.parser.walk_node(walker);
+        });
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::ignore_with_ctx`].
+pub struct IgnoreWithCtx<A, B, OA, I, E> {
+    pub(crate) parser: A,
+    pub(crate) then: B,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(B, OA, E, I)>,
+}
+
+impl<A: Copy, B: Copy, OA, I, E> Copy for IgnoreWithCtx<A, B, OA, I, E> {}
+impl<A: Clone, B: Clone, OA, I, E> Clone for IgnoreWithCtx<A, B, OA, I, E> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            then: self.then.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E, A, B, OA, OB> ParserSealed<'a, I, OB, E>
+    for IgnoreWithCtx<A, B, OA, I, extra::Full<E::Error, E::State, OA>>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+    B: Parser<'a, I, OB, extra::Full<E::Error, E::State, OA>>,
+    OA: 'a,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, OB> {
+        let p1 = self.parser.go::<Emit>(inp)?;
+        inp.with_ctx(&p1, |inp| self.then.go::<M>(inp))
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.parser.walk_node(walker);
+            self.then.walk_node(walker);
+        });
     }
 
     go_extra!(OB);
@@ -1128,6 +1707,13 @@ where
 
         inp.with_ctx(ctx, |inp| self.then.next(inp, inner_state))
     }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.parser.walk_node(walker);
+            self.then.walk_node(walker);
+        });
+    }
 }
 
 /// See [`Parser::then_with_ctx`].
@@ -1165,6 +1751,13 @@ where
         Ok(M::map(p2, |p2| (p1, p2)))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.parser.walk_node(walker);
+            self.then.walk_node(walker);
+        });
+    }
+
     go_extra!((OA, OB));
 }
 
@@ -1201,6 +1794,13 @@ where
 
         inp.with_ctx(ctx, |inp| self.then.next(inp, inner_state))
     }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.parser.walk_node(walker);
+            self.then.walk_node(walker);
+        });
+    }
 }
 
 /// See [`Parser::with_ctx`].
@@ -1231,6 +1831,10 @@ where
         inp.with_ctx(&self.ctx, |inp| self.parser.go::<M>(inp))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
@@ -1262,6 +1866,47 @@ where
         inp.with_state(&mut self.state.clone(), |inp| self.parser.go::<M>(inp))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::with_trivia`].
+pub struct WithTrivia<A, T> {
+    pub(crate) parser: A,
+    pub(crate) trivia: fn(&T) -> bool,
+}
+
+impl<A: Copy, T> Copy for WithTrivia<A, T> {}
+impl<A: Clone, T> Clone for WithTrivia<A, T> {
+    fn clone(&self) -> Self {
+        WithTrivia {
+            parser: self.parser.clone(),
+            trivia: self.trivia,
+        }
+    }
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for WithTrivia<A, I::Token>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let outer = inp.trivia.replace(self.trivia);
+        let res = self.parser.go::<M>(inp);
+        inp.trivia = outer;
+        res
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
@@ -1302,9 +1947,138 @@ where
         Ok(a)
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.start.walk_node(walker);
+            self.parser.walk_node(walker);
+            self.end.walk_node(walker);
+        });
+    }
+
     go_extra!(OA);
 }
 
+/// See [`Parser::delimited_recover`].
+pub struct DelimitedRecover<A, T, F> {
+    pub(crate) parser: A,
+    pub(crate) open: T,
+    pub(crate) close: T,
+    pub(crate) fallback: F,
+}
+
+impl<A: Copy, T: Copy, F: Copy> Copy for DelimitedRecover<A, T, F> {}
+impl<A: Clone, T: Clone, F: Clone> Clone for DelimitedRecover<A, T, F> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            open: self.open.clone(),
+            close: self.close.clone(),
+            fallback: self.fallback.clone(),
+        }
+    }
+}
+
+impl<'a, I, O, E, A, F> ParserSealed<'a, I, O, E> for DelimitedRecover<A, I::Token, F>
+where
+    I: ValueInput<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: PartialEq + Clone,
+    A: Parser<'a, I, O, E>,
+    F: Fn(I::Span) -> O,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let before = inp.offset();
+        just(self.open.clone()).go::<Check>(inp)?;
+
+        let inner = inp.save();
+        let res = self.parser.go::<M>(inp).and_then(|out| {
+            just(self.close.clone()).go::<Check>(inp)?;
+            Ok(out)
+        });
+        let Err(()) = res else {
+            return res;
+        };
+
+        // Skip to the matching close delimiter, counting any nested delimiters along the way
+        let alt = inp.errors.alt.take().expect("error but no alt?");
+        inp.rewind(inner);
+        let start = inp.offset();
+        let mut depth = 0usize;
+        loop {
+            match inp.next_inner() {
+                (_, Some(tok)) if tok == self.close => match depth.checked_sub(1) {
+                    Some(d) => depth = d,
+                    None => break,
+                },
+                (_, Some(tok)) if tok == self.open => depth += 1,
+                (_, Some(_)) => {}
+                // The block is never closed, so it can't be recovered
+                (_, None) => {
+                    inp.errors.alt = Some(alt);
+                    return Err(());
+                }
+            }
+        }
+
+        inp.emit_recovered(start..inp.offset(), alt.err);
+        let span = inp.span_since(before);
+        Ok(M::bind(|| (self.fallback)(span)))
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::recover_via_insertion`].
+#[derive(Copy, Clone)]
+pub struct RecoverViaInsertion<A, T> {
+    pub(crate) parser: A,
+    pub(crate) token: T,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for RecoverViaInsertion<A, I::Token>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: Clone,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let before = inp.save();
+        let res = self.parser.go::<M>(inp);
+        let Err(()) = res else {
+            return res;
+        };
+
+        let alt = inp.errors.alt.take().expect("error but no alt?");
+        inp.rewind(before);
+        inp.push_back(self.token.clone());
+        match self.parser.go::<M>(inp) {
+            // The inserted token must have been consumed, or the parsers that follow would see it
+            Ok(out) if inp.pending == before.pending => {
+                inp.emit_recovered(before.offset()..before.offset(), alt.err);
+                Ok(out)
+            }
+            _ => {
+                inp.rewind(before);
+                inp.errors.alt = Some(alt);
+                Err(())
+            }
+        }
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(O);
+}
+
 /// See [`Parser::padded_by`].
 pub struct PaddedBy<A, B, OB> {
     pub(crate) parser: A,
@@ -1339,6 +2113,13 @@ where
         Ok(a)
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.padding.walk_node(walker);
+            self.parser.walk_node(walker);
+        });
+    }
+
     go_extra!(OA);
 }
 
@@ -1360,6 +2141,13 @@ where
         self.choice.go::<M>(inp)
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.choice.parsers.0.walk_node(walker);
+            self.choice.parsers.1.walk_node(walker);
+        });
+    }
+
     go_extra!(O);
 }
 
@@ -1493,28 +2281,28 @@ where
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, ()> {
         if self.at_most == !0 && self.at_least == 0 {
+            // Errors from before the repetition (such as those of alternatives that were tried and failed) must
+            // survive it, since they may still be reported
+            let alt = inp.errors.alt.take();
             loop {
                 let before = inp.save();
                 match self.parser.go::<Check>(inp) {
                     Ok(()) => {}
                     Err(()) => {
                         // TODO: Helper for this? Rewind does this? (seconds one may be bad for other cases)
-                        inp.errors.alt = None;
+                        inp.errors.alt = alt;
                         inp.rewind(before);
                         break Ok(M::bind(|| ()));
                     }
                 }
-                #[cfg(debug_assertions)]
-                debug_assert!(
-                    before.offset() != inp.offset(),
-                    "found Repeated combinator making no progress at {}",
-                    self.location,
-                );
+                if before.offset() == inp.offset() {
+                    inp.errors.alt = alt;
+                    break no_progress!(inp, "Repeated", self.location);
+                }
             }
         } else {
             let mut state = self.make_iter::<Check>(inp)?;
             loop {
-                #[cfg(debug_assertions)]
                 let before = inp.offset();
                 match self.next::<Check>(inp, &mut state) {
                     Ok(Some(())) => {}
@@ -1524,16 +2312,17 @@ where
                     // `next`.
                     Err(()) => break Err(()),
                 }
-                #[cfg(debug_assertions)]
-                debug_assert!(
-                    before != inp.offset(),
-                    "found Repeated combinator making no progress at {}",
-                    self.location,
-                );
+                if before == inp.offset() {
+                    break no_progress!(inp, "Repeated", self.location);
+                }
             }
         }
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(());
 }
 
@@ -1579,6 +2368,10 @@ where
             }
         }
     }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
 }
 
 impl<'a, A, O, I, E> ConfigIterParserSealed<'a, I, O, E> for Repeated<A, O, I, E>
@@ -1621,6 +2414,34 @@ where
     }
 }
 
+/// Configuration for [`Parser::separated_by`], used in [`ConfigIterParser::configure`].
+#[derive(Default)]
+pub struct SeparatedByCfg {
+    at_least: Option<usize>,
+    at_most: Option<usize>,
+}
+
+impl SeparatedByCfg {
+    /// Set the minimum number of items accepted
+    pub fn at_least(mut self, n: usize) -> Self {
+        self.at_least = Some(n);
+        self
+    }
+
+    /// Set the maximum number of items accepted
+    pub fn at_most(mut self, n: usize) -> Self {
+        self.at_most = Some(n);
+        self
+    }
+
+    /// Set an exact number of items to accept
+    pub fn exactly(mut self, n: usize) -> Self {
+        self.at_least = Some(n);
+        self.at_most = Some(n);
+        self
+    }
+}
+
 /// See [`Parser::separated_by`].
 pub struct SeparatedBy<A, B, OA, OB, I, E> {
     pub(crate) parser: A,
@@ -1788,6 +2609,35 @@ where
             ..self
         }
     }
+
+    /// Output the slice of the input spanning every item and separator, without collecting the items.
+    ///
+    /// This is the same as [`Parser::slice`], but makes the intent clear for the common case of parsing things
+    /// like dotted paths, where the items are characters or slices of a `&str` and allocating a [`Vec`] of them
+    /// would be wasteful. Leading and trailing separators, when allowed and present, are part of the slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let path = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .separated_by(just('.'))
+    ///     .at_least(1)
+    ///     .collect_string();
+    ///
+    /// assert_eq!(path.parse("std.io.Write").into_result(), Ok("std.io.Write"));
+    /// assert_eq!(path.parse("x").into_result(), Ok("x"));
+    /// assert!(path.parse("a..b").has_errors());
+    /// ```
+    pub fn collect_string(self) -> Slice<Self, ()>
+    where
+        I: SliceInput<'a>,
+    {
+        Slice {
+            parser: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
 }
 
 impl<'a, I, E, A, B, OA, OB> IterParserSealed<'a, I, OA, E> for SeparatedBy<A, B, OA, OB, I, E>
@@ -1815,7 +2665,38 @@ where
         inp: &mut InputRef<'a, '_, I, E>,
         state: &mut Self::IterState<M>,
     ) -> IPResult<M, OA> {
-        if *state as u64 >= self.at_most {
+        self.next_cfg::<M>(inp, state, &SeparatedByCfg::default())
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.parser.walk_node(walker);
+            self.separator.walk_node(walker);
+        });
+    }
+}
+
+impl<'a, I, E, A, B, OA, OB> ConfigIterParserSealed<'a, I, OA, E>
+    for SeparatedBy<A, B, OA, OB, I, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+    B: Parser<'a, I, OB, E>,
+{
+    type Config = SeparatedByCfg;
+
+    #[inline(always)]
+    fn next_cfg<M: Mode>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+        state: &mut Self::IterState<M>,
+        cfg: &Self::Config,
+    ) -> IPResult<M, OA> {
+        let at_most = cfg.at_most.map(|x| x as u64).unwrap_or(self.at_most);
+        let at_least = cfg.at_least.unwrap_or(self.at_least);
+
+        if *state as u64 >= at_most {
             return Ok(None);
         }
 
@@ -1829,7 +2710,7 @@ where
                 Ok(()) => {
                     // Do nothing
                 }
-                Err(()) if *state < self.at_least => {
+                Err(()) if *state < at_least => {
                     inp.rewind(before_separator);
                     return Err(());
                 }
@@ -1846,7 +2727,7 @@ where
                 *state += 1;
                 Ok(Some(item))
             }
-            Err(()) if *state < self.at_least => {
+            Err(()) if *state < at_least => {
                 // We have errored before we have reached the count,
                 // and therefore should return this error, as we are
                 // still expecting items
@@ -1881,7 +2762,6 @@ where
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, ()> {
         let mut state = self.make_iter::<Check>(inp)?;
         loop {
-            #[cfg(debug_assertions)]
             let before = inp.offset();
             match self.next::<Check>(inp, &mut state) {
                 Ok(Some(())) => {}
@@ -1891,15 +2771,19 @@ where
                 // `next`.
                 Err(()) => break Err(()),
             }
-            #[cfg(debug_assertions)]
-            debug_assert!(
-                before != inp.offset(),
-                "found SeparatedBy combinator making no progress at {}",
-                self.location,
-            );
+            if before == inp.offset() {
+                break no_progress!(inp, "SeparatedBy", self.location);
+            }
         }
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.parser.walk_node(walker);
+            self.separator.walk_node(walker);
+        });
+    }
+
     go_extra!(());
 }
 
@@ -1951,22 +2835,28 @@ where
         state.0 += 1;
         Ok(out)
     }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
 }
 
-/// See [`IterParser::collect`].
-pub struct Collect<A, O, C> {
+/// See [`IterParser::collect`] and [`IterParser::collect_with_capacity_hint`].
+pub struct Collect<A, O, C, H = ()> {
     pub(crate) parser: A,
+    pub(crate) capacity: H,
     #[cfg(debug_assertions)]
     pub(crate) location: Location<'static>,
     #[allow(dead_code)]
     pub(crate) phantom: EmptyPhantom<(O, C)>,
 }
 
-impl<A: Copy, O, C> Copy for Collect<A, O, C> {}
-impl<A: Clone, O, C> Clone for Collect<A, O, C> {
+impl<A: Copy, O, C, H: Copy> Copy for Collect<A, O, C, H> {}
+impl<A: Clone, O, C, H: Clone> Clone for Collect<A, O, C, H> {
     fn clone(&self) -> Self {
         Self {
             parser: self.parser.clone(),
+            capacity: self.capacity.clone(),
             #[cfg(debug_assertions)]
             location: self.location,
             phantom: EmptyPhantom::new(),
@@ -1974,21 +2864,23 @@ impl<A: Clone, O, C> Clone for Collect<A, O, C> {
     }
 }
 
-impl<'a, I, O, E, A, C> ParserSealed<'a, I, C, E> for Collect<A, O, C>
+impl<'a, I, O, E, A, C, H> ParserSealed<'a, I, C, E> for Collect<A, O, C, H>
 where
     I: Input<'a>,
     E: ParserExtra<'a, I>,
     A: IterParser<'a, I, O, E>,
     C: Container<O>,
+    H: CapacityHint<'a, I>,
 {
     #[inline(always)]
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, C> {
-        let mut output = M::bind::<C, _>(|| C::default());
+        let mut output = M::bind::<C, _>(|| match self.capacity.capacity(inp) {
+            Some(n) => C::with_capacity(n),
+            None => C::default(),
+        });
         let mut iter_state = self.parser.make_iter::<M>(inp)?;
-        #[cfg(debug_assertions)]
         let mut i = 0;
         loop {
-            #[cfg(debug_assertions)]
             let before = inp.offset();
             match self.parser.next::<M>(inp, &mut iter_state) {
                 Ok(Some(out)) => {
@@ -1999,20 +2891,87 @@ where
             }
             // We only check after the second iteration because that's when we *must* have consumed both item
             // and separator.
+            if !A::NONCONSUMPTION_IS_OK {
+                if i >= 1 && before == inp.offset() {
+                    break no_progress!(inp, "Collect", self.location);
+                }
+                i += 1;
+            }
+        }
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(C);
+}
+
+/// See [`IterParser::collect_map`].
+pub struct CollectMap<A, K, V, C, S, Err> {
+    pub(crate) parser: A,
+    pub(crate) duplicates: Duplicates<K, S, Err>,
+    #[cfg(debug_assertions)]
+    pub(crate) location: Location<'static>,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(V, C)>,
+}
+
+impl<A: Copy, K, V, C, S, Err> Copy for CollectMap<A, K, V, C, S, Err> {}
+impl<A: Clone, K, V, C, S, Err> Clone for CollectMap<A, K, V, C, S, Err> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            duplicates: self.duplicates,
             #[cfg(debug_assertions)]
+            location: self.location,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, E, A, K, V, C> ParserSealed<'a, I, C, E> for CollectMap<A, K, V, C, I::Span, E::Error>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: IterParser<'a, I, (K, V), E>,
+    C: MapContainer<K, V>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, C> {
+        // Keys are needed to detect duplicates, so entries are always parsed in emit mode
+        let mut output = C::default();
+        let mut iter_state = self.parser.make_iter::<Emit>(inp)?;
+        let mut i = 0;
+        loop {
+            let before = inp.offset();
+            match self.parser.next::<Emit>(inp, &mut iter_state) {
+                Ok(Some((key, value))) => match self.duplicates {
+                    _ if !output.contains_key(&key) => output.push((key, value)),
+                    Duplicates::Overwrite => output.push((key, value)),
+                    Duplicates::KeepFirst => {}
+                    Duplicates::Error(f) => {
+                        let err = f(&key, inp.span_since(before));
+                        inp.emit(before.offset, err);
+                    }
+                },
+                Ok(None) => break Ok(M::bind(|| output)),
+                Err(()) => break Err(()),
+            }
+            // See `Collect`
             if !A::NONCONSUMPTION_IS_OK {
-                if i >= 1 {
-                    debug_assert!(
-                        before != inp.offset(),
-                        "found Collect combinator making no progress at {}",
-                        self.location,
-                    );
+                if i >= 1 && before == inp.offset() {
+                    break no_progress!(inp, "CollectMap", self.location);
                 }
                 i += 1;
             }
         }
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(C);
 }
 
@@ -2071,9 +3030,77 @@ where
         Ok(M::map(output, |output| unsafe { C::take(output) }))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(C);
 }
 
+/// See [`IterParser::fold`].
+pub struct Fold<A, O, T, F> {
+    pub(crate) parser: A,
+    pub(crate) init: T,
+    pub(crate) folder: F,
+    #[cfg(debug_assertions)]
+    pub(crate) location: Location<'static>,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<O>,
+}
+
+impl<A: Copy, O, T: Copy, F: Copy> Copy for Fold<A, O, T, F> {}
+impl<A: Clone, O, T: Clone, F: Clone> Clone for Fold<A, O, T, F> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            init: self.init.clone(),
+            folder: self.folder.clone(),
+            #[cfg(debug_assertions)]
+            location: self.location,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, O, E, A, T, F> ParserSealed<'a, I, T, E> for Fold<A, O, T, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: IterParser<'a, I, O, E>,
+    T: Clone,
+    F: Fn(T, O) -> T,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, T> {
+        let mut acc = M::bind(|| self.init.clone());
+        let mut iter_state = self.parser.make_iter::<M>(inp)?;
+        let mut i = 0;
+        loop {
+            let before = inp.offset();
+            match self.parser.next::<M>(inp, &mut iter_state) {
+                Ok(Some(out)) => {
+                    acc = M::combine(acc, out, |acc, out| (self.folder)(acc, out));
+                }
+                Ok(None) => break Ok(acc),
+                Err(()) => break Err(()),
+            }
+            // See `Collect` for why we only check after the second iteration.
+            if !A::NONCONSUMPTION_IS_OK {
+                if i >= 1 && before == inp.offset() {
+                    break no_progress!(inp, "Fold", self.location);
+                }
+                i += 1;
+            }
+        }
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(T);
+}
+
 /// See [`Parser::or_not`].
 #[derive(Copy, Clone)]
 pub struct OrNot<A> {
@@ -2098,9 +3125,75 @@ where
         })
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(Option<O>);
 }
 
+/// See [`Parser::or_not_with`].
+#[derive(Copy, Clone)]
+pub struct OrNotWith<A, F> {
+    pub(crate) parser: A,
+    pub(crate) default: F,
+}
+
+impl<'a, I, O, E, A, F> ParserSealed<'a, I, O, E> for OrNotWith<A, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+    F: Fn() -> O,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let before = inp.save();
+        match self.parser.go::<M>(inp) {
+            Ok(out) => Ok(out),
+            Err(()) => {
+                inp.rewind(before);
+                Ok(M::bind(&self.default))
+            }
+        }
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::unwrap_or`].
+#[derive(Copy, Clone)]
+pub struct UnwrapOr<A, O> {
+    pub(crate) parser: A,
+    pub(crate) default: O,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for UnwrapOr<A, O>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, Option<O>, E>,
+    O: Clone,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let out = self.parser.go::<M>(inp)?;
+        Ok(M::map(out, |out| {
+            out.unwrap_or_else(|| self.default.clone())
+        }))
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(O);
+}
+
 /// See [`Parser::not`].
 pub struct Not<A, OA> {
     pub(crate) parser: A,
@@ -2146,6 +3239,10 @@ where
         }
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(());
 }
 
@@ -2205,6 +3302,13 @@ where
         }
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.parser_a.walk_node(walker);
+            self.parser_b.walk_node(walker);
+        });
+    }
+
     go_extra!(OA);
 }
 
@@ -2249,7 +3353,6 @@ where
         let mut a_out = M::bind(|| Vec::new());
         let mut iter_state = self.parser_a.make_iter::<M>(inp)?;
         loop {
-            #[cfg(debug_assertions)]
             let before = inp.offset();
             match self.parser_a.next::<M>(inp, &mut iter_state) {
                 Ok(Some(out)) => {
@@ -2258,13 +3361,8 @@ where
                 Ok(None) => break,
                 Err(()) => return Err(()),
             }
-            #[cfg(debug_assertions)]
-            if !A::NONCONSUMPTION_IS_OK {
-                debug_assert!(
-                    before != inp.offset(),
-                    "found Foldr combinator making no progress at {}",
-                    self.location,
-                );
+            if !A::NONCONSUMPTION_IS_OK && before == inp.offset() {
+                return no_progress!(inp, "Foldr", self.location);
             }
         }
 
@@ -2275,6 +3373,13 @@ where
         }))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.parser_a.walk_node(walker);
+            self.parser_b.walk_node(walker);
+        });
+    }
+
     go_extra!(O);
 }
 
@@ -2319,7 +3424,6 @@ where
         let mut a_out = M::bind(|| Vec::new());
         let mut iter_state = self.parser_a.make_iter::<M>(inp)?;
         loop {
-            #[cfg(debug_assertions)]
             let before = inp.offset();
             match self.parser_a.next::<M>(inp, &mut iter_state) {
                 Ok(Some(out)) => {
@@ -2328,13 +3432,8 @@ where
                 Ok(None) => break,
                 Err(()) => return Err(()),
             }
-            #[cfg(debug_assertions)]
-            if !A::NONCONSUMPTION_IS_OK {
-                debug_assert!(
-                    before != inp.offset(),
-                    "found FoldrWithState combinator making no progress at {}",
-                    self.location,
-                );
+            if !A::NONCONSUMPTION_IS_OK && before == inp.offset() {
+                return no_progress!(inp, "FoldrWithState", self.location);
             }
         }
 
@@ -2348,6 +3447,13 @@ where
         }))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.parser_a.walk_node(walker);
+            self.parser_b.walk_node(walker);
+        });
+    }
+
     go_extra!(O);
 }
 
@@ -2392,7 +3498,6 @@ where
         let mut out = self.parser_a.go::<M>(inp)?;
         let mut iter_state = self.parser_b.make_iter::<M>(inp)?;
         loop {
-            #[cfg(debug_assertions)]
             let before = inp.offset();
             match self.parser_b.next::<M>(inp, &mut iter_state) {
                 Ok(Some(b_out)) => {
@@ -2401,17 +3506,19 @@ where
                 Ok(None) => break Ok(out),
                 Err(()) => break Err(()),
             }
-            #[cfg(debug_assertions)]
-            if !B::NONCONSUMPTION_IS_OK {
-                debug_assert!(
-                    before != inp.offset(),
-                    "found Foldl combinator making no progress at {}",
-                    self.location,
-                );
+            if !B::NONCONSUMPTION_IS_OK && before == inp.offset() {
+                break no_progress!(inp, "Foldl", self.location);
             }
         }
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.parser_a.walk_node(walker);
+            self.parser_b.walk_node(walker);
+        });
+    }
+
     go_extra!(O);
 }
 
@@ -2456,7 +3563,6 @@ where
         let mut out = self.parser_a.go::<M>(inp)?;
         let mut iter_state = self.parser_b.make_iter::<M>(inp)?;
         loop {
-            #[cfg(debug_assertions)]
             let before = inp.offset();
             match self.parser_b.next::<M>(inp, &mut iter_state) {
                 Ok(Some(b_out)) => {
@@ -2466,20 +3572,87 @@ where
                 Ok(None) => break Ok(out),
                 Err(()) => break Err(()),
             }
-            #[cfg(debug_assertions)]
-            if !B::NONCONSUMPTION_IS_OK {
-                debug_assert!(
-                    before != inp.offset(),
-                    "found FoldlWithState combinator making no progress at {}",
-                    self.location,
-                );
+            if !B::NONCONSUMPTION_IS_OK && before == inp.offset() {
+                break no_progress!(inp, "FoldlWithState", self.location);
             }
         }
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            self.parser_a.walk_node(walker);
+            self.parser_b.walk_node(walker);
+        });
+    }
+
     go_extra!(O);
 }
 
+/// See [`Parser::iterate_with`].
+pub struct IterateWith<A, OA, G, F> {
+    pub(crate) parser: A,
+    pub(crate) init: G,
+    pub(crate) f: F,
+    #[cfg(debug_assertions)]
+    pub(crate) location: Location<'static>,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OA>,
+}
+
+impl<A: Copy, OA, G: Copy, F: Copy> Copy for IterateWith<A, OA, G, F> {}
+impl<A: Clone, OA, G: Clone, F: Clone> Clone for IterateWith<A, OA, G, F> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            init: self.init.clone(),
+            f: self.f.clone(),
+            #[cfg(debug_assertions)]
+            location: self.location,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, OA, E, A, B, G, F> ParserSealed<'a, I, B, E> for IterateWith<A, OA, G, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+    G: Fn() -> B,
+    F: Fn(B, OA, &mut MapExtra<'a, '_, I, E>) -> ControlFlow<B, B>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, B> {
+        // The function decides when to stop, so item outputs must always be produced
+        let mut acc = (self.init)();
+        loop {
+            let before = inp.save();
+            let start = inp.offset();
+            let item = match self.parser.go::<Emit>(inp) {
+                Ok(item) => item,
+                Err(()) => {
+                    inp.rewind(before);
+                    break Ok(M::bind(|| acc));
+                }
+            };
+            let mut extra = MapExtra::new(start, inp);
+            acc = match (self.f)(acc, item, &mut extra) {
+                ControlFlow::Continue(acc) => acc,
+                ControlFlow::Break(acc) => break Ok(M::bind(|| acc)),
+            };
+            if start == inp.offset() {
+                break no_progress!(inp, "IterateWith", self.location);
+            }
+        }
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(B);
+}
+
 /// See [`Parser::rewind`].
 #[must_use]
 #[derive(Copy, Clone)]
@@ -2505,6 +3678,10 @@ where
         }
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
@@ -2538,9 +3715,184 @@ where
         res
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::or_suggest_insert`].
+#[derive(Clone)]
+pub struct SuggestInsert<A> {
+    pub(crate) parser: A,
+    pub(crate) text: String,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for SuggestInsert<A>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+    E::Error: SuggestionError<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        let old_alt = inp.errors.alt.take();
+        let before = inp.offset();
+        let res = self.parser.go::<M>(inp);
+
+        let new_alt = inp.errors.alt.take();
+        inp.errors.alt = old_alt;
+
+        if let Some(mut new_alt) = new_alt {
+            if res.is_err() && new_alt.pos == before.offset {
+                // SAFETY: offsets generated by previous call to `InputRef::next` (or similar).
+                let span = unsafe { inp.input.span(before.offset..before.offset) };
+                new_alt.err.suggest(span, self.text.clone());
+            }
+            inp.add_alt_err(new_alt.pos, new_alt.err);
+        }
+
+        res
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::map_err_to`].
+pub struct MapErrTo<A, E, E2> {
+    pub(crate) parser: A,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<(E, E2)>,
+}
+
+impl<A: Copy, E, E2> Copy for MapErrTo<A, E, E2> {}
+impl<A: Clone, E, E2> Clone for MapErrTo<A, E, E2> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, O, E, EA, A> ParserSealed<'a, I, O, E> for MapErrTo<A, EA, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    EA: ParserExtra<'a, I, State = E::State, Context = E::Context>,
+    A: Parser<'a, I, O, EA>,
+    E::Error: From<EA::Error>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        let (res, errors) = inp.with_errors::<EA, _>(|inp| self.parser.go::<M>(inp));
+
+        for err in errors.secondary {
+            inp.emit(err.pos, err.err.into());
+        }
+        if let Some(alt) = errors.alt {
+            inp.add_alt_err(alt.pos, alt.err.into());
+        }
+
+        res
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::inspect_err`].
+#[derive(Copy, Clone)]
+pub struct InspectErr<A, F> {
+    pub(crate) parser: A,
+    pub(crate) f: F,
+}
+
+impl<'a, I, O, E, A, F> ParserSealed<'a, I, O, E> for InspectErr<A, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+    F: Fn(&E::Error),
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let res = self.parser.go::<M>(inp);
+
+        if res.is_err() {
+            let alt = inp.errors.alt.as_ref().expect("error but no alt?");
+            // Only generated in emit mode, so that checking is free of side effects
+            M::bind(|| (self.f)(&alt.err));
+        }
+
+        res
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
+/// See [`Parser::then_catch`].
+#[derive(Copy, Clone)]
+pub struct ThenCatch<A> {
+    pub(crate) parser: A,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, Result<O, E::Error>, E> for ThenCatch<A>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, Result<O, E::Error>> {
+        let before = inp.save();
+        let alt = inp.errors.alt.take();
+
+        let res = self.parser.go::<M>(inp);
+
+        let new_alt = inp.errors.alt.take();
+        inp.errors.alt = alt;
+        match res {
+            Ok(out) => {
+                if let Some(new_alt) = new_alt {
+                    inp.add_alt_err(new_alt.pos, new_alt.err);
+                }
+                Ok(M::map(out, Ok))
+            }
+            Err(()) => {
+                inp.rewind(before);
+                let err = new_alt.expect("error but no alt?").err;
+                Ok(M::bind(|| Err(err)))
+            }
+        }
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(Result<O, E::Error>);
+}
+
 // /// See [`Parser::map_err_with_span`].
 // #[derive(Copy, Clone)]
 // pub struct MapErrWithSpan<A, F> {
@@ -2608,6 +3960,10 @@ where
         res
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
@@ -2654,6 +4010,10 @@ where
         Ok(M::bind(|| out))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(U);
 }
 
@@ -2705,6 +4065,63 @@ where
 mod tests {
     use crate::prelude::*;
 
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "making no progress"]
+    fn repeated_no_progress() {
+        let parser = empty::<&str, extra::Default>()
+            .repeated()
+            .collect::<Vec<_>>();
+
+        let _ = parser.parse("");
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "making no progress"]
+    fn separated_by_no_progress() {
+        let parser = empty::<&str, extra::Default>()
+            .separated_by(empty())
+            .collect::<Vec<_>>();
+
+        let _ = parser.parse("");
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    fn repeated_no_progress_release() {
+        // Without debug assertions, the repetition fails at the iteration that makes no progress
+        let parser = just::<_, _, extra::Err<Simple<char>>>('a')
+            .or_not()
+            .repeated()
+            .collect::<Vec<_>>();
+
+        let errs = parser.parse("aab").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(2, 3));
+        assert_eq!(errs[0].found(), Some(&'b'));
+
+        // Errors of alternatives tried before the repetition are kept
+        let parser = just::<_, _, extra::Err<Rich<char>>>('x')
+            .or_not()
+            .then(just('a').or_not().repeated());
+
+        let errs = parser.parse("b").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(0, 1));
+        assert_eq!(errs[0].expected().count(), 1);
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    fn separated_by_no_progress_release() {
+        let parser = empty::<&str, extra::Default>()
+            .separated_by(empty())
+            .collect::<Vec<_>>();
+
+        assert!(parser.parse("").has_errors());
+    }
+
     #[test]
     fn separated_by_at_least() {
         let parser = just::<_, _, extra::Default>('-')
@@ -1,7 +1,8 @@
 //! TODO
 
 use super::*;
-use alloc::collections::LinkedList;
+use alloc::{borrow::Cow, collections::LinkedList};
+use core::ops::Bound;
 use hashbrown::HashSet;
 
 /// A utility trait for types that can be constructed from a series of items.
@@ -142,6 +143,88 @@ impl<T: Ord> Container<T> for alloc::collections::BTreeSet<T> {
     }
 }
 
+/// A hint for the number of items a [`Container`] is going to hold, used by
+/// [`IterParser::collect_with_capacity_hint`].
+///
+/// This trait is implemented for:
+///
+/// - `()`: no hint, the container is created with [`Default::default`]
+/// - [`usize`]: a constant number of items
+/// - `Fn(I::Slice) -> usize`: a number of items computed from the remaining input (for [`SliceInput`]s)
+pub trait CapacityHint<'a, I: Input<'a>> {
+    /// Determine the capacity to create the container with, given the input that is about to be parsed.
+    #[doc(hidden)]
+    fn capacity<E: ParserExtra<'a, I>>(&self, inp: &InputRef<'a, '_, I, E>) -> Option<usize>;
+}
+
+impl<'a, I: Input<'a>> CapacityHint<'a, I> for () {
+    #[inline(always)]
+    fn capacity<E: ParserExtra<'a, I>>(&self, _inp: &InputRef<'a, '_, I, E>) -> Option<usize> {
+        None
+    }
+}
+
+impl<'a, I: Input<'a>> CapacityHint<'a, I> for usize {
+    #[inline(always)]
+    fn capacity<E: ParserExtra<'a, I>>(&self, _inp: &InputRef<'a, '_, I, E>) -> Option<usize> {
+        Some(*self)
+    }
+}
+
+impl<'a, I, F> CapacityHint<'a, I> for F
+where
+    I: SliceInput<'a>,
+    F: Fn(I::Slice) -> usize,
+{
+    #[inline(always)]
+    fn capacity<E: ParserExtra<'a, I>>(&self, inp: &InputRef<'a, '_, I, E>) -> Option<usize> {
+        Some(self(inp.slice_from_inner(inp.offset..)))
+    }
+}
+
+/// A [`Container`] of key-value pairs that can be queried for a key, used by [`IterParser::collect_map`].
+pub trait MapContainer<K, V>: Container<(K, V)> {
+    /// Returns `true` if this container already has an entry for the given key.
+    fn contains_key(&self, key: &K) -> bool;
+}
+
+impl<K: Eq + Hash, V> MapContainer<K, V> for HashMap<K, V> {
+    fn contains_key(&self, key: &K) -> bool {
+        (*self).contains_key(key)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Eq + Hash, V> MapContainer<K, V> for std::collections::HashMap<K, V> {
+    fn contains_key(&self, key: &K) -> bool {
+        (*self).contains_key(key)
+    }
+}
+
+impl<K: Ord, V> MapContainer<K, V> for alloc::collections::BTreeMap<K, V> {
+    fn contains_key(&self, key: &K) -> bool {
+        (*self).contains_key(key)
+    }
+}
+
+/// What to do when [`IterParser::collect_map`] encounters a key that has already been collected.
+pub enum Duplicates<K, S, Err> {
+    /// Replace the existing value with the new one. This is what [`IterParser::collect`] does.
+    Overwrite,
+    /// Keep the existing value, discarding the new one.
+    KeepFirst,
+    /// Keep the existing value, and emit the error created by the given function from the duplicate key and the
+    /// span of its entry (including any separator that precedes it). Parsing continues as normal.
+    Error(fn(&K, S) -> Err),
+}
+
+impl<K, S, Err> Copy for Duplicates<K, S, Err> {}
+impl<K, S, Err> Clone for Duplicates<K, S, Err> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
 /// A utility trait for types that hold a specific constant number of output values.
 ///
 /// # Safety
@@ -286,10 +369,28 @@ pub trait Seq<'p, T> {
     where
         T: PartialEq;
 
+    /// Check whether this sequence contains no items.
+    ///
+    /// Empty sequences are usually a mistake: [`just`] accepts them without consuming any input, [`one_of`] can never
+    /// succeed with them, and [`none_of`] accepts any token. Combinators may use this to special-case them.
+    #[inline(always)]
+    fn is_empty(&self) -> bool {
+        self.seq_iter().next().is_none()
+    }
+
     /// Convert an item of the sequence into a [`MaybeRef`].
     fn to_maybe_ref<'b>(item: Self::Item<'b>) -> MaybeRef<'p, T>
     where
         'p: 'b;
+
+    /// If the items of this sequence form a contiguous range, get the start and end of that range.
+    ///
+    /// This is used to describe the sequence compactly in errors (for example, as `'0'..='9'` rather than as a list of
+    /// every digit). By default, sequences are assumed to be arbitrary sets of items.
+    #[inline(always)]
+    fn seq_range(&self) -> Option<(MaybeRef<'p, T>, Bound<MaybeRef<'p, T>>)> {
+        None
+    }
 }
 
 impl<'p, T: Clone> Seq<'p, T> for T {
@@ -634,6 +735,14 @@ where
     {
         MaybeRef::Val(item)
     }
+
+    #[inline]
+    fn seq_range(&self) -> Option<(MaybeRef<'p, T>, Bound<MaybeRef<'p, T>>)> {
+        Some((
+            MaybeRef::Val(self.start.clone()),
+            Bound::Excluded(MaybeRef::Val(self.end.clone())),
+        ))
+    }
 }
 
 impl<'p, T> Seq<'p, T> for core::ops::RangeInclusive<T>
@@ -666,6 +775,14 @@ where
     {
         MaybeRef::Val(item)
     }
+
+    #[inline]
+    fn seq_range(&self) -> Option<(MaybeRef<'p, T>, Bound<MaybeRef<'p, T>>)> {
+        Some((
+            MaybeRef::Val(self.start().clone()),
+            Bound::Included(MaybeRef::Val(self.end().clone())),
+        ))
+    }
 }
 
 impl<'p, T> Seq<'p, T> for RangeFrom<T>
@@ -698,6 +815,11 @@ where
     {
         MaybeRef::Val(item)
     }
+
+    #[inline]
+    fn seq_range(&self) -> Option<(MaybeRef<'p, T>, Bound<MaybeRef<'p, T>>)> {
+        Some((MaybeRef::Val(self.start.clone()), Bound::Unbounded))
+    }
 }
 
 impl<'p> Seq<'p, char> for str {
@@ -726,6 +848,11 @@ impl<'p> Seq<'p, char> for str {
     {
         MaybeRef::Val(item)
     }
+
+    #[inline]
+    fn seq_range(&self) -> Option<(MaybeRef<'p, char>, Bound<MaybeRef<'p, char>>)> {
+        contiguous_chars(self)
+    }
 }
 
 impl<'p> Seq<'p, char> for &'p str {
@@ -754,6 +881,11 @@ impl<'p> Seq<'p, char> for &'p str {
     {
         MaybeRef::Val(item)
     }
+
+    #[inline]
+    fn seq_range(&self) -> Option<(MaybeRef<'p, char>, Bound<MaybeRef<'p, char>>)> {
+        contiguous_chars(self)
+    }
 }
 
 impl<'p> Seq<'p, char> for String {
@@ -782,6 +914,191 @@ impl<'p> Seq<'p, char> for String {
     {
         MaybeRef::Val(item)
     }
+
+    #[inline]
+    fn seq_range(&self) -> Option<(MaybeRef<'p, char>, Bound<MaybeRef<'p, char>>)> {
+        contiguous_chars(self)
+    }
+}
+
+// Owned and shared strings are sequences of chars, like `str`
+macro_rules! impl_seq_for_str {
+    ($([$($gen:tt)*] $ty:ty),* $(,)?) => {$(
+        impl<'p, $($gen)*> Seq<'p, char> for $ty {
+            type Item<'a> = char
+            where
+                Self: 'a;
+
+            type Iter<'a> = core::str::Chars<'a>
+            where
+                Self: 'a;
+
+            #[inline(always)]
+            fn seq_iter(&self) -> Self::Iter<'_> {
+                self.chars()
+            }
+
+            #[inline(always)]
+            fn contains(&self, val: &char) -> bool {
+                str::contains(self, *val)
+            }
+
+            #[inline]
+            fn to_maybe_ref<'b>(item: Self::Item<'b>) -> MaybeRef<'p, char>
+            where
+                'p: 'b,
+            {
+                MaybeRef::Val(item)
+            }
+
+            #[inline]
+            fn seq_range(&self) -> Option<(MaybeRef<'p, char>, Bound<MaybeRef<'p, char>>)> {
+                contiguous_chars(self)
+            }
+        }
+
+        impl<'p, $($gen)*> OrderedSeq<'p, char> for $ty {}
+    )*};
+}
+
+impl_seq_for_str!(['c: 'p] Cow<'c, str>, [] Box<str>, [] Rc<str>, [] Arc<str>);
+
+// Owned and shared slices are sequences of their items, like `Vec<T>`
+macro_rules! impl_seq_for_slice {
+    ($([$($gen:tt)*] $ty:ty),* $(,)?) => {$(
+        impl<'p, $($gen)* T: Clone> Seq<'p, T> for $ty {
+            type Item<'a> = &'a T
+            where
+                Self: 'a;
+
+            type Iter<'a> = core::slice::Iter<'a, T>
+            where
+                Self: 'a;
+
+            #[inline(always)]
+            fn seq_iter(&self) -> Self::Iter<'_> {
+                self.iter()
+            }
+
+            #[inline(always)]
+            fn contains(&self, val: &T) -> bool
+            where
+                T: PartialEq,
+            {
+                <[T]>::contains(self, val)
+            }
+
+            #[inline]
+            fn to_maybe_ref<'b>(item: Self::Item<'b>) -> MaybeRef<'p, T>
+            where
+                'p: 'b,
+            {
+                MaybeRef::Val(item.clone())
+            }
+        }
+
+        impl<'p, $($gen)* T: Clone> OrderedSeq<'p, T> for $ty {}
+    )*};
+}
+
+impl_seq_for_slice!(['c: 'p,] Cow<'c, [T]>, [] Box<[T]>, [] Rc<[T]>, [] Arc<[T]>);
+
+/// A sequence made from any cloneable iterable value, such as an iterator adaptor or a collection that does not
+/// implement [`Seq`] itself.
+///
+/// The inner value is cloned and iterated afresh each time the sequence is used, so cheaply cloneable iterables (like
+/// `Map<slice::Iter<_>, _>`) work best.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, container::IterSeq};
+/// let keywords = vec![String::from("let"), String::from("fn")];
+/// let keyword = one_of::<_, _, extra::Err<Simple<char>>>(IterSeq::new(
+///     keywords.iter().filter_map(|kw| kw.chars().next()),
+/// ));
+///
+/// assert_eq!(keyword.parse("f").into_result(), Ok('f'));
+/// assert!(keyword.parse("x").has_errors());
+/// ```
+pub struct IterSeq<I, T> {
+    iter: I,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<T>,
+}
+
+impl<I, T> IterSeq<I, T>
+where
+    I: IntoIterator<Item = T> + Clone,
+{
+    /// Create a sequence from a cloneable iterable value.
+    pub const fn new(iter: I) -> Self {
+        Self {
+            iter,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<I: Copy, T> Copy for IterSeq<I, T> {}
+impl<I: Clone, T> Clone for IterSeq<I, T> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'p, T, I> Seq<'p, T> for IterSeq<I, T>
+where
+    I: IntoIterator<Item = T> + Clone + 'p,
+{
+    type Item<'a> = T
+    where
+        Self: 'a;
+
+    type Iter<'a> = I::IntoIter
+    where
+        Self: 'a;
+
+    #[inline(always)]
+    fn seq_iter(&self) -> Self::Iter<'_> {
+        self.iter.clone().into_iter()
+    }
+
+    #[inline]
+    fn contains(&self, val: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter.clone().into_iter().any(|item| item == *val)
+    }
+
+    #[inline]
+    fn to_maybe_ref<'b>(item: Self::Item<'b>) -> MaybeRef<'p, T>
+    where
+        'p: 'b,
+    {
+        MaybeRef::Val(item)
+    }
+}
+
+impl<'p, T, I> OrderedSeq<'p, T> for IterSeq<I, T> where I: IntoIterator<Item = T> + Clone + 'p {}
+
+// Strings of at least 3 consecutive chars, like `"0123456789"`, are described as ranges
+fn contiguous_chars<'p>(s: &str) -> Option<(MaybeRef<'p, char>, Bound<MaybeRef<'p, char>>)> {
+    let mut chars = s.chars();
+    let first = chars.next()?;
+    let mut last = first;
+    for c in chars {
+        if c as u32 != last as u32 + 1 {
+            return None;
+        }
+        last = c;
+    }
+    (last as u32 >= first as u32 + 2)
+        .then_some((MaybeRef::Val(first), Bound::Included(MaybeRef::Val(last))))
 }
 
 /// A utility trait to abstract over *linear* container-like things.
@@ -803,6 +1120,53 @@ impl<'p> OrderedSeq<'p, char> for str {}
 impl<'p> OrderedSeq<'p, char> for &'p str {}
 impl<'p> OrderedSeq<'p, char> for String {}
 
+/// A utility trait for values whose items can be appended to a [`Vec`], used by [`Parser::chain`].
+///
+/// This trait is likely to change in future versions of the crate, so avoid implementing it yourself.
+pub trait Chain<T> {
+    /// The number of items that this value will append.
+    fn chain_len(&self) -> usize;
+
+    /// Append the items of this value to the end of the given vector.
+    fn append_to(self, v: &mut Vec<T>);
+}
+
+impl<T> Chain<T> for T {
+    fn chain_len(&self) -> usize {
+        1
+    }
+    fn append_to(self, v: &mut Vec<T>) {
+        v.push(self);
+    }
+}
+
+impl<T> Chain<T> for Option<T> {
+    fn chain_len(&self) -> usize {
+        self.is_some() as usize
+    }
+    fn append_to(self, v: &mut Vec<T>) {
+        v.extend(self);
+    }
+}
+
+impl<T> Chain<T> for Vec<T> {
+    fn chain_len(&self) -> usize {
+        self.len()
+    }
+    fn append_to(mut self, v: &mut Vec<T>) {
+        v.append(&mut self);
+    }
+}
+
+impl<T, const N: usize> Chain<T> for [T; N] {
+    fn chain_len(&self) -> usize {
+        N
+    }
+    fn append_to(self, v: &mut Vec<T>) {
+        v.extend(self);
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -850,4 +1214,49 @@ mod test {
         assert_eq!(&**c, &[0, 1, 2, 3]);
         drop_container::<Box<Rc<[usize; 4]>>>();
     }
+
+    #[test]
+    fn seq_is_empty() {
+        assert!(Seq::<char>::is_empty(&""));
+        assert!(!Seq::<char>::is_empty(&"a"));
+        assert!(Seq::<u8>::is_empty(&Vec::<u8>::new()));
+        assert!(Seq::<u8>::is_empty(&(3..3)));
+        assert!(!Seq::<u8>::is_empty(&(3..=3)));
+        assert!(!Seq::<u8>::is_empty(&(3..)));
+        assert!(!Seq::<char>::is_empty(&'x'));
+    }
+
+    #[test]
+    fn seq_shared_and_iter() {
+        use crate::prelude::*;
+
+        fn parse<'a, T: OrderedSeq<'a, char> + Clone>(seq: T, src: &'a str) -> bool {
+            just::<_, _, extra::Default>(seq)
+                .parse(src)
+                .into_output()
+                .is_some()
+        }
+
+        assert!(parse(Cow::from("let"), "let"));
+        assert!(parse(Cow::<str>::Owned("let".into()), "let"));
+        assert!(parse(Rc::<str>::from("let"), "let"));
+        assert!(!parse(Arc::<str>::from("let"), "lex"));
+        assert!(parse(Box::<str>::from("let"), "let"));
+        assert!(parse(Arc::<[char]>::from(['l', 'e', 't']), "let"));
+        assert!(parse(Cow::<[char]>::Borrowed(&['l', 'e', 't']), "let"));
+
+        let keywords = [String::from("let"), String::from("fn")];
+        let initial = IterSeq::new(keywords.iter().filter_map(|kw| kw.chars().next()));
+        assert!(initial.contains(&'f'));
+        assert!(!initial.contains(&'x'));
+        assert!(parse(IterSeq::new("let".chars()), "let"));
+        assert_eq!(
+            one_of::<_, &str, extra::Default>(initial)
+                .repeated()
+                .collect::<String>()
+                .parse("lf")
+                .into_result(),
+            Ok(String::from("lf")),
+        );
+    }
 }
@@ -0,0 +1,247 @@
+//! Parsers for simple data types, and a derive macro to generate them.
+//!
+//! The [`Parse`] trait associates a type with a textual syntax. It is implemented for common primitive types, and
+//! may be derived for structs and enums with `#[derive(Parse)]`.
+//!
+//! - Enums parse one of their variants, each introduced by a keyword. By default, the keyword of a variant is its
+//!   name in `snake_case`.
+//!
+//! - Structs, and the fields of enum variants, parse their fields in order. Unit structs parse a keyword, like unit
+//!   variants.
+//!
+//! Whitespace is permitted around every element. The syntax can be adjusted with `#[parse(...)]` attributes:
+//!
+//! | Attribute          | Applies to                  | Effect                                            |
+//! |--------------------|-----------------------------|---------------------------------------------------|
+//! | `keyword = "..."`  | structs, variants           | Begin with the given keyword                      |
+//! | `open = "..."`     | structs, variants           | Expect the given delimiter before the fields      |
+//! | `close = "..."`    | structs, variants           | Expect the given delimiter after the fields       |
+//! | `sep = "..."`      | structs, variants           | Expect the given separator between fields         |
+//! | `prefix = "..."`   | fields                      | Expect the given text before the field            |
+//!
+//! Derived parsers do not support generic types. They also cannot parse recursive types, since each call to
+//! [`Parse::parser`] creates the parsers of all of the fields. Use [`recursive`] to
+//! write parsers for such types by hand.
+//!
+//! # Examples
+//!
+//! ```
+//! use chumsky::{derive::Parse, prelude::*};
+//!
+//! #[derive(Parse, Debug, PartialEq)]
+//! enum Level {
+//!     Debug,
+//!     Info,
+//!     #[parse(keyword = "warn")]
+//!     Warning,
+//! }
+//!
+//! #[derive(Parse, Debug, PartialEq)]
+//! #[parse(open = "{", close = "}", sep = ",")]
+//! struct Config {
+//!     #[parse(prefix = "name:")]
+//!     name: String,
+//!     #[parse(prefix = "port:")]
+//!     port: u16,
+//!     #[parse(prefix = "level:")]
+//!     level: Level,
+//! }
+//!
+//! let parser = <Config as Parse>::parser();
+//!
+//! let src = r#"{ name: "server", port: 8080, level: warn }"#;
+//! assert_eq!(
+//!     parser.parse(src).into_result(),
+//!     Ok(Config {
+//!         name: "server".to_string(),
+//!         port: 8080,
+//!         level: Level::Warning,
+//!     }),
+//! );
+//! // The port is out of range
+//! assert!(parser.parse(r#"{ name: "server", port: 99999, level: warn }"#).has_errors());
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use core::str::FromStr;
+
+use crate::{extra::ParserExtra, prelude::*};
+
+/// Derive [`Parse`](trait@Parse) for a struct or enum.
+pub use chumsky_macros::Parse;
+
+/// A type that has a textual syntax, which can be parsed from a `&str`.
+///
+/// See the [module-level documentation](self) for how to derive this trait, and which types implement it.
+///
+/// The parser's [`ParserExtra`] defaults to [`extra::Default`], but defaults are not used for type inference: write
+/// `<T as Parse>::parser()` to get a parser that uses it.
+///
+/// # Examples
+///
+/// ```
+/// use chumsky::{derive::Parse, prelude::*};
+///
+/// #[derive(Parse, Debug, PartialEq)]
+/// enum Shape {
+///     #[parse(open = "(", close = ")")]
+///     Circle(f64),
+///     #[parse(keyword = "rect", open = "(", close = ")", sep = ",")]
+///     Rectangle { width: f64, height: f64 },
+///     Point,
+/// }
+///
+/// assert_eq!(
+///     <Vec<Shape> as Parse>::parser()
+///         .parse("[circle(1.5), rect(2, 0.5), point]")
+///         .into_result(),
+///     Ok(vec![
+///         Shape::Circle(1.5),
+///         Shape::Rectangle { width: 2.0, height: 0.5 },
+///         Shape::Point,
+///     ]),
+/// );
+/// ```
+pub trait Parse<'a, E: ParserExtra<'a, &'a str> = extra::Default>: Sized {
+    /// Create a parser for this type.
+    ///
+    /// The parser does not consume whitespace surrounding the value.
+    fn parser() -> Boxed<'a, 'a, &'a str, Self, E>;
+}
+
+fn from_str<'a, T: FromStr, E: ParserExtra<'a, &'a str>>(
+    s: &'a str,
+    span: SimpleSpan,
+) -> Result<T, E::Error> {
+    s.parse()
+        .map_err(|_| E::Error::expected_found(None, None, span))
+}
+
+macro_rules! impl_parse_for_int {
+    ($($T:ty),*) => {$(
+        /// Integers in base 10, with an optional leading `-`.
+        impl<'a, E: ParserExtra<'a, &'a str> + 'a> Parse<'a, E> for $T {
+            fn parser() -> Boxed<'a, 'a, &'a str, Self, E> {
+                just('-')
+                    .or_not()
+                    .then(text::int(10))
+                    .slice()
+                    .try_map(from_str::<_, E>)
+                    .boxed()
+            }
+        }
+    )*};
+}
+
+impl_parse_for_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+macro_rules! impl_parse_for_float {
+    ($($T:ty),*) => {$(
+        /// Decimal numbers, with an optional leading `-`, fractional part and exponent.
+        impl<'a, E: ParserExtra<'a, &'a str> + 'a> Parse<'a, E> for $T {
+            fn parser() -> Boxed<'a, 'a, &'a str, Self, E> {
+                just('-')
+                    .or_not()
+                    .then(text::int(10))
+                    .then(just('.').then(text::digits(10)).or_not())
+                    .then(
+                        one_of("eE")
+                            .then(one_of("+-").or_not())
+                            .then(text::digits(10))
+                            .or_not(),
+                    )
+                    .slice()
+                    .try_map(from_str::<_, E>)
+                    .boxed()
+            }
+        }
+    )*};
+}
+
+impl_parse_for_float!(f32, f64);
+
+/// `true` or `false`.
+impl<'a, E: ParserExtra<'a, &'a str> + 'a> Parse<'a, E> for bool {
+    fn parser() -> Boxed<'a, 'a, &'a str, Self, E> {
+        text::ascii::keyword("true")
+            .to(true)
+            .or(text::ascii::keyword("false").to(false))
+            .boxed()
+    }
+}
+
+/// A double-quoted string, supporting the escape sequences `\"`, `\\`, `\n` and `\t`.
+impl<'a, E: ParserExtra<'a, &'a str> + 'a> Parse<'a, E> for String {
+    fn parser() -> Boxed<'a, 'a, &'a str, Self, E> {
+        let escape = just('\\').ignore_then(choice((
+            just('"'),
+            just('\\'),
+            just('n').to('\n'),
+            just('t').to('\t'),
+        )));
+        none_of("\\\"")
+            .or(escape)
+            .repeated()
+            .collect()
+            .delimited_by(just('"'), just('"'))
+            .boxed()
+    }
+}
+
+/// The value, optionally.
+impl<'a, T: Parse<'a, E> + 'a, E: ParserExtra<'a, &'a str> + 'a> Parse<'a, E> for Option<T> {
+    fn parser() -> Boxed<'a, 'a, &'a str, Self, E> {
+        T::parser().or_not().boxed()
+    }
+}
+
+/// A list of values in square brackets, separated by commas, with an optional trailing comma.
+impl<'a, T: Parse<'a, E> + 'a, E: ParserExtra<'a, &'a str> + 'a> Parse<'a, E> for Vec<T> {
+    fn parser() -> Boxed<'a, 'a, &'a str, Self, E> {
+        T::parser()
+            .padded()
+            .separated_by(just(','))
+            .allow_trailing()
+            .collect()
+            .padded()
+            .delimited_by(just('['), just(']'))
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse<T: for<'a> Parse<'a>>(src: &str) -> Option<T> {
+        T::parser().parse(src).into_output()
+    }
+
+    #[test]
+    fn primitives() {
+        assert_eq!(parse::<i32>("-42"), Some(-42));
+        assert_eq!(parse::<u8>("255"), Some(255));
+        assert_eq!(parse::<u8>("256"), None);
+        assert_eq!(parse::<u8>("-1"), None);
+        assert_eq!(parse::<f64>("-1.5e3"), Some(-1500.0));
+        assert_eq!(parse::<f32>("2"), Some(2.0));
+        assert_eq!(parse::<bool>("true"), Some(true));
+        assert_eq!(parse::<bool>("falsey"), None);
+        assert_eq!(
+            parse::<String>(r#""a \"quoted\"\n\\ string""#),
+            Some("a \"quoted\"\n\\ string".to_string())
+        );
+    }
+
+    #[test]
+    fn containers() {
+        assert_eq!(parse::<Vec<u32>>("[1, 2 ,3,]"), Some(vec![1, 2, 3]));
+        assert_eq!(parse::<Vec<u32>>("[ ]"), Some(vec![]));
+        assert_eq!(
+            parse::<Vec<Vec<bool>>>("[[true], [false, true]]"),
+            Some(vec![vec![true], vec![false, true]])
+        );
+        assert_eq!(parse::<Option<i64>>("7"), Some(Some(7)));
+        assert_eq!(parse::<Option<i64>>(""), Some(None));
+    }
+}
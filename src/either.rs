@@ -28,6 +28,13 @@ where
         }
     }
 
+    fn walk_node(&self, walker: &mut crate::walk::Walker<'_>) {
+        walker.node::<Self>(|walker| match self {
+            Either::Left(l) => l.walk_node(walker),
+            Either::Right(r) => r.walk_node(walker),
+        });
+    }
+
     go_extra!(O);
 }
 
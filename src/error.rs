@@ -8,6 +8,7 @@
 
 use super::*;
 use alloc::string::ToString;
+use core::{fmt::Write, ops::Bound};
 
 /// A trait that describes parser error types.
 ///
@@ -96,6 +97,75 @@ pub trait Error<'a, I: Input<'a>>: Sized {
         self.merge(Self::expected_found(expected, found, span))
     }
 
+    /// Create a new error describing a conflict between an expected range of inputs and that which was actually found,
+    /// such as when [`one_of`] is given a range.
+    ///
+    /// `start` and `end` bound the range, which lets a message say "expected 'a'..='z'" instead of listing 26
+    /// characters. `expected` still lists every input within the range, and is what the default implementation passes
+    /// on to [`Error::expected_found`].
+    #[inline(always)]
+    fn expected_range_found<E: IntoIterator<Item = Option<MaybeRef<'a, I::Token>>>>(
+        start: MaybeRef<'a, I::Token>,
+        end: Bound<MaybeRef<'a, I::Token>>,
+        expected: E,
+        found: Option<MaybeRef<'a, I::Token>>,
+        span: I::Span,
+    ) -> Self {
+        #![allow(unused_variables)]
+        Self::expected_found(expected, found, span)
+    }
+
+    /// Create a new error describing a conflict between an expected sequence of inputs and that which was actually
+    /// found, such as when [`just`] finds the end of input part way through its sequence or [`text::ascii::keyword`]
+    /// finds a different identifier.
+    ///
+    /// `kind` says what sort of sequence `seq` is, and `seq` holds all of it, even if part of it was matched before the
+    /// conflict. `expected` holds only the input that was expected at the point of the conflict, and is what the
+    /// default implementation passes on to [`Error::expected_found`]. A keyword is compared with a whole identifier
+    /// rather than token by token, so no single input was expected and its `expected` is empty.
+    #[inline(always)]
+    fn expected_seq_found<
+        S: IntoIterator<Item = MaybeRef<'a, I::Token>>,
+        E: IntoIterator<Item = Option<MaybeRef<'a, I::Token>>>,
+    >(
+        kind: SeqKind,
+        seq: S,
+        expected: E,
+        found: Option<MaybeRef<'a, I::Token>>,
+        span: I::Span,
+    ) -> Self {
+        #![allow(unused_variables)]
+        Self::expected_found(expected, found, span)
+    }
+
+    /// Create a new error describing input that was found despite being explicitly ruled out, such as a token in the
+    /// sequence given to [`none_of`].
+    ///
+    /// `unexpected` contains every input that was ruled out, not only the one that was found, so that a message can say
+    /// "expected anything other than 'a' or 'b'". Nothing in particular was expected, so the default implementation
+    /// reports only what was found.
+    #[inline(always)]
+    fn unexpected_found<U: IntoIterator<Item = MaybeRef<'a, I::Token>>>(
+        unexpected: U,
+        found: Option<MaybeRef<'a, I::Token>>,
+        span: I::Span,
+    ) -> Self {
+        #![allow(unused_variables)]
+        Self::expected_found(None, found, span)
+    }
+
+    /// Attach the errors of the other alternatives of a [`choice`] that keeps all of them (see
+    /// [`Choice::all_errors`](crate::primitive::Choice::all_errors)) to this error, the error of the alternative that
+    /// got furthest into the input.
+    ///
+    /// `branches` holds the errors of the other alternatives, in the order that the alternatives were tried. The
+    /// default implementation drops them, leaving this error as it was.
+    #[inline(always)]
+    fn with_branches<B: IntoIterator<Item = Self>>(self, branches: B) -> Self {
+        #![allow(unused_variables)]
+        self
+    }
+
     /// Fast path for `a = Error::expected_found(...)` that may incur less overhead by, for example, reusing allocations.
     #[inline(always)]
     fn replace_expected_found<E: IntoIterator<Item = Option<MaybeRef<'a, I::Token>>>>(
@@ -139,6 +209,13 @@ pub struct Cheap<S = SimpleSpan<usize>> {
     span: S,
 }
 
+impl<S> Cheap<S> {
+    /// Get the span associated with this error.
+    pub fn span(&self) -> &S {
+        &self.span
+    }
+}
+
 impl<'a, I: Input<'a>> Error<'a, I> for Cheap<I::Span> {
     #[inline]
     fn expected_found<E: IntoIterator<Item = Option<MaybeRef<'a, I::Token>>>>(
@@ -169,6 +246,56 @@ where
     }
 }
 
+/// The category of an error produced by one of the built-in error types, such as [`Simple`] or [`Rich`].
+///
+/// Each category has a stable code (see [`ErrorCategory::code`]) that will not change meaning or be reused between
+/// versions of chumsky, making it suitable for filtering or suppressing errors, or for linking them to documentation.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum ErrorCategory {
+    /// `C001`: some input was found where different input was expected.
+    UnexpectedInput,
+    /// `C002`: the end of input was found where more input was expected.
+    UnexpectedEnd,
+    /// `C003`: input was found that was explicitly ruled out, such as by [`none_of`].
+    ExcludedInput,
+    /// `C004`: an error with a custom message, such as one created with [`Rich::custom`].
+    Custom,
+    /// `C005`: several unrelated errors that occurred at the same location.
+    Many,
+}
+
+impl ErrorCategory {
+    /// Get the stable code of this category, such as `"C001"`.
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::UnexpectedInput => "C001",
+            Self::UnexpectedEnd => "C002",
+            Self::ExcludedInput => "C003",
+            Self::Custom => "C004",
+            Self::Many => "C005",
+        }
+    }
+
+    /// Get a short description of this category, such as `"unexpected input"`.
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::UnexpectedInput => "unexpected input",
+            Self::UnexpectedEnd => "unexpected end of input",
+            Self::ExcludedInput => "excluded input",
+            Self::Custom => "custom error",
+            Self::Many => "many errors",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.code(), self.description())
+    }
+}
+
 /// A simple error type that tracks the error span and found token. This type is most useful when you want fast parsing
 /// but do not particularly care about the quality of error messages.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -179,6 +306,44 @@ pub struct Simple<'a, T, S = SimpleSpan<usize>> {
 }
 
 impl<'a, T, S> Simple<'a, T, S> {
+    /// Get the span associated with this error.
+    pub fn span(&self) -> &S {
+        &self.span
+    }
+
+    /// Get the token found by this error when it occurred. `None` implies that the end of input was found.
+    pub fn found(&self) -> Option<&T> {
+        self.found.as_deref()
+    }
+
+    /// Get the category of this error.
+    ///
+    /// Since this error type does not track what was expected, this is either [`ErrorCategory::UnexpectedInput`] or
+    /// [`ErrorCategory::UnexpectedEnd`].
+    pub fn category(&self) -> ErrorCategory {
+        match self.found {
+            Some(_) => ErrorCategory::UnexpectedInput,
+            None => ErrorCategory::UnexpectedEnd,
+        }
+    }
+
+    /// Get the stable code of this error's category. See [`ErrorCategory::code`].
+    pub fn code(&self) -> &'static str {
+        self.category().code()
+    }
+
+    /// Display this error with tokens formatted by `fmt_token`, rather than by their [`Debug`](fmt::Debug)
+    /// implementation. See [`Rich::display_with`].
+    pub fn display_with<F>(&self, fmt_token: F) -> DisplayWith<'_, Self, F>
+    where
+        F: Fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+    {
+        DisplayWith {
+            error: self,
+            fmt_token,
+        }
+    }
+
     /// Transform this error's tokens using the given function.
     ///
     /// This is useful when you wish to combine errors from multiple compilation passes (lexing and parsing, say) where
@@ -228,12 +393,44 @@ where
     }
 }
 
+/// The sort of sequence described by [`Error::expected_seq_found`] and [`RichPattern::Seq`].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum SeqKind {
+    /// A literal sequence of tokens, such as that given to [`just`].
+    Literal,
+    /// A keyword, such as that given to [`text::ascii::keyword`], which is only matched by an identifier made up of
+    /// exactly its characters.
+    Keyword,
+}
+
 /// An expected pattern for a [`Rich`] error.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
 pub enum RichPattern<'a, T, L = &'static str> {
     /// A specific token was expected.
     Token(MaybeRef<'a, T>),
+    /// A token within a range was expected.
+    Range {
+        /// The first token of the range.
+        start: MaybeRef<'a, T>,
+        /// The end of the range, or `None` if the range has no upper limit.
+        end: Option<MaybeRef<'a, T>>,
+        /// Whether `end` is itself part of the range.
+        inclusive: bool,
+    },
+    /// A specific sequence of tokens was expected, such as that of a [`just`] parser that was only partially found
+    /// before the end of the input. Keywords are written with a `keyword` prefix in messages.
+    Seq {
+        /// The sort of sequence.
+        kind: SeqKind,
+        /// The whole sequence.
+        seq: Vec<MaybeRef<'a, T>>,
+    },
+    /// Any token other than those given was expected, such as by a [`none_of`] parser.
+    NoneOf(Vec<MaybeRef<'a, T>>),
     /// A labelled pattern was expected.
     Label(L),
     /// The end of input was expected.
@@ -251,6 +448,27 @@ impl<'a, T, L> RichPattern<'a, T, L> {
     {
         match self {
             Self::Token(t) => RichPattern::Token(f(t.into_inner()).into()),
+            Self::Range {
+                start,
+                end,
+                inclusive,
+            } => RichPattern::Range {
+                start: f(start.into_inner()).into(),
+                end: end.map(|end| f(end.into_inner()).into()),
+                inclusive,
+            },
+            Self::Seq { kind, seq } => RichPattern::Seq {
+                kind,
+                seq: seq
+                    .into_iter()
+                    .map(|tok| f(tok.into_inner()).into())
+                    .collect(),
+            },
+            Self::NoneOf(toks) => RichPattern::NoneOf(
+                toks.into_iter()
+                    .map(|tok| f(tok.into_inner()).into())
+                    .collect(),
+            ),
             Self::Label(s) => RichPattern::Label(s),
             Self::EndOfInput => RichPattern::EndOfInput,
         }
@@ -263,6 +481,22 @@ impl<'a, T, L> RichPattern<'a, T, L> {
     {
         match self {
             Self::Token(tok) => RichPattern::Token(tok.into_owned()),
+            Self::Range {
+                start,
+                end,
+                inclusive,
+            } => RichPattern::Range {
+                start: start.into_owned(),
+                end: end.map(MaybeRef::into_owned),
+                inclusive,
+            },
+            Self::Seq { kind, seq } => RichPattern::Seq {
+                kind,
+                seq: seq.into_iter().map(MaybeRef::into_owned).collect(),
+            },
+            Self::NoneOf(toks) => {
+                RichPattern::NoneOf(toks.into_iter().map(MaybeRef::into_owned).collect())
+            }
             Self::Label(label) => RichPattern::Label(label),
             Self::EndOfInput => RichPattern::EndOfInput,
         }
@@ -280,6 +514,43 @@ impl<'a, T, L> RichPattern<'a, T, L> {
                 fmt_token(tok, f)?;
                 write!(f, "'")
             }
+            Self::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                write!(f, "'")?;
+                fmt_token(start, f)?;
+                write!(f, "'{}", if *inclusive { "..=" } else { ".." })?;
+                if let Some(end) = end {
+                    write!(f, "'")?;
+                    fmt_token(end, f)?;
+                    write!(f, "'")?;
+                }
+                Ok(())
+            }
+            Self::Seq { kind, seq } => {
+                if let SeqKind::Keyword = kind {
+                    write!(f, "keyword ")?;
+                }
+                write!(f, "'")?;
+                for tok in seq {
+                    fmt_token(tok, f)?;
+                }
+                write!(f, "'")
+            }
+            Self::NoneOf(toks) => {
+                write!(f, "anything other than ")?;
+                for (i, tok) in toks.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "{}", if i + 1 == toks.len() { " or " } else { ", " })?;
+                    }
+                    write!(f, "'")?;
+                    fmt_token(tok, f)?;
+                    write!(f, "'")?;
+                }
+                Ok(())
+            }
             Self::Label(label) => fmt_label(label, f),
             Self::EndOfInput => write!(f, "end of input"),
         }
@@ -294,6 +565,28 @@ where
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::Token(t) => write!(f, "{t:?}"),
+            Self::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                write!(f, "{start:?}{}", if *inclusive { "..=" } else { ".." })?;
+                end.as_ref().map_or(Ok(()), |end| write!(f, "{end:?}"))
+            }
+            Self::Seq { kind, seq } => {
+                if let SeqKind::Keyword = kind {
+                    write!(f, "keyword ")?;
+                }
+                f.debug_list()
+                    .entries(seq.iter().map(|tok| &**tok))
+                    .finish()
+            }
+            Self::NoneOf(toks) => {
+                write!(f, "none of ")?;
+                f.debug_list()
+                    .entries(toks.iter().map(|tok| &**tok))
+                    .finish()
+            }
             Self::Label(label) => write!(f, "{label:?}"),
             Self::EndOfInput => write!(f, "end of input"),
         }
@@ -308,6 +601,9 @@ where
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Token(t) => write!(f, "'{}'", &**t),
+            Self::Range { .. } | Self::Seq { .. } | Self::NoneOf(_) => {
+                self.write(f, T::fmt, L::fmt)
+            }
             Self::Label(s) => write!(f, "{s}"),
             Self::EndOfInput => write!(f, "end of input"),
         }
@@ -318,6 +614,7 @@ where
 /// The reason for a [`Rich`] error.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
 pub enum RichReason<'a, T, L = &'static str> {
     /// An unexpected input was found
     ExpectedFound {
@@ -335,6 +632,18 @@ pub enum RichReason<'a, T, L = &'static str> {
 }
 
 impl<'a, T, L> RichReason<'a, T, L> {
+    /// Display this reason with tokens formatted by `fmt_token`, rather than by their [`Display`](fmt::Display)
+    /// implementation. See [`Rich::display_with`].
+    pub fn display_with<F>(&self, fmt_token: F) -> DisplayWith<'_, Self, F>
+    where
+        F: Fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+    {
+        DisplayWith {
+            error: self,
+            fmt_token,
+        }
+    }
+
     /// Return the token that was found by this error reason. `None` implies that the end of input was expected.
     pub fn found(&self) -> Option<&T> {
         match self {
@@ -344,6 +653,24 @@ impl<'a, T, L> RichReason<'a, T, L> {
         }
     }
 
+    /// Get the category of this error reason.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::ExpectedFound { found: None, .. } => ErrorCategory::UnexpectedEnd,
+            Self::ExpectedFound { expected, .. }
+                if !expected.is_empty()
+                    && expected
+                        .iter()
+                        .all(|pat| matches!(pat, RichPattern::NoneOf(_))) =>
+            {
+                ErrorCategory::ExcludedInput
+            }
+            Self::ExpectedFound { .. } => ErrorCategory::UnexpectedInput,
+            Self::Custom(_) => ErrorCategory::Custom,
+            Self::Many(_) => ErrorCategory::Many,
+        }
+    }
+
     /// Convert this reason into an owned version of itself by cloning any borrowed internal tokens, if necessary.
     pub fn into_owned<'b>(self) -> RichReason<'b, T, L>
     where
@@ -361,7 +688,6 @@ impl<'a, T, L> RichReason<'a, T, L> {
         }
     }
 
-    #[cfg(feature = "label")]
     fn take_found(&mut self) -> Option<MaybeRef<'a, T>> {
         match self {
             RichReason::ExpectedFound { found, .. } => found.take(),
@@ -511,19 +837,69 @@ where
     }
 }
 
+/// A suggested edit that would fix an error, such as inserting a missing semicolon. See [`Rich::with_suggestion`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Suggestion<S = SimpleSpan<usize>> {
+    /// The span of the input to replace. This is empty if the suggestion is an insertion.
+    pub span: S,
+    /// The text to replace the span with.
+    pub replacement: String,
+}
+
+/// A trait implemented by [`Error`]s that can carry suggested edits. See [`Parser::or_suggest_insert`].
+pub trait SuggestionError<'a, I: Input<'a>>: Error<'a, I> {
+    /// Suggest replacing the input covered by `span` with `replacement`. Insertions are represented by an empty span.
+    fn suggest(&mut self, span: I::Span, replacement: String);
+}
+
 /// A rich default error type that tracks error spans, expected inputs, and the actual input found at an error site.
 ///
 /// Please note that it uses a [`Vec`] to remember expected symbols. If you find this to be too slow, you can
-/// implement [`Error`] for your own error type or use [`Simple`] instead.
+/// implement [`Error`] for your own error type or use [`Simple`] instead. When the tokens are bytes, [`RichByte`]
+/// tracks expected inputs without allocating.
+///
+/// Applications can attach their own data (of type `X`) to errors with [`Rich::with_extension`].
 #[derive(Clone, PartialEq, Eq, Hash)]
-pub struct Rich<'a, T, S = SimpleSpan<usize>, L = &'static str> {
+pub struct Rich<'a, T, S = SimpleSpan<usize>, L = &'static str, X = ()> {
     span: S,
     reason: Box<RichReason<'a, T, L>>,
     #[cfg(feature = "label")]
     context: Vec<(L, S)>,
+    // Boxed to keep the size of the error down, since most errors have none of these
+    extras: Option<Box<RichExtras<'a, T, S, L, X>>>,
 }
 
-impl<'a, T, S, L> Rich<'a, T, S, L> {
+// The parts of a `Rich` error that are only present when explicitly attached
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct RichExtras<'a, T, S, L, X> {
+    labels: Vec<(S, String)>,
+    suggestions: Vec<Suggestion<S>>,
+    extension: Option<X>,
+    branches: Vec<Rich<'a, T, S, L, X>>,
+}
+
+impl<'a, T, S, L, X> RichExtras<'a, T, S, L, X> {
+    fn merge(&mut self, other: Self) {
+        self.labels.extend(other.labels);
+        self.suggestions.extend(other.suggestions);
+        self.extension = self.extension.take().or(other.extension);
+        self.branches.extend(other.branches);
+    }
+
+    fn map_branches<'b, U, F>(self, f: F) -> RichExtras<'b, U, S, L, X>
+    where
+        F: FnMut(Rich<'a, T, S, L, X>) -> Rich<'b, U, S, L, X>,
+    {
+        RichExtras {
+            labels: self.labels,
+            suggestions: self.suggestions,
+            extension: self.extension,
+            branches: self.branches.into_iter().map(f).collect(),
+        }
+    }
+}
+
+impl<'a, T, S, L, X> Rich<'a, T, S, L, X> {
     fn inner_fmt(
         &self,
         f: &mut fmt::Formatter<'_>,
@@ -542,7 +918,18 @@ impl<'a, T, S, L> Rich<'a, T, S, L> {
     }
 }
 
-impl<'a, T, S, L> Rich<'a, T, S, L> {
+impl<'a, T, S, L, X> Rich<'a, T, S, L, X> {
+    fn extras_mut(&mut self) -> &mut RichExtras<'a, T, S, L, X> {
+        self.extras.get_or_insert_with(|| {
+            Box::new(RichExtras {
+                labels: Vec::new(),
+                suggestions: Vec::new(),
+                extension: None,
+                branches: Vec::new(),
+            })
+        })
+    }
+
     /// Create an error with a custom message and span
     #[inline]
     pub fn custom<M: ToString>(span: S, msg: M) -> Self {
@@ -551,6 +938,7 @@ impl<'a, T, S, L> Rich<'a, T, S, L> {
             reason: Box::new(RichReason::Custom(msg.to_string())),
             #[cfg(feature = "label")]
             context: Vec::new(),
+            extras: None,
         }
     }
 
@@ -574,23 +962,209 @@ impl<'a, T, S, L> Rich<'a, T, S, L> {
         self.reason.found()
     }
 
+    /// Get the category of this error. See [`RichReason::category`].
+    pub fn category(&self) -> ErrorCategory {
+        self.reason.category()
+    }
+
+    /// Get the stable code of this error's category, such as `"C001"`. See [`ErrorCategory::code`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let parser = just::<_, _, extra::Err<Rich<char>>>('a');
+    ///
+    /// assert_eq!(parser.parse("b").into_errors()[0].code(), "C001");
+    /// assert_eq!(parser.parse("").into_errors()[0].code(), "C002");
+    /// ```
+    pub fn code(&self) -> &'static str {
+        self.category().code()
+    }
+
+    /// Display this error with tokens formatted by `fmt_token`, rather than by their [`Display`](fmt::Display)
+    /// implementation.
+    ///
+    /// Tokens are usually displayed by their [`Display`](fmt::Display) implementation, but this is often not how they
+    /// appear in the source (a `Token::LBrace` would be better shown as `{`), and may be unsuitable for an error message
+    /// (such as a long string literal). The result can be passed to [`render`] too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// enum Token { LBrace, RBrace, Str(String) }
+    ///
+    /// let block = just::<_, _, extra::Err<Rich<Token>>>(Token::LBrace).then(just(Token::RBrace));
+    /// let tokens = [Token::LBrace, Token::Str("a long string".to_string())];
+    /// let errs = block.parse(&tokens[..]).into_errors();
+    ///
+    /// let msg = errs[0].display_with(|tok, f| match tok {
+    ///     Token::LBrace => write!(f, "{{"),
+    ///     Token::RBrace => write!(f, "}}"),
+    ///     Token::Str(_) => write!(f, "<string>"),
+    /// });
+    /// assert_eq!(msg.to_string(), "found '<string>' expected '}'");
+    /// ```
+    pub fn display_with<F>(&self, fmt_token: F) -> DisplayWith<'_, Self, F>
+    where
+        F: Fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+    {
+        DisplayWith {
+            error: self,
+            fmt_token,
+        }
+    }
+
     /// Return an iterator over the labelled contexts of this error, from least general to most.
     ///
     /// 'Context' here means parser patterns that the parser was in the process of parsing when the error occurred. To
-    /// add labelled contexts, see [`Parser::labelled`].
+    /// add labelled contexts, see [`Parser::context`] and [`Labelled::as_context`](crate::label::Labelled::as_context).
     #[cfg(feature = "label")]
     pub fn contexts(&self) -> impl Iterator<Item = (&L, &S)> {
         self.context.iter().map(|(l, s)| (l, s))
     }
 
+    /// Attach a secondary label to this error: a related location in the input, along with a message that explains
+    /// its relevance (such as the opening delimiter of an unclosed block, or the previous definition of a duplicated
+    /// item).
+    ///
+    /// Labels are preserved when errors are merged. Diagnostic reporters can display them alongside the primary span
+    /// of the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let ident = text::ascii::ident::<_, _, extra::Err<Rich<char>>>();
+    /// let params = ident
+    ///     .map_with_span(|name, span| (name, span))
+    ///     .separated_by(just(','))
+    ///     .collect::<Vec<_>>()
+    ///     .validate(|params, _, emitter| {
+    ///         for (i, (name, span)) in params.iter().enumerate() {
+    ///             if let Some((_, prev)) = params[..i].iter().find(|(prev, _)| prev == name) {
+    ///                 emitter.emit(
+    ///                     Rich::custom(*span, format!("duplicate parameter `{name}`"))
+    ///                         .with_label(*prev, "first defined here"),
+    ///                 );
+    ///             }
+    ///         }
+    ///         params
+    ///     });
+    ///
+    /// let errs = params.parse("a,b,a").into_errors();
+    /// assert_eq!(errs[0].span(), &SimpleSpan::new(4, 5));
+    /// assert_eq!(
+    ///     errs[0].labels().collect::<Vec<_>>(),
+    ///     [(&SimpleSpan::new(0, 1), "first defined here")],
+    /// );
+    /// ```
+    pub fn with_label<M: ToString>(mut self, span: S, msg: M) -> Self {
+        self.extras_mut().labels.push((span, msg.to_string()));
+        self
+    }
+
+    /// Return an iterator over the secondary labels of this error, in the order they were added.
+    ///
+    /// See [`Rich::with_label`].
+    pub fn labels(&self) -> impl ExactSizeIterator<Item = (&S, &str)> {
+        self.extras
+            .as_deref()
+            .map_or(&[][..], |extras| &extras.labels)
+            .iter()
+            .map(|(s, m)| (s, m.as_str()))
+    }
+
+    /// Attach a suggested edit to this error: replacing the input covered by `span` with `replacement` would fix (or
+    /// help to fix) the error. Insertions are represented by an empty span.
+    ///
+    /// Suggestions are preserved when errors are merged. Editors can offer them as quick-fixes. See also
+    /// [`Parser::or_suggest_insert`].
+    pub fn with_suggestion<R: ToString>(mut self, span: S, replacement: R) -> Self {
+        self.extras_mut().suggestions.push(Suggestion {
+            span,
+            replacement: replacement.to_string(),
+        });
+        self
+    }
+
+    /// Return an iterator over the suggested edits of this error, in the order they were added.
+    ///
+    /// See [`Rich::with_suggestion`].
+    pub fn suggestions(&self) -> impl ExactSizeIterator<Item = &Suggestion<S>> {
+        self.extras
+            .as_deref()
+            .map_or(&[][..], |extras| &extras.suggestions)
+            .iter()
+    }
+
+    /// Attach application-specific data to this error, such as an error code or a suggested fix.
+    ///
+    /// The type of the data is given by the `X` type parameter of the error. Chumsky never inspects it: it is carried
+    /// along unchanged as the error is merged with others (the data of the first error that has any is kept) and
+    /// emitted during error recovery.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// struct FixIt(&'static str);
+    ///
+    /// type Error<'a> = Rich<'a, char, SimpleSpan, &'static str, FixIt>;
+    ///
+    /// let semicolon = just::<_, _, extra::Err<Error>>(';').or_not().validate(|semi, span, emitter| {
+    ///     if semi.is_none() {
+    ///         emitter.emit(Rich::custom(span, "missing semicolon").with_extension(FixIt(";")));
+    ///     }
+    /// });
+    /// let stmt = text::ascii::ident().then_ignore(semicolon);
+    ///
+    /// let errs = stmt.parse("foo").into_errors();
+    /// assert_eq!(errs[0].extension(), Some(&FixIt(";")));
+    /// ```
+    pub fn with_extension(mut self, extension: X) -> Self {
+        self.extras_mut().extension = Some(extension);
+        self
+    }
+
+    /// Get the application-specific data attached to this error, if any. See [`Rich::with_extension`].
+    pub fn extension(&self) -> Option<&X> {
+        self.extras.as_ref()?.extension.as_ref()
+    }
+
+    /// Take the application-specific data attached to this error, if any. See [`Rich::with_extension`].
+    pub fn take_extension(&mut self) -> Option<X> {
+        self.extras.as_mut()?.extension.take()
+    }
+
+    /// Return an iterator over the errors of the other alternatives of a [`choice`] that failed along with the one that
+    /// produced this error, in the order that they were tried.
+    ///
+    /// This is only populated for [`choice`]s that keep the errors of all of their alternatives: see
+    /// [`Choice::all_errors`](crate::primitive::Choice::all_errors). Each of these errors may have branches of its own.
+    pub fn branches(&self) -> impl ExactSizeIterator<Item = &Self> {
+        self.extras
+            .as_deref()
+            .map_or(&[][..], |extras| &extras.branches)
+            .iter()
+    }
+
     /// Convert this error into an owned version of itself by cloning any borrowed internal tokens, if necessary.
-    pub fn into_owned<'b>(self) -> Rich<'b, T, S, L>
+    pub fn into_owned<'b>(self) -> Rich<'b, T, S, L, X>
     where
         T: Clone,
     {
         Rich {
+            span: self.span,
             reason: Box::new(self.reason.into_owned()),
-            ..self
+            #[cfg(feature = "label")]
+            context: self.context,
+            extras: self
+                .extras
+                .map(|extras| Box::new(extras.map_branches(Rich::into_owned))),
         }
     }
 
@@ -615,20 +1189,23 @@ impl<'a, T, S, L> Rich<'a, T, S, L> {
     ///
     /// This is useful when you wish to combine errors from multiple compilation passes (lexing and parsing, say) where
     /// the token type for each pass is different (`char` vs `MyToken`, say).
-    pub fn map_token<U, F: FnMut(T) -> U>(self, f: F) -> Rich<'a, U, S, L>
+    pub fn map_token<U, F: FnMut(T) -> U>(self, mut f: F) -> Rich<'a, U, S, L, X>
     where
         T: Clone,
     {
         Rich {
             span: self.span,
-            reason: Box::new(self.reason.map_token(f)),
+            reason: Box::new(self.reason.map_token(&mut f)),
             #[cfg(feature = "label")]
             context: self.context,
+            extras: self
+                .extras
+                .map(|extras| Box::new(extras.map_branches(|branch| branch.map_token(&mut f)))),
         }
     }
 }
 
-impl<'a, I: Input<'a>, L> Error<'a, I> for Rich<'a, I::Token, I::Span, L>
+impl<'a, I: Input<'a>, L, X> Error<'a, I> for Rich<'a, I::Token, I::Span, L, X>
 where
     I::Token: PartialEq,
     L: PartialEq,
@@ -653,20 +1230,113 @@ where
             }),
             #[cfg(feature = "label")]
             context: Vec::new(),
+            extras: None,
+        }
+    }
+
+    #[inline]
+    fn expected_range_found<E: IntoIterator<Item = Option<MaybeRef<'a, I::Token>>>>(
+        start: MaybeRef<'a, I::Token>,
+        end: Bound<MaybeRef<'a, I::Token>>,
+        _expected: E,
+        found: Option<MaybeRef<'a, I::Token>>,
+        span: I::Span,
+    ) -> Self {
+        let (end, inclusive) = match end {
+            Bound::Included(end) => (Some(end), true),
+            Bound::Excluded(end) => (Some(end), false),
+            Bound::Unbounded => (None, false),
+        };
+        Self {
+            span,
+            reason: Box::new(RichReason::ExpectedFound {
+                expected: vec![RichPattern::Range {
+                    start,
+                    end,
+                    inclusive,
+                }],
+                found,
+            }),
+            #[cfg(feature = "label")]
+            context: Vec::new(),
+            extras: None,
+        }
+    }
+
+    #[inline]
+    fn expected_seq_found<
+        S: IntoIterator<Item = MaybeRef<'a, I::Token>>,
+        E: IntoIterator<Item = Option<MaybeRef<'a, I::Token>>>,
+    >(
+        kind: SeqKind,
+        seq: S,
+        _expected: E,
+        found: Option<MaybeRef<'a, I::Token>>,
+        span: I::Span,
+    ) -> Self {
+        let mut seq = seq.into_iter().collect::<Vec<_>>();
+        let pattern = match kind {
+            SeqKind::Literal if seq.len() == 1 => RichPattern::Token(seq.remove(0)),
+            kind => RichPattern::Seq { kind, seq },
+        };
+        Self {
+            span,
+            reason: Box::new(RichReason::ExpectedFound {
+                expected: vec![pattern],
+                found,
+            }),
+            #[cfg(feature = "label")]
+            context: Vec::new(),
+            extras: None,
+        }
+    }
+
+    #[inline]
+    fn unexpected_found<U: IntoIterator<Item = MaybeRef<'a, I::Token>>>(
+        unexpected: U,
+        found: Option<MaybeRef<'a, I::Token>>,
+        span: I::Span,
+    ) -> Self {
+        Self {
+            span,
+            reason: Box::new(RichReason::ExpectedFound {
+                expected: vec![RichPattern::NoneOf(unexpected.into_iter().collect())],
+                found,
+            }),
+            #[cfg(feature = "label")]
+            context: Vec::new(),
+            extras: None,
         }
     }
 
     #[inline]
     fn merge(self, other: Self) -> Self {
         let new_reason = self.reason.flat_merge(*other.reason);
+        let extras = match (self.extras, other.extras) {
+            (Some(mut extras), Some(other)) => {
+                extras.merge(*other);
+                Some(extras)
+            }
+            (extras, other) => extras.or(other),
+        };
         Self {
             span: self.span,
             reason: Box::new(new_reason),
             #[cfg(feature = "label")]
             context: self.context, // TOOD: Merge contexts
+            extras,
         }
     }
 
+    #[inline]
+    fn with_branches<B: IntoIterator<Item = Self>>(mut self, branches: B) -> Self {
+        let mut branches = branches.into_iter().peekable();
+        if branches.peek().is_some() {
+            self.extras_mut().branches.extend(branches);
+        }
+        self
+    }
+
     #[inline]
     fn merge_expected_found<E: IntoIterator<Item = Option<MaybeRef<'a, I::Token>>>>(
         mut self,
@@ -748,12 +1418,83 @@ where
         }
         #[cfg(feature = "label")]
         self.context.clear();
+        self.extras = self.extras.take().and_then(|extras| {
+            (!extras.branches.is_empty()).then(|| {
+                Box::new(RichExtras {
+                    labels: Vec::new(),
+                    suggestions: Vec::new(),
+                    extension: None,
+                    branches: extras.branches,
+                })
+            })
+        });
         self
     }
 }
 
+/// Downgrade a [`Simple`] error to a [`Cheap`] error, discarding the found token.
+impl<'a, T, S> From<Simple<'a, T, S>> for Cheap<S> {
+    fn from(err: Simple<'a, T, S>) -> Self {
+        Cheap { span: err.span }
+    }
+}
+
+/// Downgrade a [`Rich`] error to a [`Cheap`] error, discarding everything but the span.
+impl<'a, T, S, L, X> From<Rich<'a, T, S, L, X>> for Cheap<S> {
+    fn from(err: Rich<'a, T, S, L, X>) -> Self {
+        Cheap { span: err.span }
+    }
+}
+
+/// Downgrade a [`RichByte`] error to a [`Cheap`] error, discarding everything but the span.
+impl<S> From<RichByte<S>> for Cheap<S> {
+    fn from(err: RichByte<S>) -> Self {
+        Cheap { span: err.span }
+    }
+}
+
+/// Downgrade a [`Rich`] error to a [`Simple`] error, keeping only the span and the found token.
+///
+/// Custom errors have no found token, so are converted to errors with a found token of `None`.
+impl<'a, T, S, L, X> From<Rich<'a, T, S, L, X>> for Simple<'a, T, S> {
+    fn from(err: Rich<'a, T, S, L, X>) -> Self {
+        let mut reason = *err.reason;
+        Simple {
+            span: err.span,
+            found: reason.take_found(),
+        }
+    }
+}
+
+/// Upgrade a [`Simple`] error to a [`Rich`] error.
+///
+/// [`Simple`] does not record what was expected, so the resulting error has no expected patterns.
+impl<'a, T, S, L, X> From<Simple<'a, T, S>> for Rich<'a, T, S, L, X> {
+    fn from(err: Simple<'a, T, S>) -> Self {
+        Rich {
+            span: err.span,
+            reason: Box::new(RichReason::ExpectedFound {
+                expected: Vec::new(),
+                found: err.found,
+            }),
+            #[cfg(feature = "label")]
+            context: Vec::new(),
+            extras: None,
+        }
+    }
+}
+
+/// Upgrade a [`Cheap`] error to a [`Rich`] error.
+///
+/// [`Cheap`] records only the span of the error, so the resulting error has a generic custom message.
+impl<'a, T, S, L, X> From<Cheap<S>> for Rich<'a, T, S, L, X> {
+    fn from(err: Cheap<S>) -> Self {
+        Rich::custom(err.span, "invalid input")
+    }
+}
+
 #[cfg(feature = "label")]
-impl<'a, I: Input<'a>, L> LabelError<'a, I, L> for Rich<'a, I::Token, I::Span, L>
+impl<'a, I: Input<'a>, L, X> LabelError<'a, I, L> for Rich<'a, I::Token, I::Span, L, X>
 where
     I::Token: PartialEq,
     L: PartialEq,
@@ -783,7 +1524,20 @@ where
     }
 }
 
-impl<'a, T, S, L> fmt::Debug for Rich<'a, T, S, L>
+impl<'a, I: Input<'a>, L, X> SuggestionError<'a, I> for Rich<'a, I::Token, I::Span, L, X>
+where
+    I::Token: PartialEq,
+    L: PartialEq,
+{
+    #[inline]
+    fn suggest(&mut self, span: I::Span, replacement: String) {
+        self.extras_mut()
+            .suggestions
+            .push(Suggestion { span, replacement });
+    }
+}
+
+impl<'a, T, S, L, X> fmt::Debug for Rich<'a, T, S, L, X>
 where
     T: fmt::Debug,
     S: fmt::Debug,
@@ -794,7 +1548,7 @@ where
     }
 }
 
-impl<'a, T, S, L> fmt::Display for Rich<'a, T, S, L>
+impl<'a, T, S, L, X> fmt::Display for Rich<'a, T, S, L, X>
 where
     T: fmt::Display,
     S: fmt::Display,
@@ -805,6 +1559,444 @@ where
     }
 }
 
+/// An error displayed with a custom formatter for its tokens. See [`Rich::display_with`].
+#[derive(Copy, Clone)]
+pub struct DisplayWith<'b, E, F> {
+    error: &'b E,
+    fmt_token: F,
+}
+
+impl<'b, E: SpannedError, F> SpannedError for DisplayWith<'b, E, F> {
+    type Span = E::Span;
+
+    fn span(&self) -> &Self::Span {
+        self.error.span()
+    }
+}
+
+impl<'a, 'b, T, S, F> fmt::Display for DisplayWith<'b, Simple<'a, T, S>, F>
+where
+    S: fmt::Debug,
+    F: Fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "found ")?;
+        write_token(f, &self.fmt_token, self.error.found())?;
+        write!(f, " at {:?}", self.error.span)
+    }
+}
+
+impl<'a, 'b, T, L, F> fmt::Display for DisplayWith<'b, RichReason<'a, T, L>, F>
+where
+    L: fmt::Display,
+    F: Fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error
+            .inner_fmt(f, &self.fmt_token, |_: &(), _| Ok(()), L::fmt, None)
+    }
+}
+
+impl<'a, 'b, T, S, L, X, F> fmt::Display for DisplayWith<'b, Rich<'a, T, S, L, X>, F>
+where
+    S: fmt::Display,
+    L: fmt::Display,
+    F: Fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error
+            .inner_fmt(f, &self.fmt_token, S::fmt, L::fmt, false)
+    }
+}
+
+/// A set of bytes, stored as a 256-bit bitmap. Used by [`RichByte`] to track expected inputs.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct ByteSet([u64; 4]);
+
+impl ByteSet {
+    /// Create an empty set.
+    pub const fn new() -> Self {
+        Self([0; 4])
+    }
+
+    /// Add a byte to the set.
+    #[inline]
+    pub fn insert(&mut self, byte: u8) {
+        self.0[byte as usize / 64] |= 1 << (byte % 64);
+    }
+
+    /// Determine whether the set contains the given byte.
+    #[inline]
+    pub fn contains(&self, byte: u8) -> bool {
+        self.0[byte as usize / 64] & (1 << (byte % 64)) != 0
+    }
+
+    /// Get the number of bytes in the set.
+    pub fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Determine whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0 == [0; 4]
+    }
+
+    /// Iterate over the bytes in the set, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..=u8::MAX).filter(|byte| self.contains(*byte))
+    }
+}
+
+impl Extend<u8> for ByteSet {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|byte| self.insert(byte));
+    }
+}
+
+impl FromIterator<u8> for ByteSet {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl fmt::Debug for ByteSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// A rich error type specialised for byte inputs, such as `&[u8]`, that tracks error spans, expected inputs, and the
+/// actual input found at an error site.
+///
+/// Unlike [`Rich`], expected bytes are stored in a [`ByteSet`] rather than a [`Vec`], so creating and merging errors
+/// never allocates. In exchange, it cannot describe labels, keywords or custom messages: only which bytes (and whether
+/// the end of input) would have been accepted.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, error::RichByte};
+/// let hex = just::<_, &[u8], extra::Err<RichByte>>(b"0x")
+///     .ignore_then(one_of(b"0123456789abcdef").repeated().at_least(1));
+///
+/// let errs = hex.parse(b"0xg").into_errors();
+/// assert_eq!(errs[0].found(), Some(&b'g'));
+/// assert_eq!(errs[0].to_string(), "found 'g' expected '0'..='9', or 'a'..='f'");
+///
+/// let errs = hex.parse(b"0x").into_errors();
+/// assert_eq!(errs[0].to_string(), "found end of input expected '0'..='9', or 'a'..='f'");
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RichByte<S = SimpleSpan<usize>> {
+    span: S,
+    expected: ByteSet,
+    expected_end: bool,
+    found: Option<u8>,
+}
+
+impl<S> RichByte<S> {
+    /// Get the span associated with this error.
+    pub fn span(&self) -> &S {
+        &self.span
+    }
+
+    /// Get the byte found by this error when it occurred. `None` implies that the end of input was found.
+    pub fn found(&self) -> Option<&u8> {
+        self.found.as_ref()
+    }
+
+    /// Get the set of bytes that were expected when this error occurred.
+    pub fn expected(&self) -> &ByteSet {
+        &self.expected
+    }
+
+    /// Determine whether the end of input was expected when this error occurred.
+    pub fn expected_end(&self) -> bool {
+        self.expected_end
+    }
+
+    fn add_expected<E: IntoIterator<Item = Option<u8>>>(&mut self, expected: E) {
+        for expected in expected {
+            match expected {
+                Some(byte) => self.expected.insert(byte),
+                None => self.expected_end = true,
+            }
+        }
+    }
+
+    fn inner_fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        mut fmt_span: impl FnMut(&S, &mut fmt::Formatter<'_>) -> fmt::Result,
+        with_spans: bool,
+    ) -> fmt::Result {
+        let fmt_byte = |byte: &u8, f: &mut fmt::Formatter<'_>| {
+            write!(f, "{}", core::ascii::escape_default(*byte))
+        };
+
+        write!(f, "found ")?;
+        write_token(f, fmt_byte, self.found.as_ref())?;
+        if with_spans {
+            write!(f, " at ")?;
+            fmt_span(&self.span, f)?;
+        }
+        write!(f, " expected ")?;
+
+        // Runs of three or more consecutive bytes are written as ranges
+        let mut runs = Vec::new();
+        for byte in self.expected.iter() {
+            match runs.last_mut() {
+                Some((_, end)) if *end as u16 + 1 == byte as u16 => *end = byte,
+                _ => runs.push((byte, byte)),
+            }
+        }
+        let mut items = runs
+            .into_iter()
+            .flat_map(|(start, end)| {
+                if end - start >= 2 {
+                    [Some((start, Some(end))), None]
+                } else if end > start {
+                    [Some((start, None)), Some((end, None))]
+                } else {
+                    [Some((start, None)), None]
+                }
+            })
+            .flatten()
+            .map(Some)
+            .chain(self.expected_end.then_some(None))
+            .peekable();
+
+        if items.peek().is_none() {
+            return write!(f, "something else");
+        }
+        let mut first = true;
+        while let Some(item) = items.next() {
+            if !first {
+                write!(f, ", ")?;
+                if items.peek().is_none() {
+                    write!(f, "or ")?;
+                }
+            }
+            first = false;
+            match item {
+                Some((start, end)) => {
+                    write_token(f, fmt_byte, Some(&start))?;
+                    if let Some(end) = end {
+                        write!(f, "..=")?;
+                        write_token(f, fmt_byte, Some(&end))?;
+                    }
+                }
+                None => write!(f, "end of input")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a, I: Input<'a, Token = u8>> Error<'a, I> for RichByte<I::Span> {
+    #[inline]
+    fn expected_found<E: IntoIterator<Item = Option<MaybeRef<'a, u8>>>>(
+        expected: E,
+        found: Option<MaybeRef<'a, u8>>,
+        span: I::Span,
+    ) -> Self {
+        let mut err = Self {
+            span,
+            expected: ByteSet::new(),
+            expected_end: false,
+            found: found.as_deref().copied(),
+        };
+        err.add_expected(expected.into_iter().map(|tok| tok.as_deref().copied()));
+        err
+    }
+
+    #[inline]
+    fn merge(mut self, other: Self) -> Self {
+        for (word, other) in self.expected.0.iter_mut().zip(other.expected.0) {
+            *word |= other;
+        }
+        self.expected_end |= other.expected_end;
+        self
+    }
+
+    #[inline]
+    fn merge_expected_found<E: IntoIterator<Item = Option<MaybeRef<'a, u8>>>>(
+        mut self,
+        expected: E,
+        _found: Option<MaybeRef<'a, u8>>,
+        _span: I::Span,
+    ) -> Self {
+        self.add_expected(expected.into_iter().map(|tok| tok.as_deref().copied()));
+        self
+    }
+
+    #[inline]
+    fn replace_expected_found<E: IntoIterator<Item = Option<MaybeRef<'a, u8>>>>(
+        mut self,
+        expected: E,
+        found: Option<MaybeRef<'a, u8>>,
+        span: I::Span,
+    ) -> Self {
+        self.span = span;
+        self.expected = ByteSet::new();
+        self.expected_end = false;
+        self.found = found.as_deref().copied();
+        self.add_expected(expected.into_iter().map(|tok| tok.as_deref().copied()));
+        self
+    }
+}
+
+impl<S> fmt::Debug for RichByte<S>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.inner_fmt(f, S::fmt, true)
+    }
+}
+
+impl<S> fmt::Display for RichByte<S>
+where
+    S: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner_fmt(f, S::fmt, false)
+    }
+}
+
+/// An error that knows the span of input at which it occurred. Used by [`render`].
+pub trait SpannedError {
+    /// The type of span that locates the error.
+    type Span: Span;
+
+    /// Get the span associated with this error.
+    fn span(&self) -> &Self::Span;
+}
+
+impl<S: Span> SpannedError for Cheap<S> {
+    type Span = S;
+
+    fn span(&self) -> &Self::Span {
+        &self.span
+    }
+}
+
+impl<'a, T, S: Span> SpannedError for Simple<'a, T, S> {
+    type Span = S;
+
+    fn span(&self) -> &Self::Span {
+        &self.span
+    }
+}
+
+impl<'a, T, S: Span, L, X> SpannedError for Rich<'a, T, S, L, X> {
+    type Span = S;
+
+    fn span(&self) -> &Self::Span {
+        &self.span
+    }
+}
+
+impl<S: Span> SpannedError for RichByte<S> {
+    type Span = S;
+
+    fn span(&self) -> &Self::Span {
+        &self.span
+    }
+}
+
+/// Render an error as plain text, with an excerpt of the source code it occurred in.
+///
+/// The excerpt shows each line that the error's span covers, with the span underlined by carets. Long spans (such as
+/// that of an unterminated string literal) are shortened to their first 80 characters with [`Span::clamp_len`], and
+/// the underline is followed by `...` to show that the span continues. The span must contain byte offsets into `src`
+/// (as is the case for spans produced when parsing a [`&str`]). This is intended for command-line tools and tests that
+/// need readable errors without depending on a diagnostic rendering crate such as
+/// [`ariadne`](https://crates.io/crates/ariadne).
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, error::render};
+/// let sum = text::int::<_, _, extra::Err<Rich<char>>>(10)
+///     .padded()
+///     .separated_by(just('+'))
+///     .collect::<Vec<_>>();
+///
+/// let src = "1 +\n2 + x";
+/// let errs = sum.parse(src).into_errors();
+/// assert_eq!(
+///     render(&errs[0], src),
+///     "\
+/// error: found 'x' expected something else
+///  --> 2:5
+///   |
+/// 2 | 2 + x
+///   |     ^
+/// ",
+/// );
+/// ```
+pub fn render<E>(error: &E, src: &str) -> String
+where
+    E: SpannedError + fmt::Display,
+    E::Span: Span<Offset = usize>,
+{
+    let span = error.span();
+    let start = floor_char_boundary(src, span.start());
+    let end = floor_char_boundary(src, span.end()).max(start);
+    let clamped = (start..end).clamp_len(src, MAX_RENDERED_SPAN_LEN);
+    let lines = clamped.split_lines(src);
+
+    let index = crate::span::LineIndex::new(src);
+    let (first_line, first_col) = index.line_col(start);
+    let gutter = (index.line(clamped.end) + 1).to_string().len();
+    let mut out = String::new();
+    let _ = writeln!(out, "error: {error}");
+    let _ = writeln!(
+        out,
+        "{:gutter$}--> {}:{}",
+        "",
+        first_line + 1,
+        first_col + 1
+    );
+    let _ = writeln!(out, "{:gutter$} |", "");
+    for (i, part) in lines.iter().enumerate() {
+        let line_no = index.line(part.start);
+        let line_span = index.line_span(line_no).unwrap_or(part.clone());
+        let line = src[line_span.clone()].trim_end_matches(['\r', '\n']);
+        // Preserve tabs before the span, so that the carets line up with it however wide they are displayed
+        let indent = src[line_span.start..part.start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        let carets = src[part.clone()].chars().count().max(1);
+        let more = if i + 1 == lines.len() && clamped.end < end {
+            "..."
+        } else {
+            ""
+        };
+
+        let _ = writeln!(out, "{:>gutter$} | {line}", line_no + 1);
+        let _ = writeln!(out, "{:gutter$} | {indent}{}{more}", "", "^".repeat(carets));
+    }
+    out
+}
+
+/// The maximum number of characters of a span that [`render`] underlines.
+const MAX_RENDERED_SPAN_LEN: usize = 80;
+
+fn floor_char_boundary(s: &str, mut idx: usize) -> usize {
+    idx = idx.min(s.len());
+    while !s.is_char_boundary(idx) {
+        idx -= 1;
+    }
+    idx
+}
+
 fn write_token<T>(
     f: &mut fmt::Formatter,
     mut fmt_token: impl FnMut(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
@@ -819,3 +2011,203 @@ fn write_token<T>(
         None => write!(f, "end of input"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_excerpts() {
+        let err = Rich::<char>::custom(SimpleSpan::new(16, 19), "unknown variable");
+        let src = "let a = 1;\n\tb = foo + 1;\n";
+        assert_eq!(
+            render(&err, src),
+            "error: unknown variable\n --> 2:6\n  |\n2 | \tb = foo + 1;\n  | \t    ^^^\n",
+        );
+
+        // Errors at the end of input, on long lines and spanning several lines
+        let src = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk";
+        let eoi = Cheap::from(Rich::<char>::custom(SimpleSpan::new(21, 21), ""));
+        assert!(render(&eoi, src).ends_with(" --> 11:2\n   |\n11 | k\n   |  ^\n"));
+        let multiline = Rich::<char>::custom(SimpleSpan::new(2, 7), "");
+        assert!(render(&multiline, src).ends_with("2 | b\n  | ^\n3 | c\n  | ^\n4 | d\n  | ^\n"));
+        // The gutter is wide enough for the last line underlined
+        let multiline = Rich::<char>::custom(SimpleSpan::new(16, 21), "");
+        assert!(
+            render(&multiline, src).ends_with(" 9 | i\n   | ^\n10 | j\n   | ^\n11 | k\n   | ^\n")
+        );
+
+        // Long spans are clamped
+        let src = format!("s = \"{}", "x".repeat(2000));
+        let unterminated =
+            Rich::<char>::custom(SimpleSpan::new(4, src.len()), "unterminated string");
+        let rendered = render(&unterminated, &src);
+        assert!(rendered.ends_with(&format!("  | {}{}...\n", " ".repeat(4), "^".repeat(80))));
+        assert_eq!(rendered.lines().count(), 5);
+    }
+
+    #[test]
+    fn error_categories() {
+        let parser = none_of::<_, _, extra::Err<Rich<char>>>("xy").then(just('a'));
+
+        let errs = parser.parse("x").into_errors();
+        assert_eq!(errs[0].category(), ErrorCategory::ExcludedInput);
+        let errs = parser.parse("b").into_errors();
+        assert_eq!(errs[0].category(), ErrorCategory::UnexpectedEnd);
+        let errs = parser.parse("bb").into_errors();
+        assert_eq!(errs[0].category(), ErrorCategory::UnexpectedInput);
+
+        let err = Rich::<char>::custom((0..1).into(), "oops");
+        assert_eq!(err.category().to_string(), "C004 custom error");
+
+        let err = <Simple<char> as Error<&str>>::expected_found(None, None, (0..0).into());
+        assert_eq!(err.code(), "C002");
+    }
+
+    #[test]
+    fn display_with_custom_tokens() {
+        let fmt_token = |tok: &char, f: &mut fmt::Formatter<'_>| write!(f, "U+{:04X}", *tok as u32);
+        let err = <Rich<char> as Error<&str>>::expected_found(
+            [Some(MaybeRef::Val('a')), None],
+            Some(MaybeRef::Val('b')),
+            SimpleSpan::new(0, 1),
+        );
+        assert_eq!(
+            err.display_with(fmt_token).to_string(),
+            "found 'U+0062' expected 'U+0061', or end of input",
+        );
+        assert_eq!(
+            render(&err.display_with(fmt_token), "b"),
+            "error: found 'U+0062' expected 'U+0061', or end of input\n --> 1:1\n  |\n1 | b\n  | ^\n",
+        );
+
+        let simple =
+            <Simple<char> as Error<&str>>::expected_found(None, None, SimpleSpan::new(1, 1));
+        assert_eq!(
+            simple.display_with(fmt_token).to_string(),
+            "found end of input at 1..1",
+        );
+    }
+
+    #[test]
+    fn rich_byte_bitmap() {
+        let set = [0, 63, 64, 255].into_iter().collect::<ByteSet>();
+        assert_eq!(set.len(), 4);
+        assert!(set.contains(64) && !set.contains(65));
+        assert_eq!(set.iter().collect::<Vec<_>>(), [0, 63, 64, 255]);
+        assert!(ByteSet::new().is_empty());
+
+        let errs = just::<_, &[u8], extra::Err<RichByte>>(b'a')
+            .or(just(b'b'))
+            .or(just(b'\n'))
+            .then(end())
+            .parse(b"c")
+            .into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(0, 1));
+        assert_eq!(errs[0].expected().iter().collect::<Vec<_>>(), b"\nab");
+        assert_eq!(errs[0].to_string(), "found 'c' expected '\\n', 'a', or 'b'");
+
+        let err = <RichByte as Error<&[u8]>>::expected_found(
+            [Some(MaybeRef::Val(b'a')), None],
+            None,
+            SimpleSpan::new(1, 1),
+        );
+        assert!(err.expected_end());
+        assert_eq!(
+            err.to_string(),
+            "found end of input expected 'a', or end of input"
+        );
+
+        let errs = one_of::<_, &[u8], extra::Err<RichByte>>(b"xyz\xff".as_slice())
+            .parse(b"\x80")
+            .into_errors();
+        assert_eq!(
+            errs[0].to_string(),
+            "found '\\x80' expected 'x'..='z', or '\\xff'",
+        );
+        assert_eq!(format!("{:?}", Cheap::from(errs[0])), "at 0..1");
+    }
+
+    #[test]
+    fn rich_size() {
+        use core::mem::size_of;
+
+        // Labels, suggestions, extensions and branches must not make every error larger
+        let context = if cfg!(feature = "label") {
+            size_of::<Vec<(&'static str, SimpleSpan)>>()
+        } else {
+            0
+        };
+        assert_eq!(
+            size_of::<Rich<char>>(),
+            size_of::<SimpleSpan>() + 2 * size_of::<usize>() + context,
+        );
+    }
+
+    #[test]
+    fn labels_survive_merging() {
+        let labelled = |msg: &'static str| {
+            any::<_, extra::Err<Rich<char>>>().try_map(move |_, span: SimpleSpan| {
+                Err(Rich::custom(span, msg).with_label(SimpleSpan::new(0, 0), msg))
+            })
+        };
+
+        let errs = labelled("a").or(labelled("b")).parse("x").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(
+            errs[0].labels().map(|(_, m)| m).collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+
+        // Labels belong to the error they were attached to, so are dropped when it is replaced by a later one
+        let errs = labelled("a")
+            .or(just('x').then(just('y')).to('z'))
+            .parse("xyz")
+            .into_errors();
+        assert_eq!(errs[0].span(), &SimpleSpan::new(2, 3));
+        assert_eq!(errs[0].labels().len(), 0);
+    }
+
+    #[test]
+    fn extension_survives_recovery() {
+        type Error<'a> = Rich<'a, char, SimpleSpan, &'static str, u32>;
+
+        let digit = any::<_, extra::Err<Error>>().try_map(|c: char, span| {
+            c.to_digit(10)
+                .ok_or_else(|| Rich::custom(span, "not a digit").with_extension(42))
+        });
+        let digits = digit
+            .recover_with(via_parser(any().to(0)))
+            .repeated()
+            .collect::<Vec<_>>();
+
+        let (out, errs) = digits.parse("1x2").into_output_errors();
+        assert_eq!(out, Some(vec![1, 0, 2]));
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].extension(), Some(&42));
+    }
+
+    #[test]
+    fn none_of_names_what_was_ruled_out() {
+        let escaped = just::<_, _, extra::Err<Rich<char>>>('\\').ignore_then(none_of("\"'"));
+
+        let errs = escaped.parse("\\'").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(1, 2));
+        assert_eq!(
+            errs[0].to_string(),
+            "found ''' expected anything other than '\"' or '''",
+        );
+        assert!(matches!(
+            errs[0].expected().next(),
+            Some(RichPattern::NoneOf(toks)) if toks.len() == 2
+        ));
+
+        // Errors that cannot describe what was ruled out still report what was found
+        let errs = none_of::<_, _, extra::Err<Simple<char>>>('x')
+            .parse("x")
+            .into_errors();
+        assert_eq!(errs[0].found(), Some(&'x'));
+    }
+}
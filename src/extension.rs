@@ -72,6 +72,96 @@ pub mod v1 {
     pub use super::current::{Ext, ExtParser};
 }
 
+/// Chumsky's internal parser interface, for combinators that need more control than [`v1::ExtParser`] gives them.
+///
+/// Implementing [`ParserSealed`] is how chumsky's own parsers are written. Unlike [`v1::ExtParser`], it lets a parser
+/// run its sub-parsers in the same [`Mode`] as itself, so that no outputs are generated when they will be discarded.
+///
+/// This module is only available with the `unstable` feature. **It carries no stability guarantees**: it exposes
+/// chumsky's internals as they are, and it may change in any release.
+///
+/// # Writing a parser
+///
+/// [`ParserSealed::go`] is generic over a [`Mode`]: in [`Emit`] mode parsers produce an output, while in [`Check`] mode
+/// outputs are `()` and are never generated. Use [`Mode::bind`], [`Mode::map`] and [`Mode::combine`] to build outputs,
+/// so that the same code works for both. On failure, record an error with [`InputRef::add_error`] (unless a
+/// sub-parser already did) and return `Err(())`.
+///
+/// The [`go_extra!`](unstable::go_extra) macro implements the remaining methods of [`ParserSealed`], and expects the impl to name its
+/// generics `'a`, `I` and `E`.
+///
+/// ```
+/// use chumsky::{
+///     extension::unstable::{go_extra, Mode, PResult, ParserSealed},
+///     input::InputRef,
+///     prelude::*,
+/// };
+///
+/// /// Parses `parser` twice, succeeding only if both outputs are equal.
+/// pub struct Twice<A>(A);
+///
+/// impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for Twice<A>
+/// where
+///     I: Input<'a>,
+///     E: extra::ParserExtra<'a, I>,
+///     A: Parser<'a, I, O, E>,
+///     O: PartialEq,
+/// {
+///     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+///         // Outputs are needed to compare them, so parse in emit mode regardless of `M`
+///         let first = inp.parse(&self.0).map_err(|err| inp.add_error(inp.offset(), err))?;
+///         let before = inp.offset();
+///         let second = inp.parse(&self.0).map_err(|err| inp.add_error(inp.offset(), err))?;
+///         if first == second {
+///             Ok(M::bind(|| first))
+///         } else {
+///             inp.add_error(before, E::Error::expected_found(None, None, inp.span_since(before)));
+///             Err(())
+///         }
+///     }
+///
+///     go_extra!(O);
+/// }
+///
+/// let parser = Twice(any::<_, extra::Err<Simple<char>>>()).repeated().collect::<String>();
+/// assert_eq!(parser.parse("aabb").into_result(), Ok("ab".to_string()));
+/// assert!(parser.parse("ab").has_errors());
+/// ```
+#[cfg(feature = "unstable")]
+pub mod unstable {
+    pub use crate::private::{Check, Emit, Located, Mode, PResult, ParserSealed};
+
+    #[doc(inline)]
+    pub use crate::__go_extra as go_extra;
+
+    /// Implement the object-safe methods of [`ParserSealed`] in terms of [`ParserSealed::go`], given the parser's
+    /// output type. See the [module-level documentation](self).
+    #[doc(hidden)]
+    #[macro_export]
+    macro_rules! __go_extra {
+        ( $O:ty ) => {
+            #[inline(always)]
+            fn go_emit(
+                &self,
+                inp: &mut $crate::input::InputRef<'a, '_, I, E>,
+            ) -> $crate::extension::unstable::PResult<$crate::extension::unstable::Emit, $O> {
+                $crate::extension::unstable::ParserSealed::<I, $O, E>::go::<
+                    $crate::extension::unstable::Emit,
+                >(self, inp)
+            }
+            #[inline(always)]
+            fn go_check(
+                &self,
+                inp: &mut $crate::input::InputRef<'a, '_, I, E>,
+            ) -> $crate::extension::unstable::PResult<$crate::extension::unstable::Check, $O> {
+                $crate::extension::unstable::ParserSealed::<I, $O, E>::go::<
+                    $crate::extension::unstable::Check,
+                >(self, inp)
+            }
+        };
+    }
+}
+
 mod current {
     use super::*;
 
@@ -1,5 +1,37 @@
 //! Generic error, state and context types for parsers
 //! Useful for custom allocation, error handling, context-specific parsers, and more.
+//!
+//! Rather than taking separate type parameters for each of these, parsers take a single [`ParserExtra`] parameter
+//! that bundles them together. The type aliases in this module specify only the types that differ from the defaults,
+//! so most parser signatures only need to mention the one they care about:
+//!
+//! ```
+//! use chumsky::{extra::ParserExtra, prelude::*};
+//!
+//! // Generic over the error, state and context types with just one type parameter
+//! fn digits<'a, E: ParserExtra<'a, &'a str>>() -> impl Parser<'a, &'a str, &'a str, E> {
+//!     text::digits(10).slice()
+//! }
+//!
+//! // Only the error type is specified: the state and context are `()`
+//! fn number<'a>() -> impl Parser<'a, &'a str, u32, extra::Err<Rich<'a, char>>> {
+//!     digits().from_str().unwrapped()
+//! }
+//!
+//! // The state type is specified too, so `Full` is used
+//! fn counted<'a>() -> impl Parser<'a, &'a str, &'a str, extra::Full<Rich<'a, char>, usize, ()>> {
+//!     digits().map_with_state(|digits, _, count: &mut usize| {
+//!         *count += 1;
+//!         digits
+//!     })
+//! }
+//!
+//! assert_eq!(number().parse("42").into_result(), Ok(42));
+//!
+//! let mut count = 0;
+//! assert_eq!(counted().parse_with_state("42", &mut count).into_result(), Ok("42"));
+//! assert_eq!(count, 1);
+//! ```
 
 use super::*;
 
@@ -24,6 +56,8 @@ where
     /// such as interned identifiers or position-dependent name resolution, however *cannot* influence
     /// the actual progress of the parser - for that, use [`Self::Context`].
     ///
+    /// Changes made to the state are not undone when the parser backtracks, unless [`Rollback`] is used.
+    ///
     /// For examples of using this type, see [`Parser::map_with_state`] or [`Parser::foldl_with_state`].
     type State: 'a;
     /// Context used for parser configuration. This is used to provide context-sensitive parsing of *input*.
@@ -33,6 +67,19 @@ where
     ///
     /// For examples of using this type, see [`Parser::ignore_with_ctx`], [`Parser::then_with_ctx`] and [`ConfigParser::configure`].
     type Context: 'a;
+
+    /// The record of the state kept by each [`Marker`](crate::input::Marker), so that the state can be restored when
+    /// the parser backtracks. This is `()` unless the state is rolled back. See [`Rollback`].
+    #[doc(hidden)]
+    type SavedState: Copy + 'a;
+
+    /// Record the current state so that it can be restored when the parser backtracks.
+    #[doc(hidden)]
+    fn save_state(state: &Self::State) -> Self::SavedState;
+
+    /// Restore the state recorded by [`ParserExtra::save_state`].
+    #[doc(hidden)]
+    fn restore_state(state: &mut Self::State, saved: Self::SavedState);
 }
 
 /// Use all default extra types. See [`ParserExtra`] for more details.
@@ -66,4 +113,95 @@ where
     type Error = E;
     type State = S;
     type Context = C;
+    type SavedState = ();
+
+    #[inline(always)]
+    fn save_state(_: &Self::State) -> Self::SavedState {}
+
+    #[inline(always)]
+    fn restore_state(_: &mut Self::State, _: Self::SavedState) {}
+}
+
+/// A state type whose changes can be undone, so that they can be rolled back when the parser backtracks. See
+/// [`Rollback`].
+pub trait RollbackState {
+    /// A record of the state, from which it can be restored.
+    ///
+    /// A copy is kept by every [`Marker`](crate::input::Marker), so this should be small, such as a length or an index.
+    type Saved: Copy;
+
+    /// Record the current state, producing a value that can later be passed to [`RollbackState::restore`].
+    fn save(&self) -> Self::Saved;
+
+    /// Undo every change made since the call to [`RollbackState::save`] that produced `saved`.
+    fn restore(&mut self, saved: Self::Saved);
+}
+
+/// Items pushed onto the vector after it is saved are removed when it is restored.
+impl<T> RollbackState for Vec<T> {
+    type Saved = usize;
+
+    #[inline(always)]
+    fn save(&self) -> Self::Saved {
+        self.len()
+    }
+
+    #[inline(always)]
+    fn restore(&mut self, saved: Self::Saved) {
+        self.truncate(saved);
+    }
+}
+
+/// Like [`Full`], but changes made to the state by a parser are undone when the parser backtracks, such as when a
+/// branch of [`Parser::or`] fails after modifying it.
+///
+/// With [`Full`], the state is never rolled back, so changes made by failed branches remain visible to the rest of
+/// the parse. The state type must implement [`RollbackState`].
+///
+/// # Examples
+///
+/// ```
+/// use chumsky::{extra, prelude::*};
+///
+/// type Extra = extra::Rollback<EmptyErr, Vec<String>, ()>;
+///
+/// // Record the name of each declaration as soon as it is parsed
+/// let decl = text::ascii::keyword::<_, _, _, Extra>("fn")
+///     .padded()
+///     .ignore_then(text::ascii::ident())
+///     .map_with_state(|name: &str, _, names: &mut Vec<String>| names.push(name.to_string()));
+///
+/// // Both branches parse a name before failing or succeeding
+/// let item = decl.clone().then_ignore(just("()")).or(decl.then_ignore(just(';')));
+///
+/// let mut names = Vec::new();
+/// assert!(!item.parse_with_state("fn foo;", &mut names).has_errors());
+/// // The name recorded by the failed first branch was rolled back
+/// assert_eq!(names, ["foo"]);
+/// ```
+pub struct Rollback<E, S, C>(PhantomData<(E, S, C)>);
+
+impl<E, S, C> Sealed for Rollback<E, S, C> {}
+impl<'a, I, E, S, C> ParserExtra<'a, I> for Rollback<E, S, C>
+where
+    I: Input<'a>,
+    E: Error<'a, I> + 'a,
+    S: RollbackState + 'a,
+    S::Saved: 'a,
+    C: 'a,
+{
+    type Error = E;
+    type State = S;
+    type Context = C;
+    type SavedState = S::Saved;
+
+    #[inline(always)]
+    fn save_state(state: &Self::State) -> Self::SavedState {
+        state.save()
+    }
+
+    #[inline(always)]
+    fn restore_state(state: &mut Self::State, saved: Self::SavedState) {
+        state.restore(saved);
+    }
 }
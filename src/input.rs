@@ -5,14 +5,22 @@
 //! [`Input`] is the primary trait used to feed input data into a chumsky parser. You can create them in a number of
 //! ways: from strings, slices, arrays, etc.
 
-pub use crate::stream::{BoxedExactSizeStream, BoxedStream, Stream};
-use core::cell::RefCell;
+pub use crate::stream::{BoxedExactSizeStream, BoxedStream, BufferedInput, Stream};
+use core::{cell::RefCell, ops::Add};
 
 use super::*;
+#[cfg(feature = "telemetry")]
+use crate::telemetry::ParseStats;
+#[cfg(feature = "debug-replay")]
+use crate::trace::{Trace, TraceEvent};
+#[cfg(feature = "mmap")]
+use core::str::Utf8Error;
 #[cfg(feature = "memoization")]
 use hashbrown::HashMap;
 #[cfg(feature = "std")]
 use std::io::{BufReader, Read, Seek};
+#[cfg(feature = "mmap")]
+use std::path::{Path, PathBuf};
 
 /// A trait for types that represents a stream of input tokens. Unlike [`Iterator`], this type
 /// supports backtracking and a few other features required by the crate.
@@ -26,6 +34,11 @@ use std::io::{BufReader, Read, Seek};
 /// - `&str`: [`SliceInput`], [`StrInput`], [`ValueInput`], [`ExactSizeInput`]
 /// - `&[T]`: [`SliceInput`], [`ValueInput`], [`BorrowInput`], [`ExactSizeInput`]
 /// - `Stream<I>`: [`ValueInput`], [`ExactSizeInput`] if `I: ExactSizeIterator`
+/// - `BufferedInput<I>`: [`ValueInput`]
+/// - `Graphemes`: [`SliceInput`], [`ValueInput`], [`ExactSizeInput`]
+/// - `Utf8Bytes`: [`SliceInput`], [`ValueInput`], [`ExactSizeInput`]
+/// - `&Normalized`: [`SliceInput`], [`StrInput`], [`ValueInput`], [`ExactSizeInput`]
+/// - `MappedFile::bytes` and `MappedFile::text`: as for `&[u8]` and `&str`
 ///
 /// This trait is sealed and so cannot be implemented by other crates because it has an unstable API. This may
 /// eventually change. For now, if you wish to use a type that chumsky does not know about as an input, consider using
@@ -70,10 +83,77 @@ pub trait Input<'a>: Sealed + 'a {
     #[doc(hidden)]
     unsafe fn span(&self, range: Range<Self::Offset>) -> Self::Span;
 
+    /// Get the span that marks the end of this input.
+    ///
+    /// This is the span given to errors that occur because the input ran out, such as those produced by [`end`] or by
+    /// a [`just`] that finds no more tokens. For most inputs, it is a zero-width span just past the last token. For
+    /// inputs created with [`Input::spanned`], it is the end of input span given to [`Input::spanned`], which might be
+    /// just past the last token or the location of an end-of-file token produced by a lexer.
+    ///
+    /// Inputs that do not know their length up front, such as [`Stream`], must read to the end of the input to find
+    /// this span.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// assert_eq!("hello".eoi_span(), SimpleSpan::new(5, 5));
+    ///
+    /// // Spans of tokens produced by a lexer, with the end of input where the lexer found the end of the file
+    /// let tokens = [('a', SimpleSpan::new(0, 1)), ('b', SimpleSpan::new(2, 3))];
+    /// assert_eq!(tokens.spanned(SimpleSpan::new(4, 4)).eoi_span(), SimpleSpan::new(4, 4));
+    /// ```
+    fn eoi_span(&self) -> Self::Span {
+        let mut offset = self.start();
+        // SAFETY: offsets are only ever generated by `start` and `next_maybe`
+        unsafe {
+            while let (next, Some(_)) = self.next_maybe(offset) {
+                offset = next;
+            }
+            self.span(offset..offset)
+        }
+    }
+
     // Get the previous offset, saturating at zero
     #[doc(hidden)]
     fn prev(offs: Self::Offset) -> Self::Offset;
 
+    /// Notify the input that a checkpoint has been created at the given offset. See [`InputRef::checkpoint`].
+    ///
+    /// Inputs that buffer tokens may use this to avoid discarding tokens that the parser may still rewind to.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn checkpoint(&self, offset: Self::Offset) {
+        let _ = offset;
+    }
+
+    /// Notify the input that a checkpoint previously created at the given offset has been released.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn release_checkpoint(&self, offset: Self::Offset) {
+        let _ = offset;
+    }
+
+    /// The number of tokens that the input can hold at once, if limited. See [`InputRef::peek_n`].
+    #[doc(hidden)]
+    #[inline(always)]
+    fn max_lookahead(&self) -> Option<usize> {
+        None
+    }
+
+    /// Whether the token between the given offsets stands in for input that could not be decoded, such as an invalid
+    /// UTF-8 sequence read from [`Utf8Bytes`]. An error is emitted for each such token that the parser consumes.
+    ///
+    /// # Safety
+    ///
+    /// As with [`Input::span`], the offsets must be generated by either [`Input::start`] or [`Input::next_maybe`].
+    #[doc(hidden)]
+    #[inline(always)]
+    unsafe fn is_undecodable(&self, range: Range<Self::Offset>) -> bool {
+        let _ = range;
+        false
+    }
+
     /// Split an input that produces tokens of type `(T, S)` into one that produces tokens of type `T` and spans of
     /// type `S`.
     ///
@@ -135,6 +215,46 @@ pub trait Input<'a>: Sealed + 'a {
             phantom: PhantomData,
         }
     }
+
+    /// Shift the spans output for this input forward by the given offset.
+    ///
+    /// This is useful when parsing a fragment of a larger document, such as the contents of a doc comment or a
+    /// template embedded in HTML: by offsetting spans by the position of the fragment within the document, the spans
+    /// produced by the parser (including those of errors) point into the original document rather than the fragment.
+    ///
+    /// The context of spans is left unchanged. To change it as well, use [`Input::map_span`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let html = "<p>{{ name }}</p>";
+    /// let start = html.find("{{").unwrap() + 2;
+    /// let end = html.find("}}").unwrap();
+    ///
+    /// let var = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .map_with_span(|name, span| (name, span))
+    ///     .padded();
+    ///
+    /// let (name, span) = var
+    ///     .parse(html[start..end].offset_spans(start))
+    ///     .into_result()
+    ///     .unwrap();
+    /// assert_eq!(name, "name");
+    /// assert_eq!(&html[span.into_range()], "name");
+    /// ```
+    fn offset_spans(
+        self,
+        offset: <Self::Span as Span>::Offset,
+    ) -> OffsetSpans<Self, <Self::Span as Span>::Offset>
+    where
+        Self: Sized,
+    {
+        OffsetSpans {
+            input: self,
+            offset,
+        }
+    }
 }
 
 /// Implement by inputs that have a known size (including spans)
@@ -145,6 +265,10 @@ pub trait ExactSizeInput<'a>: Input<'a> {
 }
 
 /// Implemented by inputs that represent slice-like streams of input tokens.
+///
+/// Inputs that implement this trait can hand out contiguous sub-slices of themselves between any two offsets,
+/// without copying. This is what allows combinators like [`Parser::slice`] and [`Parser::map_slice`] to be written
+/// generically over [`&str`], [`&[T]`], and any wrapper inputs built on top of them.
 pub trait SliceInput<'a>: ExactSizeInput<'a> {
     /// The unsized slice type of this input. For [`&str`] it's `&str`, and for [`&[T]`] it will be `&[T]`.
     type Slice;
@@ -154,14 +278,124 @@ pub trait SliceInput<'a>: ExactSizeInput<'a> {
     fn full_slice(&self) -> Self::Slice;
 
     /// Get a slice from a start and end offset
-    // TODO: Make unsafe
+    ///
+    /// Both offsets in `range` should be generated by either `Input::start` or a previous call to a method that
+    /// advances this input (such as [`ValueInput::next`]), and `range.start` should not come after `range.end`.
+    /// Otherwise, the slice is unspecified, and this may panic.
     #[doc(hidden)]
     fn slice(&self, range: Range<Self::Offset>) -> Self::Slice;
 
     /// Get a slice from a start offset till the end of the input
-    // TODO: Make unsafe
+    ///
+    /// `from.start` should be generated by either `Input::start` or a previous call to a method that advances this
+    /// input (such as [`ValueInput::next`]). Otherwise, the slice is unspecified, and this may panic.
     #[doc(hidden)]
     fn slice_from(&self, from: RangeFrom<Self::Offset>) -> Self::Slice;
+
+    /// Get the slice of the input made up of the tokens that lie within the given span, such as the span of an error.
+    ///
+    /// This works for any input, including token streams (for which the slice is the subsequence of tokens whose
+    /// spans are contained by `span`). An empty span produces an empty slice at the first token that starts at or
+    /// after it. Returns `None` if the span ends before it starts or does not contain any tokens of the input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, input::SliceInput};
+    /// let src = "let x = 42;";
+    /// let errs = just::<_, _, extra::Err<Rich<char>>>("let x = 43;").parse(src).into_errors();
+    /// assert_eq!(src.slice_for_span(errs[0].span()), Some("2"));
+    ///
+    /// // Token streams produce the tokens within the span
+    /// let tokens = [('a', SimpleSpan::from(0..2)), ('b', SimpleSpan::from(3..5)), ('c', SimpleSpan::from(6..8))];
+    /// let input = tokens.as_slice().spanned(SimpleSpan::from(8..8));
+    /// assert_eq!(input.slice_for_span(&SimpleSpan::from(2..8)), Some(&tokens[1..]));
+    /// ```
+    fn slice_for_span(&self, span: &Self::Span) -> Option<Self::Slice>
+    where
+        <Self::Span as Span>::Offset: PartialOrd,
+    {
+        let (start, end) = (span.start(), span.end());
+        if start > end {
+            return None;
+        }
+        let mut offset = self.start();
+        let mut first = None;
+        let mut range = None;
+        loop {
+            // SAFETY: offsets are only ever generated by `start` and `next_maybe`
+            let (next, tok) = unsafe { self.next_maybe(offset) };
+            if tok.is_none() {
+                break;
+            }
+            // SAFETY: As above
+            let tok_span = unsafe { self.span(offset..next) };
+            if tok_span.start() >= start {
+                if first.is_some() && tok_span.start() >= end {
+                    break;
+                }
+                first.get_or_insert(offset);
+                if tok_span.end() <= end {
+                    range = Some(range.map_or(offset, |r: Range<_>| r.start)..next);
+                }
+            }
+            offset = next;
+        }
+        match (range, first) {
+            (Some(range), _) => Some(self.slice(range)),
+            (None, first) if start == end => {
+                let at = first.unwrap_or(offset);
+                Some(self.slice(at..at))
+            }
+            (None, _) => None,
+        }
+    }
+
+    /// Get the slice of the input containing every line that the given span touches, not including the newline that
+    /// ends the last of them.
+    ///
+    /// This is useful for showing the context of an error. Like [`SliceInput::slice_for_span`], it works for any input
+    /// with character tokens, including those that map spans back onto a different source (such as
+    /// [`Normalized`]). Returns `None` if the span ends before it starts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, input::SliceInput};
+    /// let src = "fn main() {\n    let x = ;\n}";
+    /// let span = SimpleSpan::from(24..25);
+    /// assert_eq!(src.lines_for_span(&span), Some("    let x = ;"));
+    /// assert_eq!(src.lines_for_span(&SimpleSpan::from(5..20)), Some("fn main() {\n    let x = ;"));
+    /// ```
+    fn lines_for_span(&self, span: &Self::Span) -> Option<Self::Slice>
+    where
+        Self::Token: Char,
+        <Self::Span as Span>::Offset: PartialOrd,
+    {
+        let (start, end) = (span.start(), span.end());
+        if start > end {
+            return None;
+        }
+        let mut offset = self.start();
+        let mut line_start = offset;
+        loop {
+            // SAFETY: offsets are only ever generated by `start` and `next_maybe`
+            let (next, tok) = unsafe { self.next_maybe(offset) };
+            let is_newline = match tok {
+                Some(tok) => tok.borrow().to_char() == '\n',
+                None => break,
+            };
+            // SAFETY: As above
+            let tok_span = unsafe { self.span(offset..next) };
+            if is_newline && tok_span.end() <= start {
+                line_start = next;
+            } else if is_newline && tok_span.start() >= end {
+                break;
+            }
+            offset = next;
+        }
+        Some(self.slice(line_start..offset))
+    }
 }
 
 // Implemented by inputs that reference a string slice and use byte indices as their offset.
@@ -194,6 +428,9 @@ pub trait BorrowInput<'a>: Input<'a> {
 }
 
 impl<'a> Sealed for &'a str {}
+/// Offsets and spans produced when parsing a `&str` are *byte* offsets into the string, not `char` indices, so they can
+/// always be used to slice the original string. See [`LineIndex`](crate::span::LineIndex) and
+/// [`Span::to_char_range`] for converting them to line/column, `char`, or UTF-16 positions.
 impl<'a> Input<'a> for &'a str {
     type Offset = usize;
     type Token = char;
@@ -216,6 +453,11 @@ impl<'a> Input<'a> for &'a str {
         range.into()
     }
 
+    #[inline(always)]
+    fn eoi_span(&self) -> Self::Span {
+        (self.len()..self.len()).into()
+    }
+
     #[inline(always)]
     fn prev(offs: Self::Offset) -> Self::Offset {
         offs.saturating_sub(1)
@@ -267,6 +509,21 @@ impl<'a> SliceInput<'a> for &'a str {
     fn slice_from(&self, from: RangeFrom<Self::Offset>) -> Self::Slice {
         &self[from]
     }
+
+    #[inline]
+    fn slice_for_span(&self, span: &Self::Span) -> Option<Self::Slice> {
+        self.get(span.start..span.end)
+    }
+
+    #[inline]
+    fn lines_for_span(&self, span: &Self::Span) -> Option<Self::Slice> {
+        self.get(span.start..span.end)?;
+        let start = self[..span.start].rfind('\n').map_or(0, |i| i + 1);
+        let end = self[span.end..]
+            .find('\n')
+            .map_or(self.len(), |i| span.end + i);
+        Some(&self[start..end])
+    }
 }
 
 impl<'a, T> Sealed for &'a [T] {}
@@ -292,6 +549,11 @@ impl<'a, T> Input<'a> for &'a [T] {
         range.into()
     }
 
+    #[inline(always)]
+    fn eoi_span(&self) -> Self::Span {
+        (self.len()..self.len()).into()
+    }
+
     #[inline(always)]
     fn prev(offs: Self::Offset) -> Self::Offset {
         offs.saturating_sub(1)
@@ -324,6 +586,11 @@ impl<'a, T> SliceInput<'a> for &'a [T] {
     fn slice_from(&self, from: RangeFrom<Self::Offset>) -> Self::Slice {
         &self[from]
     }
+
+    #[inline]
+    fn slice_for_span(&self, span: &Self::Span) -> Option<Self::Slice> {
+        self.get(span.start..span.end)
+    }
 }
 
 impl<'a, T: Clone> ValueInput<'a> for &'a [T] {
@@ -371,6 +638,11 @@ impl<'a, T: 'a, const N: usize> Input<'a> for &'a [T; N] {
         range.into()
     }
 
+    #[inline(always)]
+    fn eoi_span(&self) -> Self::Span {
+        (N..N).into()
+    }
+
     #[inline(always)]
     fn prev(offs: Self::Offset) -> Self::Offset {
         offs.saturating_sub(1)
@@ -484,11 +756,18 @@ where
 
     #[inline(always)]
     unsafe fn span(&self, range: Range<Self::Offset>) -> Self::Span {
-        let start = self
-            .input
-            .next_maybe(range.start)
-            .1
-            .map_or(self.eoi.start(), |tok| tok.borrow().1.start());
+        let next = self.input.next_maybe(range.start).1;
+        // Zero-width spans lie at the start of the next token or, if there are no more tokens, at the end of input
+        if range.start == range.end {
+            return match next {
+                Some(tok) => S::new(
+                    self.eoi.context(),
+                    tok.borrow().1.start()..tok.borrow().1.start(),
+                ),
+                None => self.eoi.clone(),
+            };
+        }
+        let start = next.map_or(self.eoi.start(), |tok| tok.borrow().1.start());
         let end = self
             .input
             .next_maybe(I::prev(range.end))
@@ -497,10 +776,35 @@ where
         S::new(self.eoi.context(), start..end)
     }
 
+    #[inline(always)]
+    fn eoi_span(&self) -> Self::Span {
+        self.eoi.clone()
+    }
+
     #[inline(always)]
     fn prev(offs: Self::Offset) -> Self::Offset {
         I::prev(offs)
     }
+
+    #[inline(always)]
+    fn checkpoint(&self, offset: Self::Offset) {
+        self.input.checkpoint(offset)
+    }
+
+    #[inline(always)]
+    fn release_checkpoint(&self, offset: Self::Offset) {
+        self.input.release_checkpoint(offset)
+    }
+
+    #[inline(always)]
+    fn max_lookahead(&self) -> Option<usize> {
+        self.input.max_lookahead()
+    }
+
+    #[inline(always)]
+    unsafe fn is_undecodable(&self, range: Range<Self::Offset>) -> bool {
+        self.input.is_undecodable(range)
+    }
 }
 
 impl<'a, T, S, I> ExactSizeInput<'a> for SpannedInput<T, S, I>
@@ -611,10 +915,39 @@ where
         )
     }
 
+    #[inline(always)]
+    fn eoi_span(&self) -> Self::Span {
+        let inner_span = self.input.eoi_span();
+        Span::new(
+            self.context.clone(),
+            inner_span.start().into()..inner_span.end().into(),
+        )
+    }
+
     #[inline(always)]
     fn prev(offs: Self::Offset) -> Self::Offset {
         I::prev(offs)
     }
+
+    #[inline(always)]
+    fn checkpoint(&self, offset: Self::Offset) {
+        self.input.checkpoint(offset)
+    }
+
+    #[inline(always)]
+    fn release_checkpoint(&self, offset: Self::Offset) {
+        self.input.release_checkpoint(offset)
+    }
+
+    #[inline(always)]
+    fn max_lookahead(&self) -> Option<usize> {
+        self.input.max_lookahead()
+    }
+
+    #[inline(always)]
+    unsafe fn is_undecodable(&self, range: Range<Self::Offset>) -> bool {
+        self.input.is_undecodable(range)
+    }
 }
 
 impl<'a, S, I: Input<'a>> ExactSizeInput<'a> for WithContext<S, I>
@@ -731,10 +1064,35 @@ where
         (self.map_fn)(inner_span)
     }
 
+    #[inline(always)]
+    fn eoi_span(&self) -> Self::Span {
+        (self.map_fn)(self.input.eoi_span())
+    }
+
     #[inline(always)]
     fn prev(offs: Self::Offset) -> Self::Offset {
         I::prev(offs)
     }
+
+    #[inline(always)]
+    fn checkpoint(&self, offset: Self::Offset) {
+        self.input.checkpoint(offset)
+    }
+
+    #[inline(always)]
+    fn release_checkpoint(&self, offset: Self::Offset) {
+        self.input.release_checkpoint(offset)
+    }
+
+    #[inline(always)]
+    fn max_lookahead(&self) -> Option<usize> {
+        self.input.max_lookahead()
+    }
+
+    #[inline(always)]
+    unsafe fn is_undecodable(&self, range: Range<Self::Offset>) -> bool {
+        self.input.is_undecodable(range)
+    }
 }
 
 impl<'a, S, I: Input<'a>, F: 'a> ExactSizeInput<'a> for MappedSpan<S, I, F>
@@ -814,108 +1172,825 @@ where
 {
 }
 
-#[cfg(feature = "std")]
-struct IoInner<R> {
-    reader: BufReader<R>,
-    last_offset: usize,
-}
-
-/// Input type which supports seekable readers. Uses a [`BufReader`] internally to buffer input and
-/// avoid unnecessary IO calls.
-///
-/// Only available with the `std` feature
-#[cfg(feature = "std")]
-pub struct IoInput<R>(RefCell<IoInner<R>>);
-
-#[cfg(feature = "std")]
-impl<R: Read + Seek> IoInput<R> {
-    /// Create a new `IoReader` from a seekable reader.
-    pub fn new(reader: R) -> IoInput<R> {
-        IoInput(RefCell::new(IoInner {
-            reader: BufReader::new(reader),
-            last_offset: 0,
-        }))
-    }
+/// An input wrapper that shifts the spans of the input it wraps forward by a fixed offset. See
+/// [`Input::offset_spans`].
+#[derive(Copy, Clone)]
+pub struct OffsetSpans<I, O> {
+    input: I,
+    offset: O,
 }
 
-#[cfg(feature = "std")]
-impl<R: Read + Seek> Sealed for IoInput<R> {}
-#[cfg(feature = "std")]
-impl<'a, R: Read + Seek + 'a> Input<'a> for IoInput<R> {
-    type Offset = usize;
-    type Token = u8;
-    type Span = SimpleSpan;
+impl<I, O> Sealed for OffsetSpans<I, O> {}
+impl<'a, I: Input<'a>, O> Input<'a> for OffsetSpans<I, O>
+where
+    I::Span: Span<Offset = O>,
+    O: Add<Output = O> + Clone + 'a,
+{
+    type Offset = I::Offset;
+    type Token = I::Token;
+    type Span = I::Span;
 
+    #[inline(always)]
     fn start(&self) -> Self::Offset {
-        0
+        self.input.start()
     }
 
-    type TokenMaybe = u8;
+    type TokenMaybe = I::TokenMaybe;
 
+    #[inline(always)]
     unsafe fn next_maybe(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::TokenMaybe>) {
-        Self::next(self, offset)
+        self.input.next_maybe(offset)
     }
 
+    #[inline(always)]
     unsafe fn span(&self, range: Range<Self::Offset>) -> Self::Span {
-        SimpleSpan::from(range)
+        self.offset_span(self.input.span(range))
     }
 
-    fn prev(offs: Self::Offset) -> Self::Offset {
-        offs.saturating_sub(1)
+    #[inline(always)]
+    fn eoi_span(&self) -> Self::Span {
+        self.offset_span(self.input.eoi_span())
     }
-}
-
-#[cfg(feature = "std")]
-impl<'a, R: Read + Seek + 'a> ValueInput<'a> for IoInput<R> {
-    unsafe fn next(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::Token>) {
-        let mut inner = self.0.borrow_mut();
-
-        if offset != inner.last_offset {
-            let seek = offset as i64 - inner.last_offset as i64;
 
-            inner.reader.seek_relative(seek).unwrap();
+    #[inline(always)]
+    fn prev(offs: Self::Offset) -> Self::Offset {
+        I::prev(offs)
+    }
 
-            inner.last_offset = offset;
-        }
+    #[inline(always)]
+    fn checkpoint(&self, offset: Self::Offset) {
+        self.input.checkpoint(offset)
+    }
 
-        let mut out = 0;
+    #[inline(always)]
+    fn release_checkpoint(&self, offset: Self::Offset) {
+        self.input.release_checkpoint(offset)
+    }
 
-        let r = inner.reader.read_exact(std::slice::from_mut(&mut out));
+    #[inline(always)]
+    fn max_lookahead(&self) -> Option<usize> {
+        self.input.max_lookahead()
+    }
 
-        match r {
-            Ok(()) => {
-                inner.last_offset += 1;
-                (offset + 1, Some(out))
-            }
-            Err(_) => (offset, None),
-        }
+    #[inline(always)]
+    unsafe fn is_undecodable(&self, range: Range<Self::Offset>) -> bool {
+        self.input.is_undecodable(range)
     }
 }
 
-/// Represents a location in an input that can be rewound to.
-///
-/// Markers can be created with [`InputRef::save`] and rewound to with [`InputRef::rewind`].
-pub struct Marker<'a, 'parse, I: Input<'a>> {
-    pub(crate) offset: I::Offset,
-    pub(crate) err_count: usize,
-    phantom: PhantomData<fn(&'parse ()) -> &'parse ()>, // Invariance
+impl<I, O: Add<Output = O> + Clone> OffsetSpans<I, O> {
+    #[inline(always)]
+    fn offset_span<S: Span<Offset = O>>(&self, span: S) -> S {
+        Span::new(
+            span.context(),
+            span.start() + self.offset.clone()..span.end() + self.offset.clone(),
+        )
+    }
 }
 
-impl<'a, 'parse, I: Input<'a>> Marker<'a, 'parse, I> {
-    /// Get the [`Offset`] that this marker corresponds to.
-    pub fn offset(self) -> Offset<'a, 'parse, I> {
-        Offset {
-            offset: self.offset,
-            phantom: PhantomData,
-        }
+impl<'a, I: ExactSizeInput<'a>, O> ExactSizeInput<'a> for OffsetSpans<I, O>
+where
+    I::Span: Span<Offset = O>,
+    O: Add<Output = O> + Clone + 'a,
+{
+    #[inline(always)]
+    unsafe fn span_from(&self, range: RangeFrom<Self::Offset>) -> Self::Span {
+        self.offset_span(self.input.span_from(range))
     }
 }
 
-impl<'a, 'parse, I: Input<'a>> Copy for Marker<'a, 'parse, I> {}
-impl<'a, 'parse, I: Input<'a>> Clone for Marker<'a, 'parse, I> {
+impl<'a, I: ValueInput<'a>, O> ValueInput<'a> for OffsetSpans<I, O>
+where
+    I::Span: Span<Offset = O>,
+    O: Add<Output = O> + Clone + 'a,
+{
     #[inline(always)]
-    fn clone(&self) -> Self {
-        *self
+    unsafe fn next(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::Token>) {
+        self.input.next(offset)
+    }
+}
+
+impl<'a, I: BorrowInput<'a>, O> BorrowInput<'a> for OffsetSpans<I, O>
+where
+    I::Span: Span<Offset = O>,
+    O: Add<Output = O> + Clone + 'a,
+{
+    #[inline(always)]
+    unsafe fn next_ref(&self, offset: Self::Offset) -> (Self::Offset, Option<&'a Self::Token>) {
+        self.input.next_ref(offset)
+    }
+}
+
+impl<'a, I: SliceInput<'a>, O> SliceInput<'a> for OffsetSpans<I, O>
+where
+    I::Span: Span<Offset = O>,
+    O: Add<Output = O> + Clone + 'a,
+{
+    type Slice = I::Slice;
+
+    #[inline(always)]
+    fn full_slice(&self) -> Self::Slice {
+        <I as SliceInput>::full_slice(&self.input)
+    }
+
+    #[inline(always)]
+    fn slice(&self, range: Range<Self::Offset>) -> Self::Slice {
+        <I as SliceInput>::slice(&self.input, range)
+    }
+
+    #[inline(always)]
+    fn slice_from(&self, from: RangeFrom<Self::Offset>) -> Self::Slice {
+        <I as SliceInput>::slice_from(&self.input, from)
+    }
+}
+
+impl<'a, C, I, O> StrInput<'a, C> for OffsetSpans<I, O>
+where
+    I: StrInput<'a, C>,
+    I::Span: Span<Offset = O>,
+    O: Add<Output = O> + Clone + 'a,
+    C: Char,
+{
+}
+
+/// An input that yields the extended grapheme clusters of a string as its tokens, rather than `char`s.
+///
+/// A grapheme cluster is what a user would usually think of as a single character, but may be made up of several
+/// `char`s: for example, an `e` followed by a combining acute accent, or an emoji with a skin tone modifier. Using
+/// this input allows such sequences to be matched as a single token. Each token is a [`&str`] containing the cluster.
+///
+/// As with [`&str`], offsets and spans are byte offsets into the string, and slices are [`&str`]s.
+///
+/// Only available with the `graphemes` feature
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, input::Graphemes};
+/// let src = "cafe\u{301} 👍🏽";
+///
+/// let parser = any::<_, extra::Err<Simple<&str>>>()
+///     .repeated()
+///     .collect::<Vec<_>>();
+/// assert_eq!(
+///     parser.parse(Graphemes::new(src)).into_result(),
+///     Ok(vec!["c", "a", "f", "e\u{301}", " ", "👍🏽"]),
+/// );
+///
+/// // A thumbs up emoji of any skin tone, as a single token
+/// let thumbs_up = any::<_, extra::Err<Simple<&str>>>().filter(|g: &&str| g.starts_with('👍'));
+/// assert_eq!(thumbs_up.parse(Graphemes::new("👍🏽")).into_result(), Ok("👍🏽"));
+/// assert_eq!(thumbs_up.parse(Graphemes::new("👍")).into_result(), Ok("👍"));
+/// ```
+#[cfg(feature = "graphemes")]
+#[derive(Copy, Clone, Debug)]
+pub struct Graphemes<'a>(&'a str);
+
+#[cfg(feature = "graphemes")]
+impl<'a> Graphemes<'a> {
+    /// Create a new `Graphemes` input from a string.
+    pub fn new(src: &'a str) -> Self {
+        Self(src)
+    }
+
+    /// Get the underlying string.
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+}
+
+#[cfg(feature = "graphemes")]
+impl<'a> From<&'a str> for Graphemes<'a> {
+    fn from(src: &'a str) -> Self {
+        Self(src)
+    }
+}
+
+#[cfg(feature = "graphemes")]
+impl<'a> Sealed for Graphemes<'a> {}
+#[cfg(feature = "graphemes")]
+impl<'a> Input<'a> for Graphemes<'a> {
+    type Offset = usize;
+    type Token = &'a str;
+    type Span = SimpleSpan<usize>;
+
+    #[inline]
+    fn start(&self) -> Self::Offset {
+        0
+    }
+
+    type TokenMaybe = &'a str;
+
+    #[inline(always)]
+    unsafe fn next_maybe(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::TokenMaybe>) {
+        self.next(offset)
+    }
+
+    #[inline(always)]
+    unsafe fn span(&self, range: Range<Self::Offset>) -> Self::Span {
+        range.into()
+    }
+
+    #[inline(always)]
+    fn eoi_span(&self) -> Self::Span {
+        (self.0.len()..self.0.len()).into()
+    }
+
+    #[inline(always)]
+    fn prev(offs: Self::Offset) -> Self::Offset {
+        offs.saturating_sub(1)
+    }
+}
+
+#[cfg(feature = "graphemes")]
+impl<'a> ExactSizeInput<'a> for Graphemes<'a> {
+    #[inline(always)]
+    unsafe fn span_from(&self, range: RangeFrom<Self::Offset>) -> Self::Span {
+        (range.start..self.0.len()).into()
+    }
+}
+
+#[cfg(feature = "graphemes")]
+impl<'a> ValueInput<'a> for Graphemes<'a> {
+    #[inline]
+    unsafe fn next(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::Token>) {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        // SAFETY: We only ever return offsets that are at a grapheme cluster (and so character) boundary
+        let rest = unsafe { self.0.get_unchecked(offset..) };
+        match rest.graphemes(true).next() {
+            Some(g) => (offset + g.len(), Some(g)),
+            None => (offset, None),
+        }
+    }
+}
+
+#[cfg(feature = "graphemes")]
+impl<'a> SliceInput<'a> for Graphemes<'a> {
+    type Slice = &'a str;
+
+    #[inline(always)]
+    fn full_slice(&self) -> Self::Slice {
+        self.0
+    }
+
+    #[inline(always)]
+    fn slice(&self, range: Range<Self::Offset>) -> Self::Slice {
+        &self.0[range]
+    }
+
+    #[inline(always)]
+    fn slice_from(&self, from: RangeFrom<Self::Offset>) -> Self::Slice {
+        &self.0[from]
+    }
+}
+
+/// An input that views a byte slice as UTF-8 text, yielding [`char`]s as its tokens.
+///
+/// Unlike converting the bytes with [`core::str::from_utf8`] before parsing, the input is validated lazily as it is
+/// parsed, so mostly-textual inputs that contain stray invalid bytes can still be parsed. Each invalid sequence
+/// is yielded as a single [`char::REPLACEMENT_CHARACTER`] token whose span covers exactly the invalid bytes, in the same
+/// manner as [`String::from_utf8_lossy`]. When the parser consumes such a token, an error with its span is emitted,
+/// so invalid input is reported even by parsers, such as [`any`], that accept the replacement character. A replacement
+/// character that is validly encoded in the input is an ordinary token.
+///
+/// Offsets and spans are byte offsets into the slice. Because a slice may contain invalid sequences, slices are
+/// [`&[u8]`] rather than [`&str`].
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, input::Utf8Bytes};
+/// let word = any::<_, extra::Err<Rich<char>>>()
+///     .filter(|c: &char| c.is_alphanumeric())
+///     .repeated()
+///     .collect::<String>();
+///
+/// assert_eq!(word.parse(Utf8Bytes::new(b"caf\xC3\xA9")).into_result(), Ok("café".to_string()));
+///
+/// // The invalid byte is reported where it occurs
+/// let errs = word.parse(Utf8Bytes::new(b"caf\xFFe")).into_errors();
+/// assert_eq!(errs[0].span(), &SimpleSpan::new(3, 4));
+///
+/// // Even when the parser accepts the replacement character
+/// let text = any::<_, extra::Err<Rich<char>>>().repeated().collect::<String>();
+/// let (out, errs) = text.parse(Utf8Bytes::new(b"caf\xFFe")).into_output_errors();
+/// assert_eq!(out.as_deref(), Some("caf\u{FFFD}e"));
+/// assert_eq!(errs[0].span(), &SimpleSpan::new(3, 4));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Utf8Bytes<'a>(&'a [u8]);
+
+impl<'a> Utf8Bytes<'a> {
+    /// Create a new `Utf8Bytes` input from a byte slice.
+    pub fn new(src: &'a [u8]) -> Self {
+        Self(src)
+    }
+
+    /// Get the underlying bytes.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+}
+
+impl<'a> From<&'a [u8]> for Utf8Bytes<'a> {
+    fn from(src: &'a [u8]) -> Self {
+        Self(src)
+    }
+}
+
+impl<'a> Sealed for Utf8Bytes<'a> {}
+impl<'a> Input<'a> for Utf8Bytes<'a> {
+    type Offset = usize;
+    type Token = char;
+    type Span = SimpleSpan<usize>;
+
+    #[inline]
+    fn start(&self) -> Self::Offset {
+        0
+    }
+
+    type TokenMaybe = char;
+
+    #[inline(always)]
+    unsafe fn next_maybe(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::TokenMaybe>) {
+        self.next(offset)
+    }
+
+    #[inline(always)]
+    unsafe fn span(&self, range: Range<Self::Offset>) -> Self::Span {
+        range.into()
+    }
+
+    #[inline(always)]
+    fn eoi_span(&self) -> Self::Span {
+        (self.0.len()..self.0.len()).into()
+    }
+
+    #[inline(always)]
+    fn prev(offs: Self::Offset) -> Self::Offset {
+        offs.saturating_sub(1)
+    }
+
+    #[inline(always)]
+    unsafe fn is_undecodable(&self, range: Range<Self::Offset>) -> bool {
+        // Every token but the replacement character for an invalid sequence decodes on its own
+        let bytes = &self.0[range];
+        matches!(bytes.first(), Some(b) if !b.is_ascii()) && core::str::from_utf8(bytes).is_err()
+    }
+}
+
+impl<'a> ExactSizeInput<'a> for Utf8Bytes<'a> {
+    #[inline(always)]
+    unsafe fn span_from(&self, range: RangeFrom<Self::Offset>) -> Self::Span {
+        (range.start..self.0.len()).into()
+    }
+}
+
+impl<'a> ValueInput<'a> for Utf8Bytes<'a> {
+    #[inline]
+    unsafe fn next(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::Token>) {
+        let rest = &self.0[offset..];
+        match rest.first() {
+            None => return (offset, None),
+            Some(&b) if b.is_ascii() => return (offset + 1, Some(b as char)),
+            Some(_) => {}
+        }
+        // No char is longer than 4 bytes, so there's no need to validate any more than that
+        let (valid, invalid_len) = match core::str::from_utf8(&rest[..rest.len().min(4)]) {
+            Ok(s) => (s, 0),
+            Err(e) => {
+                // SAFETY: `valid_up_to` is the length of the valid prefix
+                let valid = unsafe { core::str::from_utf8_unchecked(&rest[..e.valid_up_to()]) };
+                // An error without a length is a sequence cut short by the end of the input
+                (valid, e.error_len().unwrap_or(rest.len() - e.valid_up_to()))
+            }
+        };
+        match valid.chars().next() {
+            Some(c) => (offset + c.len_utf8(), Some(c)),
+            None => (offset + invalid_len, Some(char::REPLACEMENT_CHARACTER)),
+        }
+    }
+}
+
+impl<'a> SliceInput<'a> for Utf8Bytes<'a> {
+    type Slice = &'a [u8];
+
+    #[inline(always)]
+    fn full_slice(&self) -> Self::Slice {
+        self.0
+    }
+
+    #[inline(always)]
+    fn slice(&self, range: Range<Self::Offset>) -> Self::Slice {
+        &self.0[range]
+    }
+
+    #[inline(always)]
+    fn slice_from(&self, from: RangeFrom<Self::Offset>) -> Self::Slice {
+        &self.0[from]
+    }
+}
+
+/// Text with its line endings normalized, and optionally its tabs expanded, for use as an input.
+///
+/// Parsers see the normalized text: each `\r\n` becomes a single `\n`, and, when a tab width is given, each tab becomes
+/// enough spaces to reach the next tab stop. This way, layout rules such as [`text::block_of`] count columns the same
+/// way no matter how a file was written. Spans are still byte offsets into the original source, so that diagnostics
+/// point at the right place in the file: the span of a newline covers the whole `\r\n`, and the span of each space from
+/// a tab covers the tab.
+///
+/// Offsets, and therefore slices, refer to the normalized text (see [`Normalized::as_str`]). Parse a `&Normalized`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, input::Normalized};
+/// let src = "a\r\n\tb";
+/// let normalized = Normalized::with_tab_width(src, 4);
+/// assert_eq!(normalized.as_str(), "a\n    b");
+///
+/// let b = just::<_, _, extra::Err<Simple<char>>>('b').map_with(|_, e| e.span());
+/// let parser = just('a').ignore_then(text::whitespace()).ignore_then(b);
+/// // The span is that of the `b` in the original source
+/// assert_eq!(parser.parse(&normalized).into_result(), Ok(SimpleSpan::from(4..5)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Normalized<'src> {
+    text: alloc::borrow::Cow<'src, str>,
+    src_len: usize,
+    // The regions in which the normalized text differs from the source, as `(normalized, source)` ranges, in order
+    edits: Vec<(Range<usize>, Range<usize>)>,
+}
+
+impl<'src> Normalized<'src> {
+    /// Normalize the line endings of some source text, turning each `\r\n` into `\n`.
+    pub fn new(src: &'src str) -> Self {
+        Self::normalize(src, None)
+    }
+
+    /// Normalize the line endings of some source text, and expand each tab into spaces up to the next multiple of
+    /// `tab_width` columns. Columns are counted in characters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tab_width` is zero.
+    pub fn with_tab_width(src: &'src str, tab_width: usize) -> Self {
+        assert!(tab_width > 0, "tab width must be non-zero");
+        Self::normalize(src, Some(tab_width))
+    }
+
+    fn normalize(src: &'src str, tab_width: Option<usize>) -> Self {
+        let mut text = String::with_capacity(src.len());
+        let mut edits = Vec::new();
+        let mut col = 0;
+        let mut chars = src.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match (c, tab_width) {
+                ('\r', _) if src[i + 1..].starts_with('\n') => {
+                    chars.next();
+                    edits.push((text.len()..text.len() + 1, i..i + 2));
+                    text.push('\n');
+                    col = 0;
+                }
+                ('\t', Some(width)) => {
+                    let spaces = width - col % width;
+                    edits.push((text.len()..text.len() + spaces, i..i + 1));
+                    for _ in 0..spaces {
+                        text.push(' ');
+                    }
+                    col += spaces;
+                }
+                ('\n', _) => {
+                    text.push('\n');
+                    col = 0;
+                }
+                _ => {
+                    text.push(c);
+                    col += 1;
+                }
+            }
+        }
+
+        Self {
+            text: if edits.is_empty() {
+                src.into()
+            } else {
+                text.into()
+            },
+            src_len: src.len(),
+            edits,
+        }
+    }
+
+    /// Get the normalized text.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Map an offset into the normalized text to a byte offset into the source.
+    ///
+    /// Offsets within an expanded region, such as between the spaces of a tab, are rounded to the start of the region
+    /// in the source, or to its end if `end` is set.
+    fn source_offset(&self, offset: usize, end: bool) -> usize {
+        let idx = self.edits.partition_point(|(norm, _)| norm.start <= offset);
+        match idx.checked_sub(1).map(|idx| &self.edits[idx]) {
+            None => offset,
+            Some((norm, src)) if offset >= norm.end => src.end + (offset - norm.end),
+            Some((norm, src)) if offset == norm.start || !end => src.start,
+            Some((_, src)) => src.end,
+        }
+    }
+}
+
+impl<'a, 'src> Sealed for &'a Normalized<'src> {}
+impl<'a, 'src> Input<'a> for &'a Normalized<'src> {
+    type Offset = usize;
+    type Token = char;
+    type Span = SimpleSpan<usize>;
+
+    #[inline]
+    fn start(&self) -> Self::Offset {
+        0
+    }
+
+    type TokenMaybe = char;
+
+    #[inline(always)]
+    unsafe fn next_maybe(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::TokenMaybe>) {
+        self.next(offset)
+    }
+
+    #[inline]
+    unsafe fn span(&self, range: Range<Self::Offset>) -> Self::Span {
+        (self.source_offset(range.start, false)..self.source_offset(range.end, true)).into()
+    }
+
+    #[inline(always)]
+    fn eoi_span(&self) -> Self::Span {
+        (self.src_len..self.src_len).into()
+    }
+
+    #[inline(always)]
+    fn prev(offs: Self::Offset) -> Self::Offset {
+        offs.saturating_sub(1)
+    }
+}
+
+impl<'a, 'src> ExactSizeInput<'a> for &'a Normalized<'src> {
+    #[inline]
+    unsafe fn span_from(&self, range: RangeFrom<Self::Offset>) -> Self::Span {
+        (self.source_offset(range.start, false)..self.src_len).into()
+    }
+}
+
+impl<'a, 'src> ValueInput<'a> for &'a Normalized<'src> {
+    #[inline(always)]
+    unsafe fn next(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::Token>) {
+        Normalized::as_str(self).next(offset)
+    }
+}
+
+impl<'a, 'src> StrInput<'a, char> for &'a Normalized<'src> {}
+
+impl<'a, 'src> SliceInput<'a> for &'a Normalized<'src> {
+    type Slice = &'a str;
+
+    #[inline(always)]
+    fn full_slice(&self) -> Self::Slice {
+        Normalized::as_str(self)
+    }
+
+    #[inline(always)]
+    fn slice(&self, range: Range<Self::Offset>) -> Self::Slice {
+        &Normalized::as_str(self)[range]
+    }
+
+    #[inline(always)]
+    fn slice_from(&self, from: RangeFrom<Self::Offset>) -> Self::Slice {
+        &Normalized::as_str(self)[from]
+    }
+}
+
+#[cfg(feature = "std")]
+struct IoInner<R> {
+    reader: BufReader<R>,
+    last_offset: usize,
+}
+
+/// Input type which supports seekable readers. Uses a [`BufReader`] internally to buffer input and
+/// avoid unnecessary IO calls.
+///
+/// Only available with the `std` feature
+#[cfg(feature = "std")]
+pub struct IoInput<R>(RefCell<IoInner<R>>);
+
+#[cfg(feature = "std")]
+impl<R: Read + Seek> IoInput<R> {
+    /// Create a new `IoReader` from a seekable reader.
+    pub fn new(reader: R) -> IoInput<R> {
+        IoInput(RefCell::new(IoInner {
+            reader: BufReader::new(reader),
+            last_offset: 0,
+        }))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read + Seek> Sealed for IoInput<R> {}
+#[cfg(feature = "std")]
+impl<'a, R: Read + Seek + 'a> Input<'a> for IoInput<R> {
+    type Offset = usize;
+    type Token = u8;
+    type Span = SimpleSpan;
+
+    fn start(&self) -> Self::Offset {
+        0
+    }
+
+    type TokenMaybe = u8;
+
+    unsafe fn next_maybe(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::TokenMaybe>) {
+        Self::next(self, offset)
+    }
+
+    unsafe fn span(&self, range: Range<Self::Offset>) -> Self::Span {
+        SimpleSpan::from(range)
+    }
+
+    fn prev(offs: Self::Offset) -> Self::Offset {
+        offs.saturating_sub(1)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, R: Read + Seek + 'a> ValueInput<'a> for IoInput<R> {
+    unsafe fn next(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::Token>) {
+        let mut inner = self.0.borrow_mut();
+
+        if offset != inner.last_offset {
+            let seek = offset as i64 - inner.last_offset as i64;
+
+            inner.reader.seek_relative(seek).unwrap();
+
+            inner.last_offset = offset;
+        }
+
+        let mut out = 0;
+
+        let r = inner.reader.read_exact(std::slice::from_mut(&mut out));
+
+        match r {
+            Ok(()) => {
+                inner.last_offset += 1;
+                (offset + 1, Some(out))
+            }
+            Err(_) => (offset, None),
+        }
+    }
+}
+
+/// A memory-mapped file, which can be parsed as either bytes or text without first reading it into memory.
+///
+/// The file's path is kept alongside the mapping, and the inputs returned by [`MappedFile::bytes`] and
+/// [`MappedFile::text`] carry it as the context of every span they produce, so that errors can name the file that they
+/// occurred in. Inputs borrow from the `MappedFile`, and so cannot outlive it.
+///
+/// Only available with the `mmap` feature
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, input::MappedFile};
+/// # let path = std::env::temp_dir().join("chumsky_mapped_file_doc.txt");
+/// # std::fs::write(&path, "1,2,x").unwrap();
+/// // SAFETY: the file is not modified while it is mapped
+/// let file = unsafe { MappedFile::open(&path) }.unwrap();
+///
+/// let nums = text::int::<_, _, extra::Err<Rich<char, SimpleSpan<usize, _>>>>(10)
+///     .separated_by(just(','))
+///     .collect::<Vec<_>>();
+///
+/// let errs = nums.parse(file.text().unwrap()).into_errors();
+/// assert_eq!(errs[0].span().context(), path.as_path());
+/// assert_eq!((errs[0].span().start, errs[0].span().end), (4, 5));
+/// ```
+#[cfg(feature = "mmap")]
+pub struct MappedFile {
+    path: PathBuf,
+    map: memmap2::Mmap,
+}
+
+#[cfg(feature = "mmap")]
+impl MappedFile {
+    /// Open the file at `path` and map it into memory.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified, truncated or deleted (by this process or any other) while it is mapped, since
+    /// inputs and parser outputs borrow from the mapping and assume that it does not change. See
+    /// [`memmap2::Mmap::map`] for details.
+    pub unsafe fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path)?;
+        // SAFETY: Upheld by the caller
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Ok(Self {
+            path: path.to_path_buf(),
+            map,
+        })
+    }
+
+    /// Get the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get the contents of the file.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.map
+    }
+
+    /// Get the contents of the file as a string, failing if it is not valid UTF-8.
+    pub fn as_str(&self) -> Result<&str, Utf8Error> {
+        core::str::from_utf8(self.as_bytes())
+    }
+
+    /// Get an input that yields the bytes of the file, with the file's path as the context of its spans.
+    pub fn bytes(&self) -> WithContext<SimpleSpan<usize, &Path>, &[u8]> {
+        self.as_bytes().with_context(self.path())
+    }
+
+    /// Get an input that yields the characters of the file, with the file's path as the context of its spans.
+    ///
+    /// The whole file is validated as UTF-8 upfront. See [`Utf8Bytes`] for an input that validates it lazily instead.
+    pub fn text(&self) -> Result<WithContext<SimpleSpan<usize, &Path>, &str>, Utf8Error> {
+        Ok(self.as_str()?.with_context(self.path()))
+    }
+}
+
+/// Represents a location in an input that can be rewound to.
+///
+/// Markers can be created with [`InputRef::save`] and rewound to with [`InputRef::rewind`].
+///
+/// Markers are not tracked by the input. To prevent a streaming input (such as [`BufferedInput`]) from discarding the
+/// tokens that a marker refers to, see [`InputRef::checkpoint`].
+pub struct Marker<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I> = extra::Default> {
+    pub(crate) offset: I::Offset,
+    pub(crate) err_count: usize,
+    pub(crate) pending: Option<usize>,
+    // The number of tokens that had been pushed back, so that those pushed afterwards can be dropped by rewinding
+    pub(crate) pushed: usize,
+    pub(crate) state: E::SavedState,
+    pub(crate) outline: usize,
+    phantom: PhantomData<fn(&'parse ()) -> &'parse ()>, // Invariance
+}
+
+impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> Marker<'a, 'parse, I, E> {
+    /// Get the [`Offset`] that this marker corresponds to.
+    pub fn offset(&self) -> Offset<'a, 'parse, I> {
+        Offset {
+            offset: self.offset,
+            pending: self.pending,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> Copy for Marker<'a, 'parse, I, E> {}
+impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> Clone for Marker<'a, 'parse, I, E> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+/// A [`Marker`] that is tracked by the input until it is dropped.
+///
+/// Checkpoints can be created with [`InputRef::checkpoint`]. While a checkpoint is alive, streaming inputs (such as
+/// [`BufferedInput`]) keep every token from its offset onwards buffered. When it is dropped, they may discard the tokens
+/// before the oldest checkpoint that remains.
+pub struct Checkpoint<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I> = extra::Default> {
+    input: &'parse I,
+    marker: Marker<'a, 'parse, I, E>,
+}
+
+impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> Checkpoint<'a, 'parse, I, E> {
+    /// Get the [`Marker`] that this checkpoint corresponds to, for use with [`InputRef::rewind`].
+    pub fn marker(&self) -> Marker<'a, 'parse, I, E> {
+        self.marker
+    }
+
+    /// Get the [`Offset`] that this checkpoint corresponds to.
+    pub fn offset(&self) -> Offset<'a, 'parse, I> {
+        self.marker.offset()
+    }
+}
+
+impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> Drop for Checkpoint<'a, 'parse, I, E> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.input.release_checkpoint(self.marker.offset);
     }
 }
 
@@ -924,6 +1999,7 @@ impl<'a, 'parse, I: Input<'a>> Clone for Marker<'a, 'parse, I> {
 /// If you to rewind to an old input location, see [`Marker`].
 pub struct Offset<'a, 'parse, I: Input<'a>> {
     pub(crate) offset: I::Offset,
+    pub(crate) pending: Option<usize>,
     phantom: PhantomData<fn(&'parse ()) -> &'parse ()>, // Invariance
 }
 
@@ -937,13 +2013,53 @@ impl<'a, 'parse, I: Input<'a>> Clone for Offset<'a, 'parse, I> {
 
 impl<'a, 'parse, I: Input<'a>> PartialEq for Offset<'a, 'parse, I> {
     fn eq(&self, other: &Self) -> bool {
-        self.offset == other.offset
+        self.offset == other.offset && self.pending == other.pending
     }
 }
 
+/// A token pushed back onto the input with [`InputRef::push_back`].
+///
+/// Pushed tokens are never removed, so that rewinding to a [`Marker`] can make them visible again. Instead, each one
+/// refers to the token that was pending beneath it when it was pushed, forming a stack.
+pub(crate) struct PushedToken<T> {
+    tok: T,
+    // Allows tokens to be cloned out of the stack without requiring `T: Clone` everywhere that reads tokens
+    clone: fn(&T) -> T,
+    below: Option<usize>,
+}
+
 pub(crate) struct Errors<T, E> {
     pub(crate) alt: Option<Located<T, E>>,
     pub(crate) secondary: Vec<Located<T, E>>,
+    /// For each successful error recovery, the index of the secondary error it emitted and the number of tokens it
+    /// skipped.
+    pub(crate) recoveries: Vec<(usize, usize)>,
+    /// If set, every error produced at a specific offset is recorded. See [`Parser::parse_expecting_at`].
+    pub(crate) expecting: Option<Expecting<E>>,
+    /// If set, the extent of every successful [`Parser::node`] is recorded, in the order they began. See
+    /// [`Parser::parse_spans`].
+    pub(crate) outline: Option<Vec<OutlineNode<T>>>,
+    /// Whether any parser tried to read a token at the end of the input. See [`ParseResult::is_incomplete`].
+    pub(crate) reached_eoi: bool,
+    /// The index of the secondary error emitted for the last undecodable token that was consumed. See
+    /// [`Input::is_undecodable`].
+    pub(crate) undecodable: Option<usize>,
+}
+
+/// The extent of a [`Parser::node`] that was parsed, as recorded by [`Parser::parse_spans`].
+pub(crate) struct OutlineNode<T> {
+    pub(crate) start: T,
+    pub(crate) end: T,
+    /// The number of nodes that were recorded while this one was being parsed, which immediately follow it.
+    pub(crate) descendants: usize,
+}
+
+/// The errors produced at a specific offset, whether or not they end up being reported.
+pub(crate) struct Expecting<E> {
+    pub(crate) offset: usize,
+    // The error type isn't necessarily `Clone`, so `Parser::parse_expecting_at` supplies a way to clone it
+    pub(crate) clone: fn(&E) -> E,
+    pub(crate) err: Option<E>,
 }
 
 impl<T, E> Errors<T, E> {
@@ -952,6 +2068,25 @@ impl<T, E> Errors<T, E> {
     pub(crate) fn secondary_errors_since(&mut self, err_count: usize) -> &mut [Located<T, E>] {
         self.secondary.get_mut(err_count..).unwrap_or(&mut [])
     }
+
+    /// Discard all errors (and any recoveries that emitted them) after the first `err_count` secondary errors.
+    #[inline(always)]
+    pub(crate) fn truncate(&mut self, err_count: usize) {
+        self.secondary.truncate(err_count);
+        while matches!(self.recoveries.last(), Some((idx, _)) if *idx >= err_count) {
+            self.recoveries.pop();
+        }
+        if matches!(self.undecodable, Some(idx) if idx >= err_count) {
+            self.undecodable = None;
+        }
+    }
+
+    pub(crate) fn recovery_stats(&self) -> RecoveryStats {
+        RecoveryStats {
+            recoveries: self.recoveries.len(),
+            skipped: self.recoveries.iter().map(|(_, skipped)| skipped).sum(),
+        }
+    }
 }
 
 impl<T, E> Default for Errors<T, E> {
@@ -959,6 +2094,11 @@ impl<T, E> Default for Errors<T, E> {
         Self {
             alt: None,
             secondary: Vec::new(),
+            recoveries: Vec::new(),
+            expecting: None,
+            outline: None,
+            reached_eoi: false,
+            undecodable: None,
         }
     }
 }
@@ -970,8 +2110,13 @@ pub(crate) struct InputOwn<'a, 's, I: Input<'a>, E: ParserExtra<'a, I>> {
     pub(crate) errors: Errors<I::Offset, E::Error>,
     pub(crate) state: MaybeMut<'s, E::State>,
     pub(crate) ctx: E::Context,
+    pub(crate) pushed: Vec<PushedToken<I::Token>>,
     #[cfg(feature = "memoization")]
     pub(crate) memos: HashMap<(I::Offset, usize), Option<Located<I::Offset, E::Error>>>,
+    #[cfg(feature = "debug-replay")]
+    pub(crate) trace: Option<RefCell<Trace>>,
+    #[cfg(feature = "telemetry")]
+    pub(crate) stats: Option<RefCell<ParseStats>>,
 }
 
 impl<'a, 's, I, E> InputOwn<'a, 's, I, E>
@@ -990,8 +2135,13 @@ where
             errors: Errors::default(),
             state: MaybeMut::Val(E::State::default()),
             ctx: E::Context::default(),
+            pushed: Vec::new(),
             #[cfg(feature = "memoization")]
             memos: HashMap::default(),
+            #[cfg(feature = "debug-replay")]
+            trace: None,
+            #[cfg(feature = "telemetry")]
+            stats: None,
         }
     }
 
@@ -1004,8 +2154,13 @@ where
             errors: Errors::default(),
             state: MaybeMut::Ref(state),
             ctx: E::Context::default(),
+            pushed: Vec::new(),
             #[cfg(feature = "memoization")]
             memos: HashMap::default(),
+            #[cfg(feature = "debug-replay")]
+            trace: None,
+            #[cfg(feature = "telemetry")]
+            stats: None,
         }
     }
 
@@ -1016,12 +2171,20 @@ where
             errors: &mut self.errors,
             state: &mut self.state,
             ctx: &self.ctx,
+            pushed: &mut self.pushed,
+            pending: None,
+            layout: None,
+            trivia: None,
+            limit: None,
             #[cfg(feature = "memoization")]
             memos: &mut self.memos,
+            #[cfg(feature = "debug-replay")]
+            trace: self.trace.as_ref(),
+            #[cfg(feature = "telemetry")]
+            stats: self.stats.as_ref(),
         }
     }
 
-    #[cfg(test)]
     pub(crate) fn as_ref_at<'parse>(
         &'parse mut self,
         offset: I::Offset,
@@ -1032,17 +2195,122 @@ where
             errors: &mut self.errors,
             state: &mut self.state,
             ctx: &self.ctx,
+            pushed: &mut self.pushed,
+            pending: None,
+            layout: None,
+            trivia: None,
+            limit: None,
             #[cfg(feature = "memoization")]
             memos: &mut self.memos,
+            #[cfg(feature = "debug-replay")]
+            trace: self.trace.as_ref(),
+            #[cfg(feature = "telemetry")]
+            stats: self.stats.as_ref(),
         }
     }
 
-    pub(crate) fn into_errs(self) -> Vec<E::Error> {
-        self.errors
-            .secondary
-            .into_iter()
-            .map(|err| err.err)
-            .collect()
+    /// Prepare to parse another input, keeping the buffers allocated while parsing the previous one.
+    pub(crate) fn reset(&mut self, input: I) {
+        self.input = input;
+        self.errors = Errors::default();
+        self.pushed.clear();
+        #[cfg(feature = "memoization")]
+        self.memos.clear();
+    }
+
+    /// Run a parser in mode `M` from the given offset, handing its result to `finish`.
+    ///
+    /// This is the driver shared by every way of parsing an owned input. If the parser fails, its error is taken from
+    /// the input and passed to `finish`, which decides whether it is reported. `finish` also receives the input,
+    /// positioned wherever the parser stopped.
+    pub(crate) fn drive<M: Mode, O, P: Parser<'a, I, O, E>, R>(
+        &mut self,
+        offset: I::Offset,
+        parser: &P,
+        finish: impl FnOnce(
+            &mut InputRef<'a, '_, I, E>,
+            Result<M::Output<O>, Located<I::Offset, E::Error>>,
+        ) -> R,
+    ) -> R {
+        let mut inp = self.as_ref_at(offset);
+        let res = parser.go::<M>(&mut inp);
+        let res = res.map_err(|()| inp.errors.alt.take().expect("error but no alt?"));
+        finish(&mut inp, res)
+    }
+
+    /// Like [`InputOwn::drive`], but runs the parser over only the given range of the input, as if the input ended at
+    /// the end of the range.
+    ///
+    /// Offsets and spans remain those of the whole input.
+    pub(crate) fn drive_within<M: Mode, O, P: Parser<'a, I, O, E>, R>(
+        &mut self,
+        range: Range<I::Offset>,
+        parser: &P,
+        finish: impl FnOnce(
+            &mut InputRef<'a, '_, I, E>,
+            Result<M::Output<O>, Located<I::Offset, E::Error>>,
+        ) -> R,
+    ) -> R {
+        let mut inp = self.as_ref_at(range.start);
+        inp.limit = Some(range.end);
+        let res = parser.go::<M>(&mut inp);
+        let res = res.map_err(|()| inp.errors.alt.take().expect("error but no alt?"));
+        finish(&mut inp, res)
+    }
+
+    /// Parse the whole input with the given parser in mode `M`, reporting the error if it fails.
+    pub(crate) fn run<M: Mode, O, P: Parser<'a, I, O, E>>(
+        &mut self,
+        parser: &P,
+    ) -> Option<M::Output<O>> {
+        self.drive::<M, _, _, _>(
+            self.input.start(),
+            &parser.then_ignore(end()),
+            |inp, res| res.map_err(|err| inp.errors.secondary.push(err)).ok(),
+        )
+    }
+
+    /// Parse the whole input with the given parser, as [`Parser::parse`] does.
+    pub(crate) fn parse_with<O, P: Parser<'a, I, O, E>>(
+        &mut self,
+        parser: &P,
+    ) -> ParseResult<O, E::Error> {
+        let out = self.run::<Emit, _, _>(parser);
+        self.take_result(out)
+    }
+
+    /// Take the errors produced so far, sorted by the position at which they occurred.
+    ///
+    /// The sort is stable, so errors at the same position remain in the order in which they were emitted.
+    pub(crate) fn take_errs(&mut self) -> Vec<E::Error> {
+        let mut errs = core::mem::take(&mut self.errors.secondary);
+        self.errors.undecodable = None;
+        errs.sort_by_key(|err| err.pos);
+        errs.into_iter().map(|err| err.err).collect()
+    }
+
+    /// Whether an error at the given offset may have occurred only because the input ended too soon.
+    ///
+    /// Errors can be located at the end of the input without having reached it (such as those of [`Parser::try_map`]
+    /// on the last token), so this also requires that some parser tried to read a token at the end of the input.
+    pub(crate) fn ended_at(&self, offset: I::Offset) -> bool {
+        // SAFETY: offsets generated by previous call to `InputRef::next` (or similar).
+        self.errors.reached_eoi && unsafe { self.input.next_maybe(offset).1.is_none() }
+    }
+
+    /// Produce the result of a parse from its output and the errors produced during parsing.
+    pub(crate) fn into_result<O>(mut self, out: Option<O>) -> ParseResult<O, E::Error> {
+        self.take_result(out)
+    }
+
+    /// Like [`InputOwn::into_result`], but takes the errors produced so far so that parsing may continue afterwards.
+    pub(crate) fn take_result<O>(&mut self, out: Option<O>) -> ParseResult<O, E::Error> {
+        let recovery = self.errors.recovery_stats();
+        let errs = &self.errors.secondary;
+        let incomplete = !errs.is_empty() && errs.iter().all(|err| self.ended_at(err.pos));
+        ParseResult::new(out, self.take_errs())
+            .with_recovery(recovery)
+            .with_incomplete(incomplete)
     }
 }
 
@@ -1053,8 +2321,22 @@ pub struct InputRef<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> {
     pub(crate) errors: &'parse mut Errors<I::Offset, E::Error>,
     pub(crate) state: &'parse mut E::State,
     pub(crate) ctx: &'parse E::Context,
+    pub(crate) pushed: &'parse mut Vec<PushedToken<I::Token>>,
+    /// The index of the pushed token that will be produced next, if any.
+    pub(crate) pending: Option<usize>,
+    /// The column that a line must be indented beyond to continue the current item of a [`text::block_of`], if any.
+    pub(crate) layout: Option<usize>,
+    /// Tokens that are skipped before each token is read, as configured by [`Parser::with_trivia`], if any.
+    pub(crate) trivia: Option<fn(&I::Token) -> bool>,
+    /// The offset at which the input is treated as ending, if it ends before its true end. See
+    /// [`InputOwn::drive_within`].
+    pub(crate) limit: Option<I::Offset>,
     #[cfg(feature = "memoization")]
     pub(crate) memos: &'parse mut HashMap<(I::Offset, usize), Option<Located<I::Offset, E::Error>>>,
+    #[cfg(feature = "debug-replay")]
+    pub(crate) trace: Option<&'parse RefCell<Trace>>,
+    #[cfg(feature = "telemetry")]
+    pub(crate) stats: Option<&'parse RefCell<ParseStats>>,
 }
 
 impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E> {
@@ -1074,11 +2356,21 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
             state: self.state,
             ctx: new_ctx,
             errors: self.errors,
+            pushed: self.pushed,
+            pending: self.pending,
+            layout: self.layout,
+            trivia: self.trivia,
+            limit: self.limit,
             #[cfg(feature = "memoization")]
             memos: self.memos,
+            #[cfg(feature = "debug-replay")]
+            trace: self.trace,
+            #[cfg(feature = "telemetry")]
+            stats: self.stats,
         };
         let res = f(&mut new_inp);
         self.offset = new_inp.offset;
+        self.pending = new_inp.pending;
         res
     }
 
@@ -1098,11 +2390,21 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
             state: new_state,
             ctx: self.ctx,
             errors: self.errors,
+            pushed: self.pushed,
+            pending: self.pending,
+            layout: self.layout,
+            trivia: self.trivia,
+            limit: self.limit,
             #[cfg(feature = "memoization")]
             memos: self.memos,
+            #[cfg(feature = "debug-replay")]
+            trace: self.trace,
+            #[cfg(feature = "telemetry")]
+            stats: self.stats,
         };
         let res = f(&mut new_inp);
         self.offset = new_inp.offset;
+        self.pending = new_inp.pending;
         res
     }
 
@@ -1125,19 +2427,84 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
             state: self.state,
             ctx: self.ctx,
             errors: self.errors,
+            pushed: self.pushed,
+            pending: None,
+            layout: None,
+            trivia: None,
+            limit: None,
             #[cfg(feature = "memoization")]
             memos,
+            // Offsets into a nested input are meaningless in the trace of the outer input
+            #[cfg(feature = "debug-replay")]
+            trace: None,
+            #[cfg(feature = "telemetry")]
+            stats: self.stats,
         };
         f(&mut new_inp)
     }
 
+    /// Run `f` with a fresh set of errors of a different type, returning the errors that were produced.
+    ///
+    /// The caller must emit the returned secondary errors immediately and in order, since the error recoveries that
+    /// emitted them are carried over to this input.
+    #[inline]
+    pub(crate) fn with_errors<EM, O>(
+        &mut self,
+        f: impl FnOnce(&mut InputRef<'a, '_, I, EM>) -> O,
+    ) -> (O, Errors<I::Offset, EM::Error>)
+    where
+        EM: ParserExtra<'a, I, State = E::State, Context = E::Context>,
+    {
+        let mut errors = Errors {
+            outline: self.errors.outline.take(),
+            ..Errors::default()
+        };
+        #[cfg(feature = "memoization")]
+        let mut memos = HashMap::default();
+        let mut new_inp = InputRef {
+            offset: self.offset,
+            input: self.input,
+            errors: &mut errors,
+            state: &mut *self.state,
+            ctx: self.ctx,
+            pushed: &mut *self.pushed,
+            pending: self.pending,
+            layout: self.layout,
+            trivia: self.trivia,
+            limit: self.limit,
+            #[cfg(feature = "memoization")]
+            memos: &mut memos,
+            #[cfg(feature = "debug-replay")]
+            trace: self.trace,
+            #[cfg(feature = "telemetry")]
+            stats: self.stats,
+        };
+        let res = f(&mut new_inp);
+        self.offset = new_inp.offset;
+        self.pending = new_inp.pending;
+        self.errors.reached_eoi |= errors.reached_eoi;
+        self.errors.outline = errors.outline.take();
+        let base = self.errors.secondary.len();
+        if let Some(idx) = errors.undecodable {
+            self.errors.undecodable = Some(base + idx);
+        }
+        self.errors.recoveries.extend(
+            errors
+                .recoveries
+                .drain(..)
+                .map(|(idx, skipped)| (base + idx, skipped)),
+        );
+        (res, errors)
+    }
+
     /// Get the internal offset of the input at this moment in time.
     ///
     /// Can be used for generating spans or slices. See [`InputRef::span`] and [`InputRef::slice`].
     #[inline(always)]
     pub fn offset(&self) -> Offset<'a, 'parse, I> {
         Offset {
-            offset: self.offset,
+            offset: self.trivia_end(),
+            pending: self.pending,
             phantom: PhantomData,
         }
     }
@@ -1146,21 +2513,67 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     ///
     /// You can rewind back to this state later with [`InputRef::rewind`].
     #[inline(always)]
-    pub fn save(&self) -> Marker<'a, 'parse, I> {
+    pub fn save(&self) -> Marker<'a, 'parse, I, E> {
+        #[cfg(feature = "debug-replay")]
+        self.record(TraceEvent::Save {
+            offset: self.offset.into(),
+        });
+        #[cfg(feature = "telemetry")]
+        self.count(|stats| stats.saves += 1);
         Marker {
             offset: self.offset,
             err_count: self.errors.secondary.len(),
+            pending: self.pending,
+            pushed: self.pushed.len(),
+            state: E::save_state(self.state),
+            outline: self.errors.outline.as_ref().map_or(0, Vec::len),
             phantom: PhantomData,
         }
     }
 
+    /// Save the current parse state as a [`Checkpoint`].
+    ///
+    /// Unlike a [`Marker`], a checkpoint is tracked by the input until it is dropped. Streaming inputs (such as
+    /// [`BufferedInput`]) discard buffered tokens only when a checkpoint is dropped, and never discard tokens that a
+    /// live checkpoint refers to. You can rewind back to a checkpoint with [`InputRef::rewind`] via
+    /// [`Checkpoint::marker`].
+    ///
+    /// Dropping the last live checkpoint tells the input that the parser will never rewind before it again. Markers
+    /// created with [`InputRef::save`], such as those held by [`Parser::or`] or [`Parser::repeated`], are not tracked.
+    /// A parser that may backtrack over a region in which checkpoints are dropped must therefore hold a checkpoint of
+    /// its own: rewinding to a discarded token panics.
+    #[inline(always)]
+    pub fn checkpoint(&self) -> Checkpoint<'a, 'parse, I, E> {
+        self.input.checkpoint(self.offset);
+        Checkpoint {
+            input: self.input,
+            marker: self.save(),
+        }
+    }
+
     /// Reset the parse state to that represented by the given [`Marker`].
     ///
     /// You can create a marker with which to perform rewinding using [`InputRef::save`].
     #[inline(always)]
-    pub fn rewind(&mut self, marker: Marker<'a, 'parse, I>) {
-        self.errors.secondary.truncate(marker.err_count);
+    pub fn rewind(&mut self, marker: Marker<'a, 'parse, I, E>) {
+        #[cfg(feature = "debug-replay")]
+        self.record(TraceEvent::Rewind {
+            from: self.offset.into(),
+            to: marker.offset.into(),
+        });
+        #[cfg(feature = "telemetry")]
+        self.count(|stats| {
+            stats.rewinds += 1;
+            stats.backtracks += usize::from(marker.offset < self.offset);
+        });
+        self.errors.truncate(marker.err_count);
         self.offset = marker.offset;
+        self.pending = marker.pending;
+        self.pushed.truncate(marker.pushed);
+        E::restore_state(self.state, marker.state);
+        if let Some(outline) = &mut self.errors.outline {
+            outline.truncate(marker.outline);
+        }
     }
 
     /// Get a mutable reference to the state associated with the current parse.
@@ -1184,34 +2597,227 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     where
         I: ValueInput<'a>,
     {
+        while let Some(idx) = self.pending {
+            let pushed = &self.pushed[idx];
+            if !f(&pushed.tok) {
+                return;
+            }
+            self.pending = pushed.below;
+        }
         loop {
             // SAFETY: offset was generated by previous call to `Input::next`
-            let (offset, token) = unsafe { self.input.next(self.offset) };
+            let (offset, token) = unsafe { self.read(self.offset) };
             if token.filter(&mut f).is_none() {
                 break;
             } else {
+                let start = self.offset;
                 self.offset = offset;
+                self.check_decoded(start);
+                #[cfg(feature = "debug-replay")]
+                self.record_next(start);
+                #[cfg(feature = "telemetry")]
+                self.count(|stats| stats.tokens += 1);
+            }
+        }
+    }
+
+    #[cfg(feature = "telemetry")]
+    #[inline(always)]
+    pub(crate) fn count(&self, f: impl FnOnce(&mut ParseStats)) {
+        if let Some(stats) = self.stats {
+            f(&mut stats.borrow_mut());
+        }
+    }
+
+    #[cfg(feature = "debug-replay")]
+    #[inline(always)]
+    fn record(&self, event: TraceEvent) {
+        if let Some(trace) = self.trace {
+            trace.borrow_mut().record(event);
+        }
+    }
+
+    #[cfg(feature = "debug-replay")]
+    #[inline(always)]
+    fn record_next(&self, start: I::Offset) {
+        self.record(TraceEvent::Next {
+            start: start.into(),
+            end: self.offset.into(),
+        });
+    }
+
+    /// Take the pending pushed-back token, if there is one.
+    #[inline(always)]
+    fn next_pushed(&mut self) -> Option<I::Token> {
+        let pushed = &self.pushed[self.pending?];
+        self.pending = pushed.below;
+        Some((pushed.clone)(&pushed.tok))
+    }
+
+    #[inline(always)]
+    fn peek_pushed(&self) -> Option<I::Token> {
+        let pushed = &self.pushed[self.pending?];
+        Some((pushed.clone)(&pushed.tok))
+    }
+
+    /// Pushed tokens are owned by the parse, so they cannot be borrowed for `'a` like the tokens of the input.
+    #[inline(always)]
+    fn assert_none_pushed(&self) {
+        assert!(
+            self.pending.is_none(),
+            "attempted to borrow a token that was pushed back with `InputRef::push_back`: \
+             pushed tokens can only be read by value",
+        );
+    }
+
+    /// Read the token at the given offset, treating the input as ending at `limit`.
+    ///
+    /// # Safety
+    ///
+    /// As for [`Input::next_maybe`].
+    #[inline(always)]
+    unsafe fn read_maybe(&self, offset: I::Offset) -> (I::Offset, Option<I::TokenMaybe>) {
+        match self.limit {
+            Some(limit) if offset >= limit => (offset, None),
+            _ => self.input.next_maybe(offset),
+        }
+    }
+
+    /// Read the token at the given offset by value, treating the input as ending at `limit`.
+    ///
+    /// # Safety
+    ///
+    /// As for [`ValueInput::next`].
+    #[inline(always)]
+    unsafe fn read(&self, offset: I::Offset) -> (I::Offset, Option<I::Token>)
+    where
+        I: ValueInput<'a>,
+    {
+        match self.limit {
+            Some(limit) if offset >= limit => (offset, None),
+            _ => self.input.next(offset),
+        }
+    }
+
+    /// Read a reference to the token at the given offset, treating the input as ending at `limit`.
+    ///
+    /// # Safety
+    ///
+    /// As for [`BorrowInput::next_ref`].
+    #[inline(always)]
+    unsafe fn read_ref(&self, offset: I::Offset) -> (I::Offset, Option<&'a I::Token>)
+    where
+        I: BorrowInput<'a>,
+    {
+        match self.limit {
+            Some(limit) if offset >= limit => (offset, None),
+            _ => self.input.next_ref(offset),
+        }
+    }
+
+    /// The offset after any trivia tokens that follow the current offset.
+    ///
+    /// Pushed tokens are never trivia, so nothing is skipped while one is pending.
+    #[inline(always)]
+    fn trivia_end(&self) -> I::Offset {
+        let trivia = match self.trivia {
+            Some(trivia) if self.pending.is_none() => trivia,
+            _ => return self.offset,
+        };
+        let mut offset = self.offset;
+        loop {
+            // SAFETY: offset was generated by previous call to `Input::next`
+            match unsafe { self.read_maybe(offset) } {
+                (next, Some(tok)) if trivia(tok.borrow()) => offset = next,
+                _ => return offset,
+            }
+        }
+    }
+
+    /// Emit an error if the token that was just consumed, from `start` to the current offset, stands in for input that
+    /// could not be decoded (see [`Input::is_undecodable`]).
+    #[inline(always)]
+    fn check_decoded(&mut self, start: I::Offset) {
+        // SAFETY: both offsets were generated by previous calls to `Input::next`
+        if unsafe { self.input.is_undecodable(start..self.offset) } {
+            self.emit_undecodable(start);
+        }
+    }
+
+    #[cold]
+    fn emit_undecodable(&mut self, start: I::Offset) {
+        // SAFETY: both offsets were generated by previous calls to `Input::next`
+        let (found, span) = unsafe {
+            (
+                self.input.next_maybe(start).1.map(Into::into),
+                self.input.span(start..self.offset),
+            )
+        };
+        self.errors.undecodable = Some(self.errors.secondary.len());
+        self.emit(start, E::Error::expected_found(None, found, span));
+    }
+
+    /// A parser that fails at `at` reports the token there itself, so if that token was undecodable and has only just
+    /// been consumed, drop the error emitted for it.
+    #[inline(always)]
+    fn forget_undecodable(&mut self, at: I::Offset) {
+        if let Some(idx) = self.errors.undecodable {
+            if idx + 1 == self.errors.secondary.len() && self.errors.secondary[idx].pos == at {
+                self.errors.secondary.pop();
+                self.errors.undecodable = None;
             }
         }
     }
 
+    #[inline(always)]
+    fn skip_trivia(&mut self) {
+        if self.trivia.is_some() {
+            self.offset = self.trivia_end();
+        }
+    }
+
     #[inline(always)]
     pub(crate) fn next_inner(&mut self) -> (I::Offset, Option<I::Token>)
     where
         I: ValueInput<'a>,
     {
+        if let Some(tok) = self.next_pushed() {
+            return (self.offset, Some(tok));
+        }
+        self.skip_trivia();
         // SAFETY: offset was generated by previous call to `Input::next`
-        let (offset, token) = unsafe { self.input.next(self.offset) };
+        let (offset, token) = unsafe { self.read(self.offset) };
+        if token.is_none() {
+            self.errors.reached_eoi = true;
+        }
+        let start = self.offset;
         self.offset = offset;
+        self.check_decoded(start);
+        #[cfg(feature = "debug-replay")]
+        self.record_next(start);
+        #[cfg(feature = "telemetry")]
+        self.count(|stats| stats.tokens += usize::from(token.is_some()));
         (self.offset, token)
     }
 
     #[inline(always)]
-    pub(crate) fn next_maybe_inner(&mut self) -> (I::Offset, Option<I::TokenMaybe>) {
+    pub(crate) fn next_maybe_inner(&mut self) -> (I::Offset, Option<MaybeRef<'a, I::Token>>) {
+        if let Some(tok) = self.next_pushed() {
+            return (self.offset, Some(MaybeRef::Val(tok)));
+        }
+        self.skip_trivia();
         // SAFETY: offset was generated by previous call to `Input::next`
-        let (offset, token) = unsafe { self.input.next_maybe(self.offset) };
-        let r = (self.offset, token);
+        let (offset, token) = unsafe { self.read_maybe(self.offset) };
+        if token.is_none() {
+            self.errors.reached_eoi = true;
+        }
+        let r = (self.offset, token.map(Into::into));
         self.offset = offset;
+        self.check_decoded(r.0);
+        #[cfg(feature = "debug-replay")]
+        self.record_next(r.0);
+        #[cfg(feature = "telemetry")]
+        self.count(|stats| stats.tokens += usize::from(r.1.is_some()));
         r
     }
 
@@ -1220,9 +2826,20 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     where
         I: BorrowInput<'a>,
     {
+        self.assert_none_pushed();
+        self.skip_trivia();
         // SAFETY: offset was generated by previous call to `Input::next`
-        let (offset, token) = unsafe { self.input.next_ref(self.offset) };
+        let (offset, token) = unsafe { self.read_ref(self.offset) };
+        if token.is_none() {
+            self.errors.reached_eoi = true;
+        }
+        let start = self.offset;
         self.offset = offset;
+        self.check_decoded(start);
+        #[cfg(feature = "debug-replay")]
+        self.record_next(start);
+        #[cfg(feature = "telemetry")]
+        self.count(|stats| stats.tokens += usize::from(token.is_some()));
         (self.offset, token)
     }
 
@@ -1271,7 +2888,7 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     /// See [`InputRef::next_ref`] if you want get a reference to the next token instead.
     #[inline(always)]
     pub fn next_maybe(&mut self) -> Option<MaybeRef<'a, I::Token>> {
-        self.next_maybe_inner().1.map(Into::into)
+        self.next_maybe_inner().1
     }
 
     /// Get the next token in the input by value. Returns `None` if the end of the input has been reached.
@@ -1288,6 +2905,10 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     /// Get a reference to the next token in the input. Returns `None` if the end of the input has been reached.
     ///
     /// See [`InputRef::next`] if you want get the next token by value instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the next token was [pushed back](InputRef::push_back), since pushed tokens cannot be borrowed.
     #[inline(always)]
     pub fn next_ref(&mut self) -> Option<&'a I::Token>
     where
@@ -1301,8 +2922,11 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     /// See [`InputRef::next_maybe`] for more information about what this function guarantees.
     #[inline(always)]
     pub fn peek_maybe(&self) -> Option<MaybeRef<'a, I::Token>> {
+        if let Some(tok) = self.peek_pushed() {
+            return Some(MaybeRef::Val(tok));
+        }
         // SAFETY: offset was generated by previous call to `Input::next`
-        unsafe { self.input.next_maybe(self.offset).1.map(Into::into) }
+        unsafe { self.read_maybe(self.trivia_end()).1.map(Into::into) }
     }
 
     /// Peek the next token in the input. Returns `None` if the end of the input has been reached.
@@ -1311,18 +2935,82 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     where
         I: ValueInput<'a>,
     {
+        if let Some(tok) = self.peek_pushed() {
+            return Some(tok);
+        }
         // SAFETY: offset was generated by previous call to `Input::next`
-        unsafe { self.input.next(self.offset).1 }
+        unsafe { self.read(self.trivia_end()).1 }
     }
 
     /// Peek the next token in the input. Returns `None` if the end of the input has been reached.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the next token was [pushed back](InputRef::push_back), since pushed tokens cannot be borrowed.
     #[inline(always)]
     pub fn peek_ref(&self) -> Option<&'a I::Token>
     where
         I: BorrowInput<'a>,
     {
+        self.assert_none_pushed();
         // SAFETY: offset was generated by previous call to `Input::next`
-        unsafe { self.input.next_ref(self.offset).1 }
+        unsafe { self.read_ref(self.trivia_end()).1 }
+    }
+
+    /// Peek the token `n` tokens after the next token in the input, such that `peek_n(0)` is equivalent to
+    /// [`InputRef::peek`]. Returns `Ok(None)` if the end of the input is reached first.
+    ///
+    /// This allows fixed-length lookahead without consuming (and then rewinding) input, which streaming inputs with a
+    /// bounded buffer (see [`BufferedInput::with_max_lookahead`]) depend on.
+    ///
+    /// # Errors
+    ///
+    /// If the input can hold at most `k` tokens at once, finding the token must not require reading `k` or more tokens
+    /// past the current position (which, for an input created with [`BufferedInput::with_max_lookahead`], means that
+    /// `n` must be less than `k`), since reading it would discard the next token. Otherwise, an error is returned
+    /// that spans the tokens that could be peeked and reports the next token as unexpected, since the grammar needs
+    /// more lookahead than the input allows at that point. Returning it from a parser created with [`custom`] reports
+    /// it like any other error, so the contexts of the parsers that it passes through (see [`Parser::context`]) are
+    /// attached to it.
+    #[inline]
+    pub fn peek_n(&self, mut n: usize) -> Result<Option<I::Token>, E::Error>
+    where
+        I: ValueInput<'a>,
+    {
+        let max_lookahead = self.input.max_lookahead();
+        let mut pending = self.pending;
+        while let Some(idx) = pending {
+            let pushed = &self.pushed[idx];
+            if n == 0 {
+                return Ok(Some((pushed.clone)(&pushed.tok)));
+            }
+            n -= 1;
+            pending = pushed.below;
+        }
+        let mut offset = self.offset;
+        loop {
+            if matches!(max_lookahead, Some(k) if offset.into() - self.offset.into() >= k) {
+                // SAFETY: offset was generated by previous call to `Input::next`, and the token at it is still held
+                // by the input
+                let found = unsafe { self.read(self.offset).1 }.map(MaybeRef::Val);
+                // SAFETY: offsets were generated by previous calls to `Input::next`
+                let span = unsafe { self.input.span(self.offset..offset) };
+                return Err(E::Error::expected_found(None, found, span));
+            }
+            // SAFETY: offset was generated by previous call to `Input::next`
+            let (next, tok) = unsafe { self.read(offset) };
+            let tok = match tok {
+                Some(tok) => tok,
+                None => return Ok(None),
+            };
+            offset = next;
+            if matches!(self.trivia, Some(trivia) if trivia(&tok)) {
+                continue;
+            } else if n == 0 {
+                return Ok(Some(tok));
+            }
+            n -= 1;
+        }
     }
 
     /// Skip the next token in the input.
@@ -1334,6 +3022,82 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
         let _ = self.next_inner();
     }
 
+    /// Push a token back onto the input, so that it is the next token produced.
+    ///
+    /// This makes it possible to split a token into several, or to synthesise tokens that do not appear in the input
+    /// at all. A classic use is in grammars with generics, where a lexer produces `>>` as a single shift token but a
+    /// parser closing a generic argument list needs to consume only a single `>`.
+    ///
+    /// Tokens may be pushed several times, and are produced in the reverse of the order that they were pushed.
+    /// Rewinding to a [`Marker`] restores the pushed tokens that were pending when it was saved, and drops those pushed
+    /// since.
+    ///
+    /// Pushed tokens do not occupy any space in the input, so the span of a pushed token is the empty span at the
+    /// current position. Likewise, spans and slices (such as those produced by [`Parser::map_with_span`] and
+    /// [`Parser::slice`]) are taken from the input alone: they never include pushed tokens, and a slice taken across a
+    /// token that was split with this method still contains the whole of the original token.
+    ///
+    /// # Panics
+    ///
+    /// Pushed tokens can only be read by value. Borrowing the next token (with [`InputRef::next_ref`],
+    /// [`InputRef::peek_ref`], or parsers built on them such as [`select_ref!`]) panics while a pushed token is pending.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::{Error, Simple}};
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// enum Token {
+    ///     Ident(&'static str),
+    ///     Lt,
+    ///     Gt,
+    ///     Shr,
+    /// }
+    /// use Token::*;
+    ///
+    /// let tokens = [Ident("Vec"), Lt, Ident("Vec"), Lt, Ident("u8"), Shr];
+    ///
+    /// // A `>` that closes a generic argument list may be the first half of a `>>`
+    /// let close = custom::<_, &[Token], _, extra::Err<Simple<Token>>>(|inp| {
+    ///     let before = inp.offset();
+    ///     match inp.next() {
+    ///         Some(Gt) => Ok(()),
+    ///         Some(Shr) => {
+    ///             // Consume the first half of the `>>`, leaving the second half for the next parser
+    ///             inp.push_back(Gt);
+    ///             Ok(())
+    ///         }
+    ///         found => {
+    ///             let span = inp.span_since(before);
+    ///             Err(Error::<&[Token]>::expected_found(None, found.map(Into::into), span))
+    ///         }
+    ///     }
+    /// });
+    ///
+    /// let ty = recursive(|ty| {
+    ///     select! { Ident(name) => name.to_string() }
+    ///         .then(ty.delimited_by(just(Lt), close).or_not())
+    ///         .map(|(name, arg)| match arg {
+    ///             Some(arg) => format!("{name}[{arg}]"),
+    ///             None => name,
+    ///         })
+    /// });
+    ///
+    /// assert_eq!(ty.parse(&tokens).into_result(), Ok("Vec[Vec[u8]]".to_string()));
+    /// ```
+    #[inline]
+    pub fn push_back(&mut self, tok: I::Token)
+    where
+        I::Token: Clone,
+    {
+        self.pushed.push(PushedToken {
+            tok,
+            clone: I::Token::clone,
+            below: self.pending,
+        });
+        self.pending = Some(self.pushed.len() - 1);
+    }
+
     #[cfg_attr(not(feature = "regex"), allow(dead_code))]
     #[inline]
     pub(crate) fn full_slice(&self) -> I::Slice
@@ -1361,12 +3125,22 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
         self.slice_from_inner(range.start.offset..)
     }
 
+    /// Get a slice of the input that extends from the provided [`Offset`] to the current input position.
+    #[inline]
+    pub fn slice_since(&self, before: Offset<'a, 'parse, I>) -> I::Slice
+    where
+        I: SliceInput<'a>,
+    {
+        self.slice_inner(before.offset..self.offset)
+    }
+
     // TODO: Unofy with `InputRef::slice`
     #[inline(always)]
     pub(crate) fn slice_inner(&self, range: Range<I::Offset>) -> I::Slice
     where
         I: SliceInput<'a>,
     {
+        debug_assert!(range.start <= range.end, "slice ends before it starts");
         self.input.slice(range)
     }
 
@@ -1379,7 +3153,6 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
         self.input.slice_from(range)
     }
 
-    #[cfg_attr(not(feature = "lexical-numbers"), allow(dead_code))]
     #[inline(always)]
     pub(crate) fn slice_trailing_inner(&self) -> I::Slice
     where
@@ -1417,7 +3190,12 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
     }
 
     #[inline(always)]
-    #[cfg(any(feature = "regex", feature = "lexical-numbers"))]
+    pub(crate) fn span_inner(&self, range: Range<I::Offset>) -> I::Span {
+        // SAFETY: offsets generated by previous call to `InputRef::next` (or similar).
+        unsafe { self.input.span(range) }
+    }
+
+    #[inline(always)]
     pub(crate) fn skip_bytes(&mut self, skip: usize)
     where
         I: SliceInput<'a, Offset = usize>,
@@ -1425,11 +3203,64 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
         self.offset += skip;
     }
 
+    /// Record the error explaining why the current parser failed at the given offset, for use by parsers that
+    /// implement [`ParserSealed::go`] directly.
+    ///
+    /// If other alternatives also failed, the error from the one that got furthest through the input is reported, and
+    /// errors at the same offset are merged.
+    #[cfg(feature = "unstable")]
+    #[inline]
+    pub fn add_error(&mut self, at: Offset<'a, 'parse, I>, error: E::Error) {
+        self.add_alt_err(at.offset, error);
+    }
+
+    /// Emit a secondary error at the given offset, which is reported even if parsing succeeds, for use by parsers that
+    /// implement [`ParserSealed::go`] directly.
+    #[cfg(feature = "unstable")]
+    #[inline]
+    pub fn emit_error(&mut self, at: Offset<'a, 'parse, I>, error: E::Error) {
+        self.emit(at.offset, error);
+    }
+
     #[inline]
     pub(crate) fn emit(&mut self, pos: I::Offset, error: E::Error) {
         self.errors.secondary.push(Located::at(pos, error));
     }
 
+    /// Emit the error that caused a successful error recovery, recording that the recovery skipped the given range of
+    /// input.
+    ///
+    /// The skipped tokens are counted as they were read by the parser: pushed tokens that were pending at the start of
+    /// the range and consumed within it are counted, while trivia are not.
+    #[inline]
+    pub(crate) fn emit_recovered(
+        &mut self,
+        skipped: Range<Offset<'a, 'parse, I>>,
+        error: E::Error,
+    ) {
+        let mut count = 0;
+        let mut pending = skipped.start.pending;
+        while let Some(idx) = pending.filter(|_| pending != skipped.end.pending) {
+            pending = self.pushed[idx].below;
+            count += 1;
+        }
+        let mut offset = skipped.start.offset;
+        while offset < skipped.end.offset {
+            // SAFETY: offset was generated by previous call to `Input::next`
+            let (next, tok) = unsafe { self.input.next_maybe(offset) };
+            if !matches!((self.trivia, tok), (Some(trivia), Some(tok)) if trivia(tok.borrow())) {
+                count += 1;
+            }
+            offset = next;
+        }
+        self.errors
+            .recoveries
+            .push((self.errors.secondary.len(), count));
+        #[cfg(feature = "telemetry")]
+        self.count(|stats| stats.recoveries += 1);
+        self.emit(self.offset, error);
+    }
+
     #[inline]
     pub(crate) fn add_alt<Exp: IntoIterator<Item = Option<MaybeRef<'a, I::Token>>>>(
         &mut self,
@@ -1438,6 +3269,11 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
         found: Option<MaybeRef<'a, I::Token>>,
         span: I::Span,
     ) {
+        self.forget_undecodable(at);
+        if matches!(&self.errors.expecting, Some(expecting) if expecting.offset == at.into()) {
+            return self.add_alt_err(at, Error::expected_found(expected, found, span));
+        }
+
         // Prioritize errors before choosing whether to generate the alt (avoids unnecessary error creation)
         self.errors.alt = Some(match self.errors.alt.take() {
             Some(alt) => match alt.pos.into().cmp(&at.into()) {
@@ -1455,18 +3291,86 @@ impl<'a, 'parse, I: Input<'a>, E: ParserExtra<'a, I>> InputRef<'a, 'parse, I, E>
 
     #[inline]
     pub(crate) fn add_alt_err(&mut self, at: I::Offset, err: E::Error) {
-        // Prioritize errors
+        self.forget_undecodable(at);
+        if let Some(expecting) = &mut self.errors.expecting {
+            if expecting.offset == at.into() {
+                let err = (expecting.clone)(&err);
+                expecting.err = Some(match expecting.err.take() {
+                    Some(old) => old.merge(err),
+                    None => err,
+                });
+            }
+        }
+        self.merge_alt(Located::at(at, err));
+    }
+
+    // Prioritize an error against the current alt error, without recording it as an expectation
+    #[inline]
+    pub(crate) fn merge_alt(&mut self, new: Located<I::Offset, E::Error>) {
         self.errors.alt = Some(match self.errors.alt.take() {
-            Some(alt) => match alt.pos.into().cmp(&at.into()) {
-                Ordering::Equal => Located::at(alt.pos, alt.err.merge(err)),
+            Some(alt) => match alt.pos.into().cmp(&new.pos.into()) {
+                Ordering::Equal => Located::at(alt.pos, alt.err.merge(new.err)),
                 Ordering::Greater => alt,
-                Ordering::Less => Located::at(at, err),
+                Ordering::Less => new,
             },
-            None => Located::at(at, err),
+            None => new,
         });
     }
 }
 
+/// Metadata about a successfully parsed pattern, passed to the closure in [`Parser::map_with`].
+pub struct MapExtra<'a, 'b, I: Input<'a>, E: ParserExtra<'a, I>> {
+    before: I::Offset,
+    after: I::Offset,
+    input: &'b I,
+    state: &'b mut E::State,
+    ctx: &'b E::Context,
+}
+
+impl<'a, 'b, I: Input<'a>, E: ParserExtra<'a, I>> MapExtra<'a, 'b, I, E> {
+    #[inline(always)]
+    pub(crate) fn new<'parse>(
+        before: Offset<'a, 'parse, I>,
+        inp: &'b mut InputRef<'a, 'parse, I, E>,
+    ) -> Self {
+        MapExtra {
+            before: before.offset,
+            after: inp.offset,
+            input: inp.input,
+            state: inp.state,
+            ctx: inp.ctx,
+        }
+    }
+
+    /// Get the span covering the pattern that was parsed.
+    #[inline(always)]
+    pub fn span(&self) -> I::Span {
+        // SAFETY: offsets generated by previous call to `InputRef::next` (or similar).
+        unsafe { self.input.span(self.before..self.after) }
+    }
+
+    /// Get the slice of the input covering the pattern that was parsed.
+    #[inline(always)]
+    pub fn slice(&self) -> I::Slice
+    where
+        I: SliceInput<'a>,
+    {
+        self.input.slice(self.before..self.after)
+    }
+
+    /// Get a mutable reference to the state associated with the current parse.
+    #[inline(always)]
+    pub fn state(&mut self) -> &mut E::State {
+        self.state
+    }
+
+    /// Get a reference to the context fed to the current parser.
+    #[inline(always)]
+    pub fn ctx(&self) -> &E::Context {
+        self.ctx
+    }
+}
+
 /// Struct used in [`Parser::validate`] to collect user-emitted errors
 pub struct Emitter<E> {
     emitted: Vec<E>,
@@ -0,0 +1,105 @@
+//! String interning, for parsers that produce many copies of the same identifiers.
+//!
+//! Identifier-heavy sources tend to mention the same few names over and over. Rather than allocating a new [`String`]
+//! for every occurrence, [`Parser::map_intern`] stores each distinct string once in an [`Interner`] held in the parser
+//! state, and outputs a small, [`Copy`] [`Symbol`] handle that can be compared and hashed cheaply.
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::prelude::*;
+//! use chumsky::intern::Interner;
+//!
+//! let idents = text::ascii::ident::<_, _, extra::State<Interner>>()
+//!     .map_intern()
+//!     .padded()
+//!     .repeated()
+//!     .collect::<Vec<_>>();
+//!
+//! let mut interner = Interner::new();
+//! let syms = idents.parse_with_state("foo bar foo", &mut interner).into_result().unwrap();
+//!
+//! assert_eq!(syms[0], syms[2]);
+//! assert_ne!(syms[0], syms[1]);
+//! assert_eq!(interner.resolve(syms[1]), "bar");
+//! assert_eq!(interner.len(), 2);
+//! ```
+
+use super::*;
+
+/// A handle to a string stored in an [`Interner`].
+///
+/// Symbols from the same interner are equal if and only if the strings they refer to are equal.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// The index of this symbol within its interner. Symbols are numbered in the order their strings were first
+    /// interned, starting at zero.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A simple string interner. See the [module-level documentation](self) for more information.
+///
+/// To use an interner with [`Parser::map_intern`], use it (or a type that implements
+/// [`BorrowMut<Interner>`](core::borrow::BorrowMut)) as the parser state.
+#[derive(Clone, Debug, Default)]
+pub struct Interner {
+    // Each string is allocated once, and shared between the map and the list
+    symbols: HashMap<RefC<str>, Symbol>,
+    strings: Vec<RefC<str>>,
+}
+
+impl Interner {
+    /// Create a new, empty interner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the symbol for the given string, storing the string if it has not been interned before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than `u32::MAX` distinct strings are interned.
+    pub fn intern(&mut self, s: &str) -> Symbol {
+        if let Some(sym) = self.symbols.get(s) {
+            return *sym;
+        }
+        let sym = Symbol(
+            self.strings
+                .len()
+                .try_into()
+                .expect("too many interned strings"),
+        );
+        let s = RefC::<str>::from(s);
+        self.symbols.insert(s.clone(), sym);
+        self.strings.push(s);
+        sym
+    }
+
+    /// Get the symbol for the given string, if it has been interned.
+    pub fn get(&self, s: &str) -> Option<Symbol> {
+        self.symbols.get(s).copied()
+    }
+
+    /// Get the string that the given symbol refers to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the symbol was produced by a different interner.
+    pub fn resolve(&self, sym: Symbol) -> &str {
+        &self.strings[sym.index()]
+    }
+
+    /// The number of distinct strings that have been interned.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Whether no strings have been interned.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
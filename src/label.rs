@@ -1,6 +1,7 @@
 //! Items related to parser labelling.
 
 use super::*;
+use crate::input::Marker;
 
 /// A trait implemented by [`Error`]s that can originate from labelled parsers. See [`Parser::labelled`].
 pub trait LabelError<'a, I: Input<'a>, L>: Error<'a, I> {
@@ -69,16 +70,77 @@ where
         }
 
         if self.is_context {
-            let before_offset = before.offset;
-            for err in inp.errors.secondary_errors_since(before.err_count) {
-                // SAFETY: offsets generated by previous call to `InputRef::next` (or similar).
-                let span = unsafe { inp.input.span(before_offset..err.pos) };
-                err.err.in_context(self.label.clone(), span);
-            }
+            secondary_in_context(inp, before, &self.label);
+        }
+
+        res
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::context`].
+#[derive(Copy, Clone)]
+pub struct InContext<A, L> {
+    pub(crate) parser: A,
+    pub(crate) label: L,
+}
+
+impl<'a, I, O, E, A, L> ParserSealed<'a, I, O, E> for InContext<A, L>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+    L: Clone,
+    E::Error: LabelError<'a, I, L>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let old_alt = inp.errors.alt.take();
+        let before = inp.save();
+        let res = self.parser.go::<M>(inp);
+
+        let new_alt = inp.errors.alt.take();
+        inp.errors.alt = old_alt;
+
+        // Unlike `Labelled`, errors at the very start of the context are not relabelled, so they get the context too
+        if let Some(mut new_alt) = new_alt {
+            // SAFETY: offsets generated by previous call to `InputRef::next` (or similar).
+            let span = unsafe { inp.input.span(before.offset..new_alt.pos) };
+            new_alt.err.in_context(self.label.clone(), span);
+            inp.add_alt_err(new_alt.pos, new_alt.err);
         }
 
+        secondary_in_context(inp, before, &self.label);
+
         res
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
+
+fn secondary_in_context<'a, 'parse, I, E, L>(
+    inp: &mut InputRef<'a, 'parse, I, E>,
+    before: Marker<'a, 'parse, I, E>,
+    label: &L,
+) where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    L: Clone,
+    E::Error: LabelError<'a, I, L>,
+{
+    let before_offset = before.offset;
+    for err in inp.errors.secondary_errors_since(before.err_count) {
+        // SAFETY: offsets generated by previous call to `InputRef::next` (or similar).
+        let span = unsafe { inp.input.span(before_offset..err.pos) };
+        err.err.in_context(label.clone(), span);
+    }
+}
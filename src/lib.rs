@@ -71,6 +71,8 @@ macro_rules! go_cfg_extra {
 mod blanket;
 pub mod combinator;
 pub mod container;
+#[cfg(feature = "macros")]
+pub mod derive;
 #[cfg(feature = "either")]
 pub mod either;
 pub mod error;
@@ -80,22 +82,132 @@ pub mod extra;
 #[cfg(docsrs)]
 pub mod guide;
 pub mod input;
+pub mod intern;
 #[cfg(feature = "label")]
 pub mod label;
 #[cfg(feature = "lexical-numbers")]
 pub mod number;
+#[cfg(feature = "parallel")]
+pub mod parallel;
 #[cfg(feature = "pratt")]
 pub mod pratt;
 pub mod primitive;
 mod private;
+pub mod push;
+#[cfg(feature = "recipes")]
+pub mod recipes;
 pub mod recovery;
 pub mod recursive;
 #[cfg(feature = "regex")]
 pub mod regex;
+pub mod relex;
 pub mod span;
 mod stream;
+#[cfg(feature = "telemetry")]
+pub mod telemetry;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
 pub mod text;
+#[cfg(feature = "debug-replay")]
+pub mod trace;
+#[cfg(feature = "unstable")]
+pub mod unparse;
 pub mod util;
+pub mod walk;
+
+/// Write a set of parsers as a grammar of rules, in a BNF-like syntax.
+///
+/// The macro takes a list of rules of the form `name: Type = pattern;`, where `Type` is the output type of the
+/// rule. Each rule becomes a [`Recursive`] parser that is declared before any rule is defined, so rules may refer to
+/// one another (and to themselves) regardless of the order in which they appear. The macro evaluates to the first
+/// rule.
+///
+/// The grammar may begin with `type Input = ...;` and `type Extra = ...;` items that give the input type and the
+/// [`extra::ParserExtra`] of every rule. Either may be omitted, in which case it is inferred from context (usually
+/// the return type of the function that contains the grammar). Giving the input type is necessary when actions call
+/// methods on values whose type depends on it, such as the output of `$x`.
+///
+/// Patterns are built from the following elements:
+///
+/// | Syntax             | Meaning                                                       | Equivalent to                    |
+/// |--------------------|---------------------------------------------------------------|----------------------------------|
+/// | `"foo"`, `'c'`     | A specific input                                              | [`just`]                         |
+/// | `'a'..='z'`        | Any input in the range                                        | [`one_of`]                       |
+/// | `name`             | Another rule                                                  |                                  |
+/// | `{ expr }`         | An arbitrary parser expression                                |                                  |
+/// | `( pattern )`      | A parenthesised pattern                                       |                                  |
+/// | `a b c`            | A sequence, producing a tuple of outputs                      | [`group`]                        |
+/// | `a \| b`           | The first of several alternatives that succeeds               | [`choice`]                       |
+/// | `x*`, `x+`         | Zero or more (or one or more) repetitions, collected into a `Vec` | [`Parser::repeated`]         |
+/// | `x?`               | An optional element                                           | [`Parser::or_not`]               |
+/// | `$x`               | The slice of the input that the element covers                | [`Parser::slice`]                |
+/// | `!x`               | Succeed, consuming nothing, only if the element fails         | [`Parser::not`]                  |
+///
+/// Elements of a sequence may be bound to a name with `name: element`. A sequence may end with an action,
+/// `=> { ... }`, that computes the output of the sequence from the bound names. A sequence with bindings and no
+/// action produces the bound values (as a tuple, if there is more than one).
+///
+/// Referring to a rule that does not exist is a compile-time error:
+///
+/// ```compile_fail
+/// # use chumsky::prelude::*;
+/// fn digits<'a>() -> impl Parser<'a, &'a str, Vec<char>> {
+///     chumsky::grammar! {
+///         digits: Vec<char> = digit+;
+///         // Error: no rule named `digit`
+///         digti: char = '0'..='9';
+///     }
+/// }
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// fn calc<'a>() -> impl Parser<'a, &'a str, i64> {
+///     chumsky::grammar! {
+///         type Input = &'a str;
+///
+///         expr: i64 = first:term rest:(op:('+' | '-') rhs:term)* => {
+///             rest.into_iter().fold(first, |lhs, (op, rhs)| if op == '+' { lhs + rhs } else { lhs - rhs })
+///         };
+///         term: i64 = first:atom rest:('*' rhs:atom)* => {
+///             rest.into_iter().fold(first, |lhs, rhs| lhs * rhs)
+///         };
+///         atom: i64 = digits:$('0'..='9')+ => { digits.parse().unwrap() }
+///             | '(' inner:expr ')';
+///     }
+/// }
+///
+/// assert_eq!(calc().parse("2*(3+4)-5").into_result(), Ok(9));
+/// assert!(calc().parse("2*(3+4").has_errors());
+/// ```
+///
+/// Existing parsers can be embedded in braces, which is a convenient way to handle whitespace:
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// fn list<'a>() -> impl Parser<'a, &'a str, Vec<&'a str>> {
+///     chumsky::grammar! {
+///         list: Vec<&'a str> = '[' { text::whitespace() } items:items? ']' => { items.unwrap_or_default() };
+///         items: Vec<&'a str> = first:word rest:(',' { text::whitespace() } word:word)* => {
+///             core::iter::once(first).chain(rest).collect()
+///         };
+///         word: &'a str = w:{ text::ascii::ident() } { text::whitespace() };
+///     }
+/// }
+///
+/// assert_eq!(list().parse("[foo, bar , baz]").into_result(), Ok(vec!["foo", "bar", "baz"]));
+/// assert_eq!(list().parse("[ ]").into_result(), Ok(vec![]));
+/// ```
+#[cfg(feature = "macros")]
+pub use chumsky_macros::grammar;
+
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::vec::Vec;
+}
 
 /// Commonly used functions, traits and types.
 ///
@@ -112,28 +224,38 @@ pub mod prelude {
         error::{Cheap, EmptyErr, Error as _, Rich, Simple},
         extra,
         input::Input,
-        primitive::{any, choice, custom, empty, end, group, just, map_ctx, none_of, one_of, todo},
+        primitive::{
+            any, balanced, choice, custom, empty, end, group, just, just_bytes, just_ctx, kind_of,
+            map_ctx, none_of, not_just, one_of, select_window, significant, todo,
+        },
         recovery::{nested_delimiters, skip_then_retry_until, skip_until, via_parser},
         recursive::{recursive, Recursive},
         span::{SimpleSpan, Span as _},
-        text, Boxed, ConfigIterParser, ConfigParser, IterParser, ParseResult, Parser,
+        text, Boxed, ConfigIterParser, ConfigParser, DynParser, IterParser, ParseResult, Parser,
     };
-    pub use crate::{select, select_ref};
+    pub use crate::{choice_map, group_ignoring, select, select_ref};
 }
 
-use crate::input::InputOwn;
-use alloc::{boxed::Box, rc::Rc, string::String, sync::Arc, vec, vec::Vec};
+use crate::input::{InputOwn, OutlineNode};
+use alloc::{
+    boxed::Box,
+    rc::Rc,
+    string::{String, ToString},
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
 #[cfg(feature = "nightly")]
 use core::marker::Tuple;
 use core::{
-    borrow::Borrow,
+    borrow::{Borrow, BorrowMut},
     cell::{Cell, RefCell, UnsafeCell},
     cmp::{Eq, Ordering},
     fmt,
     hash::Hash,
     marker::PhantomData,
     mem::MaybeUninit,
-    ops::{Range, RangeFrom},
+    ops::{ControlFlow, Range, RangeFrom},
     panic::Location,
     str::FromStr,
 };
@@ -142,23 +264,26 @@ use hashbrown::HashMap;
 use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
 
 #[cfg(feature = "label")]
-use self::label::{LabelError, Labelled};
+use self::label::{InContext, LabelError, Labelled};
 use self::{
     combinator::*,
     container::*,
-    error::Error,
+    error::{Error, SeqKind, SuggestionError},
     extra::ParserExtra,
-    input::{BorrowInput, Emitter, ExactSizeInput, InputRef, SliceInput, StrInput, ValueInput},
+    input::{
+        BorrowInput, Emitter, ExactSizeInput, InputRef, MapExtra, SliceInput, StrInput, ValueInput,
+    },
     prelude::*,
     primitive::Any,
     private::{
         Check, ConfigIterParserSealed, ConfigParserSealed, Emit, IPResult, IterParserSealed,
         Located, MaybeUninitExt, Mode, PResult, ParserSealed, Sealed,
     },
-    recovery::{RecoverWith, Strategy},
+    recovery::{RecoverWith, RecoveryStats, Strategy},
     span::Span,
     text::*,
     util::{MaybeMut, MaybeRef},
+    walk::Walker,
 };
 #[cfg(all(feature = "extension", doc))]
 use self::{extension::v1::*, primitive::custom, stream::Stream};
@@ -193,7 +318,7 @@ mod sync {
 
     pub(crate) type RefC<T> = alloc::sync::Arc<T>;
     pub(crate) type RefW<T> = alloc::sync::Weak<T>;
-    pub(crate) type DynParser<'a, 'b, I, O, E> = dyn Parser<'a, I, O, E> + Send + Sync + 'b;
+    pub(crate) type ParserObj<'a, 'b, I, O, E> = dyn Parser<'a, I, O, E> + Send + Sync + 'b;
 
     /// A trait that requires either nothing or `Send` and `Sync` bounds depending on whether the `sync` feature is
     /// enabled. Used to constrain API usage succinctly and easily.
@@ -207,7 +332,7 @@ mod sync {
 
     pub(crate) type RefC<T> = alloc::rc::Rc<T>;
     pub(crate) type RefW<T> = alloc::rc::Weak<T>;
-    pub(crate) type DynParser<'a, 'b, I, O, E> = dyn Parser<'a, I, O, E> + 'b;
+    pub(crate) type ParserObj<'a, 'b, I, O, E> = dyn Parser<'a, I, O, E> + 'b;
 
     /// A trait that requires either nothing or `Send` and `Sync` bounds depending on whether the `sync` feature is
     /// enabled. Used to constrain API usage succinctly and easily.
@@ -215,7 +340,7 @@ mod sync {
     impl<T> MaybeSync for T {}
 }
 
-use sync::{DynParser, MaybeSync, RefC, RefW};
+use sync::{MaybeSync, ParserObj, RefC, RefW};
 
 /// The result of running a [`Parser`]. Can be converted into a [`Result`] via
 /// [`ParseResult::into_result`] for when you only care about success or failure, or into distinct
@@ -224,11 +349,35 @@ use sync::{DynParser, MaybeSync, RefC, RefW};
 pub struct ParseResult<T, E> {
     output: Option<T>,
     errs: Vec<E>,
+    recovery: RecoveryStats,
+    incomplete: bool,
 }
 
 impl<T, E> ParseResult<T, E> {
     pub(crate) fn new(output: Option<T>, errs: Vec<E>) -> ParseResult<T, E> {
-        ParseResult { output, errs }
+        ParseResult {
+            output,
+            errs,
+            recovery: RecoveryStats::default(),
+            incomplete: false,
+        }
+    }
+
+    pub(crate) fn with_recovery(self, recovery: RecoveryStats) -> Self {
+        ParseResult { recovery, ..self }
+    }
+
+    pub(crate) fn with_incomplete(self, incomplete: bool) -> Self {
+        ParseResult { incomplete, ..self }
+    }
+
+    pub(crate) fn map_output<U>(self, f: impl FnOnce(T) -> U) -> ParseResult<U, E> {
+        ParseResult {
+            output: self.output.map(f),
+            errs: self.errs,
+            recovery: self.recovery,
+            incomplete: self.incomplete,
+        }
     }
 
     /// Whether this result contains output
@@ -241,12 +390,74 @@ impl<T, E> ParseResult<T, E> {
         !self.errs.is_empty()
     }
 
+    /// Statistics about the error recovery performed while producing this result.
+    ///
+    /// A result may contain errors without any recovery having taken place (for example, errors emitted by
+    /// [`Parser::validate`]), so this is useful for telling apart outputs that reflect the input faithfully from those
+    /// that were patched up by a recovery strategy.
+    pub fn recovery(&self) -> RecoveryStats {
+        self.recovery
+    }
+
+    /// Whether this result contains output that was produced without any errors or error recovery.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let abc = just::<_, _, extra::Err<Simple<char>>>("abc")
+    ///     .recover_with(via_parser(any().repeated().at_least(1).slice()));
+    ///
+    /// assert!(abc.parse("abc").is_fully_valid());
+    ///
+    /// let res = abc.parse("abx");
+    /// assert!(res.has_output() && !res.is_fully_valid());
+    /// assert_eq!(res.recovery().recoveries(), 1);
+    /// assert_eq!(res.recovery().skipped_tokens(), 3);
+    /// ```
+    pub fn is_fully_valid(&self) -> bool {
+        self.output.is_some() && self.errs.is_empty() && self.recovery.recoveries == 0
+    }
+
+    /// Whether every error in this result occurred at the end of the input, meaning that the input is invalid only
+    /// because it ended too soon.
+    ///
+    /// This is useful for REPLs, which can prompt for a continuation line when the input so far is incomplete rather
+    /// than reporting an error. Only the errors that are eventually reported count: an input is still incomplete if
+    /// some alternatives of a [`choice`] or [`Parser::or`] failed earlier in the input, provided that the error that
+    /// was reported lies at the end. A result without errors is never incomplete.
+    ///
+    /// To parse input that arrives a piece at a time more generally, see the [`push`] module.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let sum = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .separated_by(just('+').padded())
+    ///     .at_least(1)
+    ///     .collect::<Vec<_>>()
+    ///     .then_ignore(just(';'));
+    ///
+    /// assert!(sum.parse("1 + 2").is_incomplete());
+    /// assert!(sum.parse("1 + ").is_incomplete());
+    /// // Invalid input, which no amount of further input would make valid
+    /// assert!(!sum.parse("1 + +").is_incomplete());
+    /// // Valid input
+    /// assert!(!sum.parse("1 + 2;").is_incomplete());
+    /// ```
+    pub fn is_incomplete(&self) -> bool {
+        self.incomplete
+    }
+
     /// Get a reference to the output of this result, if it exists
     pub fn output(&self) -> Option<&T> {
         self.output.as_ref()
     }
 
     /// Get a slice containing the parse errors for this result. The slice will be empty if there are no errors.
+    ///
+    /// Errors are ordered by the position in the input at which they were produced (see [`Parser::parse`]).
     pub fn errors(&self) -> impl ExactSizeIterator<Item = &E> {
         self.errs.iter()
     }
@@ -297,6 +508,89 @@ impl<T, E> ParseResult<T, E> {
     }
 }
 
+/// The result of [`Parser::parse_retry_with`]: the result of parsing the original input and, if the input was
+/// transformed and parsed again, the result of the retry.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RetryResult<T, E> {
+    first: ParseResult<T, E>,
+    retry: Option<ParseResult<T, E>>,
+}
+
+impl<T, E> RetryResult<T, E> {
+    /// Get the result of parsing the original input.
+    pub fn first(&self) -> &ParseResult<T, E> {
+        &self.first
+    }
+
+    /// Get the result of parsing the transformed input, if the original input failed to parse and was transformed.
+    ///
+    /// Its spans are spans of the transformed input, not the original.
+    pub fn retry(&self) -> Option<&ParseResult<T, E>> {
+        self.retry.as_ref()
+    }
+
+    /// Whether the transformed input was parsed.
+    pub fn was_retried(&self) -> bool {
+        self.retry.is_some()
+    }
+
+    /// Convert this `RetryResult` into the result of the last parse: the retry if there was one, and otherwise the
+    /// parse of the original input. Use [`RetryResult::was_retried`] to tell which input its spans refer to.
+    pub fn into_last(self) -> ParseResult<T, E> {
+        self.retry.unwrap_or(self.first)
+    }
+
+    /// Convert this `RetryResult` into a tuple of the result of parsing the original input and the result of the
+    /// retry, if there was one.
+    pub fn into_parts(self) -> (ParseResult<T, E>, Option<ParseResult<T, E>>) {
+        (self.first, self.retry)
+    }
+}
+
+/// The span of a [node](Parser::node) in the input, along with the spans of the nodes nested within it. See
+/// [`Parser::parse_spans`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SpanTree<S> {
+    span: S,
+    children: Vec<SpanTree<S>>,
+}
+
+impl<S> SpanTree<S> {
+    /// Get the span of this node.
+    pub fn span(&self) -> &S {
+        &self.span
+    }
+
+    /// Get the nodes that were parsed within this node, in the order they occur in the input.
+    pub fn children(&self) -> &[SpanTree<S>] {
+        &self.children
+    }
+
+    /// Convert this tree into the span of its root and its children.
+    pub fn into_parts(self) -> (S, Vec<SpanTree<S>>) {
+        (self.span, self.children)
+    }
+
+    /// Build the trees described by an outline, as recorded by [`Parser::parse_spans`].
+    fn from_outline<'a, I: Input<'a, Span = S>>(
+        input: &I,
+        outline: &[OutlineNode<I::Offset>],
+    ) -> Vec<Self> {
+        let mut trees = Vec::new();
+        let mut i = 0;
+        while let Some(node) = outline.get(i) {
+            let end = i + 1 + node.descendants;
+            trees.push(SpanTree {
+                // SAFETY: offsets were generated by the input while parsing
+                span: unsafe { input.span(node.start..node.end) },
+                children: Self::from_outline(input, &outline[i + 1..end]),
+            });
+            i = end;
+        }
+        trees
+    }
+}
+
 /// A trait implemented by parsers.
 ///
 /// Parsers take inputs of type `I`, which will implement [`Input`]. Refer to the documentation on [`Input`] for examples
@@ -341,6 +635,8 @@ pub trait Parser<'a, I: Input<'a>, O, E: ParserExtra<'a, I> = extra::Default>:
     /// Parse a stream of tokens, yielding an output if possible, and any errors encountered along the way.
     ///
     /// If `None` is returned (i.e: parsing failed) then there will *always* be at least one item in the error `Vec`.
+    /// Errors are ordered by the position in the input at which they were produced, and errors produced at the same
+    /// position are kept in the order they were produced in, so the result is stable from one run to the next.
     /// If you want to include non-default state, use [`Parser::parse_with_state`] instead.
     ///
     /// Although the signature of this function looks complicated, it's simpler than you think! You can pass a
@@ -369,2102 +665,2305 @@ pub trait Parser<'a, I: Input<'a>, O, E: ParserExtra<'a, I> = extra::Default>:
         I: Input<'a>,
         E::Context: Default,
     {
-        let mut own = InputOwn::new_state(input, state);
-        let mut inp = own.as_ref_start();
-        let res = self.then_ignore(end()).go::<Emit>(&mut inp);
-        let alt = inp.errors.alt.take();
-        let mut errs = own.into_errs();
-        let out = match res {
-            Ok(out) => Some(out),
-            Err(()) => {
-                errs.push(alt.expect("error but no alt?").err);
-                None
-            }
-        };
-        ParseResult::new(out, errs)
+        InputOwn::new_state(input, state).parse_with(self)
     }
 
-    /// Parse a stream of tokens, ignoring any output, and returning any errors encountered along the way.
+    /// Parse many independent inputs, yielding a [`ParseResult`] for each of them, in order.
     ///
-    /// If parsing failed, then there will *always* be at least one item in the returned `Vec`.
-    /// If you want to include non-default state, use [`Parser::check_with_state`] instead.
+    /// This is equivalent to calling [`Parser::parse`] on each input in turn, except that the buffers used while
+    /// parsing (such as the tables of [`Parser::memoized`]) are reused from one input to the next. This saves time
+    /// when parsing thousands of small inputs, such as the files of a test suite. Each input is parsed with a fresh
+    /// default state: use [`Parser::parse_all_with_state`] to share state, such as an interner, between the inputs.
+    /// With the `parallel` feature, `parallel::parse_all_parallel` spreads the inputs across several threads.
     ///
-    /// Although the signature of this function looks complicated, it's simpler than you think! You can pass a
-    /// [`&[T]`], a [`&str`], [`Stream`], or anything implementing [`Input`] to it.
-    fn check(&self, input: I) -> ParseResult<(), E::Error>
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10).from_str::<u32>().unwrapped();
+    ///
+    /// let results = int.parse_all(["1", "x", "42"]);
+    /// assert_eq!(results[0].output(), Some(&1));
+    /// assert!(results[1].has_errors());
+    /// assert_eq!(results[2].output(), Some(&42));
+    /// ```
+    fn parse_all<It>(&self, inputs: It) -> Vec<ParseResult<O, E::Error>>
     where
         Self: Sized,
         I: Input<'a>,
+        It: IntoIterator<Item = I>,
         E::State: Default,
         E::Context: Default,
     {
-        self.check_with_state(input, &mut E::State::default())
+        let mut inputs = inputs.into_iter();
+        let mut own = match inputs.next() {
+            Some(input) => InputOwn::<I, E>::new(input),
+            None => return Vec::new(),
+        };
+        let mut results = vec![own.parse_with(self)];
+        for input in inputs {
+            own.reset(input);
+            own.state = MaybeMut::Val(E::State::default());
+            results.push(own.parse_with(self));
+        }
+        results
     }
 
-    /// Parse a stream of tokens, ignoring any output, and returning any errors encountered along the way.
+    /// Parse many independent inputs like [`Parser::parse_all`], sharing the provided state between all of them.
     ///
-    /// If parsing failed, then there will *always* be at least one item in the returned `Vec`.
-    /// If you want to just use a default state value, use [`Parser::check`] instead.
+    /// The inputs are parsed in order, so each sees the state as the previous one left it. This makes it possible
+    /// to intern the identifiers of every input into a single interner, for example.
     ///
-    /// Although the signature of this function looks complicated, it's simpler than you think! You can pass a
-    /// [`&[T]`], a [`&str`], [`Stream`], or anything implementing [`Input`] to it.
-    fn check_with_state(&self, input: I, state: &mut E::State) -> ParseResult<(), E::Error>
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let word = text::ascii::ident::<_, _, extra::Full<EmptyErr, Vec<String>, ()>>()
+    ///     .map_with(|word: &str, e| e.state().push(word.to_string()))
+    ///     .padded()
+    ///     .repeated()
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let mut seen = Vec::new();
+    /// let results = word.parse_all_with_state(["a b", "c"], &mut seen);
+    /// assert!(results.iter().all(|res| !res.has_errors()));
+    /// assert_eq!(seen, ["a", "b", "c"]);
+    /// ```
+    fn parse_all_with_state<It>(
+        &self,
+        inputs: It,
+        state: &mut E::State,
+    ) -> Vec<ParseResult<O, E::Error>>
     where
         Self: Sized,
         I: Input<'a>,
+        It: IntoIterator<Item = I>,
         E::Context: Default,
     {
-        let mut own = InputOwn::new_state(input, state);
-        let mut inp = own.as_ref_start();
-        let res = self.then_ignore(end()).go::<Check>(&mut inp);
-        let alt = inp.errors.alt.take();
-        let mut errs = own.into_errs();
-        let out = match res {
-            Ok(()) => Some(()),
-            Err(()) => {
-                errs.push(alt.expect("error but no alt?").err);
-                None
-            }
+        let mut inputs = inputs.into_iter();
+        let mut own = match inputs.next() {
+            Some(input) => InputOwn::<I, E>::new_state(input, state),
+            None => return Vec::new(),
         };
-        ParseResult::new(out, errs)
+        let mut results = vec![own.parse_with(self)];
+        for input in inputs {
+            own.reset(input);
+            results.push(own.parse_with(self));
+        }
+        results
     }
 
-    /// Map from a slice of the input based on the current parser's span to a value.
+    /// Parse a stream of tokens like [`Parser::parse`], retrying with a transformed input if parsing produces errors.
     ///
-    /// The returned value may borrow data from the input slice, making this function very useful
-    /// for creating zero-copy AST output values
-    fn map_slice<U, F: Fn(I::Slice) -> U>(self, f: F) -> MapSlice<'a, Self, I, O, E, F, U>
+    /// If parsing the input produces any errors, `transform` is given the original input and may return a different
+    /// input to parse instead, such as one with a byte order mark stripped or typographic quotes replaced by plain ones.
+    /// Returning `None` skips the retry. The results of both attempts are kept in the returned [`RetryResult`], so
+    /// that the errors for the original input are still available if the retry fails too. This makes it possible to
+    /// accept messy real-world input on a best-effort basis without making the grammar itself more lenient.
+    ///
+    /// Since outputs may borrow from the input, the transformed input must live as long as the original input. When
+    /// the transformation produces an owned value, such as a [`String`], store it outside the closure.
+    ///
+    /// The spans produced by the retry, including those of its errors, are spans of the transformed input. They only
+    /// point at the same places in the original input if the transformation leaves every token at the same offset, so
+    /// an error reported to the user should be mapped back to the original input if the transformation changes the
+    /// length of anything before it, as stripping a byte order mark does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let string = just::<_, _, extra::Err<Simple<char>>>('"')
+    ///     .ignore_then(none_of('"').repeated().slice())
+    ///     .then_ignore(just('"'));
+    ///
+    /// // A word processor helpfully replaced the quotes
+    /// let src = "\u{201C}hello\u{201D}";
+    /// let mut normalised = String::new();
+    /// let res = string.parse_retry_with(src, |src| {
+    ///     normalised = src.replace(['\u{201C}', '\u{201D}'], "\"");
+    ///     Some(normalised.as_str())
+    /// });
+    ///
+    /// assert!(res.was_retried());
+    /// assert_eq!(res.first().errors().len(), 1);
+    /// assert_eq!(res.into_last().into_result(), Ok("hello"));
+    ///
+    /// // Each replaced quote is 2 bytes shorter, so the spans of errors from the retry only make sense in `normalised`
+    /// let res = string.parse_retry_with("\u{201C}hi", |src| {
+    ///     normalised = src.replace(['\u{201C}', '\u{201D}'], "\"");
+    ///     Some(normalised.as_str())
+    /// });
+    /// let err = res.retry().unwrap().errors().next().unwrap();
+    /// assert_eq!(err.span(), &SimpleSpan::new(3, 3));
+    ///
+    /// // Valid input is not retried
+    /// let res = string.parse_retry_with("\"hello\"", |_| unreachable!());
+    /// assert!(!res.was_retried());
+    /// ```
+    fn parse_retry_with<F>(&self, input: I, transform: F) -> RetryResult<O, E::Error>
     where
         Self: Sized,
-        I: SliceInput<'a>,
+        I: Input<'a> + Clone,
+        F: FnOnce(I) -> Option<I>,
+        E::State: Default,
+        E::Context: Default,
     {
-        MapSlice {
-            parser: self,
-            mapper: f,
-            phantom: EmptyPhantom::new(),
-        }
+        let first = self.parse(input.clone());
+        let retry = if first.has_errors() {
+            transform(input).map(|input| self.parse(input))
+        } else {
+            None
+        };
+        RetryResult { first, retry }
     }
 
-    /// Convert the output of this parser into a slice of the input, based on the current parser's
-    /// span.
+    /// Parse a header with this parser, then lazily parse the rest of the input as a series of `body` items.
     ///
-    /// This is effectively a special case of [`map_slice`](Parser::map_slice)`(|x| x)`
-    fn slice(self) -> Slice<Self, O>
+    /// This suits formats made of a header followed by many records, such as CSV files with a header row or
+    /// write-ahead logs. Each record is only parsed when it is requested from the returned [`StreamIter`], so an
+    /// application can process records one at a time, and stop early, without the whole body being parsed up-front.
+    ///
+    /// The returned result has an output only if the header was parsed successfully. Unlike [`Parser::parse`], the
+    /// header does not need to be followed by the end of the input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let field = none_of::<_, _, extra::Err<Simple<char>>>(",\n").repeated().slice();
+    /// let row = field
+    ///     .separated_by(just(','))
+    ///     .collect::<Vec<_>>()
+    ///     .then_ignore(just('\n'));
+    ///
+    /// let src = "name,age\nalice,30\nbob,25\n";
+    /// let (header, mut rows) = row.parse_stream(src, row.clone()).into_result().unwrap();
+    /// assert_eq!(header, vec!["name", "age"]);
+    /// assert_eq!(rows.next(), Some(Ok(vec!["alice", "30"])));
+    /// assert_eq!(rows.next(), Some(Ok(vec!["bob", "25"])));
+    /// assert_eq!(rows.next(), None);
+    /// ```
+    fn parse_stream<B, OB>(
+        &self,
+        input: I,
+        body: B,
+    ) -> ParseResult<(O, StreamIter<'a, B, I, OB, E>), E::Error>
     where
         Self: Sized,
+        I: Input<'a>,
+        B: Parser<'a, I, OB, E>,
+        E::State: Default,
+        E::Context: Default,
     {
-        Slice {
-            parser: self,
-            phantom: EmptyPhantom::new(),
+        let mut own = InputOwn::new(input);
+        let (res, offset) =
+            own.drive::<Emit, _, _, _>(own.input.start(), self, |inp, res| (res, inp.offset));
+        match res {
+            Ok(header) => {
+                let result = own.take_result(Some(()));
+                result.map_output(|()| {
+                    let iter = StreamIter {
+                        item: body,
+                        sync: None,
+                        offset,
+                        own,
+                        done: false,
+                        phantom: EmptyPhantom::new(),
+                    };
+                    (header, iter)
+                })
+            }
+            Err(err) => {
+                own.errors.secondary.push(err);
+                own.into_result(None)
+            }
         }
     }
 
-    /// Filter the output of this parser, accepting only inputs that match the given predicate.
+    /// Parse a sequence of top-level items with this parser, recovering from items that fail to parse by skipping
+    /// ahead to the start of the next item.
     ///
-    /// The output type of this parser is `I`, the input that was found.
+    /// This is the outermost recovery loop of a compiler: most inputs are a list of items (functions, declarations,
+    /// statements) and an error in one of them should not prevent the rest from being parsed and checked. When an
+    /// item fails to parse, its error is produced and at least one token is skipped, followed by every token up to the
+    /// next position at which `sync` succeeds (such as the next `fn` keyword). `sync` is only used to look ahead, so
+    /// the next item is parsed from the position at which `sync` *started*.
+    ///
+    /// Each item is produced lazily by the returned [`StreamIter`], as `Ok` with the output of this parser or `Err`
+    /// with the error that caused the item to be skipped. Iteration ends at the end of the input, or after an item
+    /// that consumes no input. Errors recovered from within an item (see [`recovery`]) do not stop it from being
+    /// produced, and are available from [`StreamIter::take_errors`].
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let lowercase = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(char::is_ascii_lowercase)
-    ///     .repeated()
-    ///     .at_least(1)
-    ///     .collect::<String>();
+    /// # use chumsky::prelude::*;
+    /// let item = text::ascii::keyword::<_, _, _, extra::Err<Simple<char>>>("let")
+    ///     .ignore_then(text::ascii::ident().padded())
+    ///     .then_ignore(just(';'))
+    ///     .padded();
+    /// let sync = text::ascii::keyword("let");
     ///
-    /// assert_eq!(lowercase.parse("hello").into_result(), Ok("hello".to_string()));
-    /// assert!(lowercase.parse("Hello").has_errors());
+    /// let items = item
+    ///     .parse_items_recovering("let a; let 1; let b;", sync)
+    ///     .map(|item| item.is_ok())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(items, [true, false, true]);
     /// ```
-    fn filter<F: Fn(&O) -> bool>(self, f: F) -> Filter<Self, F>
+    fn parse_items_recovering<S, OS>(
+        &self,
+        input: I,
+        sync: S,
+    ) -> StreamIter<'a, &Self, I, O, E, S, OS>
     where
         Self: Sized,
+        I: Input<'a>,
+        S: Parser<'a, I, OS, E>,
+        E::State: Default,
+        E::Context: Default,
     {
-        Filter {
-            parser: self,
-            filter: f,
+        let own = InputOwn::new(input);
+        StreamIter {
+            item: self,
+            sync: Some(sync),
+            offset: own.input.start(),
+            own,
+            done: false,
+            phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Map the output of this parser to another value.
+    /// Parse a stream of tokens like [`Parser::parse`], additionally recording a [`Trace`](trace::Trace) of every
+    /// save, rewind and token read that the parser performed on the input.
     ///
-    /// The output type of this parser is `U`, the same as the function's output.
+    /// The trace can be replayed step by step, dumped as JSON, or summarised as a heat map of the offsets that the
+    /// parser backtracked to. See the [`trace`] module for more information.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// #[derive(Debug, PartialEq)]
-    /// enum Token { Word(String), Num(u64) }
+    /// # use chumsky::prelude::*;
+    /// let keyword = choice((
+    ///     text::ascii::keyword::<_, _, _, extra::Default>("let"),
+    ///     text::ascii::keyword("loop"),
+    ///     text::ascii::keyword("local"),
+    /// ));
+    ///
+    /// let (res, trace) = keyword.parse_traced("local");
+    /// assert_eq!(res.into_result(), Ok("local"));
+    /// // Both failed keywords caused the parser to backtrack to the start of the input
+    /// assert_eq!(trace.backtracks().get(&0), Some(&2));
+    /// ```
+    #[cfg(feature = "debug-replay")]
+    fn parse_traced(&self, input: I) -> (ParseResult<O, E::Error>, trace::Trace)
+    where
+        Self: Sized,
+        I: Input<'a>,
+        E::State: Default,
+        E::Context: Default,
+    {
+        let mut own = InputOwn::new(input);
+        own.trace = Some(RefCell::default());
+        let out = own.run::<Emit, _, _>(self);
+        let trace = own.trace.take().unwrap_or_default().into_inner();
+        (own.into_result(out), trace)
+    }
+
+    /// Parse a stream of tokens like [`Parser::parse`], counting the operations the parser performed on the input and
+    /// handing the resulting [`ParseStats`](telemetry::ParseStats) to a [`Telemetry`](telemetry::Telemetry) hook once
+    /// parsing has finished.
     ///
-    /// let word = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(|c: &char| c.is_alphabetic())
-    ///     .repeated().at_least(1)
-    ///     .collect::<String>()
-    ///     .map(Token::Word);
+    /// The counters include the tokens read, saves, rewinds and backtracks of the input position, hits and misses of
+    /// [memoized](Parser::memoized) parsers, error recoveries, and the time spent parsing. See the [`telemetry`] module
+    /// for more information.
     ///
-    /// let num = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(|c: &char| c.is_ascii_digit())
-    ///     .repeated().at_least(1)
-    ///     .collect::<String>()
-    ///     .map(|s| Token::Num(s.parse().unwrap()));
+    /// The input is parsed with a default state. There is no telemetry counterpart of [`Parser::parse_with_state`],
+    /// [`Parser::check`] or [`Parser::parse_stream`], so those cannot be monitored.
     ///
-    /// let token = word.or(num);
+    /// # Examples
     ///
-    /// assert_eq!(token.parse("test").into_result(), Ok(Token::Word("test".to_string())));
-    /// assert_eq!(token.parse("42").into_result(), Ok(Token::Num(42)));
     /// ```
-    fn map<U, F: Fn(O) -> U>(self, f: F) -> Map<Self, O, F>
+    /// # use chumsky::{prelude::*, telemetry::ParseStats};
+    /// let digits = text::digits::<_, _, extra::Default>(10).slice();
+    /// let num = digits.then_ignore(just('.')).then(digits).slice().or(digits);
+    ///
+    /// let mut stats = None;
+    /// let res = num.parse_with_telemetry("42", &mut |s: &ParseStats| stats = Some(*s));
+    /// assert_eq!(res.into_result(), Ok("42"));
+    ///
+    /// let stats = stats.unwrap();
+    /// // The parser backtracked once, to try the second alternative
+    /// assert_eq!(stats.backtracks(), 1);
+    /// assert_eq!(stats.memo_hit_rate(), None);
+    /// ```
+    #[cfg(feature = "telemetry")]
+    fn parse_with_telemetry<T>(&self, input: I, telemetry: &mut T) -> ParseResult<O, E::Error>
     where
         Self: Sized,
+        I: Input<'a>,
+        E::State: Default,
+        E::Context: Default,
+        T: telemetry::Telemetry + ?Sized,
     {
-        Map {
-            parser: self,
-            mapper: f,
-            phantom: EmptyPhantom::new(),
-        }
+        telemetry.parse_started();
+        let start = std::time::Instant::now();
+        let mut own = InputOwn::new(input);
+        own.stats = Some(RefCell::default());
+        let res = own.parse_with(self);
+        let mut stats = own.stats.take().unwrap_or_default().into_inner();
+        stats.parses = 1;
+        stats.duration = start.elapsed();
+        telemetry.parse_finished(&stats);
+        res
     }
 
-    /// Map the output of this parser to another value.
-    /// If the output of this parser isn't a tuple, use [`Parser::map`].
+    /// Parse a stream of tokens, ignoring any output, and returning the spans of every [node](Parser::node) that was
+    /// parsed, nested according to the nodes they were parsed within.
     ///
-    /// The output type of this parser is `U`, the same as the function's output.
+    /// This is useful for extracting the outline or structure of an input (for example, the spans of the items in a
+    /// source file, for code folding or a table of contents) without the cost of generating an output. Like
+    /// [`Parser::check`], the parser runs without generating outputs, so this is much cheaper than [`Parser::parse`]
+    /// with [`Parser::map_with_span`] everywhere.
+    ///
+    /// Only nodes that end up as part of the successful parse are included: those parsed by an alternative that was
+    /// later abandoned (or within a [lookahead](Parser::rewind)) are discarded. Nodes parsed within
+    /// [`Parser::nested_in`] are not included, since their spans would refer to a different input. Nodes start after
+    /// any [trivia](Parser::with_trivia) that precedes them.
+    ///
+    /// Like an output, the outline is only produced if parsing succeeds, possibly after recovering from errors: in that
+    /// case, it contains the nodes of the recovered parse. If parsing fails outright, the result has errors but no
+    /// outline.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::prelude::*;
-    /// #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-    ///  pub enum Value {
-    ///       One(u8),
-    ///      Two(u8, u8),
-    ///      Three(u8, u8, u8),
-    /// }
-    ///
-    /// fn parser<'a>() -> impl Parser<'a, &'a [u8], Vec<Value>> {
-    ///     choice((
-    ///         just(1).ignore_then(any()).map(Value::One),
-    ///         just(2)
-    ///             .ignore_then(group((any(), any())))
-    ///             .map_group(Value::Two),
-    ///         just(3)
-    ///             .ignore_then(group((any(), any(), any())))
-    ///             .map_group(Value::Three),
-    ///     ))
-    ///     .repeated()
-    ///     .collect()
-    /// }
-    ///
-    /// let bytes = &[3, 1, 2, 3, 1, 127, 2, 21, 69];
-    /// assert_eq!(
-    ///     parser().parse(bytes).into_result(),
-    ///     Ok(vec![
-    ///         Value::Three(1, 2, 3),
-    ///         Value::One(127),
-    ///         Value::Two(21, 69)
-    ///     ])
-    /// );
+    /// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>().padded();
+    /// let field = ident.then_ignore(just(';').padded()).node();
+    /// let item = text::ascii::keyword("struct")
+    ///     .padded()
+    ///     .ignore_then(ident)
+    ///     .then(field.repeated().delimited_by(just('{'), just('}')))
+    ///     .padded()
+    ///     .node();
+    /// let file = item.repeated();
+    ///
+    /// let outline = file
+    ///     .parse_spans("struct A { x; y; }\nstruct B {}")
+    ///     .into_result()
+    ///     .unwrap();
+    /// // The items, and the fields within them
+    /// assert_eq!(outline.len(), 2);
+    /// assert_eq!(outline[0].span(), &SimpleSpan::from(0..19));
+    /// assert_eq!(outline[0].children()[1].span(), &SimpleSpan::from(14..17));
+    /// assert!(outline[1].children().is_empty());
     /// ```
-    #[cfg(feature = "nightly")]
-    fn map_group<F: Fn<O>>(self, f: F) -> MapGroup<Self, O, F>
+    fn parse_spans(&self, input: I) -> ParseResult<Vec<SpanTree<I::Span>>, E::Error>
     where
         Self: Sized,
-        O: Tuple,
+        I: Input<'a>,
+        E::State: Default,
+        E::Context: Default,
     {
-        MapGroup {
-            parser: self,
-            mapper: f,
-            phantom: EmptyPhantom::new(),
-        }
+        let mut own = InputOwn::<I, E>::new(input);
+        own.errors.outline = Some(Vec::new());
+        let out = own.run::<Check, _, _>(self);
+        let outline = own.errors.outline.take().unwrap_or_default();
+        let trees = SpanTree::from_outline(&own.input, &outline);
+        own.into_result(out.map(|()| trees))
     }
 
-    /// Map the output of this parser to another value, making use of the pattern's span when doing so.
+    /// Parse a stream of tokens, returning every expectation of the parser at the given offset into the input.
     ///
-    /// This is very useful when generating an AST that attaches a span to each AST node.
+    /// This is useful for powering autocompletion: given the position of a cursor in a partially written input, it
+    /// describes what could be written there, using the same grammar used to parse the input.
     ///
-    /// The output type of this parser is `U`, the same as the function's output.
+    /// Every error that a parser produces at the offset is recorded and merged into the returned error, even if the
+    /// error is later discarded because another alternative succeeded or because an error further into the input took
+    /// priority. The parser must reach the offset for anything to be recorded, so an input that fails to parse
+    /// *before* the offset may produce no expectations at all (in which case `None` is returned).
+    ///
+    /// The offset is an offset into the input, as used internally by the input to locate tokens, rather than a
+    /// position in the spans that it produces. For [`&str`] these coincide (both are byte offsets), and for slices the
+    /// offset is the index of a token. However, inputs that produce their own spans, such as those created with
+    /// [`Input::spanned`], are still indexed by token: to complete at a source position, first find the index of the
+    /// token whose span contains (or follows) that position.
+    ///
+    /// As with [`Parser::parse`], the parser is expected to consume the whole input, so the end of the input is
+    /// expected at an offset where the parser may finish.
+    ///
+    /// Errors are recorded as they are produced by each parser, so the expectations of a [labelled](Parser::labelled)
+    /// parser include both its label and whatever its own sub-parsers expected.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::prelude::*;
+    /// # use chumsky::{prelude::*, error::RichPattern};
+    /// let value = choice((
+    ///     text::ascii::keyword("true"),
+    ///     text::ascii::keyword("false"),
+    ///     text::ascii::keyword("null"),
+    /// ));
+    /// let assignment = text::ascii::ident::<_, _, extra::Err<Rich<char>>>()
+    ///     .then_ignore(just('=').padded())
+    ///     .then(value);
+    ///
+    /// // The cursor is after the `=`
+    /// let err = assignment.parse_expecting_at("x = ", 4).unwrap();
+    /// let expected = err
+    ///     .expected()
+    ///     .map(|pat| pat.to_string())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(expected, ["keyword 'true'", "keyword 'false'", "keyword 'null'"]);
+    /// ```
+    fn parse_expecting_at(&self, input: I, offset: usize) -> Option<E::Error>
+    where
+        Self: Sized,
+        I: Input<'a>,
+        E::Error: Clone,
+        E::State: Default,
+        E::Context: Default,
+    {
+        let mut own = InputOwn::<I, E>::new(input);
+        own.errors.expecting = Some(input::Expecting {
+            offset,
+            clone: E::Error::clone,
+            err: None,
+        });
+        own.run::<Check, _, _>(self);
+        own.errors.expecting.and_then(|expecting| expecting.err)
+    }
+
+    /// Parse a stream of tokens, ignoring any output, and returning any errors encountered along the way.
     ///
-    /// // It's common for AST nodes to use a wrapper type that allows attaching span information to them
-    /// #[derive(Debug, PartialEq)]
-    /// pub struct Spanned<T>(T, SimpleSpan<usize>);
+    /// If parsing failed, then there will *always* be at least one item in the returned `Vec`. Errors are ordered in
+    /// the same way as [`Parser::parse`].
+    /// If you want to include non-default state, use [`Parser::check_with_state`] instead.
     ///
-    /// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
-    ///     .map_with_span(Spanned) // Equivalent to `.map_with_span(|ident, span| Spanned(ident, span))`
-    ///     .padded();
+    /// Although the signature of this function looks complicated, it's simpler than you think! You can pass a
+    /// [`&[T]`], a [`&str`], [`Stream`], or anything implementing [`Input`] to it.
+    fn check(&self, input: I) -> ParseResult<(), E::Error>
+    where
+        Self: Sized,
+        I: Input<'a>,
+        E::State: Default,
+        E::Context: Default,
+    {
+        self.check_with_state(input, &mut E::State::default())
+    }
+
+    /// Parse a stream of tokens, ignoring any output, and returning any errors encountered along the way.
     ///
-    /// assert_eq!(ident.parse("hello").into_result(), Ok(Spanned("hello", (0..5).into())));
-    /// assert_eq!(ident.parse("       hello   ").into_result(), Ok(Spanned("hello", (7..12).into())));
-    /// ```
-    fn map_with_span<U, F: Fn(O, I::Span) -> U>(self, f: F) -> MapWithSpan<Self, O, F>
+    /// If parsing failed, then there will *always* be at least one item in the returned `Vec`.
+    /// If you want to just use a default state value, use [`Parser::check`] instead.
+    ///
+    /// Although the signature of this function looks complicated, it's simpler than you think! You can pass a
+    /// [`&[T]`], a [`&str`], [`Stream`], or anything implementing [`Input`] to it.
+    fn check_with_state(&self, input: I, state: &mut E::State) -> ParseResult<(), E::Error>
     where
         Self: Sized,
+        I: Input<'a>,
+        E::Context: Default,
     {
-        MapWithSpan {
+        let mut own = InputOwn::new_state(input, state);
+        let out = own.run::<Check, _, _>(self);
+        own.into_result(out)
+    }
+
+    /// Map from a slice of the input based on the current parser's span to a value.
+    ///
+    /// The returned value may borrow data from the input slice, making this function very useful
+    /// for creating zero-copy AST output values
+    fn map_slice<U, F: Fn(I::Slice) -> U>(self, f: F) -> MapSlice<'a, Self, I, O, E, F, U>
+    where
+        Self: Sized,
+        I: SliceInput<'a>,
+    {
+        MapSlice {
             parser: self,
             mapper: f,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Transform the output of this parser to the pattern's span.
+    /// Convert the output of this parser into a slice of the input, based on the current parser's
+    /// span.
     ///
-    /// This is commonly used when you know what pattern you've parsed and are only interested in the span of the
-    /// pattern.
+    /// This is effectively a special case of [`map_slice`](Parser::map_slice)`(|x| x)`
     ///
-    /// The output type of this parser is `I::Span`.
+    /// The inner parser is run without generating its output, so wrapping a repetition in this combinator does not
+    /// collect the tokens it consumes: the output is produced from the start and end offsets alone. This makes
+    /// `any().and_is(terminator.not()).repeated().slice()` the zero-allocation way to take input up to a terminator.
+    /// When the terminator is a fixed character or string, [`text::until_char`] and [`text::until_str`] are faster
+    /// still.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::prelude::*;
+    /// let comment = just::<_, _, extra::Err<Simple<char>>>("/*")
+    ///     .ignore_then(any().and_is(just("*/").not()).repeated().slice())
+    ///     .then_ignore(just("*/"));
     ///
-    /// // It's common for AST nodes to use a wrapper type that allows attaching span information to them
-    /// #[derive(Debug, PartialEq)]
-    /// pub enum Expr<'a> {
-    ///     Int(&'a str, SimpleSpan),
-    ///     // The span is that of the operator, '+'
-    ///     Add(Box<Expr<'a>>, SimpleSpan, Box<Expr<'a>>),
-    /// }
-    ///
-    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
-    ///     .slice()
-    ///     .map_with_span(Expr::Int)
-    ///     .padded();
-    ///
-    /// let add_op = just('+').to_span().padded();
-    /// let sum = int.foldl(
-    ///     add_op.then(int).repeated(),
-    ///     |a, (op_span, b)| Expr::Add(Box::new(a), op_span, Box::new(b)),
-    /// );
-    ///
-    /// assert_eq!(sum.parse("42 + 7 + 13").into_result(), Ok(Expr::Add(
-    ///     Box::new(Expr::Add(
-    ///         Box::new(Expr::Int("42", (0..2).into())),
-    ///         (3..4).into(),
-    ///         Box::new(Expr::Int("7", (5..6).into())),
-    ///     )),
-    ///     (7..8).into(),
-    ///     Box::new(Expr::Int("13", (9..11).into())),
-    /// )));
+    /// assert_eq!(comment.parse("/* a * b */").into_result(), Ok(" a * b "));
     /// ```
-    fn to_span(self) -> ToSpan<Self, O>
+    fn slice(self) -> Slice<Self, O>
     where
         Self: Sized,
     {
-        ToSpan {
+        Slice {
             parser: self,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Map the output of this parser to another value, making use of the parser's state when doing so.
-    ///
-    /// This is very useful for parsing non context-free grammars.
+    /// Filter the output of this parser, accepting only inputs that match the given predicate.
     ///
-    /// The output type of this parser is `U`, the same as the function's output.
+    /// The output type of this parser is `I`, the input that was found.
     ///
     /// # Examples
     ///
-    /// ## General
-    ///
     /// ```
-    /// # use chumsky::prelude::*;
-    /// use std::ops::Range;
-    /// use lasso::{Rodeo, Spur};
-    ///
-    /// // It's common for AST nodes to use interned versions of identifiers
-    /// // Keys are generally smaller, faster to compare, and can be `Copy`
-    /// #[derive(Copy, Clone)]
-    /// pub struct Ident(Spur);
-    ///
-    /// let ident = text::ascii::ident::<_, _, extra::Full<Simple<char>, Rodeo, ()>>()
-    ///     .map_with_state(|ident, span, state| Ident(state.get_or_intern(ident)))
-    ///     .padded()
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let lowercase = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(char::is_ascii_lowercase)
     ///     .repeated()
     ///     .at_least(1)
-    ///     .collect::<Vec<_>>();
-    ///
-    /// // Test out parser
-    ///
-    /// let mut interner = Rodeo::new();
-    ///
-    /// match ident.parse_with_state("hello", &mut interner).into_result() {
-    ///     Ok(idents) => {
-    ///         assert_eq!(interner.resolve(&idents[0].0), "hello");
-    ///     }
-    ///     Err(e) => panic!("Parsing Failed: {:?}", e),
-    /// }
+    ///     .collect::<String>();
     ///
-    /// match ident.parse_with_state("hello hello", &mut interner).into_result() {
-    ///     Ok(idents) => {
-    ///         assert_eq!(idents[0].0, idents[1].0);
-    ///     }
-    ///     Err(e) => panic!("Parsing Failed: {:?}", e),
-    /// }
+    /// assert_eq!(lowercase.parse("hello").into_result(), Ok("hello".to_string()));
+    /// assert!(lowercase.parse("Hello").has_errors());
     /// ```
-    ///
-    /// See [`Parser::foldl_with_state`] for an example showing arena allocation via parser state.
-    fn map_with_state<U, F: Fn(O, I::Span, &mut E::State) -> U>(
-        self,
-        f: F,
-    ) -> MapWithState<Self, O, F>
+    fn filter<F: Fn(&O) -> bool>(self, f: F) -> Filter<Self, F>
     where
         Self: Sized,
     {
-        MapWithState {
+        Filter {
             parser: self,
-            mapper: f,
-            phantom: EmptyPhantom::new(),
+            filter: f,
         }
     }
 
-    /// After a successful parse, apply a fallible function to the output. If the function produces an error, treat it
-    /// as a parsing error.
-    ///
-    /// If you wish parsing of this pattern to continue when an error is generated instead of halting, consider using
-    /// [`Parser::validate`] instead.
+    /// Map the output of this parser to another value.
     ///
-    /// The output type of this parser is `U`, the [`Ok`] return value of the function.
+    /// The output type of this parser is `U`, the same as the function's output.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::prelude::*;
-    /// let byte = text::int::<_, _, extra::Err<Rich<char>>>(10)
-    ///     .try_map(|s: &str, span| s
-    ///         .parse::<u8>()
-    ///         .map_err(|e| Rich::custom(span, e)));
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// #[derive(Debug, PartialEq)]
+    /// enum Token { Word(String), Num(u64) }
     ///
-    /// assert!(byte.parse("255").has_output());
-    /// assert!(byte.parse("256").has_errors()); // Out of range
+    /// let word = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| c.is_alphabetic())
+    ///     .repeated().at_least(1)
+    ///     .collect::<String>()
+    ///     .map(Token::Word);
+    ///
+    /// let num = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| c.is_ascii_digit())
+    ///     .repeated().at_least(1)
+    ///     .collect::<String>()
+    ///     .map(|s| Token::Num(s.parse().unwrap()));
+    ///
+    /// let token = word.or(num);
+    ///
+    /// assert_eq!(token.parse("test").into_result(), Ok(Token::Word("test".to_string())));
+    /// assert_eq!(token.parse("42").into_result(), Ok(Token::Num(42)));
     /// ```
-    #[doc(alias = "filter_map")]
-    fn try_map<U, F: Fn(O, I::Span) -> Result<U, E::Error>>(self, f: F) -> TryMap<Self, O, F>
+    fn map<U, F: Fn(O) -> U>(self, f: F) -> Map<Self, O, F>
     where
         Self: Sized,
     {
-        TryMap {
+        Map {
             parser: self,
             mapper: f,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// After a successful parse, apply a fallible function to the output, making use of the parser's state when
-    /// doing so. If the function produces an error, treat it as a parsing error.
+    /// Map the output of this parser to another value, with an inverse function that allows the resulting parser to
+    /// be [unparsed](unparse::Unparse).
     ///
-    /// If you wish parsing of this pattern to continue when an error is generated instead of halting, consider using
-    /// [`Parser::validate`] instead.
+    /// The inverse function should return `None` for values that the mapping function could not have produced.
     ///
-    /// The output type of this parser is `U`, the [`Ok`] return value of the function.
-    fn try_map_with_state<U, F: Fn(O, I::Span, &mut E::State) -> Result<U, E::Error>>(
-        self,
-        f: F,
-    ) -> TryMapWithState<Self, O, F>
+    /// # Examples
+    ///
+    /// ```
+    /// use chumsky::{prelude::*, unparse::Unparse};
+    ///
+    /// let flag = just::<_, _, extra::Default>("yes").or(just("no")).map_invertible(
+    ///     |s| s == "yes",
+    ///     |flag| Some(if *flag { "yes" } else { "no" }),
+    /// );
+    ///
+    /// assert_eq!(flag.parse("no").into_result(), Ok(false));
+    /// assert_eq!(flag.unparse_to_string(&true), Ok("yes".to_string()));
+    /// ```
+    #[cfg(feature = "unstable")]
+    fn map_invertible<U, F, G>(self, f: F, inverse: G) -> unparse::MapInvertible<Self, O, F, G>
     where
         Self: Sized,
+        F: Fn(O) -> U,
+        G: Fn(&U) -> Option<O>,
     {
-        TryMapWithState {
+        unparse::MapInvertible {
             parser: self,
             mapper: f,
+            inverse,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Ignore the output of this parser, yielding `()` as an output instead.
-    ///
-    /// This can be used to reduce the cost of parsing by avoiding unnecessary allocations (most collections containing
-    /// [ZSTs](https://doc.rust-lang.org/nomicon/exotic-sizes.html#zero-sized-types-zsts)
-    /// [do not allocate](https://doc.rust-lang.org/std/vec/struct.Vec.html#guarantees)). For example, it's common to
-    /// want to ignore whitespace in many grammars (see [`text::whitespace`]).
+    /// Provide a function that writes the text of this parser's output, allowing it to be
+    /// [unparsed](unparse::Unparse).
     ///
-    /// The output type of this parser is `()`.
+    /// This is useful for parsers that can't be unparsed automatically, such as [`Parser::map`] or [`text::ascii::ident`].
+    /// The function should write text that this parser would parse as the given value.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// // A parser that parses any number of whitespace characters without allocating
-    /// let whitespace = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(|c: &char| c.is_whitespace())
-    ///     .ignored()
-    ///     .repeated()
-    ///     .collect::<Vec<_>>();
+    /// use chumsky::{prelude::*, unparse::{Unparse, UnparseError}};
     ///
-    /// assert_eq!(whitespace.parse("    ").into_result(), Ok(vec![(); 4]));
-    /// assert!(whitespace.parse("  hello").has_errors());
+    /// let num = text::int::<_, _, extra::Default>(10)
+    ///     .from_str::<u32>()
+    ///     .unwrapped()
+    ///     .unparse_with(|n, out| {
+    ///         out.push_str(&n.to_string());
+    ///         Ok(())
+    ///     });
+    ///
+    /// assert_eq!(num.parse("42").into_result(), Ok(42));
+    /// assert_eq!(num.unparse_to_string(&42), Ok("42".to_string()));
     /// ```
-    fn ignored(self) -> Ignored<Self, O>
+    #[cfg(feature = "unstable")]
+    fn unparse_with<F>(self, f: F) -> unparse::UnparseWith<Self, F>
     where
         Self: Sized,
+        F: Fn(&O, &mut String) -> Result<(), unparse::UnparseError>,
     {
-        Ignored {
+        unparse::UnparseWith {
             parser: self,
-            phantom: EmptyPhantom::new(),
+            unparser: f,
         }
     }
 
-    /// Memoize the parser such that later attempts to parse the same input 'remember' the attempt and exit early.
+    /// Map the output of this parser to another value.
+    /// If the output of this parser isn't a tuple, use [`Parser::map`].
     ///
-    /// If you're finding that certain inputs produce exponential behavior in your parser, strategically applying
-    /// memoization to a ['garden path'](https://en.wikipedia.org/wiki/Garden-path_sentence) rule is often an effective
-    /// way to solve the problem. At the limit, applying memoization to all combinators will turn any parser into one
-    /// with `O(n)`, albeit with very significant per-element overhead and high memory usage.
+    /// The output type of this parser is `U`, the same as the function's output.
     ///
-    /// Memoization also works with recursion, so this can be used to write parsers using
-    /// [left recursion](https://en.wikipedia.org/wiki/Left_recursion).
-    // TODO: Example
-    #[cfg(feature = "memoization")]
-    fn memoized(self) -> Memoized<Self>
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    ///  pub enum Value {
+    ///       One(u8),
+    ///      Two(u8, u8),
+    ///      Three(u8, u8, u8),
+    /// }
+    ///
+    /// fn parser<'a>() -> impl Parser<'a, &'a [u8], Vec<Value>> {
+    ///     choice((
+    ///         just(1).ignore_then(any()).map(Value::One),
+    ///         just(2)
+    ///             .ignore_then(group((any(), any())))
+    ///             .map_group(Value::Two),
+    ///         just(3)
+    ///             .ignore_then(group((any(), any(), any())))
+    ///             .map_group(Value::Three),
+    ///     ))
+    ///     .repeated()
+    ///     .collect()
+    /// }
+    ///
+    /// let bytes = &[3, 1, 2, 3, 1, 127, 2, 21, 69];
+    /// assert_eq!(
+    ///     parser().parse(bytes).into_result(),
+    ///     Ok(vec![
+    ///         Value::Three(1, 2, 3),
+    ///         Value::One(127),
+    ///         Value::Two(21, 69)
+    ///     ])
+    /// );
+    /// ```
+    #[cfg(feature = "nightly")]
+    fn map_group<F: Fn<O>>(self, f: F) -> MapGroup<Self, O, F>
     where
         Self: Sized,
+        O: Tuple,
     {
-        Memoized { parser: self }
+        MapGroup {
+            parser: self,
+            mapper: f,
+            phantom: EmptyPhantom::new(),
+        }
     }
 
-    /// Transform all outputs of this parser to a predetermined value.
+    /// Map the output of this parser to another value, with access to metadata about the parsed pattern.
     ///
-    /// The output type of this parser is `U`, the type of the predetermined value.
+    /// The closure is given a [`MapExtra`], which provides the pattern's span, the slice of the input
+    /// it covers (for [`SliceInput`]s), the parser's state, and the current context. This subsumes
+    /// [`Parser::map_with_span`], [`Parser::map_with_state`] and [`Parser::map_slice`].
+    ///
+    /// The output type of this parser is `U`, the same as the function's output.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// #[derive(Clone, Debug, PartialEq)]
-    /// enum Op { Add, Sub, Mul, Div }
+    /// # use chumsky::prelude::*;
+    /// #[derive(Debug, PartialEq)]
+    /// pub struct Ident<'a> {
+    ///     name: &'a str,
+    ///     span: SimpleSpan,
+    ///     index: usize,
+    /// }
     ///
-    /// let op = just::<_, _, extra::Err<Simple<char>>>('+').to(Op::Add)
-    ///     .or(just('-').to(Op::Sub))
-    ///     .or(just('*').to(Op::Mul))
-    ///     .or(just('/').to(Op::Div));
+    /// let ident = text::ascii::ident::<_, _, extra::Full<Simple<char>, usize, ()>>()
+    ///     .map_with(|_, e| {
+    ///         let index = *e.state();
+    ///         *e.state() += 1;
+    ///         Ident { name: e.slice(), span: e.span(), index }
+    ///     })
+    ///     .padded()
+    ///     .repeated()
+    ///     .collect::<Vec<_>>();
     ///
-    /// assert_eq!(op.parse("+").into_result(), Ok(Op::Add));
-    /// assert_eq!(op.parse("/").into_result(), Ok(Op::Div));
+    /// let mut count = 0;
+    /// assert_eq!(
+    ///     ident.parse_with_state("foo bar", &mut count).into_result(),
+    ///     Ok(vec![
+    ///         Ident { name: "foo", span: (0..3).into(), index: 0 },
+    ///         Ident { name: "bar", span: (4..7).into(), index: 1 },
+    ///     ]),
+    /// );
+    /// assert_eq!(count, 2);
     /// ```
-    fn to<U: Clone>(self, to: U) -> To<Self, O, U>
+    fn map_with<U, F: Fn(O, &mut MapExtra<'a, '_, I, E>) -> U>(self, f: F) -> MapWith<Self, O, F>
     where
         Self: Sized,
     {
-        To {
+        MapWith {
             parser: self,
-            to,
+            mapper: f,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Label this parser with the given label.
+    /// Call a function with a reference to the output of this parser, without changing it.
     ///
-    /// Labelling a parser makes all errors generated by the parser refer to the label rather than any sub-elements
-    /// within the parser. For example, labelling a parser for an expression would yield "expected expression" errors
-    /// rather than "expected integer, string, binary op, etc." errors.
-    // TODO: Example
-    #[cfg(feature = "label")]
-    fn labelled<L>(self, label: L) -> Labelled<Self, L>
+    /// This is useful for side effects such as logging or collecting metrics. The function is only called when the
+    /// parser is generating outputs: when the output is discarded (such as by [`Parser::check`], [`Parser::ignored`],
+    /// or [`Parser::rewind`]), the function is not called and has no cost. Unlike [`Parser::map`], this means that
+    /// `inspect` cannot be used to observe every successful parse, only those whose outputs are used.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// # use std::cell::RefCell;
+    /// let seen = RefCell::new(Vec::new());
+    /// let word = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .inspect(|w: &&str| seen.borrow_mut().push(w.to_string()));
+    /// let words = word.padded().repeated().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(words.parse("hello world").into_result(), Ok(vec!["hello", "world"]));
+    /// assert_eq!(*seen.borrow(), ["hello", "world"]);
+    ///
+    /// // Outputs are not generated when only checking the input, so the function is not called
+    /// assert!(!words.check("goodbye").has_errors());
+    /// assert_eq!(*seen.borrow(), ["hello", "world"]);
+    /// ```
+    fn inspect<F: Fn(&O)>(self, f: F) -> Inspect<Self, F>
     where
         Self: Sized,
-        E::Error: LabelError<'a, I, L>,
     {
-        Labelled {
-            parser: self,
-            label,
-            is_context: false,
-        }
+        Inspect { parser: self, f }
     }
 
-    /// Parse one thing and then another thing, yielding a tuple of the two outputs.
+    /// Map the output of this parser to another value, making use of the pattern's span when doing so.
     ///
-    /// The output type of this parser is `(O, U)`, a combination of the outputs of both parsers.
+    /// This is very useful when generating an AST that attaches a span to each AST node.
+    ///
+    /// The output type of this parser is `U`, the same as the function's output.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let word = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(|c: &char| c.is_alphabetic())
-    ///     .repeated()
-    ///     .at_least(1)
-    ///     .collect::<String>();
-    /// let two_words = word.then_ignore(just(' ')).then(word);
+    /// # use chumsky::prelude::*;
     ///
-    /// assert_eq!(two_words.parse("dog cat").into_result(), Ok(("dog".to_string(), "cat".to_string())));
-    /// assert!(two_words.parse("hedgehog").has_errors());
+    /// // It's common for AST nodes to use a wrapper type that allows attaching span information to them
+    /// #[derive(Debug, PartialEq)]
+    /// pub struct Spanned<T>(T, SimpleSpan<usize>);
+    ///
+    /// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .map_with_span(Spanned) // Equivalent to `.map_with_span(|ident, span| Spanned(ident, span))`
+    ///     .padded();
+    ///
+    /// assert_eq!(ident.parse("hello").into_result(), Ok(Spanned("hello", (0..5).into())));
+    /// assert_eq!(ident.parse("       hello   ").into_result(), Ok(Spanned("hello", (7..12).into())));
     /// ```
-    fn then<U, B: Parser<'a, I, U, E>>(self, other: B) -> Then<Self, B, O, U, E>
+    fn map_with_span<U, F: Fn(O, I::Span) -> U>(self, f: F) -> MapWithSpan<Self, O, F>
     where
         Self: Sized,
     {
-        Then {
-            parser_a: self,
-            parser_b: other,
+        MapWithSpan {
+            parser: self,
+            mapper: f,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse one thing and then another thing, yielding only the output of the latter.
+    /// Transform the output of this parser to the pattern's span.
     ///
-    /// The output type of this parser is `U`, the same as the second parser.
+    /// This is commonly used when you know what pattern you've parsed and are only interested in the span of the
+    /// pattern.
+    ///
+    /// The original parser is run in check mode, so its output is never generated: this makes it cheap to find the
+    /// locations of patterns (for example, to compute folding ranges or regions to highlight in an editor) without
+    /// building the values that they would otherwise produce.
+    ///
+    /// The output type of this parser is `I::Span`.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let zeroes = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| *c == '0').ignored().repeated().collect::<Vec<_>>();
-    /// let digits = any().filter(|c: &char| c.is_ascii_digit())
-    ///     .repeated()
-    ///     .collect::<String>();
-    /// let integer = zeroes
-    ///     .ignore_then(digits)
-    ///     .from_str()
-    ///     .unwrapped();
+    /// # use chumsky::prelude::*;
     ///
-    /// assert_eq!(integer.parse("00064").into_result(), Ok(64));
-    /// assert_eq!(integer.parse("32").into_result(), Ok(32));
+    /// // It's common for AST nodes to use a wrapper type that allows attaching span information to them
+    /// #[derive(Debug, PartialEq)]
+    /// pub enum Expr<'a> {
+    ///     Int(&'a str, SimpleSpan),
+    ///     // The span is that of the operator, '+'
+    ///     Add(Box<Expr<'a>>, SimpleSpan, Box<Expr<'a>>),
+    /// }
+    ///
+    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .slice()
+    ///     .map_with_span(Expr::Int)
+    ///     .padded();
+    ///
+    /// let add_op = just('+').to_span().padded();
+    /// let sum = int.foldl(
+    ///     add_op.then(int).repeated(),
+    ///     |a, (op_span, b)| Expr::Add(Box::new(a), op_span, Box::new(b)),
+    /// );
+    ///
+    /// assert_eq!(sum.parse("42 + 7 + 13").into_result(), Ok(Expr::Add(
+    ///     Box::new(Expr::Add(
+    ///         Box::new(Expr::Int("42", (0..2).into())),
+    ///         (3..4).into(),
+    ///         Box::new(Expr::Int("7", (5..6).into())),
+    ///     )),
+    ///     (7..8).into(),
+    ///     Box::new(Expr::Int("13", (9..11).into())),
+    /// )));
     /// ```
-    fn ignore_then<U, B: Parser<'a, I, U, E>>(self, other: B) -> IgnoreThen<Self, B, O, E>
+    fn to_span(self) -> ToSpan<Self, O>
     where
         Self: Sized,
     {
-        IgnoreThen {
-            parser_a: self,
-            parser_b: other,
+        ToSpan {
+            parser: self,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse one thing and then another thing, yielding only the output of the former.
+    /// Pair the output of this parser with the span of the entire input that it matched.
     ///
-    /// The output type of this parser is `O`, the same as the original parser.
+    /// The span covers everything consumed by this parser, including the parts matched by any sub-parsers whose
+    /// outputs were discarded (such as delimiters, or padding). To combine spans by hand, see [`Span::union`].
+    ///
+    /// The output type of this parser is `(O, I::Span)`.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let word = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(|c: &char| c.is_alphabetic())
-    ///     .repeated()
-    ///     .at_least(1)
-    ///     .collect::<String>();
-    ///
-    /// let punctuated = word
-    ///     .then_ignore(just('!').or(just('?')).or_not());
-    ///
-    /// let sentence = punctuated
-    ///     .padded() // Allow for whitespace gaps
-    ///     .repeated()
-    ///     .collect::<Vec<_>>();
+    /// # use chumsky::prelude::*;
+    /// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>();
+    /// let call = ident
+    ///     .then(ident.padded().delimited_by(just('('), just(')')))
+    ///     .with_span_of()
+    ///     .padded();
     ///
     /// assert_eq!(
-    ///     sentence.parse("hello! how are you?").into_result(),
-    ///     Ok(vec![
-    ///         "hello".to_string(),
-    ///         "how".to_string(),
-    ///         "are".to_string(),
-    ///         "you".to_string(),
-    ///     ]),
+    ///     call.parse("  foo( bar ) ").into_result(),
+    ///     Ok((("foo", "bar"), (2..12).into())),
     /// );
     /// ```
-    fn then_ignore<U, B: Parser<'a, I, U, E>>(self, other: B) -> ThenIgnore<Self, B, U, E>
+    fn with_span_of(self) -> WithSpanOf<Self, O>
     where
         Self: Sized,
     {
-        ThenIgnore {
-            parser_a: self,
-            parser_b: other,
+        WithSpanOf {
+            parser: self,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse input as part of a token-tree - using an input generated from within the current
-    /// input. In other words, this parser will attempt to create a *new* input stream from within
-    /// the one it is being run on, and the parser it was called on will be provided this *new* input.
-    /// By default, the original parser is expected to consume up to the end of the new stream. To
-    /// allow only consuming part of the stream, use [`Parser::lazy`] to ignore trailing tokens.
+    /// Map the output of this parser to another value, making use of the parser's state when doing so.
     ///
-    /// The provided parser `P` is expected to have both an input and output type which match the input
-    /// type of the parser it is called on. As an example, if the original parser takes an input of
-    /// `Stream<Iterator<Item = T>>`, `P` will be run first against that input, and is expected to
-    /// output a new `Stream<Iterator<Item = T>>` which the original parser will be run against.
+    /// This is very useful for parsing non context-free grammars.
     ///
-    /// The output of this parser is `O`, the output of the parser it is called on.
+    /// The output type of this parser is `U`, the same as the function's output.
     ///
     /// # Examples
     ///
-    /// ```
-    /// # use chumsky::{prelude::*, util::MaybeRef, error::Simple};
-    /// #[derive(Debug, Clone, PartialEq)]
-    /// enum Token<'a> {
-    ///     Struct,
-    ///     Ident(&'a str),
-    ///     Item(&'a str),
-    ///     Group(Vec<Token<'a>>),
-    /// }
+    /// ## General
     ///
-    /// let group = select_ref! { Token::Group(g) => g.as_slice() };
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// use std::ops::Range;
+    /// use lasso::{Rodeo, Spur};
     ///
-    /// let ident = select_ref! { Token::Ident(i) => *i };
+    /// // It's common for AST nodes to use interned versions of identifiers
+    /// // Keys are generally smaller, faster to compare, and can be `Copy`
+    /// #[derive(Copy, Clone)]
+    /// pub struct Ident(Spur);
     ///
-    /// let items = select_ref! { Token::Item(i) => *i }
+    /// let ident = text::ascii::ident::<_, _, extra::Full<Simple<char>, Rodeo, ()>>()
+    ///     .map_with_state(|ident, span, state| Ident(state.get_or_intern(ident)))
+    ///     .padded()
     ///     .repeated()
-    ///     .collect::<Vec<_>>()
-    ///     .nested_in(group);
+    ///     .at_least(1)
+    ///     .collect::<Vec<_>>();
     ///
-    /// let struc = just::<_, _, extra::Err<Simple<_>>>(&Token::Struct)
-    ///     .ignore_then(ident)
-    ///     .then(items);
+    /// // Test out parser
     ///
-    /// let tl = struc
-    ///     .repeated()
-    ///     .collect::<Vec<_>>();
+    /// let mut interner = Rodeo::new();
     ///
-    /// let tokens = [
-    ///     Token::Struct,
-    ///     Token::Ident("foo"),
-    ///     Token::Group(vec![
-    ///         Token::Item("a"),
-    ///         Token::Item("b"),
-    ///     ]),
-    /// ];
+    /// match ident.parse_with_state("hello", &mut interner).into_result() {
+    ///     Ok(idents) => {
+    ///         assert_eq!(interner.resolve(&idents[0].0), "hello");
+    ///     }
+    ///     Err(e) => panic!("Parsing Failed: {:?}", e),
+    /// }
     ///
-    /// assert_eq!(tl.parse(&tokens).into_result(), Ok(vec![("foo", vec!["a", "b"])]));
+    /// match ident.parse_with_state("hello hello", &mut interner).into_result() {
+    ///     Ok(idents) => {
+    ///         assert_eq!(idents[0].0, idents[1].0);
+    ///     }
+    ///     Err(e) => panic!("Parsing Failed: {:?}", e),
+    /// }
     /// ```
-    fn nested_in<B: Parser<'a, I, I, E>>(self, other: B) -> NestedIn<Self, B, O, E>
+    ///
+    /// See [`Parser::foldl_with_state`] for an example showing arena allocation via parser state.
+    fn map_with_state<U, F: Fn(O, I::Span, &mut E::State) -> U>(
+        self,
+        f: F,
+    ) -> MapWithState<Self, O, F>
     where
         Self: Sized,
-        I: 'a,
     {
-        NestedIn {
-            parser_a: self,
-            parser_b: other,
+        MapWithState {
+            parser: self,
+            mapper: f,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse one thing and then another thing, creating the second parser from the result of
-    /// the first. If you don't need the context in the output, use [`Parser::then_with_ctx`].
+    /// Intern the output of this parser, producing a [`Symbol`](intern::Symbol) handle rather than a string.
     ///
-    /// The output of this parser is `U`, the result of the second parser
+    /// The parser's state must be an [`Interner`](intern::Interner), or a type that implements
+    /// [`BorrowMut<Interner>`](core::borrow::BorrowMut). Each distinct string is only stored once, so this avoids
+    /// allocating for every occurrence of an identifier. See the [`intern`] module for more information.
     ///
-    /// Error recovery for this parser may be sub-optimal, as if the first parser succeeds on
-    /// recovery then the second produces an error, the primary error will point to the location in
-    /// the second parser which failed, ignoring that the first parser may be the root cause. There
-    /// may be other pathological errors cases as well.
+    /// The output type of this parser is [`Symbol`](intern::Symbol).
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let successor = just(b'\0').configure(|cfg, ctx: &u8| cfg.seq(*ctx + 1));
+    /// # use chumsky::prelude::*;
+    /// use chumsky::intern::{Interner, Symbol};
+    /// use std::borrow::BorrowMut;
+    ///
+    /// // A parser state that holds an interner alongside other data
+    /// #[derive(Default)]
+    /// struct State {
+    ///     interner: Interner,
+    ///     lets: usize,
+    /// }
     ///
-    /// // A parser that parses a single letter and then its successor
-    /// let successive_letters = one_of::<_, _, extra::Err<Simple<u8>>>(b'a'..=b'z')
-    ///     .ignore_with_ctx(successor);
+    /// impl BorrowMut<Interner> for State {
+    ///     fn borrow_mut(&mut self) -> &mut Interner { &mut self.interner }
+    /// }
+    /// # impl std::borrow::Borrow<Interner> for State {
+    /// #     fn borrow(&self) -> &Interner { &self.interner }
+    /// # }
     ///
-    /// assert_eq!(successive_letters.parse(b"ab").into_result(), Ok(b'b')); // 'b' follows 'a'
-    /// assert!(successive_letters.parse(b"ac").has_errors()); // 'c' does not follow 'a'
+    /// let name = text::ascii::ident().map_intern().map_with_state(|name, _, state: &mut State| {
+    ///     state.lets += 1;
+    ///     name
+    /// });
+    /// let binding = text::ascii::keyword::<_, _, _, extra::State<State>>("let")
+    ///     .padded()
+    ///     .ignore_then(name)
+    ///     .then_ignore(just(';').padded());
+    ///
+    /// let mut state = State::default();
+    /// let names = binding
+    ///     .repeated()
+    ///     .collect::<Vec<Symbol>>()
+    ///     .parse_with_state("let x; let y; let x;", &mut state)
+    ///     .into_result()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(names[0], names[2]);
+    /// assert_eq!(state.lets, 3);
+    /// assert_eq!(state.interner.len(), 2);
     /// ```
-    fn ignore_with_ctx<U, P>(
-        self,
-        then: P,
-    ) -> IgnoreWithCtx<Self, P, O, I, extra::Full<E::Error, E::State, O>>
+    fn map_intern(self) -> MapIntern<Self, O>
     where
         Self: Sized,
-        O: 'a,
-        P: Parser<'a, I, U, extra::Full<E::Error, E::State, O>>,
+        O: AsRef<str>,
+        E::State: BorrowMut<intern::Interner>,
     {
-        IgnoreWithCtx {
+        MapIntern {
             parser: self,
-            then,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse one thing and then another thing, creating the second parser from the result of
-    /// the first. If you don't need the context in the output, prefer [`Parser::ignore_with_ctx`].
+    /// After a successful parse, apply a fallible function to the output. If the function produces an error, treat it
+    /// as a parsing error.
     ///
-    /// The output of this parser is `(E::Context, O)`,
-    /// a combination of the context and the output of the parser.
+    /// If you wish parsing of this pattern to continue when an error is generated instead of halting, consider using
+    /// [`Parser::validate`] instead.
     ///
-    /// Error recovery for this parser may be sub-optimal, as if the first parser succeeds on
-    /// recovery then the second produces an error, the primary error will point to the location in
-    /// the second parser which failed, ignoring that the first parser may be the root cause. There
-    /// may be other pathological errors cases as well.
-    fn then_with_ctx<U, P>(
-        self,
-        then: P,
-    ) -> ThenWithCtx<Self, P, O, I, extra::Full<E::Error, E::State, O>>
-    where
-        Self: Sized,
-        O: 'a,
-        P: Parser<'a, I, U, extra::Full<E::Error, E::State, O>>,
-    {
-        ThenWithCtx {
-            parser: self,
-            then,
-            phantom: EmptyPhantom::new(),
-        }
-    }
-
-    /// Run the previous contextual parser with the provided context
+    /// The output type of this parser is `U`, the [`Ok`] return value of the function.
+    ///
+    /// # Examples
     ///
     /// ```
     /// # use chumsky::prelude::*;
-    /// # use chumsky::primitive::JustCfg;
-    ///
-    /// let generic = just(b'0').configure(|cfg, ctx: &u8| cfg.seq(*ctx));
-    ///
-    /// let parse_a = just::<_, _, extra::Default>(b'b').ignore_then(generic.with_ctx::<u8>(b'a'));
-    /// let parse_b = just::<_, _, extra::Default>(b'a').ignore_then(generic.with_ctx(b'b'));
+    /// let byte = text::int::<_, _, extra::Err<Rich<char>>>(10)
+    ///     .try_map(|s: &str, span| s
+    ///         .parse::<u8>()
+    ///         .map_err(|e| Rich::custom(span, e)));
     ///
-    /// assert_eq!(parse_a.parse(b"ba" as &[_]).into_result(), Ok::<_, Vec<EmptyErr>>(b'a'));
-    /// assert!(parse_a.parse(b"bb").has_errors());
-    /// assert_eq!(parse_b.parse(b"ab" as &[_]).into_result(), Ok(b'b'));
-    /// assert!(parse_b.parse(b"aa").has_errors());
+    /// assert!(byte.parse("255").has_output());
+    /// assert!(byte.parse("256").has_errors()); // Out of range
     /// ```
-    fn with_ctx<Ctx>(self, ctx: Ctx) -> WithCtx<Self, Ctx>
+    #[doc(alias = "filter_map")]
+    fn try_map<U, F: Fn(O, I::Span) -> Result<U, E::Error>>(self, f: F) -> TryMap<Self, O, F>
     where
         Self: Sized,
-        Ctx: 'a + Clone,
     {
-        WithCtx { parser: self, ctx }
+        TryMap {
+            parser: self,
+            mapper: f,
+            phantom: EmptyPhantom::new(),
+        }
     }
 
-    /// TODO
-    fn with_state<State>(self, state: State) -> WithState<Self, State>
+    /// After a successful parse, apply a fallible function to the output, making use of the parser's state when
+    /// doing so. If the function produces an error, treat it as a parsing error.
+    ///
+    /// If you wish parsing of this pattern to continue when an error is generated instead of halting, consider using
+    /// [`Parser::validate`] instead.
+    ///
+    /// The output type of this parser is `U`, the [`Ok`] return value of the function.
+    fn try_map_with_state<U, F: Fn(O, I::Span, &mut E::State) -> Result<U, E::Error>>(
+        self,
+        f: F,
+    ) -> TryMapWithState<Self, O, F>
     where
         Self: Sized,
-        State: 'a + Clone,
     {
-        WithState {
+        TryMapWithState {
             parser: self,
-            state,
+            mapper: f,
+            phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Applies both parsers to the same position in the input, succeeding
-    /// only if both succeed. The returned value will be that of the first parser,
-    /// and the input will be at the end of the first parser if `and_is` succeeds.
+    /// Ignore the output of this parser, yielding `()` as an output instead.
     ///
-    /// The second parser is allowed to consume more or less input than the first parser,
-    /// but like its output, how much it consumes won't affect the final result.
+    /// This can be used to reduce the cost of parsing by avoiding unnecessary allocations (most collections containing
+    /// [ZSTs](https://doc.rust-lang.org/nomicon/exotic-sizes.html#zero-sized-types-zsts)
+    /// [do not allocate](https://doc.rust-lang.org/std/vec/struct.Vec.html#guarantees)). For example, it's common to
+    /// want to ignore whitespace in many grammars (see [`text::whitespace`]).
     ///
-    /// The motivating use-case is in combination with [`Parser::not`], allowing a parser
-    /// to consume something only if it isn't also something like an escape sequence or a nested block.
+    /// The output type of this parser is `()`.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    ///
-    /// let escape = just("\\n").to('\n');
-    ///
-    /// // C-style string literal
-    /// let string = none_of::<_, _, extra::Err<Simple<char>>>('"')
-    ///     .and_is(escape.not())
-    ///     .or(escape)
+    /// // A parser that parses any number of whitespace characters without allocating
+    /// let whitespace = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| c.is_whitespace())
+    ///     .ignored()
     ///     .repeated()
-    ///     .collect::<String>()
-    ///     .padded_by(just('"'));
+    ///     .collect::<Vec<_>>();
     ///
-    /// assert_eq!(
-    ///     string.parse("\"wxyz\"").into_result().as_deref(),
-    ///     Ok("wxyz"),
-    /// );
-    /// assert_eq!(
-    ///     string.parse("\"a\nb\"").into_result().as_deref(),
-    ///     Ok("a\nb"),
-    /// );
+    /// assert_eq!(whitespace.parse("    ").into_result(), Ok(vec![(); 4]));
+    /// assert!(whitespace.parse("  hello").has_errors());
     /// ```
-    fn and_is<U, B>(self, other: B) -> AndIs<Self, B, U>
+    fn ignored(self) -> Ignored<Self, O>
     where
         Self: Sized,
-        B: Parser<'a, I, U, E>,
     {
-        AndIs {
-            parser_a: self,
-            parser_b: other,
+        Ignored {
+            parser: self,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse the pattern surrounded by the given delimiters.
+    /// Memoize the parser such that later attempts to parse the same input 'remember' the attempt and exit early.
     ///
-    /// The output type of this parser is `O`, the same as the original parser.
+    /// If you're finding that certain inputs produce exponential behavior in your parser, strategically applying
+    /// memoization to a ['garden path'](https://en.wikipedia.org/wiki/Garden-path_sentence) rule is often an effective
+    /// way to solve the problem. At the limit, applying memoization to all combinators will turn any parser into one
+    /// with `O(n)`, albeit with very significant per-element overhead and high memory usage.
+    ///
+    /// Memoization also works with recursion, so this can be used to write parsers using
+    /// [left recursion](https://en.wikipedia.org/wiki/Left_recursion).
+    ///
+    /// Only failed attempts are remembered, and a failed attempt never contributes [nodes](Parser::node) to the
+    /// outline produced by [`Parser::parse_spans`], so memoized parsers produce the same outline as unmemoized ones.
+    // TODO: Example
+    #[cfg(feature = "memoization")]
+    fn memoized(self) -> Memoized<Self>
+    where
+        Self: Sized,
+    {
+        Memoized { parser: self }
+    }
+
+    /// Mark this parser as a node in the outline of the input produced by [`Parser::parse_spans`].
+    ///
+    /// When parsing normally, this has no effect: the output type of this parser is `O`, the same as the original
+    /// parser. See [`Parser::parse_spans`] for an example.
+    fn node(self) -> Node<Self>
+    where
+        Self: Sized,
+    {
+        Node { parser: self }
+    }
+
+    /// Transform all outputs of this parser to a predetermined value.
+    ///
+    /// The output type of this parser is `U`, the type of the predetermined value.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// // A LISP-style S-expression
-    /// #[derive(Debug, PartialEq)]
-    /// enum SExpr {
-    ///     Ident(String),
-    ///     Num(u64),
-    ///     List(Vec<SExpr>),
-    /// }
-    ///
-    /// let ident = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_alphabetic())
-    ///     .repeated()
-    ///     .at_least(1)
-    ///     .collect::<String>();
-    ///
-    /// let num = text::int(10)
-    ///     .from_str()
-    ///     .unwrapped();
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// enum Op { Add, Sub, Mul, Div }
     ///
-    /// let s_expr = recursive(|s_expr| s_expr
-    ///     .padded()
-    ///     .repeated()
-    ///     .collect::<Vec<_>>()
-    ///     .map(SExpr::List)
-    ///     .delimited_by(just('('), just(')'))
-    ///     .or(ident.map(SExpr::Ident))
-    ///     .or(num.map(SExpr::Num)));
+    /// let op = just::<_, _, extra::Err<Simple<char>>>('+').to(Op::Add)
+    ///     .or(just('-').to(Op::Sub))
+    ///     .or(just('*').to(Op::Mul))
+    ///     .or(just('/').to(Op::Div));
     ///
-    /// // A valid input
-    /// assert_eq!(
-    ///     s_expr.parse("(add (mul 42 3) 15)").into_result(),
-    ///     Ok(SExpr::List(vec![
-    ///         SExpr::Ident("add".to_string()),
-    ///         SExpr::List(vec![
-    ///             SExpr::Ident("mul".to_string()),
-    ///             SExpr::Num(42),
-    ///             SExpr::Num(3),
-    ///         ]),
-    ///         SExpr::Num(15),
-    ///     ])),
-    /// );
+    /// assert_eq!(op.parse("+").into_result(), Ok(Op::Add));
+    /// assert_eq!(op.parse("/").into_result(), Ok(Op::Div));
     /// ```
-    fn delimited_by<U, V, B, C>(self, start: B, end: C) -> DelimitedBy<Self, B, C, U, V>
+    fn to<U: Clone>(self, to: U) -> To<Self, O, U>
     where
         Self: Sized,
-        B: Parser<'a, I, U, E>,
-        C: Parser<'a, I, V, E>,
     {
-        DelimitedBy {
+        To {
             parser: self,
-            start,
-            end,
+            to,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse a pattern, but with an instance of another pattern on either end, yielding the output of the inner.
+    /// Label this parser with the given label.
     ///
-    /// The output type of this parser is `O`, the same as the original parser.
+    /// Labelling a parser makes all errors generated by the parser refer to the label rather than any sub-elements
+    /// within the parser. For example, labelling a parser for an expression would yield "expected expression" errors
+    /// rather than "expected integer, string, binary op, etc." errors.
+    // TODO: Example
+    #[cfg(feature = "label")]
+    fn labelled<L>(self, label: L) -> Labelled<Self, L>
+    where
+        Self: Sized,
+        E::Error: LabelError<'a, I, L>,
+    {
+        Labelled {
+            parser: self,
+            label,
+            is_context: false,
+        }
+    }
+
+    /// Mark this parser as a context, annotating any errors that occur within it with the given label.
+    ///
+    /// Unlike [`Parser::labelled`], this does not replace the expected patterns of errors generated by the parser.
+    /// Instead, the label and a span running from the start of the context to the error location are added to each
+    /// error's context stack, allowing error reporters to render traces like "while parsing function ➜ parameter
+    /// list". For [`Rich`] errors, the stack can be inspected with [`Rich::contexts`].
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
-    ///     .padded_by(just('!'));
+    /// # use chumsky::prelude::*;
+    /// let ident = text::ascii::ident::<_, _, extra::Err<Rich<char>>>().padded();
+    /// let params = ident
+    ///     .separated_by(just(','))
+    ///     .collect::<Vec<_>>()
+    ///     .delimited_by(just('('), just(')'))
+    ///     .context("parameter list");
+    /// let func = text::ascii::keyword("fn")
+    ///     .ignore_then(ident)
+    ///     .then(params)
+    ///     .context("function");
     ///
-    /// assert_eq!(ident.parse("!hello!").into_result(), Ok("hello"));
-    /// assert!(ident.parse("hello!").has_errors());
-    /// assert!(ident.parse("!hello").has_errors());
-    /// assert!(ident.parse("hello").has_errors());
+    /// let errs = func.parse("fn foo(a, b").into_errors();
+    /// let contexts = errs[0].contexts().map(|(l, s)| (*l, s.into_range())).collect::<Vec<_>>();
+    /// assert_eq!(contexts, vec![("parameter list", 6..11), ("function", 0..11)]);
     /// ```
-    fn padded_by<U, B>(self, padding: B) -> PaddedBy<Self, B, U>
+    #[cfg(feature = "label")]
+    fn context<L>(self, label: L) -> InContext<Self, L>
     where
         Self: Sized,
-        B: Parser<'a, I, U, E>,
+        E::Error: LabelError<'a, I, L>,
     {
-        PaddedBy {
+        InContext {
             parser: self,
-            padding,
-            phantom: EmptyPhantom::new(),
+            label,
         }
     }
 
-    /// Parse one thing or, on failure, another thing.
-    ///
-    /// The output of both parsers must be of the same type, because either output can be produced.
-    ///
-    /// If both parser succeed, the output of the first parser is guaranteed to be prioritized over the output of the
-    /// second.
-    ///
-    /// If both parsers produce errors, the combinator will attempt to select from or combine the errors to produce an
-    /// error that is most likely to be useful to a human attempting to understand the problem. The exact algorithm
-    /// used is left unspecified, and is not part of the crate's semver guarantees, although regressions in error
-    /// quality should be reported in the issue tracker of the main repository.
-    ///
-    /// Please note that long chains of [`Parser::or`] combinators have been known to result in poor compilation times.
-    /// If you feel you are experiencing this, consider using [`choice`] instead.
+    /// Parse one thing and then another thing, yielding a tuple of the two outputs.
     ///
-    /// The output type of this parser is `O`, the output of both parsers.
+    /// The output type of this parser is `(O, U)`, a combination of the outputs of both parsers.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let op = just::<_, _, extra::Err<Simple<char>>>('+')
-    ///     .or(just('-'))
-    ///     .or(just('*'))
-    ///     .or(just('/'));
+    /// let word = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| c.is_alphabetic())
+    ///     .repeated()
+    ///     .at_least(1)
+    ///     .collect::<String>();
+    /// let two_words = word.then_ignore(just(' ')).then(word);
     ///
-    /// assert_eq!(op.parse("+").into_result(), Ok('+'));
-    /// assert_eq!(op.parse("/").into_result(), Ok('/'));
-    /// assert!(op.parse("!").has_errors());
+    /// assert_eq!(two_words.parse("dog cat").into_result(), Ok(("dog".to_string(), "cat".to_string())));
+    /// assert!(two_words.parse("hedgehog").has_errors());
     /// ```
-    fn or<B>(self, other: B) -> Or<Self, B>
+    fn then<U, B: Parser<'a, I, U, E>>(self, other: B) -> Then<Self, B, O, U, E>
     where
         Self: Sized,
-        B: Parser<'a, I, O, E>,
     {
-        Or {
-            choice: choice((self, other)),
+        Then {
+            parser_a: self,
+            parser_b: other,
+            phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Attempt to parse something, but only if it exists.
-    ///
-    /// If parsing of the pattern is successful, the output is `Some(_)`. Otherwise, the output is `None`.
+    /// Parse one thing and then another thing, yielding only the output of the latter.
     ///
-    /// The output type of this parser is `Option<O>`.
+    /// The output type of this parser is `U`, the same as the second parser.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let word = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_alphabetic())
+    /// let zeroes = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| *c == '0').ignored().repeated().collect::<Vec<_>>();
+    /// let digits = any().filter(|c: &char| c.is_ascii_digit())
     ///     .repeated()
-    ///     .at_least(1)
     ///     .collect::<String>();
+    /// let integer = zeroes
+    ///     .ignore_then(digits)
+    ///     .from_str()
+    ///     .unwrapped();
     ///
-    /// let word_or_question = word
-    ///     .then(just('?').or_not());
-    ///
-    /// assert_eq!(word_or_question.parse("hello?").into_result(), Ok(("hello".to_string(), Some('?'))));
-    /// assert_eq!(word_or_question.parse("wednesday").into_result(), Ok(("wednesday".to_string(), None)));
+    /// assert_eq!(integer.parse("00064").into_result(), Ok(64));
+    /// assert_eq!(integer.parse("32").into_result(), Ok(32));
     /// ```
-    fn or_not(self) -> OrNot<Self>
+    fn ignore_then<U, B: Parser<'a, I, U, E>>(self, other: B) -> IgnoreThen<Self, B, O, E>
     where
         Self: Sized,
     {
-        OrNot { parser: self }
+        IgnoreThen {
+            parser_a: self,
+            parser_b: other,
+            phantom: EmptyPhantom::new(),
+        }
     }
 
-    /// Invert the result of the contained parser, failing if it succeeds and succeeding if it fails.
-    /// The output of this parser is always `()`, the unit type.
+    /// Parse one thing and then another thing, yielding only the output of the former.
     ///
-    /// The motivating case for this is in combination with [`Parser::and_is`], allowing a parser
-    /// to consume something only if it isn't also something like an escape sequence or a nested block.
+    /// The output type of this parser is `O`, the same as the original parser.
     ///
-    /// Caveats:
-    /// - The error message produced by `not` by default will likely be fairly unhelpful - it can
-    ///   only tell the span that was wrong.
-    /// - If not careful, it's fairly easy to create non-intuitive behavior due to end-of-input
-    ///   being a valid token for a parser to consume, and as most parsers fail at end of input,
-    ///   `not` will succeed on it.
+    /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
+    /// let word = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| c.is_alphabetic())
+    ///     .repeated()
+    ///     .at_least(1)
+    ///     .collect::<String>();
     ///
-    /// #[derive(Debug, PartialEq)]
-    /// enum Tree<'a> {
-    ///     Text(&'a str),
-    ///     Group(Vec<Self>),
-    /// }
-    ///
-    /// // Arbitrary text, nested in a tree with { ... } delimiters
-    /// let tree = recursive::<_, _, extra::Err<Simple<char>>, _, _>(|tree| {
-    ///     let text = any()
-    ///         .and_is(one_of("{}").not())
-    ///         .repeated()
-    ///         .at_least(1)
-    ///         .map_slice(Tree::Text);
-    ///
-    ///     let group = tree
-    ///         .repeated()
-    ///         .collect()
-    ///         .delimited_by(just('{'), just('}'))
-    ///         .map(Tree::Group);
+    /// let punctuated = word
+    ///     .then_ignore(just('!').or(just('?')).or_not());
     ///
-    ///     text.or(group)
-    /// });
+    /// let sentence = punctuated
+    ///     .padded() // Allow for whitespace gaps
+    ///     .repeated()
+    ///     .collect::<Vec<_>>();
     ///
     /// assert_eq!(
-    ///     tree.parse("{abcd{efg{hijk}lmn{opq}rs}tuvwxyz}").into_result(),
-    ///     Ok(Tree::Group(vec![
-    ///         Tree::Text("abcd"),
-    ///         Tree::Group(vec![
-    ///             Tree::Text("efg"),
-    ///             Tree::Group(vec![
-    ///                 Tree::Text("hijk"),
-    ///             ]),
-    ///             Tree::Text("lmn"),
-    ///             Tree::Group(vec![
-    ///                 Tree::Text("opq"),
-    ///             ]),
-    ///             Tree::Text("rs"),
-    ///         ]),
-    ///         Tree::Text("tuvwxyz"),
-    ///     ])),
+    ///     sentence.parse("hello! how are you?").into_result(),
+    ///     Ok(vec![
+    ///         "hello".to_string(),
+    ///         "how".to_string(),
+    ///         "are".to_string(),
+    ///         "you".to_string(),
+    ///     ]),
     /// );
     /// ```
-    fn not(self) -> Not<Self, O>
+    fn then_ignore<U, B: Parser<'a, I, U, E>>(self, other: B) -> ThenIgnore<Self, B, U, E>
     where
         Self: Sized,
     {
-        Not {
-            parser: self,
+        ThenIgnore {
+            parser_a: self,
+            parser_b: other,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse a pattern zero or more times (analog to Regex's `<PAT>*`).
+    /// Parse one thing, then a separator, then another thing, yielding a tuple of the two things' outputs.
     ///
-    /// Input is eagerly parsed. Be aware that the parser will accept no occurrences of the pattern too. Consider using
-    /// [`Repeated::at_least`] instead if it better suits your use-case.
+    /// This is equivalent to `self.then_ignore(separator).then(other)`, and is named after the combinator of the same
+    /// name in [`nom`](https://docs.rs/nom), which makes shapes like `key = value` read more directly.
     ///
-    /// The output type of this parser can be any [`Container`].
+    /// The output type of this parser is `(O, U)`, a combination of the outputs of the first and last parsers.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let num = any::<_, extra::Err<Simple<char>>>()
-    ///     .filter(|c: &char| c.is_ascii_digit())
-    ///     .repeated()
-    ///     .at_least(1)
-    ///     .collect::<String>()
-    ///     .from_str()
-    ///     .unwrapped();
-    ///
-    /// let sum = num.clone()
-    ///     .foldl(just('+').ignore_then(num).repeated(), |a, b| a + b);
+    /// let entry = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .separated_pair(just('=').padded(), text::int(10));
     ///
-    /// assert_eq!(sum.parse("2+13+4+0+5").into_result(), Ok(24));
+    /// assert_eq!(entry.parse("width = 80").into_result(), Ok(("width", "80")));
+    /// assert!(entry.parse("width 80").has_errors());
     /// ```
-    #[cfg_attr(debug_assertions, track_caller)]
-    fn repeated(self) -> Repeated<Self, O, I, E>
+    fn separated_pair<U, V, S, B>(self, separator: S, other: B) -> SeparatedPair<Self, S, B, V>
     where
+        S: Parser<'a, I, V, E>,
+        B: Parser<'a, I, U, E>,
         Self: Sized,
     {
-        Repeated {
-            parser: self,
-            at_least: 0,
-            at_most: !0,
-            #[cfg(debug_assertions)]
-            location: *Location::caller(),
+        SeparatedPair {
+            parser_a: self,
+            separator,
+            parser_b: other,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse a pattern, separated by another, any number of times.
+    /// Parse a prefix and then this pattern, yielding only the output of this pattern.
     ///
-    /// You can use [`SeparatedBy::allow_leading`] or [`SeparatedBy::allow_trailing`] to allow leading or trailing
-    /// separators.
+    /// This is equivalent to `prefix.ignore_then(self)`, and is named after the combinator of the same name in
+    /// [`nom`](https://docs.rs/nom).
     ///
-    /// The output type of this parser can be any [`Container`].
+    /// The output type of this parser is `O`, the same as the original parser.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let shopping = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
-    ///     .padded()
-    ///     .separated_by(just(','))
-    ///     .collect::<Vec<_>>();
+    /// let var = text::ascii::ident::<_, _, extra::Err<Simple<char>>>().preceded(just('$'));
     ///
-    /// assert_eq!(shopping.parse("eggs").into_result(), Ok(vec!["eggs"]));
-    /// assert_eq!(shopping.parse("eggs, flour, milk").into_result(), Ok(vec!["eggs", "flour", "milk"]));
+    /// assert_eq!(var.parse("$home").into_result(), Ok("home"));
+    /// assert!(var.parse("home").has_errors());
     /// ```
-    ///
-    /// See [`SeparatedBy::allow_leading`] and [`SeparatedBy::allow_trailing`] for more examples.
-    #[cfg_attr(debug_assertions, track_caller)]
-    fn separated_by<U, B>(self, separator: B) -> SeparatedBy<Self, B, O, U, I, E>
+    fn preceded<U, B: Parser<'a, I, U, E>>(self, prefix: B) -> Preceded<Self, B, U>
     where
         Self: Sized,
-        B: Parser<'a, I, U, E>,
     {
-        SeparatedBy {
+        Preceded {
             parser: self,
-            separator,
-            at_least: 0,
-            at_most: !0,
-            allow_leading: false,
-            allow_trailing: false,
-            #[cfg(debug_assertions)]
-            location: *Location::caller(),
+            prefix,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Left-fold the output of the parser into a single value.
+    /// Parse this pattern and then a suffix, yielding only the output of this pattern.
     ///
-    /// The output of the original parser must be of type `(A, impl IntoIterator<Item = B>)`.
+    /// This is equivalent to `self.then_ignore(suffix)`, and is named after the combinator of the same name in
+    /// [`nom`](https://docs.rs/nom).
     ///
-    /// The output type of this parser is `A`, the left-hand component of the original parser's output.
+    /// The output type of this parser is `O`, the same as the original parser.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
-    ///     .from_str()
-    ///     .unwrapped();
-    ///
-    /// let sum = int
-    ///     .clone()
-    ///     .foldl(just('+').ignore_then(int).repeated(), |a, b| a + b);
+    /// let stmt = text::ascii::ident::<_, _, extra::Err<Simple<char>>>().terminated(just(';'));
     ///
-    /// assert_eq!(sum.parse("1+12+3+9").into_result(), Ok(25));
-    /// assert_eq!(sum.parse("6").into_result(), Ok(6));
+    /// assert_eq!(stmt.parse("return;").into_result(), Ok("return"));
+    /// assert!(stmt.parse("return").has_errors());
     /// ```
-    #[cfg_attr(debug_assertions, track_caller)]
-    fn foldl<B, F, OB>(self, other: B, f: F) -> Foldl<F, Self, B, OB, E>
+    fn terminated<U, B: Parser<'a, I, U, E>>(self, suffix: B) -> Terminated<Self, B, U>
     where
-        F: Fn(O, OB) -> O,
-        B: IterParser<'a, I, OB, E>,
         Self: Sized,
     {
-        Foldl {
-            parser_a: self,
-            parser_b: other,
-            folder: f,
-            #[cfg(debug_assertions)]
-            location: *Location::caller(),
+        Terminated {
+            parser: self,
+            suffix,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Left-fold the output of the parser into a single value, making use of the parser's state when doing so.
+    /// Parse input as part of a token-tree - using an input generated from within the current
+    /// input. In other words, this parser will attempt to create a *new* input stream from within
+    /// the one it is being run on, and the parser it was called on will be provided this *new* input.
+    /// By default, the original parser is expected to consume up to the end of the new stream. To
+    /// allow only consuming part of the stream, use [`Parser::lazy`] to ignore trailing tokens.
     ///
-    /// The output of the original parser must be of type `(A, impl IntoIterator<Item = B>)`.
+    /// The provided parser `P` is expected to have both an input and output type which match the input
+    /// type of the parser it is called on. As an example, if the original parser takes an input of
+    /// `Stream<Iterator<Item = T>>`, `P` will be run first against that input, and is expected to
+    /// output a new `Stream<Iterator<Item = T>>` which the original parser will be run against.
     ///
-    /// The output type of this parser is `A`, the left-hand component of the original parser's output.
+    /// The output of this parser is `O`, the output of the parser it is called on.
     ///
     /// # Examples
     ///
-    /// ## General
-    ///
-    /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let int = text::int::<_, _, extra::Full<Simple<char>, i32, ()>>(10)
-    ///     .from_str()
-    ///     .unwrapped();
-    ///
-    /// let sum = int
-    ///     .clone()
-    ///     .foldl_with_state(just('+').ignore_then(int).repeated(), |a, b, state| (a + b) * *state);
-    ///
-    /// let mut multiplier = 2i32;
-    /// assert_eq!(sum.parse_with_state("1+12+3+9", &mut multiplier).into_result(), Ok(134));
-    /// assert_eq!(sum.parse_with_state("6", &mut multiplier).into_result(), Ok(6));
     /// ```
+    /// # use chumsky::{prelude::*, util::MaybeRef, error::Simple};
+    /// #[derive(Debug, Clone, PartialEq)]
+    /// enum Token<'a> {
+    ///     Struct,
+    ///     Ident(&'a str),
+    ///     Item(&'a str),
+    ///     Group(Vec<Token<'a>>),
+    /// }
     ///
-    /// ## Interning / Arena Allocation
-    ///
-    /// This example assumes use of the `slotmap` crate for arena allocation.
-    ///
-    /// ```
-    /// # use chumsky::prelude::*;
-    /// use slotmap::{new_key_type, SlotMap};
+    /// let group = select_ref! { Token::Group(g) => g.as_slice() };
     ///
-    /// // Metadata type for node Ids for extra type safety
-    /// new_key_type! {
-    ///    pub struct NodeId;
-    /// }
+    /// let ident = select_ref! { Token::Ident(i) => *i };
     ///
-    /// // AST nodes reference other nodes with `NodeId`s instead of containing boxed/owned values
-    /// #[derive(Copy, Clone, Debug, PartialEq)]
-    /// enum Expr {
-    ///     Int(i32),
-    ///     Add(NodeId, NodeId),
-    /// }
+    /// let items = select_ref! { Token::Item(i) => *i }
+    ///     .repeated()
+    ///     .collect::<Vec<_>>()
+    ///     .nested_in(group);
     ///
-    /// type NodeArena = SlotMap<NodeId, Expr>;
+    /// let struc = just::<_, _, extra::Err<Simple<_>>>(&Token::Struct)
+    ///     .ignore_then(ident)
+    ///     .then(items);
     ///
-    /// // Now, define our parser
-    /// let int = text::int::<&str, _, extra::Full<Simple<char>, NodeArena, ()>>(10)
-    ///     .padded()
-    ///     .map_with_state(|s, _, state: &mut NodeArena|
-    ///         // Return the ID of the new integer node
-    ///         state.insert(Expr::Int(s.parse().unwrap()))
-    ///     );
+    /// let tl = struc
+    ///     .repeated()
+    ///     .collect::<Vec<_>>();
     ///
-    /// let sum = int.foldl_with_state(
-    ///     just('+').padded().ignore_then(int).repeated(),
-    ///     |a: NodeId, b: NodeId, state: &mut NodeArena| {
-    ///         // Inserting an item into the arena returns its ID
-    ///         state.insert(Expr::Add(a, b))
-    ///     }
-    /// );
+    /// let tokens = [
+    ///     Token::Struct,
+    ///     Token::Ident("foo"),
+    ///     Token::Group(vec![
+    ///         Token::Item("a"),
+    ///         Token::Item("b"),
+    ///     ]),
+    /// ];
     ///
-    /// // Test our parser
-    /// let mut arena = NodeArena::default();
-    /// let four_plus_eight = sum.parse_with_state("4 + 8", &mut arena).unwrap();
-    /// if let Expr::Add(a, b) = arena[four_plus_eight] {
-    ///     assert_eq!(arena[a], Expr::Int(4));
-    ///     assert_eq!(arena[b], Expr::Int(8));
-    /// } else {
-    ///     panic!("Not an Expr::Add");
-    /// }
+    /// assert_eq!(tl.parse(&tokens).into_result(), Ok(vec![("foo", vec!["a", "b"])]));
     /// ```
-    #[cfg_attr(debug_assertions, track_caller)]
-    fn foldl_with_state<B, F, OB>(self, other: B, f: F) -> FoldlWithState<F, Self, B, OB, E>
+    fn nested_in<B: Parser<'a, I, I, E>>(self, other: B) -> NestedIn<Self, B, O, E>
     where
-        F: Fn(O, OB, &mut E::State) -> O,
-        B: IterParser<'a, I, OB, E>,
         Self: Sized,
+        I: 'a,
     {
-        FoldlWithState {
+        NestedIn {
             parser_a: self,
             parser_b: other,
-            folder: f,
-            #[cfg(debug_assertions)]
-            location: *Location::caller(),
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Parse a pattern. Afterwards, the input stream will be rewound to its original state, as if parsing had not
-    /// occurred.
+    /// Run this parser on a different kind of input, projected from the output of a parser for the outer input.
     ///
-    /// This combinator is useful for cases in which you wish to avoid a parser accidentally consuming too much input,
-    /// causing later parsers to fail as a result. A typical use-case of this is that you want to parse something that
-    /// is not followed by something else.
+    /// `outer` is run first. Its output is passed to `project` to produce the input for this parser, which must then
+    /// consume all of it. Errors produced by this parser are passed to `map_err` along with the span of the input that
+    /// `outer` consumed, allowing them to be translated into the error and span types of the outer parser.
     ///
-    /// The output type of this parser is `O`, the same as the original parser.
+    /// This is useful for layered formats, such as a binary protocol with a textual payload, that would otherwise
+    /// require a separate parsing pass.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::prelude::*;
-    /// let just_numbers = text::digits::<_, _, extra::Err<Simple<char>>>(10)
-    ///     .slice()
-    ///     .padded()
-    ///     .then_ignore(none_of("+-*/").rewind())
-    ///     .separated_by(just(','))
+    /// let ident = text::ascii::ident::<&str, _, extra::Err<Rich<char>>>();
+    ///
+    /// // Identifiers carried by a byte-level protocol in which each payload is terminated by a `;`
+    /// let payload = ident.map_input(
+    ///     none_of::<_, _, extra::Err<Rich<u8>>>(b';').repeated().slice(),
+    ///     |bytes: &[u8]| core::str::from_utf8(bytes).unwrap_or(""),
+    ///     |err: Rich<char>, span: SimpleSpan| -> Rich<u8> {
+    ///         let inner = err.span();
+    ///         Rich::custom((span.start + inner.start..span.start + inner.end).into(), err)
+    ///     },
+    /// );
+    ///
+    /// let packets = payload
+    ///     .then_ignore(just(b';'))
+    ///     .repeated()
     ///     .collect::<Vec<_>>();
-    /// // 3 is not parsed because it's followed by '+'.
-    /// assert_eq!(just_numbers.lazy().parse("1, 2, 3 + 4").into_result(), Ok(vec!["1", "2"]));
+    ///
+    /// assert_eq!(packets.parse(b"foo;bar;".as_slice()).into_result(), Ok(vec!["foo", "bar"]));
+    ///
+    /// // The error span refers to the position within the byte input
+    /// let errs = packets.parse(b"foo;b@r;".as_slice()).into_errors();
+    /// assert_eq!(errs[0].span(), &SimpleSpan::from(5..6));
     /// ```
-    fn rewind(self) -> Rewind<Self>
+    fn map_input<B, OB, F, G>(
+        self,
+        outer: B,
+        project: F,
+        map_err: G,
+    ) -> MapInput<Self, B, OB, F, G, I, E>
     where
         Self: Sized,
+        F: Fn(OB) -> I,
     {
-        Rewind { parser: self }
+        MapInput {
+            parser: self,
+            outer,
+            project,
+            map_err,
+            phantom: EmptyPhantom::new(),
+        }
     }
 
-    /// Make the parser lazy, such that it parses as much as it validly can and then finished successfully, leaving
-    /// trailing input untouched.
+    /// Parse one thing and then another thing, creating the second parser from the result of
+    /// the first. If you don't need the context in the output, use [`Parser::then_with_ctx`].
     ///
-    /// The output type of this parser is `O`, the same as the original parser.
+    /// The output of this parser is `U`, the result of the second parser
+    ///
+    /// Error recovery for this parser may be sub-optimal, as if the first parser succeeds on
+    /// recovery then the second produces an error, the primary error will point to the location in
+    /// the second parser which failed, ignoring that the first parser may be the root cause. There
+    /// may be other pathological errors cases as well.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::prelude::*;
-    /// let digits = one_of::<_, _, extra::Err<Simple<char>>>('0'..='9')
-    ///     .repeated()
-    ///     .collect::<String>()
-    ///     .lazy();
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let successor = just(b'\0').configure(|cfg, ctx: &u8| cfg.seq(*ctx + 1));
     ///
-    /// assert_eq!(digits.parse("12345abcde").into_result().as_deref(), Ok("12345"));
+    /// // A parser that parses a single letter and then its successor
+    /// let successive_letters = one_of::<_, _, extra::Err<Simple<u8>>>(b'a'..=b'z')
+    ///     .ignore_with_ctx(successor);
+    ///
+    /// assert_eq!(successive_letters.parse(b"ab").into_result(), Ok(b'b')); // 'b' follows 'a'
+    /// assert!(successive_letters.parse(b"ac").has_errors()); // 'c' does not follow 'a'
     /// ```
-    fn lazy(self) -> Lazy<'a, Self, I, E>
+    fn ignore_with_ctx<U, P>(
+        self,
+        then: P,
+    ) -> IgnoreWithCtx<Self, P, O, I, extra::Full<E::Error, E::State, O>>
     where
         Self: Sized,
-        I: ValueInput<'a>,
+        O: 'a,
+        P: Parser<'a, I, U, extra::Full<E::Error, E::State, O>>,
     {
-        self.then_ignore(any().repeated())
+        IgnoreWithCtx {
+            parser: self,
+            then,
+            phantom: EmptyPhantom::new(),
+        }
     }
 
-    /// Parse a pattern, ignoring any amount of whitespace both before and after the pattern.
+    /// Parse one thing and then another thing, creating the second parser from the result of
+    /// the first. If you don't need the context in the output, prefer [`Parser::ignore_with_ctx`].
     ///
-    /// The output type of this parser is `O`, the same as the original parser.
+    /// The output of this parser is `(E::Context, O)`,
+    /// a combination of the context and the output of the parser.
     ///
-    /// # Examples
+    /// Error recovery for this parser may be sub-optimal, as if the first parser succeeds on
+    /// recovery then the second produces an error, the primary error will point to the location in
+    /// the second parser which failed, ignoring that the first parser may be the root cause. There
+    /// may be other pathological errors cases as well.
+    fn then_with_ctx<U, P>(
+        self,
+        then: P,
+    ) -> ThenWithCtx<Self, P, O, I, extra::Full<E::Error, E::State, O>>
+    where
+        Self: Sized,
+        O: 'a,
+        P: Parser<'a, I, U, extra::Full<E::Error, E::State, O>>,
+    {
+        ThenWithCtx {
+            parser: self,
+            then,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Run the previous contextual parser with the provided context
     ///
     /// ```
     /// # use chumsky::prelude::*;
-    /// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>().padded();
+    /// # use chumsky::primitive::JustCfg;
     ///
-    /// // A pattern with no whitespace surrounding it is accepted
-    /// assert_eq!(ident.parse("hello").into_result(), Ok("hello"));
-    /// // A pattern with arbitrary whitespace surrounding it is also accepted
-    /// assert_eq!(ident.parse(" \t \n  \t   world  \t  ").into_result(), Ok("world"));
+    /// let generic = just(b'0').configure(|cfg, ctx: &u8| cfg.seq(*ctx));
+    ///
+    /// let parse_a = just::<_, _, extra::Default>(b'b').ignore_then(generic.with_ctx::<u8>(b'a'));
+    /// let parse_b = just::<_, _, extra::Default>(b'a').ignore_then(generic.with_ctx(b'b'));
+    ///
+    /// assert_eq!(parse_a.parse(b"ba" as &[_]).into_result(), Ok::<_, Vec<EmptyErr>>(b'a'));
+    /// assert!(parse_a.parse(b"bb").has_errors());
+    /// assert_eq!(parse_b.parse(b"ab" as &[_]).into_result(), Ok(b'b'));
+    /// assert!(parse_b.parse(b"aa").has_errors());
     /// ```
-    fn padded(self) -> Padded<Self>
+    fn with_ctx<Ctx>(self, ctx: Ctx) -> WithCtx<Self, Ctx>
     where
         Self: Sized,
-        I: Input<'a>,
-        I::Token: Char,
+        Ctx: 'a + Clone,
     {
-        Padded { parser: self }
+        WithCtx { parser: self, ctx }
     }
 
-    // /// Flatten a nested collection.
-    // ///
-    // /// This use-cases of this method are broadly similar to those of [`Iterator::flatten`].
-    // ///
-    // /// The output type of this parser is `Vec<T>`, where the original parser output was
-    // /// `impl IntoIterator<Item = impl IntoIterator<Item = T>>`.
-    // fn flatten<T, Inner>(self) -> Map<Self, O, fn(O) -> Vec<T>>
-    // where
-    //     Self: Sized,
-    //     O: IntoIterator<Item = Inner>,
-    //     Inner: IntoIterator<Item = T>,
-    // {
-    //     self.map(|xs| xs.into_iter().flat_map(|xs| xs.into_iter()).collect())
-    // }
-
-    /// Apply a fallback recovery strategy to this parser should it fail.
-    ///
-    /// There is no silver bullet for error recovery, so this function allows you to specify one of several different
-    /// strategies at the location of your choice. Prefer an error recovery strategy that more precisely mirrors valid
-    /// syntax where possible to make error recovery more reliable.
+    /// Parse a token stream in which the given trivia tokens are skipped wherever they appear.
     ///
-    /// Because chumsky is a [PEG](https://en.m.wikipedia.org/wiki/Parsing_expression_grammar) parser, which always
-    /// take the first successful parsing route through a grammar, recovering from an error may cause the parser to
-    /// erroneously miss alternative valid routes through the grammar that do not generate recoverable errors. If you
-    /// run into cases where valid syntax fails to parse without errors, this might be happening: consider removing
-    /// error recovery or switching to a more specific error recovery strategy.
+    /// Before each token is read, any trivia tokens that precede it are skipped, so the rest of the grammar does not need
+    /// to mention them. Use [`significant`] to match trivia tokens explicitly, such as a newline that ends a statement.
+    /// Spans and slices of patterns do not include the trivia before them. Only the trivia tokens between the patterns
+    /// of this parser are skipped, so wrap the whole grammar, including any [`end`], to skip trailing trivia too.
     ///
     /// The output type of this parser is `O`, the same as the original parser.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// #[derive(Debug, PartialEq)]
-    /// enum Expr<'a> {
-    ///     Error,
-    ///     Int(&'a str),
-    ///     List(Vec<Expr<'a>>),
-    /// }
-    ///
-    /// let recovery = just::<_, _, extra::Err<Simple<char>>>('[')
-    ///         .then(none_of(']').repeated().then(just(']')));
+    /// # use chumsky::prelude::*;
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// enum Token { Ident(&'static str), Plus, Semi, Newline }
     ///
-    /// let expr = recursive::<_, _, extra::Err<Simple<char>>, _, _>(|expr| expr
-    ///     .separated_by(just(','))
+    /// let expr = select! { Token::Ident(x) => x }
+    ///     .separated_by(just::<_, _, extra::Err<Simple<Token>>>(Token::Plus))
+    ///     .at_least(1)
+    ///     .collect::<Vec<_>>();
+    /// // A statement ends with a semicolon, or with a newline once the expression is complete
+    /// let stmt = expr.then_ignore(just(Token::Semi).or(significant(just(Token::Newline))));
+    /// let stmts = stmt
+    ///     .repeated()
     ///     .collect::<Vec<_>>()
-    ///     .delimited_by(just('['), just(']'))
-    ///     .map(Expr::List)
-    ///     // If parsing a list expression fails, recover at the next delimiter, generating an error AST node
-    ///     .recover_with(via_parser(recovery.map(|_| Expr::Error)))
-    ///     .or(text::int(10).map(Expr::Int))
-    ///     .padded());
+    ///     .then_ignore(end())
+    ///     .with_trivia(|tok: &Token| *tok == Token::Newline);
     ///
-    /// assert!(expr.parse("five").has_errors()); // Text is not a valid expression in this language...
+    /// use Token::*;
+    /// let tokens = [Ident("a"), Plus, Newline, Ident("b"), Newline, Newline, Ident("c"), Semi, Newline];
     /// assert_eq!(
-    ///     expr.parse("[1, 2, 3]").into_result(),
-    ///     Ok(Expr::List(vec![Expr::Int("1"), Expr::Int("2"), Expr::Int("3")])),
-    /// ); // ...but lists and numbers are!
-    ///
-    /// // This input has two syntax errors...
-    /// let res = expr.parse("[[1, two], [3, four]]");
-    /// // ...and error recovery allows us to catch both of them!
-    /// assert_eq!(res.errors().len(), 2);
-    /// // Additionally, the AST we get back still has useful information.
-    /// assert_eq!(res.output(), Some(&Expr::List(vec![Expr::Error, Expr::Error])));
+    ///     stmts.parse(&tokens[..]).into_result(),
+    ///     Ok(vec![vec!["a", "b"], vec!["c"]]),
+    /// );
     /// ```
-    fn recover_with<S: Strategy<'a, I, O, E>>(self, strategy: S) -> RecoverWith<Self, S>
-    where
-        Self: Sized,
-    {
-        RecoverWith {
-            parser: self,
-            strategy,
-        }
-    }
-
-    /// Map the primary error of this parser to another value.
-    ///
-    /// This function is most useful when using a custom error type, allowing you to augment errors according to
-    /// context.
-    ///
-    /// The output type of this parser is `O`, the same as the original parser.
-    // TODO: Map E -> D, not E -> E
-    fn map_err<F>(self, f: F) -> MapErr<Self, F>
+    fn with_trivia(self, trivia: fn(&I::Token) -> bool) -> WithTrivia<Self, I::Token>
     where
         Self: Sized,
-        F: Fn(E::Error) -> E::Error,
     {
-        MapErr {
+        WithTrivia {
             parser: self,
-            mapper: f,
+            trivia,
         }
     }
 
-    // /// Map the primary error of this parser to another value, making use of the span from the start of the attempted
-    // /// to the point at which the error was encountered.
-    // ///
-    // /// This function is useful for augmenting errors to allow them to display the span of the initial part of a
-    // /// pattern, for example to add a "while parsing" clause to your error messages.
-    // ///
-    // /// The output type of this parser is `O`, the same as the original parser.
-    // ///
-    // // TODO: Map E -> D, not E -> E
-    // fn map_err_with_span<F>(self, f: F) -> MapErrWithSpan<Self, F>
-    // where
-    //     Self: Sized,
-    //     F: Fn(E::Error, I::Span) -> E::Error,
-    // {
-    //     MapErrWithSpan {
-    //         parser: self,
-    //         mapper: f,
-    //     }
-    // }
-
-    /// Map the primary error of this parser to another value, making use of the parser state.
-    ///
-    /// This function is useful for augmenting errors to allow them to include context in non context-free
-    /// languages, or provide contextual notes on possible causes.
-    ///
-    /// The output type of this parser is `O`, the same as the original parser.
-    ///
-    // TODO: Map E -> D, not E -> E
-    fn map_err_with_state<F>(self, f: F) -> MapErrWithState<Self, F>
+    /// TODO
+    fn with_state<State>(self, state: State) -> WithState<Self, State>
     where
         Self: Sized,
-        F: Fn(E::Error, I::Span, &mut E::State) -> E::Error,
+        State: 'a + Clone,
     {
-        MapErrWithState {
+        WithState {
             parser: self,
-            mapper: f,
+            state,
         }
     }
 
-    /// Validate an output, producing non-terminal errors if it does not fulfill certain criteria.
-    /// The errors will not immediately halt parsing on this path, but instead it will continue,
-    /// potentially emitting one or more other errors, only failing after the pattern has otherwise
-    /// successfully, or emitted another terminal error.
-    ///
-    /// This function also permits mapping the output to a value of another type, similar to [`Parser::map`].
+    /// Applies both parsers to the same position in the input, succeeding
+    /// only if both succeed. The returned value will be that of the first parser,
+    /// and the input will be at the end of the first parser if `and_is` succeeds.
     ///
-    /// If you wish parsing of this pattern to halt when an error is generated instead of continuing, consider using
-    /// [`Parser::try_map`] instead.
+    /// The second parser is allowed to consume more or less input than the first parser,
+    /// but like its output, how much it consumes won't affect the final result.
     ///
-    /// The output type of this parser is `U`, the result of the validation closure.
+    /// The motivating use-case is in combination with [`Parser::not`], allowing a parser
+    /// to consume something only if it isn't also something like an escape sequence or a nested block.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::prelude::*;
-    /// let large_int = text::int::<_, _, extra::Err<Rich<char>>>(10)
-    ///     .from_str()
-    ///     .unwrapped()
-    ///     .validate(|x: u32, span, emitter| {
-    ///         if x < 256 { emitter.emit(Rich::custom(span, format!("{} must be 256 or higher.", x))) }
-    ///         x
-    ///     });
-    ///
-    /// assert_eq!(large_int.parse("537").into_result(), Ok(537));
-    /// assert!(large_int.parse("243").into_result().is_err());
-    /// ```
-    ///
-    /// To show the difference in behavior from [`Parser::try_map`]:
-    ///
-    /// ```
-    /// # use chumsky::prelude::*;
-    /// # use chumsky::util::MaybeRef;
-    /// # use chumsky::error::Error;
-    /// // start with the same large_int validator
-    /// let large_int_val = text::int::<_, _, extra::Err<Rich<char>>>(10)
-    ///         .from_str()
-    ///         .unwrapped()
-    ///         .validate(|x: u32, span, emitter| {
-    ///             if x < 256 { emitter.emit(Rich::custom(span, format!("{} must be 256 or higher", x))) }
-    ///             x
-    ///         });
-    ///
-    /// // A try_map version of the same parser
-    /// let large_int_tm = text::int::<_, _, extra::Err<Rich<char>>>(10)
-    ///         .from_str()
-    ///         .unwrapped()
-    ///         .try_map(|x: u32, span| {
-    ///             if x < 256 {
-    ///                 Err(Rich::custom(span, format!("{} must be 256 or higher", x)))
-    ///             } else {
-    ///                 Ok(x)
-    ///             }
-    ///         });
-    ///
-    /// // Parser that uses the validation version
-    /// let multi_step_val = large_int_val.then(text::ascii::ident().padded());
-    /// // Parser that uses the try_map version
-    /// let multi_step_tm = large_int_tm.then(text::ascii::ident().padded());
+    /// # use chumsky::{prelude::*, error::Simple};
     ///
-    /// // On success, both parsers are equivalent
-    /// assert_eq!(
-    ///     multi_step_val.parse("512 foo").into_result(),
-    ///     Ok((512, "foo"))
-    /// );
+    /// let escape = just("\\n").to('\n');
     ///
-    /// assert_eq!(
-    ///     multi_step_tm.parse("512 foo").into_result(),
-    ///     Ok((512, "foo"))
-    /// );
+    /// // C-style string literal
+    /// let string = none_of::<_, _, extra::Err<Simple<char>>>('"')
+    ///     .and_is(escape.not())
+    ///     .or(escape)
+    ///     .repeated()
+    ///     .collect::<String>()
+    ///     .padded_by(just('"'));
     ///
-    /// // However, on failure, they may produce different errors:
     /// assert_eq!(
-    ///     multi_step_val.parse("100 2").into_result(),
-    ///     Err(vec![
-    ///         Rich::<char>::custom((0..3).into(), "100 must be 256 or higher"),
-    ///         <Rich<char> as Error<&str>>::expected_found([], Some(MaybeRef::Val('2')), (4..5).into()),
-    ///     ])
+    ///     string.parse("\"wxyz\"").into_result().as_deref(),
+    ///     Ok("wxyz"),
     /// );
-    ///
     /// assert_eq!(
-    ///     multi_step_tm.parse("100 2").into_result(),
-    ///     Err(vec![Rich::<char>::custom((0..3).into(), "100 must be 256 or higher")])
+    ///     string.parse("\"a\nb\"").into_result().as_deref(),
+    ///     Ok("a\nb"),
     /// );
     /// ```
-    ///
-    /// As is seen in the above example, validation doesn't prevent the emission of later errors in the
-    /// same parser, but still produces an error in the output.
-    ///
-    fn validate<U, F>(self, f: F) -> Validate<Self, O, F>
+    fn and_is<U, B>(self, other: B) -> AndIs<Self, B, U>
     where
         Self: Sized,
-        F: Fn(O, I::Span, &mut Emitter<E::Error>) -> U,
+        B: Parser<'a, I, U, E>,
     {
-        Validate {
-            parser: self,
-            validator: f,
+        AndIs {
+            parser_a: self,
+            parser_b: other,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    // /// Map the primary error of this parser to a result. If the result is [`Ok`], the parser succeeds with that value.
-    // ///
-    // /// Note that, if the closure returns [`Err`], the parser will not consume any input.
-    // ///
-    // /// The output type of this parser is `U`, the [`Ok`] type of the result.
-    // fn or_else<F>(self, f: F) -> OrElse<Self, F>
-    // where
-    //     Self: Sized,
-    //     F: Fn(E::Error) -> Result<O, E::Error>,
-    // {
-    //     OrElse {
-    //         parser: self,
-    //         or_else: f,
-    //     }
-    // }
-
-    /// Attempt to convert the output of this parser into something else using Rust's [`FromStr`] trait.
+    /// Parse the pattern surrounded by the given delimiters.
     ///
-    /// This is most useful when wanting to convert literal values into their corresponding Rust type, such as when
-    /// parsing integers.
-    ///
-    /// The output type of this parser is `Result<U, U::Err>`, the result of attempting to parse the output, `O`, into
-    /// the value `U`.
+    /// The output type of this parser is `O`, the same as the original parser.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::prelude::*;
-    /// let uint64 = text::int::<_, _, extra::Err<Simple<char>>>(10)
-    ///     .from_str::<u64>()
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// // A LISP-style S-expression
+    /// #[derive(Debug, PartialEq)]
+    /// enum SExpr {
+    ///     Ident(String),
+    ///     Num(u64),
+    ///     List(Vec<SExpr>),
+    /// }
+    ///
+    /// let ident = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_alphabetic())
+    ///     .repeated()
+    ///     .at_least(1)
+    ///     .collect::<String>();
+    ///
+    /// let num = text::int(10)
+    ///     .from_str()
     ///     .unwrapped();
     ///
-    /// assert_eq!(uint64.parse("7").into_result(), Ok(7));
-    /// assert_eq!(uint64.parse("42").into_result(), Ok(42));
+    /// let s_expr = recursive(|s_expr| s_expr
+    ///     .padded()
+    ///     .repeated()
+    ///     .collect::<Vec<_>>()
+    ///     .map(SExpr::List)
+    ///     .delimited_by(just('('), just(')'))
+    ///     .or(ident.map(SExpr::Ident))
+    ///     .or(num.map(SExpr::Num)));
+    ///
+    /// // A valid input
+    /// assert_eq!(
+    ///     s_expr.parse("(add (mul 42 3) 15)").into_result(),
+    ///     Ok(SExpr::List(vec![
+    ///         SExpr::Ident("add".to_string()),
+    ///         SExpr::List(vec![
+    ///             SExpr::Ident("mul".to_string()),
+    ///             SExpr::Num(42),
+    ///             SExpr::Num(3),
+    ///         ]),
+    ///         SExpr::Num(15),
+    ///     ])),
+    /// );
     /// ```
-    #[allow(clippy::wrong_self_convention)]
-    fn from_str<U>(self) -> Map<Self, O, fn(O) -> Result<U, U::Err>>
+    fn delimited_by<U, V, B, C>(self, start: B, end: C) -> DelimitedBy<Self, B, C, U, V>
     where
         Self: Sized,
-        U: FromStr,
-        O: AsRef<str>,
+        B: Parser<'a, I, U, E>,
+        C: Parser<'a, I, V, E>,
     {
-        self.map(|o| o.as_ref().parse())
+        DelimitedBy {
+            parser: self,
+            start,
+            end,
+            phantom: EmptyPhantom::new(),
+        }
     }
 
-    /// For parsers that produce a [`Result`] as their output, unwrap the result (panicking if an [`Err`] is
-    /// encountered).
+    /// Parse a pattern delimited by a pair of tokens, such as a block between braces, recovering from any error
+    /// within the delimiters.
     ///
-    /// In general, this method should be avoided except in cases where all possible that the parser might produce can
-    /// by parsed using [`FromStr`] without producing an error.
+    /// This behaves like [`Parser::delimited_by`] with [`just`] for each delimiter, except that if the pattern (or
+    /// the closing delimiter) fails to parse, the error is emitted and input is skipped up to the matching closing
+    /// delimiter, respecting nesting. The output is then produced by calling `fallback` with the span of the whole
+    /// delimited input, including the delimiters.
     ///
-    /// This combinator is not named `unwrap` to avoid confusion: it unwraps *during parsing*, not immediately.
+    /// If the opening delimiter is not found, or the input ends before the matching closing delimiter, this parser
+    /// fails without recovering.
     ///
-    /// The output type of this parser is `U`, the [`Ok`] value of the [`Result`].
+    /// Unlike [`nested_delimiters`], which can be used with [`Parser::recover_with`] to much the same effect, only one
+    /// pair of delimiters is considered.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::prelude::*;
-    /// let boolean = just::<_, _, extra::Err<Simple<char>>>("true")
-    ///     .or(just("false"))
-    ///     .from_str::<bool>()
-    ///     .unwrapped(); // Cannot panic: the only possible outputs generated by the parser are "true" or "false"
+    /// #[derive(Debug, PartialEq)]
+    /// enum Stmt {
+    ///     Call(String),
+    ///     Block(Vec<Stmt>),
+    ///     Error,
+    /// }
     ///
-    /// assert_eq!(boolean.parse("true").into_result(), Ok(true));
-    /// assert_eq!(boolean.parse("false").into_result(), Ok(false));
-    /// // Does not panic, because the original parser only accepts "true" or "false"
-    /// assert!(boolean.parse("42").has_errors());
+    /// let stmts = recursive(|stmts| {
+    ///     let call = text::ascii::ident::<_, _, extra::Err<Rich<char>>>()
+    ///         .then_ignore(just("();"))
+    ///         .map(|name: &str| Stmt::Call(name.to_string()));
+    ///     let block = stmts.delimited_recover('{', '}', |_| vec![Stmt::Error]).map(Stmt::Block);
+    ///     call.or(block).padded().repeated().collect::<Vec<_>>()
+    /// });
+    ///
+    /// let (out, errs) = stmts.parse("a(); { b(); { c() d(); } e(); } f();").into_output_errors();
+    /// assert_eq!(
+    ///     out,
+    ///     Some(vec![
+    ///         Stmt::Call("a".to_string()),
+    ///         // Only the innermost block is replaced, since the outer blocks recover from nothing
+    ///         Stmt::Block(vec![
+    ///             Stmt::Call("b".to_string()),
+    ///             Stmt::Block(vec![Stmt::Error]),
+    ///             Stmt::Call("e".to_string()),
+    ///         ]),
+    ///         Stmt::Call("f".to_string()),
+    ///     ]),
+    /// );
+    /// assert_eq!(errs.len(), 1);
+    /// assert_eq!(errs[0].span(), &SimpleSpan::new(17, 18)); // The missing `;`
     /// ```
-    #[track_caller]
-    fn unwrapped(self) -> Unwrapped<Self, O>
+    fn delimited_recover<F>(
+        self,
+        open: I::Token,
+        close: I::Token,
+        fallback: F,
+    ) -> DelimitedRecover<Self, I::Token, F>
     where
         Self: Sized,
+        I: ValueInput<'a>,
+        I::Token: PartialEq + Clone,
+        F: Fn(I::Span) -> O,
     {
-        Unwrapped {
+        DelimitedRecover {
             parser: self,
-            location: *Location::caller(),
-            phantom: EmptyPhantom::new(),
+            open,
+            close,
+            fallback,
         }
     }
 
-    /// Turn this [`Parser`] into an [`IterParser`] if its output type implements [`IntoIterator`].
+    /// Parse a pattern, but with an instance of another pattern on either end, yielding the output of the inner.
     ///
-    /// The resulting iterable parser will emit each element of the output type in turn.
+    /// The output type of this parser is `O`, the same as the original parser.
     ///
-    /// This is *broadly* analogous to functions like [`Vec::into_iter`], but operating at the level of parser outputs.
-    // TODO: Example
-    fn into_iter(self) -> IntoIter<Self, O>
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .padded_by(just('!'));
+    ///
+    /// assert_eq!(ident.parse("!hello!").into_result(), Ok("hello"));
+    /// assert!(ident.parse("hello!").has_errors());
+    /// assert!(ident.parse("!hello").has_errors());
+    /// assert!(ident.parse("hello").has_errors());
+    /// ```
+    fn padded_by<U, B>(self, padding: B) -> PaddedBy<Self, B, U>
     where
         Self: Sized,
-        O: IntoIterator,
+        B: Parser<'a, I, U, E>,
     {
-        IntoIter {
+        PaddedBy {
             parser: self,
+            padding,
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Box the parser, yielding a parser that performs parsing through dynamic dispatch.
-    ///
-    /// Boxing a parser might be useful for:
-    ///
-    /// - Dynamically building up parsers at run-time
-    ///
-    /// - Improving compilation times (Rust can struggle to compile code containing very long types)
+    /// Parse one thing or, on failure, another thing.
     ///
-    /// - Passing a parser over an FFI boundary
+    /// The output of both parsers must be of the same type, because either output can be produced.
     ///
-    /// - Getting around compiler implementation problems with long types such as
-    ///   [this](https://github.com/rust-lang/rust/issues/54540).
+    /// If both parser succeed, the output of the first parser is guaranteed to be prioritized over the output of the
+    /// second.
     ///
-    /// - Places where you need to name the type of a parser
+    /// If both parsers produce errors, the combinator will attempt to select from or combine the errors to produce an
+    /// error that is most likely to be useful to a human attempting to understand the problem. The exact algorithm
+    /// used is left unspecified, and is not part of the crate's semver guarantees, although regressions in error
+    /// quality should be reported in the issue tracker of the main repository.
     ///
-    /// Boxing a parser is broadly equivalent to boxing other combinators via dynamic dispatch, such as [`Iterator`].
+    /// Please note that long chains of [`Parser::or`] combinators have been known to result in poor compilation times.
+    /// If you feel you are experiencing this, consider using [`choice`] instead.
     ///
-    /// The output type of this parser is `O`, the same as the original parser.
+    /// The output type of this parser is `O`, the output of both parsers.
     ///
     /// # Examples
     ///
-    /// When not using `boxed`, the following patterns are either impossible or very difficult to express:
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let op = just::<_, _, extra::Err<Simple<char>>>('+')
+    ///     .or(just('-'))
+    ///     .or(just('*'))
+    ///     .or(just('/'));
     ///
-    /// ```compile_fail
-    /// # use chumsky::prelude::*;
+    /// assert_eq!(op.parse("+").into_result(), Ok('+'));
+    /// assert_eq!(op.parse("/").into_result(), Ok('/'));
+    /// assert!(op.parse("!").has_errors());
+    /// ```
+    fn or<B>(self, other: B) -> Or<Self, B>
+    where
+        Self: Sized,
+        B: Parser<'a, I, O, E>,
+    {
+        Or {
+            choice: choice((self, other)),
+        }
+    }
+
+    /// Attempt to parse something, but only if it exists.
     ///
-    /// pub trait Parseable: Sized {
-    ///     type Parser<'a>: Parser<'a, &'a str, Self>;
+    /// If parsing of the pattern is successful, the output is `Some(_)`. Otherwise, the output is `None`.
     ///
-    ///     fn parser<'a>() -> Self::Parser<'a>;
-    /// }
+    /// The output type of this parser is `Option<O>`.
     ///
-    /// impl Parseable for i32 {
-    ///     // We *can* write this type, but it will be very impractical, and change on any alterations
-    ///     // to the implementation
-    ///     type Parser<'a> = ???;
+    /// # Examples
     ///
-    ///     fn parser<'a>() -> Self::Parser<'a> {
-    ///         todo()
-    ///     }
-    /// }
     /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let word = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_alphabetic())
+    ///     .repeated()
+    ///     .at_least(1)
+    ///     .collect::<String>();
     ///
-    /// ```compile_fail
-    /// # use chumsky::prelude::*;
-    /// # fn user_input<'a>() -> impl IntoIterator<Item = impl Parser<'a, &'a str, char>> { [just('b')] }
-    ///
-    /// let user_input = user_input();
-    ///
-    /// let mut parser = just('a');
-    /// for i in user_input {
-    ///     // Doesn't work due to type mismatch - since every combinator creates a unique type
-    ///     parser = parser.or(i);
-    /// }
+    /// let word_or_question = word
+    ///     .then(just('?').or_not());
     ///
-    /// let parser = parser.then(just('z'));
-    /// let _ = parser.parse("b").into_result();
+    /// assert_eq!(word_or_question.parse("hello?").into_result(), Ok(("hello".to_string(), Some('?'))));
+    /// assert_eq!(word_or_question.parse("wednesday").into_result(), Ok(("wednesday".to_string(), None)));
     /// ```
+    fn or_not(self) -> OrNot<Self>
+    where
+        Self: Sized,
+    {
+        OrNot { parser: self }
+    }
+
+    /// Attempt to parse something, producing a default value generated by the given function if it does not exist.
     ///
-    /// However, with `boxed`, we can express them by making the parsers all share a common type:
+    /// This is equivalent to `.or_not().map(|x| x.unwrap_or_else(default))`, but avoids the intermediate [`Option`].
+    /// See [`Parser::or_default`] for a shorthand that uses [`Default::default`].
     ///
-    /// ```
-    /// use chumsky::prelude::*;
+    /// The output type of this parser is `O`, the same as the original parser.
     ///
-    /// pub trait Parseable: Sized {
-    ///     fn parser<'a>() -> Boxed<'a, 'a, &'a str, Self, extra::Default>;
-    /// }
+    /// # Examples
     ///
-    /// impl Parseable for i32 {
-    ///     fn parser<'a>() -> Boxed<'a, 'a, &'a str, Self, extra::Default> {
-    ///         todo().boxed()
-    ///     }
-    /// }
     /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10).from_str::<u32>().unwrapped();
+    /// // The port of an address is optional, and defaults to 80
+    /// let address = text::ascii::ident()
+    ///     .then(just(':').ignore_then(int).or_not_with(|| 80));
     ///
+    /// assert_eq!(address.parse("localhost:8080").into_result(), Ok(("localhost", 8080)));
+    /// assert_eq!(address.parse("localhost").into_result(), Ok(("localhost", 80)));
     /// ```
-    /// # use chumsky::prelude::*;
-    /// # fn user_input<'a>() -> impl IntoIterator<Item = impl Parser<'a, &'a str, char>> { [just('b'), just('c')] }
-    /// let user_input = user_input();
-    /// let mut parser = just('a').boxed();
-    /// for i in user_input {
-    ///     // Doesn't work due to type mismatch - since every combinator creates a unique type
-    ///     parser = parser.or(i).boxed();
-    /// }
-    /// let parser = parser.then(just('z'));
-    /// parser.parse("az").into_result().unwrap();
-    /// ```
-    ///
-    fn boxed<'b>(self) -> Boxed<'a, 'b, I, O, E>
+    fn or_not_with<F: Fn() -> O>(self, default: F) -> OrNotWith<Self, F>
     where
-        Self: MaybeSync + Sized + 'a + 'b,
+        Self: Sized,
     {
-        ParserSealed::boxed(self)
+        OrNotWith {
+            parser: self,
+            default,
+        }
     }
 
-    /// Use pratt-parsing to efficiently parse expressions separated by
-    /// operators of different associativity and precedence.
+    /// Attempt to parse something, producing [`Default::default`] if it does not exist.
     ///
-    /// The pratt-parsing algorithm uses recursion to efficiently parse
-    /// arbitrarily nested expressions.
+    /// This is a shorthand for `.or_not_with(O::default)`.
     ///
-    /// # Example
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
     ///
     /// ```
-    /// use chumsky::prelude::*;
-    /// use chumsky::pratt::*;
-    /// use chumsky::extra;
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>();
+    /// // Function parameters may be omitted entirely
+    /// let params = ident
+    ///     .separated_by(just(','))
+    ///     .collect::<Vec<_>>()
+    ///     .delimited_by(just('('), just(')'))
+    ///     .or_default();
+    /// let func = text::ascii::keyword("fn").padded().ignore_then(ident).then(params);
     ///
-    /// enum Expr {
-    ///     Add(Box<Self>, Box<Self>),
-    ///     Sub(Box<Self>, Box<Self>),
-    ///     Pow(Box<Self>, Box<Self>),
-    ///     Neg(Box<Self>),
-    ///     Fact(Box<Self>),
-    ///     Deref(Box<Self>),
-    ///     Literal(i32),
-    /// }
-    ///
-    /// impl std::fmt::Display for Expr {
-    ///     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-    ///         match self {
-    ///             Self::Literal(literal) => write!(f, "{literal}"),
-    ///             Self::Fact(left) => write!(f, "({left}!)"),
-    ///             Self::Deref(left) => write!(f, "(*{left})"),
-    ///             Self::Neg(right) => write!(f, "(-{right})"),
-    ///             Self::Add(left, right) => write!(f, "({left} + {right})"),
-    ///             Self::Sub(left, right) => write!(f, "({left} - {right})"),
-    ///             Self::Pow(left, right) => write!(f, "({left} ^ {right})"),
-    ///         }
-    ///     }
-    /// }
-    ///
-    /// let atom = text::int::<_, _, extra::Err<Simple<char>>>(10)
-    ///     .from_str()
-    ///     .unwrapped()
-    ///     .map(Expr::Literal);
-    ///
-    /// let operator = choice((
-    ///     // Our `-` and `+` bind the weakest, meaning that even if they occur
-    ///     // first in an expression, they will be the last executed
-    ///     left_infix(just('+'), 1, |l, r| Expr::Add(Box::new(l), Box::new(r))),
-    ///     left_infix(just('-'), 1, |l, r| Expr::Sub(Box::new(l), Box::new(r))),
-    ///     // Just like in math, we want that if we write -x^2, that our parser
-    ///     // parses that as -(x^2), so we need it to bind tighter than our
-    ///     // prefix operators
-    ///     right_infix(just('^'), 3, |l, r| Expr::Pow(Box::new(l), Box::new(r))),
-    /// ))
-    /// .padded();
-    ///
-    /// let prefix_ops = choice((
-    ///     // Notice the conflict with our `Expr::Sub`. This will still
-    ///     // parse correctly. We want negation to happen before `+` and `-`,
-    ///     // so we set it's precedence higher.
-    ///     prefix(just('-'), 2, |rhs| Expr::Neg(Box::new(rhs))),
-    ///     prefix(just('*'), 2, |rhs| Expr::Deref(Box::new(rhs))),
-    /// ))
-    /// .padded();
-    ///
-    /// // We want factorial to happen before any negation, so we need it's
-    /// // precedence to be higher than `Expr::Neg`.
-    /// let factorial = postfix(just('!'), 4, |lhs| Expr::Fact(Box::new(lhs))).padded();
-    ///
-    /// let pratt = atom
-    ///     .pratt(operator)
-    ///     .with_prefix_ops(prefix_ops)
-    ///     .with_postfix_ops(factorial)
-    ///     .map(|x| x.to_string());
-    ///
-    /// assert_eq!(
-    ///     pratt.parse("*1 + -2! - -3^2").into_result(),
-    ///     Ok("(((*1) + (-(2!))) - (-(3 ^ 2)))".to_string()),
-    /// );
-    /// ```
-    #[cfg(feature = "pratt")]
-    fn pratt<InfixOps, InfixOpsOut>(
-        self,
-        ops: InfixOps,
-    ) -> Pratt<I, O, E, Self, pratt::Infix<InfixOps, InfixOpsOut>>
-    where
-        I: Input<'a>,
-        E: ParserExtra<'a, I>,
-        InfixOps: Parser<'a, I, InfixOpsOut, E>,
-        Self: Sized,
-    {
-        Pratt {
-            atom: self,
-            ops: pratt::Infix {
-                infix: ops,
-                _phantom: EmptyPhantom::new(),
-            },
-            _phantom: EmptyPhantom::new(),
-        }
-    }
-}
-
-#[cfg(feature = "nightly")]
-impl<'a, I, O, E> ParserSealed<'a, I, O, E> for !
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-{
-    fn go<M: Mode>(&self, _inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
-        *self
-    }
-
-    go_extra!(O);
-}
-
-/// A [`Parser`] that can be configured with runtime context. This allows for context-sensitive parsing
-/// of input. Note that chumsky only supports 'left'-sensitive parsing, where the context for a parser
-/// is derived from earlier in the input.
-///
-/// Chumsky distinguishes 'state' from 'context'. State is not able to change what input a parser
-/// accepts, but may be used to change the contents of the type it emits. In this way state is expected
-/// to be idempotent - combinators such as [`Parser::map_with_state`] are allowed to not call the
-/// provided closure at all if they don't emit any output. Context and configuration, on the other hand,
-/// is used to change what kind of input a parser may accept, and thus must always be evaluated. Context
-/// isn't usable in any map combinator however - while it may affect accepted input, it is not expected
-/// to change the final result outside of how it changes what the parser itself returns.
-///
-/// Not all parsers currently support configuration. If you feel like you need a parser to be configurable
-/// and it isn't currently, please open an issue on the issue tracker of the main repository.
-pub trait ConfigParser<'a, I, O, E>: ConfigParserSealed<'a, I, O, E>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-{
-    /// A combinator that allows configuration of the parser from the current context. Context
-    /// is most often derived from [`Parser::ignore_with_ctx`], [`Parser::then_with_ctx`] or [`map_ctx`],
-    /// and is how chumsky supports parsing things such as indentation-sensitive grammars.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// # use chumsky::prelude::*;
-    ///
-    /// let int = text::int::<_, _, extra::Err<Rich<char>>>(10)
-    ///     .from_str()
-    ///     .unwrapped();
-    ///
-    /// // By default, accepts any number of items
-    /// let item = text::ascii::ident()
-    ///     .padded()
-    ///     .repeated();
-    ///
-    /// // With configuration, we can declare an exact number of items based on a prefix length
-    /// let len_prefixed_arr = int
-    ///     .ignore_with_ctx(item.configure(|repeat, ctx| repeat.exactly(*ctx)).collect::<Vec<_>>());
-    ///
-    /// assert_eq!(
-    ///     len_prefixed_arr.parse("2 foo bar").into_result(),
-    ///     Ok(vec!["foo", "bar"]),
-    /// );
-    ///
-    /// assert_eq!(
-    ///     len_prefixed_arr.parse("0").into_result(),
-    ///     Ok(vec![]),
-    /// );
-    ///
-    /// len_prefixed_arr.parse("3 foo bar baz bam").into_result().unwrap_err();
-    /// len_prefixed_arr.parse("3 foo bar").into_result().unwrap_err();
+    /// assert_eq!(func.parse("fn foo(a,b)").into_result(), Ok(("foo", vec!["a", "b"])));
+    /// assert_eq!(func.parse("fn bar").into_result(), Ok(("bar", vec![])));
     /// ```
-    fn configure<F>(self, cfg: F) -> Configure<Self, F>
+    fn or_default(self) -> OrNotWith<Self, fn() -> O>
     where
         Self: Sized,
-        F: Fn(Self::Config, &E::Context) -> Self::Config,
+        O: Default,
     {
-        Configure { parser: self, cfg }
-    }
-}
-
-/// An iterator that wraps an iterable parser. See [`IterParser::parse_iter`].
-#[cfg(test)]
-pub struct ParserIter<'a, 'iter, P: IterParser<'a, I, O, E>, I: Input<'a>, O, E: ParserExtra<'a, I>>
-{
-    parser: P,
-    offset: I::Offset,
-    own: InputOwn<'a, 'iter, I, E>,
-    iter_state: Option<P::IterState<Emit>>,
-    #[allow(dead_code)]
-    phantom: EmptyPhantom<(&'a (), O)>,
-}
-
-#[cfg(test)]
-impl<'a, 'iter, P, I: Input<'a>, O, E: ParserExtra<'a, I>> Iterator
-    for ParserIter<'a, 'iter, P, I, O, E>
-where
-    P: IterParser<'a, I, O, E>,
-{
-    type Item = O;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let mut inp = self.own.as_ref_at(self.offset);
-        let parser = &self.parser;
-
-        let iter_state = match &mut self.iter_state {
-            Some(state) => state,
-            None => {
-                let state = parser.make_iter::<Emit>(&mut inp).ok()?;
-                self.iter_state = Some(state);
-                self.iter_state.as_mut().unwrap()
-            }
-        };
-
-        let res = parser.next::<Emit>(&mut inp, iter_state);
-        self.offset = inp.offset;
-        res.ok().and_then(|res| res)
+        self.or_not_with(O::default)
     }
-}
 
-/// An iterable equivalent of [`Parser`], i.e: a parser that generates a sequence of outputs.
-pub trait IterParser<'a, I, O, E = extra::Default>: IterParserSealed<'a, I, O, E>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-{
-    /// Collect this iterable parser into a [`Container`].
+    /// Unwrap the [`Option`] output of a parser, producing the given default value when it is [`None`].
     ///
-    /// This is commonly useful for collecting parsers that output many values into containers of various kinds:
-    /// [`Vec`]s, [`String`]s, or even [`HashMap`]s. This method is analogous to [`Iterator::collect`].
+    /// This is useful for parsers that already produce an [`Option`], such as a parser built with [`Parser::or_not`]
+    /// in which the [`Some`] case needs to be distinguished elsewhere. To make a parser optional and provide a default in
+    /// one step, use [`Parser::or_not_with`].
     ///
-    /// The output type of this iterable parser is `C`, the type being collected into.
+    /// The output type of this parser is `U`, the type wrapped by the original parser's output.
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let word = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_alphabetic()) // This parser produces an output of `char`
-    ///     .repeated() // This parser is iterable (i.e: implements `IterParser`)
-    ///     .collect::<String>(); // We collect the `char`s into a `String`
+    /// // A type annotation that defaults to `any`
+    /// let annotation = just::<_, _, extra::Err<Simple<char>>>(':')
+    ///     .padded()
+    ///     .ignore_then(text::ascii::ident())
+    ///     .or_not()
+    ///     .unwrap_or("any");
+    /// let binding = text::ascii::ident().then(annotation);
     ///
-    /// assert_eq!(word.parse("hello").into_result(), Ok("hello".to_string()));
+    /// assert_eq!(binding.parse("x: int").into_result(), Ok(("x", "int")));
+    /// assert_eq!(binding.parse("y").into_result(), Ok(("y", "any")));
     /// ```
-    #[cfg_attr(debug_assertions, track_caller)]
-    fn collect<C: Container<O>>(self) -> Collect<Self, O, C>
+    fn unwrap_or<U: Clone>(self, default: U) -> UnwrapOr<Self, U>
     where
-        Self: Sized,
+        Self: Sized + Parser<'a, I, Option<U>, E>,
     {
-        Collect {
+        UnwrapOr {
             parser: self,
-            #[cfg(debug_assertions)]
-            location: *Location::caller(),
-            phantom: EmptyPhantom::new(),
+            default,
         }
     }
 
-    /// Collect this iterable parser into a [`ContainerExactly`].
+    /// Attempt to parse a pattern, catching its failure as an [`Err`] output rather than letting it fail the parse.
     ///
-    /// This is useful for situations where the number of items to consume is statically known.
-    /// A common use-case is collecting into an array.
+    /// If the pattern fails, no input is consumed and the error that it would have produced is output instead, so
+    /// that the failure can be handled locally: for example, by parsing a malformed directive again as raw text.
+    /// Unlike [`Parser::recover_with`], no input is skipped and no error is emitted unless you emit one yourself.
     ///
-    /// The output type of this iterable parser if `C`, the type being collected into.
+    /// The output type of this parser is `Result<O, E::Error>`.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use chumsky::{prelude::*, error::Simple};
-    /// let three_digit = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_numeric())
-    ///     .repeated()
-    ///     .collect_exactly::<[_; 3]>();
+    /// # use chumsky::prelude::*;
+    /// #[derive(Debug, PartialEq)]
+    /// enum Line<'a> {
+    ///     Include(&'a str),
+    ///     Text(&'a str),
+    /// }
     ///
-    /// assert_eq!(three_digit.parse("123").into_result(), Ok(['1', '2', '3']));
-    /// assert!(three_digit.parse("12").into_result().is_err());
-    /// assert!(three_digit.parse("1234").into_result().is_err());
+    /// let directive = just::<_, _, extra::Err<Rich<char>>>("#include ")
+    ///     .ignore_then(text::ascii::ident())
+    ///     .then_ignore(text::newline().rewind());
+    /// // Lines starting with a malformed directive are kept as text
+    /// let line = directive
+    ///     .then_catch()
+    ///     .then(text::line())
+    ///     .map(|(directive, text)| match directive {
+    ///         Ok(name) => Line::Include(name),
+    ///         Err(_) => Line::Text(text),
+    ///     });
+    /// let file = line.repeated().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(
+    ///     file.parse("#include foo\nhello\n#include 42\n").into_result(),
+    ///     Ok(vec![Line::Include("foo"), Line::Text("hello"), Line::Text("#include 42")]),
+    /// );
     /// ```
-    fn collect_exactly<C: ContainerExactly<O>>(self) -> CollectExactly<Self, O, C>
+    fn then_catch(self) -> ThenCatch<Self>
     where
         Self: Sized,
     {
-        CollectExactly {
-            parser: self,
-            phantom: EmptyPhantom::new(),
-        }
+        ThenCatch { parser: self }
     }
 
-    /// Collect this iterable parser into a [`usize`], outputting the number of elements that were parsed.
+    /// Invert the result of the contained parser, failing if it succeeds and succeeding if it fails.
+    /// The output of this parser is always `()`, the unit type.
     ///
-    /// This is sugar for [`.collect::<usize>()`](Self::collect).
+    /// The motivating case for this is in combination with [`Parser::and_is`], allowing a parser
+    /// to consume something only if it isn't also something like an escape sequence or a nested block.
     ///
-    /// # Examples
+    /// Caveats:
+    /// - The error message produced by `not` by default will likely be fairly unhelpful - it can
+    ///   only tell the span that was wrong.
+    /// - If not careful, it's fairly easy to create non-intuitive behavior due to end-of-input
+    ///   being a valid token for a parser to consume, and as most parsers fail at end of input,
+    ///   `not` will succeed on it.
     ///
     /// ```
-    /// # use chumsky::prelude::*;
+    /// # use chumsky::{prelude::*, error::Simple};
     ///
-    /// // Counts how many chess squares are in the input.
-    /// let squares = one_of::<_, _, extra::Err<Simple<char>>>('a'..='z').then(one_of('1'..='8')).padded().repeated().count();
+    /// #[derive(Debug, PartialEq)]
+    /// enum Tree<'a> {
+    ///     Text(&'a str),
+    ///     Group(Vec<Self>),
+    /// }
     ///
-    /// assert_eq!(squares.parse("a1 b2 c3").into_result(), Ok(3));
-    /// assert_eq!(squares.parse("e5 e7 c6 c7 f6 d5 e6 d7 e4 c5 d6 c4 b6 f5").into_result(), Ok(14));
-    /// assert_eq!(squares.parse("").into_result(), Ok(0));
+    /// // Arbitrary text, nested in a tree with { ... } delimiters
+    /// let tree = recursive::<_, _, extra::Err<Simple<char>>, _, _>(|tree| {
+    ///     let text = any()
+    ///         .and_is(one_of("{}").not())
+    ///         .repeated()
+    ///         .at_least(1)
+    ///         .map_slice(Tree::Text);
+    ///
+    ///     let group = tree
+    ///         .repeated()
+    ///         .collect()
+    ///         .delimited_by(just('{'), just('}'))
+    ///         .map(Tree::Group);
+    ///
+    ///     text.or(group)
+    /// });
+    ///
+    /// assert_eq!(
+    ///     tree.parse("{abcd{efg{hijk}lmn{opq}rs}tuvwxyz}").into_result(),
+    ///     Ok(Tree::Group(vec![
+    ///         Tree::Text("abcd"),
+    ///         Tree::Group(vec![
+    ///             Tree::Text("efg"),
+    ///             Tree::Group(vec![
+    ///                 Tree::Text("hijk"),
+    ///             ]),
+    ///             Tree::Text("lmn"),
+    ///             Tree::Group(vec![
+    ///                 Tree::Text("opq"),
+    ///             ]),
+    ///             Tree::Text("rs"),
+    ///         ]),
+    ///         Tree::Text("tuvwxyz"),
+    ///     ])),
+    /// );
     /// ```
-    fn count(self) -> Collect<Self, O, usize>
+    fn not(self) -> Not<Self, O>
     where
         Self: Sized,
     {
-        self.collect()
+        Not {
+            parser: self,
+            phantom: EmptyPhantom::new(),
+        }
     }
 
-    /// Enumerate outputs of this iterable parser.
+    /// Parse a pattern zero or more times (analog to Regex's `<PAT>*`).
     ///
-    /// This function behaves in a similar way to [`Iterator::enumerate`].
+    /// Input is eagerly parsed. Be aware that the parser will accept no occurrences of the pattern too. Consider using
+    /// [`Repeated::at_least`] instead if it better suits your use-case.
     ///
-    /// The output type of this iterable parser is `(usize, O)`.
+    /// If an iteration of the pattern succeeds without consuming any input, the parser would otherwise loop forever.
+    /// In debug builds this results in a panic pointing at the location of the call to `repeated`. In release builds,
+    /// the repetition instead fails with an error at the position where it stopped making progress, reporting the
+    /// token found there as unexpected. Either way, this is a mistake in the grammar: make sure that the pattern cannot
+    /// succeed without consuming input. Be aware that recovering parsers, such as those created with
+    /// [`Parser::recover_via_insertion`], may do so.
+    ///
+    /// The output type of this parser can be any [`Container`].
     ///
     /// # Examples
     ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
-    /// let word = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    /// let num = any::<_, extra::Err<Simple<char>>>()
+    ///     .filter(|c: &char| c.is_ascii_digit())
+    ///     .repeated()
+    ///     .at_least(1)
+    ///     .collect::<String>()
+    ///     .from_str()
+    ///     .unwrapped();
+    ///
+    /// let sum = num.clone()
+    ///     .foldl(just('+').ignore_then(num).repeated(), |a, b| a + b);
+    ///
+    /// assert_eq!(sum.parse("2+13+4+0+5").into_result(), Ok(24));
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn repeated(self) -> Repeated<Self, O, I, E>
+    where
+        Self: Sized,
+    {
+        Repeated {
+            parser: self,
+            at_least: 0,
+            at_most: !0,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Parse a pattern, separated by another, any number of times.
+    ///
+    /// You can use [`SeparatedBy::allow_leading`] or [`SeparatedBy::allow_trailing`] to allow leading or trailing
+    /// separators.
+    ///
+    /// Like [`Parser::repeated`], an iteration that consumes no input (neither separator nor item) panics in debug
+    /// builds. In release builds it ends the repetition successfully, keeping the items parsed so far.
+    ///
+    /// The output type of this parser can be any [`Container`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let shopping = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
     ///     .padded()
-    ///     .repeated() // This parser is iterable (i.e: implements `IterParser`)
-    ///     .enumerate()
-    ///     .collect::<Vec<(usize, &str)>>();
+    ///     .separated_by(just(','))
+    ///     .collect::<Vec<_>>();
     ///
-    /// assert_eq!(word.parse("hello world").into_result(), Ok(vec![(0, "hello"), (1, "world")]));
+    /// assert_eq!(shopping.parse("eggs").into_result(), Ok(vec!["eggs"]));
+    /// assert_eq!(shopping.parse("eggs, flour, milk").into_result(), Ok(vec!["eggs", "flour", "milk"]));
     /// ```
-    fn enumerate(self) -> Enumerate<Self, O>
+    ///
+    /// See [`SeparatedBy::allow_leading`] and [`SeparatedBy::allow_trailing`] for more examples.
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn separated_by<U, B>(self, separator: B) -> SeparatedBy<Self, B, O, U, I, E>
     where
         Self: Sized,
+        B: Parser<'a, I, U, E>,
     {
-        Enumerate {
+        SeparatedBy {
             parser: self,
+            separator,
+            at_least: 0,
+            at_most: !0,
+            allow_leading: false,
+            allow_trailing: false,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// Right-fold the output of the parser into a single value.
+    /// Left-fold the outputs of an iterable parser into the output of this parser.
     ///
-    /// The output of the original parser must be of type `(impl IntoIterator<Item = A>, B)`. Because right-folds work
-    /// backwards, the iterator must implement [`DoubleEndedIterator`] so that it can be reversed.
+    /// Each item produced by `other` is folded into the accumulated value as soon as it is parsed, so no intermediate
+    /// container is ever created: this is the natural shape for performance-sensitive expression parsers. `other` may
+    /// be any [`IterParser`], such as [`Parser::repeated`] or [`Parser::separated_by`].
     ///
-    /// The output type of this iterable parser is `B`, the right-hand component of the original parser's output.
+    /// The output type of this parser is `O`, the same as the original parser.
     ///
     /// # Examples
     ///
@@ -2474,23 +2973,38 @@ where
     ///     .from_str()
     ///     .unwrapped();
     ///
-    /// let signed = just('+').to(1)
-    ///     .or(just('-').to(-1))
-    ///     .repeated()
-    ///     .foldr(int, |a, b| a * b);
+    /// let sum = int
+    ///     .clone()
+    ///     .foldl(just('+').ignore_then(int).repeated(), |a, b| a + b);
     ///
-    /// assert_eq!(signed.parse("3").into_result(), Ok(3));
-    /// assert_eq!(signed.parse("-17").into_result(), Ok(-17));
-    /// assert_eq!(signed.parse("--+-+-5").into_result(), Ok(5));
+    /// assert_eq!(sum.parse("1+12+3+9").into_result(), Ok(25));
+    /// assert_eq!(sum.parse("6").into_result(), Ok(6));
+    /// ```
+    ///
+    /// Operators can be folded along with their operands:
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .from_str::<i64>()
+    ///     .unwrapped();
+    /// let op = one_of("+-");
+    ///
+    /// let expr = int.foldl(op.then(int).repeated(), |lhs, (op, rhs)| match op {
+    ///     '+' => lhs + rhs,
+    ///     _ => lhs - rhs,
+    /// });
+    ///
+    /// assert_eq!(expr.parse("10-4+3-2").into_result(), Ok(7));
     /// ```
     #[cfg_attr(debug_assertions, track_caller)]
-    fn foldr<B, F, OA>(self, other: B, f: F) -> Foldr<F, Self, B, O, E>
+    fn foldl<B, F, OB>(self, other: B, f: F) -> Foldl<F, Self, B, OB, E>
     where
-        F: Fn(O, OA) -> OA,
-        B: Parser<'a, I, OA, E>,
+        F: Fn(O, OB) -> O,
+        B: IterParser<'a, I, OB, E>,
         Self: Sized,
     {
-        Foldr {
+        Foldl {
             parser_a: self,
             parser_b: other,
             folder: f,
@@ -2500,45 +3014,87 @@ where
         }
     }
 
-    /// Right-fold the output of the parser into a single value, making use of the parser's state when doing so.
+    /// Left-fold the output of the parser into a single value, making use of the parser's state when doing so.
     ///
-    /// The output of the original parser must be of type `(impl IntoIterator<Item = A>, B)`. Because right-folds work
-    /// backwards, the iterator must implement [`DoubleEndedIterator`] so that it can be reversed.
+    /// The output of the original parser must be of type `(A, impl IntoIterator<Item = B>)`.
     ///
-    /// The output type of this parser is `B`, the right-hand component of the original parser's output.
+    /// The output type of this parser is `A`, the left-hand component of the original parser's output.
     ///
     /// # Examples
     ///
+    /// ## General
+    ///
     /// ```
     /// # use chumsky::{prelude::*, error::Simple};
     /// let int = text::int::<_, _, extra::Full<Simple<char>, i32, ()>>(10)
     ///     .from_str()
     ///     .unwrapped();
     ///
-    /// let signed = just('+').to(1)
-    ///     .or(just('-').to(-1))
-    ///     .repeated()
-    ///     .foldr_with_state(int, |a, b, state| {
-    ///         (*state) += 1;
-    ///         (a * b)
-    ///     });
+    /// let sum = int
+    ///     .clone()
+    ///     .foldl_with_state(just('+').ignore_then(int).repeated(), |a, b, state| (a + b) * *state);
+    ///
+    /// let mut multiplier = 2i32;
+    /// assert_eq!(sum.parse_with_state("1+12+3+9", &mut multiplier).into_result(), Ok(134));
+    /// assert_eq!(sum.parse_with_state("6", &mut multiplier).into_result(), Ok(6));
+    /// ```
+    ///
+    /// ## Interning / Arena Allocation
+    ///
+    /// This example assumes use of the `slotmap` crate for arena allocation.
     ///
-    /// // Test our parser
-    /// let mut folds = 0i32;
-    /// assert_eq!(signed.parse_with_state("3", &mut folds).into_result(), Ok(3));
-    /// assert_eq!(signed.parse_with_state("-17", &mut folds).into_result(), Ok(-17));
-    /// assert_eq!(signed.parse_with_state("--+-+-5", &mut folds).into_result(), Ok(5));
     /// ```
+    /// # use chumsky::prelude::*;
+    /// use slotmap::{new_key_type, SlotMap};
+    ///
+    /// // Metadata type for node Ids for extra type safety
+    /// new_key_type! {
+    ///    pub struct NodeId;
+    /// }
+    ///
+    /// // AST nodes reference other nodes with `NodeId`s instead of containing boxed/owned values
+    /// #[derive(Copy, Clone, Debug, PartialEq)]
+    /// enum Expr {
+    ///     Int(i32),
+    ///     Add(NodeId, NodeId),
+    /// }
     ///
+    /// type NodeArena = SlotMap<NodeId, Expr>;
+    ///
+    /// // Now, define our parser
+    /// let int = text::int::<&str, _, extra::Full<Simple<char>, NodeArena, ()>>(10)
+    ///     .padded()
+    ///     .map_with_state(|s, _, state: &mut NodeArena|
+    ///         // Return the ID of the new integer node
+    ///         state.insert(Expr::Int(s.parse().unwrap()))
+    ///     );
+    ///
+    /// let sum = int.foldl_with_state(
+    ///     just('+').padded().ignore_then(int).repeated(),
+    ///     |a: NodeId, b: NodeId, state: &mut NodeArena| {
+    ///         // Inserting an item into the arena returns its ID
+    ///         state.insert(Expr::Add(a, b))
+    ///     }
+    /// );
     ///
+    /// // Test our parser
+    /// let mut arena = NodeArena::default();
+    /// let four_plus_eight = sum.parse_with_state("4 + 8", &mut arena).unwrap();
+    /// if let Expr::Add(a, b) = arena[four_plus_eight] {
+    ///     assert_eq!(arena[a], Expr::Int(4));
+    ///     assert_eq!(arena[b], Expr::Int(8));
+    /// } else {
+    ///     panic!("Not an Expr::Add");
+    /// }
+    /// ```
     #[cfg_attr(debug_assertions, track_caller)]
-    fn foldr_with_state<B, F, OA>(self, other: B, f: F) -> FoldrWithState<F, Self, B, O, E>
+    fn foldl_with_state<B, F, OB>(self, other: B, f: F) -> FoldlWithState<F, Self, B, OB, E>
     where
-        F: Fn(O, OA, &mut E::State) -> OA,
-        B: Parser<'a, I, OA, E>,
+        F: Fn(O, OB, &mut E::State) -> O,
+        B: IterParser<'a, I, OB, E>,
         Self: Sized,
     {
-        FoldrWithState {
+        FoldlWithState {
             parser_a: self,
             parser_b: other,
             folder: f,
@@ -2548,185 +3104,2089 @@ where
         }
     }
 
-    /// Create an iterator over the outputs generated by an iterable parser.
+    /// Repeatedly parse a pattern, feeding each output into an accumulator with a function that decides whether to
+    /// continue.
     ///
-    /// Warning: Trailing errors will be ignored
-    // TODO: Stabilize once error handling is properly decided on
-    #[cfg(test)]
-    fn parse_iter(self, input: I) -> ParseResult<ParserIter<'a, 'static, Self, I, O, E>, E::Error>
-    where
-        Self: IterParser<'a, I, O, E> + Sized,
-        E::State: Default,
-        E::Context: Default,
-    {
-        ParseResult::new(
-            Some(ParserIter {
-                parser: self,
-                offset: input.start(),
-                own: InputOwn::new(input),
-                iter_state: None,
-                phantom: EmptyPhantom::new(),
-            }),
-            Vec::new(),
-        )
-    }
-
-    /// Create an iterator over the outputs generated by an iterable parser with the given parser state.
+    /// The accumulator starts as the result of calling `init`. After each successful parse of the pattern, `f` is given the
+    /// accumulator, the pattern's output and a [`MapExtra`] for the pattern's span and state. Returning
+    /// [`ControlFlow::Continue`] carries on parsing, while [`ControlFlow::Break`] stops immediately with the given
+    /// value. When the pattern fails to parse, the input is rewound to the end of the last successful parse and the
+    /// accumulator is produced as-is. This makes it possible to build up a structure item by item and stop on
+    /// conditions that depend on what has been parsed so far, which is awkward to express with [`Parser::repeated`].
     ///
-    /// Warning: Trailing errors will be ignored
-    // TODO: Stabilize once error handling is properly decided on
-    #[cfg(test)]
-    fn parse_iter_with_state<'parse>(
-        self,
-        input: I,
-        state: &'parse mut E::State,
-    ) -> ParseResult<ParserIter<'a, 'parse, Self, I, O, E>, E::Error>
-    where
-        Self: IterParser<'a, I, O, E> + Sized,
-        E::Context: Default,
-    {
-        ParseResult::new(
-            Some(ParserIter {
-                parser: self,
-                offset: input.start(),
-                own: InputOwn::new_state(input, state),
-                iter_state: None,
-                phantom: EmptyPhantom::new(),
-            }),
-            Vec::new(),
-        )
-    }
-}
-
-/// An iterable equivalent of [`ConfigParser`], i.e: a parser that generates a sequence of outputs and
-/// can be configured at runtime.
-pub trait ConfigIterParser<'a, I, O, E = extra::Default>:
-    ConfigIterParserSealed<'a, I, O, E>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-{
-    /// A combinator that allows configuration of the parser from the current context
-    fn configure<F>(self, cfg: F) -> IterConfigure<Self, F, O>
+    /// Like [`Parser::repeated`], an iteration that consumes no input and does not break panics in debug builds and
+    /// stops in release builds.
+    ///
+    /// The output type of this parser is `B`, the type of the accumulator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// use std::ops::ControlFlow;
+    ///
+    /// // Statements are either words or `end`, which stops the block early
+    /// let stmt = text::ascii::ident::<_, _, extra::Err<Simple<char>>>().padded();
+    /// let block = stmt.iterate_with(Vec::new, |mut stmts, stmt, _| {
+    ///     if stmt == "end" {
+    ///         ControlFlow::Break(stmts)
+    ///     } else {
+    ///         stmts.push(stmt);
+    ///         ControlFlow::Continue(stmts)
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(block.parse("a b c").into_result(), Ok(vec!["a", "b", "c"]));
+    /// assert_eq!(
+    ///     block.lazy().parse("a b end c").into_result(),
+    ///     Ok(vec!["a", "b"]),
+    /// );
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn iterate_with<B, G, F>(self, init: G, f: F) -> IterateWith<Self, O, G, F>
     where
+        G: Fn() -> B,
+        F: Fn(B, O, &mut MapExtra<'a, '_, I, E>) -> ControlFlow<B, B>,
         Self: Sized,
-        F: Fn(Self::Config, &E::Context) -> Self::Config,
     {
-        IterConfigure {
+        IterateWith {
             parser: self,
-            cfg,
+            init,
+            f,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
             phantom: EmptyPhantom::new(),
         }
     }
 
-    /// A combinator that allows fallible configuration of the parser from the current context -
-    /// if an error is returned, parsing fails.
-    fn try_configure<F>(self, cfg: F) -> TryIterConfigure<Self, F, O>
+    /// Parse a pattern. Afterwards, the input stream will be rewound to its original state, as if parsing had not
+    /// occurred.
+    ///
+    /// This combinator is useful for cases in which you wish to avoid a parser accidentally consuming too much input,
+    /// causing later parsers to fail as a result. A typical use-case of this is that you want to parse something that
+    /// is not followed by something else.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let just_numbers = text::digits::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .slice()
+    ///     .padded()
+    ///     .then_ignore(none_of("+-*/").rewind())
+    ///     .separated_by(just(','))
+    ///     .collect::<Vec<_>>();
+    /// // 3 is not parsed because it's followed by '+'.
+    /// assert_eq!(just_numbers.lazy().parse("1, 2, 3 + 4").into_result(), Ok(vec!["1", "2"]));
+    /// ```
+    fn rewind(self) -> Rewind<Self>
     where
         Self: Sized,
-        F: Fn(Self::Config, &E::Context, I::Span) -> Result<Self::Config, E::Error>,
     {
-        TryIterConfigure {
-            parser: self,
-            cfg,
-            phantom: EmptyPhantom::new(),
-        }
-    }
-}
-
-/// See [`Parser::boxed`].
-///
-/// Due to current implementation details, the inner value is not, in fact, a [`Box`], but is an [`Rc`] to facilitate
-/// efficient cloning. This is likely to change in the future. Unlike [`Box`], [`Rc`] has no size guarantees: although
-/// it is *currently* the same size as a raw pointer.
-// TODO: Don't use an Rc
-pub struct Boxed<'a, 'b, I: Input<'a>, O, E: ParserExtra<'a, I>> {
-    inner: RefC<DynParser<'a, 'b, I, O, E>>,
-}
-
-impl<'a, 'b, I: Input<'a>, O, E: ParserExtra<'a, I>> Clone for Boxed<'a, 'b, I, O, E> {
-    fn clone(&self) -> Self {
-        Self {
-            inner: self.inner.clone(),
-        }
+        Rewind { parser: self }
     }
-}
 
-impl<'a, 'b, I, O, E> ParserSealed<'a, I, O, E> for Boxed<'a, 'b, I, O, E>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-{
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
-        M::invoke(&*self.inner, inp)
+    /// Make the parser lazy, such that it parses as much as it validly can and then finished successfully, leaving
+    /// trailing input untouched.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let digits = one_of::<_, _, extra::Err<Simple<char>>>('0'..='9')
+    ///     .repeated()
+    ///     .collect::<String>()
+    ///     .lazy();
+    ///
+    /// assert_eq!(digits.parse("12345abcde").into_result().as_deref(), Ok("12345"));
+    /// ```
+    fn lazy(self) -> Lazy<'a, Self, I, E>
+    where
+        Self: Sized,
+        I: ValueInput<'a>,
+    {
+        self.then_ignore(any().repeated())
     }
 
-    fn boxed<'c>(self) -> Boxed<'a, 'c, I, O, E>
+    /// Parse a pattern, ignoring any amount of whitespace both before and after the pattern.
+    ///
+    /// Whitespace is anything accepted by [`text::whitespace`], including newlines. In line-oriented grammars, use
+    /// [`Parser::padded_by`] with [`text::inline_whitespace`] instead, so that newlines are left for the parser to see.
+    /// Like [`text::whitespace`], this respects the layout of a [`text::block_of`].
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>().padded();
+    ///
+    /// // A pattern with no whitespace surrounding it is accepted
+    /// assert_eq!(ident.parse("hello").into_result(), Ok("hello"));
+    /// // A pattern with arbitrary whitespace surrounding it is also accepted
+    /// assert_eq!(ident.parse(" \t \n  \t   world  \t  ").into_result(), Ok("world"));
+    /// ```
+    fn padded(self) -> Padded<Self>
     where
-        Self: MaybeSync + Sized + 'a + 'c,
+        Self: Sized,
+        I: Input<'a>,
+        I::Token: Char,
     {
-        // Never double-box parsers
-        self
+        Padded { parser: self }
     }
 
-    go_extra!(O);
-}
-
-impl<'a, I, O, E, T> ParserSealed<'a, I, O, E> for ::alloc::boxed::Box<T>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-    T: Parser<'a, I, O, E>,
-{
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    /// Like [`Parser::padded`], but also produce the spans of the whitespace that was skipped before and after the
+    /// pattern.
+    ///
+    /// The spans are empty when there was no whitespace to skip. This is useful for tools that need to know where the
+    /// gaps between items are, such as formatters, without keeping a full record of the trivia in the input.
+    ///
+    /// The output type of this parser is `(I::Span, O, I::Span)`: the span of the leading whitespace, the output of
+    /// the original parser, and the span of the trailing whitespace.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>().padded_with_spans();
+    ///
+    /// assert_eq!(
+    ///     ident.parse("  hello\n").into_result(),
+    ///     Ok((SimpleSpan::from(0..2), "hello", SimpleSpan::from(7..8))),
+    /// );
+    /// assert_eq!(
+    ///     ident.parse("world").into_result(),
+    ///     Ok((SimpleSpan::from(0..0), "world", SimpleSpan::from(5..5))),
+    /// );
+    /// ```
+    fn padded_with_spans(self) -> text::PaddedWithSpans<Self>
     where
         Self: Sized,
+        I: Input<'a>,
+        I::Token: Char,
     {
-        T::go::<M>(self, inp)
+        text::PaddedWithSpans { parser: self }
     }
 
-    go_extra!(O);
-}
-
-impl<'a, I, O, E, T> ParserSealed<'a, I, O, E> for ::alloc::rc::Rc<T>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-    T: Parser<'a, I, O, E>,
-{
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    /// Parse a pattern, failing if it spans more than one line.
+    ///
+    /// This is useful in layout-sensitive grammars, where some constructs (such as the header of a block) must fit on
+    /// a single line, even if the parsers that they are built from accept newlines. The error points at the first
+    /// newline within the pattern.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let words = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .padded()
+    ///     .repeated()
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(words.parse("a b\nc").into_result(), Ok(vec!["a", "b", "c"]));
+    /// assert!(words.same_line().parse("a b\nc").has_errors());
+    /// ```
+    fn same_line(self) -> text::SameLine<Self>
     where
         Self: Sized,
+        I::Token: Char,
     {
-        T::go::<M>(self, inp)
+        text::SameLine { parser: self }
     }
 
-    go_extra!(O);
-}
-
-impl<'a, I, O, E, T> ParserSealed<'a, I, O, E> for ::alloc::sync::Arc<T>
-where
-    I: Input<'a>,
-    E: ParserExtra<'a, I>,
-    T: Parser<'a, I, O, E>,
-{
-    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    /// Parse a sequence of patterns that all start in the same column, each on its own line.
+    ///
+    /// Whitespace, including newlines, is skipped before each pattern, and the column of the first pattern determines
+    /// the column of the rest. The sequence ends at the first pattern that starts in a different column, or that fails
+    /// to parse. At least one pattern must be parsed. See [`text::block_of`] to also require that patterns which
+    /// continue onto further lines are indented, following the 'offside rule'.
+    ///
+    /// Columns are counted in characters, so a tab is a single column.
+    ///
+    /// The output type of this iterable parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let stmt = text::ascii::ident::<_, _, extra::Err<Simple<char>>>();
+    /// let stmts = stmt.aligned().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(stmts.parse("  a\n  b\n\n  c").into_result(), Ok(vec!["a", "b", "c"]));
+    /// // `c` is not aligned with the others, so it is not part of the sequence
+    /// assert!(stmts.parse("  a\n  b\n c").has_errors());
+    /// assert_eq!(
+    ///     stmts.lazy().parse("  a\n  b\n c").into_result(),
+    ///     Ok(vec!["a", "b"]),
+    /// );
+    /// ```
+    fn aligned(self) -> text::Aligned<Self>
     where
         Self: Sized,
+        I::Token: Char,
     {
-        T::go::<M>(self, inp)
+        text::Aligned {
+            parser: self,
+            offside: false,
+        }
     }
 
-    go_extra!(O);
-}
-
-/// Create a parser that selects one or more input patterns and map them to an output value.
-///
-/// This is most useful when turning the tokens of a previous compilation pass (such as lexing) into data that can be
-/// used for parsing, although it can also generally be used to select inputs and map them to outputs. Any unmapped
-/// input patterns will become syntax errors, just as with [`Parser::filter`].
-///
-/// Internally, [`select!`] is very similar to [`Parser::try_map`] and thinking of it as such might make it less
+    /// Parse one thing and then another thing, concatenating their outputs into a single [`Vec`].
+    ///
+    /// Each output may be a single item, an [`Option`] (contributing zero or one items), a [`Vec`] or an array (see
+    /// [`Chain`]). This is useful for composing sequences of tokens or characters, such as when building up a literal in
+    /// a lexer, without manually rearranging the outputs of [`Parser::then`].
+    ///
+    /// Since a [`Vec`] of items could also be chained as a single item, the item type sometimes needs to be annotated.
+    ///
+    /// The output type of this parser is `Vec<T>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let digits = text::digits::<_, _, extra::Err<Simple<char>>>(10).collect::<Vec<_>>();
+    /// let int = one_of("+-")
+    ///     .or_not()
+    ///     .chain(digits)
+    ///     .map(|chars: Vec<char>| chars.into_iter().collect::<String>());
+    ///
+    /// assert_eq!(int.parse("-42").into_result(), Ok("-42".to_string()));
+    /// assert_eq!(int.parse("7").into_result(), Ok("7".to_string()));
+    /// ```
+    fn chain<T, U, B>(self, other: B) -> Map<Then<Self, B, O, U, E>, (O, U), fn((O, U)) -> Vec<T>>
+    where
+        Self: Sized,
+        B: Parser<'a, I, U, E>,
+        O: Chain<T>,
+        U: Chain<T>,
+    {
+        let chain: fn((O, U)) -> Vec<T> = |(a, b)| {
+            let mut v = Vec::with_capacity(a.chain_len() + b.chain_len());
+            a.append_to(&mut v);
+            b.append_to(&mut v);
+            v
+        };
+        self.then(other).map(chain)
+    }
+
+    /// Flatten a nested collection.
+    ///
+    /// The use-cases of this method are broadly similar to those of [`Iterator::flatten`].
+    ///
+    /// The output type of this parser is `Vec<T>`, where the original parser output was
+    /// `impl IntoIterator<Item = impl IntoIterator<Item = T>>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// // A word, followed by any number of `-`-separated words, with the separators dropped
+    /// let word = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .map(|w: &str| w.chars().collect::<Vec<_>>());
+    /// let words = word.separated_by(just('-')).collect::<Vec<_>>().flatten();
+    ///
+    /// assert_eq!(words.parse("ab-c").into_result(), Ok(vec!['a', 'b', 'c']));
+    /// ```
+    fn flatten<T, Inner>(self) -> Map<Self, O, fn(O) -> Vec<T>>
+    where
+        Self: Sized,
+        O: IntoIterator<Item = Inner>,
+        Inner: IntoIterator<Item = T>,
+    {
+        let flatten: fn(O) -> Vec<T> = |xs| xs.into_iter().flatten().collect();
+        self.map(flatten)
+    }
+
+    /// Apply a fallback recovery strategy to this parser should it fail.
+    ///
+    /// There is no silver bullet for error recovery, so this function allows you to specify one of several different
+    /// strategies at the location of your choice. Prefer an error recovery strategy that more precisely mirrors valid
+    /// syntax where possible to make error recovery more reliable.
+    ///
+    /// Because chumsky is a [PEG](https://en.m.wikipedia.org/wiki/Parsing_expression_grammar) parser, which always
+    /// take the first successful parsing route through a grammar, recovering from an error may cause the parser to
+    /// erroneously miss alternative valid routes through the grammar that do not generate recoverable errors. If you
+    /// run into cases where valid syntax fails to parse without errors, this might be happening: consider removing
+    /// error recovery or switching to a more specific error recovery strategy.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// #[derive(Debug, PartialEq)]
+    /// enum Expr<'a> {
+    ///     Error,
+    ///     Int(&'a str),
+    ///     List(Vec<Expr<'a>>),
+    /// }
+    ///
+    /// let recovery = just::<_, _, extra::Err<Simple<char>>>('[')
+    ///         .then(none_of(']').repeated().then(just(']')));
+    ///
+    /// let expr = recursive::<_, _, extra::Err<Simple<char>>, _, _>(|expr| expr
+    ///     .separated_by(just(','))
+    ///     .collect::<Vec<_>>()
+    ///     .delimited_by(just('['), just(']'))
+    ///     .map(Expr::List)
+    ///     // If parsing a list expression fails, recover at the next delimiter, generating an error AST node
+    ///     .recover_with(via_parser(recovery.map(|_| Expr::Error)))
+    ///     .or(text::int(10).map(Expr::Int))
+    ///     .padded());
+    ///
+    /// assert!(expr.parse("five").has_errors()); // Text is not a valid expression in this language...
+    /// assert_eq!(
+    ///     expr.parse("[1, 2, 3]").into_result(),
+    ///     Ok(Expr::List(vec![Expr::Int("1"), Expr::Int("2"), Expr::Int("3")])),
+    /// ); // ...but lists and numbers are!
+    ///
+    /// // This input has two syntax errors...
+    /// let res = expr.parse("[[1, two], [3, four]]");
+    /// // ...and error recovery allows us to catch both of them!
+    /// assert_eq!(res.errors().len(), 2);
+    /// // Additionally, the AST we get back still has useful information.
+    /// assert_eq!(res.output(), Some(&Expr::List(vec![Expr::Error, Expr::Error])));
+    /// ```
+    fn recover_with<S: Strategy<'a, I, O, E>>(self, strategy: S) -> RecoverWith<Self, S>
+    where
+        Self: Sized,
+    {
+        RecoverWith {
+            parser: self,
+            strategy,
+        }
+    }
+
+    /// Recover from this parser failing by pretending that the given token was present in the input, then parsing
+    /// again.
+    ///
+    /// If the parser fails, `token` is [pushed back](InputRef::push_back) onto the input at the position where the
+    /// parser started, and the parser is tried again. If it then succeeds having consumed the inserted token, the
+    /// original error is emitted and parsing continues. Otherwise, the parser fails as it did originally.
+    ///
+    /// This suits tokens that are easy to forget, such as semicolons, commas and closing delimiters, for which
+    /// skipping input in the hope of finding them would discard far more than is needed. Since the token is inserted
+    /// where the parser starts, this is usually applied to the parser of the token itself, or to a parser that begins
+    /// with it.
+    ///
+    /// When recovery takes place, the parser succeeds without consuming any of the original input. It should therefore
+    /// not be [repeated](Parser::repeated) on its own, as the repetition would never end of its own accord: pair it
+    /// with a parser that consumes input, as in the example below.
+    ///
+    /// The inserted token is subject to the same restrictions as any other pushed token: it can only be read by value,
+    /// so the parser must not borrow it with [`select_ref!`], and it never appears in slices of the input.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let stmt = text::ascii::ident::<_, _, extra::Err<Rich<char>>>()
+    ///     .padded()
+    ///     .then_ignore(just('='))
+    ///     .then(text::int(10).padded())
+    ///     .then_ignore(just(';').recover_via_insertion(';'));
+    /// let stmts = stmt.repeated().collect::<Vec<_>>();
+    ///
+    /// let (out, errs) = stmts.parse("a = 1; b = 2 c = 3;").into_output_errors();
+    /// // Every statement is parsed, despite the missing `;`
+    /// assert_eq!(out, Some(vec![("a", "1"), ("b", "2"), ("c", "3")]));
+    /// assert_eq!(errs.len(), 1);
+    /// assert_eq!(errs[0].span(), &SimpleSpan::new(13, 14));
+    /// ```
+    fn recover_via_insertion(self, token: I::Token) -> RecoverViaInsertion<Self, I::Token>
+    where
+        Self: Sized,
+        I::Token: Clone,
+    {
+        RecoverViaInsertion {
+            parser: self,
+            token,
+        }
+    }
+
+    /// Map the primary error of this parser to another value.
+    ///
+    /// This function is most useful when using a custom error type, allowing you to augment errors according to
+    /// context.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    // TODO: Map E -> D, not E -> E
+    fn map_err<F>(self, f: F) -> MapErr<Self, F>
+    where
+        Self: Sized,
+        F: Fn(E::Error) -> E::Error,
+    {
+        MapErr {
+            parser: self,
+            mapper: f,
+        }
+    }
+
+    /// Suggest inserting the given text if this parser fails, so that editors can offer to fix the error automatically.
+    ///
+    /// When this parser fails at the position where it started (such as when a required semicolon or closing
+    /// delimiter is missing), the error is annotated with a suggestion to insert `text` at that position. Errors that
+    /// occur further into the pattern are left alone, since inserting the text is unlikely to fix them.
+    ///
+    /// The parser still fails: suggestions are only a hint attached to the error. To also continue parsing as if the
+    /// text had been present, combine this with error recovery.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Suggestion};
+    /// let stmt = text::ascii::ident::<_, _, extra::Err<Rich<char>>>()
+    ///     .then_ignore(just(';').or_suggest_insert(";"));
+    ///
+    /// let errs = stmt.parse("foo").into_errors();
+    /// assert_eq!(
+    ///     errs[0].suggestions().collect::<Vec<_>>(),
+    ///     [&Suggestion { span: SimpleSpan::new(3, 3), replacement: ";".to_string() }],
+    /// );
+    /// ```
+    fn or_suggest_insert<T: ToString>(self, text: T) -> SuggestInsert<Self>
+    where
+        Self: Sized,
+        E::Error: SuggestionError<'a, I>,
+    {
+        SuggestInsert {
+            parser: self,
+            text: text.to_string(),
+        }
+    }
+
+    /// Convert the errors produced by this parser into a different error type.
+    ///
+    /// This lets a parser written with one error type be used in a parser that uses another, provided the errors can
+    /// be converted with [`From`]. Chumsky's own error types can be converted between each other: [`Rich`] and
+    /// [`Simple`] can be downgraded to simpler types without any extra information, while upgrading a simpler error
+    /// type to a richer one fills in whatever information the simpler error does not record.
+    ///
+    /// This is most useful for libraries, which can expose parsers that use a minimal error type such as [`Cheap`] (or
+    /// are generic over their error type) while still allowing applications to use them with richer error types.
+    ///
+    /// The parser's state and context types must be the same before and after the conversion.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// // A parser exposed by a library, which doesn't care much about error quality
+    /// fn number<'a>() -> impl Parser<'a, &'a str, u32, extra::Err<Cheap>> {
+    ///     text::int(10).from_str().unwrapped()
+    /// }
+    ///
+    /// // An application that wants rich errors can still make use of it
+    /// let list = number()
+    ///     .map_err_to::<extra::Err<Rich<char>>>()
+    ///     .separated_by(just(','))
+    ///     .collect::<Vec<_>>()
+    ///     .delimited_by(just('['), just(']'));
+    ///
+    /// assert_eq!(list.parse("[1,2,3]").into_result(), Ok(vec![1, 2, 3]));
+    ///
+    /// let errs = list.parse("[1,2,x]").into_errors();
+    /// assert_eq!(errs.len(), 1);
+    /// assert_eq!(errs[0].span(), &SimpleSpan::from(5..6));
+    /// ```
+    fn map_err_to<E2>(self) -> MapErrTo<Self, E, E2>
+    where
+        Self: Sized,
+        E2: ParserExtra<'a, I, State = E::State, Context = E::Context>,
+        E2::Error: From<E::Error>,
+    {
+        MapErrTo {
+            parser: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Call a function with a reference to the primary error of this parser when it fails, without changing it.
+    ///
+    /// This is useful for side effects such as logging or collecting metrics. Like [`Parser::inspect`], the function is
+    /// only called when the parser is generating outputs, and has no cost otherwise.
+    ///
+    /// Note that the error passed to the function may not be the error that is eventually reported: if the parser is
+    /// part of a larger pattern, the failure may be recovered from or another alternative may succeed.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// # use std::cell::Cell;
+    /// let failures = Cell::new(0);
+    /// let digit = one_of::<_, _, extra::Err<Simple<char>>>('0'..='9')
+    ///     .inspect_err(|_| failures.set(failures.get() + 1));
+    /// let num = digit.or(just('x')).repeated().collect::<String>();
+    ///
+    /// assert_eq!(num.parse("1x2").into_result(), Ok("1x2".to_string()));
+    /// // The digit parser failed on 'x', and at the end of the input
+    /// assert_eq!(failures.get(), 2);
+    /// ```
+    fn inspect_err<F: Fn(&E::Error)>(self, f: F) -> InspectErr<Self, F>
+    where
+        Self: Sized,
+    {
+        InspectErr { parser: self, f }
+    }
+
+    // /// Map the primary error of this parser to another value, making use of the span from the start of the attempted
+    // /// to the point at which the error was encountered.
+    // ///
+    // /// This function is useful for augmenting errors to allow them to display the span of the initial part of a
+    // /// pattern, for example to add a "while parsing" clause to your error messages.
+    // ///
+    // /// The output type of this parser is `O`, the same as the original parser.
+    // ///
+    // // TODO: Map E -> D, not E -> E
+    // fn map_err_with_span<F>(self, f: F) -> MapErrWithSpan<Self, F>
+    // where
+    //     Self: Sized,
+    //     F: Fn(E::Error, I::Span) -> E::Error,
+    // {
+    //     MapErrWithSpan {
+    //         parser: self,
+    //         mapper: f,
+    //     }
+    // }
+
+    /// Map the primary error of this parser to another value, making use of the parser state.
+    ///
+    /// This function is useful for augmenting errors to allow them to include context in non context-free
+    /// languages, or provide contextual notes on possible causes.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    // TODO: Map E -> D, not E -> E
+    fn map_err_with_state<F>(self, f: F) -> MapErrWithState<Self, F>
+    where
+        Self: Sized,
+        F: Fn(E::Error, I::Span, &mut E::State) -> E::Error,
+    {
+        MapErrWithState {
+            parser: self,
+            mapper: f,
+        }
+    }
+
+    /// Validate an output, producing non-terminal errors if it does not fulfill certain criteria.
+    /// The errors will not immediately halt parsing on this path, but instead it will continue,
+    /// potentially emitting one or more other errors, only failing after the pattern has otherwise
+    /// successfully, or emitted another terminal error.
+    ///
+    /// This function also permits mapping the output to a value of another type, similar to [`Parser::map`].
+    ///
+    /// If you wish parsing of this pattern to halt when an error is generated instead of continuing, consider using
+    /// [`Parser::try_map`] instead.
+    ///
+    /// The output type of this parser is `U`, the result of the validation closure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let large_int = text::int::<_, _, extra::Err<Rich<char>>>(10)
+    ///     .from_str()
+    ///     .unwrapped()
+    ///     .validate(|x: u32, span, emitter| {
+    ///         if x < 256 { emitter.emit(Rich::custom(span, format!("{} must be 256 or higher.", x))) }
+    ///         x
+    ///     });
+    ///
+    /// assert_eq!(large_int.parse("537").into_result(), Ok(537));
+    /// assert!(large_int.parse("243").into_result().is_err());
+    /// ```
+    ///
+    /// To show the difference in behavior from [`Parser::try_map`]:
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// # use chumsky::util::MaybeRef;
+    /// # use chumsky::error::Error;
+    /// // start with the same large_int validator
+    /// let large_int_val = text::int::<_, _, extra::Err<Rich<char>>>(10)
+    ///         .from_str()
+    ///         .unwrapped()
+    ///         .validate(|x: u32, span, emitter| {
+    ///             if x < 256 { emitter.emit(Rich::custom(span, format!("{} must be 256 or higher", x))) }
+    ///             x
+    ///         });
+    ///
+    /// // A try_map version of the same parser
+    /// let large_int_tm = text::int::<_, _, extra::Err<Rich<char>>>(10)
+    ///         .from_str()
+    ///         .unwrapped()
+    ///         .try_map(|x: u32, span| {
+    ///             if x < 256 {
+    ///                 Err(Rich::custom(span, format!("{} must be 256 or higher", x)))
+    ///             } else {
+    ///                 Ok(x)
+    ///             }
+    ///         });
+    ///
+    /// // Parser that uses the validation version
+    /// let multi_step_val = large_int_val.then(text::ascii::ident().padded());
+    /// // Parser that uses the try_map version
+    /// let multi_step_tm = large_int_tm.then(text::ascii::ident().padded());
+    ///
+    /// // On success, both parsers are equivalent
+    /// assert_eq!(
+    ///     multi_step_val.parse("512 foo").into_result(),
+    ///     Ok((512, "foo"))
+    /// );
+    ///
+    /// assert_eq!(
+    ///     multi_step_tm.parse("512 foo").into_result(),
+    ///     Ok((512, "foo"))
+    /// );
+    ///
+    /// // However, on failure, they may produce different errors:
+    /// assert_eq!(
+    ///     multi_step_val.parse("100 2").into_result(),
+    ///     Err(vec![
+    ///         Rich::<char>::custom((0..3).into(), "100 must be 256 or higher"),
+    ///         <Rich<char> as Error<&str>>::expected_found([], Some(MaybeRef::Val('2')), (4..5).into()),
+    ///     ])
+    /// );
+    ///
+    /// assert_eq!(
+    ///     multi_step_tm.parse("100 2").into_result(),
+    ///     Err(vec![Rich::<char>::custom((0..3).into(), "100 must be 256 or higher")])
+    /// );
+    /// ```
+    ///
+    /// As is seen in the above example, validation doesn't prevent the emission of later errors in the
+    /// same parser, but still produces an error in the output.
+    ///
+    fn validate<U, F>(self, f: F) -> Validate<Self, O, F>
+    where
+        Self: Sized,
+        F: Fn(O, I::Span, &mut Emitter<E::Error>) -> U,
+    {
+        Validate {
+            parser: self,
+            validator: f,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    // /// Map the primary error of this parser to a result. If the result is [`Ok`], the parser succeeds with that value.
+    // ///
+    // /// Note that, if the closure returns [`Err`], the parser will not consume any input.
+    // ///
+    // /// The output type of this parser is `U`, the [`Ok`] type of the result.
+    // fn or_else<F>(self, f: F) -> OrElse<Self, F>
+    // where
+    //     Self: Sized,
+    //     F: Fn(E::Error) -> Result<O, E::Error>,
+    // {
+    //     OrElse {
+    //         parser: self,
+    //         or_else: f,
+    //     }
+    // }
+
+    /// Attempt to convert the output of this parser into something else using Rust's [`FromStr`] trait.
+    ///
+    /// This is most useful when wanting to convert literal values into their corresponding Rust type, such as when
+    /// parsing integers.
+    ///
+    /// The output type of this parser is `Result<U, U::Err>`, the result of attempting to parse the output, `O`, into
+    /// the value `U`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let uint64 = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .from_str::<u64>()
+    ///     .unwrapped();
+    ///
+    /// assert_eq!(uint64.parse("7").into_result(), Ok(7));
+    /// assert_eq!(uint64.parse("42").into_result(), Ok(42));
+    /// ```
+    #[allow(clippy::wrong_self_convention)]
+    fn from_str<U>(self) -> Map<Self, O, fn(O) -> Result<U, U::Err>>
+    where
+        Self: Sized,
+        U: FromStr,
+        O: AsRef<str>,
+    {
+        self.map(|o| o.as_ref().parse())
+    }
+
+    /// For parsers that produce a [`Result`] as their output, unwrap the result (panicking if an [`Err`] is
+    /// encountered).
+    ///
+    /// In general, this method should be avoided except in cases where all possible that the parser might produce can
+    /// by parsed using [`FromStr`] without producing an error.
+    ///
+    /// This combinator is not named `unwrap` to avoid confusion: it unwraps *during parsing*, not immediately.
+    ///
+    /// The output type of this parser is `U`, the [`Ok`] value of the [`Result`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let boolean = just::<_, _, extra::Err<Simple<char>>>("true")
+    ///     .or(just("false"))
+    ///     .from_str::<bool>()
+    ///     .unwrapped(); // Cannot panic: the only possible outputs generated by the parser are "true" or "false"
+    ///
+    /// assert_eq!(boolean.parse("true").into_result(), Ok(true));
+    /// assert_eq!(boolean.parse("false").into_result(), Ok(false));
+    /// // Does not panic, because the original parser only accepts "true" or "false"
+    /// assert!(boolean.parse("42").has_errors());
+    /// ```
+    #[track_caller]
+    fn unwrapped(self) -> Unwrapped<Self, O>
+    where
+        Self: Sized,
+    {
+        Unwrapped {
+            parser: self,
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Turn this [`Parser`] into an [`IterParser`] if its output type implements [`IntoIterator`].
+    ///
+    /// The resulting iterable parser will emit each element of the output type in turn.
+    ///
+    /// This is *broadly* analogous to functions like [`Vec::into_iter`], but operating at the level of parser outputs.
+    // TODO: Example
+    fn into_iter(self) -> IntoIter<Self, O>
+    where
+        Self: Sized,
+        O: IntoIterator,
+    {
+        IntoIter {
+            parser: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Walk the structure of this parser, passing each of the parsers that it is made of to the given
+    /// [`Visitor`](walk::Visitor).
+    ///
+    /// Parsers are visited depth-first: each parser is [entered](walk::Visitor::enter) before, and
+    /// [left](walk::Visitor::leave) after, the parsers within it. See the [`walk`] module for details of what is visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, walk::{ParserNode, Visitor}};
+    /// // Finds `todo()` placeholders, recording the combinators that lead to each one
+    /// #[derive(Default)]
+    /// struct FindTodos {
+    ///     path: Vec<&'static str>,
+    ///     found: Vec<String>,
+    /// }
+    ///
+    /// impl Visitor for FindTodos {
+    ///     fn enter(&mut self, node: &ParserNode) -> bool {
+    ///         self.path.push(node.name());
+    ///         if node.name() == "Todo" {
+    ///             self.found.push(self.path.join(" > "));
+    ///         }
+    ///         true
+    ///     }
+    ///
+    ///     fn leave(&mut self, _: &ParserNode) {
+    ///         self.path.pop();
+    ///     }
+    /// }
+    ///
+    /// let int = text::int::<&str, _, extra::Err<Simple<char>>>(10);
+    /// let float = todo();
+    /// let number = float.or(int.map(|s: &str| s.parse::<f64>().unwrap()));
+    ///
+    /// let mut todos = FindTodos::default();
+    /// number.walk(&mut todos);
+    /// assert_eq!(todos.found, ["Or > Todo"]);
+    /// ```
+    fn walk<V: walk::Visitor>(&self, visitor: &mut V)
+    where
+        Self: Sized,
+    {
+        self.walk_node(&mut Walker::new(visitor));
+    }
+
+    /// Check that this parser contains no [`todo()`](fn@todo) placeholders, returning the locations at which any
+    /// remaining placeholders were created.
+    ///
+    /// Placeholders otherwise only make themselves known by panicking when they are used to parse input, which may
+    /// not happen until an unusual input is encountered in production. Calling this in a test makes it possible to
+    /// catch them in CI instead. See [`Parser::walk`] for details of which parsers are checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let digits = text::digits::<_, &str, extra::Err<Simple<char>>>(10).slice();
+    /// let hex = just("0x").ignore_then(todo());
+    ///
+    /// assert!(digits.assert_complete().is_ok());
+    ///
+    /// let number = hex.or(digits);
+    /// let err = number.assert_complete().unwrap_err();
+    /// assert_eq!(err.locations().len(), 1);
+    /// assert!(err.to_string().starts_with("parser contains unimplemented placeholders created at "));
+    /// ```
+    fn assert_complete(&self) -> Result<(), walk::Incomplete>
+    where
+        Self: Sized,
+    {
+        let mut todos = walk::FindTodos::new();
+        self.walk(&mut todos);
+        todos.finish()
+    }
+
+    /// Box the parser, yielding a parser that performs parsing through dynamic dispatch.
+    ///
+    /// Boxing a parser might be useful for:
+    ///
+    /// - Dynamically building up parsers at run-time
+    ///
+    /// - Improving compilation times (Rust can struggle to compile code containing very long types)
+    ///
+    /// - Passing a parser over an FFI boundary
+    ///
+    /// - Getting around compiler implementation problems with long types such as
+    ///   [this](https://github.com/rust-lang/rust/issues/54540).
+    ///
+    /// - Places where you need to name the type of a parser
+    ///
+    /// Boxing a parser is broadly equivalent to boxing other combinators via dynamic dispatch, such as [`Iterator`].
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// When not using `boxed`, the following patterns are either impossible or very difficult to express:
+    ///
+    /// ```compile_fail
+    /// # use chumsky::prelude::*;
+    ///
+    /// pub trait Parseable: Sized {
+    ///     type Parser<'a>: Parser<'a, &'a str, Self>;
+    ///
+    ///     fn parser<'a>() -> Self::Parser<'a>;
+    /// }
+    ///
+    /// impl Parseable for i32 {
+    ///     // We *can* write this type, but it will be very impractical, and change on any alterations
+    ///     // to the implementation
+    ///     type Parser<'a> = ???;
+    ///
+    ///     fn parser<'a>() -> Self::Parser<'a> {
+    ///         todo()
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// ```compile_fail
+    /// # use chumsky::prelude::*;
+    /// # fn user_input<'a>() -> impl IntoIterator<Item = impl Parser<'a, &'a str, char>> { [just('b')] }
+    ///
+    /// let user_input = user_input();
+    ///
+    /// let mut parser = just('a');
+    /// for i in user_input {
+    ///     // Doesn't work due to type mismatch - since every combinator creates a unique type
+    ///     parser = parser.or(i);
+    /// }
+    ///
+    /// let parser = parser.then(just('z'));
+    /// let _ = parser.parse("b").into_result();
+    /// ```
+    ///
+    /// However, with `boxed`, we can express them by making the parsers all share a common type:
+    ///
+    /// ```
+    /// use chumsky::prelude::*;
+    ///
+    /// pub trait Parseable: Sized {
+    ///     fn parser<'a>() -> Boxed<'a, 'a, &'a str, Self, extra::Default>;
+    /// }
+    ///
+    /// impl Parseable for i32 {
+    ///     fn parser<'a>() -> Boxed<'a, 'a, &'a str, Self, extra::Default> {
+    ///         todo().boxed()
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// # fn user_input<'a>() -> impl IntoIterator<Item = impl Parser<'a, &'a str, char>> { [just('b'), just('c')] }
+    /// let user_input = user_input();
+    /// let mut parser = just('a').boxed();
+    /// for i in user_input {
+    ///     // Doesn't work due to type mismatch - since every combinator creates a unique type
+    ///     parser = parser.or(i).boxed();
+    /// }
+    /// let parser = parser.then(just('z'));
+    /// parser.parse("az").into_result().unwrap();
+    /// ```
+    ///
+    fn boxed<'b>(self) -> Boxed<'a, 'b, I, O, E>
+    where
+        Self: MaybeSync + Sized + 'a + 'b,
+    {
+        ParserSealed::boxed(self)
+    }
+
+    /// Move this parser behind a reference-counted pointer, making it cheap to clone.
+    ///
+    /// Cloning a parser normally clones every parser it is composed of, along with any data they own (such as the
+    /// sequences given to [`just`] or the containers given to [`one_of`]). For large grammars that are cloned often,
+    /// such as those stored in closures or shared between several tasks, this can be surprisingly expensive. Cloning a
+    /// shared parser only increments a reference count, no matter how large the parser is.
+    ///
+    /// Unlike [`Parser::boxed`], the type of the parser is not erased, so there is no dynamic dispatch when parsing.
+    /// When the `sync` feature is enabled, the reference count is atomic, so shared parsers may be sent between
+    /// threads.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let keywords = ["fn", "let", "if", "else", "while", "for", "loop", "return"]
+    ///     .map(|kw| text::ascii::keyword::<_, _, _, extra::Default>(kw).to(kw.len()));
+    /// let keyword = choice(keywords).shared();
+    ///
+    /// // Cloning the parser does not clone the keywords
+    /// let words = keyword.clone().padded().repeated().collect::<Vec<_>>();
+    /// assert_eq!(words.parse("let while return").into_result(), Ok(vec![3, 5, 6]));
+    /// assert_eq!(keyword.parse("loop").into_result(), Ok(4));
+    /// ```
+    fn shared(self) -> Shared<Self>
+    where
+        Self: Sized,
+    {
+        Shared {
+            inner: RefC::new(self),
+        }
+    }
+
+    /// Box this parser if the `simplify-types` feature is enabled, or leave it untouched otherwise.
+    ///
+    /// Every combinator wraps the type of the parser it is applied to, so the type of a large grammar can become
+    /// enormous. This can cause very long compile times, particularly in debug builds, and may even cause rustc to
+    /// exceed its type length limit. [`Parser::boxed`] solves this by erasing the type of a parser, but comes with a
+    /// small runtime cost that you may not wish to pay in release builds.
+    ///
+    /// Calling this method at natural boundaries in your grammar (such as the end of each rule) lets you choose
+    /// between the two with a single switch: when the `simplify-types` feature is enabled, each call is equivalent to
+    /// [`Parser::boxed`], and when it is disabled, each call does nothing. Because the type of a parser is decided
+    /// before it is boxed, chumsky cannot insert boxes automatically: calls must be placed by hand, but grammars need
+    /// not otherwise change to switch between modes.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// fn list<'a>() -> impl Parser<'a, &'a str, Vec<u32>> {
+    ///     let int = text::int(10)
+    ///         .from_str()
+    ///         .unwrapped()
+    ///         .padded()
+    ///         .simplify_types();
+    ///
+    ///     int.separated_by(just(','))
+    ///         .collect()
+    ///         .delimited_by(just('['), just(']'))
+    ///         .simplify_types()
+    /// }
+    ///
+    /// assert_eq!(list().parse("[1, 2, 3]").into_result(), Ok(vec![1, 2, 3]));
+    /// ```
+    #[cfg(feature = "simplify-types")]
+    fn simplify_types(self) -> Boxed<'a, 'a, I, O, E>
+    where
+        Self: MaybeSync + Sized + 'a,
+    {
+        Parser::boxed(self)
+    }
+
+    /// Box this parser if the `simplify-types` feature is enabled, or leave it untouched otherwise.
+    ///
+    /// The `simplify-types` feature is disabled, so this returns the parser unchanged. When it is enabled, this is
+    /// equivalent to [`Parser::boxed`], erasing the type of the parser.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    #[cfg(not(feature = "simplify-types"))]
+    fn simplify_types(self) -> Self
+    where
+        Self: MaybeSync + Sized + 'a,
+    {
+        self
+    }
+
+    /// Use pratt-parsing to efficiently parse expressions separated by
+    /// operators of different associativity and precedence.
+    ///
+    /// The pratt-parsing algorithm uses recursion to efficiently parse
+    /// arbitrarily nested expressions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chumsky::prelude::*;
+    /// use chumsky::pratt::*;
+    /// use chumsky::extra;
+    ///
+    /// enum Expr {
+    ///     Add(Box<Self>, Box<Self>),
+    ///     Sub(Box<Self>, Box<Self>),
+    ///     Pow(Box<Self>, Box<Self>),
+    ///     Neg(Box<Self>),
+    ///     Fact(Box<Self>),
+    ///     Deref(Box<Self>),
+    ///     Literal(i32),
+    /// }
+    ///
+    /// impl std::fmt::Display for Expr {
+    ///     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    ///         match self {
+    ///             Self::Literal(literal) => write!(f, "{literal}"),
+    ///             Self::Fact(left) => write!(f, "({left}!)"),
+    ///             Self::Deref(left) => write!(f, "(*{left})"),
+    ///             Self::Neg(right) => write!(f, "(-{right})"),
+    ///             Self::Add(left, right) => write!(f, "({left} + {right})"),
+    ///             Self::Sub(left, right) => write!(f, "({left} - {right})"),
+    ///             Self::Pow(left, right) => write!(f, "({left} ^ {right})"),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let atom = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .from_str()
+    ///     .unwrapped()
+    ///     .map(Expr::Literal);
+    ///
+    /// let operator = choice((
+    ///     // Our `-` and `+` bind the weakest, meaning that even if they occur
+    ///     // first in an expression, they will be the last executed
+    ///     left_infix(just('+'), 1, |l, r| Expr::Add(Box::new(l), Box::new(r))),
+    ///     left_infix(just('-'), 1, |l, r| Expr::Sub(Box::new(l), Box::new(r))),
+    ///     // Just like in math, we want that if we write -x^2, that our parser
+    ///     // parses that as -(x^2), so we need it to bind tighter than our
+    ///     // prefix operators
+    ///     right_infix(just('^'), 3, |l, r| Expr::Pow(Box::new(l), Box::new(r))),
+    /// ))
+    /// .padded();
+    ///
+    /// let prefix_ops = choice((
+    ///     // Notice the conflict with our `Expr::Sub`. This will still
+    ///     // parse correctly. We want negation to happen before `+` and `-`,
+    ///     // so we set it's precedence higher.
+    ///     prefix(just('-'), 2, |rhs| Expr::Neg(Box::new(rhs))),
+    ///     prefix(just('*'), 2, |rhs| Expr::Deref(Box::new(rhs))),
+    /// ))
+    /// .padded();
+    ///
+    /// // We want factorial to happen before any negation, so we need it's
+    /// // precedence to be higher than `Expr::Neg`.
+    /// let factorial = postfix(just('!'), 4, |lhs| Expr::Fact(Box::new(lhs))).padded();
+    ///
+    /// let pratt = atom
+    ///     .pratt(operator)
+    ///     .with_prefix_ops(prefix_ops)
+    ///     .with_postfix_ops(factorial)
+    ///     .map(|x| x.to_string());
+    ///
+    /// assert_eq!(
+    ///     pratt.parse("*1 + -2! - -3^2").into_result(),
+    ///     Ok("(((*1) + (-(2!))) - (-(3 ^ 2)))".to_string()),
+    /// );
+    /// ```
+    #[cfg(feature = "pratt")]
+    fn pratt<InfixOps, InfixOpsOut>(
+        self,
+        ops: InfixOps,
+    ) -> Pratt<I, O, E, Self, pratt::Infix<InfixOps, InfixOpsOut>>
+    where
+        I: Input<'a>,
+        E: ParserExtra<'a, I>,
+        InfixOps: Parser<'a, I, InfixOpsOut, E>,
+        Self: Sized,
+    {
+        Pratt {
+            atom: self,
+            ops: pratt::Infix {
+                infix: ops,
+                _phantom: EmptyPhantom::new(),
+            },
+            _phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Parse expressions made of atoms separated by operators that are defined at runtime, using an
+    /// [`OpTable`](pratt::OpTable) held by the parser's state.
+    ///
+    /// This is like [`Parser::pratt`], except that the operators are not fixed when the parser is created. Instead,
+    /// `op` parses any operator, producing a key (such as its symbol) that is looked up in the table. The parser's
+    /// state must be an `OpTable`, or a type that implements [`Borrow<OpTable>`](core::borrow::Borrow). Since the state
+    /// can be modified during parsing, this allows languages with user-defined operators (such as Haskell, with its
+    /// fixity declarations) to extend the expression parser mid-parse.
+    ///
+    /// An operator that is not in the table, or not in the position in which it was found, is not consumed and ends
+    /// the expression.
+    ///
+    /// The output type of this parser is `O`, the same as the original parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// use chumsky::pratt::{OpTable, Operator};
+    ///
+    /// type Table<'a> = OpTable<&'a str, String>;
+    ///
+    /// let op = one_of("+-*/^").repeated().at_least(1).slice().padded();
+    /// let expr = text::int::<_, _, extra::State<Table>>(10)
+    ///     .map(ToString::to_string)
+    ///     .pratt_dynamic(op);
+    ///
+    /// // A fixity declaration, such as `infixr 8 ^;`, adds an infix operator to the table
+    /// let fixity = text::ascii::keyword("infixl")
+    ///     .to(false)
+    ///     .or(text::ascii::keyword("infixr").to(true))
+    ///     .then(text::int(10).from_str::<u16>().unwrapped().padded())
+    ///     .then(op)
+    ///     .then_ignore(just(';').padded())
+    ///     .map_with_state(|((right, strength), name): (_, &str), _, table: &mut Table| {
+    ///         let owned = name.to_string();
+    ///         let build = move |l, r| format!("({l} {owned} {r})");
+    ///         table.insert(if right {
+    ///             Operator::right_infix(name, strength, build)
+    ///         } else {
+    ///             Operator::left_infix(name, strength, build)
+    ///         });
+    ///     });
+    ///
+    /// let program = fixity
+    ///     .repeated()
+    ///     .collect::<Vec<_>>()
+    ///     .then(expr)
+    ///     .map(|(_, expr)| expr);
+    ///
+    /// let mut table = OpTable::new().with(Operator::left_infix("+", 6, |l, r| format!("({l} + {r})")));
+    /// assert_eq!(
+    ///     program.parse_with_state("infixr 8 ^; infixl 7 *; 1 + 2 * 3 ^ 4 ^ 5", &mut table).into_result(),
+    ///     Ok("(1 + (2 * (3 ^ (4 ^ 5))))".to_string()),
+    /// );
+    /// // `-` was never declared, so it ends the expression
+    /// assert!(program.parse_with_state("1 - 2", &mut table).has_errors());
+    /// ```
+    #[cfg(feature = "pratt")]
+    fn pratt_dynamic<Op, K>(self, op: Op) -> pratt::DynamicPratt<Self, Op, K>
+    where
+        Op: Parser<'a, I, K, E>,
+        K: PartialEq,
+        E::State: Borrow<pratt::OpTable<K, O>>,
+        Self: Sized,
+    {
+        pratt::DynamicPratt {
+            atom: self,
+            op,
+            _phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<'a, I, O, E> ParserSealed<'a, I, O, E> for !
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    fn go<M: Mode>(&self, _inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        *self
+    }
+
+    go_extra!(O);
+}
+
+/// A [`Parser`] that can be configured with runtime context. This allows for context-sensitive parsing
+/// of input. Note that chumsky only supports 'left'-sensitive parsing, where the context for a parser
+/// is derived from earlier in the input.
+///
+/// Chumsky distinguishes 'state' from 'context'. State is not able to change what input a parser
+/// accepts, but may be used to change the contents of the type it emits. In this way state is expected
+/// to be idempotent - combinators such as [`Parser::map_with_state`] are allowed to not call the
+/// provided closure at all if they don't emit any output. Context and configuration, on the other hand,
+/// is used to change what kind of input a parser may accept, and thus must always be evaluated. Context
+/// isn't usable in any map combinator however - while it may affect accepted input, it is not expected
+/// to change the final result outside of how it changes what the parser itself returns.
+///
+/// Not all parsers currently support configuration. If you feel like you need a parser to be configurable
+/// and it isn't currently, please open an issue on the issue tracker of the main repository.
+pub trait ConfigParser<'a, I, O, E>: ConfigParserSealed<'a, I, O, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    /// A combinator that allows configuration of the parser from the current context. Context
+    /// is most often derived from [`Parser::ignore_with_ctx`], [`Parser::then_with_ctx`] or [`map_ctx`],
+    /// and is how chumsky supports parsing things such as indentation-sensitive grammars.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    ///
+    /// let int = text::int::<_, _, extra::Err<Rich<char>>>(10)
+    ///     .from_str()
+    ///     .unwrapped();
+    ///
+    /// // By default, accepts any number of items
+    /// let item = text::ascii::ident()
+    ///     .padded()
+    ///     .repeated();
+    ///
+    /// // With configuration, we can declare an exact number of items based on a prefix length
+    /// let len_prefixed_arr = int
+    ///     .ignore_with_ctx(item.configure(|repeat, ctx| repeat.exactly(*ctx)).collect::<Vec<_>>());
+    ///
+    /// assert_eq!(
+    ///     len_prefixed_arr.parse("2 foo bar").into_result(),
+    ///     Ok(vec!["foo", "bar"]),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     len_prefixed_arr.parse("0").into_result(),
+    ///     Ok(vec![]),
+    /// );
+    ///
+    /// len_prefixed_arr.parse("3 foo bar baz bam").into_result().unwrap_err();
+    /// len_prefixed_arr.parse("3 foo bar").into_result().unwrap_err();
+    /// ```
+    fn configure<F>(self, cfg: F) -> Configure<Self, F>
+    where
+        Self: Sized,
+        F: Fn(Self::Config, &E::Context) -> Self::Config,
+    {
+        Configure { parser: self, cfg }
+    }
+}
+
+/// An iterator that wraps an iterable parser. See [`IterParser::parse_iter`].
+#[cfg(test)]
+pub struct ParserIter<'a, 'iter, P: IterParser<'a, I, O, E>, I: Input<'a>, O, E: ParserExtra<'a, I>>
+{
+    parser: P,
+    offset: I::Offset,
+    own: InputOwn<'a, 'iter, I, E>,
+    iter_state: Option<P::IterState<Emit>>,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(&'a (), O)>,
+}
+
+#[cfg(test)]
+impl<'a, 'iter, P, I: Input<'a>, O, E: ParserExtra<'a, I>> Iterator
+    for ParserIter<'a, 'iter, P, I, O, E>
+where
+    P: IterParser<'a, I, O, E>,
+{
+    type Item = O;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut inp = self.own.as_ref_at(self.offset);
+        let parser = &self.parser;
+
+        let iter_state = match &mut self.iter_state {
+            Some(state) => state,
+            None => {
+                let state = parser.make_iter::<Emit>(&mut inp).ok()?;
+                self.iter_state = Some(state);
+                self.iter_state.as_mut().unwrap()
+            }
+        };
+
+        let res = parser.next::<Emit>(&mut inp, iter_state);
+        self.offset = inp.offset;
+        res.ok().and_then(|res| res)
+    }
+}
+
+/// An iterator over a series of items, parsing each one when it is requested. See [`Parser::parse_stream`] and
+/// [`Parser::parse_items_recovering`].
+///
+/// Each item is either the output of the item parser or the error that it failed with. Without a `sync` parser, as
+/// from [`Parser::parse_stream`], iteration stops after the first item that fails to parse. With one, the failed item
+/// is skipped up to the next position at which `sync` succeeds and iteration carries on. Either way, iteration ends at
+/// the end of the input, or after an item that consumes no input.
+pub struct StreamIter<
+    'a,
+    P,
+    I: Input<'a>,
+    O,
+    E: ParserExtra<'a, I>,
+    S = primitive::Empty<I, E>,
+    OS = (),
+> {
+    item: P,
+    sync: Option<S>,
+    offset: I::Offset,
+    own: InputOwn<'a, 'a, I, E>,
+    done: bool,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(O, OS)>,
+}
+
+impl<'a, P, I: Input<'a>, O, E: ParserExtra<'a, I>, S, OS> StreamIter<'a, P, I, O, E, S, OS> {
+    /// Take the errors that the item parser has recovered from so far, sorted by the position at which they occurred.
+    ///
+    /// These are the errors emitted during [error recovery](recovery), which do not stop an item from being produced.
+    pub fn take_errors(&mut self) -> Vec<E::Error> {
+        self.own.take_errs()
+    }
+}
+
+impl<'a, P, I, O, E, S, OS> Iterator for StreamIter<'a, P, I, O, E, S, OS>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    P: Parser<'a, I, O, E>,
+    S: Parser<'a, I, OS, E>,
+{
+    type Item = Result<O, E::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if self.own.as_ref_at(self.offset).peek_maybe().is_none() {
+            self.done = true;
+            return None;
+        }
+
+        let item = &self.item;
+        let (res, offset) = match &self.sync {
+            None => self
+                .own
+                .drive::<Emit, _, _, _>(self.offset, item, |inp, res| {
+                    (res.map_err(|err| err.err), inp.offset)
+                }),
+            Some(sync) => {
+                let recovering = custom(|inp: &mut InputRef<'a, '_, I, E>| {
+                    let before = inp.save();
+                    let res = inp.parse(item);
+                    if res.is_err() {
+                        inp.rewind(before);
+                        // Always skip at least one token, so that the same item is not attempted again
+                        inp.next_maybe();
+                        while inp.peek_maybe().is_some() {
+                            let before_sync = inp.save();
+                            let synced = sync.go::<Check>(inp).is_ok();
+                            inp.errors.alt = None;
+                            inp.rewind(before_sync);
+                            if synced {
+                                break;
+                            }
+                            inp.next_maybe();
+                        }
+                    }
+                    Ok(res)
+                });
+                let (res, offset) =
+                    self.own
+                        .drive::<Emit, _, _, _>(self.offset, &recovering, |inp, res| {
+                            (res, inp.offset)
+                        });
+                (res.map_err(|err| err.err).and_then(|res| res), offset)
+            }
+        };
+        self.done = offset == self.offset || (res.is_err() && self.sync.is_none());
+        self.offset = offset;
+        Some(res)
+    }
+}
+
+/// An iterable equivalent of [`Parser`], i.e: a parser that generates a sequence of outputs.
+pub trait IterParser<'a, I, O, E = extra::Default>: IterParserSealed<'a, I, O, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    /// Collect this iterable parser into a [`Container`].
+    ///
+    /// This is commonly useful for collecting parsers that output many values into containers of various kinds:
+    /// [`Vec`]s, [`String`]s, or even [`HashMap`]s. This method is analogous to [`Iterator::collect`].
+    ///
+    /// The output type of this iterable parser is `C`, the type being collected into.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let word = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_alphabetic()) // This parser produces an output of `char`
+    ///     .repeated() // This parser is iterable (i.e: implements `IterParser`)
+    ///     .collect::<String>(); // We collect the `char`s into a `String`
+    ///
+    /// assert_eq!(word.parse("hello").into_result(), Ok("hello".to_string()));
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn collect<C: Container<O>>(self) -> Collect<Self, O, C>
+    where
+        Self: Sized,
+    {
+        Collect {
+            parser: self,
+            capacity: (),
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Collect this iterable parser into a [`Container`], creating the container with a capacity hint.
+    ///
+    /// This behaves exactly like [`collect`](Self::collect), except that the container is created with
+    /// [`Container::with_capacity`] rather than [`Default::default`]. For long sequences of items, this avoids
+    /// repeatedly reallocating the container as it grows.
+    ///
+    /// The hint may be any [`CapacityHint`]: either a constant [`usize`], or (for inputs that implement
+    /// [`SliceInput`]) a function that computes the hint from the remaining input. The hint is only a hint: it does
+    /// not limit the number of items that may be collected.
+    ///
+    /// The output type of this iterable parser is `C`, the type being collected into.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// // Each number takes up at least two bytes of the input, including the comma
+    /// let numbers = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .separated_by(just(','))
+    ///     .collect_with_capacity_hint::<Vec<_>, _>(|rest: &str| rest.len() / 2 + 1);
+    ///
+    /// let nums = numbers.parse("1,22,333,4444").into_result().unwrap();
+    /// assert_eq!(nums, ["1", "22", "333", "4444"]);
+    /// assert!(nums.capacity() >= 7);
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn collect_with_capacity_hint<C, H>(self, hint: H) -> Collect<Self, O, C, H>
+    where
+        Self: Sized,
+        C: Container<O>,
+        H: CapacityHint<'a, I>,
+    {
+        Collect {
+            parser: self,
+            capacity: hint,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Collect this iterable parser's key-value pairs into a map, deciding what to do with duplicate keys.
+    ///
+    /// [`collect`](Self::collect) can also collect into maps, but later entries silently overwrite earlier ones
+    /// with the same key. This method instead follows the given [`Duplicates`] policy, which may keep the first
+    /// value or emit an error for each duplicate entry.
+    ///
+    /// The output type of this iterable parser is `C`, the map being collected into.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, container::Duplicates};
+    /// use std::collections::BTreeMap;
+    ///
+    /// let entry = text::ascii::ident::<_, _, extra::Err<Rich<char>>>()
+    ///     .then_ignore(just('=').padded())
+    ///     .then(text::int(10))
+    ///     .padded();
+    ///
+    /// let first = entry
+    ///     .separated_by(just(','))
+    ///     .collect_map::<_, _, BTreeMap<_, _>>(Duplicates::KeepFirst);
+    /// assert_eq!(
+    ///     first.parse("a = 1, b = 2, a = 3").into_result(),
+    ///     Ok(BTreeMap::from([("a", "1"), ("b", "2")])),
+    /// );
+    ///
+    /// let strict = entry
+    ///     .separated_by(just(','))
+    ///     .collect_map::<_, _, BTreeMap<_, _>>(Duplicates::Error(|key, span| {
+    ///         Rich::custom(span, format!("duplicate key '{key}'"))
+    ///     }));
+    /// let errs = strict.parse("a = 1, b = 2, a = 3").into_errors();
+    /// assert_eq!(errs.len(), 1);
+    /// assert_eq!(errs[0].to_string(), "duplicate key 'a'");
+    /// // The span of the entry includes the separator before it
+    /// assert_eq!(errs[0].span(), &SimpleSpan::new(12, 19));
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn collect_map<K, V, C: MapContainer<K, V>>(
+        self,
+        duplicates: Duplicates<K, I::Span, E::Error>,
+    ) -> CollectMap<Self, K, V, C, I::Span, E::Error>
+    where
+        Self: Sized + IterParser<'a, I, (K, V), E>,
+    {
+        CollectMap {
+            parser: self,
+            duplicates,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Collect this iterable parser into a [`ContainerExactly`].
+    ///
+    /// This is useful for situations where the number of items to consume is statically known.
+    /// A common use-case is collecting into an array.
+    ///
+    /// The output type of this iterable parser if `C`, the type being collected into.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let three_digit = any::<_, extra::Err<Simple<char>>>().filter(|c: &char| c.is_numeric())
+    ///     .repeated()
+    ///     .collect_exactly::<[_; 3]>();
+    ///
+    /// assert_eq!(three_digit.parse("123").into_result(), Ok(['1', '2', '3']));
+    /// assert!(three_digit.parse("12").into_result().is_err());
+    /// assert!(three_digit.parse("1234").into_result().is_err());
+    /// ```
+    fn collect_exactly<C: ContainerExactly<O>>(self) -> CollectExactly<Self, O, C>
+    where
+        Self: Sized,
+    {
+        CollectExactly {
+            parser: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Collect this iterable parser into a [`usize`], outputting the number of elements that were parsed.
+    ///
+    /// This is sugar for [`.collect::<usize>()`](Self::collect).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    ///
+    /// // Counts how many chess squares are in the input.
+    /// let squares = one_of::<_, _, extra::Err<Simple<char>>>('a'..='z').then(one_of('1'..='8')).padded().repeated().count();
+    ///
+    /// assert_eq!(squares.parse("a1 b2 c3").into_result(), Ok(3));
+    /// assert_eq!(squares.parse("e5 e7 c6 c7 f6 d5 e6 d7 e4 c5 d6 c4 b6 f5").into_result(), Ok(14));
+    /// assert_eq!(squares.parse("").into_result(), Ok(0));
+    /// ```
+    fn count(self) -> Collect<Self, O, usize>
+    where
+        Self: Sized,
+    {
+        self.collect()
+    }
+
+    /// Fold the outputs of this iterable parser into a single value, without collecting them into a container first.
+    ///
+    /// This method is analogous to [`Iterator::fold`]. Unlike [`Parser::foldl`], no initial parser is required: the
+    /// accumulator starts at `init` (cloned each time the parser is invoked).
+    ///
+    /// The output type of this parser is `T`, the type of the accumulator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let digits = one_of::<_, _, extra::Err<Simple<char>>>('0'..='9')
+    ///     .repeated()
+    ///     .at_least(1)
+    ///     .fold(0u32, |acc, c: char| acc * 10 + c.to_digit(10).unwrap());
+    ///
+    /// assert_eq!(digits.parse("1234").into_result(), Ok(1234));
+    /// assert!(digits.parse("").has_errors());
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn fold<T, F>(self, init: T, f: F) -> Fold<Self, O, T, F>
+    where
+        T: Clone,
+        F: Fn(T, O) -> T,
+        Self: Sized,
+    {
+        Fold {
+            parser: self,
+            init,
+            folder: f,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Sum the outputs of this iterable parser, without collecting them into a container first.
+    ///
+    /// The sum starts at `S::default()` and each output is added to it in turn. This is sugar for
+    /// [`.fold(S::default(), |a, b| a + b)`](Self::fold).
+    ///
+    /// The output type of this parser is `S`, the type of the sum.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .from_str::<u64>()
+    ///     .unwrapped()
+    ///     .padded();
+    ///
+    /// let total = int.separated_by(just(',')).sum::<u64>();
+    ///
+    /// assert_eq!(total.parse("1, 2, 3, 4").into_result(), Ok(10));
+    /// assert_eq!(total.parse("").into_result(), Ok(0));
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn sum<S>(self) -> Fold<Self, O, S, fn(S, O) -> S>
+    where
+        S: Default + Clone + core::ops::Add<O, Output = S>,
+        Self: Sized,
+    {
+        self.fold(S::default(), |a, b| a + b)
+    }
+
+    /// Enumerate outputs of this iterable parser.
+    ///
+    /// This function behaves in a similar way to [`Iterator::enumerate`].
+    ///
+    /// The output type of this iterable parser is `(usize, O)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let word = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+    ///     .padded()
+    ///     .repeated() // This parser is iterable (i.e: implements `IterParser`)
+    ///     .enumerate()
+    ///     .collect::<Vec<(usize, &str)>>();
+    ///
+    /// assert_eq!(word.parse("hello world").into_result(), Ok(vec![(0, "hello"), (1, "world")]));
+    /// ```
+    fn enumerate(self) -> Enumerate<Self, O>
+    where
+        Self: Sized,
+    {
+        Enumerate {
+            parser: self,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Right-fold the output of the parser into a single value.
+    ///
+    /// The output of the original parser must be of type `(impl IntoIterator<Item = A>, B)`. Because right-folds work
+    /// backwards, the iterator must implement [`DoubleEndedIterator`] so that it can be reversed.
+    ///
+    /// The output type of this iterable parser is `B`, the right-hand component of the original parser's output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let int = text::int::<_, _, extra::Err<Simple<char>>>(10)
+    ///     .from_str()
+    ///     .unwrapped();
+    ///
+    /// let signed = just('+').to(1)
+    ///     .or(just('-').to(-1))
+    ///     .repeated()
+    ///     .foldr(int, |a, b| a * b);
+    ///
+    /// assert_eq!(signed.parse("3").into_result(), Ok(3));
+    /// assert_eq!(signed.parse("-17").into_result(), Ok(-17));
+    /// assert_eq!(signed.parse("--+-+-5").into_result(), Ok(5));
+    /// ```
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn foldr<B, F, OA>(self, other: B, f: F) -> Foldr<F, Self, B, O, E>
+    where
+        F: Fn(O, OA) -> OA,
+        B: Parser<'a, I, OA, E>,
+        Self: Sized,
+    {
+        Foldr {
+            parser_a: self,
+            parser_b: other,
+            folder: f,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Right-fold the output of the parser into a single value, making use of the parser's state when doing so.
+    ///
+    /// The output of the original parser must be of type `(impl IntoIterator<Item = A>, B)`. Because right-folds work
+    /// backwards, the iterator must implement [`DoubleEndedIterator`] so that it can be reversed.
+    ///
+    /// The output type of this parser is `B`, the right-hand component of the original parser's output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, error::Simple};
+    /// let int = text::int::<_, _, extra::Full<Simple<char>, i32, ()>>(10)
+    ///     .from_str()
+    ///     .unwrapped();
+    ///
+    /// let signed = just('+').to(1)
+    ///     .or(just('-').to(-1))
+    ///     .repeated()
+    ///     .foldr_with_state(int, |a, b, state| {
+    ///         (*state) += 1;
+    ///         (a * b)
+    ///     });
+    ///
+    /// // Test our parser
+    /// let mut folds = 0i32;
+    /// assert_eq!(signed.parse_with_state("3", &mut folds).into_result(), Ok(3));
+    /// assert_eq!(signed.parse_with_state("-17", &mut folds).into_result(), Ok(-17));
+    /// assert_eq!(signed.parse_with_state("--+-+-5", &mut folds).into_result(), Ok(5));
+    /// ```
+    ///
+    ///
+    #[cfg_attr(debug_assertions, track_caller)]
+    fn foldr_with_state<B, F, OA>(self, other: B, f: F) -> FoldrWithState<F, Self, B, O, E>
+    where
+        F: Fn(O, OA, &mut E::State) -> OA,
+        B: Parser<'a, I, OA, E>,
+        Self: Sized,
+    {
+        FoldrWithState {
+            parser_a: self,
+            parser_b: other,
+            folder: f,
+            #[cfg(debug_assertions)]
+            location: *Location::caller(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// Create an iterator over the outputs generated by an iterable parser.
+    ///
+    /// Warning: Trailing errors will be ignored
+    // TODO: Stabilize once error handling is properly decided on
+    #[cfg(test)]
+    fn parse_iter(self, input: I) -> ParseResult<ParserIter<'a, 'static, Self, I, O, E>, E::Error>
+    where
+        Self: IterParser<'a, I, O, E> + Sized,
+        E::State: Default,
+        E::Context: Default,
+    {
+        ParseResult::new(
+            Some(ParserIter {
+                parser: self,
+                offset: input.start(),
+                own: InputOwn::new(input),
+                iter_state: None,
+                phantom: EmptyPhantom::new(),
+            }),
+            Vec::new(),
+        )
+    }
+
+    /// Create an iterator over the outputs generated by an iterable parser with the given parser state.
+    ///
+    /// Warning: Trailing errors will be ignored
+    // TODO: Stabilize once error handling is properly decided on
+    #[cfg(test)]
+    fn parse_iter_with_state<'parse>(
+        self,
+        input: I,
+        state: &'parse mut E::State,
+    ) -> ParseResult<ParserIter<'a, 'parse, Self, I, O, E>, E::Error>
+    where
+        Self: IterParser<'a, I, O, E> + Sized,
+        E::Context: Default,
+    {
+        ParseResult::new(
+            Some(ParserIter {
+                parser: self,
+                offset: input.start(),
+                own: InputOwn::new_state(input, state),
+                iter_state: None,
+                phantom: EmptyPhantom::new(),
+            }),
+            Vec::new(),
+        )
+    }
+}
+
+/// An iterable equivalent of [`ConfigParser`], i.e: a parser that generates a sequence of outputs and
+/// can be configured at runtime.
+pub trait ConfigIterParser<'a, I, O, E = extra::Default>:
+    ConfigIterParserSealed<'a, I, O, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    /// A combinator that allows configuration of the parser from the current context
+    fn configure<F>(self, cfg: F) -> IterConfigure<Self, F, O>
+    where
+        Self: Sized,
+        F: Fn(Self::Config, &E::Context) -> Self::Config,
+    {
+        IterConfigure {
+            parser: self,
+            cfg,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+
+    /// A combinator that allows fallible configuration of the parser from the current context -
+    /// if an error is returned, parsing fails.
+    fn try_configure<F>(self, cfg: F) -> TryIterConfigure<Self, F, O>
+    where
+        Self: Sized,
+        F: Fn(Self::Config, &E::Context, I::Span) -> Result<Self::Config, E::Error>,
+    {
+        TryIterConfigure {
+            parser: self,
+            cfg,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+/// See [`Parser::boxed`].
+///
+/// Due to current implementation details, the inner value is not, in fact, a [`Box`], but is an [`Rc`] to facilitate
+/// efficient cloning. This is likely to change in the future. Unlike [`Box`], [`Rc`] has no size guarantees: although
+/// it is *currently* the same size as a raw pointer.
+// TODO: Don't use an Rc
+pub struct Boxed<'a, 'b, I: Input<'a>, O, E: ParserExtra<'a, I>> {
+    inner: RefC<ParserObj<'a, 'b, I, O, E>>,
+}
+
+impl<'a, 'b, I: Input<'a>, O, E: ParserExtra<'a, I>> Clone for Boxed<'a, 'b, I, O, E> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, 'b, I, O, E> ParserSealed<'a, I, O, E> for Boxed<'a, 'b, I, O, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        M::invoke(&*self.inner, inp)
+    }
+
+    fn boxed<'c>(self) -> Boxed<'a, 'c, I, O, E>
+    where
+        Self: MaybeSync + Sized + 'a + 'c,
+    {
+        // Never double-box parsers
+        self
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.inner.walk_node(walker));
+    }
+
+    go_extra!(O);
+}
+
+/// A type-erased parser, suitable for exposing parsers from one crate to another.
+///
+/// This is a [`Boxed`] parser that lives for as long as its input, which is what almost every exported parser needs.
+/// Parsers can be turned into one with [`Parser::boxed`]. See [`SubGrammar`] for a convenient way to expose
+/// sub-grammars from a library.
+pub type DynParser<'a, I, O, E = extra::Default> = Boxed<'a, 'a, I, O, E>;
+
+/// A grammar (or part of one) that a library exposes for reuse by other crates.
+///
+/// Parsers built from combinators have large concrete types that change whenever the grammar does, so naming them in
+/// a public API is impractical. Implementing this trait instead exposes the grammar through a type-erased
+/// [`DynParser`], keeping the details of how it is written private.
+///
+/// Users of the grammar are free to use a different error type to the one the grammar was written with, via
+/// [`SubGrammar::parser_with`], so the library does not need to thread generic error types through every parser.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, SubGrammar};
+/// mod arith {
+///     use chumsky::{prelude::*, SubGrammar};
+///
+///     /// Sums of integers, such as `1 + 2 + 3`.
+///     pub struct Sum;
+///
+///     impl<'a> SubGrammar<'a, &'a str, extra::Err<Cheap>> for Sum {
+///         type Output = u64;
+///
+///         fn parser() -> DynParser<'a, &'a str, u64, extra::Err<Cheap>> {
+///             let int = text::int(10).from_str::<u64>().unwrapped().padded();
+///             int.foldl(just('+').ignore_then(int).repeated(), |a, b| a + b)
+///                 .boxed()
+///         }
+///     }
+/// }
+///
+/// // In another crate, which uses richer errors
+/// let assignment = text::ascii::ident::<_, _, extra::Err<Rich<char>>>()
+///     .then_ignore(just('=').padded())
+///     .then(arith::Sum::parser_with());
+///
+/// assert_eq!(assignment.parse("x = 1 + 2 + 3").into_result(), Ok(("x", 6)));
+/// assert!(assignment.parse("x = 1 + y").has_errors());
+/// ```
+pub trait SubGrammar<'a, I: Input<'a>, E: ParserExtra<'a, I> = extra::Default> {
+    /// The output of the grammar's parser.
+    type Output;
+
+    /// Create a parser for this grammar.
+    fn parser() -> DynParser<'a, I, Self::Output, E>;
+
+    /// Create a parser for this grammar that produces a different error type, as with [`Parser::map_err_to`].
+    fn parser_with<E2>() -> DynParser<'a, I, Self::Output, E2>
+    where
+        E: MaybeSync + 'a,
+        E2: ParserExtra<'a, I, State = E::State, Context = E::Context> + MaybeSync + 'a,
+        E2::Error: From<E::Error>,
+        Self::Output: 'a,
+    {
+        Parser::boxed(Self::parser().map_err_to::<E2>())
+    }
+}
+
+/// See [`Parser::shared`].
+pub struct Shared<P: ?Sized> {
+    inner: RefC<P>,
+}
+
+impl<P: ?Sized> Clone for Shared<P> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, I, O, E, P> ParserSealed<'a, I, O, E> for Shared<P>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    P: Parser<'a, I, O, E> + ?Sized,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        M::invoke(&*self.inner, inp)
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.inner.walk_node(walker));
+    }
+
+    go_extra!(O);
+}
+
+impl<'a, I, O, E, T> ParserSealed<'a, I, O, E> for ::alloc::boxed::Box<T>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    T: Parser<'a, I, O, E>,
+{
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        T::go::<M>(self, inp)
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        T::walk_node(self, walker);
+    }
+
+    go_extra!(O);
+}
+
+impl<'a, I, O, E, T> ParserSealed<'a, I, O, E> for ::alloc::rc::Rc<T>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    T: Parser<'a, I, O, E>,
+{
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        T::go::<M>(self, inp)
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        T::walk_node(self, walker);
+    }
+
+    go_extra!(O);
+}
+
+impl<'a, I, O, E, T> ParserSealed<'a, I, O, E> for ::alloc::sync::Arc<T>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    T: Parser<'a, I, O, E>,
+{
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        T::go::<M>(self, inp)
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        T::walk_node(self, walker);
+    }
+
+    go_extra!(O);
+}
+
+/// Create a parser that selects one or more input patterns and map them to an output value.
+///
+/// This is most useful when turning the tokens of a previous compilation pass (such as lexing) into data that can be
+/// used for parsing, although it can also generally be used to select inputs and map them to outputs. Any unmapped
+/// input patterns will become syntax errors, just as with [`Parser::filter`].
+///
+/// Internally, [`select!`] is very similar to [`Parser::try_map`] and thinking of it as such might make it less
 /// confusing.
 ///
 /// `select!` requires that tokens implement [`Clone`].
@@ -2837,613 +5297,2475 @@ macro_rules! select {
     });
 }
 
-/// A version of [`select!`] that selects on token by reference instead of by value.
-///
-/// Useful if you want to extract elements from a token in a zero-copy manner.
-///
-/// `select_ref` requires that the parser input implements [`BorrowInput`].
-#[macro_export]
-macro_rules! select_ref {
-    ($($p:pat $(= $span:ident)? $(if $guard:expr)? $(=> $out:expr)?),+ $(,)?) => ({
-        $crate::primitive::select_ref(
-            move |x, span| match x {
-                $($p $(if $guard)? => ::core::option::Option::Some({ $(let $span = span;)? () $(;$out)? })),+,
-                _ => ::core::option::Option::None,
+/// A version of [`select!`] that selects on token by reference instead of by value.
+///
+/// Useful if you want to extract elements from a token in a zero-copy manner.
+///
+/// `select_ref` requires that the parser input implements [`BorrowInput`].
+#[macro_export]
+macro_rules! select_ref {
+    ($($p:pat $(= $span:ident)? $(if $guard:expr)? $(=> $out:expr)?),+ $(,)?) => ({
+        $crate::primitive::select_ref(
+            move |x, span| match x {
+                $($p $(if $guard)? => ::core::option::Option::Some({ $(let $span = span;)? () $(;$out)? })),+,
+                _ => ::core::option::Option::None,
+            }
+        )
+    });
+}
+
+/// Parse using one of many parsers with different output types, converting the output of the first to successfully
+/// parse into a common type.
+///
+/// Each branch is written as `parser => f`, where `f` is a function or closure that converts the output of `parser`
+/// into the output type of the whole parser. If `=> f` is omitted, the output is converted with [`Into::into`].
+///
+/// This is equivalent to calling [`choice`](primitive::choice) with a tuple of parsers that have each had
+/// [`Parser::map`] applied to them, and so behaves in the same way.
+///
+/// The output type of this parser is the common output type of the branches.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// #[derive(Clone, Debug, PartialEq)]
+/// enum Token<'a> {
+///     Int(u64),
+///     Ident(&'a str),
+///     Bool(bool),
+///     Comma,
+/// }
+///
+/// impl<'a> From<bool> for Token<'a> {
+///     fn from(b: bool) -> Self {
+///         Token::Bool(b)
+///     }
+/// }
+///
+/// let bool = text::ascii::keyword("true").to(true).or(text::ascii::keyword("false").to(false));
+///
+/// let token = choice_map! {
+///     text::int::<_, _, extra::Err<Simple<char>>>(10).from_str().unwrapped() => Token::Int,
+///     // Converted with `Into::into`
+///     bool,
+///     text::ascii::ident() => Token::Ident,
+///     just(',') => |_| Token::Comma,
+/// };
+///
+/// assert_eq!(
+///     token.padded().repeated().collect::<Vec<_>>().parse("42, foo, true").into_result(),
+///     Ok(vec![Token::Int(42), Token::Comma, Token::Ident("foo"), Token::Comma, Token::Bool(true)]),
+/// );
+/// ```
+#[macro_export]
+macro_rules! choice_map {
+    (@mapper) => (::core::convert::Into::into);
+    (@mapper $f:expr) => ($f);
+    ($($p:expr $(=> $f:expr)?),+ $(,)?) => ({
+        $crate::primitive::choice((
+            $($crate::Parser::map($p, $crate::choice_map!(@mapper $($f)?)),)+
+        ))
+    });
+}
+
+/// Parse a sequence of parsers, like [`group`](primitive::group), but drop the outputs of elements that only exist
+/// for their syntax (such as punctuation and keywords) from the output tuple.
+///
+/// Rust cannot tell a parser that produces `()` apart from any other parser, so ignored elements are marked by
+/// writing them as `_ = parser`, mirroring `let _ = ...`. Ignored elements are run with [`Parser::ignored`], so their
+/// outputs are never generated.
+///
+/// The output of this parser is a tuple of the outputs of the remaining elements, in order.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>().padded();
+///
+/// let binding = group_ignoring!(
+///     _ = text::ascii::keyword("let").padded(),
+///     ident,
+///     _ = just('='),
+///     text::int(10).padded(),
+///     _ = just(';'),
+/// );
+///
+/// assert_eq!(binding.parse("let x = 42;").into_result(), Ok(("x", "42")));
+/// ```
+#[macro_export]
+macro_rules! group_ignoring {
+    (@acc [$($p:expr,)*] [$($pat:tt)*] [$($out:tt)*] $(,)?) => ({
+        $crate::Parser::map(
+            $crate::primitive::group(($($p,)*)),
+            |($($pat)*)| ($($out)*),
+        )
+    });
+    (@acc [$($p:expr,)*] [$($pat:tt)*] [$($out:tt)*] _ = $q:expr $(, $($rest:tt)*)?) => (
+        $crate::group_ignoring!(
+            @acc [$($p,)* $crate::Parser::ignored($q),] [$($pat)* _,] [$($out)*] $($($rest)*)?
+        )
+    );
+    (@acc [$($p:expr,)*] [$($pat:tt)*] [$($out:tt)*] $q:expr $(, $($rest:tt)*)?) => (
+        $crate::group_ignoring!(@acc [$($p,)* $q,] [$($pat)* x,] [$($out)* x,] $($($rest)*)?)
+    );
+    ($($rest:tt)+) => ($crate::group_ignoring!(@acc [] [] [] $($rest)+));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_copy() {
+        use self::input::WithContext;
+        use self::prelude::*;
+
+        #[derive(PartialEq, Debug)]
+        enum Token<'a> {
+            Ident(&'a str),
+            String(&'a str),
+        }
+
+        type FileId = u32;
+        type Span = SimpleSpan<usize, FileId>;
+
+        fn parser<'a>() -> impl Parser<'a, WithContext<Span, &'a str>, [(Span, Token<'a>); 6]> {
+            let ident = any()
+                .filter(|c: &char| c.is_alphanumeric())
+                .repeated()
+                .at_least(1)
+                .map_slice(Token::Ident);
+
+            let string = just('"')
+                .then(any().filter(|c: &char| *c != '"').repeated())
+                .then(just('"'))
+                .map_slice(Token::String);
+
+            ident
+                .or(string)
+                .map_with_span(|token, span| (span, token))
+                .padded()
+                .repeated()
+                .collect_exactly()
+        }
+
+        assert_eq!(
+            parser()
+                .parse(r#"hello "world" these are "test" tokens"#.with_context(42))
+                .into_result(),
+            Ok([
+                (Span::new(42, 0..5), Token::Ident("hello")),
+                (Span::new(42, 6..13), Token::String("\"world\"")),
+                (Span::new(42, 14..19), Token::Ident("these")),
+                (Span::new(42, 20..23), Token::Ident("are")),
+                (Span::new(42, 24..30), Token::String("\"test\"")),
+                (Span::new(42, 31..37), Token::Ident("tokens")),
+            ]),
+        );
+    }
+
+    #[test]
+    fn zero_copy_map_span() {
+        use self::input::MappedSpan;
+        use self::prelude::*;
+
+        #[derive(PartialEq, Debug)]
+        enum Token<'a> {
+            Ident(&'a str),
+            String(&'a str),
+        }
+
+        type FileId<'a> = &'a str;
+        type Span<'a> = SimpleSpan<usize, FileId<'a>>;
+
+        fn parser<'a, F: Fn(SimpleSpan) -> Span<'a> + 'a>(
+        ) -> impl Parser<'a, MappedSpan<Span<'a>, &'a str, F>, [(Span<'a>, Token<'a>); 6]> {
+            let ident = any()
+                .filter(|c: &char| c.is_alphanumeric())
+                .repeated()
+                .at_least(1)
+                .map_slice(Token::Ident);
+
+            let string = just('"')
+                .then(any().filter(|c: &char| *c != '"').repeated())
+                .then(just('"'))
+                .map_slice(Token::String);
+
+            ident
+                .or(string)
+                .map_with_span(|token, span| (span, token))
+                .padded()
+                .repeated()
+                .collect_exactly()
+        }
+
+        let filename = "file.txt".to_string();
+        let fstr = filename.as_str();
+
+        assert_eq!(
+            parser()
+                .parse(
+                    r#"hello "world" these are "test" tokens"#
+                        .map_span(|span| Span::new(fstr, span.start()..span.end()))
+                )
+                .into_result(),
+            Ok([
+                (Span::new("file.txt", 0..5), Token::Ident("hello")),
+                (Span::new("file.txt", 6..13), Token::String("\"world\"")),
+                (Span::new("file.txt", 14..19), Token::Ident("these")),
+                (Span::new("file.txt", 20..23), Token::Ident("are")),
+                (Span::new("file.txt", 24..30), Token::String("\"test\"")),
+                (Span::new("file.txt", 31..37), Token::Ident("tokens")),
+            ]),
+        );
+    }
+
+    #[test]
+    fn zero_copy_offset_spans() {
+        use self::prelude::*;
+
+        let src = "fn main() { let x = 1 + ; }";
+        let (start, end) = (src.find('{').unwrap() + 1, src.rfind('}').unwrap());
+
+        let expr = text::int::<_, _, extra::Err<Simple<char>>>(10)
+            .padded()
+            .separated_by(just('+'))
+            .collect::<Vec<_>>();
+        let stmt = text::ascii::keyword("let")
+            .padded()
+            .ignore_then(text::ascii::ident())
+            .then_ignore(just('=').padded())
+            .then(expr.map_with_span(|_, span| span));
+
+        let errs = stmt
+            .parse(src[start..end].offset_spans(start))
+            .into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(&src[errs[0].span().into_range()], ";");
+
+        let (name, span) = stmt
+            .parse("let y = 2 + 3".offset_spans(100))
+            .into_result()
+            .unwrap();
+        assert_eq!(name, "y");
+        assert_eq!(span, SimpleSpan::from(108..113));
+    }
+
+    #[test]
+    fn zero_copy_repetition() {
+        use self::prelude::*;
+
+        fn parser<'a>() -> impl Parser<'a, &'a str, Vec<u64>> {
+            any()
+                .filter(|c: &char| c.is_ascii_digit())
+                .repeated()
+                .at_least(1)
+                .at_most(3)
+                .map_slice(|b: &str| b.parse::<u64>().unwrap())
+                .padded()
+                .separated_by(just(',').padded())
+                .allow_trailing()
+                .collect()
+                .delimited_by(just('['), just(']'))
+        }
+
+        assert_eq!(
+            parser().parse("[122 , 23,43,    4, ]").into_result(),
+            Ok(vec![122, 23, 43, 4]),
+        );
+        assert_eq!(
+            parser().parse("[0, 3, 6, 900,120]").into_result(),
+            Ok(vec![0, 3, 6, 900, 120]),
+        );
+        assert_eq!(
+            parser().parse("[200,400,50  ,0,0, ]").into_result(),
+            Ok(vec![200, 400, 50, 0, 0]),
+        );
+
+        assert!(parser().parse("[1234,123,12,1]").has_errors());
+        assert!(parser().parse("[,0, 1, 456]").has_errors());
+        assert!(parser().parse("[3, 4, 5, 67 89,]").has_errors());
+    }
+
+    #[test]
+    fn zero_copy_group() {
+        use self::prelude::*;
+
+        fn parser<'a>() -> impl Parser<'a, &'a str, (&'a str, u64, char)> {
+            group((
+                any()
+                    .filter(|c: &char| c.is_ascii_alphabetic())
+                    .repeated()
+                    .at_least(1)
+                    .slice()
+                    .padded(),
+                any()
+                    .filter(|c: &char| c.is_ascii_digit())
+                    .repeated()
+                    .at_least(1)
+                    .map_slice(|s: &str| s.parse::<u64>().unwrap())
+                    .padded(),
+                any().filter(|c: &char| !c.is_whitespace()).padded(),
+            ))
+        }
+
+        assert_eq!(
+            parser().parse("abc 123 [").into_result(),
+            Ok(("abc", 123, '[')),
+        );
+        assert_eq!(
+            parser().parse("among3d").into_result(),
+            Ok(("among", 3, 'd')),
+        );
+        assert_eq!(
+            parser().parse("cba321,").into_result(),
+            Ok(("cba", 321, ',')),
+        );
+
+        assert!(parser().parse("abc 123  ").has_errors());
+        assert!(parser().parse("123abc ]").has_errors());
+        assert!(parser().parse("and one &").has_errors());
+    }
+
+    #[test]
+    fn zero_copy_group_array() {
+        use self::prelude::*;
+
+        fn parser<'a>() -> impl Parser<'a, &'a str, [char; 3]> {
+            group([just('a'), just('b'), just('c')])
+        }
+
+        assert_eq!(parser().parse("abc").into_result(), Ok(['a', 'b', 'c']));
+        assert!(parser().parse("abd").has_errors());
+    }
+
+    #[test]
+    fn unicode_str() {
+        let input = "🄯🄚🹠🴎🄐🝋🰏🄂🬯🈦g🸵🍩🕔🈳2🬙🨞🅢🭳🎅h🵚🧿🏩🰬k🠡🀔🈆🝹🤟🉗🴟📵🰄🤿🝜🙘🹄5🠻🡉🱖🠓";
+        let mut own = InputOwn::<_, extra::Default>::new(input);
+        let mut inp = own.as_ref_start();
+
+        while let Some(_c) = inp.next() {}
+    }
+
+    #[test]
+    fn iter() {
+        use self::prelude::*;
+
+        fn parser<'a>() -> impl IterParser<'a, &'a str, char> {
+            any().repeated()
+        }
+
+        let mut chars = String::new();
+        for c in parser().parse_iter(&"abcdefg").into_result().unwrap() {
+            chars.push(c);
+        }
+
+        assert_eq!(&chars, "abcdefg");
+    }
+
+    #[test]
+    #[cfg(feature = "memoization")]
+    fn exponential() {
+        use self::prelude::*;
+
+        fn parser<'a>() -> impl Parser<'a, &'a str, String> {
+            recursive(|expr| {
+                let atom = any()
+                    .filter(|c: &char| c.is_alphabetic())
+                    .repeated()
+                    .at_least(1)
+                    .collect()
+                    .or(expr.delimited_by(just('('), just(')')));
+
+                atom.clone()
+                    .then_ignore(just('+'))
+                    .then(atom.clone())
+                    .map(|(a, b)| format!("{}{}", a, b))
+                    .memoized()
+                    .or(atom)
+            })
+            .then_ignore(end())
+        }
+
+        parser()
+            .parse("((((((((((((((((((((((((((((((a+b))))))))))))))))))))))))))))))")
+            .into_result()
+            .unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "memoization")]
+    fn left_recursive() {
+        use self::prelude::*;
+
+        fn parser<'a>() -> impl Parser<'a, &'a str, String> {
+            recursive(|expr| {
+                let atom = any()
+                    .filter(|c: &char| c.is_alphabetic())
+                    .repeated()
+                    .at_least(1)
+                    .collect();
+
+                let sum = expr
+                    .clone()
+                    .then_ignore(just('+'))
+                    .then(expr)
+                    .map(|(a, b)| format!("{}{}", a, b))
+                    .memoized();
+
+                sum.or(atom)
+            })
+            .then_ignore(end())
+        }
+
+        assert_eq!(parser().parse("a+b+c").into_result().unwrap(), "abc");
+    }
+
+    #[cfg(debug_assertions)]
+    mod debug_asserts {
+        use super::prelude::*;
+
+        // TODO panic when left recursive parser is detected
+        // #[test]
+        // #[should_panic]
+        // fn debug_assert_left_recursive() {
+        //     recursive(|expr| {
+        //         let atom = any::<&str, extra::Default>()
+        //             .filter(|c: &char| c.is_alphabetic())
+        //             .repeated()
+        //             .at_least(1)
+        //             .collect();
+
+        //         let sum = expr
+        //             .clone()
+        //             .then_ignore(just('+'))
+        //             .then(expr)
+        //             .map(|(a, b)| format!("{}{}", a, b));
+
+        //         sum.or(atom)
+        //     })
+        //     .then_ignore(end())
+        //     .parse("a+b+c");
+        // }
+
+        #[test]
+        #[should_panic]
+        #[cfg(debug_assertions)]
+        fn debug_assert_collect() {
+            empty::<&str, extra::Default>()
+                .to(())
+                .repeated()
+                .collect::<()>()
+                .parse("a+b+c")
+                .unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        #[cfg(debug_assertions)]
+        fn debug_assert_separated_by() {
+            empty::<&str, extra::Default>()
+                .to(())
+                .separated_by(empty())
+                .collect::<()>()
+                .parse("a+b+c");
+        }
+
+        #[test]
+        fn debug_assert_separated_by2() {
+            assert_eq!(
+                empty::<&str, extra::Default>()
+                    .to(())
+                    .separated_by(just(','))
+                    .count()
+                    .parse(",")
+                    .unwrap(),
+                2
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        #[cfg(debug_assertions)]
+        fn debug_assert_foldl() {
+            assert_eq!(
+                empty::<&str, extra::Default>()
+                    .to(1)
+                    .foldl(empty().repeated(), |n, ()| n + 1)
+                    .parse("a+b+c")
+                    .unwrap(),
+                3
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        #[cfg(debug_assertions)]
+        fn debug_assert_foldl_with_state() {
+            let mut state = 100;
+            empty::<&str, extra::Full<EmptyErr, i32, ()>>()
+                .foldl_with_state(empty().to(()).repeated(), |_, _, _| ())
+                .parse_with_state("a+b+c", &mut state);
+        }
+
+        #[test]
+        #[should_panic]
+        #[cfg(debug_assertions)]
+        fn debug_assert_foldr() {
+            empty::<&str, extra::Default>()
+                .to(())
+                .repeated()
+                .foldr(empty(), |_, _| ())
+                .parse("a+b+c");
+        }
+
+        #[test]
+        #[should_panic]
+        #[cfg(debug_assertions)]
+        fn debug_assert_foldr_with_state() {
+            empty::<&str, extra::Default>()
+                .to(())
+                .repeated()
+                .foldr_with_state(empty(), |_, _, _| ())
+                .parse_with_state("a+b+c", &mut ());
+        }
+
+        #[test]
+        #[should_panic]
+        #[cfg(debug_assertions)]
+        fn debug_assert_repeated() {
+            empty::<&str, extra::Default>()
+                .to(())
+                .repeated()
+                .parse("a+b+c");
+        }
+
+        // TODO what about IterConfigure and TryIterConfigure?
+    }
+
+    #[test]
+    #[should_panic]
+    fn recursive_define_twice() {
+        let mut expr = Recursive::declare();
+        expr.define({
+            let atom = any::<&str, extra::Default>()
+                .filter(|c: &char| c.is_alphabetic())
+                .repeated()
+                .at_least(1)
+                .collect();
+            let sum = expr
+                .clone()
+                .then_ignore(just('+'))
+                .then(expr.clone())
+                .map(|(a, b)| format!("{}{}", a, b));
+
+            sum.or(atom)
+        });
+        expr.define(expr.clone());
+
+        expr.then_ignore(end()).parse("a+b+c");
+    }
+
+    #[test]
+    #[should_panic]
+    fn todo_err() {
+        let expr = todo::<&str, String, extra::Default>();
+        expr.then_ignore(end()).parse("a+b+c");
+    }
+
+    #[test]
+    fn arc_impl() {
+        fn parser<'a>() -> impl Parser<'a, &'a str, Vec<u64>> {
+            Arc::new(
+                any()
+                    .filter(|c: &char| c.is_ascii_digit())
+                    .repeated()
+                    .at_least(1)
+                    .at_most(3)
+                    .map_slice(|b: &str| b.parse::<u64>().unwrap())
+                    .padded()
+                    .separated_by(just(',').padded())
+                    .allow_trailing()
+                    .collect()
+                    .delimited_by(just('['), just(']')),
+            )
+        }
+
+        assert_eq!(
+            parser().parse("[122 , 23,43,    4, ]").into_result(),
+            Ok(vec![122, 23, 43, 4]),
+        );
+        assert_eq!(
+            parser().parse("[0, 3, 6, 900,120]").into_result(),
+            Ok(vec![0, 3, 6, 900, 120]),
+        );
+        assert_eq!(
+            parser().parse("[200,400,50  ,0,0, ]").into_result(),
+            Ok(vec![200, 400, 50, 0, 0]),
+        );
+    }
+
+    #[test]
+    fn box_impl() {
+        fn parser<'a>() -> impl Parser<'a, &'a str, Vec<u64>> {
+            Box::new(
+                any()
+                    .filter(|c: &char| c.is_ascii_digit())
+                    .repeated()
+                    .at_least(1)
+                    .at_most(3)
+                    .map_slice(|b: &str| b.parse::<u64>().unwrap())
+                    .padded()
+                    .separated_by(just(',').padded())
+                    .allow_trailing()
+                    .collect()
+                    .delimited_by(just('['), just(']')),
+            )
+        }
+
+        assert_eq!(
+            parser().parse("[122 , 23,43,    4, ]").into_result(),
+            Ok(vec![122, 23, 43, 4]),
+        );
+        assert_eq!(
+            parser().parse("[0, 3, 6, 900,120]").into_result(),
+            Ok(vec![0, 3, 6, 900, 120]),
+        );
+        assert_eq!(
+            parser().parse("[200,400,50  ,0,0, ]").into_result(),
+            Ok(vec![200, 400, 50, 0, 0]),
+        );
+    }
+
+    #[test]
+    fn rc_impl() {
+        fn parser<'a>() -> impl Parser<'a, &'a str, Vec<u64>> {
+            Rc::new(
+                any()
+                    .filter(|c: &char| c.is_ascii_digit())
+                    .repeated()
+                    .at_least(1)
+                    .at_most(3)
+                    .map_slice(|b: &str| b.parse::<u64>().unwrap())
+                    .padded()
+                    .separated_by(just(',').padded())
+                    .allow_trailing()
+                    .collect()
+                    .delimited_by(just('['), just(']')),
+            )
+        }
+
+        assert_eq!(
+            parser().parse("[122 , 23,43,    4, ]").into_result(),
+            Ok(vec![122, 23, 43, 4]),
+        );
+        assert_eq!(
+            parser().parse("[0, 3, 6, 900,120]").into_result(),
+            Ok(vec![0, 3, 6, 900, 120]),
+        );
+        assert_eq!(
+            parser().parse("[200,400,50  ,0,0, ]").into_result(),
+            Ok(vec![200, 400, 50, 0, 0]),
+        );
+    }
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    struct MyErr(&'static str);
+
+    impl<'a, I> Error<'a, I> for MyErr
+    where
+        I: Input<'a>,
+    {
+        fn expected_found<E: IntoIterator<Item = Option<MaybeRef<'a, I::Token>>>>(
+            _expected: E,
+            _found: Option<MaybeRef<'a, I::Token>>,
+            _span: I::Span,
+        ) -> Self {
+            MyErr("expected found")
+        }
+
+        fn merge(self, other: Self) -> Self {
+            if self == MyErr("special") || other == MyErr("special") {
+                MyErr("special")
+            } else {
+                self
             }
+        }
+    }
+
+    #[test]
+    fn err_prio_0() {
+        #[allow(dead_code)]
+        fn always_err<'a>() -> impl Parser<'a, &'a str, (), extra::Err<MyErr>> {
+            empty().try_map(|_, _| Err(MyErr("special")))
+        }
+
+        assert_eq!(
+            always_err().parse("test").into_result().unwrap_err(),
+            vec![MyErr("special")]
         )
-    });
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+    }
 
     #[test]
-    fn zero_copy() {
-        use self::input::WithContext;
-        use self::prelude::*;
-
-        #[derive(PartialEq, Debug)]
-        enum Token<'a> {
-            Ident(&'a str),
-            String(&'a str),
+    fn err_prio_1() {
+        #[allow(dead_code)]
+        fn always_err_choice<'a>() -> impl Parser<'a, &'a str, (), extra::Err<MyErr>> {
+            choice((just("something").ignored(), empty())).try_map(|_, _| Err(MyErr("special")))
         }
 
-        type FileId = u32;
-        type Span = SimpleSpan<usize, FileId>;
+        assert_eq!(
+            always_err_choice().parse("test").into_result().unwrap_err(),
+            vec![MyErr("special")]
+        )
+    }
 
-        fn parser<'a>() -> impl Parser<'a, WithContext<Span, &'a str>, [(Span, Token<'a>); 6]> {
-            let ident = any()
-                .filter(|c: &char| c.is_alphanumeric())
+    #[test]
+    fn into_iter_no_error() {
+        fn parser<'a>() -> impl Parser<'a, &'a str, (), extra::Err<MyErr>> {
+            let many_as = just('a')
+                .ignored()
                 .repeated()
                 .at_least(1)
-                .map_slice(Token::Ident);
-
-            let string = just('"')
-                .then(any().filter(|c: &char| *c != '"').repeated())
-                .then(just('"'))
-                .map_slice(Token::String);
+                .collect::<Vec<_>>();
 
-            ident
-                .or(string)
-                .map_with_span(|token, span| (span, token))
-                .padded()
-                .repeated()
-                .collect_exactly()
+            many_as.into_iter().collect()
         }
 
+        assert_eq!(parser().parse("aaa").into_result().unwrap(), ());
+    }
+
+    #[test]
+    fn map_err_to() {
+        // Secondary errors emitted by the inner parser are converted too
+        let digit = any::<_, extra::Err<Rich<char>>>()
+            .validate(|c: char, span, emitter| {
+                if !c.is_ascii_digit() {
+                    emitter.emit(Rich::custom(span, "not a digit"));
+                }
+                c
+            })
+            .map_err_to::<extra::Err<Simple<char>>>();
+        let (out, errs) = digit
+            .repeated()
+            .collect::<String>()
+            .parse("1a2")
+            .into_output_errors();
+        assert_eq!(out.as_deref(), Some("1a2"));
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::from(1..2));
+
+        // As are errors produced by the inner parser failing
+        let simple =
+            just::<_, _, extra::Err<Simple<char>>>('a').map_err_to::<extra::Err<Rich<char>>>();
+        let errs = simple.or(just('b')).parse("c").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].found(), Some(&'c'));
         assert_eq!(
-            parser()
-                .parse(r#"hello "world" these are "test" tokens"#.with_context(42))
-                .into_result(),
-            Ok([
-                (Span::new(42, 0..5), Token::Ident("hello")),
-                (Span::new(42, 6..13), Token::String("\"world\"")),
-                (Span::new(42, 14..19), Token::Ident("these")),
-                (Span::new(42, 20..23), Token::Ident("are")),
-                (Span::new(42, 24..30), Token::String("\"test\"")),
-                (Span::new(42, 31..37), Token::Ident("tokens")),
-            ]),
+            errs[0].expected().collect::<Vec<_>>(),
+            vec![&error::RichPattern::Token('b'.into())],
         );
     }
 
     #[test]
-    fn zero_copy_map_span() {
-        use self::input::MappedSpan;
-        use self::prelude::*;
+    fn errors_sorted_by_position() {
+        let parser = custom::<_, &str, _, extra::Err<Rich<char>>>(|inp| {
+            inp.emit(2, Rich::custom((2..3).into(), "c"));
+            inp.emit(0, Rich::custom((0..1).into(), "a"));
+            inp.emit(2, Rich::custom((2..3).into(), "c again"));
+            Ok(())
+        })
+        .then(any().repeated())
+        .then(just('!'));
+
+        let errs = parser.parse("abc").into_errors();
+        assert_eq!(
+            errs.iter().map(|e| e.to_string()).collect::<Vec<_>>(),
+            vec!["a", "c", "c again", "found end of input expected '!'"],
+        );
+    }
 
-        #[derive(PartialEq, Debug)]
-        enum Token<'a> {
-            Ident(&'a str),
-            String(&'a str),
+    #[test]
+    fn one_of_range_errors() {
+        fn expected<'a, P: Parser<'a, &'a str, char, extra::Err<Rich<'a, char>>>>(
+            parser: P,
+            input: &'a str,
+        ) -> String {
+            parser.parse(input).into_errors()[0].to_string()
         }
 
-        type FileId<'a> = &'a str;
-        type Span<'a> = SimpleSpan<usize, FileId<'a>>;
+        assert_eq!(
+            expected(one_of('0'..='9'), "x"),
+            "found 'x' expected '0'..='9'",
+        );
+        assert_eq!(
+            expected(one_of('a'..'m'), "!"),
+            "found '!' expected 'a'..'m'"
+        );
+        assert_eq!(expected(one_of('a'..), "!"), "found '!' expected 'a'..");
+        assert_eq!(
+            expected(one_of("abcdef"), "!"),
+            "found '!' expected 'a'..='f'",
+        );
+        // Non-contiguous sequences are still listed in full
+        assert_eq!(
+            expected(one_of("ab_"), "!"),
+            "found '!' expected 'a', 'b', or '_'",
+        );
+        // Ranges merge with other expected patterns
+        assert_eq!(
+            expected(one_of('0'..='9').or(one_of("_-")), "!"),
+            "found '!' expected '0'..='9', '_', or '-'",
+        );
+    }
 
-        fn parser<'a, F: Fn(SimpleSpan) -> Span<'a> + 'a>(
-        ) -> impl Parser<'a, MappedSpan<Span<'a>, &'a str, F>, [(Span<'a>, Token<'a>); 6]> {
-            let ident = any()
-                .filter(|c: &char| c.is_alphanumeric())
-                .repeated()
-                .at_least(1)
-                .map_slice(Token::Ident);
+    #[test]
+    fn inspect_skipped_when_checking() {
+        use core::cell::Cell;
+
+        let outputs = Cell::new(0);
+        let errors = Cell::new(0);
+        let parser = just::<_, &str, extra::Default>('a')
+            .inspect(|_| outputs.set(outputs.get() + 1))
+            .inspect_err(|_| errors.set(errors.get() + 1));
+
+        assert!(parser.check("b").has_errors());
+        assert!(!parser.ignored().parse("a").has_errors());
+        assert_eq!((outputs.get(), errors.get()), (0, 0));
+
+        assert!(parser.parse("b").has_errors());
+        assert!(!parser.parse("a").has_errors());
+        assert_eq!((outputs.get(), errors.get()), (1, 1));
+    }
 
-            let string = just('"')
-                .then(any().filter(|c: &char| *c != '"').repeated())
-                .then(just('"'))
-                .map_slice(Token::String);
+    #[test]
+    fn map_with_check_skips_outputs() {
+        use core::cell::Cell;
 
-            ident
-                .or(string)
-                .map_with_span(|token, span| (span, token))
-                .padded()
-                .repeated()
-                .collect_exactly()
+        let outputs = Cell::new(0);
+        let word = text::ascii::ident::<_, _, extra::Default>()
+            .inspect(|_| outputs.set(outputs.get() + 1))
+            .map_with(|w, e| (w, e.span()));
+        let words = word.padded().repeated().collect::<Vec<_>>();
+
+        assert!(!words.check("foo bar").has_errors());
+        assert_eq!(outputs.get(), 0);
+        assert_eq!(
+            words.parse("foo bar").into_result(),
+            Ok(vec![("foo", SimpleSpan::new(0, 3)), ("bar", SimpleSpan::new(4, 7))]),
+        );
+        assert_eq!(outputs.get(), 2);
+    }
+
+    #[test]
+    fn to_span_skips_outputs() {
+        use core::cell::Cell;
+
+        let outputs = Cell::new(0);
+        let block = just::<_, &str, extra::Default>('{')
+            .then(none_of('}').repeated().collect::<String>())
+            .then(just('}'))
+            .inspect(|_| outputs.set(outputs.get() + 1));
+        let spans = block.to_span().padded().repeated().collect::<Vec<_>>();
+
+        assert_eq!(
+            spans.parse("{a} {bc}").into_result(),
+            Ok(vec![SimpleSpan::new(0, 3), SimpleSpan::new(4, 8)]),
+        );
+        assert_eq!(outputs.get(), 0);
+    }
+
+    #[test]
+    fn chain_and_flatten() {
+        let sign = one_of::<_, &str, extra::Default>("+-").or_not();
+        let exp = one_of("eE")
+            .chain(sign)
+            .chain(text::digits(10).collect::<Vec<_>>());
+        assert_eq!(
+            exp.parse("e-10").into_result(),
+            Ok(vec!['e', '-', '1', '0'])
+        );
+        assert_eq!(exp.parse("E3").into_result(), Ok(vec!['E', '3']));
+
+        let pairs = any::<&str, extra::Default>()
+            .then(any())
+            .map(|(a, b)| [a, b])
+            .repeated()
+            .collect::<Vec<_>>()
+            .flatten();
+        assert_eq!(
+            pairs.parse("abcd").into_result(),
+            Ok(vec!['a', 'b', 'c', 'd'])
+        );
+    }
+
+    #[test]
+    fn optional_defaults() {
+        let ab = just::<_, _, extra::Default>("ab").or_not_with(|| "none");
+        assert_eq!(
+            ab.then(just('a')).parse("a").into_result(),
+            Ok(("none", 'a'))
+        );
+        assert_eq!(
+            ab.then(just('a')).parse("aba").into_result(),
+            Ok(("ab", 'a'))
+        );
+
+        let digits = text::digits::<_, &str, extra::Default>(10)
+            .collect::<String>()
+            .or_default();
+        assert_eq!(digits.parse("").into_result(), Ok(String::new()));
+
+        let sign = one_of::<_, &str, extra::Default>("+-")
+            .or_not()
+            .unwrap_or('+');
+        assert_eq!(sign.parse("-").into_result(), Ok('-'));
+        assert_eq!(sign.parse("").into_result(), Ok('+'));
+    }
+
+    #[test]
+    fn recovery_stats() {
+        let stmt = text::int::<_, _, extra::Err<Simple<char>>>(10)
+            .then_ignore(just(';'))
+            .recover_with(skip_until(any().ignored(), just(';').ignored(), || "?"));
+        let stmts = stmt.padded().repeated().collect::<Vec<_>>();
+
+        let res = stmts.parse("1; 2;");
+        assert!(res.is_fully_valid());
+        assert_eq!(res.recovery(), RecoveryStats::default());
+
+        let res = stmts.parse("1; x2; 3; ;");
+        assert!(!res.is_fully_valid());
+        assert_eq!(res.output(), Some(&vec!["1", "?", "3", "?"]));
+        assert_eq!(res.errors().len(), 2);
+        assert_eq!(res.recovery().recoveries(), 2);
+        assert_eq!(res.recovery().skipped_tokens(), 4);
+
+        // Recoveries in branches that are backtracked out of are not counted
+        let a = just::<_, _, extra::Err<Simple<char>>>('a')
+            .recover_with(via_parser(any()))
+            .then_ignore(just('!'));
+        let b = just('b').then_ignore(just('?'));
+        let res = a.or(b).parse("b?");
+        assert_eq!(res.output(), Some(&'b'));
+        assert!(res.is_fully_valid());
+
+        // Recoveries within a parser with a different error type are counted
+        let stmt = text::int::<_, _, extra::Err<Simple<char>>>(10)
+            .then_ignore(just(';'))
+            .recover_with(skip_until(any().ignored(), just(';').ignored(), || "?"))
+            .map_err_to::<extra::Err<Rich<char>>>();
+        let res = stmt.padded().repeated().collect::<Vec<_>>().parse("1; x2;");
+        assert_eq!(res.errors().len(), 1);
+        assert_eq!(res.recovery().recoveries(), 1);
+        assert_eq!(res.recovery().skipped_tokens(), 3);
+
+        // Trivia are not counted as skipped, but pushed tokens are
+        let stmt = just::<_, _, extra::Err<Simple<char>>>('a')
+            .then_ignore(just(';'))
+            .recover_with(skip_until(any().ignored(), just(';').ignored(), || 'a'));
+        let res = stmt
+            .repeated()
+            .collect::<Vec<_>>()
+            .then_ignore(end())
+            .with_trivia(|c: &char| *c == ' ')
+            .parse("a ; b x ;");
+        assert_eq!(res.output(), Some(&vec!['a', 'a']));
+        assert_eq!(res.recovery().skipped_tokens(), 3);
+
+        let split = custom::<_, &str, _, extra::Err<Simple<char>>>(|inp| {
+            inp.push_back('x');
+            Ok(())
+        });
+        let res = split
+            .ignore_then(just('a').recover_with(via_parser(any().then(any()).to('?'))))
+            .parse("b");
+        assert_eq!(res.output(), Some(&'?'));
+        assert_eq!(res.recovery().skipped_tokens(), 2);
+    }
+
+    #[test]
+    fn select_window_pairs() {
+        let arrow = select_window::<_, &str, _, extra::Err<Rich<char>>, 2>(|[a, b], span| {
+            (a == '=' && b == '>').then_some(span)
+        });
+        assert_eq!(arrow.parse("=>").into_result(), Ok(SimpleSpan::new(0, 2)));
+
+        let errs = arrow.parse("=<").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].found(), Some(&'='));
+        assert_eq!(errs[0].span(), &SimpleSpan::new(0, 1));
+
+        let errs = arrow.parse("=").into_errors();
+        assert_eq!(errs[0].found(), None);
+
+        let op = arrow.to("=>").or(just("=").or(just(">")));
+        assert_eq!(
+            op.repeated()
+                .collect::<Vec<_>>()
+                .parse("=>==>>")
+                .into_result(),
+            Ok(vec!["=>", "=", "=>", ">"])
+        );
+    }
+
+    #[test]
+    fn kind_of_tokens() {
+        use self::primitive::TokenKind;
+
+        #[derive(Clone, Debug, PartialEq)]
+        enum Token {
+            Int(i64),
+            Str(&'static str),
+            Comma,
         }
 
-        let filename = "file.txt".to_string();
-        let fstr = filename.as_str();
+        impl TokenKind for Token {
+            type Kind = core::mem::Discriminant<Token>;
+
+            fn kind(&self) -> Self::Kind {
+                core::mem::discriminant(self)
+            }
+        }
+
+        let kind = |tok| core::mem::discriminant(&tok);
+        let lit =
+            kind_of::<_, &[Token], extra::Default>([kind(Token::Int(0)), kind(Token::Str(""))]);
+        let list = lit
+            .separated_by(kind_of([kind(Token::Comma)]))
+            .collect::<Vec<_>>();
 
         assert_eq!(
-            parser()
-                .parse(
-                    r#"hello "world" these are "test" tokens"#
-                        .map_span(|span| Span::new(fstr, span.start()..span.end()))
-                )
+            list.parse(&[Token::Int(1), Token::Comma, Token::Str("a")])
                 .into_result(),
-            Ok([
-                (Span::new("file.txt", 0..5), Token::Ident("hello")),
-                (Span::new("file.txt", 6..13), Token::String("\"world\"")),
-                (Span::new("file.txt", 14..19), Token::Ident("these")),
-                (Span::new("file.txt", 20..23), Token::Ident("are")),
-                (Span::new("file.txt", 24..30), Token::String("\"test\"")),
-                (Span::new("file.txt", 31..37), Token::Ident("tokens")),
-            ]),
+            Ok(vec![Token::Int(1), Token::Str("a")]),
         );
+        assert!(list.parse(&[Token::Comma]).has_errors());
     }
 
     #[test]
-    fn zero_copy_repetition() {
-        use self::prelude::*;
+    fn push_back_tokens() {
+        #[derive(Clone, Debug, PartialEq)]
+        enum Token {
+            Lt,
+            Gt,
+            Shr,
+        }
+        use Token::*;
 
-        fn parser<'a>() -> impl Parser<'a, &'a str, Vec<u64>> {
-            any()
-                .filter(|c: &char| c.is_ascii_digit())
-                .repeated()
-                .at_least(1)
-                .at_most(3)
-                .map_slice(|b: &str| b.parse::<u64>().unwrap())
-                .padded()
-                .separated_by(just(',').padded())
-                .allow_trailing()
-                .collect()
-                .delimited_by(just('['), just(']'))
+        let gt = custom::<_, &[Token], _, extra::Default>(|inp| match inp.next() {
+            Some(Gt) => Ok(()),
+            Some(Shr) => {
+                inp.push_back(Gt);
+                Ok(())
+            }
+            _ => Err(EmptyErr::default()),
+        });
+
+        // Pushed tokens count as progress
+        assert_eq!(gt.repeated().count().parse(&[Shr, Gt]).into_result(), Ok(3));
+
+        // Pushed tokens are visible to other parsers
+        assert!(gt.then(just(Gt)).parse(&[Shr]).into_result().is_ok());
+
+        // Pushed tokens are discarded by rewinding
+        let split_then_lt = gt.then(just(Lt)).to(1);
+        let whole = just(Shr).to(2);
+        assert_eq!(split_then_lt.or(whole).parse(&[Shr]).into_result(), Ok(2));
+
+        // Tokens pushed after a marker are dropped when rewinding to it
+        let pushed_after_rewind = custom::<_, &[Token], _, extra::Default>(|inp| {
+            let before = inp.save();
+            inp.push_back(Gt);
+            inp.push_back(Lt);
+            inp.rewind(before);
+            Ok(inp.pushed.len())
+        });
+        assert_eq!(pushed_after_rewind.parse(&[]).into_result(), Ok(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "pushed tokens can only be read by value")]
+    fn push_back_then_borrow() {
+        let split = custom::<_, &[char], _, extra::Default>(|inp| {
+            inp.next();
+            inp.push_back('b');
+            Ok(())
+        });
+        let _ = split.then(select_ref! { 'b' = span => span }).parse(&['a']);
+    }
+
+    #[test]
+    fn balanced_tokens() {
+        #[derive(Clone, Debug, PartialEq)]
+        enum Token {
+            Open,
+            Close,
+            Word(&'static str),
         }
+        use Token::*;
 
+        let block = balanced::<&[Token], extra::Err<Simple<Token>>>(Open, Close);
+        let tokens = [Open, Word("a"), Open, Close, Open, Word("b"), Close, Close];
+        assert_eq!(block.parse(&tokens).into_result(), Ok(&tokens[1..7]));
+
+        // The final closing delimiter is missing
+        let errs = block.parse(&tokens[..7]).into_errors();
         assert_eq!(
-            parser().parse("[122 , 23,43,    4, ]").into_result(),
-            Ok(vec![122, 23, 43, 4]),
+            errs.iter().map(|e| *e.span()).collect::<Vec<_>>(),
+            [SimpleSpan::new(7, 7)],
         );
+        assert!(block.parse(&[Word("a")]).has_errors());
+    }
+
+    #[test]
+    fn separated_collect_string() {
+        let path = text::ascii::ident::<_, _, extra::Default>()
+            .separated_by(just("::"))
+            .allow_leading()
+            .at_least(1)
+            .collect_string();
+        assert_eq!(path.parse("a::b::c").into_result(), Ok("a::b::c"));
+        assert_eq!(path.parse("::core").into_result(), Ok("::core"));
+        assert!(path.parse("a::").has_errors());
+
+        let bytes = any::<_, extra::Default>()
+            .filter(u8::is_ascii_digit)
+            .separated_by(just(b'_'))
+            .collect_string()
+            .then_ignore(end());
         assert_eq!(
-            parser().parse("[0, 3, 6, 900,120]").into_result(),
-            Ok(vec![0, 3, 6, 900, 120]),
+            bytes.parse(b"1_0_0".as_slice()).into_result(),
+            Ok(b"1_0_0".as_slice())
         );
+    }
+
+    #[test]
+    fn collect_map_duplicates() {
+        use alloc::collections::BTreeMap;
+
+        let entry = text::ascii::ident::<_, _, extra::Err<Rich<char>>>()
+            .then_ignore(just(':'))
+            .then(text::int(10));
+        let map = |duplicates| {
+            entry
+                .separated_by(just(' '))
+                .collect_map::<_, _, BTreeMap<_, _>>(duplicates)
+        };
+
+        let src = "x:1 y:2 x:3";
         assert_eq!(
-            parser().parse("[200,400,50  ,0,0, ]").into_result(),
-            Ok(vec![200, 400, 50, 0, 0]),
+            map(Duplicates::Overwrite).parse(src).into_result(),
+            Ok(BTreeMap::from_iter([("x", "3"), ("y", "2")])),
+        );
+        assert_eq!(
+            map(Duplicates::KeepFirst).parse(src).into_result(),
+            Ok(BTreeMap::from_iter([("x", "1"), ("y", "2")])),
         );
 
-        assert!(parser().parse("[1234,123,12,1]").has_errors());
-        assert!(parser().parse("[,0, 1, 456]").has_errors());
-        assert!(parser().parse("[3, 4, 5, 67 89,]").has_errors());
+        let res = map(Duplicates::Error(|key, span| {
+            Rich::custom(span, format!("{key} is already defined"))
+        }))
+        .parse(src);
+        assert_eq!(
+            res.output(),
+            Some(&BTreeMap::from_iter([("x", "1"), ("y", "2")]))
+        );
+        assert_eq!(
+            res.errors()
+                .map(|e| (e.to_string(), *e.span()))
+                .collect::<Vec<_>>(),
+            [("x is already defined".to_string(), SimpleSpan::new(7, 11))],
+        );
     }
 
     #[test]
-    fn zero_copy_group() {
-        use self::prelude::*;
+    fn just_eof_expects_whole_seq() {
+        let kw = just::<_, _, extra::Err<Rich<char>>>("while");
+        let errs = kw.parse("whi").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(0, 3));
+        assert_eq!(errs[0].to_string(), "found end of input expected 'while'");
+
+        // Mismatches before the end of input still point at the offending token
+        let errs = kw.parse("whale").into_errors();
+        assert_eq!(errs[0].span(), &SimpleSpan::new(2, 3));
+        assert_eq!(errs[0].to_string(), "found 'a' expected 'i'");
+
+        let errs = just::<_, _, extra::Err<Rich<char>>>('x')
+            .parse("")
+            .into_errors();
+        assert_eq!(errs[0].to_string(), "found end of input expected 'x'");
+    }
 
-        fn parser<'a>() -> impl Parser<'a, &'a str, (&'a str, u64, char)> {
-            group((
-                any()
-                    .filter(|c: &char| c.is_ascii_alphabetic())
-                    .repeated()
-                    .at_least(1)
-                    .slice()
-                    .padded(),
-                any()
-                    .filter(|c: &char| c.is_ascii_digit())
-                    .repeated()
-                    .at_least(1)
-                    .map_slice(|s: &str| s.parse::<u64>().unwrap())
-                    .padded(),
-                any().filter(|c: &char| !c.is_whitespace()).padded(),
-            ))
-        }
+    #[test]
+    fn then_catch_outputs_errors() {
+        let num = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .then_ignore(just(';'))
+            .then_catch();
+        let parser = num.then(any().repeated().slice());
+
+        let (caught, rest) = parser.parse("12x").into_result().unwrap();
+        let err = caught.unwrap_err();
+        assert_eq!(err.span(), &SimpleSpan::new(2, 3));
+        assert_eq!(err.found(), Some(&'x'));
+        // Nothing was consumed by the failed attempt
+        assert_eq!(rest, "12x");
+
+        assert_eq!(parser.parse("3;a").into_result(), Ok((Ok("3"), "a")));
+
+        // The caught error does not leak into the errors of the outer parse
+        let errs = num.then(just('!')).parse("x?").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].found(), Some(&'x'));
+        assert_eq!(
+            errs[0].expected().collect::<Vec<_>>(),
+            [&error::RichPattern::Token('!'.into())]
+        );
+    }
 
+    #[test]
+    #[cfg(feature = "debug-replay")]
+    fn parse_traced_records_backtracking() {
+        use crate::trace::TraceEvent;
+
+        let parser = just::<_, _, extra::Default>("ab")
+            .or(just("ac"))
+            .repeated()
+            .collect::<Vec<_>>();
+
+        let (res, trace) = parser.parse_traced("abac");
+        assert_eq!(res.into_result(), Ok(vec!["ab", "ac"]));
+        assert!(trace
+            .events()
+            .contains(&TraceEvent::Rewind { from: 4, to: 2 }));
+        assert_eq!(trace.backtracks().get(&2), Some(&1));
+        // The first token of the second item was read once for each alternative
+        assert_eq!(trace.reads().get(&2), Some(&2));
+        // Replaying the trace ends at the end of the input
+        assert_eq!(trace.replay().last().map(|(offset, _)| offset), Some(4));
+
+        // Tracing does not change the result of parsing
         assert_eq!(
-            parser().parse("abc 123 [").into_result(),
-            Ok(("abc", 123, '[')),
+            parser.parse_traced("abx").0.into_errors(),
+            parser.parse("abx").into_errors()
         );
+    }
+
+    #[test]
+    #[cfg(all(feature = "telemetry", feature = "memoization"))]
+    fn parse_with_telemetry_counts_operations() {
+        use crate::telemetry::ParseStats;
+
+        // Both alternatives share the same memoized parser
+        let item = just::<_, _, extra::Err<Simple<char>>>('a').memoized();
+        let item = &item;
+        let parser = item
+            .then(just('b'))
+            .or(item.then(just('c')))
+            .recover_with(skip_then_retry_until(any().ignored(), end()))
+            .repeated()
+            .collect::<Vec<_>>();
+
+        let mut totals = ParseStats::default();
+        let mut last = ParseStats::default();
+        let res = parser.parse_with_telemetry("abacxab", &mut |s: &ParseStats| last = *s);
         assert_eq!(
-            parser().parse("among3d").into_result(),
-            Ok(("among", 3, 'd')),
+            res.into_output(),
+            Some(vec![('a', 'b'), ('a', 'c'), ('a', 'b')])
         );
+        assert_eq!(last.parses(), 1);
+        assert_eq!(last.recoveries(), 1);
+        // The second alternative reused the failures of `a` at `x` and at the end of the input
+        assert_eq!(last.memo_hits(), 2);
+        assert!(last.memo_misses() > 2);
+        assert!(last.backtracks() > 0 && last.rewinds() >= last.backtracks());
+        assert!(last.tokens() > "abacxab".len());
+
+        // Each failure of `a` is reused by the second alternative
+        let _ = parser.parse_with_telemetry("x", &mut totals);
+        let _ = parser.parse_with_telemetry("x", &mut totals);
+        assert_eq!(totals.parses(), 2);
+        assert_eq!(totals.memo_hits(), 4);
+        assert_eq!(totals.memo_hit_rate(), Some(0.5));
+
+        // Counting does not change the result of parsing
         assert_eq!(
-            parser().parse("cba321,").into_result(),
-            Ok(("cba", 321, ',')),
+            parser
+                .parse_with_telemetry("abx", &mut totals)
+                .into_errors(),
+            parser.parse("abx").into_errors()
         );
+    }
 
-        assert!(parser().parse("abc 123  ").has_errors());
-        assert!(parser().parse("123abc ]").has_errors());
-        assert!(parser().parse("and one &").has_errors());
+    #[test]
+    fn suggest_insert_only_at_start() {
+        let close = just::<_, _, extra::Err<Rich<char>>>("])").or_suggest_insert("])");
+        let list = just('[').ignore_then(text::int(10)).then_ignore(close);
+
+        let errs = list.parse("[1 ").into_errors();
+        let suggestions = errs[0].suggestions().collect::<Vec<_>>();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].span, SimpleSpan::new(2, 2));
+        assert_eq!(suggestions[0].replacement, "])");
+
+        // The pattern was partially present, so inserting it would not help
+        assert_eq!(list.parse("[1]x").into_errors()[0].suggestions().len(), 0);
+        // Errors elsewhere are not annotated
+        assert_eq!(list.parse("x").into_errors()[0].suggestions().len(), 0);
     }
 
     #[test]
-    fn zero_copy_group_array() {
-        use self::prelude::*;
+    fn shared_clone_is_shallow() {
+        struct NotClone(Vec<char>);
+
+        let vowels = NotClone(vec!['a', 'e', 'i', 'o', 'u']);
+        let vowel = any::<_, extra::Default>()
+            .filter(move |c| vowels.0.contains(c))
+            .shared();
+
+        // The parser is cloneable, despite owning data that is not
+        let word = vowel.clone().repeated().at_least(1).collect::<String>();
+        assert_eq!(word.parse("aei").into_result(), Ok("aei".to_string()));
+        assert_eq!(vowel.parse("o").into_result(), Ok('o'));
+        assert!(vowel.parse("x").has_errors());
+    }
 
-        fn parser<'a>() -> impl Parser<'a, &'a str, [char; 3]> {
-            group([just('a'), just('b'), just('c')])
+    #[test]
+    #[cfg(feature = "simplify-types")]
+    fn simplify_types_erases_type() {
+        fn type_name_of<T>(_: &T) -> &'static str {
+            core::any::type_name::<T>()
         }
 
-        assert_eq!(parser().parse("abc").into_result(), Ok(['a', 'b', 'c']));
-        assert!(parser().parse("abd").has_errors());
+        let int = text::int::<_, _, extra::Default>(10)
+            .padded()
+            .separated_by(just(','))
+            .collect::<Vec<_>>()
+            .simplify_types();
+        assert_eq!(
+            type_name_of(&int),
+            core::any::type_name::<Boxed<&str, Vec<&str>, extra::Default>>(),
+        );
+        assert!(!type_name_of(&int).contains("SeparatedBy"));
+        assert_eq!(int.parse("1, 2").into_result(), Ok(vec!["1", "2"]));
     }
 
     #[test]
-    fn unicode_str() {
-        let input = "🄯🄚🹠🴎🄐🝋🰏🄂🬯🈦g🸵🍩🕔🈳2🬙🨞🅢🭳🎅h🵚🧿🏩🰬k🠡🀔🈆🝹🤟🉗🴟📵🰄🤿🝜🙘🹄5🠻🡉🱖🠓";
-        let mut own = InputOwn::<_, extra::Default>::new(input);
-        let mut inp = own.as_ref_start();
+    fn walk_visits_every_parser_once() {
+        use self::walk::{ParserNode, Visitor};
+
+        #[derive(Default)]
+        struct Names(Vec<String>);
+        impl Visitor for Names {
+            fn enter(&mut self, node: &ParserNode) -> bool {
+                let name = node.name().to_string();
+                self.0.push(if node.is_revisit() {
+                    format!("{}*", name)
+                } else {
+                    name
+                });
+                // Don't look inside the atoms
+                node.name() != "DelimitedBy"
+            }
+        }
 
-        while let Some(_c) = inp.next() {}
+        let list = recursive(|list| {
+            let atom = Parser::boxed(
+                just::<_, &str, extra::Default>('x')
+                    .ignored()
+                    .or(list.delimited_by(just('('), just(')'))),
+            );
+            atom.separated_by(just(',')).collect::<()>()
+        });
+        let mut names = Names::default();
+        list.clone().then_ignore(end()).walk(&mut names);
+        assert_eq!(
+            names.0,
+            [
+                "ThenIgnore",
+                "Recursive",
+                "Collect",
+                "SeparatedBy",
+                "Boxed",
+                "Or",
+                "Ignored",
+                "Just",
+                "DelimitedBy",
+                "Just",
+                "End",
+            ]
+        );
+
+        // The recursive definition is only walked once
+        let mut names = Names::default();
+        let twice = list.clone().then(list);
+        twice.walk(&mut names);
+        assert_eq!(names.0.len(), 11);
+        assert_eq!(names.0[10], "Recursive*");
     }
 
     #[test]
-    fn iter() {
-        use self::prelude::*;
+    fn assert_complete_finds_todos() {
+        let stmt = recursive(|stmt| {
+            let block = stmt
+                .repeated()
+                .collect::<Vec<_>>()
+                .delimited_by(just('{'), just('}'));
+            let expr = todo::<&str, (), extra::Default>();
+            block.ignored().or(expr.then_ignore(just(';')))
+        });
+        let err = stmt.clone().then(stmt).assert_complete().unwrap_err();
+        // The placeholder is inside a recursive parser, so it is only reported once
+        assert_eq!(err.locations().len(), 1);
+        assert_eq!(err.locations()[0].file(), file!());
+
+        assert!(just::<_, &str, extra::Default>('x')
+            .repeated()
+            .assert_complete()
+            .is_ok());
+    }
 
-        fn parser<'a>() -> impl IterParser<'a, &'a str, char> {
-            any().repeated()
+    #[test]
+    fn relex_matches_lex() {
+        use self::relex::{TextEdit, Tokens};
+
+        fn token<'a>() -> impl Parser<'a, &'a str, String, extra::Err<Simple<'a, char>>> {
+            choice((
+                text::ascii::ident(),
+                text::int(10),
+                just('"')
+                    .then(none_of('"').repeated())
+                    .then(just('"'))
+                    .slice(),
+                one_of("+-*/=;").slice(),
+            ))
+            .map(ToString::to_string)
         }
-
-        let mut chars = String::new();
-        for c in parser().parse_iter(&"abcdefg").into_result().unwrap() {
-            chars.push(c);
+        fn trivia<'a>() -> impl Parser<'a, &'a str, (), extra::Err<Simple<'a, char>>> {
+            let comment = just("//").then(none_of('\n').repeated()).ignored();
+            text::whitespace().at_least(1).or(comment).repeated()
         }
 
-        assert_eq!(&chars, "abcdefg");
+        let mut src = String::from("let x = 1 + foo; // one\nlet y = \"two\";");
+        let (mut tokens, errs) = Tokens::lex(&token(), &trivia(), &src);
+        assert!(errs.is_empty());
+
+        let mut edit = |range: Range<usize>, text: &str| {
+            src.replace_range(range.clone(), text);
+            let relexed =
+                tokens.relex(&token(), &trivia(), &src, &TextEdit::new(range, text.len()));
+            let (expected, _) = Tokens::lex(&token(), &trivia(), &src);
+            assert_eq!(tokens, expected, "after editing to {:?}", src);
+            (relexed.tokens(), relexed.removed(), relexed.errors().len())
+        };
+
+        // Merge two identifiers by deleting the space between them, then split them again
+        assert_eq!(edit(3..4, ""), (0..1, 2, 0));
+        assert_eq!(edit(3..3, " "), (0..2, 1, 0));
+        // Open a string, swallowing the tokens up to the one that closes it, then close it again
+        let (_, _, errs) = edit(8..8, "\"");
+        assert_eq!(errs, 1);
+        assert_eq!(edit(8..9, ""), (3..12, 3, 0));
+        // Comment out a statement
+        assert_eq!(edit(0..0, "//"), (0..1, 8, 0));
+        // An unlexable character
+        assert_eq!(edit(40..40, "?"), (4..5, 1, 1));
+        // Remove everything
+        edit(0..41, "");
+        assert!(tokens.is_empty());
     }
 
     #[test]
-    #[cfg(feature = "memoization")]
-    fn exponential() {
-        use self::prelude::*;
+    fn parse_items_recovering_skips_to_sync() {
+        let item = just::<_, _, extra::Err<Simple<char>>>("fn ")
+            .ignore_then(text::ascii::ident())
+            .then_ignore(just("();"));
+        let sync = just("fn ");
+
+        let mut items = item.parse_items_recovering("fn a();fn b(x);fn c();", sync);
+        assert_eq!(items.next(), Some(Ok("a")));
+        // The error is at the start of the unexpected argument
+        let err = items.next().unwrap().unwrap_err();
+        assert_eq!(err.span(), &SimpleSpan::from(12..13));
+        assert_eq!(items.next(), Some(Ok("c")));
+        assert_eq!(items.next(), None);
+        assert!(items.take_errors().is_empty());
+
+        // Garbage at the end of the input is skipped in one go
+        let items = item
+            .parse_items_recovering("fn a();garbage", sync)
+            .collect::<Vec<_>>();
+        assert_eq!(items.len(), 2);
+        assert!(items[1].is_err());
+    }
 
-        fn parser<'a>() -> impl Parser<'a, &'a str, String> {
-            recursive(|expr| {
-                let atom = any()
-                    .filter(|c: &char| c.is_alphabetic())
-                    .repeated()
-                    .at_least(1)
-                    .collect()
-                    .or(expr.delimited_by(just('('), just(')')));
+    #[test]
+    fn parse_spans_outline() {
+        // The first alternative parses a node before failing, so it must not appear in the outline
+        let digit = any::<_, extra::Err<Simple<char>>>()
+            .filter(char::is_ascii_digit)
+            .node();
+        let list = recursive(|list| {
+            choice((
+                digit.then_ignore(just('!')).ignored(),
+                digit.ignored(),
+                list.delimited_by(just('('), just(')')).ignored(),
+            ))
+            .separated_by(just(','))
+            .node()
+        });
 
-                atom.clone()
-                    .then_ignore(just('+'))
-                    .then(atom.clone())
-                    .map(|(a, b)| format!("{}{}", a, b))
-                    .memoized()
-                    .or(atom)
-            })
-            .then_ignore(end())
-        }
+        let outline = list.parse_spans("1,(2,3),4!").into_result().unwrap();
+        let spans = |trees: &[SpanTree<SimpleSpan>]| {
+            trees
+                .iter()
+                .map(|tree| tree.span().into_range())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(outline.len(), 1);
+        assert_eq!(outline[0].span(), &SimpleSpan::from(0..10));
+        assert_eq!(spans(outline[0].children()), [0..1, 3..6, 8..9]);
+        assert_eq!(spans(outline[0].children()[1].children()), [3..4, 5..6]);
+
+        // A failed parse produces errors, but no outline
+        let res = list.parse_spans("1,(2");
+        assert!(res.has_errors() && !res.has_output());
+    }
 
-        parser()
-            .parse("((((((((((((((((((((((((((((((a+b))))))))))))))))))))))))))))))")
+    #[test]
+    fn parse_spans_skips_trivia() {
+        let item = just::<_, _, extra::Default>('a').node();
+        let outline = item
+            .repeated()
+            .then_ignore(end())
+            .with_trivia(|c: &char| c.is_whitespace())
+            .parse_spans("  a a ")
             .into_result()
             .unwrap();
+        assert_eq!(outline[0].span(), &SimpleSpan::from(2..3));
+        assert_eq!(outline[1].span(), &SimpleSpan::from(4..5));
     }
 
     #[test]
     #[cfg(feature = "memoization")]
-    fn left_recursive() {
-        use self::prelude::*;
-
-        fn parser<'a>() -> impl Parser<'a, &'a str, String> {
-            recursive(|expr| {
-                let atom = any()
-                    .filter(|c: &char| c.is_alphabetic())
-                    .repeated()
-                    .at_least(1)
-                    .collect();
+    fn parse_spans_memoized() {
+        let outline = |memoize: bool| {
+            let int = text::int::<_, _, extra::Default>(10).node();
+            let int = if memoize {
+                Parser::boxed(int.memoized())
+            } else {
+                Parser::boxed(int)
+            };
+            // `int` fails at `x` and hits the cache in the second alternative, and succeeds at `2` but is reparsed
+            // by the second alternative after the first one fails
+            choice((
+                int.clone().then_ignore(just(';')),
+                int.then_ignore(just(',')),
+                just("x").node(),
+            ))
+            .repeated()
+            .collect::<Vec<_>>()
+            .parse_spans("1;x2,")
+            .into_result()
+            .unwrap()
+            .iter()
+            .map(|tree| tree.span().into_range())
+            .collect::<Vec<_>>()
+        };
 
-                let sum = expr
-                    .clone()
-                    .then_ignore(just('+'))
-                    .then(expr)
-                    .map(|(a, b)| format!("{}{}", a, b))
-                    .memoized();
+        assert_eq!(outline(false), [0..1, 2..3, 3..4]);
+        assert_eq!(outline(true), outline(false));
+    }
 
-                sum.or(atom)
-            })
-            .then_ignore(end())
-        }
+    #[test]
+    fn split_words_then_parse_tokens() {
+        let src = "move 3 left\n";
+        let words = text::split_words::<_, _, extra::Err<Simple<char>>>()
+            .parse(src)
+            .into_result()
+            .unwrap();
 
-        assert_eq!(parser().parse("a+b+c").into_result().unwrap(), "abc");
+        let cmd = just::<_, _, extra::Err<Simple<&str>>>("move")
+            .ignore_then(
+                any()
+                    .filter(|w: &&str| w.parse::<i32>().is_ok())
+                    .map(|w: &str| w.parse::<i32>().unwrap()),
+            )
+            .then(choice((just("left").to(-1), just("right").to(1))))
+            .map(|(n, dir)| n * dir);
+        let eoi = SimpleSpan::from(src.len()..src.len());
+        assert_eq!(
+            cmd.parse(words.as_slice().spanned(eoi)).into_result(),
+            Ok(-3)
+        );
+        assert!(cmd.parse(words[..2].spanned(eoi)).has_errors());
     }
 
-    #[cfg(debug_assertions)]
-    mod debug_asserts {
-        use super::prelude::*;
+    #[test]
+    fn slice_and_lines_for_span() {
+        use self::input::{Normalized, SliceInput};
+
+        let src = "ab\r\ncd\r\nef";
+        let norm = Normalized::new(src);
+        let input = &norm;
+        // Spans of a normalized input refer to the source, while slices come from the normalized text
+        assert_eq!(input.slice_for_span(&SimpleSpan::from(2..7)), Some("\ncd"));
+        assert_eq!(input.slice_for_span(&SimpleSpan::from(5..5)), Some(""));
+        assert_eq!(input.slice_for_span(&SimpleSpan::new(5, 4)), None);
+        assert_eq!(input.lines_for_span(&SimpleSpan::from(5..6)), Some("cd"));
+        assert_eq!(
+            input.lines_for_span(&SimpleSpan::from(1..9)),
+            Some("ab\ncd\nef")
+        );
 
-        // TODO panic when left recursive parser is detected
-        // #[test]
-        // #[should_panic]
-        // fn debug_assert_left_recursive() {
-        //     recursive(|expr| {
-        //         let atom = any::<&str, extra::Default>()
-        //             .filter(|c: &char| c.is_alphabetic())
-        //             .repeated()
-        //             .at_least(1)
-        //             .collect();
+        let bytes = b"one\ntwo\nthree".as_slice();
+        assert_eq!(
+            bytes.slice_for_span(&SimpleSpan::from(4..7)),
+            Some(&b"two"[..])
+        );
+        assert_eq!(
+            bytes.lines_for_span(&SimpleSpan::from(5..5)),
+            Some(&b"two"[..])
+        );
+        assert_eq!(
+            bytes.lines_for_span(&SimpleSpan::from(9..13)),
+            Some(&b"three"[..])
+        );
 
-        //         let sum = expr
-        //             .clone()
-        //             .then_ignore(just('+'))
-        //             .then(expr)
-        //             .map(|(a, b)| format!("{}{}", a, b));
+        assert_eq!(src.lines_for_span(&SimpleSpan::from(9..9)), Some("ef"));
+        assert_eq!(src.slice_for_span(&SimpleSpan::from(0..20)), None);
+    }
 
-        //         sum.or(atom)
-        //     })
-        //     .then_ignore(end())
-        //     .parse("a+b+c");
-        // }
+    #[test]
+    fn configure_from_length_prefix() {
+        // A count byte, followed by that many comma-separated items, followed by the count byte again
+        let items = any()
+            .separated_by(just(b','))
+            .configure(|cfg, n: &u8| cfg.exactly(*n as usize))
+            .collect::<Vec<_>>()
+            .then_ignore(just_ctx());
+        let record = any::<_, extra::Err<Simple<u8>>>().ignore_with_ctx(items);
 
-        #[test]
-        #[should_panic]
-        #[cfg(debug_assertions)]
-        fn debug_assert_collect() {
-            empty::<&str, extra::Default>()
-                .to(())
+        assert_eq!(
+            record.parse(b"\x02a,b\x02".as_slice()).into_result(),
+            Ok(vec![b'a', b'b'])
+        );
+        assert_eq!(
+            record.parse(b"\x00\x00".as_slice()).into_result(),
+            Ok(vec![])
+        );
+        assert!(record.parse(b"\x02a\x02".as_slice()).has_errors());
+        assert!(record.parse(b"\x01a,b\x01".as_slice()).has_errors());
+        assert!(record.parse(b"\x01a\x02".as_slice()).has_errors());
+    }
+
+    #[test]
+    fn rollback_state_on_backtrack() {
+        fn items<'a, E>() -> impl Parser<'a, &'a str, Vec<((), char)>, E>
+        where
+            E: ParserExtra<'a, &'a str, State = Vec<char>>,
+        {
+            let push = any().map_with_state(|c, _, seen: &mut Vec<char>| seen.push(c));
+            push.then(just('!'))
+                .or(push.then(just('?')))
                 .repeated()
-                .collect::<()>()
-                .parse("a+b+c")
-                .unwrap();
+                .collect()
         }
 
-        #[test]
-        #[should_panic]
-        #[cfg(debug_assertions)]
-        fn debug_assert_separated_by() {
-            empty::<&str, extra::Default>()
-                .to(())
-                .separated_by(empty())
-                .collect::<()>()
-                .parse("a+b+c");
-        }
+        let mut seen = Vec::new();
+        items::<extra::Full<EmptyErr, _, ()>>()
+            .parse_with_state("a?b!c", &mut seen)
+            .into_result()
+            .unwrap_err();
+        assert_eq!(seen, ['a', 'a', 'b', 'c', 'c']);
 
-        #[test]
-        fn debug_assert_separated_by2() {
-            assert_eq!(
-                empty::<&str, extra::Default>()
-                    .to(())
-                    .separated_by(just(','))
-                    .count()
-                    .parse(",")
-                    .unwrap(),
-                2
-            );
-        }
+        let mut seen = Vec::new();
+        items::<extra::Rollback<EmptyErr, _, ()>>()
+            .parse_with_state("a?b!c", &mut seen)
+            .into_result()
+            .unwrap_err();
+        assert_eq!(seen, ['a', 'b']);
+    }
 
-        #[test]
-        #[should_panic]
-        #[cfg(debug_assertions)]
-        fn debug_assert_foldl() {
-            assert_eq!(
-                empty::<&str, extra::Default>()
-                    .to(1)
-                    .foldl(empty().repeated(), |n, ()| n + 1)
-                    .parse("a+b+c")
-                    .unwrap(),
-                3
-            );
-        }
+    #[test]
+    fn rollback_state_with_snapshots() {
+        use crate::extra::RollbackState;
 
-        #[test]
-        #[should_panic]
-        #[cfg(debug_assertions)]
-        fn debug_assert_foldl_with_state() {
-            let mut state = 100;
-            empty::<&str, extra::Full<EmptyErr, i32, ()>>()
-                .foldl_with_state(empty().to(()).repeated(), |_, _, _| ())
-                .parse_with_state("a+b+c", &mut state);
-        }
+        // A state that can't be restored from a length alone
+        #[derive(Default)]
+        struct Deepest(usize);
 
-        #[test]
-        #[should_panic]
-        #[cfg(debug_assertions)]
-        fn debug_assert_foldr() {
-            empty::<&str, extra::Default>()
-                .to(())
-                .repeated()
-                .foldr(empty(), |_, _| ())
-                .parse("a+b+c");
+        impl RollbackState for Deepest {
+            type Saved = usize;
+
+            fn save(&self) -> usize {
+                self.0
+            }
+
+            fn restore(&mut self, saved: usize) {
+                self.0 = saved;
+            }
         }
 
-        #[test]
-        #[should_panic]
-        #[cfg(debug_assertions)]
-        fn debug_assert_foldr_with_state() {
-            empty::<&str, extra::Default>()
-                .to(())
-                .repeated()
-                .foldr_with_state(empty(), |_, _, _| ())
-                .parse_with_state("a+b+c", &mut ());
+        let depth = just::<_, _, extra::Rollback<EmptyErr, Deepest, ()>>('(')
+            .repeated()
+            .count()
+            .map_with_state(|n, _, deepest: &mut Deepest| deepest.0 = deepest.0.max(n));
+        let item = depth.then_ignore(just('!')).or(just("((x").ignored());
+
+        let mut deepest = Deepest::default();
+        // The depth recorded by the failed first branch is rolled back
+        assert!(!item.parse_with_state("((x", &mut deepest).has_errors());
+        assert_eq!(deepest.0, 0);
+        assert!(!item.parse_with_state("((!", &mut deepest).has_errors());
+        assert_eq!(deepest.0, 2);
+    }
+
+    #[test]
+    fn padded_with_spans_reports_gaps() {
+        let item = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+            .padded_with_spans()
+            .map(|(leading, ident, trailing): (SimpleSpan, _, SimpleSpan)| {
+                (leading.into_range(), ident, trailing.into_range())
+            })
+            .separated_by(just(','))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            item.parse("a ,\n  b,c  ").into_result(),
+            Ok(vec![
+                (0..0, "a", 1..2),
+                (3..6, "b", 7..7),
+                (8..8, "c", 9..11)
+            ])
+        );
+        assert!(item.parse(" a b").has_errors());
+    }
+
+    #[test]
+    fn group_ignoring_drops_marked_outputs() {
+        let pair = group_ignoring!(
+            _ = just::<_, _, extra::Err<Simple<char>>>('('),
+            any(),
+            _ = just(','),
+            any(),
+            _ = just(')'),
+        );
+        assert_eq!(pair.parse("(a,b)").into_result(), Ok(('a', 'b')));
+        assert!(pair.parse("(a;b)").has_errors());
+
+        let single = group_ignoring!(any::<_, extra::Err<Simple<char>>>(), _ = just(';'));
+        assert_eq!(single.parse("x;").into_result(), Ok(('x',)));
+
+        let none = group_ignoring!(_ = just::<_, _, extra::Err<Simple<char>>>("ab"), _ = end());
+        assert_eq!(none.parse("ab").into_result(), Ok(()));
+    }
+
+    #[test]
+    fn map_input_translates_errors() {
+        let digits =
+            text::int::<&str, _, extra::Err<Rich<char>>>(10).validate(|x: &str, span, emitter| {
+                if x.len() > 2 {
+                    emitter.emit(Rich::custom(span, "too long"));
+                }
+                x
+            });
+        let field = digits
+            .map_input(
+                none_of::<_, _, extra::Err<Rich<u8>>>(b',')
+                    .repeated()
+                    .slice(),
+                |bytes: &[u8]| core::str::from_utf8(bytes).unwrap(),
+                |err: Rich<char>, span: SimpleSpan| -> Rich<u8> {
+                    let inner = err.span();
+                    Rich::custom(
+                        (span.start + inner.start..span.start + inner.end).into(),
+                        err,
+                    )
+                },
+            )
+            .or(just(b"-".as_slice()).to("none"))
+            .separated_by(just(b','))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            field.parse(b"12,-,3".as_slice()).into_result(),
+            Ok(vec!["12", "none", "3"])
+        );
+
+        let (out, errs) = field.parse(b"1,234".as_slice()).into_output_errors();
+        assert_eq!(out, Some(vec!["1", "234"]));
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::from(2..5));
+
+        let errs = field.parse(b"1,2x".as_slice()).into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::from(3..4));
+    }
+
+    #[test]
+    fn parse_all_matches_parse() {
+        let sum = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .from_str::<u32>()
+            .unwrapped()
+            .separated_by(just('+'))
+            .collect::<Vec<_>>()
+            .map(|xs| xs.into_iter().sum::<u32>());
+        // Memoized results must not leak from one input into the next
+        #[cfg(feature = "memoization")]
+        let sum = sum.memoized();
+
+        let inputs = (0..100)
+            .map(|i| {
+                if i % 7 == 0 {
+                    format!("{i}+")
+                } else {
+                    format!("{i}+{i}")
+                }
+            })
+            .collect::<Vec<_>>();
+        let results = sum.parse_all(inputs.iter().map(String::as_str));
+        assert_eq!(results.len(), inputs.len());
+        for (input, res) in inputs.iter().zip(results) {
+            assert_eq!(res.into_result(), sum.parse(input.as_str()).into_result());
         }
-
-        #[test]
-        #[should_panic]
-        #[cfg(debug_assertions)]
-        fn debug_assert_repeated() {
-            empty::<&str, extra::Default>()
-                .to(())
-                .repeated()
-                .parse("a+b+c");
+        assert!(sum.parse_all(core::iter::empty::<&str>()).is_empty());
+
+        #[cfg(feature = "parallel")]
+        {
+            let parallel =
+                crate::parallel::parse_all_parallel(&sum, inputs.iter().map(String::as_str));
+            for (input, res) in inputs.iter().zip(parallel) {
+                assert_eq!(res.into_result(), sum.parse(input.as_str()).into_result());
+            }
         }
-
-        // TODO what about IterConfigure and TryIterConfigure?
     }
 
     #[test]
-    #[should_panic]
-    fn recursive_define_twice() {
-        let mut expr = Recursive::declare();
-        expr.define({
-            let atom = any::<&str, extra::Default>()
-                .filter(|c: &char| c.is_alphabetic())
-                .repeated()
-                .at_least(1)
-                .collect();
-            let sum = expr
-                .clone()
-                .then_ignore(just('+'))
-                .then(expr.clone())
-                .map(|(a, b)| format!("{}{}", a, b));
+    fn normalized_spans_map_to_source() {
+        use crate::input::Normalized;
+
+        let normalized = Normalized::with_tab_width("a\r\n\tb\r\n", 4);
+        let spans = any::<_, extra::Default>()
+            .map_with(|c, e| (c, e.span()))
+            .repeated()
+            .collect::<Vec<_>>()
+            .parse(&normalized)
+            .into_result()
+            .unwrap();
+        let space = (' ', SimpleSpan::from(3..4));
+        assert_eq!(
+            spans,
+            [
+                ('a', SimpleSpan::from(0..1)),
+                ('\n', SimpleSpan::from(1..3)),
+                space,
+                space,
+                space,
+                space,
+                ('b', SimpleSpan::from(4..5)),
+                ('\n', SimpleSpan::from(5..7)),
+            ],
+        );
 
-            sum.or(atom)
-        });
-        expr.define(expr.clone());
+        // Tab stops depend on the column at which the tab appears
+        assert_eq!(Normalized::with_tab_width("ab\tc", 4).as_str(), "ab  c");
+        assert_eq!(Normalized::new("a\tb\rc").as_str(), "a\tb\rc");
 
-        expr.then_ignore(end()).parse("a+b+c");
+        let ws = text::whitespace::<_, _, extra::Default>()
+            .slice()
+            .map_with(|ws, e| (ws, e.span()));
+        assert_eq!(
+            ws.parse(&Normalized::with_tab_width("\r\n\t", 2))
+                .into_result(),
+            Ok(("\n  ", SimpleSpan::from(0..3))),
+        );
     }
 
     #[test]
-    #[should_panic]
-    fn todo_err() {
-        let expr = todo::<&str, String, extra::Default>();
-        expr.then_ignore(end()).parse("a+b+c");
+    fn parse_stream_stops_at_first_error() {
+        let header = just::<_, &str, extra::Err<Simple<char>>>("v1\n");
+        let record = text::int(10).then_ignore(just('\n'));
+
+        let (_, records) = header
+            .parse_stream("v1\n1\n22\nx\n3\n", record)
+            .into_result()
+            .unwrap();
+        let records = records.collect::<Vec<_>>();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[..2], [Ok("1"), Ok("22")]);
+        assert!(records[2].is_err());
+
+        let res = header.parse_stream("v2\n1\n", record);
+        assert!(!res.has_output());
+        assert_eq!(res.errors().len(), 1);
     }
 
     #[test]
-    fn arc_impl() {
-        fn parser<'a>() -> impl Parser<'a, &'a str, Vec<u64>> {
-            Arc::new(
-                any()
-                    .filter(|c: &char| c.is_ascii_digit())
-                    .repeated()
-                    .at_least(1)
-                    .at_most(3)
-                    .map_slice(|b: &str| b.parse::<u64>().unwrap())
-                    .padded()
-                    .separated_by(just(',').padded())
-                    .allow_trailing()
-                    .collect()
-                    .delimited_by(just('['), just(']')),
-            )
-        }
+    fn not_just_reports_whole_sequence() {
+        let body = not_just::<_, &str, extra::Err<Rich<char>>>("-->");
+        assert_eq!(body.parse("-").into_result(), Ok('-'));
+        assert_eq!(
+            body.repeated()
+                .slice()
+                .then_ignore(just("-->"))
+                .parse("a--b-->")
+                .into_result(),
+            Ok("a--b"),
+        );
+
+        let errs = body.parse("-->").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::from(0..3));
+        assert_eq!(errs[0].found(), Some(&'-'));
+    }
 
+    #[test]
+    fn trivia_skipped_unless_significant() {
+        let spanned = any::<&str, extra::Default>()
+            .map_with(|c, e| (c, e.span()))
+            .repeated()
+            .collect::<Vec<_>>()
+            .then_ignore(end())
+            .with_trivia(|c: &char| *c == ' ');
         assert_eq!(
-            parser().parse("[122 , 23,43,    4, ]").into_result(),
-            Ok(vec![122, 23, 43, 4]),
+            spanned.parse(" a  b ").into_result(),
+            Ok(vec![
+                ('a', SimpleSpan::from(1..2)),
+                ('b', SimpleSpan::from(4..5))
+            ]),
         );
+
+        let spaced = just::<_, &str, extra::Default>('a')
+            .then_ignore(significant(just(' ')))
+            .then(just('b'))
+            .then_ignore(end())
+            .with_trivia(|c: &char| *c == ' ');
+        assert_eq!(spaced.parse("a   b  ").into_result(), Ok(('a', 'b')));
+        assert!(spaced.parse("ab").has_errors());
+    }
+
+    #[test]
+    fn parse_retry_keeps_both_errors() {
+        let digits = text::digits::<_, _, extra::Err<Simple<char>>>(10).slice();
+
+        let res = digits.parse_retry_with("\u{FEFF}12x", |src| src.strip_prefix('\u{FEFF}'));
+        let (first, retry) = res.into_parts();
+        let retry = retry.unwrap();
         assert_eq!(
-            parser().parse("[0, 3, 6, 900,120]").into_result(),
-            Ok(vec![0, 3, 6, 900, 120]),
+            first.errors().next().unwrap().span(),
+            &SimpleSpan::new(0, 3)
         );
         assert_eq!(
-            parser().parse("[200,400,50  ,0,0, ]").into_result(),
-            Ok(vec![200, 400, 50, 0, 0]),
+            retry.errors().next().unwrap().span(),
+            &SimpleSpan::new(2, 3)
         );
+
+        // The transform may decline to retry
+        let res = digits.parse_retry_with("x", |src| src.strip_prefix('\u{FEFF}'));
+        assert!(!res.was_retried());
+        assert!(res.into_last().has_errors());
     }
 
     #[test]
-    fn box_impl() {
-        fn parser<'a>() -> impl Parser<'a, &'a str, Vec<u64>> {
-            Box::new(
-                any()
-                    .filter(|c: &char| c.is_ascii_digit())
-                    .repeated()
-                    .at_least(1)
-                    .at_most(3)
-                    .map_slice(|b: &str| b.parse::<u64>().unwrap())
-                    .padded()
-                    .separated_by(just(',').padded())
-                    .allow_trailing()
-                    .collect()
-                    .delimited_by(just('['), just(']')),
-            )
+    fn positional_aliases_match_then() {
+        let key = text::ascii::ident::<_, _, extra::Err<Rich<char>>>();
+        let value = text::int(10);
+
+        let alias = key
+            .separated_pair(just('=').padded(), value)
+            .preceded(just('$'))
+            .terminated(just(';'));
+        let expanded = just('$')
+            .ignore_then(key.then_ignore(just('=').padded()).then(value))
+            .then_ignore(just(';'));
+
+        assert_eq!(alias.parse("$a = 1;").into_result(), Ok(("a", "1")));
+        for input in ["$a = 1", "a = 1;", "$a 1;", "$a = x;"] {
+            assert_eq!(
+                alias.parse(input).into_errors(),
+                expanded.parse(input).into_errors(),
+                "{input:?}",
+            );
+        }
+    }
+
+    #[test]
+    fn just_bytes_errors_match_just() {
+        let fast = just_bytes::<&[u8], extra::Err<Rich<u8>>, 4>(b"\x7fELF");
+        let slow = just::<_, &[u8], extra::Err<Rich<u8>>>(b"\x7fELF");
+
+        assert_eq!(fast.parse(b"\x7fELF").into_result(), Ok(*b"\x7fELF"));
+        for input in [b"".as_slice(), b"\x7fEL", b"MZ", b"\x7fELX"] {
+            assert_eq!(
+                fast.parse(input).into_errors(),
+                slow.parse(input).into_errors(),
+                "{input:?}",
+            );
+        }
+    }
+
+    #[test]
+    fn iterate_with_stack_machine() {
+        use core::ops::ControlFlow;
+
+        #[derive(Debug, PartialEq)]
+        enum Tree {
+            Leaf(char),
+            Group(Vec<Tree>),
         }
 
+        // Build nested groups with an explicit stack instead of recursion, stopping at an unmatched `)`
+        let tree = any::<_, extra::Default>()
+            .filter(|c: &char| !c.is_whitespace())
+            .padded()
+            .iterate_with(
+                || vec![Vec::new()],
+                |mut stack, c, _| {
+                    match c {
+                        '(' => stack.push(Vec::new()),
+                        ')' if stack.len() == 1 => return ControlFlow::Break(stack),
+                        ')' => {
+                            let group = stack.pop().unwrap();
+                            stack.last_mut().unwrap().push(Tree::Group(group));
+                        }
+                        c => stack.last_mut().unwrap().push(Tree::Leaf(c)),
+                    }
+                    ControlFlow::Continue(stack)
+                },
+            )
+            .map(|mut stack| stack.swap_remove(0));
+
         assert_eq!(
-            parser().parse("[122 , 23,43,    4, ]").into_result(),
-            Ok(vec![122, 23, 43, 4]),
+            tree.parse("a (b c) (d (e))").into_result(),
+            Ok(vec![
+                Tree::Leaf('a'),
+                Tree::Group(vec![Tree::Leaf('b'), Tree::Leaf('c')]),
+                Tree::Group(vec![Tree::Leaf('d'), Tree::Group(vec![Tree::Leaf('e')])]),
+            ]),
         );
         assert_eq!(
-            parser().parse("[0, 3, 6, 900,120]").into_result(),
-            Ok(vec![0, 3, 6, 900, 120]),
+            tree.then_ignore(any().repeated())
+                .parse("a b ) c")
+                .into_result(),
+            Ok(vec![Tree::Leaf('a'), Tree::Leaf('b')]),
+        );
+
+        // Spans are available to the function
+        let spans = any::<&str, extra::Default>().iterate_with(Vec::new, |mut spans, _, e| {
+            spans.push(e.span().into_range());
+            ControlFlow::Continue(spans)
+        });
+        assert_eq!(spans.parse("ab").into_result(), Ok(vec![0..1, 1..2]));
+    }
+
+    #[test]
+    fn choice_all_errors() {
+        let digit = |c| just::<_, _, extra::Err<Rich<char>>>(c).then(just('!'));
+        let errs = choice([digit('1'), digit('2'), digit('3')])
+            .all_errors()
+            .parse("2?")
+            .into_errors();
+        assert_eq!(errs.len(), 1);
+        // The second alternative got furthest, and the others failed at the start
+        assert_eq!(errs[0].span(), &SimpleSpan::new(1, 2));
+        assert_eq!(
+            errs[0].branches().map(|b| b.span()).collect::<Vec<_>>(),
+            [&SimpleSpan::new(0, 1), &SimpleSpan::new(0, 1)]
         );
+
+        // When an alternative succeeds, the errors of the others are merged as usual
+        let errs = choice((just::<_, _, extra::Err<Rich<char>>>('a'), just('b')))
+            .all_errors()
+            .then(just('c'))
+            .or(just('a').then(just('d')))
+            .parse("b")
+            .into_errors();
+        assert_eq!(errs[0].branches().len(), 0);
+
+        // The error left behind by the successful alternative is kept
+        let errs = choice((
+            just::<_, _, extra::Err<Rich<char>>>('x').ignored(),
+            just('1').repeated().at_least(1),
+        ))
+        .all_errors()
+        .then(just(';'))
+        .parse("11,")
+        .into_errors();
+        assert_eq!(errs[0].span(), &SimpleSpan::new(2, 3));
         assert_eq!(
-            parser().parse("[200,400,50  ,0,0, ]").into_result(),
-            Ok(vec![200, 400, 50, 0, 0]),
+            errs[0]
+                .expected()
+                .map(|pat| pat.to_string())
+                .collect::<Vec<_>>(),
+            ["'1'", "';'"]
         );
     }
 
     #[test]
-    fn rc_impl() {
-        fn parser<'a>() -> impl Parser<'a, &'a str, Vec<u64>> {
-            Rc::new(
-                any()
-                    .filter(|c: &char| c.is_ascii_digit())
+    #[cfg(feature = "mmap")]
+    fn mapped_file_input() {
+        use crate::input::MappedFile;
+
+        let path = std::env::temp_dir().join("chumsky_mapped_file_input.bin");
+        std::fs::write(&path, b"ab\xFF").unwrap();
+        // SAFETY: Nothing else writes to the file
+        let file = unsafe { MappedFile::open(&path) }.unwrap();
+        assert_eq!(file.path(), path.as_path());
+        assert!(file.text().is_err());
+
+        let errs = just::<_, _, extra::Err<Simple<u8, SimpleSpan<usize, _>>>>(b"abc")
+            .parse(file.bytes())
+            .into_errors();
+        assert_eq!(errs[0].span().context(), path.as_path());
+        assert_eq!((errs[0].span().start, errs[0].span().end), (2, 3));
+
+        // SAFETY: The file does not exist
+        assert!(unsafe { MappedFile::open(path.join("missing")) }.is_err());
+    }
+
+    #[test]
+    fn utf8_bytes_input() {
+        use crate::input::Utf8Bytes;
+
+        let text = none_of::<_, _, extra::Err<Rich<char>>>(char::REPLACEMENT_CHARACTER)
+            .repeated()
+            .collect::<String>();
+        assert_eq!(
+            text.parse(Utf8Bytes::new("a\u{e9}\u{20ac}\u{1f600}".as_bytes()))
+                .into_result(),
+            Ok("a\u{e9}\u{20ac}\u{1f600}".to_string())
+        );
+
+        // A lone continuation byte, a truncated sequence, an overlong encoding, and a sequence cut short by the end
+        for (src, span) in [
+            (&b"ab\x80c"[..], 2..3),
+            (b"\xE2\x82x", 0..2),
+            (b"x\xC0\xAF", 1..2),
+            (b"xy\xF0\x9F\x98", 2..5),
+        ] {
+            let errs = text.parse(Utf8Bytes::new(src)).into_errors();
+            assert_eq!(errs.len(), 1);
+            assert_eq!(errs[0].span(), &SimpleSpan::from(span));
+        }
+
+        // Slices are bytes, and can span invalid sequences, each of which is still reported
+        let (out, errs) = any::<_, extra::Err<Rich<char>>>()
+            .repeated()
+            .slice()
+            .parse(Utf8Bytes::new(b"a\xFFb\xC0\xAF\xEF\xBF\xBD"))
+            .into_output_errors();
+        assert_eq!(out, Some(&b"a\xFFb\xC0\xAF\xEF\xBF\xBD"[..]));
+        let spans = errs.iter().map(|err| err.span().into_range());
+        assert_eq!(spans.collect::<Vec<_>>(), [1..2, 3..4, 4..5]);
+
+        // Invalid sequences that are only looked at, or read by a branch that is backtracked, are reported once
+        let (_, errs) = any::<_, extra::Err<Rich<char>>>()
+            .rewind()
+            .ignore_then(just('a').or(any()))
+            .repeated()
+            .parse(Utf8Bytes::new(b"\xFF"))
+            .into_output_errors();
+        assert_eq!(errs.len(), 1);
+    }
+
+    #[test]
+    fn delimited_recover_skips_nested_blocks() {
+        let list = text::int::<_, _, extra::Err<Simple<char>>>(10)
+            .separated_by(just(','))
+            .collect::<Vec<_>>()
+            .delimited_recover('[', ']', |span: SimpleSpan| {
+                vec!["?"; span.end - span.start]
+            });
+
+        assert_eq!(list.parse("[1,2]").into_result(), Ok(vec!["1", "2"]));
+
+        // Nested brackets are skipped over as a whole
+        let res = list.parse("[1,[2],3]");
+        assert_eq!(res.output(), Some(&vec!["?"; 9]));
+        assert_eq!(res.errors().count(), 1);
+        assert_eq!(res.recovery().recoveries(), 1);
+        assert_eq!(res.recovery().skipped_tokens(), 8);
+        assert!(list.check("[1,[2],3]").has_errors());
+
+        // Without an opening delimiter, or with no matching closing delimiter, there's nothing to recover
+        assert!(!list.parse("1,2]").has_output());
+        assert!(!list.parse("[1,[2]").has_output());
+    }
+
+    #[test]
+    fn recover_via_insertion_inserts_missing_token() {
+        let item = text::int::<_, _, extra::Err<Simple<char>>>(10).padded();
+        let list = item
+            .then(
+                just(',')
+                    .ignore_then(item)
+                    .recover_via_insertion(',')
                     .repeated()
-                    .at_least(1)
-                    .at_most(3)
-                    .map_slice(|b: &str| b.parse::<u64>().unwrap())
-                    .padded()
-                    .separated_by(just(',').padded())
-                    .allow_trailing()
-                    .collect()
-                    .delimited_by(just('['), just(']')),
+                    .collect::<Vec<_>>(),
             )
-        }
+            .delimited_by(just('['), just(']').recover_via_insertion(']'));
+
+        assert_eq!(list.parse("[1,2]").into_result(), Ok(("1", vec!["2"])));
+
+        let res = list.parse("[1,2 3");
+        assert_eq!(res.output(), Some(&("1", vec!["2", "3"])));
+        assert_eq!(res.errors().count(), 2);
+        assert_eq!(res.recovery().recoveries(), 2);
+        assert_eq!(res.recovery().skipped_tokens(), 0);
+
+        // Inserting the token doesn't help, so the original error remains
+        let ab = just::<_, _, extra::Err<Simple<char>>>('a')
+            .then(just('b'))
+            .recover_via_insertion('a');
+        let errs = ab.parse("c").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(0, 1));
+    }
+
+    #[test]
+    fn incomplete_input() {
+        let expr = recursive(|expr| {
+            let atom = text::int::<_, _, extra::Err<Rich<char>>>(10)
+                .slice()
+                .or(expr.delimited_by(just('('), just(')')));
+            choice((
+                atom.clone()
+                    .then_ignore(just('+').padded())
+                    .then(atom.clone())
+                    .slice(),
+                atom,
+            ))
+        })
+        .then_ignore(end());
+
+        assert!(expr.parse("(1 +").is_incomplete());
+        assert!(expr.parse("(1 + 2").is_incomplete());
+        assert!(expr.parse("((1)").is_incomplete());
+        assert!(!expr.parse("(1 + 2)").is_incomplete());
+        assert!(!expr.parse("(1 +)").is_incomplete());
+        assert!(!expr.parse("1 )").is_incomplete());
+        assert!(!expr.check("(1 +)").is_incomplete());
+        assert!(expr.check("(1 +").is_incomplete());
+    }
 
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn parse_parallel_matches_sequential() {
+        use crate::parallel::parse_parallel;
+
+        let item = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .separated_by(just(','))
+            .collect::<Vec<_>>();
+        let delimiter = just(';');
+
+        let input = (0..1000)
+            .map(|i| {
+                if i % 3 == 0 {
+                    format!("{i},{i}")
+                } else {
+                    i.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(";");
         assert_eq!(
-            parser().parse("[122 , 23,43,    4, ]").into_result(),
-            Ok(vec![122, 23, 43, 4]),
+            parse_parallel(&item, &delimiter, input.as_str()).into_result(),
+            item.separated_by(delimiter)
+                .collect::<Vec<_>>()
+                .parse(input.as_str())
+                .into_result(),
         );
+
+        // Items are parsed as if the input ended at the end of the item, so greedy parsers stop there
+        let rest = any::<_, extra::Err<Rich<char>>>()
+            .repeated()
+            .collect::<String>();
         assert_eq!(
-            parser().parse("[0, 3, 6, 900,120]").into_result(),
-            Ok(vec![0, 3, 6, 900, 120]),
+            parse_parallel(&rest, &delimiter, "ab;cd").into_result(),
+            Ok(vec!["ab".to_string(), "cd".to_string()]),
         );
-        assert_eq!(
-            parser().parse("[200,400,50  ,0,0, ]").into_result(),
-            Ok(vec![200, 400, 50, 0, 0]),
+
+        // An item that stops short of its end is an error, reported at the first token it did not consume
+        let a = just::<_, _, extra::Err<Rich<char>>>('a');
+        let errs = parse_parallel(&a, &delimiter, "ab;a").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(1, 2));
+    }
+
+    #[test]
+    fn collect_with_capacity_hint() {
+        let items = any::<_, extra::Default>().repeated();
+
+        let constant = items.collect_with_capacity_hint::<Vec<_>, _>(64);
+        let out = constant.parse("abc").into_result().unwrap();
+        assert_eq!(out, ['a', 'b', 'c']);
+        assert!(out.capacity() >= 64);
+
+        // The hint is computed from the input remaining when the collection starts
+        let computed = just('.').ignore_then(
+            items.collect_with_capacity_hint::<String, _>(|rest: &str| rest.len() * 10),
         );
+        let out = computed.parse(".abc").into_result().unwrap();
+        assert_eq!(out, "abc");
+        assert!(out.capacity() >= 30);
     }
 
-    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-    struct MyErr(&'static str);
+    #[test]
+    fn reparse_buffer_token_stream() {
+        use crate::push::{ReparseBuffer, Step};
 
-    impl<'a, I> Error<'a, I> for MyErr
-    where
-        I: Input<'a>,
-    {
-        fn expected_found<E: IntoIterator<Item = Option<MaybeRef<'a, I::Token>>>>(
-            _expected: E,
-            _found: Option<MaybeRef<'a, I::Token>>,
-            _span: I::Span,
-        ) -> Self {
-            MyErr("expected found")
+        fn message<'a>() -> impl Parser<'a, &'a [u8], Vec<u8>> {
+            none_of(b'>')
+                .repeated()
+                .collect()
+                .delimited_by(just(b'<'), just(b'>'))
         }
 
-        fn merge(self, other: Self) -> Self {
-            if self == MyErr("special") || other == MyErr("special") {
-                MyErr("special")
-            } else {
-                self
-            }
-        }
+        let mut conn = ReparseBuffer::tokens();
+        conn.extend(*b"<ab");
+        assert!(matches!(conn.parse(&message()), Step::Incomplete));
+
+        conn.extend(*b"c><d");
+        let Step::Done { result, consumed } = conn.parse(&message()) else {
+            panic!("message is complete");
+        };
+        assert_eq!(result.into_result(), Ok(b"abc".to_vec()));
+        assert_eq!(consumed, 5);
+
+        conn.consume(consumed);
+        assert_eq!(conn.as_slice(), b"<d");
+        assert!(matches!(conn.parse(&message()), Step::Incomplete));
+
+        // Errors before the end of the input are reported rather than waiting for more input
+        conn.consume(2);
+        conn.extend(*b"x>");
+        let Step::Done { result, consumed } = conn.parse(&message()) else {
+            panic!("message is invalid");
+        };
+        assert!(result.has_errors());
+        assert_eq!(consumed, 0);
     }
 
     #[test]
-    fn err_prio_0() {
-        #[allow(dead_code)]
-        fn always_err<'a>() -> impl Parser<'a, &'a str, (), extra::Err<MyErr>> {
-            empty().try_map(|_, _| Err(MyErr("special")))
-        }
+    fn parse_expecting_at_records_discarded_errors() {
+        let ab = just::<_, _, extra::Err<Rich<char>>>('a')
+            .or(just('b'))
+            .repeated()
+            .then_ignore(just(';'));
+
+        let expected = |input, offset| {
+            ab.parse_expecting_at(input, offset)
+                .map(|err| err.expected().cloned().collect::<Vec<_>>())
+        };
+        let tok = |c: char| error::RichPattern::Token(c.into());
+
+        // The input parses successfully, but the alternatives that failed at each offset are still reported
+        assert_eq!(expected("ab;", 1), Some(vec![tok('a')]));
+        assert_eq!(expected("ab;", 2), Some(vec![tok('a'), tok('b')]));
+        // Nothing failed at the end of the input, so there is nothing to report
+        assert_eq!(expected("ab;", 3), None);
+        assert_eq!(expected("ab", 2), Some(vec![tok('a'), tok('b'), tok(';')]));
+        // The parser fails before reaching the offset
+        assert_eq!(ab.parse_expecting_at("x;ab", 3), None);
+    }
 
-        assert_eq!(
-            always_err().parse("test").into_result().unwrap_err(),
-            vec![MyErr("special")]
-        )
+    #[test]
+    fn parse_expecting_at_spanned_offsets() {
+        let tokens = [('a', SimpleSpan::new(0, 1)), ('c', SimpleSpan::new(4, 5))];
+        let ab = just::<_, _, extra::Err<Rich<char, SimpleSpan>>>('a').then(just('b'));
+
+        // Offsets index tokens rather than the source positions of their spans
+        let err = ab.parse_expecting_at(tokens.spanned(SimpleSpan::new(5, 5)), 1);
+        assert_eq!(err.map(|err| *err.span()), Some(SimpleSpan::new(4, 5)));
+        assert!(ab
+            .parse_expecting_at(tokens.spanned(SimpleSpan::new(5, 5)), 4)
+            .is_none());
     }
 
     #[test]
-    fn err_prio_1() {
-        #[allow(dead_code)]
-        fn always_err_choice<'a>() -> impl Parser<'a, &'a str, (), extra::Err<MyErr>> {
-            choice((just("something").ignored(), empty())).try_map(|_, _| Err(MyErr("special")))
+    fn spanned_errors_at_eoi() {
+        // An EOF token produced by the lexer, after some trailing whitespace
+        let eoi = SimpleSpan::new(10, 13);
+        let tokens = [('a', SimpleSpan::new(0, 1)), ('b', SimpleSpan::new(2, 3))];
+
+        let abc = just::<_, _, extra::Err<Rich<char>>>('a')
+            .then(just('b'))
+            .then(just('c'));
+        let errs = abc.parse(tokens.spanned(eoi)).into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &eoi);
+
+        let a = just::<_, _, extra::Err<Rich<char>>>('a').then_ignore(end());
+        let errs = a.parse(tokens.spanned(eoi)).into_errors();
+        assert_eq!(errs[0].span(), &SimpleSpan::new(2, 3));
+        assert_eq!(tokens.spanned(eoi).eoi_span(), eoi);
+    }
+
+    #[test]
+    fn sub_grammar_error_conversion() {
+        struct Digits;
+
+        impl<'a> SubGrammar<'a, &'a str, extra::Err<Simple<'a, char>>> for Digits {
+            type Output = &'a str;
+
+            fn parser() -> DynParser<'a, &'a str, &'a str, extra::Err<Simple<'a, char>>> {
+                Parser::boxed(text::digits(10).slice())
+            }
         }
 
-        assert_eq!(
-            always_err_choice().parse("test").into_result().unwrap_err(),
-            vec![MyErr("special")]
-        )
+        let plain = Digits::parser();
+        assert_eq!(plain.parse("123").into_result(), Ok("123"));
+
+        let rich = Digits::parser_with::<extra::Err<Rich<char>>>();
+        let errs = rich.parse("x").into_errors();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), &SimpleSpan::new(0, 1));
     }
 
     #[test]
-    fn into_iter_no_error() {
-        fn parser<'a>() -> impl Parser<'a, &'a str, (), extra::Err<MyErr>> {
-            let many_as = just('a')
-                .ignored()
-                .repeated()
-                .at_least(1)
-                .collect::<Vec<_>>();
+    fn empty_seqs() {
+        let nothing = just::<_, _, extra::Default>("").then(just('a'));
+        assert_eq!(nothing.parse("a").into_result(), Ok(("", 'a')));
 
-            many_as.into_iter().collect()
-        }
+        let anything = none_of::<_, _, extra::Default>("");
+        assert_eq!(anything.parse("x").into_result(), Ok('x'));
+    }
 
-        assert_eq!(parser().parse("aaa").into_result().unwrap(), ());
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "empty sequence"]
+    fn empty_one_of_panics() {
+        let _ = one_of::<_, _, extra::Default>("").parse("x");
+    }
+
+    #[test]
+    #[cfg(feature = "graphemes")]
+    fn graphemes_input() {
+        use crate::input::Graphemes;
+
+        // 'é' as a single char, and as 'e' followed by a combining accent
+        let e_acute = one_of::<_, _, extra::Default>(["\u{e9}", "e\u{301}"]);
+        let word = e_acute.or(any()).repeated().at_least(1).slice();
+        assert_eq!(
+            word.parse(Graphemes::new("e\u{301}t\u{e9}")).into_result(),
+            Ok("e\u{301}t\u{e9}")
+        );
+
+        let flags = just::<_, _, extra::Err<Simple<&str>>>("🇳🇿")
+            .to_span()
+            .repeated()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            flags.parse(Graphemes::from("🇳🇿🇳🇿")).into_result(),
+            Ok(vec![SimpleSpan::new(0, 8), SimpleSpan::new(8, 16)])
+        );
+        // The grapheme 'e' with an accent is not the same as the grapheme 'e'
+        assert!(just::<_, _, extra::Default>("e")
+            .parse(Graphemes::new("e\u{301}"))
+            .has_errors());
     }
 }
@@ -0,0 +1,219 @@
+//! A driver for parsing independent items of an input in parallel.
+//!
+//! Many inputs are made up of top-level items that can be parsed without knowledge of one another: records separated
+//! by blank lines, rows of a log file, function definitions, etc. [`parse_parallel`] finds the boundaries between
+//! these items with a (cheap) delimiter parser, then parses the items across several threads, combining the results
+//! in order.
+//!
+//! Items are parsed against the original input, so the spans of outputs and errors are the same as they would be when
+//! parsing the whole input on a single thread.
+//!
+//! Many independent inputs, such as the files of a test suite, can also be parsed in parallel with
+//! [`parse_all_parallel`].
+
+use super::*;
+use core::num::NonZeroUsize;
+
+/// Parse the items of an input in parallel.
+///
+/// The input is first split into items by scanning it for matches of `delimiter`. Items that would be empty (such as
+/// those before a leading delimiter, or after a trailing one) are skipped. Each item is then parsed by `parser` as if
+/// the input ended at the end of the item, so the parser never reads past it. The parser must consume the whole item:
+/// if it stops short of the end of the item, an error is produced as if by [`end`].
+///
+/// Items are divided evenly between as many threads as there are available CPUs. Each item is parsed with a fresh
+/// default state and context.
+///
+/// The outputs of the items are returned in the order that the items appear in the input. If any item fails to
+/// produce an output, the result has no output. Errors are ordered first by item and then by position within the
+/// item, which is the same order that [`Parser::parse`] would produce.
+///
+/// Finding the boundaries between items happens on the calling thread, so the delimiter should be simple and
+/// unambiguous. In particular, it must not match anywhere *inside* an item, since the item would then be split in
+/// two.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, parallel::parse_parallel};
+/// let record = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+///     .then_ignore(just('='))
+///     .then(text::int(10).from_str::<u32>().unwrapped());
+/// let blank_line = text::newline().repeated().at_least(2);
+///
+/// assert_eq!(
+///     parse_parallel(&record, &blank_line, "a=1\n\nb=2\n\n\nc=3\n\n").into_result(),
+///     Ok(vec![("a", 1), ("b", 2), ("c", 3)]),
+/// );
+///
+/// // Errors in one item do not prevent the others from being parsed
+/// let errs = parse_parallel(&record, &blank_line, "a=1\n\nb=x\n\nc=y").into_errors();
+/// assert_eq!(errs.iter().map(|e| e.span().start).collect::<Vec<_>>(), [7, 12]);
+/// ```
+pub fn parse_parallel<'a, I, O, E, P, D, DO>(
+    parser: &P,
+    delimiter: &D,
+    input: I,
+) -> ParseResult<Vec<O>, E::Error>
+where
+    I: Input<'a> + Clone + Send,
+    I::Offset: Sync,
+    E: ParserExtra<'a, I>,
+    E::Error: Send,
+    E::State: Default,
+    E::Context: Default,
+    O: Send,
+    P: Parser<'a, I, O, E> + Sync,
+    D: Parser<'a, I, DO, E>,
+{
+    let items = split(delimiter, input.clone());
+
+    let threads = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let per_thread = items.len().saturating_sub(1) / threads + 1;
+    let results = std::thread::scope(|scope| {
+        items
+            .chunks(per_thread)
+            .map(|group| {
+                let input = input.clone();
+                scope.spawn(move || {
+                    group
+                        .iter()
+                        .map(|&(start, end)| parse_item(parser, input.clone(), start..end))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let mut outputs = Some(Vec::with_capacity(results.len()));
+    let mut errs = Vec::new();
+    let mut recovery = RecoveryStats::default();
+    for (out, item_errs, item_recovery) in results {
+        match (&mut outputs, out) {
+            (Some(outputs), Some(out)) => outputs.push(out),
+            _ => outputs = None,
+        }
+        errs.extend(item_errs);
+        recovery.recoveries += item_recovery.recoveries;
+        recovery.skipped += item_recovery.skipped;
+    }
+    ParseResult::new(outputs, errs).with_recovery(recovery)
+}
+
+/// Parse many independent inputs in parallel, yielding a [`ParseResult`] for each of them, in order.
+///
+/// This is the parallel equivalent of [`Parser::parse_all`]: the inputs are divided evenly between as many threads as
+/// there are available CPUs, and each thread parses its share of the inputs with [`Parser::parse_all`], reusing its
+/// buffers from one input to the next. Each input is parsed with a fresh default state and context.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, parallel::parse_all_parallel};
+/// let int = text::int::<_, _, extra::Err<Simple<char>>>(10).from_str::<u32>().unwrapped();
+///
+/// let files = ["1", "2", "x", "4"];
+/// let results = parse_all_parallel(&int, files);
+/// assert_eq!(results.len(), 4);
+/// assert_eq!(results[1].output(), Some(&2));
+/// assert!(results[2].has_errors());
+/// ```
+pub fn parse_all_parallel<'a, I, O, E, P, It>(
+    parser: &P,
+    inputs: It,
+) -> Vec<ParseResult<O, E::Error>>
+where
+    I: Input<'a> + Send,
+    E: ParserExtra<'a, I>,
+    E::Error: Send,
+    E::State: Default,
+    E::Context: Default,
+    O: Send,
+    P: Parser<'a, I, O, E> + Sync,
+    It: IntoIterator<Item = I>,
+{
+    let mut inputs = inputs.into_iter().collect::<Vec<_>>();
+
+    let threads = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let per_thread = inputs.len().saturating_sub(1) / threads + 1;
+    let mut groups = Vec::new();
+    while !inputs.is_empty() {
+        let rest = inputs.split_off(per_thread.min(inputs.len()));
+        groups.push(core::mem::replace(&mut inputs, rest));
+    }
+
+    std::thread::scope(|scope| {
+        groups
+            .into_iter()
+            .map(|group| scope.spawn(move || parser.parse_all(group)))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    })
+}
+
+/// Find the (non-empty) items of the input that lie between matches of `delimiter`.
+fn split<'a, I, E, D, DO>(delimiter: &D, input: I) -> Vec<(I::Offset, I::Offset)>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: Default,
+    E::Context: Default,
+    D: Parser<'a, I, DO, E>,
+{
+    let mut own = InputOwn::<I, E>::new(input);
+    let mut inp = own.as_ref_start();
+    let mut items = Vec::new();
+    let mut start = inp.offset;
+    loop {
+        let before = inp.save();
+        let res = delimiter.go::<Check>(&mut inp);
+        // Failing to find a delimiter is not an error
+        inp.errors.alt = None;
+        if res.is_ok() && inp.offset > before.offset {
+            items.push((start, before.offset));
+            start = inp.offset;
+            continue;
+        }
+        inp.rewind(before);
+        if inp.next_maybe_inner().1.is_none() {
+            items.push((start, inp.offset));
+            break;
+        }
+    }
+    items.retain(|(start, end)| start < end);
+    items
+}
+
+/// Parse a single item, which must span exactly the given range of the input.
+fn parse_item<'a, I, O, E, P>(
+    parser: &P,
+    input: I,
+    item: Range<I::Offset>,
+) -> (Option<O>, Vec<E::Error>, RecoveryStats)
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: Default,
+    E::Context: Default,
+    P: Parser<'a, I, O, E>,
+{
+    let mut own = InputOwn::<I, E>::new(input);
+    let out = own.drive_within::<Emit, _, _, _>(item, &parser.then_ignore(end()), |inp, res| {
+        res.map_err(|err| inp.errors.secondary.push(err)).ok()
+    });
+    let recovery = own.errors.recovery_stats();
+    (out, own.take_errs(), recovery)
+}
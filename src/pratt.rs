@@ -6,18 +6,28 @@
 //!
 //! The [`Parser::pratt`] method creates a [`Pratt`] parser. See the
 //! method's documentation for an example of how it can be used.
+//!
+//! For operators that are only known at runtime, such as those declared
+//! by the input being parsed, [`Parser::pratt_dynamic`] instead looks
+//! up operators in an [`OpTable`] held by the parser's state.
 
 mod ops;
+mod table;
 use ops::Strength;
 pub use ops::{InfixOp, PostfixOp, PrefixOp};
+pub use table::{OpTable, Operator};
 
-use core::cmp::{self, Ordering};
+use core::{
+    borrow::Borrow,
+    cmp::{self, Ordering},
+};
 
 use crate::{
     extra::ParserExtra,
     input::InputRef,
     prelude::Input,
     private::{Check, Emit, Mode, PResult, ParserSealed},
+    walk::Walker,
     EmptyPhantom, Parser,
 };
 
@@ -527,6 +537,10 @@ macro_rules! impl_parse {
                 self.pratt_parse::<M>(inp, None)
             }
 
+            fn walk_node(&self, walker: &mut Walker<'_>) {
+                walker.node::<Self>(|walker| self.atom.walk_node(walker));
+            }
+
             go_extra!(O);
         }
     };
@@ -540,11 +554,133 @@ impl_parse!(InfixPostfix<InfixOps, InfixOpsOut, PostfixOps, PostfixOpsOut>);
 
 impl_parse!(InfixPrefixPostfix<InfixOps, InfixOpsOut, PrefixOps, PrefixOpsOut, PostfixOps, PostfixOpsOut,>);
 
+/// A pratt parser whose operators are looked up in an [`OpTable`] held by the parser's state, allowing them to be
+/// defined at runtime.
+///
+/// See [`Parser::pratt_dynamic`].
+pub struct DynamicPratt<Atom, Op, K> {
+    pub(crate) atom: Atom,
+    pub(crate) op: Op,
+    pub(crate) _phantom: EmptyPhantom<K>,
+}
+
+impl<Atom: Copy, Op: Copy, K> Copy for DynamicPratt<Atom, Op, K> {}
+impl<Atom: Clone, Op: Clone, K> Clone for DynamicPratt<Atom, Op, K> {
+    fn clone(&self) -> Self {
+        Self {
+            atom: self.atom.clone(),
+            op: self.op.clone(),
+            _phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<Atom, Op, K> DynamicPratt<Atom, Op, K> {
+    fn pratt_parse<'a, I, O, E, M>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+        min_strength: Option<Strength>,
+    ) -> PResult<M, O>
+    where
+        I: Input<'a>,
+        E: ParserExtra<'a, I>,
+        E::State: Borrow<OpTable<K, O>>,
+        Atom: Parser<'a, I, O, E>,
+        Op: Parser<'a, I, K, E>,
+        K: PartialEq,
+        M: Mode,
+    {
+        let pre_op = inp.save();
+        let prefix = match self.op.go::<Emit>(inp) {
+            Ok(key) => table(inp).prefix(&key),
+            Err(()) => None,
+        };
+        let mut left = match prefix {
+            Some((prec, build)) => {
+                let right = self.pratt_parse::<I, O, E, M>(inp, Some(prec.strength_right()))?;
+                M::map(right, |right| build(right))
+            }
+            None => {
+                inp.rewind(pre_op);
+                self.atom.go::<M>(inp)?
+            }
+        };
+
+        loop {
+            let pre_op = inp.save();
+            let key = match self.op.go::<Emit>(inp) {
+                Ok(key) => key,
+                Err(()) => {
+                    inp.rewind(pre_op);
+                    return Ok(left);
+                }
+            };
+
+            if let Some((prec, build)) = table(inp).postfix(&key) {
+                if prec.strength_left().is_lt(&min_strength) {
+                    inp.rewind(pre_op);
+                    return Ok(left);
+                }
+                left = M::map(left, |left| build(left));
+                continue;
+            }
+
+            let (prec, build) = match table(inp).infix(&key) {
+                Some((prec, _)) if prec.strength_left().is_lt(&min_strength) => {
+                    inp.rewind(pre_op);
+                    return Ok(left);
+                }
+                Some(op) => op,
+                // The operator is not known (or is only known in prefix position)
+                None => {
+                    inp.rewind(pre_op);
+                    return Ok(left);
+                }
+            };
+
+            let right = self.pratt_parse::<I, O, E, M>(inp, Some(prec.strength_right()))?;
+            left = M::combine(left, right, |left, right| build(left, right));
+        }
+    }
+}
+
+fn table<'a, 'b, I, O, E, K>(inp: &'b mut InputRef<'a, '_, I, E>) -> &'b OpTable<K, O>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: Borrow<OpTable<K, O>>,
+{
+    (*inp.state()).borrow()
+}
+
+impl<'a, I, O, E, Atom, Op, K> ParserSealed<'a, I, O, E> for DynamicPratt<Atom, Op, K>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: Borrow<OpTable<K, O>>,
+    Atom: Parser<'a, I, O, E>,
+    Op: Parser<'a, I, K, E>,
+    K: PartialEq,
+{
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
+    where
+        Self: Sized,
+    {
+        self.pratt_parse::<I, O, E, M>(inp, None)
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.atom.walk_node(walker));
+    }
+
+    go_extra!(O);
+}
+
 #[cfg(test)]
 mod tests {
     use crate::error::Error;
     use crate::extra::Err;
-    use crate::prelude::{choice, end, just, Simple, SimpleSpan};
+    use crate::prelude::{any, choice, end, just, Simple, SimpleSpan};
     use crate::util::MaybeRef;
     use crate::{text, ParseResult};
 
@@ -764,4 +900,56 @@ mod tests {
             Ok("(((§(1 + (-(~(2!)))))$) * 3)".to_string()),
         )
     }
+
+    #[test]
+    fn with_dynamic_ops() {
+        type Extra = crate::extra::Full<Simple<'static, char>, OpTable<char, Expr>, ()>;
+
+        let parser = text::int::<_, _, Extra>(10)
+            .from_str()
+            .unwrapped()
+            .map(Expr::Literal)
+            .pratt_dynamic(any())
+            .map(|x| x.to_string());
+
+        let mut table = OpTable::new()
+            .with(Operator::left_infix('+', 1, |l, r| {
+                Expr::Add(Box::new(l), Box::new(r))
+            }))
+            .with(Operator::left_infix('-', 1, |l, r| {
+                Expr::Sub(Box::new(l), Box::new(r))
+            }))
+            .with(Operator::right_infix('*', 2, |l, r| {
+                Expr::Mul(Box::new(l), Box::new(r))
+            }))
+            .with(Operator::prefix('-', 4, |rhs| Expr::Negate(Box::new(rhs))))
+            .with(Operator::prefix('~', 4, |rhs| Expr::Not(Box::new(rhs))))
+            .with(Operator::prefix('§', 1, |rhs| {
+                Expr::Confusion(Box::new(rhs))
+            }))
+            .with(Operator::postfix('!', 5, |lhs| {
+                Expr::Factorial(Box::new(lhs))
+            }))
+            .with(Operator::postfix('$', 0, |lhs| Expr::Value(Box::new(lhs))));
+
+        // The same operators as `with_pre_and_postfix_ops`, with `-` in both prefix and infix position
+        assert_eq!(
+            parser
+                .parse_with_state("§1+-~2!$*3", &mut table)
+                .into_result(),
+            Ok("(((§(1 + (-(~(2!)))))$) * 3)".to_string()),
+        );
+        assert!(!parser.check_with_state("1-2*3", &mut table).has_errors());
+
+        // Redeclaring an operator replaces it, while unknown operators end the expression
+        table.insert(Operator::left_infix('-', 3, |l, r| {
+            Expr::Sub(Box::new(l), Box::new(r))
+        }));
+        assert_eq!(table.len(), 8);
+        assert_eq!(
+            parser.parse_with_state("1+2-3", &mut table).into_result(),
+            Ok("(1 + (2 - 3))".to_string()),
+        );
+        assert!(parser.parse_with_state("1/2", &mut table).has_errors());
+    }
 }
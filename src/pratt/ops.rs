@@ -157,6 +157,10 @@ where
         }
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(PrattOpOutput<InfixBuilder<Expr>>);
 }
 
@@ -217,6 +221,10 @@ where
         }
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(PrattOpOutput<PrefixBuilder<Expr>>);
 }
 
@@ -277,6 +285,10 @@ where
         }
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(PrattOpOutput<PostfixBuilder<Expr>>);
 }
 
@@ -0,0 +1,221 @@
+use super::ops::{Assoc, Precedence};
+use crate::sync::{MaybeSync, RefC};
+use alloc::vec::Vec;
+
+#[cfg(not(feature = "sync"))]
+type UnaryFn<O> = dyn Fn(O) -> O;
+#[cfg(feature = "sync")]
+type UnaryFn<O> = dyn Fn(O) -> O + Send + Sync;
+
+#[cfg(not(feature = "sync"))]
+type BinaryFn<O> = dyn Fn(O, O) -> O;
+#[cfg(feature = "sync")]
+type BinaryFn<O> = dyn Fn(O, O) -> O + Send + Sync;
+
+enum Kind<O> {
+    Prefix(RefC<UnaryFn<O>>),
+    Postfix(RefC<UnaryFn<O>>),
+    Infix(Assoc, RefC<BinaryFn<O>>),
+}
+
+impl<O> Clone for Kind<O> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Prefix(build) => Self::Prefix(build.clone()),
+            Self::Postfix(build) => Self::Postfix(build.clone()),
+            Self::Infix(assoc, build) => Self::Infix(*assoc, build.clone()),
+        }
+    }
+}
+
+impl<O> Kind<O> {
+    // Operators in the same position cannot share a key, but (for example) a prefix and an infix operator can
+    fn same_position(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::Prefix(_), Self::Prefix(_))
+                | (Self::Postfix(_), Self::Postfix(_))
+                | (Self::Infix(..), Self::Infix(..))
+        )
+    }
+}
+
+/// An operator in an [`OpTable`], identified by a key such as its symbol.
+///
+/// Unlike [`InfixOp`](super::InfixOp), [`PrefixOp`](super::PrefixOp) and [`PostfixOp`](super::PostfixOp), operators
+/// are built from closures and can be created at runtime, such as when a parser encounters a fixity declaration.
+pub struct Operator<K, O> {
+    key: K,
+    strength: u16,
+    kind: Kind<O>,
+}
+
+impl<K: Clone, O> Clone for Operator<K, O> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key.clone(),
+            strength: self.strength,
+            kind: self.kind.clone(),
+        }
+    }
+}
+
+impl<K, O> Operator<K, O> {
+    /// Creates a left associative infix operator identified by `key`, and a function which is used to `build` its
+    /// output. The operator's precedence is determined by `strength`. The higher the value, the higher the
+    /// precedence.
+    pub fn left_infix<F>(key: K, strength: u16, build: F) -> Self
+    where
+        F: Fn(O, O) -> O + MaybeSync + 'static,
+    {
+        Self {
+            key,
+            strength,
+            kind: Kind::Infix(Assoc::Left, RefC::new(build)),
+        }
+    }
+
+    /// Creates a right associative infix operator identified by `key`, and a function which is used to `build` its
+    /// output. The operator's precedence is determined by `strength`. The higher the value, the higher the
+    /// precedence.
+    pub fn right_infix<F>(key: K, strength: u16, build: F) -> Self
+    where
+        F: Fn(O, O) -> O + MaybeSync + 'static,
+    {
+        Self {
+            key,
+            strength,
+            kind: Kind::Infix(Assoc::Right, RefC::new(build)),
+        }
+    }
+
+    /// Creates a prefix operator (a right-associative unary operator) identified by `key`, and a function which is
+    /// used to `build` its output. The operator's precedence is determined by `strength`. The higher the value, the
+    /// higher the precedence.
+    pub fn prefix<F>(key: K, strength: u16, build: F) -> Self
+    where
+        F: Fn(O) -> O + MaybeSync + 'static,
+    {
+        Self {
+            key,
+            strength,
+            kind: Kind::Prefix(RefC::new(build)),
+        }
+    }
+
+    /// Creates a postfix operator (a left-associative unary operator) identified by `key`, and a function which is
+    /// used to `build` its output. The operator's precedence is determined by `strength`. The higher the value, the
+    /// higher the precedence.
+    pub fn postfix<F>(key: K, strength: u16, build: F) -> Self
+    where
+        F: Fn(O) -> O + MaybeSync + 'static,
+    {
+        Self {
+            key,
+            strength,
+            kind: Kind::Postfix(RefC::new(build)),
+        }
+    }
+
+    /// The key that identifies this operator.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// The strength with which this operator binds.
+    pub fn strength(&self) -> u16 {
+        self.strength
+    }
+}
+
+/// A table of operators that can be extended at runtime, used by
+/// [`Parser::pratt_dynamic`](crate::Parser::pratt_dynamic).
+///
+/// Tables are usually built up front with [`OpTable::with`], and may be extended with [`OpTable::insert`] during
+/// parsing by keeping the table in the parser's state. See
+/// [`Parser::pratt_dynamic`](crate::Parser::pratt_dynamic) for an example.
+pub struct OpTable<K, O> {
+    ops: Vec<Operator<K, O>>,
+}
+
+impl<K, O> Default for OpTable<K, O> {
+    fn default() -> Self {
+        Self { ops: Vec::new() }
+    }
+}
+
+impl<K: Clone, O> Clone for OpTable<K, O> {
+    fn clone(&self) -> Self {
+        Self {
+            ops: self.ops.clone(),
+        }
+    }
+}
+
+impl<K: PartialEq, O> OpTable<K, O> {
+    /// Create a new, empty table of operators.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an operator to this table, returning the table.
+    ///
+    /// See [`OpTable::insert`] for how conflicting operators are handled.
+    pub fn with(mut self, op: Operator<K, O>) -> Self {
+        self.insert(op);
+        self
+    }
+
+    /// Add an operator to this table.
+    ///
+    /// An operator replaces any existing operator with the same key in the same position, so redeclaring the
+    /// fixity of an infix operator changes it. Operators in different positions, such as prefix `-` and infix `-`,
+    /// may share a key.
+    pub fn insert(&mut self, op: Operator<K, O>) {
+        match self
+            .ops
+            .iter_mut()
+            .find(|old| old.key == op.key && old.kind.same_position(&op.kind))
+        {
+            Some(old) => *old = op,
+            None => self.ops.push(op),
+        }
+    }
+
+    /// The number of operators in this table.
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// Whether this table contains no operators.
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    pub(super) fn prefix(&self, key: &K) -> Option<(Precedence, RefC<UnaryFn<O>>)> {
+        self.ops.iter().find_map(|op| match &op.kind {
+            Kind::Prefix(build) if op.key == *key => {
+                Some((Precedence::new(op.strength, Assoc::Right), build.clone()))
+            }
+            _ => None,
+        })
+    }
+
+    pub(super) fn postfix(&self, key: &K) -> Option<(Precedence, RefC<UnaryFn<O>>)> {
+        self.ops.iter().find_map(|op| match &op.kind {
+            Kind::Postfix(build) if op.key == *key => {
+                Some((Precedence::new(op.strength, Assoc::Right), build.clone()))
+            }
+            _ => None,
+        })
+    }
+
+    pub(super) fn infix(&self, key: &K) -> Option<(Precedence, RefC<BinaryFn<O>>)> {
+        self.ops.iter().find_map(|op| match &op.kind {
+            Kind::Infix(assoc, build) if op.key == *key => {
+                Some((Precedence::new(op.strength, *assoc), build.clone()))
+            }
+            _ => None,
+        })
+    }
+}
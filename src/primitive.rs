@@ -10,10 +10,15 @@
 //! ## The Important Ones
 //!
 //! - [`just`]: parses a specific input or sequence of inputs
+//! - [`just_ctx`]: parses the input or sequence of inputs given by the current context
 //! - [`any`]: parses any single input
 //! - [`one_of`]: parses any one of a sequence of inputs
 //! - [`none_of`]: parses any input that does not appear in a sequence of inputs
+//! - [`not_just`]: parses any input, unless a specific sequence of inputs begins there
+//! - [`kind_of`]: parses any input whose [kind](TokenKind) appears in a sequence of kinds
 //! - [`end`]: parses the end of input (i.e: if there any more inputs, this parse fails)
+//! - [`balanced`]: parses a region enclosed by correctly nested delimiters, without parsing its contents
+//! - [`select_window`]: parses a fixed number of inputs, if a function accepts them
 
 use super::*;
 
@@ -45,7 +50,7 @@ where
         match inp.next_maybe_inner() {
             (_, None) => Ok(M::bind(|| ())),
             (at, Some(tok)) => {
-                inp.add_alt(at, None, Some(tok.into()), inp.span_since(before));
+                inp.add_alt(at, None, Some(tok), inp.span_since(before));
                 Err(())
             }
         }
@@ -112,7 +117,7 @@ impl<T> Default for JustCfg<T> {
 
 /// See [`just`].
 pub struct Just<T, I, E = EmptyErr> {
-    seq: T,
+    pub(crate) seq: T,
     #[allow(dead_code)]
     phantom: EmptyPhantom<(E, I)>,
 }
@@ -131,6 +136,10 @@ impl<T: Clone, I, E> Clone for Just<T, I, E> {
 ///
 /// The output type of this parser is `C`, the input or sequence that was provided.
 ///
+/// If the sequence is empty (such as `just("")`), this parser behaves like [`empty`]: it always succeeds without
+/// consuming any input, producing the empty sequence. Repeating such a parser makes no progress, and so will panic
+/// in debug builds and fail in release builds (see [`Parser::repeated`]).
+///
 /// # Examples
 ///
 /// ```
@@ -187,29 +196,239 @@ where
         cfg: Self::Config,
     ) -> PResult<M, T> {
         let seq = cfg.seq.as_ref().unwrap_or(&self.seq);
+        go_just::<M, _, _, _>(inp, seq)
+    }
+
+    go_cfg_extra!(T);
+}
+
+// Match `seq` at the current offset, as `just` and `just_ctx` do.
+#[inline]
+fn go_just<'a, M, I, E, T>(inp: &mut InputRef<'a, '_, I, E>, seq: &T) -> PResult<M, T>
+where
+    M: Mode,
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: PartialEq,
+    T: OrderedSeq<'a, I::Token> + Clone,
+{
+    let start = inp.offset();
+    if let Some(()) = seq.seq_iter().find_map(|next| {
+        let before = inp.offset();
+        match inp.next_maybe_inner() {
+            (_, Some(tok)) if next.borrow() == &*tok => None,
+            // If the input ends part way through the sequence, describe all of it, spanning the partial match
+            (at, None) => {
+                inp.add_alt_err(
+                    at,
+                    E::Error::expected_seq_found(
+                        SeqKind::Literal,
+                        seq.seq_iter().map(T::to_maybe_ref),
+                        Some(Some(T::to_maybe_ref(next))),
+                        None,
+                        inp.span_since(start),
+                    ),
+                );
+                Some(())
+            }
+            // If the first token doesn't match, describe the sequence as a whole (if it has several tokens)
+            (at, found) if before == start && seq.seq_iter().nth(1).is_some() => {
+                inp.add_alt_err(
+                    at,
+                    E::Error::expected_seq_found(
+                        SeqKind::Literal,
+                        seq.seq_iter().map(T::to_maybe_ref),
+                        Some(Some(T::to_maybe_ref(next))),
+                        found,
+                        inp.span_since(before),
+                    ),
+                );
+                Some(())
+            }
+            (at, found) => {
+                inp.add_alt(
+                    at,
+                    Some(Some(T::to_maybe_ref(next))),
+                    found,
+                    inp.span_since(before),
+                );
+                Some(())
+            }
+        }
+    }) {
+        Err(())
+    } else {
+        Ok(M::bind(|| seq.clone()))
+    }
+}
+
+/// See [`just_ctx`].
+pub struct JustCtx<I, E> {
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(E, I)>,
+}
+
+impl<I, E> Copy for JustCtx<I, E> {}
+impl<I, E> Clone for JustCtx<I, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+/// A parser that accepts only the input given by the current context, such as a delimiter chosen by an earlier part
+/// of the input.
+///
+/// This is equivalent to configuring [`just`] with the context (see [`ConfigParser::configure`]), but needs no
+/// placeholder sequence. The context is usually provided by [`Parser::ignore_with_ctx`] or [`Parser::then_with_ctx`].
+///
+/// The output type of this parser is the context type, the input or sequence that was matched.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// // A quoted string whose delimiter is whichever character appears first, as in `sed` expressions
+/// let body = any()
+///     .and_is(just_ctx().not())
+///     .repeated()
+///     .slice()
+///     .then_ignore(just_ctx());
+/// let quoted = any::<_, extra::Err<Simple<char>>>().ignore_with_ctx(body);
+///
+/// assert_eq!(quoted.parse("|a/b|").into_result(), Ok("a/b"));
+/// assert_eq!(quoted.parse("/a|b/").into_result(), Ok("a|b"));
+/// assert!(quoted.parse("/a|b|").has_errors());
+/// ```
+pub const fn just_ctx<'a, I, E>() -> JustCtx<I, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+{
+    JustCtx {
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+impl<'a, I, E> ParserSealed<'a, I, E::Context, E> for JustCtx<I, E>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: PartialEq,
+    E::Context: OrderedSeq<'a, I::Token> + Clone,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, E::Context> {
+        let ctx = inp.ctx;
+        go_just::<M, _, _, _>(inp, ctx)
+    }
+
+    go_extra!(E::Context);
+}
+
+/// See [`just_bytes`].
+pub struct JustBytes<I, E, const N: usize> {
+    bytes: [u8; N],
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(E, I)>,
+}
+
+impl<I, E, const N: usize> Copy for JustBytes<I, E, N> {}
+impl<I, E, const N: usize> Clone for JustBytes<I, E, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+/// A parser that accepts only the given sequence of bytes.
+///
+/// This behaves like [`just`] with a byte string, but the length of the sequence is a const generic, so the
+/// comparison is a loop with a fixed number of iterations that the compiler can unroll. This makes it a good fit for
+/// matching short literals such as HTTP methods or magic numbers in hot binary parsers.
+///
+/// The output type of this parser is `[u8; N]`, the sequence that was provided.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Simple};
+/// let method = choice((
+///     just_bytes::<&[u8], extra::Err<Simple<u8>>, 3>(b"GET"),
+///     just_bytes(b"PUT"),
+/// ));
+///
+/// assert_eq!(method.parse(b"GET").into_result(), Ok(*b"GET"));
+/// assert_eq!(method.parse(b"PUT").into_result(), Ok(*b"PUT"));
+/// assert!(method.parse(b"POST").has_errors());
+/// assert!(method.parse(b"GE").has_errors());
+/// ```
+pub const fn just_bytes<'a, I, E, const N: usize>(bytes: &[u8; N]) -> JustBytes<I, E, N>
+where
+    I: ValueInput<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    JustBytes {
+        bytes: *bytes,
+        phantom: EmptyPhantom::new(),
+    }
+}
 
-        if let Some(()) = seq.seq_iter().find_map(|next| {
+impl<'a, I, E, const N: usize> ParserSealed<'a, I, [u8; N], E> for JustBytes<I, E, N>
+where
+    I: ValueInput<'a, Token = u8>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, [u8; N]> {
+        let start = inp.offset();
+        for (i, next) in self.bytes.iter().enumerate() {
             let before = inp.offset();
-            match inp.next_maybe_inner() {
-                (_, Some(tok)) if next.borrow() == tok.borrow() => None,
-                (at, found) => {
+            match inp.next_inner() {
+                (_, Some(byte)) if byte == *next => {}
+                // Errors are described in the same way as by `just`
+                (at, None) => {
+                    let err_span = inp.span_since(start);
+                    inp.add_alt_err(
+                        at,
+                        E::Error::expected_seq_found(
+                            SeqKind::Literal,
+                            self.bytes.into_iter().map(MaybeRef::Val),
+                            Some(Some(MaybeRef::Val(*next))),
+                            None,
+                            err_span,
+                        ),
+                    );
+                    return Err(());
+                }
+                (at, Some(byte)) if i == 0 && N > 1 => {
+                    let err_span = inp.span_since(before);
+                    inp.add_alt_err(
+                        at,
+                        E::Error::expected_seq_found(
+                            SeqKind::Literal,
+                            self.bytes.into_iter().map(MaybeRef::Val),
+                            Some(Some(MaybeRef::Val(*next))),
+                            Some(MaybeRef::Val(byte)),
+                            err_span,
+                        ),
+                    );
+                    return Err(());
+                }
+                (at, Some(byte)) => {
+                    let err_span = inp.span_since(before);
                     inp.add_alt(
                         at,
-                        Some(Some(T::to_maybe_ref(next))),
-                        found.map(|f| f.into()),
-                        inp.span_since(before),
+                        Some(Some(MaybeRef::Val(*next))),
+                        Some(MaybeRef::Val(byte)),
+                        err_span,
                     );
-                    Some(())
+                    return Err(());
                 }
             }
-        }) {
-            Err(())
-        } else {
-            Ok(M::bind(|| seq.clone()))
         }
+        Ok(M::bind(|| self.bytes))
     }
 
-    go_cfg_extra!(T);
+    go_extra!([u8; N]);
 }
 
 /// See [`one_of`].
@@ -231,6 +450,12 @@ impl<T: Clone, I, E> Clone for OneOf<T, I, E> {
 
 /// A parser that accepts one of a sequence of specific inputs.
 ///
+/// If the sequence is a contiguous range of inputs (such as `'0'..='9'`, or `"abcdef"`), errors will describe it as a
+/// range rather than listing every input within it (see [`Seq::seq_range`]).
+///
+/// If the sequence is empty (such as `one_of("")`), this parser can never succeed. Since this is almost certainly a
+/// mistake, it panics in debug builds. In release builds, it always fails with an error that expects nothing.
+///
 /// The output type of this parser is `I`, the input that was found.
 ///
 /// # Examples
@@ -272,13 +497,137 @@ where
             #[allow(suspicious_double_ref_op)] // Is this a clippy bug?
             (_, Some(tok)) if self.seq.contains(tok.borrow()) => Ok(M::bind(|| tok)),
             (at, found) => {
-                let err_span = inp.span_since(before);
-                inp.add_alt(
-                    at,
-                    self.seq.seq_iter().map(|e| Some(T::to_maybe_ref(e))),
-                    found.map(|f| f.into()),
-                    err_span,
+                debug_assert!(
+                    !self.seq.is_empty(),
+                    "one_of was given an empty sequence, so it can never succeed",
                 );
+                let err_span = inp.span_since(before);
+                let expected = self.seq.seq_iter().map(|e| Some(T::to_maybe_ref(e)));
+                match self.seq.seq_range() {
+                    Some((start, end)) => inp.add_alt_err(
+                        at,
+                        E::Error::expected_range_found(
+                            start,
+                            end,
+                            expected,
+                            found.map(|f| f.into()),
+                            err_span,
+                        ),
+                    ),
+                    None => inp.add_alt(at, expected, found.map(|f| f.into()), err_span),
+                }
+                Err(())
+            }
+        }
+    }
+
+    go_extra!(I::Token);
+}
+
+/// A token that can be classified by its kind, independently of any data that it carries. See [`kind_of`] for an
+/// example implementation.
+pub trait TokenKind {
+    /// The type that describes the kind of a token.
+    type Kind: PartialEq;
+
+    /// Get the kind of this token.
+    fn kind(&self) -> Self::Kind;
+}
+
+/// See [`kind_of`].
+pub struct KindOf<T, I, E> {
+    kinds: T,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(E, I)>,
+}
+
+impl<T: Copy, I, E> Copy for KindOf<T, I, E> {}
+impl<T: Clone, I, E> Clone for KindOf<T, I, E> {
+    fn clone(&self) -> Self {
+        Self {
+            kinds: self.kinds.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+/// A parser that accepts any input whose [kind](TokenKind) is one of a sequence of kinds.
+///
+/// This is like [`one_of`], but compares only the kinds of tokens rather than the tokens themselves. This is useful
+/// when parsing the output of a lexer, where tokens often carry data (such as the value of a literal) that should not
+/// affect whether they are accepted, and comparing whole tokens would be both incorrect and slow.
+///
+/// The output type of this parser is `I`, the input that was found.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Simple, primitive::TokenKind};
+/// #[derive(Clone, Debug, PartialEq)]
+/// enum Token {
+///     Num(u64),
+///     Plus,
+///     Minus,
+///     Star,
+/// }
+///
+/// #[derive(Copy, Clone, Debug, PartialEq)]
+/// enum Kind {
+///     Num,
+///     Plus,
+///     Minus,
+///     Star,
+/// }
+///
+/// impl TokenKind for Token {
+///     type Kind = Kind;
+///
+///     fn kind(&self) -> Kind {
+///         match self {
+///             Token::Num(_) => Kind::Num,
+///             Token::Plus => Kind::Plus,
+///             Token::Minus => Kind::Minus,
+///             Token::Star => Kind::Star,
+///         }
+///     }
+/// }
+///
+/// let sign = kind_of::<_, &[Token], extra::Err<Simple<Token>>>([Kind::Plus, Kind::Minus]);
+///
+/// assert_eq!(sign.parse(&[Token::Minus]).into_result(), Ok(Token::Minus));
+/// assert!(sign.parse(&[Token::Star]).has_errors());
+/// // Any number is accepted, whatever its value
+/// let num = kind_of::<_, &[Token], extra::Err<Simple<Token>>>([Kind::Num]);
+/// assert_eq!(num.parse(&[Token::Num(42)]).into_result(), Ok(Token::Num(42)));
+/// ```
+pub const fn kind_of<'a, T, I, E>(kinds: T) -> KindOf<T, I, E>
+where
+    I: ValueInput<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: TokenKind,
+    T: Seq<'a, <I::Token as TokenKind>::Kind>,
+{
+    KindOf {
+        kinds,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+impl<'a, I, E, T> ParserSealed<'a, I, I::Token, E> for KindOf<T, I, E>
+where
+    I: ValueInput<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: TokenKind,
+    T: Seq<'a, <I::Token as TokenKind>::Kind>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, I::Token> {
+        let before = inp.offset();
+        match inp.next_inner() {
+            (_, Some(tok)) if self.kinds.contains(&tok.kind()) => Ok(M::bind(|| tok)),
+            (at, found) => {
+                let err_span = inp.span_since(before);
+                inp.add_alt(at, None, found.map(|f| f.into()), err_span);
                 Err(())
             }
         }
@@ -306,6 +655,11 @@ impl<T: Clone, I, E> Clone for NoneOf<T, I, E> {
 
 /// A parser that accepts any input that is *not* in a sequence of specific inputs.
 ///
+/// If the sequence is empty (such as `none_of("")`), this parser behaves like [`any`].
+///
+/// When a token in the sequence is found, the error describes the whole sequence as having been ruled out (see
+/// [`Error::unexpected_found`]) rather than expecting nothing in particular.
+///
 /// The output type of this parser is `I`, the input that was found.
 ///
 /// # Examples
@@ -348,6 +702,110 @@ where
             (_, Some(tok)) if !self.seq.contains(tok.borrow()) => Ok(M::bind(|| tok)),
             (at, found) => {
                 let err_span = inp.span_since(before);
+                inp.add_alt_err(
+                    at,
+                    E::Error::unexpected_found(
+                        self.seq.seq_iter().map(T::to_maybe_ref),
+                        found.map(|f| f.into()),
+                        err_span,
+                    ),
+                );
+                Err(())
+            }
+        }
+    }
+
+    go_extra!(I::Token);
+}
+
+/// See [`not_just`].
+pub struct NotJust<T, I, E> {
+    seq: T,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(E, I)>,
+}
+
+impl<T: Copy, I, E> Copy for NotJust<T, I, E> {}
+impl<T: Clone, I, E> Clone for NotJust<T, I, E> {
+    fn clone(&self) -> Self {
+        Self {
+            seq: self.seq.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+/// A parser that accepts any single input, unless the given sequence of inputs begins at this position.
+///
+/// This is useful for the bodies of strings and comments that end at a terminator of several tokens, such as `*/`:
+/// the body stops just before the terminator, leaving it for the parser that follows. Only a single input is consumed
+/// either way, so a lone `*` within a comment is accepted.
+///
+/// If the sequence is empty (such as `not_just("")`), this parser behaves like [`any`].
+///
+/// When the sequence is found, the error describes it as having been ruled out (see [`Error::unexpected_found`]),
+/// spanning the whole sequence.
+///
+/// The output type of this parser is `I`, the input that was found.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Simple};
+/// let comment = just::<_, _, extra::Err<Simple<char>>>("/*")
+///     .ignore_then(not_just("*/").repeated().slice())
+///     .then_ignore(just("*/"));
+///
+/// assert_eq!(comment.parse("/* a * b / c */").into_result(), Ok(" a * b / c "));
+/// assert!(comment.parse("/* a */ b */").has_errors());
+/// assert!(comment.parse("/* unterminated").has_errors());
+/// ```
+pub const fn not_just<'a, T, I, E>(seq: T) -> NotJust<T, I, E>
+where
+    I: ValueInput<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: PartialEq,
+    T: OrderedSeq<'a, I::Token>,
+{
+    NotJust {
+        seq,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+impl<'a, I, E, T> ParserSealed<'a, I, I::Token, E> for NotJust<T, I, E>
+where
+    I: ValueInput<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: PartialEq,
+    T: OrderedSeq<'a, I::Token>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, I::Token> {
+        let before = inp.save();
+        let start = inp.offset();
+        let at_seq = !self.seq.is_empty()
+            && self.seq.seq_iter().all(
+                |next| matches!(inp.next_maybe_inner(), (_, Some(tok)) if next.borrow() == &*tok),
+            );
+        let seq_span = inp.span_since(start);
+        inp.rewind(before);
+
+        match inp.next_inner() {
+            (_, Some(tok)) if !at_seq => Ok(M::bind(|| tok)),
+            (at, found) if at_seq => {
+                inp.add_alt_err(
+                    at,
+                    E::Error::unexpected_found(
+                        self.seq.seq_iter().map(T::to_maybe_ref),
+                        found.map(|f| f.into()),
+                        seq_span,
+                    ),
+                );
+                Err(())
+            }
+            (at, found) => {
+                let err_span = inp.span_since(start);
                 inp.add_alt(at, None, found.map(|f| f.into()), err_span);
                 Err(())
             }
@@ -357,6 +815,123 @@ where
     go_extra!(I::Token);
 }
 
+/// See [`balanced`].
+pub struct Balanced<T, I, E> {
+    open: T,
+    close: T,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(E, I)>,
+}
+
+impl<T: Copy, I, E> Copy for Balanced<T, I, E> {}
+impl<T: Clone, I, E> Clone for Balanced<T, I, E> {
+    fn clone(&self) -> Self {
+        Self {
+            open: self.open.clone(),
+            close: self.close.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+/// A parser that accepts a region of input enclosed by a pair of delimiters, skipping over its contents.
+///
+/// The region must begin with `open` and end with the matching `close`. Any number of further `open`/`close` pairs may
+/// appear within the region, provided that they are correctly nested. Nothing else about the contents is checked, which
+/// makes this parser useful for deferring the parsing of a block until later (such as the body of a macro invocation),
+/// or for quickly skipping over blocks that are not of interest.
+///
+/// Unlike [`nested_delimiters`], this is an ordinary parser rather than a recovery
+/// strategy: it fails if the delimiters are not balanced.
+///
+/// The output type of this parser is `I::Slice`, the contents of the region excluding the outermost delimiters.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Simple};
+/// let block = balanced::<_, extra::Err<Simple<char>>>('{', '}');
+///
+/// assert_eq!(block.parse("{ a { b } { { c } } }").into_result(), Ok(" a { b } { { c } } "));
+/// assert_eq!(block.parse("{}").into_result(), Ok(""));
+/// // The braces are not balanced
+/// assert!(block.parse("{ a { b }").has_errors());
+/// // There are trailing characters after the closing brace
+/// assert!(block.parse("{ a } }").has_errors());
+///
+/// // Defer parsing the body of a macro until later
+/// let mac = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+///     .then_ignore(just('!'))
+///     .then(balanced('(', ')'));
+///
+/// assert_eq!(mac.parse("vec!(1, (2 + 3))").into_result(), Ok(("vec", "1, (2 + 3)")));
+/// ```
+pub const fn balanced<'a, I, E>(open: I::Token, close: I::Token) -> Balanced<I::Token, I, E>
+where
+    I: ValueInput<'a> + SliceInput<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: PartialEq + Clone,
+{
+    Balanced {
+        open,
+        close,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+impl<'a, I, E> ParserSealed<'a, I, I::Slice, E> for Balanced<I::Token, I, E>
+where
+    I: ValueInput<'a> + SliceInput<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: PartialEq + Clone,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, I::Slice> {
+        let before = inp.offset();
+        match inp.next_inner() {
+            (_, Some(tok)) if tok == self.open => {}
+            (at, found) => {
+                let err_span = inp.span_since(before);
+                inp.add_alt(
+                    at,
+                    Some(Some(MaybeRef::Val(self.open.clone()))),
+                    found.map(|f| f.into()),
+                    err_span,
+                );
+                return Err(());
+            }
+        }
+
+        let start = inp.offset();
+        let mut depth = 0usize;
+        loop {
+            let end = inp.offset();
+            match inp.next_inner() {
+                (_, Some(tok)) if tok == self.close => {
+                    if depth == 0 {
+                        return Ok(M::bind(|| inp.slice(start..end)));
+                    }
+                    depth -= 1;
+                }
+                (_, Some(tok)) if tok == self.open => depth += 1,
+                (_, Some(_)) => {}
+                (at, None) => {
+                    let err_span = inp.span_since(end);
+                    inp.add_alt(
+                        at,
+                        Some(Some(MaybeRef::Val(self.close.clone()))),
+                        None,
+                        err_span,
+                    );
+                    return Err(());
+                }
+            }
+        }
+    }
+
+    go_extra!(I::Slice);
+}
+
 /// See [`custom`].
 pub struct Custom<F, I, O, E> {
     f: F,
@@ -536,6 +1111,113 @@ where
     go_extra!(O);
 }
 
+/// See [`select_window`].
+pub struct SelectWindow<F, I, O, E, const N: usize> {
+    filter: F,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(E, O, I)>,
+}
+
+impl<F: Copy, I, O, E, const N: usize> Copy for SelectWindow<F, I, O, E, N> {}
+impl<F: Clone, I, O, E, const N: usize> Clone for SelectWindow<F, I, O, E, N> {
+    fn clone(&self) -> Self {
+        Self {
+            filter: self.filter.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+/// A parser that inspects a fixed-size window of the next `N` tokens, accepting all of them if the given function
+/// returns `Some(_)`.
+///
+/// This is the multi-token equivalent of [`select!`]: the function is given the tokens of the window and their
+/// combined span, and decides whether to accept them and what to output in one step. This is useful for rules that
+/// depend on pairs of tokens, such as treating `>` followed by `>` as a right shift, without the cost of trying each
+/// possibility as an alternative.
+///
+/// If there are fewer than `N` tokens left in the input, this parser fails.
+///
+/// The output type of this parser is `O`, the output of the given function.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, error::Simple};
+/// #[derive(Clone, Debug, PartialEq)]
+/// enum Token {
+///     Gt,
+///     Eq,
+///     Ident(&'static str),
+/// }
+///
+/// // Generics like `Vec<Vec<T>>` require the lexer to emit `>>` as two tokens, so the parser recombines them
+/// let op = select_window::<_, &[Token], _, extra::Err<Simple<Token>>, 2>(|[a, b], _| match (a, b) {
+///     (Token::Gt, Token::Gt) => Some(">>"),
+///     (Token::Gt, Token::Eq) => Some(">="),
+///     _ => None,
+/// });
+///
+/// assert_eq!(op.parse(&[Token::Gt, Token::Gt]).into_result(), Ok(">>"));
+/// assert_eq!(op.parse(&[Token::Gt, Token::Eq]).into_result(), Ok(">="));
+/// assert!(op.parse(&[Token::Gt, Token::Ident("x")]).has_errors());
+/// assert!(op.parse(&[Token::Gt]).has_errors());
+/// ```
+pub const fn select_window<'a, F, I, O, E, const N: usize>(filter: F) -> SelectWindow<F, I, O, E, N>
+where
+    I: ValueInput<'a>,
+    I::Token: Clone + 'a,
+    E: ParserExtra<'a, I>,
+    F: Fn([I::Token; N], I::Span) -> Option<O>,
+{
+    SelectWindow {
+        filter,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+impl<'a, I, O, E, F, const N: usize> ParserSealed<'a, I, O, E> for SelectWindow<F, I, O, E, N>
+where
+    I: ValueInput<'a>,
+    I::Token: Clone + 'a,
+    E: ParserExtra<'a, I>,
+    F: Fn([I::Token; N], I::Span) -> Option<O>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let before = inp.offset();
+        let mut first = None;
+        let mut window = [(); N].map(|_| None);
+        for tok in window.iter_mut() {
+            match inp.next_inner() {
+                (at, Some(next)) => {
+                    first.get_or_insert_with(|| (at, next.clone(), inp.span_since(before)));
+                    *tok = Some(next);
+                }
+                (at, None) => {
+                    let err_span = inp.span_since(before);
+                    inp.add_alt(at, None, None, err_span);
+                    return Err(());
+                }
+            }
+        }
+        let window = window.map(|tok| tok.expect("window was filled"));
+        if let Some(out) = (self.filter)(window, inp.span_since(before)) {
+            return Ok(M::bind(|| out));
+        }
+        match first {
+            Some((at, tok, err_span)) => inp.add_alt(at, None, Some(tok.into()), err_span),
+            None => {
+                let err_span = inp.span_since(before);
+                inp.add_alt(inp.offset, None, None, err_span);
+            }
+        }
+        Err(())
+    }
+
+    go_extra!(O);
+}
+
 /// See [`any`].
 pub struct Any<I, E> {
     #[allow(dead_code)]
@@ -623,6 +1305,10 @@ where
         inp.with_ctx(&(self.mapper)(inp.ctx()), |inp| self.parser.go::<M>(inp))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
@@ -695,6 +1381,49 @@ where
     }
 }
 
+/// See [`significant`].
+#[derive(Copy, Clone)]
+pub struct Significant<A> {
+    parser: A,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for Significant<A>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let outer = inp.trivia.take();
+        let res = self.parser.go::<M>(inp);
+        inp.trivia = outer;
+        res
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(O);
+}
+
+/// Parse a pattern without skipping the trivia tokens configured by [`Parser::with_trivia`].
+///
+/// Trivia tokens are seen by the pattern just like any other token, so they can be matched explicitly. This is how
+/// grammars in which newlines are usually insignificant can still treat them as the end of a statement in some places,
+/// as with automatic semicolon insertion in Go or JavaScript. Trivia before the pattern is not skipped either, so the
+/// pattern starts immediately after the previous token.
+///
+/// The output type of this parser is `O`, the same as the original parser.
+///
+/// # Examples
+///
+/// See [`Parser::with_trivia`].
+pub const fn significant<A>(parser: A) -> Significant<A> {
+    Significant { parser }
+}
+
 /// See [`fn@todo`].
 pub struct Todo<I, O, E> {
     location: Location<'static>,
@@ -755,13 +1484,100 @@ where
         )
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.leaf_at::<Self>(self.location);
+    }
+
     go_extra!(O);
 }
 
 /// See [`choice`].
 #[derive(Copy, Clone)]
 pub struct Choice<T> {
-    parsers: T,
+    pub(crate) parsers: T,
+    pub(crate) all_errors: bool,
+}
+
+impl<T> Choice<T> {
+    /// Keep the error of every alternative when they all fail, rather than only the best one.
+    ///
+    /// Normally, the errors of alternatives that fail at the same place are merged together, and those of alternatives
+    /// that fail earlier than others are discarded. With this option, the error of the alternative that got furthest
+    /// into the input is reported as usual, but the errors of all of the others are attached to it with
+    /// [`Error::with_branches`]. This is useful for debugging grammars, or for tools that want to show every possible
+    /// interpretation of the input. [`Rich`] keeps them as a nested list, available from
+    /// [`Rich::branches`](crate::error::Rich::branches): other error types discard them by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let stmt = choice((
+    ///     just::<_, _, extra::Err<Rich<char>>>("let ").ignore_then(text::ascii::ident()),
+    ///     text::ascii::ident().then_ignore(just("()")),
+    /// ))
+    /// .all_errors();
+    ///
+    /// let errs = stmt.parse("let 1").into_errors();
+    /// // The first alternative got furthest, so its error is reported...
+    /// assert_eq!(errs[0].span(), &SimpleSpan::new(4, 5));
+    /// // ...along with the error of the second
+    /// let branches = errs[0].branches().collect::<Vec<_>>();
+    /// assert_eq!(branches.len(), 1);
+    /// assert_eq!(branches[0].span(), &SimpleSpan::new(3, 4));
+    /// ```
+    pub fn all_errors(self) -> Self {
+        Self {
+            all_errors: true,
+            ..self
+        }
+    }
+}
+
+// The errors of the failed alternatives of a `choice` that keeps all of them, gathered separately rather than merged
+struct Branches<'a, I: Input<'a>, E: ParserExtra<'a, I>> {
+    prior: Option<Located<I::Offset, E::Error>>,
+    errors: Vec<Located<I::Offset, E::Error>>,
+}
+
+impl<'a, I: Input<'a>, E: ParserExtra<'a, I>> Branches<'a, I, E> {
+    fn start(inp: &mut InputRef<'a, '_, I, E>) -> Self {
+        Self {
+            prior: inp.errors.alt.take(),
+            errors: Vec::new(),
+        }
+    }
+
+    fn failed(&mut self, inp: &mut InputRef<'a, '_, I, E>) {
+        self.errors.extend(inp.errors.alt.take());
+    }
+
+    fn finish(mut self, inp: &mut InputRef<'a, '_, I, E>, succeeded: bool) {
+        // Keep the error left behind by a successful alternative, such as that of a trailing repetition
+        let current = inp.errors.alt.take();
+        inp.errors.alt = self.prior;
+        if let Some(current) = current {
+            inp.merge_alt(current);
+        }
+        if succeeded {
+            // An alternative succeeded, so the errors of the others are treated as they would be otherwise
+            self.errors.into_iter().for_each(|err| inp.merge_alt(err));
+        } else if !self.errors.is_empty() {
+            // The first of the alternatives that got furthest is the primary error
+            let furthest = (0..self.errors.len()).fold(0, |best, i| {
+                if self.errors[i].pos.into() > self.errors[best].pos.into() {
+                    i
+                } else {
+                    best
+                }
+            });
+            let primary = self.errors.remove(furthest);
+            let err = primary
+                .err
+                .with_branches(self.errors.into_iter().map(|branch| branch.err));
+            inp.merge_alt(Located::at(primary.pos, err));
+        }
+    }
 }
 
 /// Parse using a tuple of many parsers, producing the output of the first to successfully parse.
@@ -776,7 +1592,8 @@ pub struct Choice<T> {
 ///
 /// These qualities make this parser ideal for lexers.
 ///
-/// The output type of this parser is the output type of the inner parsers.
+/// The output type of this parser is the output type of the inner parsers. If the inner parsers have different output
+/// types, see [`choice_map!`](crate::choice_map).
 ///
 /// # Examples
 ///
@@ -811,7 +1628,10 @@ pub struct Choice<T> {
 /// );
 /// ```
 pub const fn choice<T>(parsers: T) -> Choice<T> {
-    Choice { parsers }
+    Choice {
+        parsers,
+        all_errors: false,
+    }
 }
 
 macro_rules! impl_choice_for_tuple {
@@ -833,23 +1653,55 @@ macro_rules! impl_choice_for_tuple {
             fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
                 let before = inp.save();
 
-                let Choice { parsers: ($Head, $($X,)*), .. } = self;
+                let Choice { parsers: ($Head, $($X,)*), all_errors } = self;
+                let mut branches = if *all_errors { Some(Branches::start(inp)) } else { None };
 
                 match $Head.go::<M>(inp) {
-                    Ok(out) => return Ok(out),
-                    Err(()) => inp.rewind(before),
+                    Ok(out) => {
+                        if let Some(branches) = branches {
+                            branches.finish(inp, true);
+                        }
+                        return Ok(out);
+                    }
+                    Err(()) => {
+                        if let Some(branches) = &mut branches {
+                            branches.failed(inp);
+                        }
+                        inp.rewind(before);
+                    }
                 }
 
                 $(
                     match $X.go::<M>(inp) {
-                        Ok(out) => return Ok(out),
-                        Err(()) => inp.rewind(before),
+                        Ok(out) => {
+                            if let Some(branches) = branches {
+                                branches.finish(inp, true);
+                            }
+                            return Ok(out);
+                        }
+                        Err(()) => {
+                            if let Some(branches) = &mut branches {
+                                branches.failed(inp);
+                            }
+                            inp.rewind(before);
+                        }
                     }
                 )*
 
+                if let Some(branches) = branches {
+                    branches.finish(inp, false);
+                }
                 Err(())
             }
 
+            fn walk_node(&self, walker: &mut Walker<'_>) {
+                let Choice { parsers: ($Head, $($X,)*), .. } = self;
+                walker.node::<Self>(|walker| {
+                    $Head.walk_node(walker);
+                    $($X.walk_node(walker);)*
+                });
+            }
+
             go_extra!(O);
         }
     };
@@ -865,6 +1717,10 @@ macro_rules! impl_choice_for_tuple {
                 self.parsers.0.go::<M>(inp)
             }
 
+            fn walk_node(&self, walker: &mut Walker<'_>) {
+                walker.node::<Self>(|walker| self.parsers.0.walk_node(walker));
+            }
+
             go_extra!(O);
         }
     };
@@ -887,19 +1743,38 @@ where
             Err(())
         } else {
             let before = inp.save();
-            match self.parsers.iter().find_map(|parser| {
+            let mut branches = if self.all_errors {
+                Some(Branches::start(inp))
+            } else {
+                None
+            };
+            let out = self.parsers.iter().find_map(|parser| {
                 inp.rewind(before);
                 match parser.go::<M>(inp) {
                     Ok(out) => Some(out),
-                    Err(()) => None,
+                    Err(()) => {
+                        if let Some(branches) = &mut branches {
+                            branches.failed(inp);
+                        }
+                        None
+                    }
                 }
-            }) {
-                Some(out) => Ok(out),
-                None => Err(()),
+            });
+            if let Some(branches) = branches {
+                branches.finish(inp, out.is_some());
             }
+            out.ok_or(())
         }
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            for parser in &self.parsers {
+                parser.walk_node(walker);
+            }
+        });
+    }
+
     go_extra!(O);
 }
 
@@ -913,6 +1788,8 @@ pub struct Group<T> {
 /// otherwise returning an error if any parsers fail.
 ///
 /// This parser is to [`Parser::then`] as [`choice`] is to [`Parser::or`]
+///
+/// To leave the outputs of syntax-only elements out of the output tuple, see [`group_ignoring!`](crate::group_ignoring).
 pub const fn group<T>(parsers: T) -> Group<T> {
     Group { parsers }
 }
@@ -938,6 +1815,14 @@ where
         Ok(M::array(unsafe { MaybeUninitExt::array_assume_init(arr) }))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| {
+            for parser in &self.parsers {
+                parser.walk_node(walker);
+            }
+        });
+    }
+
     go_extra!([O; N]);
 }
 
@@ -995,6 +1880,13 @@ macro_rules! impl_group_for_tuple {
                 Ok(flatten_map!(<M> $($X)*))
             }
 
+            fn walk_node(&self, walker: &mut Walker<'_>) {
+                let Group { parsers: ($($X,)*) } = self;
+                walker.node::<Self>(|walker| {
+                    $($X.walk_node(walker);)*
+                });
+            }
+
             go_extra!(($($O,)*));
         }
     };
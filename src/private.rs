@@ -1,20 +1,36 @@
 use super::*;
 
+/// An error, along with the input offset at which it occurred.
 #[derive(Clone)]
-pub(crate) struct Located<T, E> {
+pub struct Located<T, E> {
     pub(crate) pos: T,
     pub(crate) err: E,
 }
 
 impl<T, E> Located<T, E> {
+    /// Locate the given error at the given offset.
     #[inline]
     pub fn at(pos: T, err: E) -> Self {
         Self { pos, err }
     }
+
+    /// Get the offset at which the error occurred.
+    #[cfg(feature = "unstable")]
+    pub fn pos(&self) -> &T {
+        &self.pos
+    }
+
+    /// Get the error.
+    #[cfg(feature = "unstable")]
+    pub fn err(&self) -> &E {
+        &self.err
+    }
 }
 
-/// The result of calling [`Parser::go`]
-pub(crate) type PResult<M, O> = Result<<M as Mode>::Output<O>, ()>;
+/// The result of calling [`ParserSealed::go`].
+///
+/// Failure carries no error: the error that explains it has already been recorded in the [`InputRef`].
+pub type PResult<M, O> = Result<<M as Mode>::Output<O>, ()>;
 /// The result of calling [`IterParser::next`]
 pub(crate) type IPResult<M, O> = Result<Option<<M as Mode>::Output<O>>, ()>;
 
@@ -51,7 +67,7 @@ pub trait Mode {
     fn array<T, const N: usize>(x: [Self::Output<T>; N]) -> Self::Output<[T; N]>;
 
     /// Invoke a parser user the current mode. This is normally equivalent to
-    /// [`parser.go::<M>(inp)`](Parser::go), but it can be called on unsized values such as
+    /// [`parser.go::<M>(inp)`](ParserSealed::go), but it can be called on unsized values such as
     /// `dyn Parser`.
     fn invoke<'a, I, O, E, P>(parser: &P, inp: &mut InputRef<'a, '_, I, E>) -> PResult<Self, O>
     where
@@ -60,7 +76,7 @@ pub trait Mode {
         P: Parser<'a, I, O, E> + ?Sized;
 
     /// Invoke a parser with configuration using the current mode. This is normally equivalent
-    /// to [`parser.go::<M>(inp)`](ConfigParser::go_cfg), but it can be called on unsized values
+    /// to `parser.go_cfg::<M>(inp, cfg)`, but it can be called on unsized values
     /// such as `dyn Parser`.
     fn invoke_cfg<'a, I, O, E, P>(
         parser: &P,
@@ -193,14 +209,35 @@ impl Mode for Check {
 // TODO: Consider removing these sealed traits in favour of `Sealed`, with the given methods just being on `Parser`
 // with doc(hidden)
 
+/// The core of [`Parser`], which every parser implements.
+///
+/// Implementing this trait implements [`Parser`]. See [`extension::unstable`](crate::extension::unstable) for how to do
+/// so outside of chumsky.
 pub trait ParserSealed<'a, I: Input<'a>, O, E: ParserExtra<'a, I>> {
+    /// Run this parser on the input in the given [`Mode`].
+    ///
+    /// On failure, the parser must have recorded an error explaining why (usually with
+    /// [`InputRef::add_error`]). The input does not need to be rewound: that is the caller's responsibility.
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O>
     where
         Self: Sized;
 
+    /// Run this parser in [`Emit`] mode. This is object-safe, unlike [`ParserSealed::go`], and is implemented by
+    /// [`go_extra!`](crate::extension::unstable::go_extra).
     fn go_emit(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<Emit, O>;
+    /// Run this parser in [`Check`] mode. This is object-safe, unlike [`ParserSealed::go`], and is implemented by
+    /// [`go_extra!`](crate::extension::unstable::go_extra).
     fn go_check(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<Check, O>;
 
+    /// Visit this parser, and the parsers within it. See [`Parser::walk`].
+    ///
+    /// By default, the parser is visited as a leaf. Parsers that contain other parsers should override this.
+    #[doc(hidden)]
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|_| {});
+    }
+
+    /// See [`Parser::boxed`].
     fn boxed<'b>(self) -> Boxed<'a, 'b, I, O, E>
     where
         Self: MaybeSync + Sized + 'a + 'b,
@@ -253,6 +290,12 @@ where
         inp: &mut InputRef<'a, '_, I, E>,
         state: &mut Self::IterState<M>,
     ) -> IPResult<M, O>;
+
+    /// Visit this iterable parser, and the parsers within it. See [`Parser::walk`].
+    #[doc(hidden)]
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|_| {});
+    }
 }
 
 pub trait ConfigIterParserSealed<'a, I, O, E>: IterParserSealed<'a, I, O, E>
@@ -0,0 +1,227 @@
+//! Parsing of input that arrives a piece at a time, such as lines typed into a REPL or packets read from a socket.
+//!
+//! A [`ReparseBuffer`] buffers the input that has arrived so far. Parsing it produces a [`Step`]: either the parser
+//! finished (successfully or not), or it ran out of input part way through, in which case the caller should wait for
+//! more input and parse again.
+//!
+//! No parser state is kept between attempts: each attempt re-parses the buffered input from the start, so no async
+//! runtime or special support from the parser is needed. The price is that an item that arrives in `k` pieces is
+//! parsed `k` times, which is quadratic in the worst case. This is cheap for items that are small or arrive in few
+//! pieces, such as the statements of a REPL or the messages of a protocol, but makes the buffer unsuitable for
+//! streaming a single large input: use [`BufferedInput`](crate::input::BufferedInput) for that instead. To protect
+//! against items that never end, the buffer can be given a maximum length with [`ReparseBuffer::with_limit`].
+//!
+//! Once a parse finishes, the input that it consumed can be discarded with [`ReparseBuffer::consume`], ready for the
+//! next item.
+//!
+//! A parse is only considered incomplete when it *fails* at the end of the buffered input. A parser that succeeds on a
+//! prefix of what it would eventually accept (such as `text::int(10)` given `"12"`, when `"123"` is on its way)
+//! finishes early, so items should end with something unambiguous such as a delimiter or, for a REPL, [`end`].
+//!
+//! Since the parser borrows the buffer, it must be created anew each time the buffer is parsed (for example, by a
+//! function), rather than being kept alive across changes to the buffer.
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, push::{ReparseBuffer, Step}};
+//! fn stmt<'a>() -> impl Parser<'a, &'a str, (&'a str, &'a str), extra::Err<Simple<'a, char>>> {
+//!     text::ascii::ident()
+//!         .then_ignore(just('=').padded())
+//!         .then(text::int(10))
+//!         .then_ignore(just(';'))
+//!         .padded()
+//! }
+//!
+//! let mut repl = ReparseBuffer::text();
+//!
+//! // The statement is incomplete, so we should prompt for another line
+//! repl.push_str("x = ");
+//! assert!(matches!(repl.parse(&stmt()), Step::Incomplete));
+//!
+//! repl.push_str("42;\n");
+//! let Step::Done { result, consumed } = repl.parse(&stmt()) else {
+//!     panic!("the statement is complete");
+//! };
+//! assert_eq!(result.into_result(), Ok(("x", "42")));
+//! assert_eq!(consumed, 8);
+//!
+//! // The next statement can be parsed once the consumed input is discarded
+//! repl.consume(consumed);
+//! assert_eq!(repl.as_str(), "");
+//! ```
+
+use super::*;
+
+/// The outcome of parsing the input buffered by a [`ReparseBuffer`].
+#[derive(Debug)]
+pub enum Step<O, E> {
+    /// The parser ran out of input. Append more input and parse again.
+    Incomplete,
+    /// The parser finished.
+    Done {
+        /// The result of the parse.
+        result: ParseResult<O, E>,
+        /// The number of tokens (or, for text, bytes) of input that the parser consumed. This is zero if the parser
+        /// failed.
+        consumed: usize,
+    },
+}
+
+/// A buffer of input that has arrived so far, for parsing input a piece at a time by re-parsing it as it grows. See
+/// the [module-level documentation](self) for more information.
+///
+/// Text is buffered with a `ReparseBuffer<String>` (created with [`ReparseBuffer::text`]), and is parsed as a
+/// [`&str`]. Other tokens are buffered with a `ReparseBuffer<Vec<T>>` (created with [`ReparseBuffer::tokens`]), and
+/// are parsed as a [`&[T]`].
+#[derive(Clone, Debug, Default)]
+pub struct ReparseBuffer<B> {
+    buffer: B,
+    limit: Option<usize>,
+}
+
+impl<B> ReparseBuffer<B> {
+    /// Limit the length of an incomplete item to `limit` tokens (or, for text, bytes).
+    ///
+    /// Once the buffer holds at least `limit` tokens, a parse that runs out of input finishes with its error instead
+    /// of being [`Step::Incomplete`], so that a peer that never finishes an item cannot make the buffer (and the cost
+    /// of re-parsing it) grow without bound.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, push::{ReparseBuffer, Step}};
+    /// let line = any::<_, extra::Err<Simple<char>>>()
+    ///     .and_is(just('\n').not())
+    ///     .repeated()
+    ///     .then_ignore(just('\n'));
+    ///
+    /// let mut conn = ReparseBuffer::text().with_limit(8);
+    /// conn.push_str("hello");
+    /// assert!(matches!(conn.parse(&line), Step::Incomplete));
+    ///
+    /// conn.push_str(" world");
+    /// let Step::Done { result, .. } = conn.parse(&line) else {
+    ///     panic!("the line is too long");
+    /// };
+    /// assert!(result.has_errors());
+    /// ```
+    pub fn with_limit(self, limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..self
+        }
+    }
+
+    fn is_full(&self, len: usize) -> bool {
+        matches!(self.limit, Some(limit) if len >= limit)
+    }
+}
+
+impl ReparseBuffer<String> {
+    /// Create a new, empty buffer of text.
+    pub fn text() -> Self {
+        Self::default()
+    }
+
+    /// Append text to the buffer.
+    pub fn push_str(&mut self, s: &str) {
+        self.buffer.push_str(s);
+    }
+
+    /// Discard the first `n` bytes of the buffer, usually the input consumed by a finished parse.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the length of the buffer, or does not lie on a char boundary.
+    pub fn consume(&mut self, n: usize) {
+        self.buffer.drain(..n);
+    }
+
+    /// The text buffered so far.
+    pub fn as_str(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Parse the buffered text.
+    pub fn parse<'a, P, O, E>(&'a self, parser: &P) -> Step<O, E::Error>
+    where
+        P: Parser<'a, &'a str, O, E>,
+        E: ParserExtra<'a, &'a str>,
+        E::State: Default,
+        E::Context: Default,
+    {
+        let full = self.is_full(self.buffer.len());
+        parse_buffered(parser, self.buffer.as_str(), full)
+    }
+}
+
+impl<T> ReparseBuffer<Vec<T>> {
+    /// Create a new, empty buffer of tokens.
+    pub fn tokens() -> Self {
+        Self {
+            buffer: Vec::new(),
+            limit: None,
+        }
+    }
+
+    /// Append tokens to the buffer.
+    pub fn extend<Iter: IntoIterator<Item = T>>(&mut self, tokens: Iter) {
+        self.buffer.extend(tokens);
+    }
+
+    /// Discard the first `n` tokens of the buffer, usually the input consumed by a finished parse.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the length of the buffer.
+    pub fn consume(&mut self, n: usize) {
+        self.buffer.drain(..n);
+    }
+
+    /// The tokens buffered so far.
+    pub fn as_slice(&self) -> &[T] {
+        &self.buffer
+    }
+
+    /// Parse the buffered tokens.
+    pub fn parse<'a, P, O, E>(&'a self, parser: &P) -> Step<O, E::Error>
+    where
+        P: Parser<'a, &'a [T], O, E>,
+        E: ParserExtra<'a, &'a [T]>,
+        E::State: Default,
+        E::Context: Default,
+    {
+        let full = self.is_full(self.buffer.len());
+        parse_buffered(parser, self.buffer.as_slice(), full)
+    }
+}
+
+/// Parse a prefix of `input`, which is incomplete if the parser fails at the end of it (unless the buffer is full).
+fn parse_buffered<'a, I, O, E, P>(parser: &P, input: I, full: bool) -> Step<O, E::Error>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: Default,
+    E::Context: Default,
+    P: Parser<'a, I, O, E>,
+{
+    let mut own = InputOwn::<I, E>::new(input);
+    let (res, consumed) = own.drive::<Emit, _, _, _>(own.input.start(), parser, |inp, res| {
+        (res, inp.offset.into())
+    });
+    let (out, consumed) = match res {
+        Ok(out) => (Some(out), consumed),
+        Err(alt) => {
+            if own.ended_at(alt.pos) && !full {
+                return Step::Incomplete;
+            }
+            own.errors.secondary.push(alt);
+            (None, 0)
+        }
+    };
+    Step::Done {
+        result: own.into_result(out),
+        consumed,
+    }
+}
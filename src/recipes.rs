@@ -0,0 +1,21 @@
+//! Ready-made parsers for common data formats.
+//!
+//! *“The ships hung in the sky in much the same way that bricks don't.”*
+//!
+//! Each recipe is a small, self-contained parser built entirely on chumsky's public API. They're useful directly (for
+//! quick tools that need to read a config file or some JSON) and as worked examples of idiomatic chumsky: error
+//! recovery, span tracking, and zero-copy slicing of the input.
+//!
+//! - [`json`]: JSON values, with error recovery
+//! - [`csv`]: comma-separated values, with quoted fields
+//! - [`ini`]: INI files, with sections and comments
+//! - [`toml`]: a lightweight subset of TOML
+//! - [`values`]: semantic versions, IPv4 addresses and RFC 3339 timestamps
+//!
+//! All recipes parse `&str` inputs and produce [`Rich`](crate::error::Rich) errors.
+
+pub mod csv;
+pub mod ini;
+pub mod json;
+pub mod toml;
+pub mod values;
@@ -0,0 +1,103 @@
+//! A parser for comma-separated values, as described by [RFC 4180](https://www.rfc-editor.org/rfc/rfc4180).
+//!
+//! Fields are borrowed directly from the input wherever possible: only quoted fields containing escaped quotes (`""`)
+//! require an allocation.
+
+use alloc::{borrow::Cow, vec::Vec};
+
+use crate::prelude::*;
+
+/// A single record (i.e: line) of a CSV file.
+pub type Record<'a> = Vec<Cow<'a, str>>;
+
+/// A parser for a single CSV field, either quoted or unquoted.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// use chumsky::recipes::csv::field;
+///
+/// assert_eq!(field().parse("hello").into_result().as_deref(), Ok("hello"));
+/// assert_eq!(field().parse(r#""a, ""quoted"" field""#).into_result().as_deref(), Ok(r#"a, "quoted" field"#));
+/// ```
+pub fn field<'a>() -> impl Parser<'a, &'a str, Cow<'a, str>, extra::Err<Rich<'a, char>>> + Clone {
+    let quoted = none_of('"')
+        .ignored()
+        .or(just("\"\"").ignored())
+        .repeated()
+        .slice()
+        .map(|s: &str| {
+            if s.contains("\"\"") {
+                Cow::Owned(s.replace("\"\"", "\""))
+            } else {
+                Cow::Borrowed(s)
+            }
+        })
+        .delimited_by(just('"'), just('"'));
+
+    let unquoted = none_of(",\"\r\n").repeated().slice().map(Cow::Borrowed);
+
+    quoted.or(unquoted)
+}
+
+/// A parser for a CSV file, producing a list of records.
+///
+/// Records are separated by newlines, and a single trailing newline is permitted. Records are not required to have the
+/// same number of fields: if your application requires this, check it after parsing.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// use chumsky::recipes::csv::csv;
+///
+/// let records = csv().parse("name,age\nAlice,42\n\"Smith, Bob\",7\n").into_result().unwrap();
+///
+/// assert_eq!(records, vec![
+///     vec!["name", "age"],
+///     vec!["Alice", "42"],
+///     vec!["Smith, Bob", "7"],
+/// ]);
+/// ```
+pub fn csv<'a>() -> impl Parser<'a, &'a str, Vec<Record<'a>>, extra::Err<Rich<'a, char>>> + Clone {
+    let record = field().separated_by(just(',')).at_least(1).collect();
+
+    // A record at the very end of the input would be empty: it's just the trailing newline
+    end()
+        .not()
+        .ignore_then(record)
+        .separated_by(text::newline())
+        .collect()
+        .then_ignore(text::newline().or_not())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn borrowed_fields() {
+        let records = csv().parse("a,b,c\n1,,3").into_result().unwrap();
+        assert_eq!(records, vec![vec!["a", "b", "c"], vec!["1", "", "3"]]);
+        assert!(records
+            .iter()
+            .flatten()
+            .all(|f| matches!(f, Cow::Borrowed(_))));
+    }
+
+    #[test]
+    fn quoted_fields() {
+        let records = csv()
+            .parse("\"multi\nline\",\"say \"\"hi\"\"\"\r\n")
+            .into_result()
+            .unwrap();
+        assert_eq!(records, vec![vec!["multi\nline", "say \"hi\""]]);
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(csv().parse("").into_result(), Ok(vec![]));
+        assert!(csv().parse("\"unterminated").has_errors());
+    }
+}
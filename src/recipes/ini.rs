@@ -0,0 +1,179 @@
+//! A parser for INI configuration files.
+//!
+//! Keys, values, and section names are borrowed directly from the input. Both `;` and `#` comments are supported,
+//! either on their own line or at the end of a line.
+
+use alloc::vec::Vec;
+
+use crate::prelude::*;
+
+/// A `key = value` entry.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry<'a> {
+    /// The key of the entry, with surrounding whitespace removed.
+    pub key: &'a str,
+    /// The value of the entry, with surrounding whitespace removed.
+    pub value: &'a str,
+    /// The span of the entire entry.
+    pub span: SimpleSpan,
+}
+
+/// A `[section]`, and the entries that follow it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Section<'a> {
+    /// The name of the section.
+    pub name: &'a str,
+    /// The span of the section header.
+    pub span: SimpleSpan,
+    /// The entries in this section.
+    pub entries: Vec<Entry<'a>>,
+}
+
+/// A parsed INI file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ini<'a> {
+    /// Entries that appear before the first section.
+    pub globals: Vec<Entry<'a>>,
+    /// The sections of the file, in order.
+    pub sections: Vec<Section<'a>>,
+}
+
+impl<'a> Ini<'a> {
+    /// Find the value of the first entry with the given key in the given section (or in the globals, if `section` is
+    /// `None`).
+    pub fn get(&self, section: Option<&str>, key: &str) -> Option<&'a str> {
+        let entries = match section {
+            Some(name) => &self.sections.iter().find(|s| s.name == name)?.entries,
+            None => &self.globals,
+        };
+        entries.iter().find(|e| e.key == key).map(|e| e.value)
+    }
+}
+
+/// A parser for an INI file.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// use chumsky::recipes::ini::ini;
+///
+/// let src = "
+/// name = demo # the name of the program
+///
+/// [server]
+/// host = localhost
+/// ; The port to listen on
+/// port = 8080
+/// ";
+///
+/// let ini = ini().parse(src).into_result().unwrap();
+/// assert_eq!(ini.get(None, "name"), Some("demo"));
+/// assert_eq!(ini.get(Some("server"), "port"), Some("8080"));
+/// assert_eq!(ini.get(Some("client"), "port"), None);
+/// ```
+pub fn ini<'a>() -> impl Parser<'a, &'a str, Ini<'a>, extra::Err<Rich<'a, char>>> + Clone {
+    let ws = text::inline_whitespace();
+    let comment = one_of(";#").then(none_of("\r\n").repeated()).ignored();
+    let line_end = ws
+        .then(comment.or_not())
+        .then(text::newline().or(end()))
+        .ignored();
+    // Blank lines must end in a newline so that they always make progress
+    let blank = ws.then(comment.or_not()).then(text::newline()).ignored();
+
+    let key = none_of("=;#\r\n").repeated().at_least(1).slice();
+    let value = none_of(";#\r\n").repeated().slice();
+
+    let entry = key
+        .then_ignore(just('='))
+        .then(value)
+        .map_with(|(key, value): (&str, &str), e| Entry {
+            key: key.trim(),
+            value: value.trim(),
+            span: e.span(),
+        })
+        .padded_by(ws)
+        .then_ignore(line_end);
+
+    let entries = blank
+        .repeated()
+        .ignore_then(entry)
+        .repeated()
+        .collect::<Vec<_>>();
+
+    let header = none_of("]\r\n")
+        .repeated()
+        .slice()
+        .delimited_by(just('['), just(']'))
+        .map_with(|name: &str, e| (name.trim(), e.span()))
+        .padded_by(ws)
+        .then_ignore(line_end);
+
+    let section =
+        blank
+            .repeated()
+            .ignore_then(header)
+            .then(entries)
+            .map(|((name, span), entries)| Section {
+                name,
+                span,
+                entries,
+            });
+
+    entries
+        .then(section.repeated().collect())
+        .then_ignore(blank.repeated())
+        .then_ignore(ws.then(comment.or_not()))
+        .map(|(globals, sections)| Ini { globals, sections })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections() {
+        let ini = ini()
+            .parse("a=1\n[x]\nb = 2\n\n[ y ]\n; nothing here\n")
+            .into_result()
+            .unwrap();
+        assert_eq!(
+            ini,
+            Ini {
+                globals: vec![Entry {
+                    key: "a",
+                    value: "1",
+                    span: (0..3).into(),
+                }],
+                sections: vec![
+                    Section {
+                        name: "x",
+                        span: (4..7).into(),
+                        entries: vec![Entry {
+                            key: "b",
+                            value: "2",
+                            span: (8..13).into(),
+                        }],
+                    },
+                    Section {
+                        name: "y",
+                        span: (15..20).into(),
+                        entries: vec![],
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn no_trailing_newline() {
+        let ini = ini().parse("[s]\nk=v # comment").into_result().unwrap();
+        assert_eq!(ini.get(Some("s"), "k"), Some("v"));
+    }
+
+    #[test]
+    fn invalid_line() {
+        assert!(ini().parse("[s]\nnot an entry\n").has_errors());
+    }
+}
@@ -0,0 +1,209 @@
+//! A JSON parser, with error recovery.
+//!
+//! Malformed arrays, objects, and values are recovered from where possible, producing [`Json::Invalid`] in place of
+//! the broken value so that the rest of the document can still be inspected.
+
+use alloc::{string::String, vec::Vec};
+
+use crate::prelude::*;
+
+/// A JSON value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    /// A value that could not be parsed, produced during error recovery.
+    Invalid,
+    /// `null`.
+    Null,
+    /// `true` or `false`.
+    Bool(bool),
+    /// A number.
+    Num(f64),
+    /// A string, with escape sequences resolved.
+    Str(String),
+    /// An array of values.
+    Array(Vec<Json>),
+    /// An object, with its members in the order they appeared.
+    Object(Vec<(String, Json)>),
+}
+
+/// A parser for a JSON value, surrounded by optional whitespace.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// use chumsky::recipes::json::{json, Json};
+///
+/// assert_eq!(
+///     json().parse(r#"{ "a": [1, true, null], "b": "x\ty" }"#).into_result(),
+///     Ok(Json::Object(vec![
+///         ("a".to_string(), Json::Array(vec![Json::Num(1.0), Json::Bool(true), Json::Null])),
+///         ("b".to_string(), Json::Str("x\ty".to_string())),
+///     ])),
+/// );
+///
+/// // Errors are recovered from, so the rest of the document is still available
+/// let (out, errs) = json().parse("[1, ?, 3]").into_output_errors();
+/// assert_eq!(out, Some(Json::Array(vec![Json::Num(1.0), Json::Invalid, Json::Num(3.0)])));
+/// assert_eq!(errs.len(), 1);
+/// ```
+pub fn json<'a>() -> impl Parser<'a, &'a str, Json, extra::Err<Rich<'a, char>>> + Clone {
+    recursive(|value| {
+        let digits = text::digits(10).slice();
+
+        let frac = just('.').then(digits);
+
+        let exp = just('e')
+            .or(just('E'))
+            .then(one_of("+-").or_not())
+            .then(digits);
+
+        let number = just('-')
+            .or_not()
+            .then(text::int(10))
+            .then(frac.or_not())
+            .then(exp.or_not())
+            .map_slice(|s: &str| s.parse().unwrap())
+            .boxed();
+
+        let escape = just('\\')
+            .ignore_then(choice((
+                just('\\'),
+                just('/'),
+                just('"'),
+                just('b').to('\x08'),
+                just('f').to('\x0C'),
+                just('n').to('\n'),
+                just('r').to('\r'),
+                just('t').to('\t'),
+                just('u').ignore_then(text::digits(16).exactly(4).slice().validate(
+                    |digits, span, emitter| {
+                        char::from_u32(u32::from_str_radix(digits, 16).unwrap()).unwrap_or_else(
+                            || {
+                                emitter.emit(Rich::custom(span, "invalid unicode character"));
+                                '\u{FFFD}' // unicode replacement character
+                            },
+                        )
+                    },
+                )),
+            )))
+            .boxed();
+
+        let string = none_of("\\\"")
+            .or(escape)
+            .repeated()
+            .collect::<String>()
+            .delimited_by(just('"'), just('"'))
+            .boxed();
+
+        let array = value
+            .clone()
+            .separated_by(just(',').padded().recover_with(skip_then_retry_until(
+                any().ignored(),
+                one_of(",]").ignored(),
+            )))
+            .allow_trailing()
+            .collect()
+            .padded()
+            .delimited_by(
+                just('['),
+                just(']')
+                    .ignored()
+                    .recover_with(via_parser(end()))
+                    .recover_with(skip_then_retry_until(any().ignored(), end())),
+            )
+            .boxed();
+
+        let member = string.clone().then_ignore(just(':').padded()).then(value);
+        let object = member
+            .separated_by(just(',').padded().recover_with(skip_then_retry_until(
+                any().ignored(),
+                one_of(",}").ignored(),
+            )))
+            .collect()
+            .padded()
+            .delimited_by(
+                just('{'),
+                just('}')
+                    .ignored()
+                    .recover_with(via_parser(end()))
+                    .recover_with(skip_then_retry_until(any().ignored(), end())),
+            )
+            .boxed();
+
+        choice((
+            just("null").to(Json::Null),
+            just("true").to(Json::Bool(true)),
+            just("false").to(Json::Bool(false)),
+            number.map(Json::Num),
+            string.map(Json::Str),
+            array.map(Json::Array),
+            object.map(Json::Object),
+        ))
+        .recover_with(via_parser(nested_delimiters(
+            '{',
+            '}',
+            [('[', ']')],
+            |_| Json::Invalid,
+        )))
+        .recover_with(via_parser(nested_delimiters(
+            '[',
+            ']',
+            [('{', '}')],
+            |_| Json::Invalid,
+        )))
+        // As a last resort, treat anything up to the next delimiter as an invalid value
+        .recover_with(via_parser(
+            none_of(",[]{}").repeated().at_least(1).to(Json::Invalid),
+        ))
+        .padded()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn scalars() {
+        assert_eq!(json().parse("null").into_result(), Ok(Json::Null));
+        assert_eq!(json().parse(" false ").into_result(), Ok(Json::Bool(false)));
+        assert_eq!(json().parse("-1.5e3").into_result(), Ok(Json::Num(-1500.0)));
+        assert_eq!(
+            json().parse(r#""a\"b\u0041""#).into_result(),
+            Ok(Json::Str("a\"bA".to_string()))
+        );
+    }
+
+    #[test]
+    fn nested() {
+        assert_eq!(
+            json().parse(r#"{"a": {"b": []}, "c": [{}]}"#).into_result(),
+            Ok(Json::Object(vec![
+                (
+                    "a".to_string(),
+                    Json::Object(vec![("b".to_string(), Json::Array(vec![]))])
+                ),
+                ("c".to_string(), Json::Array(vec![Json::Object(vec![])])),
+            ]))
+        );
+    }
+
+    #[test]
+    fn recovery() {
+        let (out, errs) = json().parse(r#"{"a": 1, "b": ?}"#).into_output_errors();
+        assert_eq!(
+            out,
+            Some(Json::Object(vec![
+                ("a".to_string(), Json::Num(1.0)),
+                ("b".to_string(), Json::Invalid),
+            ]))
+        );
+        assert_eq!(errs.len(), 1);
+
+        let (out, errs) = json().parse("[1, [2, 3}, 4]").into_output_errors();
+        assert!(out.is_some());
+        assert_eq!(errs.len(), 2);
+    }
+}
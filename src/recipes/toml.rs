@@ -0,0 +1,340 @@
+//! A parser for a lightweight subset of [TOML](https://toml.io).
+//!
+//! The following are supported:
+//!
+//! - Bare, quoted, and dotted keys (quoted keys are taken literally, without processing escape sequences)
+//! - Basic strings (with escape sequences) and literal strings
+//! - Integers, floats, and booleans
+//! - Arrays (which may span multiple lines and contain comments) and inline tables
+//! - `[table]` headers
+//!
+//! Multi-line strings, dates and times, and arrays of tables (`[[table]]`) are not supported.
+//!
+//! Strings are borrowed directly from the input unless they contain escape sequences, and every key/value pair keeps
+//! its span so that applications can report errors that point back into the source.
+
+use alloc::{borrow::Cow, string::String, vec::Vec};
+
+use crate::prelude::*;
+
+/// A TOML value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value<'a> {
+    /// A string.
+    String(Cow<'a, str>),
+    /// An integer.
+    Integer(i64),
+    /// A floating-point number.
+    Float(f64),
+    /// A boolean.
+    Boolean(bool),
+    /// An array of values.
+    Array(Vec<Value<'a>>),
+    /// An inline table, such as `{ x = 1, y = 2 }`.
+    InlineTable(Vec<KeyValue<'a>>),
+}
+
+/// A `key = value` pair.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyValue<'a> {
+    /// The components of the (possibly dotted) key.
+    pub key: Vec<&'a str>,
+    /// The value.
+    pub value: Value<'a>,
+    /// The span of the entire pair.
+    pub span: SimpleSpan,
+}
+
+/// A `[table]` and the key/value pairs that follow it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Table<'a> {
+    /// The components of the (possibly dotted) table name.
+    pub header: Vec<&'a str>,
+    /// The span of the table header.
+    pub span: SimpleSpan,
+    /// The key/value pairs in this table.
+    pub entries: Vec<KeyValue<'a>>,
+}
+
+/// A parsed TOML document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Document<'a> {
+    /// Key/value pairs that appear before the first table header.
+    pub root: Vec<KeyValue<'a>>,
+    /// The tables of the document, in order.
+    pub tables: Vec<Table<'a>>,
+}
+
+type Err<'a> = extra::Err<Rich<'a, char>>;
+
+fn comment<'a>() -> impl Parser<'a, &'a str, (), Err<'a>> + Copy {
+    just('#').then(none_of("\r\n").repeated()).ignored()
+}
+
+fn key<'a>() -> impl Parser<'a, &'a str, Vec<&'a str>, Err<'a>> + Clone {
+    let bare = any()
+        .filter(|c: &char| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+        .repeated()
+        .at_least(1)
+        .slice();
+    let quoted = none_of("\"\r\n")
+        .repeated()
+        .slice()
+        .delimited_by(just('"'), just('"'));
+
+    bare.or(quoted)
+        .separated_by(just('.').padded_by(text::inline_whitespace()))
+        .at_least(1)
+        .collect()
+}
+
+fn value<'a>() -> impl Parser<'a, &'a str, Value<'a>, Err<'a>> + Clone {
+    recursive(|value| {
+        let escape = just('\\').ignore_then(choice((
+            just('\\'),
+            just('"'),
+            just('b').to('\x08'),
+            just('f').to('\x0C'),
+            just('n').to('\n'),
+            just('r').to('\r'),
+            just('t').to('\t'),
+            just('u')
+                .ignore_then(text::digits(16).exactly(4).slice())
+                .or(just('U').ignore_then(text::digits(16).exactly(8).slice()))
+                .try_map(|digits: &str, span| {
+                    char::from_u32(u32::from_str_radix(digits, 16).unwrap())
+                        .ok_or_else(|| Rich::custom(span, "invalid unicode character"))
+                }),
+        )));
+
+        // Strings without escapes can be borrowed from the input, saving an allocation
+        let borrowed = none_of("\\\"\r\n")
+            .repeated()
+            .slice()
+            .map(Cow::Borrowed)
+            .then_ignore(just('"').rewind());
+        let owned = none_of("\\\"\r\n")
+            .or(escape)
+            .repeated()
+            .collect::<String>()
+            .map(Cow::Owned);
+        let basic_string = borrowed.or(owned).delimited_by(just('"'), just('"'));
+
+        let literal_string = none_of("'\r\n")
+            .repeated()
+            .slice()
+            .map(Cow::Borrowed)
+            .delimited_by(just('\''), just('\''));
+
+        let boolean = just("true")
+            .to(true)
+            .or(just("false").to(false))
+            .map(Value::Boolean);
+
+        let digits = text::digits(10).slice();
+        let number = one_of("+-")
+            .or_not()
+            .then(text::int(10))
+            .then(just('.').then(digits).or_not())
+            .then(
+                one_of("eE")
+                    .then(one_of("+-").or_not())
+                    .then(digits)
+                    .or_not(),
+            )
+            .slice()
+            .try_map(|s: &str, span| {
+                if s.contains(['.', 'e', 'E']) {
+                    s.parse()
+                        .map(Value::Float)
+                        .map_err(|e| Rich::custom(span, e))
+                } else {
+                    s.parse()
+                        .map(Value::Integer)
+                        .map_err(|e| Rich::custom(span, e))
+                }
+            });
+
+        // Arrays may span several lines and contain comments
+        let ws_nl = any()
+            .filter(|c: &char| c.is_whitespace())
+            .ignored()
+            .or(comment())
+            .repeated();
+        let array = value
+            .clone()
+            .padded_by(ws_nl)
+            .separated_by(just(','))
+            .allow_trailing()
+            .collect()
+            .padded_by(ws_nl)
+            .delimited_by(just('['), just(']'))
+            .map(Value::Array);
+
+        let inline_table = key_value(value)
+            .padded_by(text::inline_whitespace())
+            .separated_by(just(','))
+            .collect()
+            .padded_by(text::inline_whitespace())
+            .delimited_by(just('{'), just('}'))
+            .map(Value::InlineTable);
+
+        choice((
+            basic_string.map(Value::String),
+            literal_string.map(Value::String),
+            boolean,
+            number,
+            array,
+            inline_table,
+        ))
+    })
+}
+
+fn key_value<'a>(
+    value: impl Parser<'a, &'a str, Value<'a>, Err<'a>> + Clone,
+) -> impl Parser<'a, &'a str, KeyValue<'a>, Err<'a>> + Clone {
+    key()
+        .then_ignore(just('=').padded_by(text::inline_whitespace()))
+        .then(value)
+        .map_with(|(key, value), e| KeyValue {
+            key,
+            value,
+            span: e.span(),
+        })
+}
+
+/// A parser for a TOML document.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// use chumsky::recipes::toml::{toml, Value};
+///
+/// let src = r#"
+/// title = "example" # A comment
+///
+/// [owner.details]
+/// name = 'Tom'
+/// tags = [
+///     "a",
+///     "b", # The last tag
+/// ]
+/// point = { x = 1, y = -2.5 }
+/// "#;
+///
+/// let doc = toml().parse(src).into_result().unwrap();
+///
+/// assert_eq!(doc.root[0].key, vec!["title"]);
+/// assert_eq!(doc.root[0].value, Value::String("example".into()));
+/// assert_eq!(doc.tables[0].header, vec!["owner", "details"]);
+/// assert_eq!(doc.tables[0].entries.len(), 3);
+/// ```
+pub fn toml<'a>() -> impl Parser<'a, &'a str, Document<'a>, Err<'a>> + Clone {
+    let ws = text::inline_whitespace();
+    let line_end = ws
+        .then(comment().or_not())
+        .then(text::newline().or(end()))
+        .ignored();
+    // Blank lines must end in a newline so that they always make progress
+    let blank = ws.then(comment().or_not()).then(text::newline()).ignored();
+
+    let entries = blank
+        .repeated()
+        .ignore_then(key_value(value()).padded_by(ws).then_ignore(line_end))
+        .repeated()
+        .collect::<Vec<_>>();
+
+    let header = key()
+        .padded_by(ws)
+        .delimited_by(just('['), just(']'))
+        .map_with(|header, e| (header, e.span()))
+        .padded_by(ws)
+        .then_ignore(line_end);
+
+    let table = blank
+        .repeated()
+        .ignore_then(header)
+        .then(entries.clone())
+        .map(|((header, span), entries)| Table {
+            header,
+            span,
+            entries,
+        });
+
+    entries
+        .then(table.repeated().collect())
+        .then_ignore(blank.repeated())
+        .then_ignore(ws.then(comment().or_not()))
+        .map(|(root, tables)| Document { root, tables })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_value(src: &str) -> Result<Value<'_>, Vec<Rich<'_, char>>> {
+        value().parse(src).into_result()
+    }
+
+    #[test]
+    fn scalars() {
+        assert_eq!(parse_value("42"), Ok(Value::Integer(42)));
+        assert_eq!(parse_value("-7"), Ok(Value::Integer(-7)));
+        assert_eq!(parse_value("+1.5e2"), Ok(Value::Float(150.0)));
+        assert_eq!(parse_value("true"), Ok(Value::Boolean(true)));
+        assert!(parse_value("99999999999999999999").is_err());
+    }
+
+    #[test]
+    fn strings() {
+        assert!(matches!(
+            parse_value(r#""plain""#),
+            Ok(Value::String(Cow::Borrowed("plain")))
+        ));
+        assert_eq!(
+            parse_value(r#""tab\there\u0021""#),
+            Ok(Value::String(Cow::Owned("tab\there!".into())))
+        );
+        assert_eq!(
+            parse_value(r"'C:\path'"),
+            Ok(Value::String(Cow::Borrowed(r"C:\path")))
+        );
+    }
+
+    #[test]
+    fn document() {
+        let doc = toml()
+            .parse("a.b = 1\n\n[t]\n\"quoted key\" = [1, [2]]\n")
+            .into_result()
+            .unwrap();
+        assert_eq!(
+            doc,
+            Document {
+                root: vec![KeyValue {
+                    key: vec!["a", "b"],
+                    value: Value::Integer(1),
+                    span: (0..7).into(),
+                }],
+                tables: vec![Table {
+                    header: vec!["t"],
+                    span: (9..12).into(),
+                    entries: vec![KeyValue {
+                        key: vec!["quoted key"],
+                        value: Value::Array(vec![
+                            Value::Integer(1),
+                            Value::Array(vec![Value::Integer(2)])
+                        ]),
+                        span: (13..36).into(),
+                    }],
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(toml().parse("key = \n").has_errors());
+        assert!(toml().parse("[unclosed\n").has_errors());
+    }
+}
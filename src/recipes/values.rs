@@ -0,0 +1,367 @@
+//! Parsers for common textual values: semantic versions, IPv4 addresses and RFC 3339 timestamps.
+//!
+//! Each parser follows its specification strictly (rejecting leading zeroes, out-of-range fields, impossible dates,
+//! etc.) and produces a structured value. Free-form parts of a value, such as the pre-release identifiers of a
+//! version, are borrowed directly from the input.
+
+use alloc::format;
+
+use crate::prelude::*;
+
+/// A semantic version, as described by [Semantic Versioning 2.0.0](https://semver.org/).
+///
+/// Note that the derived equality compares build metadata, which the specification ignores when determining
+/// precedence.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Version<'a> {
+    /// The major version.
+    pub major: u64,
+    /// The minor version.
+    pub minor: u64,
+    /// The patch version.
+    pub patch: u64,
+    /// The dot-separated pre-release identifiers (e.g: `alpha.1`), if any.
+    pub pre: Option<&'a str>,
+    /// The dot-separated build metadata identifiers (e.g: `exp.sha.5114f85`), if any.
+    pub build: Option<&'a str>,
+}
+
+/// A parser for a semantic version, as described by [Semantic Versioning 2.0.0](https://semver.org/).
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// use chumsky::recipes::values::{semver, Version};
+///
+/// assert_eq!(
+///     semver().parse("1.0.0-alpha.1+exp.sha.5114f85").into_result(),
+///     Ok(Version { major: 1, minor: 0, patch: 0, pre: Some("alpha.1"), build: Some("exp.sha.5114f85") }),
+/// );
+/// // Numeric identifiers may not have leading zeroes
+/// assert!(semver().parse("1.02.0").has_errors());
+/// assert!(semver().parse("1.0.0-alpha.01").has_errors());
+/// ```
+pub fn semver<'a>() -> impl Parser<'a, &'a str, Version<'a>, extra::Err<Rich<'a, char>>> + Clone {
+    let number = text::int(10).try_map(|s: &str, span| {
+        s.parse::<u64>()
+            .map_err(|_| Rich::custom(span, "version number is too large"))
+    });
+
+    let ident = any()
+        .filter(|c: &char| c.is_ascii_alphanumeric() || *c == '-')
+        .repeated()
+        .at_least(1)
+        .slice();
+
+    let pre_ident = ident.try_map(|s: &str, span| {
+        if s.len() > 1 && s.starts_with('0') && s.bytes().all(|b| b.is_ascii_digit()) {
+            Err(Rich::custom(
+                span,
+                "numeric identifiers must not have leading zeroes",
+            ))
+        } else {
+            Ok(s)
+        }
+    });
+
+    let pre = just('-').ignore_then(
+        pre_ident
+            .separated_by(just('.'))
+            .at_least(1)
+            .collect_string(),
+    );
+    let build = just('+').ignore_then(ident.separated_by(just('.')).at_least(1).collect_string());
+
+    number
+        .then_ignore(just('.'))
+        .then(number)
+        .then_ignore(just('.'))
+        .then(number)
+        .then(pre.or_not())
+        .then(build.or_not())
+        .map(|((((major, minor), patch), pre), build)| Version {
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+        })
+}
+
+/// A parser for an IPv4 address in dotted-decimal notation, producing its octets.
+///
+/// Octets may not have leading zeroes, since these are interpreted as octal by some (but not all) other parsers. The
+/// octets can be converted into an address with `std::net::Ipv4Addr::from`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// use chumsky::recipes::values::ipv4;
+///
+/// assert_eq!(ipv4().parse("192.168.0.1").into_result(), Ok([192, 168, 0, 1]));
+/// assert!(ipv4().parse("256.0.0.1").has_errors());
+/// assert!(ipv4().parse("10.0.0.01").has_errors());
+/// assert!(ipv4().parse("10.0.1").has_errors());
+/// ```
+pub fn ipv4<'a>() -> impl Parser<'a, &'a str, [u8; 4], extra::Err<Rich<'a, char>>> + Clone {
+    let octet = text::int(10).try_map(|s: &str, span| {
+        s.parse::<u8>()
+            .map_err(|_| Rich::custom(span, "octet must be between 0 and 255"))
+    });
+
+    octet.separated_by(just('.')).exactly(4).collect_exactly()
+}
+
+/// A calendar date, as found in an RFC 3339 timestamp.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    /// The year, between 0 and 9999.
+    pub year: u16,
+    /// The month, between 1 and 12.
+    pub month: u8,
+    /// The day of the month, starting at 1.
+    pub day: u8,
+}
+
+/// A time of day, as found in an RFC 3339 timestamp.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time {
+    /// The hour, between 0 and 23.
+    pub hour: u8,
+    /// The minute, between 0 and 59.
+    pub minute: u8,
+    /// The second, between 0 and 60 (to allow for leap seconds).
+    pub second: u8,
+    /// The fraction of the second, in nanoseconds. Digits beyond nanosecond precision are discarded.
+    pub nanosecond: u32,
+}
+
+/// A timestamp, as described by [RFC 3339](https://www.rfc-editor.org/rfc/rfc3339).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DateTime {
+    /// The date, in local time.
+    pub date: Date,
+    /// The time of day, in local time.
+    pub time: Time,
+    /// The offset of local time from UTC, in minutes. An unknown offset (`-00:00`) is treated as UTC.
+    pub offset: i16,
+}
+
+fn is_leap_year(year: u16) -> bool {
+    match (year % 4, year % 100, year % 400) {
+        (_, _, 0) => true,
+        (_, 0, _) => false,
+        (0, _, _) => true,
+        _ => false,
+    }
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// A parser for exactly `n` decimal digits.
+fn fixed_digits<'a>(n: usize) -> impl Parser<'a, &'a str, u32, extra::Err<Rich<'a, char>>> + Clone {
+    text::digits(10)
+        .exactly(n)
+        .slice()
+        .map(|s: &str| s.parse().unwrap())
+}
+
+/// A parser for `n` decimal digits with a value no greater than `max`.
+fn bounded<'a>(
+    n: usize,
+    max: u32,
+    what: &'static str,
+) -> impl Parser<'a, &'a str, u8, extra::Err<Rich<'a, char>>> + Clone {
+    fixed_digits(n).try_map(move |x, span| {
+        if x <= max {
+            Ok(x as u8)
+        } else {
+            Err(Rich::custom(span, format!("invalid {what}")))
+        }
+    })
+}
+
+/// A parser for a timestamp, as described by [RFC 3339](https://www.rfc-editor.org/rfc/rfc3339).
+///
+/// Both the date and the time are validated: impossible dates (such as February 30th, or February 29th of a year
+/// that is not a leap year) are rejected. A second of `60` is permitted, to allow for leap seconds.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// use chumsky::recipes::values::{datetime_rfc3339, Date, DateTime, Time};
+///
+/// assert_eq!(
+///     datetime_rfc3339().parse("1996-12-19T16:39:57.5-08:00").into_result(),
+///     Ok(DateTime {
+///         date: Date { year: 1996, month: 12, day: 19 },
+///         time: Time { hour: 16, minute: 39, second: 57, nanosecond: 500_000_000 },
+///         offset: -8 * 60,
+///     }),
+/// );
+/// assert!(datetime_rfc3339().parse("2023-02-29T00:00:00Z").has_errors());
+/// assert!(datetime_rfc3339().parse("2023-01-01T24:00:00Z").has_errors());
+/// ```
+pub fn datetime_rfc3339<'a>(
+) -> impl Parser<'a, &'a str, DateTime, extra::Err<Rich<'a, char>>> + Clone {
+    let date = fixed_digits(4)
+        .then_ignore(just('-'))
+        .then(bounded(2, 12, "month"))
+        .then_ignore(just('-'))
+        .then(fixed_digits(2))
+        .try_map(|((year, month), day), span| {
+            let year = year as u16;
+            if month == 0 {
+                Err(Rich::custom(span, "invalid month"))
+            } else if day == 0 || day > days_in_month(year, month) as u32 {
+                Err(Rich::custom(span, "invalid day of month"))
+            } else {
+                Ok(Date {
+                    year,
+                    month,
+                    day: day as u8,
+                })
+            }
+        });
+
+    let fraction = just('.')
+        .ignore_then(text::digits(10).at_least(1).slice())
+        .map(|s: &str| {
+            s.bytes()
+                .chain(core::iter::repeat(b'0'))
+                .take(9)
+                .fold(0, |n, b| n * 10 + (b - b'0') as u32)
+        });
+
+    let time = bounded(2, 23, "hour")
+        .then_ignore(just(':'))
+        .then(bounded(2, 59, "minute"))
+        .then_ignore(just(':'))
+        .then(bounded(2, 60, "second"))
+        .then(fraction.or_not())
+        .map(|(((hour, minute), second), nanosecond)| Time {
+            hour,
+            minute,
+            second,
+            nanosecond: nanosecond.unwrap_or(0),
+        });
+
+    let numeric_offset = one_of("+-")
+        .then(bounded(2, 23, "offset hour"))
+        .then_ignore(just(':'))
+        .then(bounded(2, 59, "offset minute"))
+        .map(|((sign, hour), minute)| {
+            let offset = hour as i16 * 60 + minute as i16;
+            if sign == '-' {
+                -offset
+            } else {
+                offset
+            }
+        });
+    let offset = one_of("Zz").to(0).or(numeric_offset);
+
+    date.then_ignore(one_of("Tt"))
+        .then(time)
+        .then(offset)
+        .map(|((date, time), offset)| DateTime { date, time, offset })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn semver_precedence_examples() {
+        // Every example from the precedence section of the specification
+        for version in [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "2.0.0",
+            "2.1.0",
+            "2.1.1",
+        ] {
+            assert!(!semver().parse(version).has_errors(), "{version}");
+        }
+        for version in [
+            "1",
+            "1.0",
+            "1.0.0-",
+            "1.0.0+",
+            "1.0.0-a..b",
+            "v1.0.0",
+            "1.0.0-Ω",
+        ] {
+            assert!(semver().parse(version).has_errors(), "{version}");
+        }
+        assert!(semver().parse("18446744073709551616.0.0").has_errors());
+    }
+
+    #[test]
+    fn ipv4_bounds() {
+        assert_eq!(ipv4().parse("0.0.0.0").into_result(), Ok([0; 4]));
+        assert_eq!(ipv4().parse("255.255.255.255").into_result(), Ok([255; 4]));
+        for addr in ["1.2.3.4.5", "1.2.3.", "1..2.3", "1.2.3.-4", "a.b.c.d", ""] {
+            assert!(ipv4().parse(addr).has_errors(), "{addr}");
+        }
+    }
+
+    #[test]
+    fn datetime_validation() {
+        let dt = datetime_rfc3339()
+            .parse("2000-02-29t23:59:60.123456789123Z")
+            .into_result()
+            .unwrap();
+        assert_eq!(
+            dt.date,
+            Date {
+                year: 2000,
+                month: 2,
+                day: 29
+            }
+        );
+        assert_eq!(dt.time.second, 60);
+        assert_eq!(dt.time.nanosecond, 123_456_789);
+        assert_eq!(dt.offset, 0);
+
+        assert_eq!(
+            datetime_rfc3339()
+                .parse("1937-01-01T12:00:27.87+00:20")
+                .into_result()
+                .map(|dt| (dt.time.nanosecond, dt.offset)),
+            Ok((870_000_000, 20)),
+        );
+
+        for dt in [
+            "1900-02-29T00:00:00Z",
+            "2000-04-31T00:00:00Z",
+            "2000-00-01T00:00:00Z",
+            "2000-13-01T00:00:00Z",
+            "2000-01-00T00:00:00Z",
+            "2000-01-01T00:60:00Z",
+            "2000-01-01T00:00:61Z",
+            "2000-01-01T00:00:00.Z",
+            "2000-01-01T00:00:00",
+            "2000-01-01T00:00:00+24:00",
+            "2000-01-01 00:00:00Z",
+            "20000-01-01T00:00:00Z",
+        ] {
+            assert!(datetime_rfc3339().parse(dt).has_errors(), "{dt}");
+        }
+    }
+}
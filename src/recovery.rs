@@ -40,6 +40,7 @@ where
         _parser: &P,
     ) -> PResult<M, O> {
         let alt = inp.errors.alt.take().expect("error but no alt?");
+        let before = inp.offset();
         let out = match self.0.go::<M>(inp) {
             Ok(out) => out,
             Err(()) => {
@@ -47,11 +48,37 @@ where
                 return Err(());
             }
         };
-        inp.emit(inp.offset, alt.err);
+        inp.emit_recovered(before..inp.offset(), alt.err);
         Ok(out)
     }
 }
 
+/// Statistics about the error recovery that took place during a parse. See [`ParseResult::recovery`].
+///
+/// Only successful recoveries that contributed to the final result are counted: recoveries performed while parsing a
+/// branch that was later backtracked out of are discarded along with the errors they emitted.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RecoveryStats {
+    pub(crate) recoveries: usize,
+    pub(crate) skipped: usize,
+}
+
+impl RecoveryStats {
+    /// The number of times that a recovery strategy successfully recovered from an error.
+    pub fn recoveries(&self) -> usize {
+        self.recoveries
+    }
+
+    /// The total number of tokens that were skipped or consumed by recovery strategies.
+    ///
+    /// For [`via_parser`], this is the number of tokens consumed by the recovery parser. For [`skip_until`], it is the
+    /// number of tokens skipped, including those matched by `until`. For [`skip_then_retry_until`], it is the number of
+    /// tokens skipped before the original parser was successfully retried.
+    pub fn skipped_tokens(&self) -> usize {
+        self.skipped
+    }
+}
+
 /// See [`Parser::recover_with`].
 #[derive(Copy, Clone)]
 pub struct RecoverWith<A, S> {
@@ -84,6 +111,10 @@ where
         }
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
@@ -109,6 +140,7 @@ where
         parser: &P,
     ) -> PResult<M, O> {
         let alt = inp.errors.alt.take().expect("error but no alt?");
+        let start = inp.offset();
         loop {
             let before = inp.save();
             if let Ok(()) = self.until.go::<Check>(inp) {
@@ -130,7 +162,7 @@ where
                     .secondary_errors_since(before.err_count)
                     .is_empty()
             }) {
-                inp.emit(inp.offset, alt.err);
+                inp.emit_recovered(start..before.offset(), alt.err);
                 break Ok(out);
             } else {
                 inp.errors.alt.take();
@@ -169,10 +201,11 @@ where
         _parser: &P,
     ) -> PResult<M, O> {
         let alt = inp.errors.alt.take().expect("error but no alt?");
+        let start = inp.offset();
         loop {
             let before = inp.save();
             if let Ok(()) = self.until.go::<Check>(inp) {
-                inp.emit(inp.offset, alt.err);
+                inp.emit_recovered(start..inp.offset(), alt.err);
                 break Ok(M::bind(|| (self.fallback)()));
             }
             inp.rewind(before);
@@ -68,12 +68,12 @@ enum RecursiveInner<T: ?Sized> {
 
 /// Type for recursive parsers that are defined through a call to `recursive`, and as such
 /// need no internal indirection
-pub type Direct<'a, 'b, I, O, Extra> = DynParser<'a, 'b, I, O, Extra>;
+pub type Direct<'a, 'b, I, O, Extra> = ParserObj<'a, 'b, I, O, Extra>;
 
 /// Type for recursive parsers that are defined through a call to [`Recursive::declare`], and as
 /// such require an additional layer of allocation.
 pub struct Indirect<'a, 'b, I: Input<'a>, O, Extra: ParserExtra<'a, I>> {
-    inner: OnceCell<Box<DynParser<'a, 'b, I, O, Extra>>>,
+    inner: OnceCell<Box<ParserObj<'a, 'b, I, O, Extra>>>,
 }
 
 /// A parser that can be defined in terms of itself by separating its [declaration](Recursive::declare) from its
@@ -198,6 +198,15 @@ where
         })
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        let parser = self.parser();
+        walker.recursive::<Self>(RefC::as_ptr(&parser) as *const () as usize, |walker| {
+            if let Some(inner) = parser.inner.get() {
+                inner.walk_node(walker);
+            }
+        });
+    }
+
     go_extra!(O);
 }
 
@@ -211,6 +220,13 @@ where
         recurse(move || M::invoke(&*self.parser(), inp))
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        let parser = self.parser();
+        walker.recursive::<Self>(RefC::as_ptr(&parser) as *const () as usize, |walker| {
+            parser.walk_node(walker);
+        });
+    }
+
     go_extra!(O);
 }
 
@@ -272,7 +288,7 @@ where
     F: FnOnce(Recursive<Direct<'a, 'b, I, O, E>>) -> A,
 {
     let rc = RefC::new_cyclic(|rc| {
-        let rc: RefW<DynParser<'a, 'b, I, O, E>> = rc.clone() as _;
+        let rc: RefW<ParserObj<'a, 'b, I, O, E>> = rc.clone() as _;
         let parser = Recursive {
             inner: RecursiveInner::Unowned(rc.clone()),
         };
@@ -0,0 +1,293 @@
+//! Lexing text again after it has been edited, for editors and language servers that lex the same file many times.
+//!
+//! An edit usually only changes the tokens close to it, so lexing a large file from scratch after every keystroke
+//! wastes most of its effort. [`Tokens`] holds the tokens of a text, along with their spans. When the text is edited,
+//! [`Tokens::relex`] lexes just the region around the edit, stopping as soon as the new tokens line up with the old
+//! ones again, and splices the new tokens in place of the old. The result can be parsed like any other list of
+//! spanned tokens, using [`Input::spanned`].
+//!
+//! Tokens are lexed one at a time by two parsers: `token`, which parses a single token, and `trivia`, which skips
+//! whatever may come before each token, such as whitespace and comments. Input that `token` fails to parse is
+//! skipped one character at a time, producing an error for each failure.
+//!
+//! Lexing again gives the same tokens as lexing the new text from scratch, provided that the lexer is context-free:
+//! how the text following a token is lexed must not depend on the text before it, and where a token ends must not
+//! depend on the text beyond the character that follows it. Almost all lexers satisfy this.
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, relex::{TextEdit, Tokens}};
+//! #[derive(Clone, Debug, PartialEq)]
+//! enum Token {
+//!     Num(u64),
+//!     Ident(String),
+//!     Op(char),
+//! }
+//!
+//! fn token<'a>() -> impl Parser<'a, &'a str, Token, extra::Err<Rich<'a, char>>> {
+//!     choice((
+//!         text::int(10).from_str().unwrapped().map(Token::Num),
+//!         text::ascii::ident().map(|s: &str| Token::Ident(s.to_string())),
+//!         one_of("+-*/=").map(Token::Op),
+//!     ))
+//! }
+//!
+//! let mut src = String::from("x = 1 + 2");
+//! let (mut tokens, errs) = Tokens::lex(&token(), &text::whitespace(), &src);
+//! assert!(errs.is_empty());
+//! assert_eq!(tokens.len(), 5);
+//!
+//! // Replace `1` with `10 * y`
+//! src.replace_range(4..5, "10 * y");
+//! let relexed = tokens.relex(&token(), &text::whitespace(), &src, &TextEdit::new(4..5, 6));
+//!
+//! // Only the tokens in place of `1` were lexed again
+//! assert_eq!(relexed.tokens(), 2..5);
+//! assert_eq!(relexed.removed(), 1);
+//! assert_eq!(tokens.as_slice()[6], (Token::Num(2), SimpleSpan::from(13..14)));
+//!
+//! // The tokens are ready to be parsed
+//! let eoi = SimpleSpan::from(src.len()..src.len());
+//! let assign = select! { Token::Ident(x) => x }
+//!     .then_ignore(just(Token::Op('=')))
+//!     .then(any::<_, extra::Default>().repeated().count());
+//! assert_eq!(
+//!     assign.parse(tokens.as_slice().spanned(eoi)).into_result(),
+//!     Ok(("x".to_string(), 5)),
+//! );
+//! ```
+
+use super::*;
+
+/// A change to a text, replacing a range of bytes with new text.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TextEdit {
+    range: Range<usize>,
+    len: usize,
+}
+
+impl TextEdit {
+    /// An edit that replaces the bytes of the old text in `range` with `len` bytes of new text.
+    pub fn new(range: Range<usize>, len: usize) -> Self {
+        Self { range, len }
+    }
+
+    /// An edit that inserts `len` bytes of new text at byte offset `at`.
+    pub fn insert(at: usize, len: usize) -> Self {
+        Self::new(at..at, len)
+    }
+
+    /// An edit that deletes the bytes of the old text in `range`.
+    pub fn delete(range: Range<usize>) -> Self {
+        Self::new(range, 0)
+    }
+
+    /// The range of bytes in the old text that were replaced.
+    pub fn old_range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// The range of bytes in the new text that replaced them.
+    pub fn new_range(&self) -> Range<usize> {
+        self.range.start..self.range.start + self.len
+    }
+
+    // Map an offset in the old text at or after the end of the edit to the same position in the new text
+    fn shift(&self, offset: usize) -> usize {
+        offset - self.range.end + self.range.start + self.len
+    }
+}
+
+/// The tokens of a text, along with their spans, which can be kept up to date as the text is edited. See the
+/// [module-level documentation](self) for more information.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Tokens<T> {
+    tokens: Vec<(T, SimpleSpan)>,
+}
+
+impl<T> Tokens<T> {
+    /// Lex the whole of `src`, returning its tokens and any errors produced while lexing it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `token` succeeds without consuming any input.
+    pub fn lex<'a, P, Tr, OT, E>(token: &P, trivia: &Tr, src: &'a str) -> (Self, Vec<E::Error>)
+    where
+        P: Parser<'a, &'a str, T, E>,
+        Tr: Parser<'a, &'a str, OT, E>,
+        E: ParserExtra<'a, &'a str>,
+        E::State: Default,
+        E::Context: Default,
+    {
+        let (tokens, errors) = lex_from(token, trivia, src, 0, |_| false);
+        (Self { tokens }, errors)
+    }
+
+    /// Update the tokens after the text they were lexed from has been edited, given the new text, `src`.
+    ///
+    /// Tokens are lexed again from the end of the last token that the edit could not have changed, until the new tokens
+    /// line up with the old tokens that follow the edit, which are kept with their spans moved to match the new text.
+    /// The errors of the result are those produced while lexing again; errors produced earlier for the tokens that were
+    /// replaced are no longer relevant.
+    ///
+    /// The tokens must have been lexed from the text before the edit by the same `token` and `trivia` parsers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `token` succeeds without consuming any input.
+    pub fn relex<'a, P, Tr, OT, E>(
+        &mut self,
+        token: &P,
+        trivia: &Tr,
+        src: &'a str,
+        edit: &TextEdit,
+    ) -> Relexed<E::Error>
+    where
+        P: Parser<'a, &'a str, T, E>,
+        Tr: Parser<'a, &'a str, OT, E>,
+        E: ParserExtra<'a, &'a str>,
+        E::State: Default,
+        E::Context: Default,
+    {
+        let old = edit.old_range();
+        let new = edit.new_range();
+
+        // A token that ends before the edit is separated from it by at least one unchanged character, so is unchanged
+        let keep = self
+            .tokens
+            .partition_point(|(_, span)| span.end < old.start);
+        let start = keep.checked_sub(1).map_or(0, |i| self.tokens[i].1.end);
+
+        let old_tokens = &self.tokens[keep..];
+        let mut resume = self.tokens.len();
+        let (relexed, errors) = lex_from(token, trivia, src, start, |offset| {
+            // The text after the end of an old token that follows the edit is unchanged, so it will lex the same way
+            if offset < new.end {
+                return false;
+            }
+            let old_offset = offset - new.end + old.end;
+            match old_tokens.binary_search_by_key(&old_offset, |(_, span)| span.end) {
+                Ok(i) => {
+                    resume = keep + i + 1;
+                    true
+                }
+                Err(_) => false,
+            }
+        });
+
+        for (_, span) in &mut self.tokens[resume..] {
+            *span = SimpleSpan::from(edit.shift(span.start)..edit.shift(span.end));
+        }
+        let inserted = relexed.len();
+        self.tokens.splice(keep..resume, relexed);
+        Relexed {
+            tokens: keep..keep + inserted,
+            removed: resume - keep,
+            errors,
+        }
+    }
+
+    /// The number of tokens.
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Whether there are no tokens.
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// The tokens, along with their spans.
+    pub fn as_slice(&self) -> &[(T, SimpleSpan)] {
+        &self.tokens
+    }
+
+    /// Convert into a [`Vec`] of the tokens, along with their spans.
+    pub fn into_vec(self) -> Vec<(T, SimpleSpan)> {
+        self.tokens
+    }
+}
+
+/// The outcome of updating [`Tokens`] after an edit. See [`Tokens::relex`].
+#[derive(Clone, Debug)]
+pub struct Relexed<E> {
+    tokens: Range<usize>,
+    removed: usize,
+    errors: Vec<E>,
+}
+
+impl<E> Relexed<E> {
+    /// The indices of the tokens that were lexed again.
+    pub fn tokens(&self) -> Range<usize> {
+        self.tokens.clone()
+    }
+
+    /// The number of old tokens that they replaced.
+    pub fn removed(&self) -> usize {
+        self.removed
+    }
+
+    /// The errors produced while lexing again, sorted by the position at which they occurred.
+    pub fn errors(&self) -> &[E] {
+        &self.errors
+    }
+
+    /// Convert into the errors produced while lexing again.
+    pub fn into_errors(self) -> Vec<E> {
+        self.errors
+    }
+}
+
+/// Lex tokens starting at `offset`, until the end of the input or until `stop` returns `true` for the offset at which
+/// the next token would be lexed.
+fn lex_from<'a, T, P, Tr, OT, E>(
+    token: &P,
+    trivia: &Tr,
+    src: &'a str,
+    mut offset: usize,
+    mut stop: impl FnMut(usize) -> bool,
+) -> (Vec<(T, SimpleSpan)>, Vec<E::Error>)
+where
+    P: Parser<'a, &'a str, T, E>,
+    Tr: Parser<'a, &'a str, OT, E>,
+    E: ParserExtra<'a, &'a str>,
+    E::State: Default,
+    E::Context: Default,
+{
+    let mut own = InputOwn::<&'a str, E>::new(src);
+    let mut tokens = Vec::new();
+    while !stop(offset) {
+        let mut inp = own.as_ref_at(offset);
+        let before = inp.save();
+        if trivia.go::<Check>(&mut inp).is_err() {
+            inp.errors.alt = None;
+            inp.rewind(before);
+        }
+        if inp.peek_maybe().is_none() {
+            break;
+        }
+
+        let before = inp.save();
+        let start = inp.offset;
+        match token.go::<Emit>(&mut inp) {
+            Ok(tok) => {
+                assert!(
+                    inp.offset > start,
+                    "token parser made no progress at offset {}",
+                    start,
+                );
+                tokens.push((tok, SimpleSpan::from(start..inp.offset)));
+            }
+            Err(()) => {
+                let alt = inp.errors.alt.take().expect("error but no alt?");
+                inp.rewind(before);
+                inp.errors.secondary.push(alt);
+                // Skip a character, so that lexing continues with whatever follows it
+                inp.next_maybe();
+            }
+        }
+        offset = inp.offset;
+    }
+    (tokens, own.take_errs())
+}
@@ -5,6 +5,7 @@
 //! You can use the [`Span`] trait to connect up chumsky to your compiler's knowledge of the input source.
 
 use super::*;
+use core::ops::{Add, Sub};
 
 /// A trait that describes a span over a particular range of inputs.
 ///
@@ -43,6 +44,143 @@ pub trait Span {
 
     /// Return the end offset of the span.
     fn end(&self) -> Self::Offset;
+
+    /// Return the smallest span that covers both this span and `other`, including any gap between them.
+    ///
+    /// This is useful for building the span of an AST node out of the spans of its children. The context of the
+    /// resulting span is taken from `self`: combining spans from different contexts (such as different files) is
+    /// unlikely to produce a meaningful result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::span::{Span, SimpleSpan};
+    /// let lhs = SimpleSpan::from(4..7);
+    /// let rhs = SimpleSpan::from(10..12);
+    /// assert_eq!(lhs.union(&rhs), SimpleSpan::from(4..12));
+    /// assert_eq!(rhs.union(&lhs), SimpleSpan::from(4..12));
+    /// ```
+    fn union(&self, other: &Self) -> Self
+    where
+        Self: Sized,
+        Self::Offset: Ord,
+    {
+        Self::new(
+            self.context(),
+            self.start().min(other.start())..self.end().max(other.end()),
+        )
+    }
+
+    /// Convert this span, assumed to contain byte offsets into `src`, into a range of `char` indices.
+    ///
+    /// Spans produced when parsing a [`&str`] are byte offsets, which is what you want for slicing the source string.
+    /// Some tools instead expect positions to be counted in `char`s.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either end of the span is out of bounds or does not lie on a `char` boundary in `src`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::span::{Span, SimpleSpan};
+    /// let src = "λx. x";
+    /// // 'x' is the 2nd char, but starts at byte 2 since 'λ' is 2 bytes long
+    /// assert_eq!(SimpleSpan::from(2..3).to_char_range(src), 1..2);
+    /// ```
+    fn to_char_range(&self, src: &str) -> Range<usize>
+    where
+        Self: Span<Offset = usize>,
+    {
+        let start = src[..self.start()].chars().count();
+        start..start + src[self.start()..self.end()].chars().count()
+    }
+
+    /// Convert this span, assumed to contain byte offsets into `src`, into a range of UTF-16 code unit indices.
+    ///
+    /// This is the unit used by many editor protocols, such as the Language Server Protocol.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either end of the span is out of bounds or does not lie on a `char` boundary in `src`.
+    fn to_utf16_range(&self, src: &str) -> Range<usize>
+    where
+        Self: Span<Offset = usize>,
+    {
+        let start = utf16_len(&src[..self.start()]);
+        start..start + utf16_len(&src[self.start()..self.end()])
+    }
+
+    /// Shorten this span, assumed to contain byte offsets into `src`, so that it covers at most `max_len` `char`s.
+    ///
+    /// The start of the span is kept. This is useful when reporting errors whose spans can be very long, such as an
+    /// unterminated string literal that runs to the end of the input, so that only the start of the span is
+    /// highlighted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either end of the span is out of bounds or does not lie on a `char` boundary in `src`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::span::{Span, SimpleSpan};
+    /// let src = "\"λ unterminated";
+    /// assert_eq!(SimpleSpan::from(0..src.len()).clamp_len(src, 3), SimpleSpan::from(0..4));
+    /// assert_eq!(SimpleSpan::from(0..4).clamp_len(src, 10), SimpleSpan::from(0..4));
+    /// ```
+    fn clamp_len(&self, src: &str, max_len: usize) -> Self
+    where
+        Self: Span<Offset = usize> + Sized,
+    {
+        let end = src[self.start()..self.end()]
+            .char_indices()
+            .nth(max_len)
+            .map_or(self.end(), |(i, _)| self.start() + i);
+        Self::new(self.context(), self.start()..end)
+    }
+
+    /// Split this span, assumed to contain byte offsets into `src`, into one span for each line that it covers.
+    ///
+    /// Line terminators (`'\n'` or `"\r\n"`) are not included in any of the resulting spans, and a span that ends with
+    /// a line terminator does not produce an empty span for the following line. An empty span produces a single empty
+    /// span. This is useful when rendering errors, since most renderers can only underline a single line at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either end of the span is out of bounds or does not lie on a `char` boundary in `src`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::span::{Span, SimpleSpan};
+    /// let src = "let s = \"a\r\nb\nc";
+    /// assert_eq!(
+    ///     SimpleSpan::from(8..src.len()).split_lines(src),
+    ///     vec![SimpleSpan::from(8..10), SimpleSpan::from(12..13), SimpleSpan::from(14..15)],
+    /// );
+    /// ```
+    fn split_lines(&self, src: &str) -> Vec<Self>
+    where
+        Self: Span<Offset = usize> + Sized,
+    {
+        let mut lines = Vec::new();
+        let mut start = self.start();
+        for (i, _) in src[self.start()..self.end()].match_indices('\n') {
+            let newline = self.start() + i;
+            let end = newline - src[start..newline].ends_with('\r') as usize;
+            lines.push(Self::new(self.context(), start..end));
+            start = newline + 1;
+        }
+        if start < self.end() || lines.is_empty() {
+            lines.push(Self::new(self.context(), start..self.end()));
+        }
+        lines
+    }
+}
+
+fn utf16_len(s: &str) -> usize {
+    s.chars().map(char::len_utf16).sum()
 }
 
 /// The most basic implementor of `Span` - akin to `Range`, but `Copy` since it's not also
@@ -74,10 +212,82 @@ impl<T> SimpleSpan<T> {
     {
         Self::new(offset.clone(), offset)
     }
+}
+
+impl<T, C> SimpleSpan<T, C> {
+    /// Replace the context of this span, keeping its offsets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::span::{Span, SimpleSpan};
+    /// let span = SimpleSpan::new(3, 7).with_context("main.rs");
+    /// assert_eq!(span.context(), "main.rs");
+    /// assert_eq!(span.start(), 3);
+    /// ```
+    pub fn with_context<D>(self, context: D) -> SimpleSpan<T, D> {
+        SimpleSpan {
+            start: self.start,
+            end: self.end,
+            context,
+        }
+    }
 
-    /// Convert this span into a [`std::ops::Range`].
+    /// Get a reference to the context of this span, without cloning it like [`Span::context`] does.
+    pub fn context_ref(&self) -> &C {
+        &self.context
+    }
+
+    /// Convert this span into a [`std::ops::Range`], discarding its context.
     pub fn into_range(self) -> Range<T> {
-        self.into()
+        self.start..self.end
+    }
+
+    /// Get the number of offsets that this span covers.
+    pub fn len(&self) -> T::Output
+    where
+        T: Copy + Sub,
+    {
+        self.end - self.start
+    }
+
+    /// Returns `true` if this span covers no offsets.
+    pub fn is_empty(&self) -> bool
+    where
+        T: PartialOrd,
+    {
+        self.start >= self.end
+    }
+
+    /// Returns `true` if the given offset lies within this span.
+    pub fn contains(&self, offset: &T) -> bool
+    where
+        T: PartialOrd,
+    {
+        &self.start <= offset && offset < &self.end
+    }
+
+    /// Move both ends of this span forward by the given amount, such as when the input being parsed was taken
+    /// from part way through a larger source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::span::SimpleSpan;
+    /// let span = SimpleSpan::new(3, 7);
+    /// assert_eq!(span.offset_by(10), SimpleSpan::new(13, 17));
+    /// assert_eq!(span.len(), 4);
+    /// assert!(span.contains(&6) && !span.contains(&7));
+    /// ```
+    pub fn offset_by(self, amount: T) -> Self
+    where
+        T: Copy + Add<Output = T>,
+    {
+        SimpleSpan {
+            start: self.start + amount,
+            end: self.end + amount,
+            context: self.context,
+        }
     }
 }
 
@@ -91,12 +301,9 @@ impl<T> From<Range<T>> for SimpleSpan<T> {
     }
 }
 
-impl<T> From<SimpleSpan<T>> for Range<T> {
-    fn from(span: SimpleSpan<T>) -> Self {
-        Range {
-            start: span.start,
-            end: span.end,
-        }
+impl<T, C> From<SimpleSpan<T, C>> for Range<T> {
+    fn from(span: SimpleSpan<T, C>) -> Self {
+        span.into_range()
     }
 }
 
@@ -185,3 +392,236 @@ impl<T: Clone> Span for Range<T> {
         self.end.clone()
     }
 }
+
+/// An index of the lines in a source string, used to convert byte offsets into line and column positions (and back).
+///
+/// Spans produced when parsing a [`&str`] are byte offsets. Diagnostics and editor integrations usually want
+/// line/column positions instead, with columns counted in either `char`s or (for protocols such as the Language Server
+/// Protocol) UTF-16 code units. Building a `LineIndex` once makes these conversions cheap.
+///
+/// Lines are separated by `'\n'` and both lines and columns are zero-based. A `'\r'` preceding a `'\n'` is treated as
+/// the last character of its line.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::span::LineIndex;
+/// let src = "let a = 1;\nlet 𝕓 = a;";
+/// let index = LineIndex::new(src);
+///
+/// let b = src.find('𝕓').unwrap();
+/// let eq = src.rfind('=').unwrap();
+///
+/// assert_eq!(index.line_col(b), (1, 4));
+/// // '𝕓' is a single char, but two UTF-16 code units
+/// assert_eq!(index.line_col(eq), (1, 6));
+/// assert_eq!(index.line_col_utf16(eq), (1, 7));
+///
+/// assert_eq!(index.offset(1, 6), Some(eq));
+/// assert_eq!(index.offset_utf16(1, 7), Some(eq));
+/// ```
+#[derive(Clone, Debug)]
+pub struct LineIndex<'a> {
+    src: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    /// Create a new index of the lines in `src`.
+    pub fn new(src: &'a str) -> Self {
+        let line_starts = core::iter::once(0)
+            .chain(src.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { src, line_starts }
+    }
+
+    /// Get the number of lines in the source. This is always at least 1.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Get the byte range of the given line, including its line terminator (if any).
+    pub fn line_span(&self, line: usize) -> Option<Range<usize>> {
+        let start = *self.line_starts.get(line)?;
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .copied()
+            .unwrap_or(self.src.len());
+        Some(start..end)
+    }
+
+    /// Get the line that the given byte offset falls within.
+    ///
+    /// Offsets at or beyond the end of the source are considered to be on the last line.
+    pub fn line(&self, offset: usize) -> usize {
+        match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next) => next - 1,
+        }
+    }
+
+    /// Convert a byte offset into a line and a column, counted in `char`s.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset is out of bounds or does not lie on a `char` boundary.
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let line = self.line(offset);
+        let col = self.src[self.line_starts[line]..offset].chars().count();
+        (line, col)
+    }
+
+    /// Convert a byte offset into a line and a column, counted in UTF-16 code units.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset is out of bounds or does not lie on a `char` boundary.
+    pub fn line_col_utf16(&self, offset: usize) -> (usize, usize) {
+        let line = self.line(offset);
+        let col = utf16_len(&self.src[self.line_starts[line]..offset]);
+        (line, col)
+    }
+
+    /// Convert a line and a column, counted in `char`s, into a byte offset.
+    ///
+    /// Returns `None` if the line does not exist or the column lies beyond the end of the line.
+    pub fn offset(&self, line: usize, col: usize) -> Option<usize> {
+        self.offset_by(line, col, |_| 1)
+    }
+
+    /// Convert a line and a column, counted in UTF-16 code units, into a byte offset.
+    ///
+    /// Returns `None` if the line does not exist, the column lies beyond the end of the line, or the column falls in
+    /// the middle of a `char`.
+    pub fn offset_utf16(&self, line: usize, col: usize) -> Option<usize> {
+        self.offset_by(line, col, char::len_utf16)
+    }
+
+    fn offset_by(&self, line: usize, col: usize, width: impl Fn(char) -> usize) -> Option<usize> {
+        let span = self.line_span(line)?;
+        let mut units = 0;
+        for (i, c) in self.src[span.clone()].char_indices() {
+            if units == col {
+                return Some(span.start + i);
+            } else if units > col {
+                return None;
+            }
+            units += width(c);
+        }
+        (units == col).then_some(span.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn char_and_utf16_ranges() {
+        let src = "a\u{e9}\u{1d4b7}b";
+        // 'b' is at byte 7, char 3, and UTF-16 code unit 4
+        let span = SimpleSpan::from(7..8);
+        assert_eq!(span.to_char_range(src), 3..4);
+        assert_eq!(span.to_utf16_range(src), 4..5);
+        assert_eq!((3..8).to_char_range(src), 2..4);
+        assert_eq!((3..8).to_utf16_range(src), 2..5);
+    }
+
+    #[test]
+    fn clamp_and_split_lines() {
+        let src = "ab\r\n\nc\u{e9}d\n";
+        assert_eq!((0..src.len()).clamp_len(src, 0), 0..0);
+        assert_eq!((5..9).clamp_len(src, 2), 5..8);
+        assert_eq!((5..9).clamp_len(src, 3), 5..9);
+
+        assert_eq!((0..src.len()).split_lines(src), [0..2, 4..4, 5..9]);
+        assert_eq!((1..6).split_lines(src), [1..2, 4..4, 5..6]);
+        assert_eq!(
+            SimpleSpan::splat(3).split_lines(src),
+            [SimpleSpan::splat(3)]
+        );
+        assert_eq!(
+            SimpleSpan::new(4, 5).with_context('f').split_lines(src),
+            [SimpleSpan::new(4, 4).with_context('f')]
+        );
+    }
+
+    #[test]
+    fn line_index() {
+        let src = "ab\r\n\ncd\u{e9}";
+        let index = LineIndex::new(src);
+        assert_eq!(index.line_count(), 3);
+        assert_eq!(index.line_span(0), Some(0..4));
+        assert_eq!(index.line_span(2), Some(5..9));
+        assert_eq!(index.line_span(3), None);
+
+        assert_eq!(index.line_col(0), (0, 0));
+        assert_eq!(index.line_col(2), (0, 2));
+        assert_eq!(index.line_col(4), (1, 0));
+        assert_eq!(index.line_col(9), (2, 3));
+
+        for offset in [0, 1, 2, 3, 4, 5, 6, 7, 9] {
+            let (line, col) = index.line_col(offset);
+            assert_eq!(index.offset(line, col), Some(offset));
+            let (line, col) = index.line_col_utf16(offset);
+            assert_eq!(index.offset_utf16(line, col), Some(offset));
+        }
+
+        // Past the end of a line, or of the source
+        assert_eq!(index.offset(1, 2), None);
+        assert_eq!(index.offset(2, 5), None);
+        assert_eq!(index.offset(3, 0), None);
+    }
+
+    #[test]
+    fn simple_span_helpers() {
+        let span = SimpleSpan::new(2, 5).with_context('f');
+        assert_eq!(span.context_ref(), &'f');
+        assert_eq!(span.len(), 3);
+        assert!(!span.is_empty() && SimpleSpan::splat(4).is_empty());
+        assert!(span.contains(&2) && !span.contains(&5));
+        assert_eq!(span.offset_by(1), SimpleSpan::new(3, 6).with_context('f'));
+        assert_eq!(span.into_range(), 2..5);
+        assert_eq!(span.to_string(), "2..5");
+    }
+
+    #[test]
+    fn union() {
+        let a = SimpleSpan::new(3, 5);
+        assert_eq!(a.union(&SimpleSpan::new(1, 4)), SimpleSpan::new(1, 5));
+        assert_eq!(a.union(&SimpleSpan::new(4, 4)), a);
+        assert_eq!(("a.rs", 3..5).union(&("b.rs", 8..9)), ("a.rs", 3..9));
+    }
+
+    #[test]
+    fn spans_cover_matched_input() {
+        use crate::prelude::*;
+
+        // The rewinds caused by the failed alternative and the trailing lookahead must not affect the span
+        let word = text::ascii::ident::<_, _, extra::Default>();
+        let parser = word
+            .then_ignore(just(':'))
+            .or(word)
+            .then(just('!').or_not())
+            .then_ignore(just(';').rewind())
+            .padded()
+            .with_span_of();
+
+        assert_eq!(
+            parser.lazy().parse(" foo:;").into_output(),
+            Some((("foo", None), SimpleSpan::new(0, 5)))
+        );
+        assert_eq!(
+            parser.lazy().parse(" foo!;x").into_output(),
+            Some((("foo", Some('!')), SimpleSpan::new(0, 5)))
+        );
+        assert_eq!(
+            word.then(word.padded())
+                .map_with_span(|_, span| span)
+                .parse("ab cd")
+                .into_output(),
+            Some(SimpleSpan::new(0, 5))
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use super::*;
 
+use alloc::collections::{BTreeMap, VecDeque};
 use core::cell::Cell;
 
 /// An input that dynamically pulls tokens from an [`Iterator`].
@@ -130,6 +131,226 @@ where
     }
 }
 
+/// An input that pulls tokens from an [`Iterator`] on demand, keeping only a window of them buffered.
+///
+/// Unlike [`Stream`], which retains every token it has ever pulled, a `BufferedInput` can discard tokens that the
+/// parser will never need to rewind to (see [`BufferedInput::discard_before`]). This keeps memory usage bounded when
+/// parsing long token streams with grammars that only need a small amount of backtracking.
+///
+/// Offsets remain absolute (i.e: the offset of a token is its index in the original iterator), so spans generated by
+/// this input are unaffected by discarding.
+///
+/// Tokens can also be discarded automatically as parsing progresses. The input keeps track of every live
+/// [`Checkpoint`](crate::input::Checkpoint) and, whenever one is dropped, discards everything before the oldest one
+/// that remains (or, if there are none left, before the one that was just dropped). Plain
+/// [`Marker`](crate::input::Marker)s, such as those that combinators like [`Parser::or`] rewind to, are not tracked:
+/// see [`InputRef::checkpoint`](crate::input::InputRef::checkpoint) for how to use checkpoints safely.
+///
+/// For a hard limit on memory usage, see [`BufferedInput::with_max_lookahead`].
+pub struct BufferedInput<I: Iterator> {
+    inner: RefCell<BufferedInner<I>>,
+}
+
+struct BufferedInner<I: Iterator> {
+    buffer: VecDeque<I::Item>,
+    // The offset of the first token in `buffer`
+    base: usize,
+    // The number of live checkpoints at each offset
+    checkpoints: BTreeMap<usize, usize>,
+    // The maximum number of tokens to keep in `buffer`, if limited
+    max_lookahead: Option<usize>,
+    iter: I,
+}
+
+impl<I: Iterator> BufferedInput<I> {
+    /// Create a new buffered input from an [`Iterator`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, input::BufferedInput};
+    /// let input = BufferedInput::from_iter("1 22 333".chars());
+    ///
+    /// let parser = text::digits::<_, _, extra::Err<Simple<_>>>(10)
+    ///     .padded()
+    ///     .repeated()
+    ///     .count();
+    ///
+    /// assert_eq!(parser.parse(input).into_result(), Ok(3));
+    /// ```
+    pub fn from_iter<J: IntoIterator<IntoIter = I>>(iter: J) -> Self {
+        Self {
+            inner: RefCell::new(BufferedInner {
+                buffer: VecDeque::new(),
+                base: 0,
+                checkpoints: BTreeMap::new(),
+                max_lookahead: None,
+                iter: iter.into_iter(),
+            }),
+        }
+    }
+
+    /// Create a new buffered input from an [`Iterator`] that never holds more than `k` tokens.
+    ///
+    /// Pulling a new token from the iterator discards the oldest buffered token once `k` tokens are held, regardless
+    /// of any live checkpoints. This guarantees constant memory usage for LL(k) grammars: parsers may look up to
+    /// `k - 1` tokens past the next one with [`InputRef::peek_n`](crate::input::InputRef::peek_n), and may rewind
+    /// within the last `k` tokens read. Peeking further than that is a parse error (see
+    /// [`InputRef::peek_n`](crate::input::InputRef::peek_n)), while rewinding further than that panics, reporting how
+    /// far the parser attempted to go.
+    ///
+    /// These limits are enforced while parsing, not when the parser is built. Whether a combinator such as
+    /// [`Parser::or`] rewinds further than `k` tokens depends on how much input its alternatives consume before they
+    /// fail, which in general depends on the input itself. Test a grammar against representative inputs to be
+    /// confident that it stays within its lookahead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use chumsky::{prelude::*, input::BufferedInput};
+    /// // An LL(1) grammar: each alternative is chosen by its first token
+    /// let item = choice((
+    ///     just::<_, _, extra::Err<Simple<char>>>('a').then(just('b')).to(1),
+    ///     just('c').then(just('d')).to(2),
+    /// ));
+    ///
+    /// let input = BufferedInput::with_max_lookahead("abcdab".chars(), 1);
+    /// assert_eq!(item.repeated().collect::<Vec<_>>().parse(input).into_result(), Ok(vec![1, 2, 1]));
+    /// ```
+    pub fn with_max_lookahead<J: IntoIterator<IntoIter = I>>(iter: J, k: usize) -> Self {
+        assert!(
+            k > 0,
+            "a BufferedInput must be able to hold at least one token"
+        );
+        let input = Self::from_iter(iter);
+        input.inner.borrow_mut().max_lookahead = Some(k);
+        input
+    }
+
+    /// Discard all buffered tokens that come before the given offset.
+    ///
+    /// After calling this, attempting to rewind the input to an offset before `offset` will result in a panic.
+    pub fn discard_before(&self, offset: usize) {
+        let mut inner = self.inner.borrow_mut();
+        let n = offset.saturating_sub(inner.base).min(inner.buffer.len());
+        inner.buffer.drain(..n);
+        inner.base += n;
+    }
+
+    /// Get the number of tokens currently held in the buffer.
+    pub fn buffered(&self) -> usize {
+        self.inner.borrow().buffer.len()
+    }
+}
+
+impl<I: Iterator> Sealed for BufferedInput<I> {}
+impl<'a, I: Iterator + 'a> Input<'a> for BufferedInput<I>
+where
+    I::Item: Clone,
+{
+    type Offset = usize;
+    type Token = I::Item;
+    type Span = SimpleSpan<usize>;
+
+    #[inline(always)]
+    fn start(&self) -> Self::Offset {
+        0
+    }
+
+    type TokenMaybe = I::Item;
+
+    #[inline(always)]
+    unsafe fn next_maybe(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::TokenMaybe>) {
+        self.next(offset)
+    }
+
+    #[inline(always)]
+    unsafe fn span(&self, range: Range<Self::Offset>) -> Self::Span {
+        range.into()
+    }
+
+    #[inline]
+    fn prev(offs: Self::Offset) -> Self::Offset {
+        offs.saturating_sub(1)
+    }
+
+    #[inline]
+    fn checkpoint(&self, offset: Self::Offset) {
+        *self
+            .inner
+            .borrow_mut()
+            .checkpoints
+            .entry(offset)
+            .or_insert(0) += 1;
+    }
+
+    #[inline]
+    fn release_checkpoint(&self, offset: Self::Offset) {
+        let low = {
+            let mut inner = self.inner.borrow_mut();
+            if let Some(count) = inner.checkpoints.get_mut(&offset) {
+                *count -= 1;
+                if *count == 0 {
+                    inner.checkpoints.remove(&offset);
+                }
+            }
+            inner.checkpoints.keys().next().copied().unwrap_or(offset)
+        };
+        self.discard_before(low);
+    }
+
+    #[inline]
+    fn max_lookahead(&self) -> Option<usize> {
+        self.inner.borrow().max_lookahead
+    }
+}
+
+impl<'a, I: Iterator + 'a> ValueInput<'a> for BufferedInput<I>
+where
+    I::Item: Clone,
+{
+    #[inline]
+    unsafe fn next(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::Token>) {
+        let mut inner = self.inner.borrow_mut();
+        let inner = &mut *inner;
+
+        let idx = offset.checked_sub(inner.base).unwrap_or_else(|| match inner.max_lookahead {
+            Some(k) => panic!(
+                "attempted to rewind to offset {} of a BufferedInput, exceeding its maximum lookahead of {} tokens",
+                offset, k,
+            ),
+            None => panic!(
+                "attempted to read offset {} of a BufferedInput, but tokens before offset {} were discarded",
+                offset, inner.base,
+            ),
+        });
+
+        // Pull new items into the buffer if we need them
+        let mut idx = idx;
+        while inner.buffer.len() <= idx {
+            match inner.iter.next() {
+                Some(tok) => {
+                    inner.buffer.push_back(tok);
+                    if matches!(inner.max_lookahead, Some(k) if inner.buffer.len() > k) {
+                        inner.buffer.pop_front();
+                        inner.base += 1;
+                        idx -= 1;
+                    }
+                }
+                None => break,
+            }
+        }
+
+        let tok = inner.buffer.get(idx).cloned();
+
+        (offset + tok.is_some() as usize, tok)
+    }
+}
+
 #[test]
 fn spanned() {
     fn parser<'a>() -> impl Parser<
@@ -145,3 +366,159 @@ fn spanned() {
 
     assert_eq!(parser().parse(stream).into_result(), Ok('h'));
 }
+
+#[test]
+fn buffered() {
+    fn parser<'a>() -> impl Parser<'a, BufferedInput<core::str::Chars<'static>>, Vec<&'static str>>
+    {
+        just('a')
+            .then(just('b'))
+            .to("ab")
+            .or(just('a').then(just('c')).to("ac"))
+            .repeated()
+            .collect()
+    }
+
+    let input = BufferedInput::from_iter("abacab".chars());
+    assert_eq!(
+        parser().parse(input).into_result(),
+        Ok(vec!["ab", "ac", "ab"])
+    );
+
+    let input = BufferedInput::from_iter("abcdef".chars());
+    // SAFETY: offsets are generated by previous calls to `next`
+    unsafe {
+        assert_eq!(input.next(0), (1, Some('a')));
+        assert_eq!(input.next(3), (4, Some('d')));
+        input.discard_before(2);
+        assert_eq!(input.buffered(), 2);
+        assert_eq!(input.next(2), (3, Some('c')));
+        assert_eq!(input.next(6), (6, None));
+    }
+}
+
+#[test]
+fn buffered_checkpoints() {
+    type Input = BufferedInput<core::str::Chars<'static>>;
+
+    fn counts<'a>() -> impl Parser<'a, Input, Vec<usize>> + Clone {
+        custom(|inp: &mut InputRef<'a, '_, Input, _>| {
+            let mut buffered = Vec::new();
+            loop {
+                let before = inp.checkpoint();
+                if inp.next() != Some('a') {
+                    inp.rewind(before.marker());
+                    break;
+                }
+                drop(before);
+                buffered.push(inp.input.buffered());
+            }
+            Ok(buffered)
+        })
+    }
+
+    assert_eq!(
+        counts()
+            .then_ignore(just('b'))
+            .parse(BufferedInput::from_iter("aaaab".chars()))
+            .into_result(),
+        Ok(vec![1, 1, 1, 1])
+    );
+
+    // A parser that backtracks over `counts` holds a checkpoint, keeping the tokens it may rewind to buffered
+    let counts_or_all = custom(|inp: &mut InputRef<'_, '_, Input, _>| {
+        let before = inp.checkpoint();
+        match inp.parse(counts().then_ignore(just('c'))) {
+            Ok(out) => Ok(out),
+            Err(_) => {
+                inp.rewind(before.marker());
+                inp.parse(just("aaaa").to(vec![4]))
+            }
+        }
+    })
+    .then_ignore(just('b'));
+    assert_eq!(
+        counts_or_all
+            .parse(BufferedInput::from_iter("aaaab".chars()))
+            .into_result(),
+        Ok(vec![4])
+    );
+}
+
+#[test]
+#[should_panic(expected = "attempted to read offset 0 of a BufferedInput")]
+fn buffered_plain_marker_not_tracked() {
+    let counts = custom(
+        |inp: &mut InputRef<'_, '_, BufferedInput<core::str::Chars<'static>>, extra::Default>| {
+            let mut count = 0;
+            loop {
+                let before = inp.checkpoint();
+                if inp.next() != Some('a') {
+                    inp.rewind(before.marker());
+                    break Ok(count);
+                }
+                count += 1;
+            }
+        },
+    );
+
+    // The marker saved by `or` is not tracked, so the tokens it rewinds to have been discarded
+    let _ = counts
+        .then_ignore(just('c'))
+        .or(just("aaaa").to(4))
+        .then_ignore(just('b'))
+        .parse(BufferedInput::from_iter("aaaab".chars()));
+}
+
+#[test]
+fn buffered_max_lookahead() {
+    let peeks = custom::<_, BufferedInput<core::str::Chars<'static>>, _, extra::Default>(|inp| {
+        let peeked = (0..3)
+            .map(|n| inp.peek_n(n))
+            .collect::<Result<Vec<_>, _>>()?;
+        inp.skip();
+        Ok((peeked, inp.input.buffered()))
+    });
+
+    assert_eq!(
+        peeks
+            .then_ignore(any().repeated())
+            .parse(BufferedInput::with_max_lookahead("abcd".chars(), 3))
+            .into_result(),
+        Ok((vec![Some('a'), Some('b'), Some('c')], 3))
+    );
+    assert_eq!(
+        peeks
+            .then_ignore(any().repeated())
+            .parse(BufferedInput::with_max_lookahead("ab".chars(), 3))
+            .into_result(),
+        Ok((vec![Some('a'), Some('b'), None], 2))
+    );
+}
+
+#[test]
+#[cfg(feature = "label")]
+fn buffered_max_lookahead_peek_exceeded() {
+    type Input = BufferedInput<core::str::Chars<'static>>;
+    let peek = custom::<_, Input, _, extra::Err<Rich<char>>>(|inp| inp.peek_n(2));
+    let errs = peek
+        .then(any())
+        .context("pair")
+        .parse(BufferedInput::with_max_lookahead("abc".chars(), 2))
+        .into_errors();
+    assert_eq!(errs.len(), 1);
+    assert_eq!(errs[0].span(), &SimpleSpan::new(0, 2));
+    assert_eq!(errs[0].found(), Some(&'a'));
+    assert_eq!(
+        errs[0].contexts().collect::<Vec<_>>(),
+        [(&"pair", &SimpleSpan::new(0, 0))],
+    );
+}
+
+#[test]
+#[should_panic(expected = "exceeding its maximum lookahead of 1 tokens")]
+fn buffered_max_lookahead_exceeded() {
+    let parser =
+        just::<_, BufferedInput<core::str::Chars<'static>>, extra::Default>("ab").or(just("ac"));
+    let _ = parser.parse(BufferedInput::with_max_lookahead("ac".chars(), 1));
+}
@@ -0,0 +1,154 @@
+//! Counters describing how a parser went about parsing its input, for monitoring parsers in production.
+//!
+//! [`Parser::parse_with_telemetry`] parses an input like [`Parser::parse`], counting the operations the parser
+//! performed along the way: tokens read, saves and rewinds of the input position, memoization hits and misses, and
+//! error recoveries. Once parsing has finished, these [`ParseStats`] are handed to a [`Telemetry`] hook, which might
+//! export them as metrics, log them, or accumulate them over many inputs.
+//!
+//! Counting is cheap, but not free, so it is only compiled in when the `telemetry` feature is enabled. Even then,
+//! parsers that are not run through [`Parser::parse_with_telemetry`] only pay for checking whether to count.
+//!
+//! [`Parser::parse_with_telemetry`] is currently the only way to collect these counters. The other ways of running a
+//! parser, such as [`Parser::parse_with_state`], [`Parser::check`] or [`Parser::parse_stream`], do not accept a hook,
+//! so a parser that needs a custom state or only checks its input cannot be monitored yet.
+//!
+//! A high ratio of [backtracks](ParseStats::backtracks) or [token reads](ParseStats::tokens) to the length of the input
+//! is a sign of a grammar that explores many alternatives before committing to one, which can often be improved by
+//! factoring out common prefixes or by [memoizing](Parser::memoized) the parsers that are retried most. The
+//! [`trace`](crate::trace) module (enabled by the `debug-replay` feature) can then show exactly where that happens.
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, telemetry::ParseStats};
+//! let keyword = choice((
+//!     text::ascii::keyword::<_, _, _, extra::Default>("let"),
+//!     text::ascii::keyword("loop"),
+//!     text::ascii::keyword("local"),
+//! ))
+//! .padded();
+//! let keywords = keyword.repeated().collect::<Vec<_>>();
+//!
+//! // Accumulate the counters of every parse
+//! let mut totals = ParseStats::default();
+//! for src in ["let loop", "local let"] {
+//!     assert!(!keywords.parse_with_telemetry(src, &mut totals).has_errors());
+//! }
+//! assert_eq!(totals.parses(), 2);
+//! assert!(totals.backtracks() > 0);
+//! ```
+
+use super::*;
+use core::ops::AddAssign;
+use std::time::Duration;
+
+/// Counters describing a single parse, or the sum of several. See [`Parser::parse_with_telemetry`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ParseStats {
+    pub(crate) parses: usize,
+    pub(crate) tokens: usize,
+    pub(crate) saves: usize,
+    pub(crate) rewinds: usize,
+    pub(crate) backtracks: usize,
+    pub(crate) memo_hits: usize,
+    pub(crate) memo_misses: usize,
+    pub(crate) recoveries: usize,
+    pub(crate) duration: Duration,
+}
+
+impl ParseStats {
+    /// The number of parses these counters describe.
+    pub fn parses(&self) -> usize {
+        self.parses
+    }
+
+    /// The number of tokens read from the input, including those read again after backtracking.
+    ///
+    /// Pushed-back tokens are not counted, since they are not read from the input.
+    pub fn tokens(&self) -> usize {
+        self.tokens
+    }
+
+    /// The number of times the parser saved its position in the input, so that it could rewind to it later.
+    pub fn saves(&self) -> usize {
+        self.saves
+    }
+
+    /// The number of times the parser rewound to a saved position, whether or not that moved it backwards.
+    pub fn rewinds(&self) -> usize {
+        self.rewinds
+    }
+
+    /// The number of rewinds that moved the parser backwards, abandoning input that it had already read.
+    pub fn backtracks(&self) -> usize {
+        self.backtracks
+    }
+
+    /// The number of times a [memoized](Parser::memoized) parser reused an earlier result.
+    pub fn memo_hits(&self) -> usize {
+        self.memo_hits
+    }
+
+    /// The number of times a [memoized](Parser::memoized) parser had no earlier result to reuse, so ran its parser.
+    pub fn memo_misses(&self) -> usize {
+        self.memo_misses
+    }
+
+    /// The fraction of lookups of [memoized](Parser::memoized) results that were hits, or `None` if there were no
+    /// lookups.
+    pub fn memo_hit_rate(&self) -> Option<f64> {
+        let lookups = self.memo_hits + self.memo_misses;
+        (lookups > 0).then(|| self.memo_hits as f64 / lookups as f64)
+    }
+
+    /// The number of times a recovery strategy successfully recovered from an error.
+    ///
+    /// Unlike [`ParseResult::recovery`], this includes recoveries performed while parsing a branch that was later
+    /// backtracked out of, since they took time all the same.
+    pub fn recoveries(&self) -> usize {
+        self.recoveries
+    }
+
+    /// The time spent parsing.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+impl AddAssign for ParseStats {
+    fn add_assign(&mut self, other: Self) {
+        self.parses += other.parses;
+        self.tokens += other.tokens;
+        self.saves += other.saves;
+        self.rewinds += other.rewinds;
+        self.backtracks += other.backtracks;
+        self.memo_hits += other.memo_hits;
+        self.memo_misses += other.memo_misses;
+        self.recoveries += other.recoveries;
+        self.duration += other.duration;
+    }
+}
+
+/// A hook that receives the [`ParseStats`] of each parse performed by [`Parser::parse_with_telemetry`].
+///
+/// This is implemented for closures taking a `&ParseStats`, and for [`ParseStats`] itself, which adds the counters of
+/// each parse to its own.
+pub trait Telemetry {
+    /// Called before parsing begins.
+    fn parse_started(&mut self) {}
+
+    /// Called once parsing has finished, with the counters of the parse.
+    fn parse_finished(&mut self, stats: &ParseStats);
+}
+
+impl<F: FnMut(&ParseStats)> Telemetry for F {
+    fn parse_finished(&mut self, stats: &ParseStats) {
+        self(stats)
+    }
+}
+
+impl Telemetry for ParseStats {
+    fn parse_finished(&mut self, stats: &ParseStats) {
+        *self += *stats;
+    }
+}
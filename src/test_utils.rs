@@ -0,0 +1,214 @@
+//! Assertions for testing parsers, with readable failure messages.
+//!
+//! Parser test suites tend to be large, and a bare `assert_eq!` on a [`ParseResult`] makes for poor reading when it
+//! fails: a deeply nested output is printed on a single line, and errors are printed without the source they refer to.
+//! The [`assert_parses_to!`](crate::assert_parses_to) and [`assert_errors_with!`](crate::assert_errors_with) macros
+//! instead show a line-by-line diff of the expected and actual outputs, and [render](crate::error::render) each error
+//! with an excerpt of the input.
+//!
+//! The macros work with parsers of [`&str`] whose errors implement [`SpannedError`] and [`Display`](fmt::Display),
+//! such as [`Simple`] and [`Rich`].
+//!
+//! # Examples
+//!
+//! ```
+//! # use chumsky::{prelude::*, assert_errors_with, assert_parses_to};
+//! let list = text::int::<_, _, extra::Err<Rich<char>>>(10)
+//!     .padded()
+//!     .separated_by(just(','))
+//!     .collect::<Vec<_>>()
+//!     .delimited_by(just('['), just(']'));
+//!
+//! assert_parses_to!(list, "[1, 2, 3]", vec!["1", "2", "3"]);
+//! assert_errors_with!(list, "[1, 2 3]", ["found '3' expected ',', or ']'"]);
+//! ```
+
+use super::*;
+use crate::error::{render, SpannedError};
+
+/// Assert that a parser parses an input without errors, producing the expected output.
+///
+/// On failure, the panic message shows a diff of the expected and actual outputs, or the errors produced, rendered with
+/// excerpts of the input. See the [module-level documentation](crate::test_utils) for more information.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, assert_parses_to};
+/// let word = text::ascii::ident::<_, _, extra::Err<Simple<char>>>().padded();
+///
+/// assert_parses_to!(word, " hello ", "hello");
+/// ```
+#[macro_export]
+macro_rules! assert_parses_to {
+    ($parser:expr, $input:expr, $expected:expr $(,)?) => {
+        $crate::test_utils::assert_parses_to(&$parser, $input, $expected)
+    };
+}
+
+/// Assert that a parser produces exactly the expected errors when parsing an input, compared by their
+/// [`Display`](core::fmt::Display) messages.
+///
+/// On failure, the panic message shows a diff of the expected and actual messages, followed by every error produced,
+/// rendered with an excerpt of the input. See the [module-level documentation](crate::test_utils) for more information.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, assert_errors_with};
+/// let digit = one_of::<_, _, extra::Err<Rich<char>>>('0'..='9');
+///
+/// assert_errors_with!(digit, "x", ["found 'x' expected '0'..='9'"]);
+/// assert_errors_with!(digit, "7", [] as [&str; 0]);
+/// ```
+#[macro_export]
+macro_rules! assert_errors_with {
+    ($parser:expr, $input:expr, $expected:expr $(,)?) => {
+        $crate::test_utils::assert_errors_with(&$parser, $input, $expected)
+    };
+}
+
+/// Assert that `parser` parses `input` without errors, producing `expected`. See [`assert_parses_to!`].
+#[track_caller]
+pub fn assert_parses_to<'a, P, O, E>(parser: &P, input: &'a str, expected: O)
+where
+    P: Parser<'a, &'a str, O, E>,
+    O: PartialEq + fmt::Debug,
+    E: ParserExtra<'a, &'a str>,
+    E::Error: SpannedError + fmt::Display,
+    <E::Error as SpannedError>::Span: Span<Offset = usize>,
+    E::State: Default,
+    E::Context: Default,
+{
+    let (output, errors) = parser.parse(input).into_output_errors();
+    if !errors.is_empty() {
+        panic!(
+            "parsing {:?} produced {} error(s):\n\n{}",
+            input,
+            errors.len(),
+            render_all(&errors, input),
+        );
+    }
+    let Some(output) = output else {
+        panic!("parsing {:?} produced no output", input);
+    };
+    if output != expected {
+        panic!(
+            "parsing {:?} produced an unexpected output (- expected, + actual):\n\n{}",
+            input,
+            diff_lines(&format!("{:#?}", expected), &format!("{:#?}", output)),
+        );
+    }
+}
+
+/// Assert that `parser` produces errors with exactly the `expected` messages when parsing `input`. See
+/// [`assert_errors_with!`].
+#[track_caller]
+pub fn assert_errors_with<'a, P, O, E, M>(parser: &P, input: &'a str, expected: M)
+where
+    P: Parser<'a, &'a str, O, E>,
+    E: ParserExtra<'a, &'a str>,
+    E::Error: SpannedError + fmt::Display,
+    <E::Error as SpannedError>::Span: Span<Offset = usize>,
+    E::State: Default,
+    E::Context: Default,
+    M: IntoIterator,
+    M::Item: AsRef<str>,
+{
+    let errors = parser.parse(input).into_errors();
+    let expected = expected
+        .into_iter()
+        .map(|msg| msg.as_ref().to_string())
+        .collect::<Vec<_>>();
+    let actual = errors.iter().map(|err| err.to_string()).collect::<Vec<_>>();
+    if actual != expected {
+        panic!(
+            "parsing {:?} produced unexpected errors (- expected, + actual):\n\n{}\n{}",
+            input,
+            diff_lines(&expected.join("\n"), &actual.join("\n")),
+            render_all(&errors, input),
+        );
+    }
+}
+
+// Render each error with an excerpt of the input, separated by blank lines
+fn render_all<E>(errors: &[E], input: &str) -> String
+where
+    E: SpannedError + fmt::Display,
+    E::Span: Span<Offset = usize>,
+{
+    errors
+        .iter()
+        .map(|err| render(err, input))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Compare two texts line by line, marking lines only in `expected` with `-` and lines only in `actual` with `+`.
+fn diff_lines(expected: &str, actual: &str) -> String {
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+
+    // The length of the longest common subsequence of the lines after each pair of positions
+    let mut lcs = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            out += &format!("  {}\n", expected[i]);
+            i += 1;
+            j += 1;
+        } else if i < expected.len() && (j == actual.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out += &format!("- {}\n", expected[i]);
+            i += 1;
+        } else {
+            out += &format!("+ {}\n", actual[j]);
+            j += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_marks_changed_lines() {
+        assert_eq!(
+            diff_lines("a\nb\nc", "a\nx\nc\nd"),
+            "  a\n- b\n+ x\n  c\n+ d\n"
+        );
+        assert_eq!(diff_lines("", "a"), "+ a\n");
+    }
+
+    #[test]
+    #[should_panic = "parsing \"[1, x]\" produced 1 error(s):\n\nerror: found 'x' expected"]
+    fn parses_to_renders_errors() {
+        let list = text::int::<_, _, extra::Err<Rich<char>>>(10)
+            .padded()
+            .separated_by(just(','))
+            .collect::<Vec<_>>()
+            .delimited_by(just('['), just(']'));
+        assert_parses_to!(list, "[1, x]", vec!["1"]);
+    }
+
+    #[test]
+    #[should_panic = "(- expected, + actual):\n\n  [\n      \"1\",\n-     \"3\",\n+     \"2\",\n  ]\n"]
+    fn parses_to_diffs_outputs() {
+        let list = text::int::<_, _, extra::Err<Simple<char>>>(10)
+            .separated_by(just(','))
+            .collect::<Vec<_>>();
+        assert_parses_to!(list, "1,2", vec!["1", "3"]);
+    }
+}
@@ -9,6 +9,7 @@
 use crate::prelude::*;
 
 use super::*;
+use alloc::borrow::Cow;
 
 /// A trait implemented by textual character types (currently, [`u8`] and [`char`]).
 ///
@@ -29,6 +30,9 @@ pub trait Char: Sized + Copy + PartialEq + fmt::Debug + Sealed + 'static {
     /// Returns true if the character is canonically considered to be whitespace.
     fn is_whitespace(&self) -> bool;
 
+    /// Returns true if the character is a newline, as recognised by [`newline`].
+    fn is_newline(&self) -> bool;
+
     /// Return the '0' digit of the character.
     fn digit_zero() -> Self;
 
@@ -49,6 +53,15 @@ pub trait Char: Sized + Copy + PartialEq + fmt::Debug + Sealed + 'static {
 
     /// Turn a string of this character type into an iterator over those characters.
     fn str_to_chars(s: &Self::Str) -> Self::StrCharIter<'_>;
+
+    /// Return the number of bytes that this character occupies within a [`Char::Str`].
+    fn len_bytes(&self) -> usize;
+
+    /// Find the byte offset of the first occurrence of the character `needle` within `haystack`.
+    fn str_find_char(haystack: &Self::Str, needle: Self) -> Option<usize>;
+
+    /// Find the byte offset of the first occurrence of the string `needle` within `haystack`.
+    fn str_find(haystack: &Self::Str, needle: &Self::Str) -> Option<usize>;
 }
 
 impl Sealed for char {}
@@ -64,6 +77,18 @@ impl Char for char {
     fn is_whitespace(&self) -> bool {
         char::is_whitespace(*self)
     }
+    fn is_newline(&self) -> bool {
+        [
+            '\n',       // Line feed
+            '\r',       // Carriage return
+            '\x0B',     // Vertical tab
+            '\x0C',     // Form feed
+            '\u{0085}', // Next line
+            '\u{2028}', // Line separator
+            '\u{2029}', // Paragraph separator
+        ]
+        .contains(self)
+    }
     fn digit_zero() -> Self {
         '0'
     }
@@ -79,6 +104,16 @@ impl Char for char {
         s.chars()
     }
 
+    fn len_bytes(&self) -> usize {
+        self.len_utf8()
+    }
+    fn str_find_char(haystack: &Self::Str, needle: Self) -> Option<usize> {
+        haystack.find(needle)
+    }
+    fn str_find(haystack: &Self::Str, needle: &Self::Str) -> Option<usize> {
+        haystack.find(needle)
+    }
+
     fn is_ident_start(&self) -> bool {
         unicode_ident::is_xid_start(*self)
     }
@@ -101,6 +136,10 @@ impl Char for u8 {
     fn is_whitespace(&self) -> bool {
         self.is_ascii_whitespace()
     }
+    fn is_newline(&self) -> bool {
+        // The non-ASCII newlines are multi-byte sequences in UTF-8, and their bytes appear within other characters
+        matches!(*self, b'\n' | b'\r' | b'\x0B' | b'\x0C')
+    }
     fn digit_zero() -> Self {
         b'0'
     }
@@ -116,6 +155,29 @@ impl Char for u8 {
         s.iter().copied()
     }
 
+    fn len_bytes(&self) -> usize {
+        1
+    }
+    fn str_find_char(haystack: &Self::Str, needle: Self) -> Option<usize> {
+        haystack.iter().position(|b| *b == needle)
+    }
+    fn str_find(haystack: &Self::Str, needle: &Self::Str) -> Option<usize> {
+        let (&first, rest) = match needle.split_first() {
+            Some(split) => split,
+            None => return Some(0),
+        };
+        // Skip quickly between occurrences of the first byte, only comparing the rest of the needle at each one
+        let mut offset = 0;
+        while let Some(pos) = Self::str_find_char(&haystack[offset..], first) {
+            let start = offset + pos;
+            if haystack[start + 1..].starts_with(rest) {
+                return Some(start);
+            }
+            offset = start + 1;
+        }
+        None
+    }
+
     fn is_ident_start(&self) -> bool {
         self.to_char().is_ident_start()
     }
@@ -139,17 +201,310 @@ where
     A: Parser<'a, I, O, E>,
 {
     fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
-        inp.skip_while(|c| c.is_whitespace());
+        skip_whitespace(inp);
         let out = self.parser.go::<M>(inp)?;
-        inp.skip_while(|c| c.is_whitespace());
+        skip_whitespace(inp);
         Ok(out)
     }
 
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(O);
+}
+
+/// See [`Parser::padded_with_spans`].
+#[derive(Copy, Clone)]
+pub struct PaddedWithSpans<A> {
+    pub(crate) parser: A,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, (I::Span, O, I::Span), E> for PaddedWithSpans<A>
+where
+    I: ValueInput<'a>,
+    E: ParserExtra<'a, I>,
+    I::Token: Char,
+    A: Parser<'a, I, O, E>,
+{
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, (I::Span, O, I::Span)> {
+        let before = inp.offset();
+        skip_whitespace(inp);
+        let leading = inp.span_since(before);
+        let out = self.parser.go::<M>(inp)?;
+        let before = inp.offset();
+        skip_whitespace(inp);
+        let trailing = inp.span_since(before);
+        Ok(M::map(out, |out| (leading, out, trailing)))
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!((I::Span, O, I::Span));
+}
+
+// Whether the newline at the current offset ends the current item of a `block_of`, because the line that follows it
+// is not indented beyond `col`. Blank lines never end an item.
+fn is_offside<'a, I, E>(inp: &InputRef<'a, '_, I, E>, col: usize) -> bool
+where
+    I: ValueInput<'a>,
+    I::Token: Char,
+    E: ParserExtra<'a, I>,
+{
+    // SAFETY: offsets generated by previous call to `Input::next`
+    let (mut offset, _) = unsafe { inp.input.next(inp.offset) };
+    let mut line_col = 0;
+    loop {
+        // SAFETY: As above
+        match unsafe { inp.input.next(offset) } {
+            (_, None) => return false,
+            (_, Some(c)) if c.to_char() == '\n' => return false,
+            (next, Some(c)) if c.is_whitespace() => {
+                line_col += 1;
+                offset = next;
+            }
+            (_, Some(_)) => return line_col <= col,
+        }
+    }
+}
+
+// Skip whitespace, stopping at a newline that ends the current item of a `block_of`
+fn skip_whitespace<'a, I, E>(inp: &mut InputRef<'a, '_, I, E>)
+where
+    I: ValueInput<'a>,
+    I::Token: Char,
+    E: ParserExtra<'a, I>,
+{
+    match inp.layout {
+        None => inp.skip_while(|c| c.is_whitespace()),
+        Some(col) => {
+            while let Some(c) = inp.peek() {
+                if !c.is_whitespace() || (c.to_char() == '\n' && is_offside(inp, col)) {
+                    break;
+                }
+                inp.skip();
+            }
+        }
+    }
+}
+
+// A single whitespace character that does not end the current item of a `block_of`
+#[derive(Copy, Clone)]
+struct Whitespace;
+
+impl<'a, I, E> ParserSealed<'a, I, (), E> for Whitespace
+where
+    I: ValueInput<'a>,
+    I::Token: Char,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, ()> {
+        let before = inp.offset();
+        match inp.peek() {
+            Some(c)
+                if c.is_whitespace()
+                    && !(c.to_char() == '\n'
+                        && matches!(inp.layout, Some(col) if is_offside(inp, col))) =>
+            {
+                inp.skip();
+                Ok(M::bind(|| ()))
+            }
+            _ => {
+                // Errors are the same as those of `any().filter(..)`
+                inp.skip();
+                let span = inp.span_since(before);
+                inp.add_alt(inp.offset().offset, None, None, span);
+                Err(())
+            }
+        }
+    }
+
+    go_extra!(());
+}
+
+// The column of the current offset within its line, counted in characters
+fn column<'a, I, E>(inp: &InputRef<'a, '_, I, E>) -> usize
+where
+    I: ValueInput<'a> + SliceInput<'a, Offset = usize>,
+    I::Token: Char,
+    I::Slice: AsRef<<I::Token as Char>::Str>,
+    E: ParserExtra<'a, I>,
+{
+    let offset = inp.offset;
+    let full = inp.full_slice();
+    let leading = &bytes::<I::Token>(full.as_ref())[..offset];
+    // `\n` is always a whole character, so the line starts on a character boundary
+    let line_start = leading
+        .iter()
+        .rposition(|b| *b == b'\n')
+        .map_or(0, |pos| pos + 1);
+    let line = inp.slice_inner(line_start..offset);
+    I::Token::str_to_chars(line.as_ref()).count()
+}
+
+fn bytes<C: Char>(s: &C::Str) -> &[u8] {
+    s.as_ref()
+}
+
+/// See [`Parser::same_line`].
+#[derive(Copy, Clone)]
+pub struct SameLine<A> {
+    pub(crate) parser: A,
+}
+
+impl<'a, I, O, E, A> ParserSealed<'a, I, O, E> for SameLine<A>
+where
+    I: ValueInput<'a> + SliceInput<'a, Offset = usize>,
+    I::Token: Char,
+    I::Slice: AsRef<<I::Token as Char>::Str>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let before = inp.offset;
+        let out = self.parser.go::<M>(inp)?;
+        let matched = inp.slice_inner(before..inp.offset);
+        match bytes::<I::Token>(matched.as_ref())
+            .iter()
+            .position(|b| *b == b'\n')
+        {
+            None => Ok(out),
+            Some(pos) => {
+                let at = before + pos;
+                let newline = I::Token::from_ascii(b'\n');
+                let span = inp.span_inner(at..at + 1);
+                inp.add_alt_err(
+                    at,
+                    E::Error::expected_found([], Some(MaybeRef::Val(newline)), span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
     go_extra!(O);
 }
 
+/// See [`Parser::aligned`] and [`block_of`].
+#[derive(Copy, Clone)]
+pub struct Aligned<A> {
+    pub(crate) parser: A,
+    pub(crate) offside: bool,
+}
+
+impl<'a, I, O, E, A> IterParserSealed<'a, I, O, E> for Aligned<A>
+where
+    I: ValueInput<'a> + SliceInput<'a, Offset = usize>,
+    I::Token: Char,
+    I::Slice: AsRef<<I::Token as Char>::Str>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    // The column of the first item, once it has been parsed
+    type IterState<M: Mode> = Option<usize>;
+
+    #[inline(always)]
+    fn make_iter<M: Mode>(
+        &self,
+        _inp: &mut InputRef<'a, '_, I, E>,
+    ) -> PResult<Emit, Self::IterState<M>> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn next<M: Mode>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+        state: &mut Self::IterState<M>,
+    ) -> IPResult<M, O> {
+        let before = inp.save();
+        inp.skip_while(|c| c.is_whitespace());
+        let col = column(inp);
+        if matches!(*state, Some(first) if first != col) {
+            // An item that starts in a different column ends the sequence
+            inp.rewind(before);
+            return Ok(None);
+        }
+
+        let outer = inp.layout;
+        if self.offside {
+            inp.layout = Some(col);
+        }
+        let res = self.parser.go::<M>(inp);
+        inp.layout = outer;
+        let item = match res {
+            Ok(item) => item,
+            Err(()) if state.is_some() => {
+                inp.rewind(before);
+                return Ok(None);
+            }
+            Err(()) => return Err(()),
+        };
+
+        *state = Some(col);
+        Ok(Some(item))
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+}
+
+/// A parser that accepts a block of items laid out according to the 'offside rule', as in Haskell, F# or Python.
+///
+/// Each item in the block must start in the same column as the first (see [`Parser::aligned`]), and any line that an
+/// item continues onto must be indented further than the item itself. While an item is being parsed, [`whitespace`]
+/// and [`Parser::padded`] do not skip a newline if the line after it is not indented far enough, so the item ends
+/// there. Blocks may be nested, and each item of an inner block is limited by the column of the outer block as well.
+///
+/// Whitespace, including newlines, is skipped before each item. The block ends at the first item that starts in a
+/// different column, or that fails to parse. Columns are counted in characters, so a tab is a single column.
+///
+/// The output type of this iterable parser is `O`, the output of `item`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// // A binding is a name, and a value that may continue onto further lines
+/// let value = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+///     .separated_by(text::whitespace().at_least(1))
+///     .at_least(1)
+///     .collect::<Vec<_>>();
+/// let binding = text::ascii::ident()
+///     .then_ignore(just('=').padded())
+///     .then(value);
+/// let block = text::block_of(binding).collect::<Vec<_>>();
+///
+/// assert_eq!(
+///     block.parse("  a = b c\n      d\n  e = f").into_result(),
+///     Ok(vec![("a", vec!["b", "c", "d"]), ("e", vec!["f"])]),
+/// );
+/// // `d` is not indented far enough to continue the first binding, so it must start another
+/// assert!(block.parse("  a = b c\n  d\n  e = f").has_errors());
+/// ```
+pub const fn block_of<A>(item: A) -> Aligned<A> {
+    Aligned {
+        parser: item,
+        offside: true,
+    }
+}
+
 /// A parser that accepts (and ignores) any number of whitespace characters.
 ///
+/// When `C` is [`char`], this accepts any character with the Unicode `White_Space` property. See
+/// [`ascii::whitespace`] for a parser that only accepts ASCII whitespace, and [`inline_whitespace`] or
+/// [`unicode::inline_whitespace`] for parsers that do not accept newlines. Within an item of a [`block_of`], newlines
+/// that are followed by a line which is not indented far enough to continue the item are not accepted.
+///
 /// This parser is a `Parser::Repeated` and so methods such as `at_least()` can be called on it.
 ///
 /// The output type of this parser is `()`.
@@ -170,14 +525,14 @@ pub fn whitespace<'a, C: Char, I: ValueInput<'a> + StrInput<'a, C>, E: ParserExt
 where
     I::Token: Char,
 {
-    any()
-        .filter(|c: &I::Token| c.is_whitespace())
-        .ignored()
-        .repeated()
+    Whitespace.repeated()
 }
 
 /// A parser that accepts (and ignores) any number of inline whitespace characters.
 ///
+/// Only spaces and tabs are accepted. See [`unicode::inline_whitespace`] for a parser that also accepts other Unicode
+/// whitespace that is not a newline.
+///
 /// This parser is a `Parser::Repeated` and so methods such as `at_least()` can be called on it.
 ///
 /// The output type of this parser is `()`.
@@ -221,6 +576,9 @@ where
 /// - Line separator (`\u{2028}`)
 /// - Paragraph separator (`\u{2029}`)
 ///
+/// The last three are only recognised in inputs of [`char`]s. When parsing bytes, only the ASCII newlines are
+/// recognised, since the bytes that encode the others in UTF-8 also appear within other characters.
+///
 /// # Examples
 ///
 /// ```
@@ -245,128 +603,1088 @@ where
     just(I::Token::from_ascii(b'\r'))
         .or_not()
         .ignore_then(just(I::Token::from_ascii(b'\n')))
-        .or(any().filter(|c: &I::Token| {
-            [
-                '\r',       // Carriage return
-                '\x0B',     // Vertical tab
-                '\x0C',     // Form feed
-                '\u{0085}', // Next line
-                '\u{2028}', // Line separator
-                '\u{2029}', // Paragraph separator
-            ]
-            .contains(&c.to_char())
-        }))
+        .or(any().filter(I::Token::is_newline))
         .ignored()
 }
 
-/// A parser that accepts one or more ASCII digits.
+/// A parser that accepts either a newline (as per [`newline`]) or the end of the input.
 ///
-/// The output type of this parser is `I::Slice` (i.e: [`&str`] when `I` is [`&str`], and [`&[u8]`]
-/// when `I::Slice` is [`&[u8]`]).
+/// This is useful for line-oriented grammars, where the last line of a file may or may not end with a newline.
 ///
-/// The `radix` parameter functions identically to [`char::is_digit`]. If in doubt, choose `10`.
+/// The output type of this parser is `()`.
 ///
 /// # Examples
 ///
 /// ```
 /// # use chumsky::prelude::*;
-/// let digits = text::digits::<_, _, extra::Err<Simple<char>>>(10).slice();
+/// let entry = text::ascii::ident::<_, _, extra::Err<Simple<char>>>()
+///     .then_ignore(text::eol_or_eof());
 ///
-/// assert_eq!(digits.parse("0").into_result(), Ok("0"));
-/// assert_eq!(digits.parse("1").into_result(), Ok("1"));
-/// assert_eq!(digits.parse("01234").into_result(), Ok("01234"));
-/// assert_eq!(digits.parse("98345").into_result(), Ok("98345"));
-/// // A string of zeroes is still valid. Use `int` if this is not desirable.
-/// assert_eq!(digits.parse("0000").into_result(), Ok("0000"));
-/// assert!(digits.parse("").has_errors());
+/// let entries = entry.repeated().collect::<Vec<_>>();
+///
+/// assert_eq!(entries.parse("foo\r\nbar\nbaz").into_result(), Ok(vec!["foo", "bar", "baz"]));
+/// assert_eq!(entries.parse("foo\n").into_result(), Ok(vec!["foo"]));
 /// ```
 #[must_use]
-pub fn digits<'a, C, I, E>(radix: u32) -> Repeated<impl Parser<'a, I, C, E> + Copy + Clone, C, I, E>
+pub fn eol_or_eof<'a, I: ValueInput<'a>, E: ParserExtra<'a, I>>() -> impl Parser<'a, I, (), E> + Copy
 where
-    C: Char,
-    I: ValueInput<'a> + Input<'a, Token = C>,
-    E: ParserExtra<'a, I>,
+    I::Token: Char,
 {
-    any()
-        // Use try_map over filter to get a better error on failure
-        .try_map(move |c: C, span| {
-            if c.is_digit(radix) {
-                Ok(c)
-            } else {
-                Err(Error::expected_found([], Some(MaybeRef::Val(c)), span))
-            }
-        })
-        .repeated()
-        .at_least(1)
+    newline().or(end())
 }
 
-/// A parser that accepts a non-negative integer.
+/// A parser that accepts the remainder of the current line, without consuming the newline that ends it.
 ///
-/// An integer is defined as a non-empty sequence of ASCII digits, where the first digit is non-zero or the sequence
-/// has length one.
+/// Every character up to the next newline (as per [`newline`]) or the end of the input is accepted, including none at
+/// all. Note that a `\r` is considered a newline in its own right.
 ///
-/// The output type of this parser is `I::Slice` (i.e: [`&str`] when `I` is [`&str`], and [`&[u8]`]
-/// when `I::Slice` is [`&[u8]`]).
+/// The output type of this parser is [`Char::Str`] (i.e: [`&str`] when `C` is [`char`], and [`&[u8]`] when `C` is
+/// [`u8`]).
 ///
-/// The `radix` parameter functions identically to [`char::is_digit`]. If in doubt, choose `10`.
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// // A line comment
+/// let comment = just::<_, _, extra::Err<Simple<char>>>("//")
+///     .ignore_then(text::rest_of_line());
+///
+/// assert_eq!(comment.lazy().parse("// Hello, world!\nfoo").into_result(), Ok(" Hello, world!"));
+/// assert_eq!(comment.lazy().parse("//\r\n").into_result(), Ok(""));
+/// assert_eq!(comment.parse("// End of file").into_result(), Ok(" End of file"));
+/// ```
+#[must_use]
+pub fn rest_of_line<'a, I: ValueInput<'a> + StrInput<'a, C>, C: Char, E: ParserExtra<'a, I>>(
+) -> impl Parser<'a, I, &'a C::Str, E> + Copy {
+    any().filter(|c: &C| !c.is_newline()).repeated().slice()
+}
+
+/// A parser that accepts a single line, including the newline that ends it (if any).
+///
+/// The line may be ended by a newline (as per [`newline`]) or by the end of the input, so the last line of an input is
+/// accepted whether or not it has a trailing newline. Because this parser fails at the end of the input, it can be
+/// repeated to split an input into lines.
+///
+/// The output type of this parser is [`Char::Str`] (i.e: [`&str`] when `C` is [`char`], and [`&[u8]`] when `C` is
+/// [`u8`]), and does not include the newline.
 ///
 /// # Examples
 ///
 /// ```
 /// # use chumsky::prelude::*;
-/// let dec = text::int::<_, _, extra::Err<Simple<char>>>(10);
+/// let lines = text::line::<_, _, extra::Err<Simple<char>>>()
+///     .repeated()
+///     .collect::<Vec<_>>();
 ///
-/// assert_eq!(dec.parse("0").into_result(), Ok("0"));
-/// assert_eq!(dec.parse("1").into_result(), Ok("1"));
-/// assert_eq!(dec.parse("1452").into_result(), Ok("1452"));
-/// // No leading zeroes are permitted!
-/// assert!(dec.parse("04").has_errors());
+/// assert_eq!(lines.parse("foo\r\n\nbar").into_result(), Ok(vec!["foo", "", "bar"]));
+/// assert_eq!(lines.parse("foo\nbar\n").into_result(), Ok(vec!["foo", "bar"]));
+/// assert_eq!(lines.parse("").into_result(), Ok(vec![]));
+/// ```
+#[must_use]
+pub fn line<'a, I: ValueInput<'a> + StrInput<'a, C>, C: Char, E: ParserExtra<'a, I>>(
+) -> impl Parser<'a, I, &'a C::Str, E> + Copy {
+    end()
+        .not()
+        .ignore_then(rest_of_line())
+        .then_ignore(eol_or_eof())
+}
+
+/// A parser that accepts a word: a maximal run of one or more non-whitespace characters.
 ///
-/// let hex = text::int::<_, _, extra::Err<Simple<char>>>(16);
+/// Whitespace is as per [`Char::is_whitespace`]. Unlike [`ascii::ident`], any other character may appear in a word, so
+/// this is useful for simple command-style syntax where a full lexer would be overkill. See [`split_words`] to split a
+/// whole input into words.
+///
+/// The output type of this parser is [`Char::Str`] (i.e: [`&str`] when `C` is [`char`], and [`&[u8]`] when `C` is
+/// [`u8`]).
+///
+/// # Examples
 ///
-/// assert_eq!(hex.parse("2A").into_result(), Ok("2A"));
-/// assert_eq!(hex.parse("d").into_result(), Ok("d"));
-/// assert_eq!(hex.parse("b4").into_result(), Ok("b4"));
-/// assert!(hex.parse("0B").has_errors());
 /// ```
+/// # use chumsky::prelude::*;
+/// let command = text::word::<_, _, extra::Err<Simple<char>>>()
+///     .then_ignore(text::inline_whitespace().at_least(1))
+///     .then(text::word());
 ///
+/// assert_eq!(command.parse("open ./a-file.txt").into_result(), Ok(("open", "./a-file.txt")));
+/// assert!(command.parse("open ").has_errors());
+/// ```
 #[must_use]
-pub fn int<'a, I: ValueInput<'a> + StrInput<'a, C>, C: Char, E: ParserExtra<'a, I>>(
-    radix: u32,
-) -> impl Parser<'a, I, &'a C::Str, E> + Copy + Clone {
+pub fn word<'a, I: ValueInput<'a> + StrInput<'a, C>, C: Char, E: ParserExtra<'a, I>>(
+) -> impl Parser<'a, I, &'a C::Str, E> + Copy {
     any()
-        // Use try_map over filter to get a better error on failure
-        .try_map(move |c: C, span| {
-            if c.is_digit(radix) && c != C::digit_zero() {
-                Ok(c)
-            } else {
-                Err(Error::expected_found([], Some(MaybeRef::Val(c)), span))
-            }
-        })
-        // This error never appears due to `repeated` so can use `filter`
-        .then(any().filter(move |c: &C| c.is_digit(radix)).repeated())
-        .ignored()
-        .or(just(C::digit_zero()).ignored())
+        .filter(|c: &C| !c.is_whitespace())
+        .repeated()
+        .at_least(1)
         .slice()
 }
 
-/// Parsers and utilities for working with ASCII inputs.
-pub mod ascii {
-    use super::*;
+/// A parser that splits the entire input into [`word`]s, along with the span of each.
+///
+/// Any amount of whitespace is accepted before, between and after the words, and an input containing no words at all
+/// produces an empty list. The resulting tokens can be fed to a second parser (for example, with [`Input::spanned`]) to
+/// build quick command parsers or test DSLs without writing a dedicated lexer.
+///
+/// The output type of this parser is `Vec<(&C::Str, I::Span)>`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let words = text::split_words::<_, _, extra::Err<Simple<char>>>();
+///
+/// assert_eq!(
+///     words.parse("  set  x 42\n").into_result(),
+///     Ok(vec![
+///         ("set", SimpleSpan::from(2..5)),
+///         ("x", SimpleSpan::from(7..8)),
+///         ("42", SimpleSpan::from(9..11)),
+///     ]),
+/// );
+/// assert_eq!(words.parse(" \t ").into_result(), Ok(vec![]));
+/// ```
+#[must_use]
+pub fn split_words<'a, I: ValueInput<'a> + StrInput<'a, C>, C: Char, E: ParserExtra<'a, I>>(
+) -> impl Parser<'a, I, Vec<(&'a C::Str, I::Span)>, E> + Copy {
+    whitespace().ignore_then(
+        word()
+            .map_with_span(|word, span| (word, span))
+            .then_ignore(whitespace())
+            .repeated()
+            .collect(),
+    )
+}
 
-    /// A parser that accepts a C-style identifier.
-    ///
-    /// The output type of this parser is [`Char::Str`] (i.e: [`&str`] when `C` is [`char`], and [`&[u8]`] when `C` is
-    /// [`u8`]).
-    ///
-    /// An identifier is defined as an ASCII alphabetic character or an underscore followed by any number of alphanumeric
-    /// characters or underscores. The regex pattern for it is `[a-zA-Z_][a-zA-Z0-9_]*`.
-    #[must_use]
-    pub fn ident<'a, I: ValueInput<'a> + StrInput<'a, C>, C: Char, E: ParserExtra<'a, I>>(
-    ) -> impl Parser<'a, I, &'a C::Str, E> + Copy + Clone {
-        any()
-            // Use try_map over filter to get a better error on failure
-            .try_map(|c: C, span| {
+/// A parser that accepts a byte order mark (U+FEFF), as sometimes found at the start of text files.
+///
+/// When `C` is [`char`], this is the single character U+FEFF. When `C` is [`u8`], this is its UTF-8 encoding, the
+/// bytes `EF BB BF`. To accept input whether or not it starts with a byte order mark, use it with [`Parser::or_not`].
+///
+/// The output type of this parser is `()`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let file = text::bom::<&[u8], extra::Err<Simple<u8>>>()
+///     .or_not()
+///     .ignore_then(text::ascii::ident());
+///
+/// assert_eq!(file.parse(b"\xEF\xBB\xBFkey").into_result(), Ok(b"key".as_slice()));
+/// assert_eq!(file.parse(b"key").into_result(), Ok(b"key".as_slice()));
+/// assert!(file.parse(b"\xEF\xBBkey").has_errors());
+/// ```
+#[must_use]
+pub fn bom<'a, I: ValueInput<'a>, E: ParserExtra<'a, I>>() -> impl Parser<'a, I, (), E> + Copy
+where
+    I::Token: Char,
+{
+    Bom
+}
+
+#[derive(Copy, Clone)]
+struct Bom;
+
+impl<'a, I, E> ParserSealed<'a, I, (), E> for Bom
+where
+    I: ValueInput<'a>,
+    I::Token: Char,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, ()> {
+        const BOM: &[u8] = "\u{FEFF}".as_bytes();
+
+        let before = inp.offset();
+        let mut matched = 0;
+        while matched < BOM.len() {
+            let mut buf = [0; 4];
+            match inp.next_inner() {
+                (_, Some(c)) if BOM[matched..].starts_with(utf8_bytes(c, &mut buf)) => {
+                    matched += c.len_bytes();
+                }
+                (at, found) => {
+                    let span = inp.span_since(before);
+                    inp.add_alt(at, None, found.map(Into::into), span);
+                    return Err(());
+                }
+            }
+        }
+        Ok(M::bind(|| ()))
+    }
+
+    go_extra!(());
+}
+
+// The UTF-8 encoding of a character: itself, for bytes
+fn utf8_bytes<C: Char>(c: C, buf: &mut [u8; 4]) -> &[u8] {
+    if c.len_bytes() == 1 {
+        // Both ASCII `char`s and bytes convert to a `char` with the same value
+        buf[0] = c.to_char() as u8;
+        &buf[..1]
+    } else {
+        c.to_char().encode_utf8(buf).as_bytes()
+    }
+}
+
+/// A parser that accepts a shebang line (such as `#!/usr/bin/env python3`), as found at the start of scripts,
+/// including the newline that ends it.
+///
+/// A shebang is only meaningful at the very start of the input (after a [`bom`], if any), so this parser should only be
+/// used there. To accept scripts whether or not they have a shebang, use it with [`Parser::or_not`].
+///
+/// The output type of this parser is [`Char::Str`] (i.e: [`&str`] when `C` is [`char`], and [`&[u8]`] when `C` is
+/// [`u8`]), the rest of the line after the `#!`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let script = text::shebang::<_, _, extra::Err<Simple<char>>>()
+///     .or_not()
+///     .then(text::ascii::ident());
+///
+/// assert_eq!(
+///     script.parse("#!/usr/bin/env lua\nmain").into_result(),
+///     Ok((Some("/usr/bin/env lua"), "main")),
+/// );
+/// assert_eq!(script.parse("main").into_result(), Ok((None, "main")));
+/// ```
+#[must_use]
+pub fn shebang<'a, I: ValueInput<'a> + StrInput<'a, C>, C: Char, E: ParserExtra<'a, I>>(
+) -> impl Parser<'a, I, &'a C::Str, E> + Copy {
+    just(C::from_ascii(b'#'))
+        .ignore_then(just(C::from_ascii(b'!')))
+        .ignore_then(rest_of_line())
+        .then_ignore(eol_or_eof())
+}
+
+/// An encoding of Unicode text, as detected by [`sniff_encoding`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Encoding {
+    /// UTF-8.
+    Utf8,
+    /// UTF-16, with the least significant byte of each code unit first.
+    Utf16Le,
+    /// UTF-16, with the most significant byte of each code unit first.
+    Utf16Be,
+}
+
+/// Detect the encoding of some bytes from their byte order mark, returning the encoding and the length of the byte
+/// order mark in bytes.
+///
+/// Bytes without a byte order mark are assumed to be UTF-8, with a byte order mark of length zero.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::text::{sniff_encoding, Encoding};
+/// assert_eq!(sniff_encoding(b"\xEF\xBB\xBFa"), (Encoding::Utf8, 3));
+/// assert_eq!(sniff_encoding(b"\xFF\xFEa\0"), (Encoding::Utf16Le, 2));
+/// assert_eq!(sniff_encoding(b"a"), (Encoding::Utf8, 0));
+/// ```
+pub fn sniff_encoding(bytes: &[u8]) -> (Encoding, usize) {
+    match bytes {
+        [0xEF, 0xBB, 0xBF, ..] => (Encoding::Utf8, 3),
+        [0xFF, 0xFE, ..] => (Encoding::Utf16Le, 2),
+        [0xFE, 0xFF, ..] => (Encoding::Utf16Be, 2),
+        _ => (Encoding::Utf8, 0),
+    }
+}
+
+/// An error produced by [`decode`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The bytes are not valid UTF-8.
+    InvalidUtf8(core::str::Utf8Error),
+    /// The bytes are not valid UTF-16. The offset of the invalid code unit is given in bytes, from the start of the
+    /// input (including the byte order mark).
+    InvalidUtf16(usize),
+    /// The bytes are in an encoding that cannot be decoded without the `transcode` feature.
+    Unsupported(Encoding),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUtf8(err) => write!(f, "invalid UTF-8: {err}"),
+            Self::InvalidUtf16(offset) => write!(f, "invalid UTF-16 at byte {offset}"),
+            Self::Unsupported(encoding) => write!(f, "unsupported encoding {encoding:?}"),
+        }
+    }
+}
+
+/// Decode some bytes into a string that can be parsed, using their byte order mark (as per [`sniff_encoding`]) to
+/// determine their encoding.
+///
+/// The byte order mark is not included in the resulting string. UTF-8 input is borrowed without copying. UTF-16 input
+/// is transcoded into a new string if the `transcode` feature is enabled, and otherwise produces
+/// [`DecodeError::Unsupported`].
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let ident = text::ascii::ident::<_, _, extra::Err<Simple<char>>>();
+///
+/// let src = text::decode(b"\xEF\xBB\xBFhello").unwrap();
+/// assert_eq!(ident.parse(src.as_ref()).into_result(), Ok("hello"));
+/// # #[cfg(feature = "transcode")]
+/// # {
+/// let src = text::decode(b"\xFE\xFF\0h\0i").unwrap();
+/// assert_eq!(ident.parse(src.as_ref()).into_result(), Ok("hi"));
+/// # }
+/// ```
+pub fn decode(bytes: &[u8]) -> Result<Cow<'_, str>, DecodeError> {
+    let (encoding, bom_len) = sniff_encoding(bytes);
+    let rest = &bytes[bom_len..];
+    match encoding {
+        Encoding::Utf8 => core::str::from_utf8(rest)
+            .map(Cow::Borrowed)
+            .map_err(DecodeError::InvalidUtf8),
+        #[cfg(feature = "transcode")]
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            let from_bytes = match encoding {
+                Encoding::Utf16Le => u16::from_le_bytes,
+                _ => u16::from_be_bytes,
+            };
+            let units = rest.chunks_exact(2);
+            // A trailing odd byte is an incomplete code unit
+            if !units.remainder().is_empty() {
+                return Err(DecodeError::InvalidUtf16(bytes.len() - 1));
+            }
+            let units = units.map(|unit| from_bytes([unit[0], unit[1]]));
+            let mut out = String::with_capacity(rest.len() / 2);
+            let mut offset = bom_len;
+            for c in core::char::decode_utf16(units) {
+                let c = c.map_err(|_| DecodeError::InvalidUtf16(offset))?;
+                offset += c.len_utf16() * 2;
+                out.push(c);
+            }
+            Ok(Cow::Owned(out))
+        }
+        #[cfg(not(feature = "transcode"))]
+        encoding => Err(DecodeError::Unsupported(encoding)),
+    }
+}
+
+enum Delimiter<'a, C: Char> {
+    Char(C),
+    Str(&'a C::Str),
+}
+
+impl<'a, C: Char> Copy for Delimiter<'a, C> {}
+impl<'a, C: Char> Clone for Delimiter<'a, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+/// See [`until_char`] and [`until_str`].
+pub struct Until<'a, C: Char, I, E> {
+    delimiter: Delimiter<'a, C>,
+    consume: bool,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(I, E)>,
+}
+
+impl<'a, C: Char, I, E> Copy for Until<'a, C, I, E> {}
+impl<'a, C: Char, I, E> Clone for Until<'a, C, I, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, C: Char, I, E> Until<'a, C, I, E> {
+    /// Consume the delimiter too, rather than stopping just before it.
+    ///
+    /// The delimiter is still not included in the output.
+    pub fn consume_delimiter(self) -> Self {
+        Self {
+            consume: true,
+            ..self
+        }
+    }
+}
+
+impl<'a, C, I, E> ParserSealed<'a, I, &'a C::Str, E> for Until<'a, C, I, E>
+where
+    C: Char,
+    I: StrInput<'a, C>,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, &'a C::Str> {
+        let before = inp.offset();
+        let haystack = inp.slice_trailing_inner();
+        let found = match self.delimiter {
+            Delimiter::Char(c) => C::str_find_char(haystack, c).map(|pos| (pos, c.len_bytes())),
+            Delimiter::Str(s) => {
+                C::str_find(haystack, s).map(|pos| (pos, <C::Str as AsRef<[u8]>>::as_ref(s).len()))
+            }
+        };
+        match found {
+            Some((pos, len)) => {
+                inp.skip_bytes(pos);
+                let out = inp.slice_since(before);
+                if self.consume {
+                    inp.skip_bytes(len);
+                }
+                Ok(M::bind(|| out))
+            }
+            None => {
+                inp.skip_bytes(<C::Str as AsRef<[u8]>>::as_ref(haystack).len());
+                let span = inp.span_since(before);
+                let err = match self.delimiter {
+                    Delimiter::Char(c) => {
+                        E::Error::expected_found(Some(Some(MaybeRef::Val(c))), None, span)
+                    }
+                    Delimiter::Str(s) => E::Error::expected_seq_found(
+                        SeqKind::Literal,
+                        C::str_to_chars(s).map(MaybeRef::Val),
+                        C::str_to_chars(s).next().map(|c| Some(MaybeRef::Val(c))),
+                        None,
+                        span,
+                    ),
+                };
+                inp.add_alt_err(inp.offset().offset, err);
+                Err(())
+            }
+        }
+    }
+
+    go_extra!(&'a C::Str);
+}
+
+/// A parser that accepts every character up to the first occurrence of the given delimiter character, failing if it
+/// does not occur.
+///
+/// Unlike `any().and_is(just(c).not()).repeated()`, this scans the input directly rather than invoking a parser for
+/// each character, and so is much faster for long runs of input such as the contents of string literals. By default,
+/// the parser stops before the delimiter: use [`Until::consume_delimiter`] to consume it too.
+///
+/// The output type of this parser is [`Char::Str`] (i.e: [`&str`] when `C` is [`char`], and [`&[u8]`] when `C` is
+/// [`u8`]), and does not include the delimiter.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let string = just::<_, _, extra::Err<Simple<char>>>('"')
+///     .ignore_then(text::until_char('"').consume_delimiter());
+///
+/// assert_eq!(string.parse(r#""hello, world""#).into_result(), Ok("hello, world"));
+/// assert_eq!(string.parse(r#""""#).into_result(), Ok(""));
+/// // Unterminated
+/// assert!(string.parse(r#""hello"#).has_errors());
+/// ```
+#[must_use]
+pub const fn until_char<'a, C: Char, I: StrInput<'a, C>, E: ParserExtra<'a, I>>(
+    delimiter: C,
+) -> Until<'a, C, I, E> {
+    Until {
+        delimiter: Delimiter::Char(delimiter),
+        consume: false,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// A parser that accepts every character up to the first occurrence of the given delimiter string, failing if it does
+/// not occur.
+///
+/// This is the multi-character equivalent of [`until_char`]: it uses a substring search rather than invoking a parser
+/// at each position, as `any().and_is(just(s).not()).repeated()` would.
+///
+/// The output type of this parser is [`Char::Str`] (i.e: [`&str`] when `C` is [`char`], and [`&[u8]`] when `C` is
+/// [`u8`]), and does not include the delimiter.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let comment = just::<_, _, extra::Err<Rich<char>>>("<!--")
+///     .ignore_then(text::until_str("-->").consume_delimiter());
+///
+/// assert_eq!(comment.parse("<!-- a -- b -->").into_result(), Ok(" a -- b "));
+/// let errs = comment.parse("<!-- a -").into_errors();
+/// assert_eq!(errs[0].to_string(), "found end of input expected '-->'");
+/// ```
+#[must_use]
+pub const fn until_str<'a, C: Char, I: StrInput<'a, C>, E: ParserExtra<'a, I>>(
+    delimiter: &'a C::Str,
+) -> Until<'a, C, I, E> {
+    Until {
+        delimiter: Delimiter::Str(delimiter),
+        consume: false,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// A parser that accepts one or more ASCII digits.
+///
+/// The output type of this parser is `I::Slice` (i.e: [`&str`] when `I` is [`&str`], and [`&[u8]`]
+/// when `I::Slice` is [`&[u8]`]).
+///
+/// The `radix` parameter functions identically to [`char::is_digit`]. If in doubt, choose `10`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let digits = text::digits::<_, _, extra::Err<Simple<char>>>(10).slice();
+///
+/// assert_eq!(digits.parse("0").into_result(), Ok("0"));
+/// assert_eq!(digits.parse("1").into_result(), Ok("1"));
+/// assert_eq!(digits.parse("01234").into_result(), Ok("01234"));
+/// assert_eq!(digits.parse("98345").into_result(), Ok("98345"));
+/// // A string of zeroes is still valid. Use `int` if this is not desirable.
+/// assert_eq!(digits.parse("0000").into_result(), Ok("0000"));
+/// assert!(digits.parse("").has_errors());
+/// ```
+#[must_use]
+pub fn digits<'a, C, I, E>(radix: u32) -> Repeated<impl Parser<'a, I, C, E> + Copy + Clone, C, I, E>
+where
+    C: Char,
+    I: ValueInput<'a> + Input<'a, Token = C>,
+    E: ParserExtra<'a, I>,
+{
+    any()
+        // Use try_map over filter to get a better error on failure
+        .try_map(move |c: C, span| {
+            if c.is_digit(radix) {
+                Ok(c)
+            } else {
+                Err(Error::expected_found([], Some(MaybeRef::Val(c)), span))
+            }
+        })
+        .repeated()
+        .at_least(1)
+}
+
+/// A parser that accepts a non-negative integer.
+///
+/// An integer is defined as a non-empty sequence of ASCII digits, where the first digit is non-zero or the sequence
+/// has length one.
+///
+/// The output type of this parser is `I::Slice` (i.e: [`&str`] when `I` is [`&str`], and [`&[u8]`]
+/// when `I::Slice` is [`&[u8]`]).
+///
+/// The `radix` parameter functions identically to [`char::is_digit`]. If in doubt, choose `10`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::prelude::*;
+/// let dec = text::int::<_, _, extra::Err<Simple<char>>>(10);
+///
+/// assert_eq!(dec.parse("0").into_result(), Ok("0"));
+/// assert_eq!(dec.parse("1").into_result(), Ok("1"));
+/// assert_eq!(dec.parse("1452").into_result(), Ok("1452"));
+/// // No leading zeroes are permitted!
+/// assert!(dec.parse("04").has_errors());
+///
+/// let hex = text::int::<_, _, extra::Err<Simple<char>>>(16);
+///
+/// assert_eq!(hex.parse("2A").into_result(), Ok("2A"));
+/// assert_eq!(hex.parse("d").into_result(), Ok("d"));
+/// assert_eq!(hex.parse("b4").into_result(), Ok("b4"));
+/// assert!(hex.parse("0B").has_errors());
+/// ```
+///
+#[must_use]
+pub fn int<'a, I: ValueInput<'a> + StrInput<'a, C>, C: Char, E: ParserExtra<'a, I>>(
+    radix: u32,
+) -> impl Parser<'a, I, &'a C::Str, E> + Copy + Clone {
+    any()
+        // Use try_map over filter to get a better error on failure
+        .try_map(move |c: C, span| {
+            if c.is_digit(radix) && c != C::digit_zero() {
+                Ok(c)
+            } else {
+                Err(Error::expected_found([], Some(MaybeRef::Val(c)), span))
+            }
+        })
+        // This error never appears due to `repeated` so can use `filter`
+        .then(any().filter(move |c: &C| c.is_digit(radix)).repeated())
+        .ignored()
+        .or(just(C::digit_zero()).ignored())
+        .slice()
+}
+
+/// The syntax of the numeric literals accepted by [`number`].
+///
+/// Each part of the syntax is configured by an associated constant. The defaults accept only unsigned decimal
+/// integers, such as `42`, so a policy need only override the constants for the parts its language permits.
+///
+/// Prefixes, separators, exponent markers and suffixes must be ASCII.
+pub trait NumberPolicy {
+    /// Whether a literal may begin with a `+` or `-` sign.
+    const SIGN: bool = false;
+
+    /// The prefixes that may appear before the digits of a literal, each with the radix of the digits it introduces
+    /// (e.g: `("0x", 16)`). Literals without a prefix are decimal.
+    ///
+    /// Prefixes are matched case-sensitively, and are tried in order.
+    const PREFIXES: &'static [(&'static str, u32)] = &[];
+
+    /// A character that may be used to separate groups of digits (e.g: `'_'` in `1_000_000`).
+    ///
+    /// Separators may appear anywhere after the first digit of each part of a literal.
+    const SEPARATOR: Option<char> = None;
+
+    /// Whether a decimal literal may have a fractional part, a `.` followed by digits.
+    const FRACTION: bool = false;
+
+    /// The characters that may introduce the exponent of a decimal literal (e.g: `['e', 'E']`). An exponent is
+    /// digits, optionally preceded by a sign.
+    const EXPONENT: &'static [char] = &[];
+
+    /// The suffixes that may follow a literal to indicate its type (e.g: `"u8"` or `"f32"`).
+    ///
+    /// If several suffixes match, the longest is used.
+    const SUFFIXES: &'static [&'static str] = &[];
+}
+
+/// A numeric literal parsed by [`number`].
+///
+/// Each part of the literal is given as a slice of the input, with any separators left in place.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NumberLiteral<S> {
+    /// The whole literal, including its sign, prefix and suffix.
+    pub slice: S,
+    /// Whether the literal began with a `-` sign.
+    pub negative: bool,
+    /// The radix of the literal's digits, as given by its prefix, or 10 if it had none.
+    pub radix: u32,
+    /// The digits of the integer part of the literal, after the prefix.
+    pub integer: S,
+    /// The digits of the fractional part of the literal, after the `.`, if it had one.
+    pub fraction: Option<S>,
+    /// The exponent of the literal, after the exponent marker and including any sign, if it had one.
+    pub exponent: Option<S>,
+    /// The suffix of the literal, if it had one.
+    pub suffix: Option<S>,
+}
+
+impl<S> NumberLiteral<S> {
+    /// Whether the literal had a fractional part or an exponent.
+    pub fn is_float(&self) -> bool {
+        self.fraction.is_some() || self.exponent.is_some()
+    }
+}
+
+/// A parser that accepts a numeric literal with the syntax described by the policy `P`.
+///
+/// Almost every language has numeric literals, and most of them differ only in which of a handful of features they
+/// support: signs, radix prefixes, digit separators, fractions, exponents and type suffixes. A [`NumberPolicy`]
+/// chooses between them, so that the same well-tested parser can be used for each language.
+///
+/// A `.` or exponent marker that is not followed by digits is not part of the literal, so `1.max(2)` is parsed as the
+/// literal `1` followed by `.max(2)`. Similarly, nothing checks what follows the literal, so a language that forbids
+/// `123abc` should check for that itself.
+///
+/// The output type of this parser is [`NumberLiteral<&C::Str>`](NumberLiteral).
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, text::{NumberLiteral, NumberPolicy}};
+/// struct Rust;
+///
+/// impl NumberPolicy for Rust {
+///     const PREFIXES: &'static [(&'static str, u32)] = &[("0x", 16), ("0o", 8), ("0b", 2)];
+///     const SEPARATOR: Option<char> = Some('_');
+///     const FRACTION: bool = true;
+///     const EXPONENT: &'static [char] = &['e', 'E'];
+///     const SUFFIXES: &'static [&'static str] = &["u8", "u16", "u32", "u64", "f32", "f64"];
+/// }
+///
+/// let number = text::number::<Rust, _, _, extra::Err<Rich<char>>>();
+///
+/// let lit = number.parse("0xFF_u8").into_result().unwrap();
+/// assert_eq!((lit.radix, lit.integer, lit.suffix), (16, "FF_", Some("u8")));
+///
+/// assert_eq!(
+///     number.parse("1_000.5e-3f64").into_result(),
+///     Ok(NumberLiteral {
+///         slice: "1_000.5e-3f64",
+///         negative: false,
+///         radix: 10,
+///         integer: "1_000",
+///         fraction: Some("5"),
+///         exponent: Some("-3"),
+///         suffix: Some("f64"),
+///     }),
+/// );
+///
+/// // Rust has no signed literals, and hexadecimal literals need at least one digit
+/// assert!(number.parse("-1").has_errors());
+/// assert!(number.parse("0x").has_errors());
+/// ```
+#[must_use]
+pub fn number<'a, P, I, C, E>() -> impl Parser<'a, I, NumberLiteral<&'a C::Str>, E> + Copy
+where
+    P: NumberPolicy,
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    Number::<P, C> {
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+struct Number<P, C> {
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<(P, C)>,
+}
+
+impl<P, C> Copy for Number<P, C> {}
+impl<P, C> Clone for Number<P, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, P, I, C, E> ParserSealed<'a, I, NumberLiteral<&'a C::Str>, E> for Number<P, C>
+where
+    P: NumberPolicy,
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    #[inline]
+    fn go<M: Mode>(
+        &self,
+        inp: &mut InputRef<'a, '_, I, E>,
+    ) -> PResult<M, NumberLiteral<&'a C::Str>> {
+        let start = inp.offset;
+
+        let negative = match inp.peek().map(|c| c.to_char()) {
+            Some(sign @ ('+' | '-')) if P::SIGN => {
+                inp.skip();
+                sign == '-'
+            }
+            _ => false,
+        };
+
+        let radix = P::PREFIXES
+            .iter()
+            .find(|(prefix, _)| eat_ascii(inp, prefix))
+            .map_or(10, |(_, radix)| *radix);
+
+        let integer = eat_digits::<P, _, _, _>(inp, radix)?;
+
+        let mut fraction = None;
+        if radix == 10 && P::FRACTION {
+            let before = inp.save();
+            if eat_ascii(inp, ".") && matches!(inp.peek(), Some(c) if c.is_digit(10)) {
+                fraction = Some(eat_digits::<P, _, _, _>(inp, 10)?);
+            } else {
+                inp.rewind(before);
+            }
+        }
+
+        let mut exponent = None;
+        if radix == 10 && !P::EXPONENT.is_empty() {
+            let before = inp.save();
+            if matches!(inp.peek(), Some(c) if P::EXPONENT.contains(&c.to_char())) {
+                inp.skip();
+                let exp_start = inp.offset;
+                if !eat_ascii(inp, "+") {
+                    eat_ascii(inp, "-");
+                }
+                if matches!(inp.peek(), Some(c) if c.is_digit(10)) {
+                    let digits = eat_digits::<P, _, _, _>(inp, 10)?;
+                    exponent = Some(exp_start..digits.end);
+                }
+            }
+            if exponent.is_none() {
+                inp.rewind(before);
+            }
+        }
+
+        // Take the longest matching suffix, so that suffixes which are prefixes of others (like `i` and `i32`) work
+        let suffix_start = inp.offset;
+        let longest = P::SUFFIXES
+            .iter()
+            .filter(|s| {
+                let before = inp.save();
+                let found = eat_ascii(inp, s);
+                inp.rewind(before);
+                found
+            })
+            .max_by_key(|s| s.len());
+        let suffix = longest.map(|s| {
+            eat_ascii(inp, s);
+            suffix_start..inp.offset
+        });
+
+        let end = inp.offset;
+        Ok(M::bind(|| NumberLiteral {
+            slice: inp.slice_inner(start..end),
+            negative,
+            radix,
+            integer: inp.slice_inner(integer),
+            fraction: fraction.map(|r| inp.slice_inner(r)),
+            exponent: exponent.map(|r| inp.slice_inner(r)),
+            suffix: suffix.map(|r| inp.slice_inner(r)),
+        }))
+    }
+
+    go_extra!(NumberLiteral<&'a C::Str>);
+}
+
+// Consume the given ASCII text if it comes next, leaving the input untouched otherwise
+fn eat_ascii<'a, I, C, E>(inp: &mut InputRef<'a, '_, I, E>, s: &str) -> bool
+where
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    let before = inp.save();
+    for b in s.bytes() {
+        if inp.next().map(|c| c.to_char()) != Some(b as char) {
+            inp.rewind(before);
+            return false;
+        }
+    }
+    true
+}
+
+// Consume a digit of the given radix, followed by any number of digits and separators
+fn eat_digits<'a, P, I, C, E>(
+    inp: &mut InputRef<'a, '_, I, E>,
+    radix: u32,
+) -> Result<Range<usize>, ()>
+where
+    P: NumberPolicy,
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+{
+    let start = inp.offset;
+    let before = inp.offset();
+    match inp.next_inner() {
+        (_, Some(c)) if c.is_digit(radix) => {}
+        (at, found) => {
+            let span = inp.span_since(before);
+            inp.add_alt(at, None, found.map(Into::into), span);
+            return Err(());
+        }
+    }
+    while matches!(inp.peek(), Some(c) if c.is_digit(radix) || Some(c.to_char()) == P::SEPARATOR) {
+        inp.skip();
+    }
+    Ok(start..inp.offset)
+}
+
+/// A word parsed by [`reserved`]: either one of its reserved keywords, or an ordinary identifier.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Word<K, S> {
+    /// A reserved keyword.
+    Keyword(K),
+    /// An identifier that is not a reserved keyword.
+    Ident(S),
+}
+
+impl<K, S> Word<K, S> {
+    /// Returns the keyword, if this word is one.
+    pub fn keyword(self) -> Option<K> {
+        match self {
+            Self::Keyword(k) => Some(k),
+            Self::Ident(_) => None,
+        }
+    }
+
+    /// Returns the identifier, if this word is not a reserved keyword.
+    pub fn ident(self) -> Option<S> {
+        match self {
+            Self::Keyword(_) => None,
+            Self::Ident(s) => Some(s),
+        }
+    }
+}
+
+/// See [`reserved`].
+pub struct Reserved<A, C, Str, K> {
+    ident: A,
+    keywords: Vec<(Str, K)>,
+    case_sensitive: bool,
+    #[allow(dead_code)]
+    phantom: EmptyPhantom<C>,
+}
+
+impl<A: Clone, C, Str: Clone, K: Clone> Clone for Reserved<A, C, Str, K> {
+    fn clone(&self) -> Self {
+        Self {
+            ident: self.ident.clone(),
+            keywords: self.keywords.clone(),
+            case_sensitive: self.case_sensitive,
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<A, C, Str, K> Reserved<A, C, Str, K> {
+    /// Match keywords regardless of case, as in SQL or BASIC, such that `SELECT`, `select` and `Select` are all the
+    /// same keyword.
+    ///
+    /// Identifiers keep their original case in the output.
+    pub fn case_insensitive(self) -> Self {
+        Self {
+            case_sensitive: false,
+            ..self
+        }
+    }
+}
+
+fn eq_ignore_case<C: Char>(a: &C::Str, b: &C::Str) -> bool {
+    C::str_to_chars(a)
+        .flat_map(|c| c.to_char().to_lowercase())
+        .eq(C::str_to_chars(b).flat_map(|c| c.to_char().to_lowercase()))
+}
+
+impl<'a, I, C, E, A, Str, K> ParserSealed<'a, I, Word<K, &'a C::Str>, E> for Reserved<A, C, Str, K>
+where
+    I: StrInput<'a, C>,
+    C: Char,
+    C::Str: PartialEq,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, &'a C::Str, E>,
+    Str: AsRef<C::Str>,
+    K: Clone,
+{
+    #[inline]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, Word<K, &'a C::Str>> {
+        let s = self.ident.go::<Emit>(inp)?;
+        let keyword = self.keywords.iter().find(|(kw, _)| {
+            if self.case_sensitive {
+                kw.as_ref() == s
+            } else {
+                eq_ignore_case::<C>(kw.as_ref(), s)
+            }
+        });
+        Ok(M::bind(|| match keyword {
+            Some((_, k)) => Word::Keyword(k.clone()),
+            None => Word::Ident(s),
+        }))
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.ident.walk_node(walker));
+    }
+
+    go_extra!(Word<K, &'a C::Str>);
+}
+
+/// A parser that accepts an identifier using the given `ident` parser, and then classifies it as either one of a set of
+/// reserved `keywords` or an ordinary identifier.
+///
+/// Keywords are given as pairs of the keyword's text and the value to produce when it is found. By default, keywords
+/// are matched case-sensitively: use [`Reserved::case_insensitive`] for languages like SQL, where they are not.
+///
+/// Because the whole identifier is parsed first, identifiers that merely begin with a keyword (such as `selection`)
+/// are not mistaken for it. Use [`Word::ident`] with [`Parser::try_map`] to reject reserved words where only an
+/// identifier is permitted.
+///
+/// The output type of this parser is [`Word<K, &C::Str>`](Word).
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, text::Word};
+/// #[derive(Clone, Debug, PartialEq)]
+/// enum Kw { Select, From, Where }
+///
+/// let word = text::reserved::<_, _, _, _, _, extra::Err<Rich<char>>>(
+///     text::ascii::ident(),
+///     [("select", Kw::Select), ("from", Kw::From), ("where", Kw::Where)],
+/// )
+/// .case_insensitive();
+///
+/// assert_eq!(word.parse("SELECT").into_result(), Ok(Word::Keyword(Kw::Select)));
+/// assert_eq!(word.parse("From").into_result(), Ok(Word::Keyword(Kw::From)));
+/// // Identifiers keep their case
+/// assert_eq!(word.parse("Users").into_result(), Ok(Word::Ident("Users")));
+/// // A keyword at the start of an identifier is still an identifier
+/// assert_eq!(word.parse("wherever").into_result(), Ok(Word::Ident("wherever")));
+///
+/// // Where only an identifier is permitted, reserved words can be rejected
+/// let column = word.try_map(|w, span| w.ident().ok_or(Rich::custom(span, "reserved word")));
+/// assert_eq!(column.parse("name").into_result(), Ok("name"));
+/// assert!(column.parse("where").has_errors());
+/// ```
+pub fn reserved<'a, A, I, C, Str, K, E>(
+    ident: A,
+    keywords: impl IntoIterator<Item = (Str, K)>,
+) -> Reserved<A, C, Str, K>
+where
+    I: StrInput<'a, C>,
+    C: Char,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, &'a C::Str, E>,
+    Str: AsRef<C::Str>,
+{
+    Reserved {
+        ident,
+        keywords: keywords.into_iter().collect(),
+        case_sensitive: true,
+        phantom: EmptyPhantom::new(),
+    }
+}
+
+/// Parsers and utilities for working with ASCII inputs.
+pub mod ascii {
+    use super::*;
+
+    /// A parser that accepts (and ignores) any number of ASCII whitespace characters.
+    ///
+    /// The accepted characters are those of [`char::is_ascii_whitespace`]: space, `\t`, `\n`, `\x0C` and `\r`. Unlike
+    /// [`text::whitespace`](super::whitespace), this parser does not need to consider Unicode whitespace (such as
+    /// non-breaking spaces) when `C` is [`char`], making it a cheaper choice for grammars that only permit ASCII
+    /// whitespace.
+    ///
+    /// This parser is a `Parser::Repeated` and so methods such as `at_least()` can be called on it.
+    ///
+    /// The output type of this parser is `()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let whitespace = text::ascii::whitespace::<_, _, extra::Err<Simple<char>>>();
+    ///
+    /// assert_eq!(whitespace.parse(" \t\r\n").into_result(), Ok(()));
+    /// // Non-breaking spaces are not ASCII whitespace
+    /// assert!(whitespace.parse("\u{A0}").has_errors());
+    /// ```
+    pub fn whitespace<'a, C: Char, I: ValueInput<'a> + StrInput<'a, C>, E: ParserExtra<'a, I>>(
+    ) -> Repeated<impl Parser<'a, I, (), E> + Copy, (), I, E>
+    where
+        I::Token: Char,
+    {
+        any()
+            .filter(|c: &I::Token| c.to_char().is_ascii_whitespace())
+            .ignored()
+            .repeated()
+    }
+
+    /// A parser that accepts a C-style identifier.
+    ///
+    /// The output type of this parser is [`Char::Str`] (i.e: [`&str`] when `C` is [`char`], and [`&[u8]`] when `C` is
+    /// [`u8`]).
+    ///
+    /// An identifier is defined as an ASCII alphabetic character or an underscore followed by any number of alphanumeric
+    /// characters or underscores. The regex pattern for it is `[a-zA-Z_][a-zA-Z0-9_]*`.
+    #[must_use]
+    pub fn ident<'a, I: ValueInput<'a> + StrInput<'a, C>, C: Char, E: ParserExtra<'a, I>>(
+    ) -> impl Parser<'a, I, &'a C::Str, E> + Copy + Clone {
+        any()
+            // Use try_map over filter to get a better error on failure
+            .try_map(|c: C, span| {
                 if c.to_char().is_ascii_alphabetic() || c.to_char() == '_' {
                     Ok(c)
                 } else {
@@ -426,15 +1744,27 @@ pub mod ascii {
                 assert!(c.to_char().is_ascii_alphanumeric() || c.to_char() == '_', "Trailing characters of a keyword must be ASCII alphanumeric or an underscore, not {:?}", c);
             }
         }
-        ident()
-            .try_map(move |s: &C::Str, span| {
-                if s == keyword.as_ref() {
-                    Ok(())
-                } else {
-                    Err(Error::expected_found(None, None, span))
+        custom(move |inp| {
+            let before = inp.save();
+            let found = inp.peek_maybe();
+            match inp.parse(ident()) {
+                Ok(s) if s == keyword.as_ref() => return Ok(s),
+                // A different identifier was found, so the error spans all of it
+                Ok(_) => {}
+                // No identifier was found, so the error spans the token in its place
+                Err(_) => {
+                    inp.rewind(before);
+                    inp.skip();
                 }
-            })
-            .slice()
+            }
+            Err(Error::expected_seq_found(
+                SeqKind::Keyword,
+                C::str_to_chars(keyword.as_ref()).map(MaybeRef::Val),
+                None,
+                found,
+                inp.span_since(before.offset()),
+            ))
+        })
     }
 }
 
@@ -442,6 +1772,71 @@ pub mod ascii {
 pub mod unicode {
     use super::*;
 
+    /// A parser that accepts (and ignores) any number of characters with the Unicode `White_Space` property.
+    ///
+    /// When `C` is [`char`], this is the same as [`text::whitespace`](super::whitespace). When `C` is [`u8`], each byte
+    /// is treated as a character in the range `0..=255`, so a byte `0x85` or `0xA0` is accepted: this is only correct
+    /// for Latin-1 inputs, so prefer [`text::ascii::whitespace`](super::ascii::whitespace) for UTF-8 bytes.
+    ///
+    /// This parser is a `Parser::Repeated` and so methods such as `at_least()` can be called on it.
+    ///
+    /// The output type of this parser is `()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let whitespace = text::unicode::whitespace::<_, _, extra::Err<Simple<char>>>();
+    ///
+    /// assert_eq!(whitespace.parse(" \t\n\u{A0}\u{2003}\u{2028}").into_result(), Ok(()));
+    /// ```
+    pub fn whitespace<'a, C: Char, I: ValueInput<'a> + StrInput<'a, C>, E: ParserExtra<'a, I>>(
+    ) -> Repeated<impl Parser<'a, I, (), E> + Copy, (), I, E>
+    where
+        I::Token: Char,
+    {
+        any()
+            .filter(|c: &I::Token| c.to_char().is_whitespace())
+            .ignored()
+            .repeated()
+    }
+
+    /// A parser that accepts (and ignores) any number of Unicode whitespace characters, except for newlines.
+    ///
+    /// This accepts the same characters as [`whitespace`], apart from those recognised by
+    /// [`text::newline`](super::newline). It is useful for line-oriented grammars that permit Unicode whitespace, such
+    /// as non-breaking spaces, within a line. For ASCII inputs, [`text::inline_whitespace`](super::inline_whitespace)
+    /// (which only accepts spaces and tabs) is usually sufficient.
+    ///
+    /// This parser is a `Parser::Repeated` and so methods such as `at_least()` can be called on it.
+    ///
+    /// The output type of this parser is `()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let inline_whitespace = text::unicode::inline_whitespace::<_, _, extra::Err<Simple<char>>>();
+    ///
+    /// assert_eq!(inline_whitespace.parse(" \t\u{A0}\u{2003}").into_result(), Ok(()));
+    /// assert!(inline_whitespace.parse("\n").has_errors());
+    /// assert!(inline_whitespace.parse("\u{2028}").has_errors());
+    /// ```
+    pub fn inline_whitespace<
+        'a,
+        C: Char,
+        I: ValueInput<'a> + StrInput<'a, C>,
+        E: ParserExtra<'a, I>,
+    >() -> Repeated<impl Parser<'a, I, (), E> + Copy, (), I, E>
+    where
+        I::Token: Char,
+    {
+        any()
+            .filter(|c: &I::Token| c.to_char().is_whitespace() && !c.is_newline())
+            .ignored()
+            .repeated()
+    }
+
     /// A parser that accepts an identifier.
     ///
     /// The output type of this parser is [`Char::Str`] (i.e: [`&str`] when `C` is [`char`], and [`&[u8]`] when `C` is
@@ -517,15 +1912,27 @@ pub mod unicode {
                 assert!(c.is_ident_continue(), "Trailing characters of a keyword must be valid as unicode XID_CONTINUE, not {:?}", c);
             }
         }
-        ident()
-            .try_map(move |s: &C::Str, span| {
-                if s == keyword.as_ref() {
-                    Ok(())
-                } else {
-                    Err(Error::expected_found(None, None, span))
+        custom(move |inp| {
+            let before = inp.save();
+            let found = inp.peek_maybe();
+            match inp.parse(ident()) {
+                Ok(s) if s == keyword.as_ref() => return Ok(s),
+                // A different identifier was found, so the error spans all of it
+                Ok(_) => {}
+                // No identifier was found, so the error spans the token in its place
+                Err(_) => {
+                    inp.rewind(before);
+                    inp.skip();
                 }
-            })
-            .slice()
+            }
+            Err(Error::expected_seq_found(
+                SeqKind::Keyword,
+                C::str_to_chars(keyword.as_ref()).map(MaybeRef::Val),
+                None,
+                found,
+                inp.span_since(before.offset()),
+            ))
+        })
     }
 }
 
@@ -534,6 +1941,7 @@ pub mod unicode {
 #[cfg(test)]
 mod tests {
     use crate::prelude::*;
+    use text::Word;
 
     fn make_ascii_kw_parser<'a, C: text::Char, I: crate::StrInput<'a, C>>(
         s: &'a C::Str,
@@ -586,4 +1994,334 @@ mod tests {
     fn keyword_unicode_in_ascii() {
         make_ascii_kw_parser::<char, &str>("שלום");
     }
+
+    #[test]
+    fn keyword_errors_name_the_keyword() {
+        let kw = text::ascii::keyword::<_, _, _, extra::Err<Rich<char>>>("fn");
+
+        let errs = kw.parse("let").into_errors();
+        assert_eq!(errs[0].span(), &SimpleSpan::new(0, 3));
+        assert_eq!(errs[0].to_string(), "found 'l' expected keyword 'fn'");
+        assert!(matches!(
+            errs[0].expected().next(),
+            Some(crate::error::RichPattern::Seq { kind: crate::error::SeqKind::Keyword, seq }) if seq.len() == 2
+        ));
+
+        let errs = kw.parse("(").into_errors();
+        assert_eq!(errs[0].span(), &SimpleSpan::new(0, 1));
+        assert_eq!(errs[0].to_string(), "found '(' expected keyword 'fn'");
+
+        // Plain sequences are named as a whole too, when none of them was found
+        let errs = just::<_, _, extra::Err<Rich<char>>>("fn")
+            .or(just("let"))
+            .parse("x")
+            .into_errors();
+        assert_eq!(errs[0].to_string(), "found 'x' expected 'fn', or 'let'");
+    }
+
+    #[test]
+    fn reserved_words() {
+        #[derive(Clone, Debug, PartialEq)]
+        enum Kw {
+            Let,
+            In,
+        }
+
+        let word = text::reserved::<_, _, _, _, _, extra::Default>(
+            text::ascii::ident(),
+            [("let", Kw::Let), ("in", Kw::In)],
+        );
+        assert_eq!(word.parse("let").into_result(), Ok(Word::Keyword(Kw::Let)));
+        assert_eq!(word.parse("LET").into_result(), Ok(Word::Ident("LET")));
+        assert_eq!(word.parse("inner").into_result(), Ok(Word::Ident("inner")));
+        assert!(word.parse("1").has_errors());
+
+        let word = word.case_insensitive();
+        assert_eq!(word.parse("LET").into_result(), Ok(Word::Keyword(Kw::Let)));
+        assert_eq!(word.parse("In").into_result(), Ok(Word::Keyword(Kw::In)));
+
+        // Byte strings work too
+        let word = text::reserved::<_, &[u8], _, _, _, extra::Default>(
+            text::ascii::ident(),
+            [(b"end".as_slice(), ())],
+        )
+        .case_insensitive();
+        assert_eq!(
+            word.parse(b"END".as_slice()).into_result(),
+            Ok(Word::Keyword(()))
+        );
+    }
+
+    #[test]
+    fn layout() {
+        #[derive(Debug, PartialEq)]
+        enum Tree<'a> {
+            Leaf(&'a str),
+            Node(&'a str, Vec<Tree<'a>>),
+        }
+
+        let tree = recursive::<_, _, extra::Default, _, _>(|tree| {
+            text::ascii::ident()
+                .then(
+                    just(':')
+                        .ignore_then(text::block_of(tree).collect())
+                        .or_not(),
+                )
+                .map(|(name, kids)| match kids {
+                    Some(kids) => Tree::Node(name, kids),
+                    None => Tree::Leaf(name),
+                })
+        });
+        let forest = text::block_of(tree).collect::<Vec<_>>().padded();
+        assert_eq!(
+            forest.parse("a:\n  b\n  c:\n    d\n  e\nf").into_result(),
+            Ok(vec![
+                Tree::Node(
+                    "a",
+                    vec![
+                        Tree::Leaf("b"),
+                        Tree::Node("c", vec![Tree::Leaf("d")]),
+                        Tree::Leaf("e"),
+                    ]
+                ),
+                Tree::Leaf("f"),
+            ]),
+        );
+        // Misaligned items end the block, leaving the rest unparsed
+        assert!(forest.parse("a\n b").has_errors());
+
+        let call = text::ascii::ident::<_, _, extra::Default>()
+            .padded()
+            .repeated()
+            .collect::<Vec<_>>()
+            .same_line();
+        assert_eq!(call.parse("f x y").into_result(), Ok(vec!["f", "x", "y"]));
+        assert!(call.parse("f x\ny").has_errors());
+
+        let column = text::ascii::ident::<_, _, extra::Default>()
+            .aligned()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            column.parse("  a\n  b\n  c").into_result(),
+            Ok(vec!["a", "b", "c"])
+        );
+    }
+
+    #[test]
+    fn prologue() {
+        let script = text::bom::<&str, extra::Default>()
+            .or_not()
+            .ignore_then(text::shebang().or_not())
+            .then(text::ascii::ident());
+        assert_eq!(
+            script.parse("\u{FEFF}#!/bin/sh\r\nmain").into_result(),
+            Ok((Some("/bin/sh"), "main"))
+        );
+        assert_eq!(
+            script.parse("#!\nmain").into_result(),
+            Ok((Some(""), "main"))
+        );
+        assert!(script.parse("\u{FFFE}main").has_errors());
+    }
+
+    #[test]
+    fn decode() {
+        assert!(matches!(
+            text::decode(b"\xEF\xBB\xBF\xFF"),
+            Err(text::DecodeError::InvalidUtf8(_))
+        ));
+        assert_eq!(text::decode(b"").as_deref(), Ok(""));
+
+        #[cfg(not(feature = "transcode"))]
+        assert_eq!(
+            text::decode(b"\xFF\xFEh\0"),
+            Err(text::DecodeError::Unsupported(text::Encoding::Utf16Le))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "transcode")]
+    fn decode_utf16() {
+        assert_eq!(
+            text::decode(b"\xFF\xFEh\0=\0=\xD8\x00\xDE").as_deref(),
+            Ok("h=\u{1F600}")
+        );
+        assert_eq!(
+            text::decode(b"\xFF\xFEh\0\x00\xD8h\0"),
+            Err(text::DecodeError::InvalidUtf16(4))
+        );
+        assert_eq!(
+            text::decode(b"\xFE\xFF\0h\0"),
+            Err(text::DecodeError::InvalidUtf16(4))
+        );
+    }
+
+    #[test]
+    fn whitespace_variants() {
+        let ascii = text::ascii::whitespace::<_, &[u8], extra::Default>();
+        assert!(!ascii.parse(b" \t\r\n\x0C" as &[u8]).has_errors());
+        assert!(ascii.parse(b"\xA0" as &[u8]).has_errors());
+
+        let unicode = text::unicode::whitespace::<_, &str, extra::Default>();
+        assert!(!unicode.parse("\u{A0}\u{3000}\n").has_errors());
+        assert!(unicode.parse("\u{200B}").has_errors());
+
+        let inline = text::unicode::inline_whitespace::<_, &str, extra::Default>();
+        assert!(!inline.parse("\u{A0} \t").has_errors());
+        assert!(inline.parse("\r\n").has_errors());
+    }
+
+    #[test]
+    fn lines() {
+        let lines = text::line::<&[u8], _, extra::Default>()
+            .repeated()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines.parse(b"a b\r\n\rc\x0Cd" as &[u8]).into_result(),
+            Ok(vec![b"a b" as &[u8], b"", b"c", b"d"]),
+        );
+        assert_eq!(
+            lines.parse(b"\n\n" as &[u8]).into_result(),
+            Ok(vec![b"" as &[u8], b""]),
+        );
+
+        // The bytes of multi-byte characters are never newlines, even where they coincide with `\u{85}`
+        let utf8 = "wait…\nzażółć".as_bytes();
+        assert_eq!(
+            lines.parse(utf8).into_result(),
+            Ok(vec!["wait…".as_bytes(), "zażółć".as_bytes()]),
+        );
+        assert_eq!(
+            text::rest_of_line::<&[u8], _, extra::Default>()
+                .parse("ą…".as_bytes())
+                .into_result(),
+            Ok("ą…".as_bytes()),
+        );
+
+        // The last line doesn't need a trailing newline
+        let key_value = text::ascii::ident::<&str, _, extra::Default>()
+            .then_ignore(just('='))
+            .then(text::rest_of_line())
+            .then_ignore(text::eol_or_eof())
+            .repeated()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            key_value.parse("a=1\nb=two words").into_result(),
+            Ok(vec![("a", "1"), ("b", "two words")]),
+        );
+    }
+
+    #[test]
+    fn line_oriented_padding() {
+        // A .env-style file: every assignment must be on its own line
+        let ident = text::ascii::ident::<_, _, extra::Default>();
+        let assignment = ident
+            .then_ignore(just('=').padded_by(text::inline_whitespace()))
+            .then(ident)
+            .padded_by(text::unicode::inline_whitespace());
+        let file = assignment
+            .separated_by(text::newline().repeated().at_least(1))
+            .allow_leading()
+            .allow_trailing()
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            file.parse("\nA = b \u{A0}\r\n\n  C=d\n").into_result(),
+            Ok(vec![("A", "b"), ("C", "d")]),
+        );
+        assert!(file.parse("A =\nb").has_errors());
+    }
+
+    #[test]
+    fn until_delimiter() {
+        // The delimiter's first byte occurs before the delimiter itself
+        let comment = text::until_str::<_, _, extra::Default>(b"*/".as_slice());
+        assert_eq!(
+            comment.lazy().parse(b"a * b */ c".as_slice()).into_result(),
+            Ok(b"a * b ".as_slice()),
+        );
+        assert!(comment.parse(b"a * b *".as_slice()).has_errors());
+
+        let field = text::until_char::<_, _, extra::Default>('\u{e9}')
+            .consume_delimiter()
+            .then(text::until_char(';'))
+            .then_ignore(just(';'));
+        assert_eq!(
+            field.parse("caf\u{e9}t\u{e9};").into_result(),
+            Ok(("caf", "t\u{e9}")),
+        );
+
+        // Stopping before the delimiter leaves it for the next parser
+        let before = text::until_str::<_, _, extra::Default>("->")
+            .then(just("->"))
+            .slice();
+        assert_eq!(before.parse("x->").into_result(), Ok("x->"));
+        assert_eq!(
+            text::until_str::<_, &str, extra::Default>("")
+                .parse("")
+                .into_result(),
+            Ok("")
+        );
+    }
+
+    #[test]
+    fn number_policy() {
+        use text::{NumberLiteral, NumberPolicy};
+
+        struct Plain;
+        impl NumberPolicy for Plain {}
+
+        struct Signed;
+        impl NumberPolicy for Signed {
+            const SIGN: bool = true;
+            const PREFIXES: &'static [(&'static str, u32)] = &[("0x", 16), ("0X", 16)];
+            const FRACTION: bool = true;
+            const EXPONENT: &'static [char] = &['e'];
+            const SUFFIXES: &'static [&'static str] = &["i", "i32", "f"];
+        }
+
+        let plain = text::number::<Plain, _, _, extra::Default>();
+        assert_eq!(
+            plain.lazy().parse("0x1").into_result().map(|n| n.slice),
+            Ok("0")
+        );
+        assert_eq!(
+            plain.lazy().parse("1.5").into_result().map(|n| n.slice),
+            Ok("1")
+        );
+        assert!(plain.parse("+1").has_errors());
+        assert!(plain.parse("1_0").has_errors());
+
+        let bytes = text::number::<Signed, _, _, extra::Default>();
+        assert_eq!(
+            bytes.parse(b"-0XaBi32".as_slice()).into_result(),
+            Ok(NumberLiteral {
+                slice: b"-0XaBi32".as_slice(),
+                negative: true,
+                radix: 16,
+                integer: b"aB".as_slice(),
+                fraction: None,
+                exponent: None,
+                suffix: Some(b"i32".as_slice()),
+            })
+        );
+
+        let signed = text::number::<Signed, _, _, extra::Default>();
+        let lit = signed.parse("+2.5e+10f").into_result().unwrap();
+        assert!(!lit.negative && lit.is_float());
+        assert_eq!(lit.exponent, Some("+10"));
+        assert_eq!(lit.suffix, Some("f"));
+
+        // A `.` or exponent marker without digits after it is left alone
+        let member = signed.then(just(".max").slice());
+        assert_eq!(
+            member
+                .parse("3.max")
+                .into_result()
+                .map(|(n, _)| n.is_float()),
+            Ok(false)
+        );
+        let lit = signed.lazy().parse("3ex").into_result().unwrap();
+        assert_eq!((lit.slice, lit.exponent), ("3", None));
+    }
 }
@@ -0,0 +1,179 @@
+//! Recording of parse traces, for diagnosing backtracking.
+//!
+//! A [`Trace`] records every save, rewind and token read that a parser performs on its input, in the order they
+//! happened. Replaying the trace makes it possible to follow the parser through the input step by step, see where it
+//! abandoned a branch, and find the offsets that it backtracked to most often.
+//!
+//! Traces are produced by [`Parser::parse_traced`]. They can be dumped as JSON with [`Trace::to_json`] for use with
+//! external tools, or summarised as a textual heat map with [`Trace::heat_map`].
+//!
+//! All offsets in a trace are the [`Input::Offset`]s of the input being parsed, converted to `usize`. For `&str`
+//! inputs, these are byte offsets. Events that occur while parsing a nested input (such as with
+//! [`Parser::nested_in`]) are not recorded.
+
+use super::*;
+use alloc::collections::BTreeMap;
+use core::fmt::Write as _;
+
+/// A single event recorded in a [`Trace`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TraceEvent {
+    /// The parser saved its position, so that it can rewind to it later.
+    Save {
+        /// The offset that was saved.
+        offset: usize,
+    },
+    /// The parser rewound to an earlier saved position.
+    Rewind {
+        /// The offset that the parser was at before rewinding.
+        from: usize,
+        /// The offset that the parser rewound to.
+        to: usize,
+    },
+    /// The parser read a token (or reached the end of the input).
+    Next {
+        /// The offset of the start of the token.
+        start: usize,
+        /// The offset of the end of the token. This is equal to `start` if the end of the input was reached.
+        end: usize,
+    },
+}
+
+impl TraceEvent {
+    /// The offset of the input after this event occurred.
+    pub fn offset(&self) -> usize {
+        match self {
+            Self::Save { offset } => *offset,
+            Self::Rewind { to, .. } => *to,
+            Self::Next { end, .. } => *end,
+        }
+    }
+}
+
+/// A record of the operations a parser performed on its input. See [`Parser::parse_traced`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Trace {
+    events: Vec<TraceEvent>,
+}
+
+impl Trace {
+    #[inline(always)]
+    pub(crate) fn record(&mut self, event: TraceEvent) {
+        self.events.push(event);
+    }
+
+    /// The events in this trace, in the order they occurred.
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    /// Replay the trace, yielding each event along with the offset of the input after it occurred.
+    pub fn replay(&self) -> impl Iterator<Item = (usize, TraceEvent)> + '_ {
+        self.events.iter().map(|event| (event.offset(), *event))
+    }
+
+    /// The number of times the parser backtracked to each offset.
+    ///
+    /// Only rewinds that actually moved the parser backwards are counted.
+    pub fn backtracks(&self) -> BTreeMap<usize, usize> {
+        let mut backtracks = BTreeMap::new();
+        for event in &self.events {
+            if let TraceEvent::Rewind { from, to } = *event {
+                if to < from {
+                    *backtracks.entry(to).or_insert(0) += 1;
+                }
+            }
+        }
+        backtracks
+    }
+
+    /// The number of times the parser read the token starting at each offset.
+    ///
+    /// Offsets that are read many times are often a sign of pathological backtracking.
+    pub fn reads(&self) -> BTreeMap<usize, usize> {
+        let mut reads = BTreeMap::new();
+        for event in &self.events {
+            if let TraceEvent::Next { start, .. } = *event {
+                *reads.entry(start).or_insert(0) += 1;
+            }
+        }
+        reads
+    }
+
+    /// Produce a textual summary of how often the parser backtracked to each offset, with the number of times the
+    /// token at that offset was read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let parser = choice((just::<_, _, extra::Default>("ab"), just("ac"), just("ad")));
+    /// let (_, trace) = parser.parse_traced("ad");
+    ///
+    /// assert_eq!(trace.backtracks().get(&0), Some(&2));
+    /// assert_eq!(trace.heat_map(), "\
+    /// offset  backtracks  reads
+    ///      0           2      3  ########################################
+    /// ");
+    /// ```
+    pub fn heat_map(&self) -> String {
+        const WIDTH: usize = 40;
+
+        let backtracks = self.backtracks();
+        let reads = self.reads();
+        let max = backtracks.values().copied().max().unwrap_or(0);
+
+        let mut out = String::from("offset  backtracks  reads\n");
+        for (offset, count) in backtracks {
+            let bar = (count * WIDTH / max).max(1);
+            let reads = reads.get(&offset).copied().unwrap_or(0);
+            writeln!(
+                out,
+                "{offset:>6}  {count:>10}  {reads:>5}  {}",
+                "#".repeat(bar)
+            )
+            .unwrap();
+        }
+        out
+    }
+
+    /// Dump this trace as a JSON document.
+    ///
+    /// The document is an object with a single `events` field, containing an array of events. Each event has a
+    /// `kind` field (one of `"save"`, `"rewind"` or `"next"`) along with the fields of the corresponding
+    /// [`TraceEvent`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky::prelude::*;
+    /// let (_, trace) = just::<_, _, extra::Default>('a').or(just('b')).parse_traced("b");
+    ///
+    /// assert_eq!(
+    ///     trace.to_json(),
+    ///     r#"{"events":[{"kind":"save","offset":0},{"kind":"next","start":0,"end":1},{"kind":"rewind","from":1,"to":0},{"kind":"next","start":0,"end":1},{"kind":"next","start":1,"end":1}]}"#,
+    /// );
+    /// ```
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"events\":[");
+        for (i, event) in self.events.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            match event {
+                TraceEvent::Save { offset } => {
+                    write!(out, "{{\"kind\":\"save\",\"offset\":{offset}}}")
+                }
+                TraceEvent::Rewind { from, to } => {
+                    write!(out, "{{\"kind\":\"rewind\",\"from\":{from},\"to\":{to}}}")
+                }
+                TraceEvent::Next { start, end } => {
+                    write!(out, "{{\"kind\":\"next\",\"start\":{start},\"end\":{end}}}")
+                }
+            }
+            .unwrap();
+        }
+        out.push_str("]}");
+        out
+    }
+}
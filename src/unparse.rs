@@ -0,0 +1,608 @@
+//! Turning the outputs of parsers back into text.
+//!
+//! *“The Babel fish is small, yellow, leech-like, and probably the oddest thing in the Universe.”*
+//!
+//! The [`Unparse`] trait is the dual of [`Parser`]: given a value that a parser could have produced, it writes text
+//! that the parser would turn back into that value. This lets a single grammar definition drive both a parser and a
+//! printer, which is useful for formatters and code generators that must round-trip their output.
+//!
+//! [`Unparse`] is implemented for the combinators that don't lose information about their input, such as
+//! [`just`], [`Parser::then`] (and its variants, such as [`Parser::separated_pair`]), [`Parser::or`], [`choice`], [`Parser::or_not`], [`Parser::to`],
+//! [`Parser::delimited_by`], [`Parser::slice`] and collected [`Parser::repeated`] or [`Parser::separated_by`]
+//! parsers. Combinators that run arbitrary code, such as [`Parser::map`], cannot be inverted automatically: use
+//! [`Parser::map_invertible`] or [`Parser::unparse_with`] to provide the inverse yourself.
+//!
+//! Some parsers accept many spellings of the same value. Unparsing always writes a single canonical spelling: the
+//! first alternative of a choice that succeeds, no optional whitespace, no leading or trailing separators, etc.
+//!
+//! # Examples
+//!
+//! ```
+//! use chumsky::{prelude::*, unparse::Unparse};
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! enum Op {
+//!     Add,
+//!     Sub,
+//! }
+//!
+//! let op = choice((just('+').to(Op::Add), just('-').to(Op::Sub)));
+//! let num = text::int::<_, _, extra::Default>(10).slice();
+//! let expr = num
+//!     .then(op.padded().then(num).repeated().collect::<Vec<_>>())
+//!     .delimited_by(just('('), just(')'));
+//!
+//! let value = expr.parse("(1 + 2 -3)").into_result().unwrap();
+//! assert_eq!(value, ("1", vec![(Op::Add, "2"), (Op::Sub, "3")]));
+//!
+//! let text = expr.unparse_to_string(&value).unwrap();
+//! assert_eq!(text, "(1+2-3)");
+//! assert_eq!(expr.parse(&text).into_result(), Ok(value));
+//! ```
+
+use super::*;
+use crate::primitive::{Choice, Just};
+
+/// An error produced when a value cannot be unparsed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UnparseError {
+    /// The value could not have been produced by the parser, such as a value that differs from the fixed output of
+    /// [`Parser::to`], or a collection with the wrong number of elements.
+    Mismatch,
+    /// None of the alternatives of a choice could unparse the value.
+    NoAlternative,
+    /// The output of the parser is discarded (as with [`Parser::ignore_then`]), and the parser has no canonical text
+    /// to write in its place.
+    NoCanonicalForm,
+}
+
+impl fmt::Display for UnparseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Mismatch => write!(f, "value could not have been produced by the parser"),
+            Self::NoAlternative => write!(f, "no alternative could unparse the value"),
+            Self::NoCanonicalForm => write!(f, "discarded parser has no canonical text"),
+        }
+    }
+}
+
+/// A parser that can write text which, when parsed, produces a given output.
+///
+/// See the [module-level documentation](self) for more information.
+pub trait Unparse<O> {
+    /// Write text that this parser would parse as `value` to `out`.
+    ///
+    /// If an error is returned, `out` may contain partial output.
+    fn unparse(&self, value: &O, out: &mut String) -> Result<(), UnparseError>;
+
+    /// Write the canonical text of this parser to `out`, for use when its output is discarded.
+    ///
+    /// By default, this fails with [`UnparseError::NoCanonicalForm`]. Parsers with a single possible output, such as
+    /// [`just`], override it.
+    fn unparse_ignored(&self, out: &mut String) -> Result<(), UnparseError> {
+        let _ = out;
+        Err(UnparseError::NoCanonicalForm)
+    }
+
+    /// Unparse `value` into a new string.
+    fn unparse_to_string(&self, value: &O) -> Result<String, UnparseError> {
+        let mut out = String::new();
+        self.unparse(value, &mut out)?;
+        Ok(out)
+    }
+}
+
+impl<'a, T, I, E> Unparse<T> for Just<T, I, E>
+where
+    T: Seq<'a, char>,
+{
+    fn unparse(&self, _value: &T, out: &mut String) -> Result<(), UnparseError> {
+        self.unparse_ignored(out)
+    }
+
+    fn unparse_ignored(&self, out: &mut String) -> Result<(), UnparseError> {
+        out.extend(self.seq.seq_iter().map(|c| *c.borrow()));
+        Ok(())
+    }
+}
+
+impl<'b, A, O> Unparse<&'b str> for Slice<A, O> {
+    fn unparse(&self, value: &&'b str, out: &mut String) -> Result<(), UnparseError> {
+        out.push_str(value);
+        Ok(())
+    }
+}
+
+impl<A, OA, O> Unparse<O> for To<A, OA, O>
+where
+    A: Unparse<OA>,
+    O: PartialEq,
+{
+    fn unparse(&self, value: &O, out: &mut String) -> Result<(), UnparseError> {
+        if *value == self.to {
+            self.parser.unparse_ignored(out)
+        } else {
+            Err(UnparseError::Mismatch)
+        }
+    }
+
+    fn unparse_ignored(&self, out: &mut String) -> Result<(), UnparseError> {
+        self.parser.unparse_ignored(out)
+    }
+}
+
+impl<A, OA> Unparse<()> for Ignored<A, OA>
+where
+    A: Unparse<OA>,
+{
+    fn unparse(&self, _value: &(), out: &mut String) -> Result<(), UnparseError> {
+        self.parser.unparse_ignored(out)
+    }
+
+    fn unparse_ignored(&self, out: &mut String) -> Result<(), UnparseError> {
+        self.parser.unparse_ignored(out)
+    }
+}
+
+impl<A, B, OA, OB, E> Unparse<(OA, OB)> for Then<A, B, OA, OB, E>
+where
+    A: Unparse<OA>,
+    B: Unparse<OB>,
+{
+    fn unparse(&self, (a, b): &(OA, OB), out: &mut String) -> Result<(), UnparseError> {
+        self.parser_a.unparse(a, out)?;
+        self.parser_b.unparse(b, out)
+    }
+
+    fn unparse_ignored(&self, out: &mut String) -> Result<(), UnparseError> {
+        self.parser_a.unparse_ignored(out)?;
+        self.parser_b.unparse_ignored(out)
+    }
+}
+
+impl<A, B, OA, OB, E> Unparse<OB> for IgnoreThen<A, B, OA, E>
+where
+    A: Unparse<OA>,
+    B: Unparse<OB>,
+{
+    fn unparse(&self, value: &OB, out: &mut String) -> Result<(), UnparseError> {
+        self.parser_a.unparse_ignored(out)?;
+        self.parser_b.unparse(value, out)
+    }
+
+    fn unparse_ignored(&self, out: &mut String) -> Result<(), UnparseError> {
+        self.parser_a.unparse_ignored(out)?;
+        self.parser_b.unparse_ignored(out)
+    }
+}
+
+impl<A, B, OA, OB, E> Unparse<OA> for ThenIgnore<A, B, OB, E>
+where
+    A: Unparse<OA>,
+    B: Unparse<OB>,
+{
+    fn unparse(&self, value: &OA, out: &mut String) -> Result<(), UnparseError> {
+        self.parser_a.unparse(value, out)?;
+        self.parser_b.unparse_ignored(out)
+    }
+
+    fn unparse_ignored(&self, out: &mut String) -> Result<(), UnparseError> {
+        self.parser_a.unparse_ignored(out)?;
+        self.parser_b.unparse_ignored(out)
+    }
+}
+
+impl<A, S, B, OA, OS, OB> Unparse<(OA, OB)> for SeparatedPair<A, S, B, OS>
+where
+    A: Unparse<OA>,
+    S: Unparse<OS>,
+    B: Unparse<OB>,
+{
+    fn unparse(&self, (a, b): &(OA, OB), out: &mut String) -> Result<(), UnparseError> {
+        self.parser_a.unparse(a, out)?;
+        self.separator.unparse_ignored(out)?;
+        self.parser_b.unparse(b, out)
+    }
+
+    fn unparse_ignored(&self, out: &mut String) -> Result<(), UnparseError> {
+        self.parser_a.unparse_ignored(out)?;
+        self.separator.unparse_ignored(out)?;
+        self.parser_b.unparse_ignored(out)
+    }
+}
+
+impl<A, B, OA, OB> Unparse<OA> for Preceded<A, B, OB>
+where
+    A: Unparse<OA>,
+    B: Unparse<OB>,
+{
+    fn unparse(&self, value: &OA, out: &mut String) -> Result<(), UnparseError> {
+        self.prefix.unparse_ignored(out)?;
+        self.parser.unparse(value, out)
+    }
+
+    fn unparse_ignored(&self, out: &mut String) -> Result<(), UnparseError> {
+        self.prefix.unparse_ignored(out)?;
+        self.parser.unparse_ignored(out)
+    }
+}
+
+impl<A, B, OA, OB> Unparse<OA> for Terminated<A, B, OB>
+where
+    A: Unparse<OA>,
+    B: Unparse<OB>,
+{
+    fn unparse(&self, value: &OA, out: &mut String) -> Result<(), UnparseError> {
+        self.parser.unparse(value, out)?;
+        self.suffix.unparse_ignored(out)
+    }
+
+    fn unparse_ignored(&self, out: &mut String) -> Result<(), UnparseError> {
+        self.parser.unparse_ignored(out)?;
+        self.suffix.unparse_ignored(out)
+    }
+}
+
+impl<A, B, C, OA, OB, OC> Unparse<OA> for DelimitedBy<A, B, C, OB, OC>
+where
+    A: Unparse<OA>,
+    B: Unparse<OB>,
+    C: Unparse<OC>,
+{
+    fn unparse(&self, value: &OA, out: &mut String) -> Result<(), UnparseError> {
+        self.start.unparse_ignored(out)?;
+        self.parser.unparse(value, out)?;
+        self.end.unparse_ignored(out)
+    }
+
+    fn unparse_ignored(&self, out: &mut String) -> Result<(), UnparseError> {
+        self.start.unparse_ignored(out)?;
+        self.parser.unparse_ignored(out)?;
+        self.end.unparse_ignored(out)
+    }
+}
+
+impl<A, B, OA, OB> Unparse<OA> for PaddedBy<A, B, OB>
+where
+    A: Unparse<OA>,
+    B: Unparse<OB>,
+{
+    fn unparse(&self, value: &OA, out: &mut String) -> Result<(), UnparseError> {
+        self.padding.unparse_ignored(out)?;
+        self.parser.unparse(value, out)?;
+        self.padding.unparse_ignored(out)
+    }
+
+    fn unparse_ignored(&self, out: &mut String) -> Result<(), UnparseError> {
+        self.padding.unparse_ignored(out)?;
+        self.parser.unparse_ignored(out)?;
+        self.padding.unparse_ignored(out)
+    }
+}
+
+/// Whitespace is optional, so none is written.
+impl<A, O> Unparse<O> for Padded<A>
+where
+    A: Unparse<O>,
+{
+    fn unparse(&self, value: &O, out: &mut String) -> Result<(), UnparseError> {
+        self.parser.unparse(value, out)
+    }
+
+    fn unparse_ignored(&self, out: &mut String) -> Result<(), UnparseError> {
+        self.parser.unparse_ignored(out)
+    }
+}
+
+/// `None` is written as nothing.
+impl<A, O> Unparse<Option<O>> for OrNot<A>
+where
+    A: Unparse<O>,
+{
+    fn unparse(&self, value: &Option<O>, out: &mut String) -> Result<(), UnparseError> {
+        match value {
+            Some(value) => self.parser.unparse(value, out),
+            None => Ok(()),
+        }
+    }
+
+    fn unparse_ignored(&self, _out: &mut String) -> Result<(), UnparseError> {
+        Ok(())
+    }
+}
+
+impl<A, B, O> Unparse<O> for Or<A, B>
+where
+    A: Unparse<O>,
+    B: Unparse<O>,
+{
+    fn unparse(&self, value: &O, out: &mut String) -> Result<(), UnparseError> {
+        self.choice.unparse(value, out)
+    }
+
+    fn unparse_ignored(&self, out: &mut String) -> Result<(), UnparseError> {
+        self.choice.unparse_ignored(out)
+    }
+}
+
+macro_rules! impl_unparse_for_choice_tuple {
+    () => {};
+    ($head:ident $($X:ident)*) => {
+        impl_unparse_for_choice_tuple!($($X)*);
+        impl_unparse_for_choice_tuple!(~ $head $($X)*);
+    };
+    (~ $($X:ident)+) => {
+        #[allow(non_snake_case)]
+        impl<$($X),*, O> Unparse<O> for Choice<($($X,)*)>
+        where
+            $($X: Unparse<O>),*
+        {
+            fn unparse(&self, value: &O, out: &mut String) -> Result<(), UnparseError> {
+                let Choice { parsers: ($($X,)*), .. } = self;
+                let before = out.len();
+                $(
+                    if $X.unparse(value, out).is_ok() {
+                        return Ok(());
+                    }
+                    // Discard the partial output of the failed alternative
+                    out.truncate(before);
+                )*
+                Err(UnparseError::NoAlternative)
+            }
+
+            fn unparse_ignored(&self, out: &mut String) -> Result<(), UnparseError> {
+                let Choice { parsers: ($($X,)*), .. } = self;
+                let before = out.len();
+                $(
+                    if $X.unparse_ignored(out).is_ok() {
+                        return Ok(());
+                    }
+                    out.truncate(before);
+                )*
+                Err(UnparseError::NoAlternative)
+            }
+        }
+    };
+}
+
+impl_unparse_for_choice_tuple!(A_ B_ C_ D_ E_ F_ G_ H_ I_ J_ K_ L_ M_ N_ O_ P_ Q_ R_ S_ T_ U_ V_ W_ X_ Y_ Z_);
+
+impl<A, O, const N: usize> Unparse<O> for Choice<[A; N]>
+where
+    A: Unparse<O>,
+{
+    fn unparse(&self, value: &O, out: &mut String) -> Result<(), UnparseError> {
+        let before = out.len();
+        for parser in &self.parsers {
+            if parser.unparse(value, out).is_ok() {
+                return Ok(());
+            }
+            out.truncate(before);
+        }
+        Err(UnparseError::NoAlternative)
+    }
+}
+
+/// Collections are written element by element. The number of elements must be within the bounds given to
+/// [`Repeated::at_least`] and [`Repeated::at_most`].
+impl<A, OA, I, E, C> Unparse<C> for Collect<Repeated<A, OA, I, E>, OA, C>
+where
+    A: Unparse<OA>,
+    for<'v> &'v C: IntoIterator<Item = &'v OA>,
+{
+    fn unparse(&self, value: &C, out: &mut String) -> Result<(), UnparseError> {
+        let mut count = 0;
+        for item in value {
+            self.parser.parser.unparse(item, out)?;
+            count += 1;
+        }
+        if count < self.parser.at_least || count as u64 > self.parser.at_most {
+            return Err(UnparseError::Mismatch);
+        }
+        Ok(())
+    }
+}
+
+/// Collections are written element by element, with a separator between each pair of elements. The number of
+/// elements must be within the bounds given to [`SeparatedBy::at_least`] and [`SeparatedBy::at_most`].
+impl<A, B, OA, OB, I, E, C> Unparse<C> for Collect<SeparatedBy<A, B, OA, OB, I, E>, OA, C>
+where
+    A: Unparse<OA>,
+    B: Unparse<OB>,
+    for<'v> &'v C: IntoIterator<Item = &'v OA>,
+{
+    fn unparse(&self, value: &C, out: &mut String) -> Result<(), UnparseError> {
+        let mut count = 0;
+        for item in value {
+            if count > 0 {
+                self.parser.separator.unparse_ignored(out)?;
+            }
+            self.parser.parser.unparse(item, out)?;
+            count += 1;
+        }
+        if count < self.parser.at_least || count as u64 > self.parser.at_most {
+            return Err(UnparseError::Mismatch);
+        }
+        Ok(())
+    }
+}
+
+/// See [`Parser::map_invertible`].
+pub struct MapInvertible<A, OA, F, G> {
+    pub(crate) parser: A,
+    pub(crate) mapper: F,
+    pub(crate) inverse: G,
+    #[allow(dead_code)]
+    pub(crate) phantom: EmptyPhantom<OA>,
+}
+
+impl<A: Copy, OA, F: Copy, G: Copy> Copy for MapInvertible<A, OA, F, G> {}
+impl<A: Clone, OA, F: Clone, G: Clone> Clone for MapInvertible<A, OA, F, G> {
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            mapper: self.mapper.clone(),
+            inverse: self.inverse.clone(),
+            phantom: EmptyPhantom::new(),
+        }
+    }
+}
+
+impl<'a, I, O, E, A, OA, F, G> ParserSealed<'a, I, O, E> for MapInvertible<A, OA, F, G>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, OA, E>,
+    F: Fn(OA) -> O,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        let out = self.parser.go::<M>(inp)?;
+        Ok(M::map(out, &self.mapper))
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(O);
+}
+
+impl<A, OA, O, F, G> Unparse<O> for MapInvertible<A, OA, F, G>
+where
+    A: Unparse<OA>,
+    G: Fn(&O) -> Option<OA>,
+{
+    fn unparse(&self, value: &O, out: &mut String) -> Result<(), UnparseError> {
+        let value = (self.inverse)(value).ok_or(UnparseError::Mismatch)?;
+        self.parser.unparse(&value, out)
+    }
+
+    fn unparse_ignored(&self, out: &mut String) -> Result<(), UnparseError> {
+        self.parser.unparse_ignored(out)
+    }
+}
+
+/// See [`Parser::unparse_with`].
+#[derive(Copy, Clone)]
+pub struct UnparseWith<A, F> {
+    pub(crate) parser: A,
+    pub(crate) unparser: F,
+}
+
+impl<'a, I, O, E, A, F> ParserSealed<'a, I, O, E> for UnparseWith<A, F>
+where
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    A: Parser<'a, I, O, E>,
+{
+    #[inline(always)]
+    fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, O> {
+        self.parser.go::<M>(inp)
+    }
+
+    fn walk_node(&self, walker: &mut Walker<'_>) {
+        walker.node::<Self>(|walker| self.parser.walk_node(walker));
+    }
+
+    go_extra!(O);
+}
+
+impl<A, O, F> Unparse<O> for UnparseWith<A, F>
+where
+    F: Fn(&O, &mut String) -> Result<(), UnparseError>,
+{
+    fn unparse(&self, value: &O, out: &mut String) -> Result<(), UnparseError> {
+        (self.unparser)(value, out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Value<'a> {
+        Num(u32),
+        Ident(&'a str),
+    }
+
+    #[test]
+    fn round_trip() {
+        let num = text::int::<_, _, extra::Default>(10)
+            .slice()
+            .map(|s: &str| Value::Num(s.parse().unwrap()))
+            .unparse_with(|value, out| match value {
+                Value::Num(n) => {
+                    out.push_str(&n.to_string());
+                    Ok(())
+                }
+                _ => Err(UnparseError::Mismatch),
+            });
+        let ident = text::ascii::ident()
+            .unparse_with(|ident: &&str, out| {
+                out.push_str(ident);
+                Ok(())
+            })
+            .map_invertible(Value::Ident, |value| match value {
+                Value::Ident(ident) => Some(*ident),
+                _ => None,
+            });
+        let list = choice((num, ident))
+            .separated_by(just(',').padded())
+            .allow_trailing()
+            .collect::<Vec<_>>()
+            .delimited_by(just('['), just(']'));
+
+        let value = list.parse("[foo, 1 ,bar,]").into_result().unwrap();
+        assert_eq!(
+            value,
+            vec![Value::Ident("foo"), Value::Num(1), Value::Ident("bar")]
+        );
+        let text = list.unparse_to_string(&value).unwrap();
+        assert_eq!(text, "[foo,1,bar]");
+        assert_eq!(list.parse(&text).into_result(), Ok(value));
+
+        let entry = just::<_, &str, extra::Default>('a')
+            .separated_pair(just('=').padded(), just('1'))
+            .preceded(just('$'))
+            .terminated(just(';'));
+        assert_eq!(entry.unparse_to_string(&('a', '1')).unwrap(), "$a=1;");
+    }
+
+    #[test]
+    fn unparse_errors() {
+        let bit = just::<_, &str, extra::Default>('0')
+            .to(false)
+            .or(just('1').to(true));
+        let byte = bit.repeated().exactly(8).collect::<Vec<_>>();
+        assert_eq!(
+            byte.unparse_to_string(&vec![true, false, true, true, false, false, true, false]),
+            Ok("10110010".to_string()),
+        );
+        assert_eq!(
+            byte.unparse_to_string(&vec![true]),
+            Err(UnparseError::Mismatch)
+        );
+
+        let keyword = just::<_, &str, extra::Default>("let")
+            .to(1)
+            .or(just("fn").to(2));
+        assert_eq!(keyword.unparse_to_string(&2), Ok("fn".to_string()));
+        assert_eq!(
+            keyword.unparse_to_string(&3),
+            Err(UnparseError::NoAlternative)
+        );
+
+        let field = none_of::<_, &str, extra::Default>(';').repeated().slice();
+        assert_eq!(
+            field.then_ignore(just(';')).unparse_to_string(&"abc"),
+            Ok("abc;".to_string()),
+        );
+        assert_eq!(
+            field.ignore_then(just(';')).unparse_to_string(&';'),
+            Err(UnparseError::NoCanonicalForm),
+        );
+    }
+}
@@ -174,3 +174,141 @@ impl<'de, T: Deserialize<'de>, R: Deref<Target = T>> Deserialize<'de> for Maybe<
         deserializer.deserialize_newtype_struct("Maybe", MaybeVisitor(PhantomData))
     }
 }
+
+/// Assert that a parser behaves identically when run in both of chumsky's internal modes.
+///
+/// Internally, chumsky runs parsers in one of two modes: one that generates outputs (used by [`Parser::parse`]) and
+/// one that only checks that the input is valid (used by [`Parser::check`], and by combinators that discard the
+/// output of their inner parser, like [`Parser::ignored`] or [`Parser::rewind`]). A parser must accept and reject
+/// exactly the same inputs, and produce exactly the same errors, regardless of the mode it is run in. If it doesn't,
+/// it will appear to behave inconsistently depending on where it is used within a larger parser.
+///
+/// This function runs `parser` on `input` in both modes and panics if they disagree on whether parsing succeeded, or
+/// on the errors that were produced. It is mostly useful for testing custom parsers that treat the two modes
+/// differently, such as those written with chumsky's extension API.
+///
+/// # Panics
+///
+/// Panics if the two modes disagree.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, util::assert_modes_agree};
+/// let digits = text::digits::<_, _, extra::Err<Simple<char>>>(10).slice();
+///
+/// assert_modes_agree(&digits, "123");
+/// assert_modes_agree(&digits, "12a");
+/// ```
+#[track_caller]
+pub fn assert_modes_agree<'a, P, I, O, E>(parser: &P, input: I)
+where
+    P: Parser<'a, I, O, E>,
+    I: Input<'a> + Clone,
+    E: ParserExtra<'a, I>,
+    E::Error: fmt::Debug + PartialEq,
+    E::State: Default,
+    E::Context: Default,
+{
+    let (out, emit_errs) = parser.parse(input.clone()).into_output_errors();
+    let (check, check_errs) = parser.check(input).into_output_errors();
+
+    let outcome = |ok: bool| if ok { "succeeded" } else { "failed" };
+    assert_eq!(
+        out.is_some(),
+        check.is_some(),
+        "parser {} when generating output, but {} when checking",
+        outcome(out.is_some()),
+        outcome(check.is_some()),
+    );
+    assert_eq!(
+        emit_errs, check_errs,
+        "parser produced different errors when generating output (left) and when checking (right)",
+    );
+}
+
+/// Run a parser on some input as cheaply as possible, returning whether it succeeded without producing any errors.
+///
+/// Like [`Parser::check`], this runs the parser without generating outputs. Unlike it, no [`ParseResult`] is built:
+/// errors are still created and collected while parsing (the parser needs them to pick between alternatives and to
+/// recover), but they are never sorted or returned. This makes it a fair baseline when benchmarking chumsky
+/// against other parsing libraries, since little more than the work of parsing itself is measured. Wrap the input
+/// and the result in `black_box` to stop the optimiser from removing the call.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky::{prelude::*, util::parse_benchable};
+/// use std::hint::black_box;
+///
+/// let words = text::ascii::ident::<_, _, extra::Default>()
+///     .padded()
+///     .repeated();
+///
+/// assert!(black_box(parse_benchable(&words, black_box("the quick brown fox"))));
+/// assert!(!black_box(parse_benchable(&words, black_box("the quick brown fox!"))));
+/// ```
+pub fn parse_benchable<'a, P, I, O, E>(parser: &P, input: I) -> bool
+where
+    P: Parser<'a, I, O, E>,
+    I: Input<'a>,
+    E: ParserExtra<'a, I>,
+    E::State: Default,
+    E::Context: Default,
+{
+    let mut state = E::State::default();
+    let mut own = InputOwn::new_state(input, &mut state);
+    own.run::<Check, _, _>(parser).is_some() && own.errors.secondary.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn modes_agree() {
+        let ident = text::ascii::ident::<_, _, extra::Err<Rich<char>>>()
+            .separated_by(just(',').padded())
+            .collect::<Vec<_>>()
+            .recover_with(skip_then_retry_until(any().ignored(), end()));
+
+        assert_modes_agree(&ident, "a, b, c");
+        assert_modes_agree(&ident, "a, b, !");
+        assert_modes_agree(&ident, "");
+    }
+
+    #[test]
+    #[should_panic = "when checking"]
+    fn modes_disagree() {
+        // A (broken) parser that only consumes a token when generating output
+        struct Divergent;
+
+        impl<'a, I: Input<'a>, E: ParserExtra<'a, I>> ParserSealed<'a, I, (), E> for Divergent {
+            fn go<M: Mode>(&self, inp: &mut InputRef<'a, '_, I, E>) -> PResult<M, ()> {
+                M::choose(
+                    inp,
+                    |inp| {
+                        inp.next_maybe();
+                        Ok(())
+                    },
+                    |_| Ok(()),
+                )
+            }
+
+            go_extra!(());
+        }
+
+        assert_modes_agree::<_, _, _, extra::Default>(&Divergent, "a");
+    }
+
+    #[test]
+    fn benchable_rejects_recovered_errors() {
+        let a = just::<_, _, extra::Err<Rich<char>>>('a').recover_with(via_parser(any()));
+
+        assert!(parse_benchable(&a, "a"));
+        // Recovery succeeds, but an error is still produced
+        assert!(a.parse("b").has_output());
+        assert!(!parse_benchable(&a, "b"));
+    }
+}
@@ -0,0 +1,192 @@
+//! Walking the structure of a parser, for tooling such as linters, grammar visualisers and instrumentation.
+//!
+//! Every parser is built from combinators that wrap other parsers. [`Parser::walk`] visits each of them in turn,
+//! depth-first and in the order they appear in the combinator tree, passing a [`ParserNode`] describing each one to a
+//! [`Visitor`]. This makes it possible to count the parsers that make up a grammar, find [`todo()`](fn@todo)
+//! placeholders before release, or print the structure of a grammar for debugging.
+//!
+//! The walk only visits parsers: the closures, values and configuration that combinators hold are not visited, and
+//! neither are the parsers held by [recovery strategies](crate::recovery) or by the operators of a
+//! [Pratt parser](crate::pratt). Parsers created with the [extension API](crate::extension) are visited as leaves.
+//!
+//! A [recursive](mod@crate::recursive) parser is visited every time it is reached, but its definition is only walked
+//! the first time, so walking a recursive grammar always terminates.
+
+use super::*;
+use core::{any::type_name, panic::Location};
+
+/// A parser reached while walking the structure of another parser. See [`Parser::walk`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ParserNode {
+    type_name: &'static str,
+    revisit: bool,
+    location: Option<Location<'static>>,
+}
+
+impl ParserNode {
+    /// The name of the parser's type, without its module path or generic parameters (e.g: `"Map"` or `"Just"`).
+    ///
+    /// This is usually the name of the combinator or primitive that created the parser, in `UpperCamelCase`.
+    pub fn name(&self) -> &'static str {
+        let name = self.type_name.split('<').next().unwrap_or(self.type_name);
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
+    /// The full name of the parser's type, as given by [`core::any::type_name`].
+    ///
+    /// Like [`core::any::type_name`], the exact contents of this string are not guaranteed to be stable between
+    /// compiler versions, so it should only be used for diagnostics.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Whether this is a [recursive](mod@crate::recursive) parser whose definition has already been walked, and so
+    /// will not be walked again.
+    pub fn is_revisit(&self) -> bool {
+        self.revisit
+    }
+
+    /// The location in the source code at which the parser was created, if it was recorded.
+    ///
+    /// Currently, this is only recorded for [`todo()`](fn@todo) placeholders.
+    pub fn location(&self) -> Option<Location<'static>> {
+        self.location
+    }
+}
+
+/// A visitor of the parsers that make up another parser. See [`Parser::walk`].
+pub trait Visitor {
+    /// Visit a parser, before any of the parsers within it.
+    ///
+    /// Return `false` to skip the parsers within this one.
+    fn enter(&mut self, node: &ParserNode) -> bool;
+
+    /// Finish visiting a parser, after all of the parsers within it.
+    fn leave(&mut self, node: &ParserNode) {
+        let _ = node;
+    }
+}
+
+/// The progress of a call to [`Parser::walk`], passed between parsers as they are walked.
+pub struct Walker<'v> {
+    visitor: &'v mut dyn Visitor,
+    // The addresses of the recursive parsers whose definitions have been walked
+    seen: Vec<usize>,
+}
+
+impl<'v> Walker<'v> {
+    pub(crate) fn new(visitor: &'v mut dyn Visitor) -> Self {
+        Self {
+            visitor,
+            seen: Vec::new(),
+        }
+    }
+
+    /// Visit a parser of type `P`, walking the parsers within it with `inner` unless the visitor skips them.
+    #[inline]
+    pub(crate) fn node<P: ?Sized>(&mut self, inner: impl FnOnce(&mut Self)) {
+        self.visit(
+            ParserNode {
+                type_name: type_name::<P>(),
+                revisit: false,
+                location: None,
+            },
+            inner,
+        );
+    }
+
+    /// Visit a parser of type `P` that was created at the given location, and contains no other parsers.
+    pub(crate) fn leaf_at<P: ?Sized>(&mut self, location: Location<'static>) {
+        self.visit(
+            ParserNode {
+                type_name: type_name::<P>(),
+                revisit: false,
+                location: Some(location),
+            },
+            |_| {},
+        );
+    }
+
+    /// Visit a recursive parser of type `P` with the given address, walking its definition with `inner` only if it
+    /// has not been walked already.
+    pub(crate) fn recursive<P: ?Sized>(&mut self, addr: usize, inner: impl FnOnce(&mut Self)) {
+        let revisit = self.seen.contains(&addr);
+        if !revisit {
+            self.seen.push(addr);
+        }
+        self.visit(
+            ParserNode {
+                type_name: type_name::<P>(),
+                revisit,
+                location: None,
+            },
+            |walker| {
+                if !revisit {
+                    inner(walker)
+                }
+            },
+        );
+    }
+
+    fn visit(&mut self, node: ParserNode, inner: impl FnOnce(&mut Self)) {
+        if self.visitor.enter(&node) {
+            inner(self);
+        }
+        self.visitor.leave(&node);
+    }
+}
+
+/// The error returned by [`Parser::assert_complete`] when a parser still contains [`todo()`](fn@todo) placeholders.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Incomplete {
+    todos: Vec<Location<'static>>,
+}
+
+impl Incomplete {
+    /// The locations in the source code at which each of the placeholders were created, in the order they were found.
+    pub fn locations(&self) -> &[Location<'static>] {
+        &self.todos
+    }
+}
+
+impl fmt::Display for Incomplete {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "parser contains unimplemented placeholders created at ")?;
+        for (i, location) in self.todos.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", location)?;
+        }
+        Ok(())
+    }
+}
+
+// Collects the locations of every `todo()` placeholder
+pub(crate) struct FindTodos(Vec<Location<'static>>);
+
+impl FindTodos {
+    pub(crate) fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub(crate) fn finish(self) -> Result<(), Incomplete> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(Incomplete { todos: self.0 })
+        }
+    }
+}
+
+impl Visitor for FindTodos {
+    fn enter(&mut self, node: &ParserNode) -> bool {
+        if let (Some(location), "Todo") = (node.location(), node.name()) {
+            // The same placeholder may be reached more than once if the parser containing it was cloned
+            if !self.0.contains(&location) {
+                self.0.push(location);
+            }
+        }
+        true
+    }
+}